@@ -246,6 +246,17 @@ impl Account {
                 requested_ledger_version.map(|inner| inner.0),
             )?;
 
+        // Verify the account is tracked (e.g., if we're a partial state node)
+        let state_key =
+            StateKey::resource_typed::<AccountResource>(address.inner()).map_err(|e| {
+                BasicErrorWith404::internal_with_code(
+                    e,
+                    LumioErrorCode::InternalError,
+                    &latest_ledger_info,
+                )
+            })?;
+        context.check_state_key_is_tracked(&state_key)?;
+
         Ok(Self {
             context,
             address,
@@ -667,13 +678,7 @@ impl Account {
                 resource_type.to_canonical_string(),
                 self.address
             ))
-            .map_err(|err| {
-                BasicErrorWith404::internal_with_code(
-                    err,
-                    LumioErrorCode::InternalError,
-                    &ledger_info,
-                )
-            })?
+            .map_err(|err| state_view.convert_read_error(err, &ledger_info))?
             .ok_or_else(|| {
                 resource_not_found(
                     self.address,
@@ -98,6 +98,12 @@ impl BasicApi {
             )
             .unwrap(),
         );
+        if let Some(state_sync_progress) = self.context.state_sync_progress.get_progress() {
+            info.insert(
+                "state_sync_progress".to_string(),
+                serde_json::to_value(state_sync_progress).unwrap(),
+            );
+        }
 
         // Insert storage configuration information
         info.insert(
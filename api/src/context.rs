@@ -7,9 +7,11 @@ use crate::{
     metrics,
     response::{
         bcs_api_disabled, block_not_found_by_height, block_not_found_by_version,
-        block_pruned_by_height, json_api_disabled, version_not_found, version_pruned,
-        ForbiddenError, InternalError, NotFoundError, ServiceUnavailableError, StdApiError,
+        block_pruned_by_height, json_api_disabled, state_key_not_tracked, version_not_found,
+        version_pruned, ForbiddenError, InternalError, NotFoundError, ServiceUnavailableError,
+        StdApiError,
     },
+    tracked_state_view::TrackedStateView,
 };
 use anyhow::{anyhow, bail, ensure, format_err, Context as AnyhowContext, Result};
 use lumio_api_types::{
@@ -22,6 +24,7 @@ use lumio_gas_schedule::{LumioGasParameters, FromOnChainGasSchedule};
 use lumio_logger::{error, info, Schema};
 use lumio_mempool::{MempoolClientRequest, MempoolClientSender, SubmissionStatus};
 use lumio_storage_interface::{
+    partial_state::PartialStateStore,
     state_store::state_view::db_state_view::{
         DbStateView, DbStateViewAtVersion, LatestDbStateCheckpointView,
     },
@@ -44,6 +47,7 @@ use lumio_types::{
         state_value::StateValue,
         TStateView,
     },
+    state_sync_progress::SharedStateSyncProgress,
     transaction::{
         block_epilogue::BlockEndInfo,
         use_case::{UseCaseAwareTransaction, UseCaseKey},
@@ -83,6 +87,8 @@ pub struct Context {
     simulate_txn_stats: Arc<FunctionStats>,
     pub indexer_reader: Option<Arc<dyn IndexerReader>>,
     pub wait_for_hash_active_connections: Arc<AtomicUsize>,
+    pub state_sync_progress: SharedStateSyncProgress,
+    pub partial_state_store: Option<Arc<PartialStateStore>>,
 }
 
 impl std::fmt::Debug for Context {
@@ -135,9 +141,26 @@ impl Context {
             simulate_txn_stats,
             indexer_reader,
             wait_for_hash_active_connections: Arc::new(AtomicUsize::new(0)),
+            state_sync_progress: SharedStateSyncProgress::new(),
+            partial_state_store: None,
         }
     }
 
+    /// Sets the state sync progress report served by the API
+    pub fn with_state_sync_progress(
+        mut self,
+        state_sync_progress: SharedStateSyncProgress,
+    ) -> Self {
+        self.state_sync_progress = state_sync_progress;
+        self
+    }
+
+    /// Sets the partial state store (if we're a partial state node)
+    pub fn with_partial_state_store(mut self, partial_state_store: Arc<PartialStateStore>) -> Self {
+        self.partial_state_store = Some(partial_state_store);
+        self
+    }
+
     pub fn max_transactions_page_size(&self) -> u16 {
         self.node_config.api.max_transactions_page_size
     }
@@ -154,16 +177,18 @@ impl Context {
         self.node_config.api.max_account_modules_page_size
     }
 
-    pub fn latest_state_view(&self) -> Result<DbStateView> {
-        Ok(self.db.latest_state_checkpoint_view()?)
+    pub fn latest_state_view(&self) -> Result<TrackedStateView> {
+        let state_view = self.db.latest_state_checkpoint_view()?;
+        Ok(self.create_tracked_state_view(state_view))
     }
 
     pub fn latest_state_view_poem<E: InternalError>(
         &self,
         ledger_info: &LedgerInfo,
-    ) -> Result<DbStateView, E> {
+    ) -> Result<TrackedStateView, E> {
         self.db
             .latest_state_checkpoint_view()
+            .map(|state_view| self.create_tracked_state_view(state_view))
             .context("Failed to read latest state checkpoint from DB")
             .map_err(|e| E::internal_with_code(e, LumioErrorCode::InternalError, ledger_info))
     }
@@ -178,7 +203,7 @@ impl Context {
     pub fn state_view<E: StdApiError>(
         &self,
         requested_ledger_version: Option<u64>,
-    ) -> Result<(LedgerInfo, u64, DbStateView), E> {
+    ) -> Result<(LedgerInfo, u64, TrackedStateView), E> {
         let (latest_ledger_info, requested_ledger_version) =
             self.get_latest_ledger_info_and_verify_lookup_version(requested_ledger_version)?;
 
@@ -191,8 +216,22 @@ impl Context {
         Ok((latest_ledger_info, requested_ledger_version, state_view))
     }
 
-    pub fn state_view_at_version(&self, version: Version) -> Result<DbStateView> {
-        Ok(self.db.state_view_at_version(Some(version))?)
+    pub fn state_view_at_version(&self, version: Version) -> Result<TrackedStateView> {
+        let state_view = self.db.state_view_at_version(Some(version))?;
+        Ok(self.create_tracked_state_view(state_view))
+    }
+
+    /// Wraps the given state view so that reads of untracked
+    /// state keys are rejected (if we're a partial state node).
+    fn create_tracked_state_view(&self, state_view: DbStateView) -> TrackedStateView {
+        TrackedStateView::new(state_view, self.partial_state_store.clone())
+    }
+
+    /// Returns true iff prefixed state values should be read via the indexer
+    /// reader. This is required if storage sharding is enabled, unless we're a
+    /// partial state node (where the states are served by the partial state store).
+    fn read_prefixed_state_values_from_indexer(&self) -> bool {
+        db_sharding_enabled(&self.node_config) && self.partial_state_store.is_none()
     }
 
     pub fn chain_id(&self) -> ChainId {
@@ -374,8 +413,7 @@ impl Context {
 
     pub fn get_state_value(&self, state_key: &StateKey, version: u64) -> Result<Option<Vec<u8>>> {
         Ok(self
-            .db
-            .state_view_at_version(Some(version))?
+            .state_view_at_version(version)?
             .get_state_value_bytes(state_key)?
             .map(|val| val.to_vec()))
     }
@@ -441,7 +479,7 @@ impl Context {
         address: AccountAddress,
         version: u64,
     ) -> Result<HashMap<StateKey, StateValue>> {
-        let mut iter = if !db_sharding_enabled(&self.node_config) {
+        let mut iter = if !self.read_prefixed_state_values_from_indexer() {
             Box::new(
                 self.db
                     .get_prefixed_state_value_iterator(
@@ -475,7 +513,7 @@ impl Context {
         version: u64,
         limit: u64,
     ) -> Result<(Vec<(StructTag, Vec<u8>)>, Option<StateKey>)> {
-        let account_iter = if !db_sharding_enabled(&self.node_config) {
+        let account_iter = if !self.read_prefixed_state_values_from_indexer() {
             Box::new(
                 self.db
                     .get_prefixed_state_value_iterator(
@@ -566,7 +604,7 @@ impl Context {
         version: u64,
         limit: u64,
     ) -> Result<(Vec<(ModuleId, Vec<u8>)>, Option<StateKey>)> {
-        let account_iter = if !db_sharding_enabled(&self.node_config) {
+        let account_iter = if !self.read_prefixed_state_values_from_indexer() {
             Box::new(
                 self.db
                     .get_prefixed_state_value_iterator(
@@ -1283,7 +1321,7 @@ impl Context {
         }
     }
 
-    pub fn estimate_gas_price<E: InternalError>(
+    pub fn estimate_gas_price<E: ForbiddenError + InternalError>(
         &self,
         ledger_info: &LedgerInfo,
     ) -> Result<GasEstimation, E> {
@@ -1455,12 +1493,15 @@ impl Context {
         Ok(estimation)
     }
 
-    fn min_gas_unit_price<E: InternalError>(&self, ledger_info: &LedgerInfo) -> Result<u64, E> {
+    fn min_gas_unit_price<E: ForbiddenError + InternalError>(
+        &self,
+        ledger_info: &LedgerInfo,
+    ) -> Result<u64, E> {
         let (_, gas_schedule) = self.get_gas_schedule(ledger_info)?;
         Ok(gas_schedule.vm.txn.min_price_per_gas_unit.into())
     }
 
-    pub fn get_gas_schedule<E: InternalError>(
+    pub fn get_gas_schedule<E: ForbiddenError + InternalError>(
         &self,
         ledger_info: &LedgerInfo,
     ) -> Result<(u64, LumioGasParameters), E> {
@@ -1496,8 +1537,7 @@ impl Context {
 
             // Retrieve the gas schedule from storage and parse it accordingly
             let state_view = self
-                .db
-                .state_view_at_version(Some(ledger_info.version()))
+                .state_view_at_version(ledger_info.version())
                 .map_err(|e| {
                     E::internal_with_code(e, LumioErrorCode::InternalError, ledger_info)
                 })?;
@@ -1521,11 +1561,12 @@ impl Context {
                             LumioGasParameters::from_on_chain_gas_schedule(&gas_schedule, 0).ok()
                         })
                         .ok_or_else(|| {
-                            E::internal_with_code(
+                            // The gas schedule may not be tracked (if we're a partial state node)
+                            state_view.untracked_error_or(E::internal_with_code(
                                 "Failed to retrieve gas schedule",
                                 LumioErrorCode::InternalError,
                                 ledger_info,
-                            )
+                            ))
                         }),
                 }?
             };
@@ -1540,7 +1581,7 @@ impl Context {
         }
     }
 
-    pub fn execution_onchain_config<E: InternalError>(
+    pub fn execution_onchain_config<E: ForbiddenError + InternalError>(
         &self,
         ledger_info: &LedgerInfo,
     ) -> Result<OnChainExecutionConfig, E> {
@@ -1566,14 +1607,16 @@ impl Context {
 
             // Retrieve the execution config from storage and parse it accordingly
             let state_view = self
-                .db
-                .state_view_at_version(Some(ledger_info.version()))
+                .state_view_at_version(ledger_info.version())
                 .map_err(|e| {
                     E::internal_with_code(e, LumioErrorCode::InternalError, ledger_info)
                 })?;
 
-            let execution_onchain_config = OnChainExecutionConfig::fetch_config(&state_view)
-                .unwrap_or_else(OnChainExecutionConfig::default_if_missing);
+            // Note: the missing config default must not be used if the config isn't tracked
+            let execution_onchain_config = OnChainExecutionConfig::fetch_config(&state_view);
+            state_view.check_state_keys_are_tracked()?;
+            let execution_onchain_config =
+                execution_onchain_config.unwrap_or_else(OnChainExecutionConfig::default_if_missing);
 
             // Update the cache
             cache.execution_onchain_config = execution_onchain_config.clone();
@@ -1602,6 +1645,20 @@ impl Context {
         Ok(())
     }
 
+    /// Verifies that the given state key is tracked by the node. Partial
+    /// state nodes only serve the states of the tracked accounts.
+    pub fn check_state_key_is_tracked<E: ForbiddenError>(
+        &self,
+        state_key: &StateKey,
+    ) -> Result<(), E> {
+        match &self.partial_state_store {
+            Some(partial_state_store) if !partial_state_store.is_tracked(state_key) => {
+                Err(state_key_not_tracked(format!("State key {:?}", state_key)))
+            },
+            _ => Ok(()),
+        }
+    }
+
     pub fn last_updated_gas_schedule(&self) -> Option<u64> {
         self.gas_schedule_cache.read().unwrap().last_updated_epoch
     }
@@ -25,6 +25,7 @@ pub mod spec;
 mod state;
 #[cfg(test)]
 pub mod tests;
+pub mod tracked_state_view;
 mod transactions;
 mod view_function;
 
@@ -621,6 +621,13 @@ pub fn api_disabled<S: Display, E: ForbiddenError>(identifier: S) -> E {
     )
 }
 
+pub fn state_key_not_tracked<S: Display, E: ForbiddenError>(identifier: S) -> E {
+    E::forbidden_with_code_no_info(
+        format!("{} is not tracked by this partial state node", identifier),
+        LumioErrorCode::StateKeyNotTracked,
+    )
+}
+
 pub fn api_forbidden<S: Display, E: ForbiddenError>(identifier: S, extra_help: S) -> E {
     E::forbidden_with_code_no_info(
         format!("{} is not allowed. {}", identifier, extra_help),
@@ -22,8 +22,11 @@ use anyhow::{anyhow, Context as AnyhowContext};
 use lumio_config::config::{ApiConfig, NodeConfig};
 use lumio_logger::info;
 use lumio_mempool::MempoolClientSender;
-use lumio_storage_interface::DbReader;
-use lumio_types::{chain_id::ChainId, indexer::indexer_db_reader::IndexerReader};
+use lumio_storage_interface::{partial_state::PartialStateStore, DbReader};
+use lumio_types::{
+    chain_id::ChainId, indexer::indexer_db_reader::IndexerReader,
+    state_sync_progress::SharedStateSyncProgress,
+};
 use futures::channel::oneshot;
 use poem::{
     handler,
@@ -46,12 +49,18 @@ pub fn bootstrap(
     db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
     indexer_reader: Option<Arc<dyn IndexerReader>>,
+    state_sync_progress: SharedStateSyncProgress,
+    partial_state_store: Option<Arc<PartialStateStore>>,
     port_tx: Option<oneshot::Sender<u16>>,
 ) -> anyhow::Result<Runtime> {
     let max_runtime_workers = get_max_runtime_workers(&config.api);
     let runtime = lumio_runtimes::spawn_named_runtime("api".into(), Some(max_runtime_workers));
 
-    let context = Context::new(chain_id, db, mp_sender, config.clone(), indexer_reader);
+    let mut context = Context::new(chain_id, db, mp_sender, config.clone(), indexer_reader)
+        .with_state_sync_progress(state_sync_progress);
+    if let Some(partial_state_store) = partial_state_store {
+        context = context.with_partial_state_store(partial_state_store);
+    }
 
     attach_poem_to_runtime(runtime.handle(), context.clone(), config, false, port_tx)
         .context("Failed to attach poem to runtime")?;
@@ -301,7 +310,7 @@ mod tests {
     use crate::runtime::get_max_runtime_workers;
     use lumio_api_test_context::{new_test_context, TestContext};
     use lumio_config::config::{ApiConfig, NodeConfig};
-    use lumio_types::chain_id::ChainId;
+    use lumio_types::{chain_id::ChainId, state_sync_progress::SharedStateSyncProgress};
     use std::time::Duration;
 
     // TODO: Unignore this when I figure out why this only works when being
@@ -363,6 +372,8 @@ mod tests {
             context.db.clone(),
             context.mempool.ac_client.clone(),
             None,
+            SharedStateSyncProgress::new(),
+            None,
             None,
         );
         assert!(ret.is_ok());
@@ -294,13 +294,7 @@ impl StateApi {
                 tag.to_canonical_string(),
                 address
             ))
-            .map_err(|err| {
-                BasicErrorWith404::internal_with_code(
-                    err,
-                    LumioErrorCode::InternalError,
-                    &ledger_info,
-                )
-            })?
+            .map_err(|err| state_view.convert_read_error(err, &ledger_info))?
             .ok_or_else(|| resource_not_found(address, &tag, ledger_version, &ledger_info))?;
 
         match accept_type {
@@ -345,13 +339,7 @@ impl StateApi {
         let bytes = state_view
             .get_state_value_bytes(&state_key)
             .context(format!("Failed to query DB to check for {:?}", state_key))
-            .map_err(|err| {
-                BasicErrorWith404::internal_with_code(
-                    err,
-                    LumioErrorCode::InternalError,
-                    &ledger_info,
-                )
-            })?
+            .map_err(|err| state_view.convert_read_error(err, &ledger_info))?
             .ok_or_else(|| module_not_found(address, &name, ledger_version, &ledger_info))?;
 
         match accept_type {
@@ -434,13 +422,7 @@ impl StateApi {
                 "Failed when trying to retrieve table item from the DB with key: {}",
                 key
             ))
-            .map_err(|err| {
-                BasicErrorWith404::internal_with_code(
-                    err,
-                    LumioErrorCode::InternalError,
-                    &ledger_info,
-                )
-            })?
+            .map_err(|err| state_view.convert_read_error(err, &ledger_info))?
             .ok_or_else(|| {
                 table_item_not_found(table_handle, &key, ledger_version, &ledger_info)
             })?;
@@ -489,13 +471,7 @@ impl StateApi {
                 "Failed when trying to retrieve table item from the DB with key: {}",
                 table_item_request.key,
             ))
-            .map_err(|err| {
-                BasicErrorWith404::internal_with_code(
-                    err,
-                    LumioErrorCode::InternalError,
-                    &ledger_info,
-                )
-            })?
+            .map_err(|err| state_view.convert_read_error(err, &ledger_info))?
             .ok_or_else(|| {
                 build_not_found(
                     "Table Item",
@@ -548,13 +524,7 @@ impl StateApi {
         let state_value = state_view
             .get_state_value(&state_key)
             .context(format!("Failed fetching state value. key: {}", request.key,))
-            .map_err(|err| {
-                BasicErrorWith404::internal_with_code(
-                    err,
-                    LumioErrorCode::InternalError,
-                    &ledger_info,
-                )
-            })?
+            .map_err(|err| state_view.convert_read_error(err, &ledger_info))?
             .ok_or_else(|| {
                 build_not_found(
                     "Raw State Value",
@@ -4,6 +4,7 @@
 
 use super::new_test_context;
 use lumio_api_test_context::current_function_name;
+use lumio_types::state_sync_progress::{StateSyncPhase, StateSyncProgress};
 use serde_json::json;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
@@ -123,6 +124,35 @@ async fn test_cors_on_non_200_responses() {
     assert_eq!(cors_header, "test");
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_info_state_sync_progress() {
+    let context = new_test_context(current_function_name!());
+
+    // Verify that no progress is reported before state sync publishes a report
+    let info = context.get("/info").await;
+    assert!(info.get("state_sync_progress").is_none());
+
+    // Publish a progress report and verify it is reported
+    context
+        .context
+        .state_sync_progress
+        .update_progress(StateSyncProgress {
+            phase: StateSyncPhase::Transactions,
+            synced_version: 200,
+            target_version: Some(1000),
+            num_states_synced: None,
+            num_states_total: None,
+            throughput_per_second: Some(20),
+            estimated_seconds_remaining: Some(40),
+        });
+    let info = context.get("/info").await;
+    let state_sync_progress = &info["state_sync_progress"];
+    assert_eq!(state_sync_progress["phase"], "transactions");
+    assert_eq!(state_sync_progress["synced_version"], 200);
+    assert_eq!(state_sync_progress["target_version"], 1000);
+    assert_eq!(state_sync_progress["estimated_seconds_remaining"], 40);
+}
+
 /// Verifies gzip compression is applied when accept-encoding header is present
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_compression_middleware() {
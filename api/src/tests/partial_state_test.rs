@@ -0,0 +1,114 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::new_test_context_with_config;
+use lumio_api_test_context::{current_function_name, TestContext};
+use lumio_config::config::{NodeConfig, PartialStateSyncConfig};
+use lumio_types::account_address::AccountAddress;
+use serde_json::{json, Value};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_partial_state_resource() {
+    // Create a partial state node that tracks the framework and the root account
+    let root_address = AccountAddress::from_hex_literal("0xa550c18").unwrap();
+    let context = new_partial_state_test_context(current_function_name!(), &[
+        AccountAddress::ONE,
+        root_address,
+    ]);
+
+    // Verify that a tracked resource can be read
+    context
+        .expect_status_code(200)
+        .get(&get_account_resource(root_address))
+        .await;
+
+    // Verify that an untracked resource is rejected
+    let untracked_address = AccountAddress::from_hex_literal("0xa550c19").unwrap();
+    let resp = context
+        .expect_status_code(403)
+        .get(&get_account_resource(untracked_address))
+        .await;
+    assert_state_key_not_tracked(&resp);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_partial_state_view_function() {
+    // Create a partial state node that only tracks the framework
+    let context = new_partial_state_test_context(current_function_name!(), &[AccountAddress::ONE]);
+
+    // Verify that a view function that only reads tracked state succeeds
+    context
+        .expect_status_code(200)
+        .post("/view", build_coin_decimals_request())
+        .await;
+
+    // Verify that a view function that reads untracked state is rejected
+    let resp = context
+        .expect_status_code(403)
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::coin::balance",
+                "arguments": vec!["0xa550c18"],
+                "type_arguments": vec!["0x1::lumio_coin::LumioCoin"],
+            }),
+        )
+        .await;
+    assert_state_key_not_tracked(&resp);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_partial_state_untracked_framework() {
+    // Create a partial state node that doesn't track the framework
+    let root_address = AccountAddress::from_hex_literal("0xa550c18").unwrap();
+    let context = new_partial_state_test_context(current_function_name!(), &[root_address]);
+
+    // Verify that gas estimation is rejected (the gas schedule isn't tracked)
+    let resp = context
+        .expect_status_code(403)
+        .get("/estimate_gas_price")
+        .await;
+    assert_state_key_not_tracked(&resp);
+
+    // Verify that view functions are rejected (the framework modules aren't tracked)
+    let resp = context
+        .expect_status_code(403)
+        .post("/view", build_coin_decimals_request())
+        .await;
+    assert_state_key_not_tracked(&resp);
+}
+
+/// Verifies that the given response is a state key not tracked error
+fn assert_state_key_not_tracked(resp: &Value) {
+    assert_eq!(resp["error_code"], "state_key_not_tracked");
+}
+
+fn get_account_resource(address: AccountAddress) -> String {
+    format!("/accounts/{}/resource/0x1::account::Account", address)
+}
+
+fn build_coin_decimals_request() -> Value {
+    let arguments: Vec<String> = Vec::new();
+    json!({
+        "function": "0x1::coin::decimals",
+        "arguments": arguments,
+        "type_arguments": vec!["0x1::lumio_coin::LumioCoin"],
+    })
+}
+
+/// Creates a test context for a partial state node that tracks the given addresses
+fn new_partial_state_test_context(
+    test_name: String,
+    tracked_addresses: &[AccountAddress],
+) -> TestContext {
+    let mut node_config = NodeConfig::default();
+    node_config.state_sync.partial_state_sync = PartialStateSyncConfig {
+        enable_partial_state_sync: true,
+        tracked_address_prefixes: tracked_addresses
+            .iter()
+            .map(|address| address.to_hex())
+            .collect(),
+        ..Default::default()
+    };
+    new_test_context_with_config(test_name, node_config, false, false)
+}
@@ -0,0 +1,110 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::response::{state_key_not_tracked, ForbiddenError, InternalError};
+use lumio_api_types::{LumioErrorCode, LedgerInfo};
+use lumio_storage_interface::{
+    partial_state::PartialStateStore, state_store::state_view::db_state_view::DbStateView,
+};
+use lumio_types::{
+    state_store::{
+        errors::StateViewError, state_key::StateKey, state_slot::StateSlot,
+        state_storage_usage::StateStorageUsage, StateViewId, StateViewResult, TStateView,
+    },
+    transaction::Version,
+};
+use std::sync::{Arc, Mutex};
+
+/// The state view used to serve API requests. On partial state nodes, the
+/// view rejects reads of state keys that are not tracked by the node, and
+/// records the first rejected key. This allows requests that read state
+/// indirectly (e.g., view functions, simulations and config reads) to be
+/// failed with a `StateKeyNotTracked` error.
+pub struct TrackedStateView {
+    state_view: DbStateView,
+    partial_state_store: Option<Arc<PartialStateStore>>,
+    untracked_state_key: Mutex<Option<StateKey>>,
+}
+
+impl TrackedStateView {
+    pub fn new(
+        state_view: DbStateView,
+        partial_state_store: Option<Arc<PartialStateStore>>,
+    ) -> Self {
+        Self {
+            state_view,
+            partial_state_store,
+            untracked_state_key: Mutex::new(None),
+        }
+    }
+
+    /// Returns the first untracked state key read via the view (if any)
+    pub fn untracked_state_key(&self) -> Option<StateKey> {
+        self.untracked_state_key.lock().unwrap().clone()
+    }
+
+    /// Returns a `StateKeyNotTracked` error if an untracked state
+    /// key was read via the view. Otherwise, returns Ok.
+    pub fn check_state_keys_are_tracked<E: ForbiddenError>(&self) -> Result<(), E> {
+        match self.untracked_state_key() {
+            Some(state_key) => Err(state_key_not_tracked(format!("State key {:?}", state_key))),
+            None => Ok(()),
+        }
+    }
+
+    /// Converts the given state read error into an API error. If an untracked
+    /// state key was read, a `StateKeyNotTracked` error is returned. Otherwise,
+    /// an internal error is returned.
+    pub fn convert_read_error<E: ForbiddenError + InternalError>(
+        &self,
+        error: anyhow::Error,
+        ledger_info: &LedgerInfo,
+    ) -> E {
+        self.untracked_error_or(E::internal_with_code(
+            error,
+            LumioErrorCode::InternalError,
+            ledger_info,
+        ))
+    }
+
+    /// Returns a `StateKeyNotTracked` error if an untracked state key was
+    /// read via the view (i.e., the untracked read caused the failure).
+    /// Otherwise, returns the given error.
+    pub fn untracked_error_or<E: ForbiddenError>(&self, error: E) -> E {
+        self.check_state_keys_are_tracked().err().unwrap_or(error)
+    }
+}
+
+impl TStateView for TrackedStateView {
+    type Key = StateKey;
+
+    fn id(&self) -> StateViewId {
+        self.state_view.id()
+    }
+
+    fn get_state_slot(&self, state_key: &StateKey) -> StateViewResult<StateSlot> {
+        // Verify the state key is tracked (if we're a partial state node)
+        if let Some(partial_state_store) = &self.partial_state_store {
+            if !partial_state_store.is_tracked(state_key) {
+                self.untracked_state_key
+                    .lock()
+                    .unwrap()
+                    .get_or_insert_with(|| state_key.clone());
+                return Err(StateViewError::Other(format!(
+                    "The state key is not tracked by this partial state node: {:?}",
+                    state_key
+                )));
+            }
+        }
+
+        self.state_view.get_state_slot(state_key)
+    }
+
+    fn get_usage(&self) -> StateViewResult<StateStorageUsage> {
+        self.state_view.get_usage()
+    }
+
+    fn next_version(&self) -> Version {
+        self.state_view.next_version()
+    }
+}
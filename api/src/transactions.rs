@@ -648,6 +648,8 @@ impl TransactionsApi {
                     vec![signed_transaction.sender().to_vec()],
                     context.node_config.api.max_gas_view_function,
                 );
+                // Reject the request if the balance lookup read untracked state
+                state_view.check_state_keys_are_tracked::<SubmitTransactionError>()?;
                 let values = output.values.map_err(|status| {
                     let (err_string, vm_error_code) =
                         convert_view_function_error(&status, &state_view, &context);
@@ -1560,6 +1562,9 @@ impl TransactionsApi {
             LumioSimulationVM::create_vm_and_simulate_signed_transaction(&txn, &state_view);
         let version = ledger_info.version();
 
+        // Reject the simulation if it read any untracked state (e.g., on a partial state node)
+        state_view.check_state_keys_are_tracked::<SubmitTransactionError>()?;
+
         // Ensure that all known statuses return their values in the output (even if they aren't supposed to)
         let exe_status = ExecutionStatus::conmbine_vm_status_for_simulation(
             output.auxiliary_data(),
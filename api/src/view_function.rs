@@ -121,11 +121,11 @@ fn view_request(
             .as_converter(context.db.clone(), context.indexer_reader.clone())
             .convert_view_function(data.0)
             .map_err(|err| {
-                BasicErrorWith404::bad_request_with_code(
+                state_view.untracked_error_or(BasicErrorWith404::bad_request_with_code(
                     err,
                     LumioErrorCode::InvalidInput,
                     &ledger_info,
-                )
+                ))
             })?,
         ViewFunctionRequest::Bcs(data) | ViewFunctionRequest::Old(data) => {
             bcs::from_bytes_with_limit(data.0.as_slice(), MAX_RECURSIVE_TYPES_ALLOWED as usize)
@@ -164,6 +164,9 @@ fn view_request(
         context.node_config.api.max_gas_view_function,
     );
 
+    // Reject the request if the function read any untracked state (e.g., on a partial state node)
+    state_view.check_state_keys_are_tracked::<BasicErrorWith404>()?;
+
     let values = output.values.map_err(|status| {
         let (err_string, vm_error_code) =
             convert_view_function_error(&status, &state_view, &context);
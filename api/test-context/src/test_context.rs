@@ -32,7 +32,8 @@ use lumio_sdk::{
     },
 };
 use lumio_storage_interface::{
-    state_store::state_view::db_state_view::DbStateView, DbReaderWriter,
+    partial_state::PartialStateStore, state_store::state_view::db_state_view::DbStateView,
+    DbReaderWriter,
 };
 use lumio_temppath::TempPath;
 use lumio_types::{
@@ -206,7 +207,7 @@ pub fn new_test_context_inner(
         end_version,
     );
 
-    let context = Context::new(
+    let mut context = Context::new(
         ChainId::test(),
         db.clone(),
         mempool.ac_client.clone(),
@@ -214,6 +215,16 @@ pub fn new_test_context_inner(
         mock_indexer_service.get_indexer_reader(),
     );
 
+    // Create the partial state store (if partial state syncing is enabled)
+    let partial_state_sync_config = &node_config.state_sync.partial_state_sync;
+    if partial_state_sync_config.enable_partial_state_sync {
+        let tracked_key_prefixes = partial_state_sync_config
+            .get_tracked_key_prefixes()
+            .unwrap();
+        let partial_state_store = PartialStateStore::new(tracked_key_prefixes, None);
+        context = context.with_partial_state_store(Arc::new(partial_state_store));
+    }
+
     // Configure the testing depending on which API version we're testing.
     let runtime_handle = tokio::runtime::Handle::current();
     let poem_address =
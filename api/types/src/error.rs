@@ -81,6 +81,8 @@ pub enum LumioErrorCode {
     BlockNotFound = 108,
     ///  StateValue not found at the requested version
     StateValueNotFound = 109,
+    /// State key is not tracked by this node (e.g., a partial state node)
+    StateKeyNotTracked = 110,
 
     /// Ledger version is pruned
     VersionPruned = 200,
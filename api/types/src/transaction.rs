@@ -22,6 +22,7 @@ use lumio_types::{
     block_metadata_ext::BlockMetadataExt,
     contract_event::{ContractEvent, EventWithVersion},
     dkg::{DKGTranscript, DKGTranscriptMetadata},
+    equivocation::{EquivocationEvidence, EquivocationKind},
     function_info::FunctionInfo,
     jwks::{jwk::JWK, ProviderJWKs, QuorumCertifiedUpdate},
     keyless,
@@ -679,6 +680,7 @@ impl BlockMetadataTransaction {
 pub enum ValidatorTransaction {
     ObservedJwkUpdate(JWKUpdateTransaction),
     DkgResult(DKGResultTransaction),
+    EquivocationEvidence(EquivocationEvidenceTransaction),
 }
 
 impl ValidatorTransaction {
@@ -688,6 +690,9 @@ impl ValidatorTransaction {
                 "validator_transaction__observed_jwk_update"
             },
             ValidatorTransaction::DkgResult(_) => "validator_transaction__dkg_result",
+            ValidatorTransaction::EquivocationEvidence(_) => {
+                "validator_transaction__equivocation_evidence"
+            },
         }
     }
 
@@ -695,6 +700,7 @@ impl ValidatorTransaction {
         match self {
             ValidatorTransaction::ObservedJwkUpdate(t) => &t.info,
             ValidatorTransaction::DkgResult(t) => &t.info,
+            ValidatorTransaction::EquivocationEvidence(t) => &t.info,
         }
     }
 
@@ -702,6 +708,7 @@ impl ValidatorTransaction {
         match self {
             ValidatorTransaction::ObservedJwkUpdate(t) => &mut t.info,
             ValidatorTransaction::DkgResult(t) => &mut t.info,
+            ValidatorTransaction::EquivocationEvidence(t) => &mut t.info,
         }
     }
 
@@ -709,6 +716,7 @@ impl ValidatorTransaction {
         match self {
             ValidatorTransaction::ObservedJwkUpdate(t) => t.timestamp,
             ValidatorTransaction::DkgResult(t) => t.timestamp,
+            ValidatorTransaction::EquivocationEvidence(t) => t.timestamp,
         }
     }
 
@@ -716,6 +724,7 @@ impl ValidatorTransaction {
         match self {
             ValidatorTransaction::ObservedJwkUpdate(t) => &t.events,
             ValidatorTransaction::DkgResult(t) => &t.events,
+            ValidatorTransaction::EquivocationEvidence(t) => &t.events,
         }
     }
 }
@@ -753,6 +762,14 @@ impl
                 timestamp: U64::from(timestamp),
                 quorum_certified_update: quorum_certified_update.into(),
             }),
+            lumio_types::validator_txn::ValidatorTransaction::EquivocationEvidence(evidence) => {
+                Self::EquivocationEvidence(EquivocationEvidenceTransaction {
+                    info,
+                    events,
+                    timestamp: U64::from(timestamp),
+                    evidence: evidence.into(),
+                })
+            },
         }
     }
 }
@@ -861,6 +878,51 @@ impl From<DKGTranscript> for ExportedDKGTranscript {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct EquivocationEvidenceTransaction {
+    #[serde(flatten)]
+    #[oai(flatten)]
+    pub info: TransactionInfo,
+    pub events: Vec<Event>,
+    pub timestamp: U64,
+    pub evidence: ExportedEquivocationEvidence,
+}
+
+/// A more API-friendly representation of `lumio_types::equivocation::EquivocationEvidence`, with its proof in BCS.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ExportedEquivocationEvidence {
+    pub author: Address,
+    pub epoch: U64,
+    pub round: U64,
+    /// Either `vote` or `proposal`
+    pub kind: String,
+    pub proof: HexEncodedBytes,
+}
+
+impl From<EquivocationEvidence> for ExportedEquivocationEvidence {
+    fn from(value: EquivocationEvidence) -> Self {
+        let kind = match value.kind() {
+            EquivocationKind::Vote => "vote",
+            EquivocationKind::Proposal => "proposal",
+        };
+        let EquivocationEvidence {
+            author,
+            epoch,
+            round,
+            proof,
+        } = value;
+        Self {
+            author: author.into(),
+            epoch: epoch.into(),
+            round: round.into(),
+            kind: kind.to_string(),
+            proof: HexEncodedBytes::from(
+                to_bytes(&proof).expect("BCS serialization of EquivocationProof can't fail"),
+            ),
+        }
+    }
+}
+
 /// An event from a transaction
 #[derive(Clone, Debug, Deserialize, Eq, Object, PartialEq, Serialize)]
 pub struct Event {
@@ -11,6 +11,7 @@ use crate::{
 use lumio_types::chain_id::ChainId;
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
+use std::path::PathBuf;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -21,6 +22,12 @@ pub struct AdminServiceConfig {
     // If empty, will allow all requests without authentication. (Not allowed on mainnet.)
     pub authentication_configs: Vec<AuthenticationConfig>,
     pub malloc_stats_max_len: usize,
+    // Directory to create online DB checkpoints in. Online checkpoints are disabled if unset.
+    // Must be on the same filesystem as the DB, as checkpoints are made of hardlinks.
+    pub checkpoint_dir: Option<PathBuf>,
+    // Number of most recent online checkpoints to keep, older ones are deleted after a new
+    // checkpoint is made.
+    pub checkpoint_retention: usize,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
@@ -46,6 +53,8 @@ impl Default for AdminServiceConfig {
             port: 9102,
             authentication_configs: vec![],
             malloc_stats_max_len: 2 * 1024 * 1024,
+            checkpoint_dir: None,
+            checkpoint_retention: 2,
         }
     }
 }
@@ -77,6 +86,15 @@ impl ConfigSanitizer for AdminServiceConfig {
             }
         }
 
+        if node_config.admin_service.checkpoint_dir.is_some()
+            && node_config.admin_service.checkpoint_retention == 0
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "checkpoint_retention must be at least 1 when checkpoint_dir is set.".into(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -230,4 +248,26 @@ mod tests {
         assert_eq!(node_config.admin_service.enabled, Some(false));
         assert!(modified_config);
     }
+
+    #[test]
+    fn test_sanitize_checkpoint_retention() {
+        // Create a node config with checkpoints enabled but nothing retained
+        let node_config = NodeConfig {
+            admin_service: AdminServiceConfig {
+                checkpoint_dir: Some(PathBuf::from("/tmp/checkpoints")),
+                checkpoint_retention: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config fails sanitization
+        let error = AdminServiceConfig::sanitize(
+            &node_config,
+            NodeType::Validator,
+            Some(ChainId::testnet()),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
 }
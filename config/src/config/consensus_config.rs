@@ -102,6 +102,12 @@ pub struct ConsensusConfig {
     pub enable_round_timeout_msg: bool,
     pub enable_optimistic_proposal_rx: bool,
     pub enable_optimistic_proposal_tx: bool,
+    /// If set, every consensus message sent and received, local timeout and state computer result
+    /// is recorded to a trace file per epoch in this directory, to be replayed offline.
+    pub trace_recording_dir: Option<PathBuf>,
+    /// Whether equivocation evidence detected by this node is submitted on-chain as a validator
+    /// transaction. Evidence is persisted in the consensus db either way.
+    pub submit_equivocation_evidence: bool,
 }
 
 /// Deprecated
@@ -384,6 +390,8 @@ impl Default for ConsensusConfig {
             enable_round_timeout_msg: true,
             enable_optimistic_proposal_rx: true,
             enable_optimistic_proposal_tx: false,
+            trace_recording_dir: None,
+            submit_equivocation_evidence: false,
         }
     }
 }
@@ -49,6 +49,8 @@ pub struct ExecutionConfig {
     pub paranoid_hot_potato_verification: bool,
     /// Enables enhanced metrics around processed transactions
     pub processed_transactions_detailed_counters: bool,
+    /// Enables per-block conflict analytics for parallel execution (BlockSTM)
+    pub blockstm_conflict_analytics: bool,
     /// Used during DB bootstrapping
     pub genesis_waypoint: Option<WaypointConfig>,
 }
@@ -81,6 +83,7 @@ impl Default for ExecutionConfig {
             paranoid_hot_potato_verification: true,
             discard_failed_blocks: false,
             processed_transactions_detailed_counters: false,
+            blockstm_conflict_analytics: false,
             genesis_waypoint: None,
         }
     }
@@ -6,9 +6,13 @@ use crate::config::{
     config_optimizer::ConfigOptimizer, config_sanitizer::ConfigSanitizer,
     node_config_loader::NodeType, Error, NodeConfig,
 };
-use lumio_types::chain_id::ChainId;
+use lumio_types::{
+    account_address::AccountAddress, chain_id::ChainId,
+    state_store::state_key::prefix::StateKeyPrefix, transaction::Version,
+};
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
+use std::path::PathBuf;
 
 // Whether to enable size and time-aware chunking (for non-production networks).
 // Note: once this becomes stable, we should enable it for all networks (e.g., Mainnet).
@@ -31,11 +35,13 @@ const MAX_TRANSACTION_OUTPUT_CHUNK_SIZE: u64 = 3000;
 const MAX_CONCURRENT_REQUESTS: u64 = 6;
 const MAX_CONCURRENT_STATE_REQUESTS: u64 = 6;
 
-#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct StateSyncConfig {
+    pub backup_restore: BackupRestoreConfig,
     pub data_streaming_service: DataStreamingServiceConfig,
     pub lumio_data_client: LumioDataClientConfig,
+    pub partial_state_sync: PartialStateSyncConfig,
     pub state_sync_driver: StateSyncDriverConfig,
     pub storage_service: StorageServiceConfig,
 }
@@ -52,6 +58,11 @@ pub enum BootstrappingMode {
     ExecuteTransactionsFromGenesis,
     /// Executes transactions or applies outputs from genesis (whichever is faster)
     ExecuteOrApplyFromGenesis,
+    /// Restores the state snapshot (at the latest epoch ending version available)
+    /// from backup storage, instead of downloading the states from peers, and
+    /// applies the backed up transaction outputs after it (up to the last backed
+    /// up epoch ending version)
+    RestoreFromBackup,
 }
 
 impl BootstrappingMode {
@@ -65,12 +76,102 @@ impl BootstrappingMode {
                 "execute_transactions_from_genesis"
             },
             BootstrappingMode::ExecuteOrApplyFromGenesis => "execute_or_apply_from_genesis",
+            BootstrappingMode::RestoreFromBackup => "restore_from_backup",
         }
     }
 
-    /// Returns true iff the bootstrapping mode is fast sync
+    /// Returns true iff the bootstrapping mode is fast sync (i.e., the node
+    /// syncs a state snapshot instead of all transactions since genesis).
     pub fn is_fast_sync(&self) -> bool {
-        *self == BootstrappingMode::DownloadLatestStates
+        matches!(
+            self,
+            BootstrappingMode::DownloadLatestStates | BootstrappingMode::RestoreFromBackup
+        )
+    }
+
+    /// Returns true iff the state snapshot is restored from backup storage
+    pub fn is_restore_from_backup(&self) -> bool {
+        *self == BootstrappingMode::RestoreFromBackup
+    }
+}
+
+/// The backup storage to restore from when bootstrapping using
+/// `BootstrappingMode::RestoreFromBackup`. Exactly one storage must be set.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackupRestoreConfig {
+    /// The local directory holding the backups (i.e., the backup-cli `LocalFs` storage)
+    pub local_fs_dir: Option<PathBuf>,
+    /// The backup-cli `CommandAdapter` config file used to access remote backup storage
+    pub command_adapter_config: Option<PathBuf>,
+    /// The directory to cache backup metadata files (defaults to a temporary directory)
+    pub metadata_cache_dir: Option<PathBuf>,
+    /// The maximum number of concurrent downloads from the backup storage
+    pub max_concurrent_downloads: u64,
+}
+
+impl Default for BackupRestoreConfig {
+    fn default() -> Self {
+        Self {
+            local_fs_dir: None,
+            command_adapter_config: None,
+            metadata_cache_dir: None,
+            max_concurrent_downloads: 8,
+        }
+    }
+}
+
+/// The config for partial state syncing. When enabled, the node only syncs
+/// (and serves) the states held under the tracked address prefixes, instead
+/// of the entire blockchain state. Note: this is only supported by fullnodes.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PartialStateSyncConfig {
+    /// Whether to enable partial state syncing
+    pub enable_partial_state_sync: bool,
+    /// The hex encoded address prefixes of the tracked accounts (and table handles)
+    pub tracked_address_prefixes: Vec<String>,
+    /// The interval (ms) at which to refresh the tracked states
+    pub refresh_interval_ms: u64,
+    /// The timeout (ms) of each data request sent when refreshing the tracked states
+    pub request_timeout_ms: u64,
+}
+
+impl Default for PartialStateSyncConfig {
+    fn default() -> Self {
+        Self {
+            enable_partial_state_sync: false,
+            tracked_address_prefixes: vec![],
+            refresh_interval_ms: 10_000, // 10 seconds
+            request_timeout_ms: 10_000,  // 10 seconds
+        }
+    }
+}
+
+impl PartialStateSyncConfig {
+    /// Returns the state key prefixes of all tracked states
+    pub fn get_tracked_key_prefixes(&self) -> Result<Vec<StateKeyPrefix>, Error> {
+        let mut tracked_key_prefixes = vec![];
+        for address_prefix in &self.tracked_address_prefixes {
+            let address_prefix = address_prefix.trim_start_matches("0x");
+            let address_prefix_bytes = hex::decode(address_prefix).map_err(|error| {
+                Error::Unexpected(format!(
+                    "Invalid tracked address prefix: {:?}. Error: {:?}",
+                    address_prefix, error
+                ))
+            })?;
+            if address_prefix_bytes.is_empty()
+                || address_prefix_bytes.len() > AccountAddress::LENGTH
+            {
+                return Err(Error::Unexpected(format!(
+                    "The tracked address prefix must be between 1 and {} bytes! Found: {:?}",
+                    AccountAddress::LENGTH,
+                    address_prefix
+                )));
+            }
+            tracked_key_prefixes.extend(StateKeyPrefix::for_address_prefix(&address_prefix_bytes));
+        }
+        Ok(tracked_key_prefixes)
     }
 }
 
@@ -85,6 +186,8 @@ pub enum ContinuousSyncingMode {
     ExecuteTransactions,
     /// Executes transactions or applies outputs to stay up-to-date (whichever is faster)
     ExecuteTransactionsOrApplyOutputs,
+    /// Does not sync beyond the bootstrapped version (e.g., for forensic nodes)
+    Frozen,
 }
 
 impl ContinuousSyncingMode {
@@ -95,8 +198,14 @@ impl ContinuousSyncingMode {
             ContinuousSyncingMode::ExecuteTransactionsOrApplyOutputs => {
                 "execute_transactions_or_apply_outputs"
             },
+            ContinuousSyncingMode::Frozen => "frozen",
         }
     }
+
+    /// Returns true iff the node should not sync beyond the bootstrapped version
+    pub fn is_frozen(&self) -> bool {
+        *self == ContinuousSyncingMode::Frozen
+    }
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
@@ -104,6 +213,9 @@ impl ContinuousSyncingMode {
 pub struct StateSyncDriverConfig {
     /// The mode by which to bootstrap
     pub bootstrapping_mode: BootstrappingMode,
+    /// The version of the state snapshot to bootstrap to when fast syncing. This must
+    /// be an epoch ending version. If not set, the latest snapshot is used.
+    pub bootstrapping_target_version: Option<Version>,
     /// The maximum time taken to process a commit notification
     pub commit_notification_timeout_ms: u64,
     /// The mode by which to sync after bootstrapping
@@ -136,6 +248,7 @@ impl Default for StateSyncDriverConfig {
     fn default() -> Self {
         Self {
             bootstrapping_mode: BootstrappingMode::ExecuteOrApplyFromGenesis,
+            bootstrapping_target_version: None,
             commit_notification_timeout_ms: 5000,
             continuous_syncing_mode: ContinuousSyncingMode::ExecuteTransactionsOrApplyOutputs,
             enable_auto_bootstrapping: false,
@@ -189,6 +302,8 @@ pub struct StorageServiceConfig {
     pub min_time_to_ignore_peers_secs: u64,
     /// The interval (ms) to refresh the request moderator state
     pub request_moderator_refresh_interval_ms: u64,
+    /// The request scheduler config (i.e., request prioritization and per-peer fairness)
+    pub request_scheduler: RequestSchedulerConfig,
     /// The interval (ms) to refresh the storage summary
     pub storage_summary_refresh_interval_ms: u64,
 }
@@ -213,11 +328,65 @@ impl Default for StorageServiceConfig {
             max_transaction_output_chunk_size: MAX_TRANSACTION_OUTPUT_CHUNK_SIZE,
             min_time_to_ignore_peers_secs: 300, // 5 minutes
             request_moderator_refresh_interval_ms: 1000, // 1 second
+            request_scheduler: RequestSchedulerConfig::default(),
             storage_summary_refresh_interval_ms: 100, // Optimal for <= 10 blocks per second
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RequestSchedulerConfig {
+    /// Whether or not to enable request scheduling. If disabled, all requests are
+    /// processed as they arrive (regardless of the request class or peer).
+    pub enable_request_scheduling: bool,
+
+    /// Whether or not to return throttled requests as `TooManyInvalidRequests`
+    /// errors, instead of `RequestThrottled` errors. Older clients are unable to
+    /// decode `RequestThrottled` errors, so this should only be disabled once
+    /// all clients have been upgraded.
+    pub legacy_throttling_errors: bool,
+
+    /// Maximum number of response bytes (per second) to send to a single peer for
+    /// bulk requests. Validators are exempt from this limit.
+    pub max_bulk_bytes_per_peer_per_second: u64,
+
+    /// Maximum number of bulk requests (e.g., historical transactions and state
+    /// values) to process concurrently across all peers.
+    pub max_concurrent_bulk_requests: u64,
+
+    /// Maximum number of bulk requests to process concurrently for a single
+    /// peer. Validators are exempt from this limit.
+    pub max_concurrent_bulk_requests_per_peer: u64,
+
+    /// The percentage of the concurrent bulk requests that may be used by peers on
+    /// the public network. The remainder is reserved for validators and VFNs.
+    pub max_public_bulk_requests_percentage: u64,
+}
+
+impl Default for RequestSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            enable_request_scheduling: true,
+            legacy_throttling_errors: true,
+            max_bulk_bytes_per_peer_per_second: 50 * 1024 * 1024, // 50 MiB
+            max_concurrent_bulk_requests: 128,
+            max_concurrent_bulk_requests_per_peer: 16,
+            max_public_bulk_requests_percentage: 75,
+        }
+    }
+}
+
+impl RequestSchedulerConfig {
+    /// Returns the maximum number of concurrent bulk requests for
+    /// peers on the public network.
+    pub fn max_concurrent_public_bulk_requests(&self) -> u64 {
+        self.max_concurrent_bulk_requests
+            .saturating_mul(self.max_public_bulk_requests_percentage)
+            / 100
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct DataStreamingServiceConfig {
@@ -388,6 +557,38 @@ impl Default for LumioDataMultiFetchConfig {
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LumioPeerReputationConfig {
+    /// The duration (in seconds) after which the reputation of a disconnected
+    /// peer is forgotten (unless the peer is currently banned).
+    pub disconnected_peer_ttl_secs: u64,
+    /// Whether or not to persist peer reputations across restarts (in the data directory)
+    pub enable_persistence: bool,
+    /// The duration (in seconds) of the first ban of a peer. Each subsequent
+    /// ban of the same peer doubles the duration (up to the maximum).
+    pub initial_ban_duration_secs: u64,
+    /// The maximum duration (in seconds) of a peer ban
+    pub max_ban_duration_secs: u64,
+    /// The number of proof verification failures before a peer is banned
+    pub max_proof_verification_failures: u64,
+    /// Interval (in seconds) between persisting the peer reputations
+    pub persistence_interval_secs: u64,
+}
+
+impl Default for LumioPeerReputationConfig {
+    fn default() -> Self {
+        Self {
+            disconnected_peer_ttl_secs: 604_800, // 1 week
+            enable_persistence: true,
+            initial_ban_duration_secs: 300, // 5 minutes
+            max_ban_duration_secs: 86_400,  // 1 day
+            max_proof_verification_failures: 3,
+            persistence_interval_secs: 60, // 1 minute
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct LumioLatencyFilteringConfig {
@@ -448,10 +649,15 @@ pub struct LumioDataClientConfig {
     pub max_transaction_output_chunk_size: u64,
     /// Timeout (in ms) when waiting for an optimistic fetch response
     pub optimistic_fetch_timeout_ms: u64,
+    /// The peer reputation config for the data client
+    pub peer_reputation_config: LumioPeerReputationConfig,
     /// First timeout (in ms) when waiting for a response
     pub response_timeout_ms: u64,
     /// Timeout (in ms) when waiting for a subscription response
     pub subscription_response_timeout_ms: u64,
+    /// Duration (in ms) to avoid sending (non-realtime) requests to a peer
+    /// after the peer has throttled one of our requests.
+    pub throttled_peer_cooldown_ms: u64,
     /// Whether or not to request compression for incoming data
     pub use_compression: bool,
 }
@@ -474,9 +680,11 @@ impl Default for LumioDataClientConfig {
             max_subscription_lag_secs: 20, // 20 seconds
             max_transaction_chunk_size: MAX_TRANSACTION_CHUNK_SIZE,
             max_transaction_output_chunk_size: MAX_TRANSACTION_OUTPUT_CHUNK_SIZE,
-            optimistic_fetch_timeout_ms: 5000,        // 5 seconds
+            optimistic_fetch_timeout_ms: 5000, // 5 seconds
+            peer_reputation_config: LumioPeerReputationConfig::default(),
             response_timeout_ms: 10_000,              // 10 seconds
             subscription_response_timeout_ms: 15_000, // 15 seconds (longer than a regular timeout because of prefetching)
+            throttled_peer_cooldown_ms: 1000,         // 1 second
             use_compression: true,
         }
     }
@@ -489,15 +697,111 @@ impl ConfigSanitizer for StateSyncConfig {
         chain_id: Option<ChainId>,
     ) -> Result<(), Error> {
         // Sanitize the state sync driver config
-        StateSyncDriverConfig::sanitize(node_config, node_type, chain_id)
+        StateSyncDriverConfig::sanitize(node_config, node_type, chain_id)?;
+
+        // Sanitize the backup restore config
+        BackupRestoreConfig::sanitize(node_config, node_type, chain_id)?;
+
+        // Sanitize the partial state sync config
+        PartialStateSyncConfig::sanitize(node_config, node_type, chain_id)?;
+
+        // Sanitize the storage service config
+        StorageServiceConfig::sanitize(node_config, node_type, chain_id)
     }
 }
 
-impl ConfigSanitizer for StateSyncDriverConfig {
+impl ConfigSanitizer for BackupRestoreConfig {
     fn sanitize(
         node_config: &NodeConfig,
         _node_type: NodeType,
         _chain_id: Option<ChainId>,
+    ) -> Result<(), Error> {
+        let sanitizer_name = Self::get_sanitizer_name();
+        let backup_restore_config = &node_config.state_sync.backup_restore;
+
+        // Verify that a single backup storage is configured for
+        // nodes that restore from backup.
+        let restore_from_backup = node_config
+            .state_sync
+            .state_sync_driver
+            .bootstrapping_mode
+            .is_restore_from_backup();
+        let num_storages = backup_restore_config.local_fs_dir.is_some() as usize
+            + backup_restore_config.command_adapter_config.is_some() as usize;
+        if restore_from_backup && num_storages != 1 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "Exactly one of local_fs_dir and command_adapter_config must be set for nodes \
+                that restore from backup!"
+                    .to_string(),
+            ));
+        }
+
+        // Verify that the number of concurrent downloads is non-zero
+        if backup_restore_config.max_concurrent_downloads == 0 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The maximum number of concurrent backup downloads must be non-zero!".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl ConfigSanitizer for PartialStateSyncConfig {
+    fn sanitize(
+        node_config: &NodeConfig,
+        node_type: NodeType,
+        _chain_id: Option<ChainId>,
+    ) -> Result<(), Error> {
+        let sanitizer_name = Self::get_sanitizer_name();
+        let partial_state_sync_config = &node_config.state_sync.partial_state_sync;
+
+        // Nothing to verify if partial state syncing is disabled
+        if !partial_state_sync_config.enable_partial_state_sync {
+            return Ok(());
+        }
+
+        // Verify that validators don't enable partial state syncing
+        if node_type.is_validator() {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "Partial state syncing should not be enabled for validators!".to_string(),
+            ));
+        }
+
+        // Verify that the tracked address prefixes are valid and non-empty
+        let tracked_key_prefixes = partial_state_sync_config
+            .get_tracked_key_prefixes()
+            .map_err(|error| {
+                Error::ConfigSanitizerFailed(sanitizer_name.clone(), error.to_string())
+            })?;
+        if tracked_key_prefixes.is_empty() {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "At least one tracked address prefix must be set for partial state syncing!"
+                    .to_string(),
+            ));
+        }
+
+        // Verify that the refresh interval is non-zero
+        if partial_state_sync_config.refresh_interval_ms == 0 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The partial state sync refresh interval must be non-zero!".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl ConfigSanitizer for StateSyncDriverConfig {
+    fn sanitize(
+        node_config: &NodeConfig,
+        node_type: NodeType,
+        _chain_id: Option<ChainId>,
     ) -> Result<(), Error> {
         let sanitizer_name = Self::get_sanitizer_name();
         let state_sync_driver_config = &node_config.state_sync.state_sync_driver;
@@ -513,6 +817,76 @@ impl ConfigSanitizer for StateSyncDriverConfig {
             ));
         }
 
+        // Verify that a bootstrapping target version is only set for nodes that are fast syncing
+        if state_sync_driver_config
+            .bootstrapping_target_version
+            .is_some()
+            && !fast_sync_enabled
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "A bootstrapping target version can only be set for nodes that are fast syncing!"
+                    .to_string(),
+            ));
+        }
+
+        // Verify that validators don't freeze continuous syncing
+        if node_type.is_validator() && state_sync_driver_config.continuous_syncing_mode.is_frozen()
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "Validators should not use the frozen continuous syncing mode!".to_string(),
+            ));
+        }
+
+        // Verify that consensus observer isn't enabled for frozen nodes
+        if node_config.consensus_observer.observer_enabled
+            && state_sync_driver_config.continuous_syncing_mode.is_frozen()
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "Consensus observer should not be enabled with the frozen continuous syncing mode!"
+                    .to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl ConfigSanitizer for StorageServiceConfig {
+    fn sanitize(
+        node_config: &NodeConfig,
+        _node_type: NodeType,
+        _chain_id: Option<ChainId>,
+    ) -> Result<(), Error> {
+        let sanitizer_name = Self::get_sanitizer_name();
+        let request_scheduler_config = &node_config.state_sync.storage_service.request_scheduler;
+
+        // Nothing to verify if request scheduling is disabled
+        if !request_scheduler_config.enable_request_scheduling {
+            return Ok(());
+        }
+
+        // Verify that the bulk request limits are non-zero
+        if request_scheduler_config.max_concurrent_bulk_requests == 0
+            || request_scheduler_config.max_concurrent_bulk_requests_per_peer == 0
+            || request_scheduler_config.max_bulk_bytes_per_peer_per_second == 0
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The request scheduler bulk request limits must be non-zero!".to_string(),
+            ));
+        }
+
+        // Verify that the public network percentage is valid
+        if request_scheduler_config.max_public_bulk_requests_percentage > 100 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The public bulk requests percentage must not be greater than 100!".to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -951,6 +1325,167 @@ mod tests {
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
 
+    #[test]
+    fn test_sanitize_restore_from_backup_storage() {
+        // Create a node config that restores from backup without a backup storage
+        let mut node_config = NodeConfig {
+            state_sync: StateSyncConfig {
+                state_sync_driver: StateSyncDriverConfig {
+                    bootstrapping_mode: BootstrappingMode::RestoreFromBackup,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that sanitization fails
+        let error =
+            StateSyncConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Configure both backup storages and verify that sanitization fails
+        let backup_restore_config = &mut node_config.state_sync.backup_restore;
+        backup_restore_config.local_fs_dir = Some(PathBuf::from("/opt/backups"));
+        backup_restore_config.command_adapter_config = Some(PathBuf::from("/opt/s3.yaml"));
+        let error =
+            StateSyncConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Configure a single backup storage and verify that sanitization succeeds
+        node_config.state_sync.backup_restore.command_adapter_config = None;
+        StateSyncConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap();
+    }
+
+    #[test]
+    fn test_sanitize_partial_state_sync() {
+        // Create a node config with partial state syncing enabled (but no tracked prefixes)
+        let mut node_config = NodeConfig {
+            state_sync: StateSyncConfig {
+                partial_state_sync: PartialStateSyncConfig {
+                    enable_partial_state_sync: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that sanitization fails
+        let error =
+            StateSyncConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Add an invalid tracked prefix and verify that sanitization fails
+        let partial_state_sync_config = &mut node_config.state_sync.partial_state_sync;
+        partial_state_sync_config.tracked_address_prefixes = vec!["0xinvalid".into()];
+        let error =
+            StateSyncConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Add valid tracked prefixes and verify that sanitization succeeds
+        let partial_state_sync_config = &mut node_config.state_sync.partial_state_sync;
+        partial_state_sync_config.tracked_address_prefixes = vec!["0x0a0b".into(), "cafe".into()];
+        StateSyncConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap();
+        let tracked_key_prefixes = node_config
+            .state_sync
+            .partial_state_sync
+            .get_tracked_key_prefixes()
+            .unwrap();
+        assert_eq!(tracked_key_prefixes.len(), 4); // Access paths and table items for each
+
+        // Verify that sanitization fails for validators
+        let error = StateSyncConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_sanitize_request_scheduler() {
+        // Create a node config with an invalid public bulk requests percentage
+        let mut node_config = NodeConfig {
+            state_sync: StateSyncConfig {
+                storage_service: StorageServiceConfig {
+                    request_scheduler: RequestSchedulerConfig {
+                        max_public_bulk_requests_percentage: 101,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that sanitization fails
+        let error =
+            StateSyncConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Set a valid percentage but disable the per-peer concurrency and verify sanitization fails
+        let request_scheduler_config =
+            &mut node_config.state_sync.storage_service.request_scheduler;
+        request_scheduler_config.max_public_bulk_requests_percentage = 50;
+        request_scheduler_config.max_concurrent_bulk_requests_per_peer = 0;
+        let error =
+            StateSyncConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Disable request scheduling and verify that sanitization succeeds
+        node_config
+            .state_sync
+            .storage_service
+            .request_scheduler
+            .enable_request_scheduling = false;
+        StateSyncConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap();
+
+        // Verify the public bulk request limit is calculated correctly
+        let request_scheduler_config = RequestSchedulerConfig {
+            max_concurrent_bulk_requests: 10,
+            max_public_bulk_requests_percentage: 75,
+            ..Default::default()
+        };
+        assert_eq!(
+            request_scheduler_config.max_concurrent_public_bulk_requests(),
+            7
+        );
+    }
+
+    #[test]
+    fn test_sanitize_pinned_bootstrapping_target() {
+        // Create a node config with a bootstrapping target version (but no fast sync)
+        let mut node_config = NodeConfig {
+            state_sync: StateSyncConfig {
+                state_sync_driver: StateSyncDriverConfig {
+                    bootstrapping_target_version: Some(1000),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that sanitization fails
+        let error =
+            StateSyncConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Enable fast sync and freeze continuous syncing, and verify that sanitization succeeds
+        let state_sync_driver_config = &mut node_config.state_sync.state_sync_driver;
+        state_sync_driver_config.bootstrapping_mode = BootstrappingMode::DownloadLatestStates;
+        state_sync_driver_config.continuous_syncing_mode = ContinuousSyncingMode::Frozen;
+        StateSyncConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap();
+
+        // Verify that sanitization fails for frozen validators
+        let error = StateSyncConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Verify that sanitization fails for frozen nodes running consensus observer
+        node_config.consensus_observer.observer_enabled = true;
+        let error =
+            StateSyncConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
     /// Creates and returns a node config with the syncing modes set to execution
     fn create_execution_mode_config() -> NodeConfig {
         NodeConfig {
@@ -139,6 +139,11 @@ pub struct RocksdbConfig {
     /// If not zero, dump stats to LOG every this many seconds. `None` means using RocksDB's
     /// default.
     pub stats_dump_period_sec: Option<u32>,
+    /// Keep the data in process memory instead of RocksDB, which is only meant to make tests
+    /// faster. Only honored in test builds of LumioDB (i.e. with its `fuzzing` feature), and never
+    /// read from or written to config files.
+    #[serde(skip)]
+    pub use_in_memory_backend: bool,
 }
 
 impl RocksdbConfig {
@@ -172,6 +177,7 @@ impl Default for RocksdbConfig {
             stats_level: Some(RocksDBStatsLevel::ExceptHistogramOrTimers),
             // Use RocksDB's default if not specified.
             stats_dump_period_sec: None,
+            use_in_memory_backend: false,
         }
     }
 }
@@ -24,6 +24,7 @@ pub mod proposal_msg;
 pub mod quorum_cert;
 pub mod randomness;
 pub mod request_response;
+pub mod round_timeline;
 pub mod round_timeout;
 pub mod safety_data;
 pub mod sync_info;
@@ -0,0 +1,171 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{Author, Round};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The consensus events recorded for each round, in the order they usually happen
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundEvent {
+    ProposalReceived,
+    ProposalVerified,
+    VoteSent,
+    QcFormed,
+    Ordered,
+    Executed,
+    Signed,
+    Committed,
+}
+
+/// The time (in microseconds since the unix epoch) of each event of a round, as observed by a
+/// single node. Only the first occurrence of each event is kept.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RoundTimeline {
+    pub epoch: u64,
+    pub round: Round,
+    pub events: BTreeMap<RoundEvent, u64>,
+}
+
+impl RoundTimeline {
+    pub fn new(epoch: u64, round: Round) -> Self {
+        Self {
+            epoch,
+            round,
+            events: BTreeMap::new(),
+        }
+    }
+}
+
+/// The round timelines recorded by a single node
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RoundTimelineDump {
+    /// The node's validator address, if it's a validator
+    pub author: Option<Author>,
+    pub rounds: Vec<RoundTimeline>,
+}
+
+/// An event of a round, as observed by one of the merged nodes
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MergedRoundEvent {
+    pub author: Option<Author>,
+    pub event: RoundEvent,
+    pub timestamp_usecs: u64,
+    /// Time since the first event of the round, across all nodes
+    pub since_round_start_usecs: u64,
+}
+
+/// The events of a round across several nodes, ordered by time
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MergedRoundTimeline {
+    pub epoch: u64,
+    pub round: Round,
+    pub events: Vec<MergedRoundEvent>,
+}
+
+/// Merges the dumps of several nodes into a single timeline per round, ordered by epoch and
+/// round. Note: the timestamps come from the clocks of each node, so they're only as comparable
+/// as the clocks are synchronized.
+pub fn merge_round_timelines(dumps: Vec<RoundTimelineDump>) -> Vec<MergedRoundTimeline> {
+    let mut rounds: BTreeMap<(u64, Round), Vec<(Option<Author>, RoundEvent, u64)>> =
+        BTreeMap::new();
+    for dump in dumps {
+        for timeline in dump.rounds {
+            let events = rounds.entry((timeline.epoch, timeline.round)).or_default();
+            events.extend(
+                timeline
+                    .events
+                    .into_iter()
+                    .map(|(event, timestamp_usecs)| (dump.author, event, timestamp_usecs)),
+            );
+        }
+    }
+
+    rounds
+        .into_iter()
+        .map(|((epoch, round), mut events)| {
+            events.sort_by_key(|(author, event, timestamp_usecs)| {
+                (*timestamp_usecs, *event, *author)
+            });
+            let round_start_usecs = events.first().map_or(0, |(_, _, timestamp)| *timestamp);
+            MergedRoundTimeline {
+                epoch,
+                round,
+                events: events
+                    .into_iter()
+                    .map(|(author, event, timestamp_usecs)| MergedRoundEvent {
+                        author,
+                        event,
+                        timestamp_usecs,
+                        since_round_start_usecs: timestamp_usecs - round_start_usecs,
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timeline(epoch: u64, round: Round, events: &[(RoundEvent, u64)]) -> RoundTimeline {
+        RoundTimeline {
+            epoch,
+            round,
+            events: events.iter().cloned().collect(),
+        }
+    }
+
+    #[test]
+    fn test_merge_round_timelines() {
+        let author_1 = Author::random();
+        let author_2 = Author::random();
+        let dumps = vec![
+            RoundTimelineDump {
+                author: Some(author_1),
+                rounds: vec![
+                    timeline(2, 5, &[
+                        (RoundEvent::ProposalReceived, 100),
+                        (RoundEvent::VoteSent, 130),
+                    ]),
+                    timeline(1, 9, &[(RoundEvent::Committed, 50)]),
+                ],
+            },
+            RoundTimelineDump {
+                author: Some(author_2),
+                rounds: vec![timeline(2, 5, &[
+                    (RoundEvent::ProposalReceived, 110),
+                    (RoundEvent::VoteSent, 120),
+                ])],
+            },
+        ];
+
+        let merged = merge_round_timelines(dumps);
+        assert_eq!(merged.len(), 2);
+
+        // Rounds are ordered by epoch and round
+        assert_eq!((merged[0].epoch, merged[0].round), (1, 9));
+        assert_eq!(merged[0].events, vec![MergedRoundEvent {
+            author: Some(author_1),
+            event: RoundEvent::Committed,
+            timestamp_usecs: 50,
+            since_round_start_usecs: 0,
+        }]);
+
+        // Events of all nodes are ordered by time
+        assert_eq!((merged[1].epoch, merged[1].round), (2, 5));
+        let events: Vec<_> = merged[1]
+            .events
+            .iter()
+            .map(|event| (event.author, event.event, event.since_round_start_usecs))
+            .collect();
+        assert_eq!(events, vec![
+            (Some(author_1), RoundEvent::ProposalReceived, 0),
+            (Some(author_2), RoundEvent::ProposalReceived, 10),
+            (Some(author_2), RoundEvent::VoteSent, 20),
+            (Some(author_1), RoundEvent::VoteSent, 30),
+        ]);
+    }
+}
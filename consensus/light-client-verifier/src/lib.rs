@@ -0,0 +1,133 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Verifies the commit decisions streamed by the consensus publisher to light clients.
+//!
+//! A light client starts from a trusted epoch waypoint and follows the chain of commit
+//! decisions: each decision must be signed by a quorum of the current validator set, and the
+//! decision ending an epoch carries the validator set of the next one. A client that falls
+//! behind by one or more epochs first catches up with an [`EpochChangeProof`] before it can
+//! verify the decisions of the latest epoch. The consensus publisher sends one from the epoch
+//! given on subscription, and again whenever it detects that the client missed an epoch change.
+
+use anyhow::{bail, format_err, Result};
+use lumio_types::{
+    epoch_change::EpochChangeProof,
+    epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::Version,
+    trusted_state::{TrustedState, TrustedStateChange},
+    waypoint::Waypoint,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// The result of verifying a commit decision (or epoch change proof)
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VerifiedUpdate {
+    /// The trusted version moved forward, in the same epoch
+    Version(Version),
+    /// The trusted version moved forward into a new epoch, with the given validator set
+    Epoch(EpochState),
+    /// The ledger info is the one already trusted
+    NoChange,
+}
+
+/// Tracks the trusted view of the ledger of a light client, ratcheting it forward with every
+/// verified commit decision.
+#[derive(Clone, Debug)]
+pub struct LightClientVerifier {
+    trusted_state: TrustedState,
+}
+
+impl LightClientVerifier {
+    /// Creates a verifier trusting the given epoch waypoint. The first commit decision (or epoch
+    /// change proof) must start with the epoch-ending ledger info matching the waypoint.
+    pub fn new(epoch_waypoint: Waypoint) -> Self {
+        Self {
+            trusted_state: TrustedState::from_epoch_waypoint(epoch_waypoint),
+        }
+    }
+
+    /// Creates a verifier from a previously saved trusted state
+    pub fn from_trusted_state(trusted_state: TrustedState) -> Self {
+        Self { trusted_state }
+    }
+
+    /// Returns the current trusted state (e.g., to persist it across restarts)
+    pub fn trusted_state(&self) -> &TrustedState {
+        &self.trusted_state
+    }
+
+    /// Returns the current trusted epoch state, if the waypoint has been verified
+    pub fn epoch_state(&self) -> Option<&EpochState> {
+        match &self.trusted_state {
+            TrustedState::EpochWaypoint(_) => None,
+            TrustedState::EpochState { epoch_state, .. } => Some(epoch_state),
+        }
+    }
+
+    /// Returns the current trusted version
+    pub fn version(&self) -> Version {
+        self.trusted_state.version()
+    }
+
+    /// Verifies a commit decision of the current epoch (or the one ending it) and ratchets the
+    /// trusted state forward. Stale decisions are rejected.
+    pub fn verify_commit_decision(
+        &mut self,
+        commit_proof: &LedgerInfoWithSignatures,
+    ) -> Result<VerifiedUpdate> {
+        // The decision ending the epoch is its own epoch change proof
+        let epoch_change_proof = if commit_proof.ledger_info().ends_epoch() {
+            EpochChangeProof::new(vec![commit_proof.clone()], false)
+        } else {
+            EpochChangeProof::new(vec![], false)
+        };
+        self.verify_and_ratchet(commit_proof, &epoch_change_proof)
+    }
+
+    /// Verifies a chain of epoch changes starting at the current trusted epoch (stale ledger
+    /// infos are skipped) and ratchets the trusted state into the last epoch of the proof.
+    pub fn verify_epoch_change_proof(
+        &mut self,
+        epoch_change_proof: &EpochChangeProof,
+    ) -> Result<VerifiedUpdate> {
+        let latest_li = epoch_change_proof
+            .ledger_info_with_sigs
+            .last()
+            .ok_or_else(|| format_err!("The epoch change proof is empty"))?;
+        self.verify_and_ratchet(latest_li, epoch_change_proof)
+    }
+
+    fn verify_and_ratchet(
+        &mut self,
+        latest_li: &LedgerInfoWithSignatures,
+        epoch_change_proof: &EpochChangeProof,
+    ) -> Result<VerifiedUpdate> {
+        let change = self
+            .trusted_state
+            .verify_and_ratchet_inner(latest_li, epoch_change_proof)?;
+        let update = match &change {
+            TrustedStateChange::Version { new_state } => {
+                VerifiedUpdate::Version(new_state.version())
+            },
+            TrustedStateChange::Epoch { new_state, .. } => match new_state {
+                TrustedState::EpochState { epoch_state, .. } => {
+                    VerifiedUpdate::Epoch(epoch_state.clone())
+                },
+                TrustedState::EpochWaypoint(_) => {
+                    bail!("An epoch change must lead to an epoch state")
+                },
+            },
+            TrustedStateChange::NoChange => VerifiedUpdate::NoChange,
+        };
+        if let Some(new_state) = change.new_state() {
+            self.trusted_state = new_state;
+        }
+        Ok(update)
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{LightClientVerifier, VerifiedUpdate};
+use lumio_crypto::HashValue;
+use lumio_types::{
+    block_info::BlockInfo,
+    epoch_change::EpochChangeProof,
+    epoch_state::EpochState,
+    ledger_info::{generate_ledger_info_with_sig, LedgerInfo, LedgerInfoWithSignatures},
+    transaction::Version,
+    validator_signer::ValidatorSigner,
+    validator_verifier::{random_validator_verifier, ValidatorVerifier},
+    waypoint::Waypoint,
+};
+
+/// Returns a commit decision signed by the given signers, ending the epoch if the next
+/// validator verifier is specified.
+fn commit_decision(
+    signers: &[ValidatorSigner],
+    epoch: u64,
+    version: Version,
+    next_verifier: Option<&ValidatorVerifier>,
+) -> LedgerInfoWithSignatures {
+    let next_epoch_state =
+        next_verifier.map(|verifier| EpochState::new(epoch + 1, verifier.clone()));
+    let block_info = BlockInfo::new(
+        epoch,
+        version,
+        HashValue::random(),
+        HashValue::random(),
+        version,
+        version,
+        next_epoch_state,
+    );
+    generate_ledger_info_with_sig(signers, LedgerInfo::new(block_info, HashValue::zero()))
+}
+
+#[test]
+fn test_follow_commit_decisions() {
+    let (signers_0, _) = random_validator_verifier(4, None, false);
+    let (signers_1, verifier_1) = random_validator_verifier(4, None, false);
+    let (signers_2, verifier_2) = random_validator_verifier(4, None, false);
+
+    // Start from the waypoint of the ledger info ending epoch 0
+    let epoch_0_end = commit_decision(&signers_0, 0, 10, Some(&verifier_1));
+    let waypoint = Waypoint::new_epoch_boundary(epoch_0_end.ledger_info()).unwrap();
+    let mut light_client = LightClientVerifier::new(waypoint);
+    assert!(light_client.epoch_state().is_none());
+
+    // A commit decision of epoch 1 can't be verified before the waypoint
+    let commit = commit_decision(&signers_1, 1, 20, None);
+    assert!(light_client.verify_commit_decision(&commit).is_err());
+
+    // Verify the waypoint ledger info, moving into epoch 1
+    assert_eq!(
+        light_client.verify_commit_decision(&epoch_0_end).unwrap(),
+        VerifiedUpdate::Epoch(EpochState::new(1, verifier_1.clone()))
+    );
+    assert_eq!(
+        light_client.verify_commit_decision(&epoch_0_end).unwrap(),
+        VerifiedUpdate::NoChange
+    );
+
+    // Follow the commit decisions of epoch 1
+    assert_eq!(
+        light_client.verify_commit_decision(&commit).unwrap(),
+        VerifiedUpdate::Version(20)
+    );
+    assert_eq!(light_client.version(), 20);
+
+    // Decisions signed by the wrong validators, and stale decisions, are rejected
+    let forged = commit_decision(&signers_2, 1, 30, None);
+    assert!(light_client.verify_commit_decision(&forged).is_err());
+    let stale = commit_decision(&signers_1, 1, 15, None);
+    assert!(light_client.verify_commit_decision(&stale).is_err());
+    assert_eq!(light_client.version(), 20);
+
+    // The decision ending epoch 1 moves the light client into epoch 2
+    let epoch_1_end = commit_decision(&signers_1, 1, 40, Some(&verifier_2));
+    assert_eq!(
+        light_client.verify_commit_decision(&epoch_1_end).unwrap(),
+        VerifiedUpdate::Epoch(EpochState::new(2, verifier_2))
+    );
+
+    // After which only the validators of epoch 2 are trusted
+    assert!(light_client
+        .verify_commit_decision(&commit_decision(&signers_1, 2, 50, None))
+        .is_err());
+    assert_eq!(
+        light_client
+            .verify_commit_decision(&commit_decision(&signers_2, 2, 50, None))
+            .unwrap(),
+        VerifiedUpdate::Version(50)
+    );
+}
+
+#[test]
+fn test_catch_up_with_epoch_change_proof() {
+    let (signers_0, _) = random_validator_verifier(4, None, false);
+    let (signers_1, verifier_1) = random_validator_verifier(4, None, false);
+    let (signers_2, verifier_2) = random_validator_verifier(4, None, false);
+    let (_, verifier_3) = random_validator_verifier(4, None, false);
+
+    let epoch_0_end = commit_decision(&signers_0, 0, 10, Some(&verifier_1));
+    let epoch_1_end = commit_decision(&signers_1, 1, 20, Some(&verifier_2));
+    let epoch_2_end = commit_decision(&signers_2, 2, 30, Some(&verifier_3));
+    let waypoint = Waypoint::new_epoch_boundary(epoch_0_end.ledger_info()).unwrap();
+    let mut light_client = LightClientVerifier::new(waypoint);
+
+    // A decision ending a later epoch can't be verified without the epochs in between
+    assert!(light_client.verify_commit_decision(&epoch_2_end).is_err());
+
+    // An empty or broken proof is rejected
+    assert!(light_client
+        .verify_epoch_change_proof(&EpochChangeProof::new(vec![], false))
+        .is_err());
+    assert!(light_client
+        .verify_epoch_change_proof(&EpochChangeProof::new(
+            vec![epoch_0_end.clone(), epoch_2_end.clone()],
+            false
+        ))
+        .is_err());
+
+    // Catch up to epoch 2, and then follow its commit decisions
+    assert_eq!(
+        light_client
+            .verify_epoch_change_proof(&EpochChangeProof::new(
+                vec![epoch_0_end.clone(), epoch_1_end.clone()],
+                false
+            ))
+            .unwrap(),
+        VerifiedUpdate::Epoch(EpochState::new(2, verifier_2))
+    );
+    assert_eq!(
+        light_client.verify_commit_decision(&epoch_2_end).unwrap(),
+        VerifiedUpdate::Epoch(EpochState::new(3, verifier_3))
+    );
+
+    // Fully stale proofs are rejected
+    assert!(light_client
+        .verify_epoch_change_proof(&EpochChangeProof::new(
+            vec![epoch_0_end, epoch_1_end],
+            false
+        ))
+        .is_err());
+}
@@ -19,6 +19,10 @@ pub enum Error {
     IncorrectPreferredRound(u64, u64),
     #[error("Unable to verify that the new tree extends the parent: {0}")]
     InvalidAccumulatorExtension(String),
+    #[error("Invalid safety data interchange: {0}")]
+    InvalidInterchange(String),
+    #[error("Importing would lower {0} from {1} to {2}")]
+    InterchangeLowersWatermark(String, u64, u64),
     #[error("Invalid EpochChangeProof: {0}")]
     InvalidEpochChangeProof(String),
     #[error("Internal error: {0}")]
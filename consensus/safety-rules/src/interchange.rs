@@ -0,0 +1,149 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Slashing-protection interchange: a versioned document carrying the safety data of a
+//! validator, signed with its consensus key, so it can be moved to another secure storage
+//! backend without copying the backend itself.
+
+use crate::{persistent_safety_storage::PersistentSafetyStorage, Error};
+use lumio_consensus_types::{common::Author, safety_data::SafetyData};
+use lumio_crypto::{bls12381, PrivateKey, Signature, SigningKey};
+use lumio_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use lumio_logger::prelude::*;
+use lumio_types::waypoint::Waypoint;
+use serde::{Deserialize, Serialize};
+
+/// The version of the interchange format produced by this code.
+pub const INTERCHANGE_VERSION: u64 = 1;
+
+/// The signed part of an interchange document.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, CryptoHasher, BCSCryptoHash)]
+pub struct SafetyDataExport {
+    pub version: u64,
+    pub author: Author,
+    pub consensus_public_key: bls12381::PublicKey,
+    pub waypoint: Waypoint,
+    pub safety_data: SafetyData,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SafetyDataInterchange {
+    pub data: SafetyDataExport,
+    pub signature: bls12381::Signature,
+}
+
+impl SafetyDataInterchange {
+    pub fn from_json(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    pub fn to_json(&self) -> Result<Vec<u8>, Error> {
+        Ok(serde_json::to_vec_pretty(self)?)
+    }
+}
+
+/// Exports the safety data of `storage`, signed with its default consensus key.
+pub fn export(storage: &mut PersistentSafetyStorage) -> Result<SafetyDataInterchange, Error> {
+    let consensus_private_key = storage.default_consensus_sk()?;
+    let data = SafetyDataExport {
+        version: INTERCHANGE_VERSION,
+        author: storage.author()?,
+        consensus_public_key: consensus_private_key.public_key(),
+        waypoint: storage.waypoint()?,
+        safety_data: storage.safety_data()?,
+    };
+    let signature = consensus_private_key
+        .sign(&data)
+        .map_err(|error| Error::InternalError(error.to_string()))?;
+    Ok(SafetyDataInterchange { data, signature })
+}
+
+/// Imports `interchange` into `storage`, which must hold the same author and consensus key.
+/// Fails without writing anything if any watermark in `storage` is ahead of the imported one.
+pub fn import(
+    storage: &mut PersistentSafetyStorage,
+    interchange: &SafetyDataInterchange,
+) -> Result<(), Error> {
+    let data = &interchange.data;
+    if data.version != INTERCHANGE_VERSION {
+        return Err(Error::InvalidInterchange(format!(
+            "unsupported version {}, expected {}",
+            data.version, INTERCHANGE_VERSION
+        )));
+    }
+
+    let author = storage.author()?;
+    if data.author != author {
+        return Err(Error::InvalidInterchange(format!(
+            "exported for {}, but the storage belongs to {}",
+            data.author, author
+        )));
+    }
+    // Only succeeds if the storage holds the private key of the exported public key.
+    storage.consensus_sk_by_pk(data.consensus_public_key.clone())?;
+    interchange
+        .signature
+        .verify(data, &data.consensus_public_key)
+        .map_err(|error| Error::InvalidInterchange(format!("bad signature: {}", error)))?;
+
+    let current = storage.safety_data()?;
+    let imported = &data.safety_data;
+    if imported.epoch < current.epoch {
+        return Err(Error::InterchangeLowersWatermark(
+            "epoch".into(),
+            current.epoch,
+            imported.epoch,
+        ));
+    }
+    // Rounds start over in every epoch, so they only compare within the same one.
+    if imported.epoch == current.epoch {
+        for (name, current, imported) in [
+            (
+                "last_voted_round",
+                current.last_voted_round,
+                imported.last_voted_round,
+            ),
+            (
+                "preferred_round",
+                current.preferred_round,
+                imported.preferred_round,
+            ),
+            (
+                "one_chain_round",
+                current.one_chain_round,
+                imported.one_chain_round,
+            ),
+            (
+                "highest_timeout_round",
+                current.highest_timeout_round,
+                imported.highest_timeout_round,
+            ),
+        ] {
+            if imported < current {
+                return Err(Error::InterchangeLowersWatermark(
+                    name.into(),
+                    current,
+                    imported,
+                ));
+            }
+        }
+    }
+    // A storage that was never given a waypoint has nothing to lower.
+    if let Ok(current_waypoint) = storage.waypoint() {
+        if data.waypoint.version() < current_waypoint.version() {
+            return Err(Error::InterchangeLowersWatermark(
+                "waypoint version".into(),
+                current_waypoint.version(),
+                data.waypoint.version(),
+            ));
+        }
+    }
+
+    storage.set_safety_data(imported.clone())?;
+    storage.set_waypoint(&data.waypoint)?;
+    info!(
+        "Imported safety data for {}: {}, waypoint {}",
+        author, imported, data.waypoint
+    );
+    Ok(())
+}
@@ -7,6 +7,7 @@
 mod consensus_state;
 mod counters;
 mod error;
+pub mod interchange;
 mod local_client;
 mod logging;
 mod persistent_safety_storage;
@@ -20,7 +21,7 @@ mod t_safety_rules;
 mod thread;
 
 pub use crate::{
-    consensus_state::ConsensusState, error::Error,
+    consensus_state::ConsensusState, error::Error, interchange::SafetyDataInterchange,
     persistent_safety_storage::PersistentSafetyStorage, process::Process,
     safety_rules::SafetyRules, safety_rules_manager::SafetyRulesManager,
     t_safety_rules::TSafetyRules,
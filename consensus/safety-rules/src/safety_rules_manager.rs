@@ -3,13 +3,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    interchange::{self, SafetyDataInterchange},
     local_client::LocalClient,
     persistent_safety_storage::PersistentSafetyStorage,
     process::ProcessService,
     remote_service::RemoteService,
     serializer::{SerializerClient, SerializerService},
     thread::ThreadService,
-    SafetyRules, TSafetyRules,
+    Error, SafetyRules, TSafetyRules,
 };
 use lumio_config::config::{InitialSafetyRulesConfig, SafetyRulesConfig, SafetyRulesService};
 use lumio_crypto::bls12381::PublicKey;
@@ -103,6 +104,32 @@ pub fn storage(config: &SafetyRulesConfig) -> PersistentSafetyStorage {
     }
 }
 
+/// Exports the safety data in the storage of `config` as a signed interchange document.
+pub fn export_safety_data(config: &SafetyRulesConfig) -> Result<SafetyDataInterchange, Error> {
+    let internal_storage: Storage = (&config.backend).into();
+    internal_storage.available()?;
+    let mut storage = PersistentSafetyStorage::new(internal_storage, false);
+    interchange::export(&mut storage)
+}
+
+/// Imports a signed interchange document into the storage of `config`, which must already be
+/// initialized. Refuses to lower any watermark already in the storage.
+pub fn import_safety_data(
+    config: &SafetyRulesConfig,
+    interchange: &SafetyDataInterchange,
+) -> Result<(), Error> {
+    let internal_storage: Storage = (&config.backend).into();
+    internal_storage.available()?;
+    let mut storage = PersistentSafetyStorage::new(internal_storage, false);
+    if let Err(error) = storage.author() {
+        return Err(Error::SecureStorageUnexpectedError(format!(
+            "Safety rules storage is not initialized: {}",
+            error
+        )));
+    }
+    interchange::import(&mut storage, interchange)
+}
+
 enum SafetyRulesWrapper {
     Local(Arc<RwLock<SafetyRules>>),
     Process(ProcessService),
@@ -0,0 +1,155 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    interchange::{self, SafetyDataInterchange, INTERCHANGE_VERSION},
+    safety_rules_manager, test_utils, Error, PersistentSafetyStorage,
+};
+use lumio_config::config::{OnDiskStorageConfig, SafetyRulesConfig, SecureBackend};
+use lumio_consensus_types::safety_data::SafetyData;
+use lumio_secure_storage::{KVStorage, OnDiskStorage, Storage, VaultStorage};
+use lumio_types::validator_signer::ValidatorSigner;
+use lumio_vault_client::dev::{self, ROOT_TOKEN};
+use std::path::PathBuf;
+
+fn exported(signer: &ValidatorSigner, safety_data: SafetyData) -> SafetyDataInterchange {
+    let mut storage = test_utils::test_storage(signer);
+    storage.set_safety_data(safety_data).unwrap();
+    interchange::export(&mut storage).unwrap()
+}
+
+fn initialize(storage: Storage, signer: &ValidatorSigner) -> PersistentSafetyStorage {
+    PersistentSafetyStorage::initialize(
+        storage,
+        signer.author(),
+        signer.private_key().clone(),
+        test_utils::validator_signers_to_waypoint(&[signer]),
+        true,
+    )
+}
+
+fn on_disk_config(path: PathBuf) -> SafetyRulesConfig {
+    let mut on_disk = OnDiskStorageConfig::default();
+    on_disk.path = path;
+    SafetyRulesConfig {
+        backend: SecureBackend::OnDiskStorage(on_disk),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_import_into_fresh_on_disk_storage() {
+    let signer = ValidatorSigner::from_int(0);
+    let document = exported(&signer, SafetyData::new(3, 12, 10, 11, None, 12));
+    let document = SafetyDataInterchange::from_json(&document.to_json().unwrap()).unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("secure_storage.json");
+    initialize(Storage::from(OnDiskStorage::new(path.clone())), &signer);
+    let config = on_disk_config(path.clone());
+    safety_rules_manager::import_safety_data(&config, &document).unwrap();
+
+    let mut storage = PersistentSafetyStorage::new(Storage::from(OnDiskStorage::new(path)), true);
+    assert_eq!(storage.safety_data().unwrap(), document.data.safety_data);
+    assert_eq!(storage.waypoint().unwrap(), document.data.waypoint);
+    let exported_again = safety_rules_manager::export_safety_data(&config).unwrap();
+    assert_eq!(exported_again.data, document.data);
+}
+
+#[test]
+fn test_import_into_uninitialized_storage() {
+    let signer = ValidatorSigner::from_int(0);
+    let document = exported(&signer, SafetyData::new(3, 12, 10, 11, None, 12));
+
+    let dir = tempfile::tempdir().unwrap();
+    let config = on_disk_config(dir.path().join("secure_storage.json"));
+    assert!(matches!(
+        safety_rules_manager::import_safety_data(&config, &document).unwrap_err(),
+        Error::SecureStorageUnexpectedError(_)
+    ));
+}
+
+#[test]
+fn test_import_refuses_to_lower_watermarks() {
+    let signer = ValidatorSigner::from_int(0);
+    let mut storage = test_utils::test_storage(&signer);
+    let current = SafetyData::new(3, 20, 18, 19, None, 20);
+    storage.set_safety_data(current.clone()).unwrap();
+
+    let lower_round = exported(&signer, SafetyData::new(3, 12, 18, 19, None, 20));
+    assert_eq!(
+        interchange::import(&mut storage, &lower_round).unwrap_err(),
+        Error::InterchangeLowersWatermark("last_voted_round".into(), 20, 12)
+    );
+    let lower_epoch = exported(&signer, SafetyData::new(2, 30, 28, 29, None, 30));
+    assert_eq!(
+        interchange::import(&mut storage, &lower_epoch).unwrap_err(),
+        Error::InterchangeLowersWatermark("epoch".into(), 3, 2)
+    );
+    assert_eq!(storage.safety_data().unwrap(), current);
+
+    // Rounds of a later epoch are newer even when they are lower.
+    let later_epoch = exported(&signer, SafetyData::new(4, 2, 1, 1, None, 2));
+    interchange::import(&mut storage, &later_epoch).unwrap();
+    assert_eq!(storage.safety_data().unwrap(), later_epoch.data.safety_data);
+}
+
+#[test]
+fn test_import_rejects_invalid_documents() {
+    let signer = ValidatorSigner::from_int(0);
+    let document = exported(&signer, SafetyData::new(3, 12, 10, 11, None, 12));
+    let mut storage = test_utils::test_storage(&signer);
+
+    let mut tampered = document.clone();
+    tampered.data.safety_data.last_voted_round = 100;
+    assert!(matches!(
+        interchange::import(&mut storage, &tampered),
+        Err(Error::InvalidInterchange(_))
+    ));
+
+    let mut unsupported = document.clone();
+    unsupported.data.version = INTERCHANGE_VERSION + 1;
+    assert!(matches!(
+        interchange::import(&mut storage, &unsupported),
+        Err(Error::InvalidInterchange(_))
+    ));
+
+    let mut other_validator = test_utils::test_storage(&ValidatorSigner::from_int(1));
+    assert!(matches!(
+        interchange::import(&mut other_validator, &document),
+        Err(Error::InvalidInterchange(_))
+    ));
+
+    interchange::import(&mut storage, &document).unwrap();
+}
+
+/// Depends on running Vault, see `vault.rs`.
+#[test]
+fn test_import_into_vault() {
+    if dev::test_host_safe().is_none() {
+        return;
+    }
+
+    let signer = ValidatorSigner::from_int(0);
+    let document = exported(&signer, SafetyData::new(3, 12, 10, 11, None, 12));
+
+    let mut vault = Storage::from(VaultStorage::new(
+        dev::test_host(),
+        ROOT_TOKEN.to_string(),
+        None,
+        None,
+        true,
+        None,
+        None,
+    ));
+    vault.reset_and_clear().unwrap();
+    let mut storage = initialize(vault, &signer);
+    interchange::import(&mut storage, &document).unwrap();
+    assert_eq!(storage.safety_data().unwrap(), document.data.safety_data);
+
+    let lower = exported(&signer, SafetyData::new(3, 5, 5, 5, None, 5));
+    assert_eq!(
+        interchange::import(&mut storage, &lower).unwrap_err(),
+        Error::InterchangeLowersWatermark("last_voted_round".into(), 12, 5)
+    );
+}
@@ -18,6 +18,7 @@ use crate::{
         execution_client::TExecutionClient,
         pipeline_builder::{PipelineBuilder, PreCommitStatus},
     },
+    round_timeline::{record_round_event, RoundTimelineRecorder},
     util::time_service::TimeService,
 };
 use anyhow::{bail, ensure, format_err, Context};
@@ -28,6 +29,7 @@ use lumio_consensus_types::{
     common::Round,
     pipelined_block::{ExecutionSummary, OrderedBlockWindow, PipelinedBlock},
     quorum_cert::QuorumCert,
+    round_timeline::RoundEvent,
     sync_info::SyncInfo,
     timeout_2chain::TwoChainTimeoutCertificate,
     wrapped_ledger_info::WrappedLedgerInfo,
@@ -55,9 +57,13 @@ mod block_store_test;
 #[path = "sync_manager.rs"]
 pub mod sync_manager;
 
-fn update_counters_for_ordered_blocks(ordered_blocks: &[Arc<PipelinedBlock>]) {
+fn update_counters_for_ordered_blocks(
+    ordered_blocks: &[Arc<PipelinedBlock>],
+    round_timeline: Option<&RoundTimelineRecorder>,
+) {
     for block in ordered_blocks {
         observe_block(block.block().timestamp_usecs(), BlockStage::ORDERED);
+        record_round_event(round_timeline, block.round(), RoundEvent::Ordered);
         if block.block().is_opt_block() {
             observe_block(
                 block.block().timestamp_usecs(),
@@ -340,7 +346,7 @@ impl BlockStore {
         self.inner
             .write()
             .insert_ordered_cert(finality_proof_clone.clone());
-        update_counters_for_ordered_blocks(&blocks_to_commit);
+        update_counters_for_ordered_blocks(&blocks_to_commit, self.round_timeline().as_deref());
 
         self.execution_client
             .finalize_order(blocks_to_commit, finality_proof.clone())
@@ -539,6 +545,11 @@ impl BlockStore {
                     pipelined_block.block().timestamp_usecs(),
                     BlockStage::QC_ADDED,
                 );
+                record_round_event(
+                    self.round_timeline().as_deref(),
+                    pipelined_block.round(),
+                    RoundEvent::QcFormed,
+                );
                 if pipelined_block.block().is_opt_block() {
                     observe_block(
                         pipelined_block.block().timestamp_usecs(),
@@ -614,6 +625,13 @@ impl BlockStore {
     pub fn pre_commit_status(&self) -> Option<Arc<Mutex<PreCommitStatus>>> {
         self.pre_commit_status.clone()
     }
+
+    /// Returns the round timeline of the epoch (if the pipeline records one)
+    pub fn round_timeline(&self) -> Option<Arc<RoundTimelineRecorder>> {
+        self.pipeline_builder
+            .as_ref()
+            .and_then(PipelineBuilder::round_timeline)
+    }
 }
 
 impl BlockReader for BlockStore {
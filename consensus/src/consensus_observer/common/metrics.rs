@@ -226,6 +226,16 @@ pub static PUBLISHER_NUM_ACTIVE_SUBSCRIBERS: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Gauge for tracking the number of commit decision subscribers for the consensus publisher
+pub static PUBLISHER_NUM_COMMIT_DECISION_SUBSCRIBERS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "consensus_publisher_num_commit_decision_subscribers",
+        "Gauge related to commit decision subscribers for the consensus publisher",
+        &["network_id"]
+    )
+    .unwrap()
+});
+
 /// Counter for tracking received RPC requests by the consensus publisher
 pub static PUBLISHER_RECEIVED_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
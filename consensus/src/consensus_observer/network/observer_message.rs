@@ -11,7 +11,7 @@ use lumio_consensus_types::{
 use lumio_crypto::{hash::CryptoHash, HashValue};
 use lumio_types::{
     block_info::{BlockInfo, Round},
-    epoch_change::Verifier,
+    epoch_change::{EpochChangeProof, Verifier},
     epoch_state::EpochState,
     ledger_info::LedgerInfoWithSignatures,
     transaction::SignedTransaction,
@@ -61,6 +61,13 @@ impl ConsensusObserverMessage {
         let block_payload = BlockPayload::new(block, transaction_payload);
         ConsensusObserverDirectSend::BlockPayload(block_payload)
     }
+
+    /// Creates and returns a new epoch change proof message using the given proof
+    pub fn new_epoch_change_proof_message(
+        epoch_change_proof: EpochChangeProof,
+    ) -> ConsensusObserverDirectSend {
+        ConsensusObserverDirectSend::EpochChangeProof(epoch_change_proof)
+    }
 }
 
 impl Display for ConsensusObserverMessage {
@@ -84,6 +91,13 @@ impl Display for ConsensusObserverMessage {
 pub enum ConsensusObserverRequest {
     Subscribe,
     Unsubscribe,
+    /// Subscribes to commit decisions only (e.g., for light clients and bridges), starting
+    /// from the given epoch (i.e., the latest epoch the subscriber has verified). The publisher
+    /// first sends an epoch change proof from that epoch, and after that, epoch changes are
+    /// carried by the commit decisions that end each epoch.
+    SubscribeCommitDecisions {
+        epoch: u64,
+    },
 }
 
 impl ConsensusObserverRequest {
@@ -92,6 +106,9 @@ impl ConsensusObserverRequest {
         match self {
             ConsensusObserverRequest::Subscribe => "subscribe",
             ConsensusObserverRequest::Unsubscribe => "unsubscribe",
+            ConsensusObserverRequest::SubscribeCommitDecisions { .. } => {
+                "subscribe_commit_decisions"
+            },
         }
     }
 }
@@ -132,6 +149,8 @@ pub enum ConsensusObserverDirectSend {
     CommitDecision(CommitDecision),
     BlockPayload(BlockPayload),
     OrderedBlockWithWindow(OrderedBlockWithWindow),
+    /// Sent (only) to commit decision subscribers that are behind by one or more epochs
+    EpochChangeProof(EpochChangeProof),
 }
 
 impl ConsensusObserverDirectSend {
@@ -142,6 +161,7 @@ impl ConsensusObserverDirectSend {
             ConsensusObserverDirectSend::CommitDecision(_) => "commit_decision",
             ConsensusObserverDirectSend::BlockPayload(_) => "block_payload",
             ConsensusObserverDirectSend::OrderedBlockWithWindow(_) => "ordered_block_with_window",
+            ConsensusObserverDirectSend::EpochChangeProof(_) => "epoch_change_proof",
         }
     }
 }
@@ -172,6 +192,14 @@ impl Display for ConsensusObserverDirectSend {
                     ordered_block_with_window.ordered_block.proof_block_info(),
                 )
             },
+            ConsensusObserverDirectSend::EpochChangeProof(epoch_change_proof) => {
+                write!(
+                    f,
+                    "EpochChangeProof: ledger infos: {}, more: {}",
+                    epoch_change_proof.ledger_info_with_sigs.len(),
+                    epoch_change_proof.more,
+                )
+            },
         }
     }
 }
@@ -630,6 +630,15 @@ impl ConsensusObserver {
                 )
                 .await;
             },
+            ConsensusObserverDirectSend::EpochChangeProof(_) => {
+                // Epoch change proofs are only sent to commit decision subscribers
+                warn!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Received an unexpected epoch change proof from peer: {:?}",
+                        peer_network_id
+                    ))
+                );
+            },
         }
 
         // Update the metrics for the processed blocks
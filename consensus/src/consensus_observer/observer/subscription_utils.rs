@@ -859,8 +859,11 @@ mod tests {
 
         // Create a consensus publisher
         let consensus_observer_config = ConsensusObserverConfig::default();
-        let (consensus_publisher, _) =
-            ConsensusPublisher::new(consensus_observer_config, consensus_observer_client.clone());
+        let (consensus_publisher, _) = ConsensusPublisher::new(
+            consensus_observer_config,
+            consensus_observer_client.clone(),
+            Arc::new(MockDatabaseReader::new()),
+        );
         let consensus_publisher = Arc::new(consensus_publisher);
 
         // Sort the peers and verify that no peers are returned
@@ -921,6 +924,7 @@ mod tests {
             Arc::new(ConsensusPublisher::new_with_active_subscribers(
                 consensus_observer_config,
                 consensus_observer_client.clone(),
+                Arc::new(MockDatabaseReader::new()),
                 HashSet::from_iter(vec![sorted_peers[2]]),
             ));
 
@@ -20,9 +20,15 @@ use lumio_config::{config::ConsensusObserverConfig, network_id::PeerNetworkId};
 use lumio_infallible::RwLock;
 use lumio_logger::{error, info, warn};
 use lumio_network::application::interface::NetworkClient;
+use lumio_storage_interface::DbReader;
+use lumio_types::ledger_info::LedgerInfo;
 use futures::StreamExt;
 use futures_channel::mpsc;
-use std::{collections::HashSet, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 use tokio::time::interval;
 use tokio_stream::wrappers::IntervalStream;
 
@@ -39,6 +45,13 @@ pub struct ConsensusPublisher {
     // The set of active subscribers that have subscribed to consensus updates
     active_subscribers: Arc<RwLock<HashSet<PeerNetworkId>>>,
 
+    // The subscribers that have subscribed to commit decisions only, along with
+    // the latest epoch each subscriber is known to have verified.
+    commit_decision_subscribers: Arc<RwLock<HashMap<PeerNetworkId, u64>>>,
+
+    // The database reader (used to fetch epoch change proofs for commit decision subscribers)
+    db_reader: Arc<dyn DbReader>,
+
     // The sender for outbound network messages
     outbound_message_sender: mpsc::Sender<(PeerNetworkId, ConsensusObserverDirectSend)>,
 }
@@ -49,6 +62,7 @@ impl ConsensusPublisher {
         consensus_observer_client: Arc<
             ConsensusObserverClient<NetworkClient<ConsensusObserverMessage>>,
         >,
+        db_reader: Arc<dyn DbReader>,
     ) -> (
         Self,
         mpsc::Receiver<(PeerNetworkId, ConsensusObserverDirectSend)>,
@@ -63,6 +77,8 @@ impl ConsensusPublisher {
             consensus_observer_client,
             consensus_observer_config,
             active_subscribers: Arc::new(RwLock::new(HashSet::new())),
+            commit_decision_subscribers: Arc::new(RwLock::new(HashMap::new())),
+            db_reader,
             outbound_message_sender,
         };
 
@@ -77,11 +93,15 @@ impl ConsensusPublisher {
         consensus_observer_client: Arc<
             ConsensusObserverClient<NetworkClient<ConsensusObserverMessage>>,
         >,
+        db_reader: Arc<dyn DbReader>,
         active_subscribers: HashSet<PeerNetworkId>,
     ) -> Self {
         // Create the consensus publisher
-        let (consensus_publisher, _) =
-            ConsensusPublisher::new(consensus_observer_config, consensus_observer_client);
+        let (consensus_publisher, _) = ConsensusPublisher::new(
+            consensus_observer_config,
+            consensus_observer_client,
+            db_reader,
+        );
 
         // Update the active subscribers
         *consensus_publisher.active_subscribers.write() = active_subscribers;
@@ -92,13 +112,86 @@ impl ConsensusPublisher {
 
     /// Adds the given subscriber to the set of active subscribers
     fn add_active_subscriber(&self, peer_network_id: PeerNetworkId) {
+        self.commit_decision_subscribers
+            .write()
+            .remove(&peer_network_id);
         self.active_subscribers.write().insert(peer_network_id);
     }
 
+    /// Adds the given subscriber to the set of commit decision subscribers, and sends
+    /// it an epoch change proof from the given epoch to the latest epoch (if it's behind).
+    fn add_commit_decision_subscriber(&self, peer_network_id: PeerNetworkId, epoch: u64) {
+        self.active_subscribers.write().remove(&peer_network_id);
+
+        // Identify the latest epoch known to storage
+        let latest_epoch = match self.db_reader.get_latest_ledger_info() {
+            Ok(ledger_info) => ledger_info.ledger_info().next_block_epoch(),
+            Err(error) => {
+                warn!(LogSchema::new(LogEntry::ConsensusPublisher)
+                    .event(LogEvent::Subscription)
+                    .message(&format!(
+                        "Failed to get the latest ledger info for the new subscriber! Error: {:?}",
+                        error
+                    )));
+                epoch
+            },
+        };
+
+        // Catch the subscriber up to the latest epoch
+        let epoch = self.send_epoch_change_proof(&peer_network_id, epoch, latest_epoch);
+        self.commit_decision_subscribers
+            .write()
+            .insert(peer_network_id, epoch);
+    }
+
+    /// Sends an epoch change proof from the start epoch to the end epoch (exclusive) to the
+    /// given subscriber (if the start epoch is behind), and returns the epoch the subscriber
+    /// will have verified once it processes the proof.
+    fn send_epoch_change_proof(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> u64 {
+        // If the subscriber is not behind, there's nothing to send
+        if start_epoch >= end_epoch {
+            return start_epoch;
+        }
+
+        // Fetch the epoch change proof from storage
+        let epoch_change_proof = match self
+            .db_reader
+            .get_epoch_ending_ledger_infos(start_epoch, end_epoch)
+        {
+            Ok(epoch_change_proof) => epoch_change_proof,
+            Err(error) => {
+                warn!(LogSchema::new(LogEntry::ConsensusPublisher)
+                    .event(LogEvent::SendDirectSendMessage)
+                    .message(&format!(
+                        "Failed to get epoch change proof ({} to {}) for peer {:?}! Error: {:?}",
+                        start_epoch, end_epoch, peer_network_id, error
+                    )));
+                return start_epoch;
+            },
+        };
+
+        // Send the proof to the subscriber (the proof may be truncated by storage)
+        let verified_epoch = start_epoch + epoch_change_proof.ledger_info_with_sigs.len() as u64;
+        self.send_message_to_peer(
+            peer_network_id,
+            ConsensusObserverMessage::new_epoch_change_proof_message(epoch_change_proof),
+        );
+        verified_epoch
+    }
+
     /// Garbage collect inactive subscriptions by removing peers that are no longer connected
     fn garbage_collect_subscriptions(&self) {
-        // Get the set of active subscribers
-        let active_subscribers = self.get_active_subscribers();
+        // Get the set of active and commit decision subscribers
+        let all_subscribers: HashSet<PeerNetworkId> = self
+            .get_active_subscribers()
+            .union(&self.get_commit_decision_subscribers())
+            .cloned()
+            .collect();
 
         // Get the connected peers and metadata
         let peers_and_metadata = self.consensus_observer_client.get_peers_and_metadata();
@@ -120,7 +213,7 @@ impl ConsensusPublisher {
         // Identify the active subscribers that are no longer connected
         let connected_peers: HashSet<PeerNetworkId> =
             connected_peers_and_metadata.keys().cloned().collect();
-        let disconnected_subscribers: HashSet<PeerNetworkId> = active_subscribers
+        let disconnected_subscribers: HashSet<PeerNetworkId> = all_subscribers
             .difference(&connected_peers)
             .cloned()
             .collect();
@@ -136,20 +229,28 @@ impl ConsensusPublisher {
                 )));
         }
 
-        // Update the number of active subscribers for each network
+        // Update the number of active and commit decision subscribers for each network
         let active_subscribers = self.get_active_subscribers();
+        let commit_decision_subscribers = self.get_commit_decision_subscribers();
         for network_id in peers_and_metadata.get_registered_networks() {
-            // Calculate the number of active subscribers for the network
-            let num_active_subscribers = active_subscribers
-                .iter()
-                .filter(|peer_network_id| peer_network_id.network_id() == network_id)
-                .count() as i64;
+            // Calculate the number of subscribers for the network
+            let count_for_network = |subscribers: &HashSet<PeerNetworkId>| {
+                subscribers
+                    .iter()
+                    .filter(|peer_network_id| peer_network_id.network_id() == network_id)
+                    .count() as i64
+            };
 
-            // Update the active subscriber metric
+            // Update the subscriber metrics
             metrics::set_gauge(
                 &metrics::PUBLISHER_NUM_ACTIVE_SUBSCRIBERS,
                 &network_id,
-                num_active_subscribers,
+                count_for_network(&active_subscribers),
+            );
+            metrics::set_gauge(
+                &metrics::PUBLISHER_NUM_COMMIT_DECISION_SUBSCRIBERS,
+                &network_id,
+                count_for_network(&commit_decision_subscribers),
             );
         }
     }
@@ -159,9 +260,21 @@ impl ConsensusPublisher {
         self.active_subscribers.read().clone()
     }
 
-    /// Removes the given subscriber from the set of active subscribers
+    /// Returns a clone of the current commit decision subscribers
+    pub fn get_commit_decision_subscribers(&self) -> HashSet<PeerNetworkId> {
+        self.commit_decision_subscribers
+            .read()
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    /// Removes the given subscriber from the sets of active and commit decision subscribers
     fn remove_active_subscriber(&self, peer_network_id: &PeerNetworkId) {
         self.active_subscribers.write().remove(peer_network_id);
+        self.commit_decision_subscribers
+            .write()
+            .remove(peer_network_id);
     }
 
     /// Processes a network message received by the consensus publisher
@@ -204,33 +317,100 @@ impl ConsensusPublisher {
                 // Send a simple unsubscription ACK
                 response_sender.send(ConsensusObserverResponse::UnsubscribeAck);
             },
+            ConsensusObserverRequest::SubscribeCommitDecisions { epoch } => {
+                // Add the peer to the set of commit decision subscribers
+                self.add_commit_decision_subscriber(peer_network_id, epoch);
+                info!(LogSchema::new(LogEntry::ConsensusPublisher)
+                    .event(LogEvent::Subscription)
+                    .message(&format!(
+                        "New peer subscribed to commit decisions from epoch {}! Peer: {:?}",
+                        epoch, peer_network_id
+                    )));
+
+                // Send a simple subscription ACK
+                response_sender.send(ConsensusObserverResponse::SubscribeAck);
+            },
         }
     }
 
-    /// Publishes a direct send message to all active subscribers. Note: this method
-    /// is non-blocking (to avoid blocking callers during publishing, e.g., consensus).
+    /// Publishes a direct send message to all active subscribers (and commit decisions
+    /// to the commit decision subscribers). Note: this method is non-blocking (to avoid
+    /// blocking callers during publishing, e.g., consensus).
     pub fn publish_message(&self, message: ConsensusObserverDirectSend) {
-        // Get the active subscribers
-        let active_subscribers = self.get_active_subscribers();
-
         // Send the message to all active subscribers
-        for peer_network_id in &active_subscribers {
-            // Send the message to the outbound receiver for publishing
-            let mut outbound_message_sender = self.outbound_message_sender.clone();
-            if let Err(error) =
-                outbound_message_sender.try_send((*peer_network_id, message.clone()))
-            {
-                // The message send failed
-                warn!(LogSchema::new(LogEntry::ConsensusPublisher)
-                        .event(LogEvent::SendDirectSendMessage)
-                        .message(&format!(
-                            "Failed to send outbound message to the receiver for peer {:?}! Error: {:?}",
-                            peer_network_id, error
-                    )));
+        for peer_network_id in &self.get_active_subscribers() {
+            self.send_message_to_peer(peer_network_id, message.clone());
+        }
+
+        // Send commit decisions to the commit decision subscribers
+        if let ConsensusObserverDirectSend::CommitDecision(commit_decision) = &message {
+            self.publish_commit_decision(commit_decision.commit_proof().ledger_info(), &message);
+        }
+    }
+
+    /// Publishes the commit decision to all commit decision subscribers. Subscribers
+    /// that are behind the epoch of the decision (e.g., because they missed the decision
+    /// that ended their epoch) are first sent an epoch change proof to close the gap.
+    fn publish_commit_decision(
+        &self,
+        commit_ledger_info: &LedgerInfo,
+        message: &ConsensusObserverDirectSend,
+    ) {
+        // Take a snapshot of the subscribers (the lock must not be held
+        // while fetching epoch change proofs from storage).
+        let commit_epoch = commit_ledger_info.epoch();
+        let subscriber_epochs: Vec<(PeerNetworkId, u64)> = self
+            .commit_decision_subscribers
+            .read()
+            .iter()
+            .map(|(peer_network_id, verified_epoch)| (*peer_network_id, *verified_epoch))
+            .collect();
+
+        // Send the commit decision to each subscriber
+        let mut updated_epochs = vec![];
+        for (peer_network_id, verified_epoch) in subscriber_epochs {
+            // Close the epoch gap (if any)
+            let mut updated_epoch =
+                self.send_epoch_change_proof(&peer_network_id, verified_epoch, commit_epoch);
+
+            // Send the commit decision (and note the epoch change, if the decision ends the epoch)
+            self.send_message_to_peer(&peer_network_id, message.clone());
+            if commit_ledger_info.ends_epoch() {
+                updated_epoch = updated_epoch.max(commit_epoch + 1);
+            }
+            updated_epochs.push((peer_network_id, verified_epoch, updated_epoch));
+        }
+
+        // Update the verified epochs of the subscribers. Subscribers that were removed
+        // or re-subscribed in the meantime are skipped (their state is more recent).
+        let mut commit_decision_subscribers = self.commit_decision_subscribers.write();
+        for (peer_network_id, verified_epoch, updated_epoch) in updated_epochs {
+            if let Some(subscriber_epoch) = commit_decision_subscribers.get_mut(&peer_network_id) {
+                if *subscriber_epoch == verified_epoch {
+                    *subscriber_epoch = updated_epoch;
+                }
             }
         }
     }
 
+    /// Sends the message to the outbound receiver for publishing to the given peer
+    fn send_message_to_peer(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        message: ConsensusObserverDirectSend,
+    ) {
+        let mut outbound_message_sender = self.outbound_message_sender.clone();
+        if let Err(error) = outbound_message_sender.try_send((*peer_network_id, message)) {
+            // The message send failed
+            warn!(LogSchema::new(LogEntry::ConsensusPublisher)
+                .event(LogEvent::SendDirectSendMessage)
+                .message(&format!(
+                    "Failed to send outbound message to the receiver for peer {:?}! Error: {:?}",
+                    peer_network_id, error
+                )));
+        }
+    }
+
     /// Starts the consensus publisher
     pub async fn start(
         self,
@@ -361,16 +541,34 @@ mod test {
         application::{metadata::ConnectionState, storage::PeersAndMetadata},
         transport::ConnectionMetadata,
     };
+    use lumio_storage_interface::Result;
     use lumio_types::{
         aggregate_signature::AggregateSignature,
         block_info::BlockInfo,
+        epoch_change::EpochChangeProof,
+        epoch_state::EpochState,
         ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
         PeerId,
     };
     use futures::FutureExt;
     use maplit::hashmap;
+    use mockall::{mock, predicate::eq};
     use tokio_stream::StreamExt;
 
+    // This is a simple mock of the DbReader (it generates a MockDatabaseReader)
+    mock! {
+        pub DatabaseReader {}
+        impl DbReader for DatabaseReader {
+            fn get_latest_ledger_info(&self) -> Result<LedgerInfoWithSignatures>;
+
+            fn get_epoch_ending_ledger_infos(
+                &self,
+                start_epoch: u64,
+                end_epoch: u64,
+            ) -> Result<EpochChangeProof>;
+        }
+    }
+
     #[test]
     pub fn test_garbage_collect_subscriptions() {
         // Create a network client
@@ -384,6 +582,7 @@ mod test {
         let (consensus_publisher, _) = ConsensusPublisher::new(
             ConsensusObserverConfig::default(),
             consensus_observer_client,
+            Arc::new(MockDatabaseReader::new()),
         );
 
         // Add a peer to the peers and metadata
@@ -455,6 +654,7 @@ mod test {
         let (consensus_publisher, _) = ConsensusPublisher::new(
             ConsensusObserverConfig::default(),
             consensus_observer_client,
+            Arc::new(MockDatabaseReader::new()),
         );
 
         // Subscribe a new peer to consensus updates and verify the subscription
@@ -509,6 +709,7 @@ mod test {
         let (consensus_publisher, mut outbound_message_receiver) = ConsensusPublisher::new(
             ConsensusObserverConfig::default(),
             consensus_observer_client,
+            Arc::new(MockDatabaseReader::new()),
         );
 
         // Subscribe a new peer to consensus updates
@@ -598,6 +799,211 @@ mod test {
         assert!(outbound_message_receiver.next().now_or_never().is_none());
     }
 
+    #[tokio::test]
+    async fn test_publish_commit_decisions() {
+        // Create a network client
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let network_client =
+            NetworkClient::new(vec![], vec![], hashmap![], peers_and_metadata.clone());
+        let consensus_observer_client = Arc::new(ConsensusObserverClient::new(network_client));
+
+        // Create a consensus publisher (with storage at epoch 0)
+        let mut db_reader = MockDatabaseReader::new();
+        db_reader
+            .expect_get_latest_ledger_info()
+            .returning(|| Ok(create_ledger_info(0, false)));
+        let (consensus_publisher, mut outbound_message_receiver) = ConsensusPublisher::new(
+            ConsensusObserverConfig::default(),
+            consensus_observer_client,
+            Arc::new(db_reader),
+        );
+
+        // Subscribe a peer to commit decisions only and verify the subscription
+        let peer_network_id_1 = PeerNetworkId::new(network_id, PeerId::random());
+        process_commit_decision_subscription_for_peer(&consensus_publisher, &peer_network_id_1);
+        verify_active_subscribers(&consensus_publisher, 0, vec![], vec![&peer_network_id_1]);
+        assert!(consensus_publisher
+            .get_commit_decision_subscribers()
+            .contains(&peer_network_id_1));
+
+        // Publish a block payload and verify that it was not sent to the peer
+        let block_payload_message = ConsensusObserverMessage::new_block_payload_message(
+            BlockInfo::empty(),
+            BlockTransactionPayload::empty(),
+        );
+        consensus_publisher.publish_message(block_payload_message);
+        assert!(outbound_message_receiver.next().now_or_never().is_none());
+
+        // Publish a commit decision and verify that it was sent to the peer
+        let commit_decision_message =
+            ConsensusObserverMessage::new_commit_decision_message(LedgerInfoWithSignatures::new(
+                LedgerInfo::new(BlockInfo::empty(), HashValue::zero()),
+                AggregateSignature::empty(),
+            ));
+        consensus_publisher.publish_message(commit_decision_message.clone());
+        let (peer_network_id, message) = outbound_message_receiver.next().await.unwrap();
+        assert_eq!(peer_network_id, peer_network_id_1);
+        assert_eq!(message, commit_decision_message);
+
+        // Subscribe the peer to all consensus updates and verify the subscription is replaced
+        process_subscription_for_peer(&consensus_publisher, &peer_network_id_1);
+        verify_active_subscribers(&consensus_publisher, 1, vec![&peer_network_id_1], vec![]);
+        assert!(consensus_publisher
+            .get_commit_decision_subscribers()
+            .is_empty());
+
+        // Subscribe the peer to commit decisions again, and then unsubscribe it
+        process_commit_decision_subscription_for_peer(&consensus_publisher, &peer_network_id_1);
+        process_unsubscription_for_peer(&consensus_publisher, &peer_network_id_1);
+        verify_active_subscribers(&consensus_publisher, 0, vec![], vec![&peer_network_id_1]);
+        assert!(consensus_publisher
+            .get_commit_decision_subscribers()
+            .is_empty());
+
+        // Publish another commit decision and verify that no messages were sent
+        consensus_publisher.publish_message(commit_decision_message);
+        assert!(outbound_message_receiver.next().now_or_never().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_publish_epoch_change_proofs() {
+        // Create a network client
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let network_client =
+            NetworkClient::new(vec![], vec![], hashmap![], peers_and_metadata.clone());
+        let consensus_observer_client = Arc::new(ConsensusObserverClient::new(network_client));
+
+        // Create a database reader with storage at epoch 3, that
+        // expects epoch change proofs from epoch 1 and from epoch 3.
+        let mut db_reader = MockDatabaseReader::new();
+        db_reader
+            .expect_get_latest_ledger_info()
+            .returning(|| Ok(create_ledger_info(3, false)));
+        db_reader
+            .expect_get_epoch_ending_ledger_infos()
+            .with(eq(1), eq(3))
+            .times(1)
+            .returning(|_, _| Ok(create_epoch_change_proof(1, 3)));
+        db_reader
+            .expect_get_epoch_ending_ledger_infos()
+            .with(eq(3), eq(5))
+            .times(1)
+            .returning(|_, _| Ok(create_epoch_change_proof(3, 5)));
+
+        // Create a consensus publisher
+        let (consensus_publisher, mut outbound_message_receiver) = ConsensusPublisher::new(
+            ConsensusObserverConfig::default(),
+            consensus_observer_client,
+            Arc::new(db_reader),
+        );
+
+        // Subscribe a peer to commit decisions from epoch 1, and verify
+        // that it receives the epoch change proof to the latest epoch.
+        let peer_network_id_1 = PeerNetworkId::new(network_id, PeerId::random());
+        process_commit_decision_subscription_for_peer_at_epoch(
+            &consensus_publisher,
+            &peer_network_id_1,
+            1,
+        );
+        let (peer_network_id, message) = outbound_message_receiver.next().await.unwrap();
+        assert_eq!(peer_network_id, peer_network_id_1);
+        assert_eq!(
+            message,
+            ConsensusObserverMessage::new_epoch_change_proof_message(create_epoch_change_proof(
+                1, 3
+            ))
+        );
+
+        // Publish a commit decision for epoch 3, and verify only the decision is sent
+        let commit_decision_message =
+            ConsensusObserverMessage::new_commit_decision_message(create_ledger_info(3, false));
+        consensus_publisher.publish_message(commit_decision_message.clone());
+        let (_, message) = outbound_message_receiver.next().await.unwrap();
+        assert_eq!(message, commit_decision_message);
+        assert!(outbound_message_receiver.next().now_or_never().is_none());
+
+        // Publish a commit decision for epoch 5 (the decisions ending epochs 3 and 4 were
+        // missed), and verify the epoch change proof is sent before the decision.
+        let commit_decision_message =
+            ConsensusObserverMessage::new_commit_decision_message(create_ledger_info(5, true));
+        consensus_publisher.publish_message(commit_decision_message.clone());
+        let (_, message) = outbound_message_receiver.next().await.unwrap();
+        assert_eq!(
+            message,
+            ConsensusObserverMessage::new_epoch_change_proof_message(create_epoch_change_proof(
+                3, 5
+            ))
+        );
+        let (_, message) = outbound_message_receiver.next().await.unwrap();
+        assert_eq!(message, commit_decision_message);
+
+        // Publish a commit decision for epoch 6 (the previous decision ended epoch 5),
+        // and verify that no epoch change proof is required.
+        let commit_decision_message =
+            ConsensusObserverMessage::new_commit_decision_message(create_ledger_info(6, false));
+        consensus_publisher.publish_message(commit_decision_message.clone());
+        let (_, message) = outbound_message_receiver.next().await.unwrap();
+        assert_eq!(message, commit_decision_message);
+        assert!(outbound_message_receiver.next().now_or_never().is_none());
+    }
+
+    /// Creates an epoch change proof with the ledger infos ending the given epochs
+    fn create_epoch_change_proof(start_epoch: u64, end_epoch: u64) -> EpochChangeProof {
+        let ledger_infos = (start_epoch..end_epoch)
+            .map(|epoch| create_ledger_info(epoch, true))
+            .collect();
+        EpochChangeProof::new(ledger_infos, false)
+    }
+
+    /// Creates a ledger info for the given epoch (that optionally ends the epoch)
+    fn create_ledger_info(epoch: u64, ends_epoch: bool) -> LedgerInfoWithSignatures {
+        let next_epoch_state = ends_epoch.then(EpochState::empty);
+        let block_info = BlockInfo::new(
+            epoch,
+            0,
+            HashValue::zero(),
+            HashValue::zero(),
+            epoch,
+            0,
+            next_epoch_state,
+        );
+        LedgerInfoWithSignatures::new(
+            LedgerInfo::new(block_info, HashValue::zero()),
+            AggregateSignature::empty(),
+        )
+    }
+
+    /// Processes a commit decision subscription request (from epoch 0) for the given peer
+    fn process_commit_decision_subscription_for_peer(
+        consensus_publisher: &ConsensusPublisher,
+        peer_network_id: &PeerNetworkId,
+    ) {
+        process_commit_decision_subscription_for_peer_at_epoch(
+            consensus_publisher,
+            peer_network_id,
+            0,
+        );
+    }
+
+    /// Processes a commit decision subscription request from the given epoch for the given peer
+    fn process_commit_decision_subscription_for_peer_at_epoch(
+        consensus_publisher: &ConsensusPublisher,
+        peer_network_id: &PeerNetworkId,
+        epoch: u64,
+    ) {
+        // Create the subscribe message
+        let network_message = ConsensusPublisherNetworkMessage::new(
+            *peer_network_id,
+            ConsensusObserverRequest::SubscribeCommitDecisions { epoch },
+            ResponseSender::new_for_test(),
+        );
+
+        // Process the subscription request
+        consensus_publisher.process_network_message(network_message);
+    }
+
     /// Processes a subscription request for the given peer
     fn process_subscription_for_peer(
         consensus_publisher: &ConsensusPublisher,
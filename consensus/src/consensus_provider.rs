@@ -19,6 +19,8 @@ use crate::{
     pipeline::execution_client::{DummyExecutionClient, ExecutionProxyClient, TExecutionClient},
     quorum_store::quorum_store_db::QuorumStoreDB,
     rand::rand_gen::storage::db::RandDb,
+    record_replay,
+    round_timeline::RoundTimelines,
     state_computer::ExecutionProxy,
     txn_notifier::MempoolNotifier,
     util::time_service::ClockTimeService,
@@ -53,8 +55,18 @@ pub fn start_consensus(
     reconfig_events: ReconfigNotificationListener<DbBackedOnChainConfig>,
     vtxn_pool: VTxnPoolState,
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
-) -> (Runtime, Arc<StorageWriteProxy>, Arc<QuorumStoreDB>) {
+) -> (
+    Runtime,
+    Arc<StorageWriteProxy>,
+    Arc<QuorumStoreDB>,
+    RoundTimelines,
+) {
     let runtime = lumio_runtimes::spawn_named_runtime("consensus".into(), None);
+    if let Some(dir) = &node_config.consensus.trace_recording_dir {
+        if let Err(e) = record_replay::enable_recording(dir) {
+            warn!(error = ?e, "Failed to enable consensus trace recording.");
+        }
+    }
     let storage = Arc::new(StorageWriteProxy::new(node_config, lumio_db.reader.clone()));
     let quorum_store_db = Arc::new(QuorumStoreDB::new(node_config.storage.dir()));
 
@@ -113,6 +125,7 @@ pub fn start_consensus(
         rand_storage,
         consensus_publisher,
     );
+    let round_timelines = epoch_mgr.round_timelines();
 
     let (network_task, network_receiver) = NetworkTask::new(network_service_events, self_receiver);
 
@@ -120,7 +133,7 @@ pub fn start_consensus(
     runtime.spawn(epoch_mgr.start(timeout_receiver, network_receiver));
 
     debug!("Consensus started.");
-    (runtime, storage, quorum_store_db)
+    (runtime, storage, quorum_store_db, round_timelines)
 }
 
 /// A helper function to start the consensus observer
@@ -13,15 +13,17 @@ use lumio_crypto::HashValue;
 use lumio_logger::prelude::*;
 use lumio_schemadb::{batch::SchemaBatch, schema::Schema, Options, DB, DEFAULT_COLUMN_FAMILY_NAME};
 use lumio_storage_interface::LumioDbError;
+use lumio_types::equivocation::EquivocationEvidence;
 pub use schema::{
     block::BlockSchema,
     dag::{CertifiedNodeSchema, DagVoteSchema, NodeSchema},
+    equivocation_evidence::EquivocationEvidenceSchema,
     quorum_certificate::QCSchema,
 };
 use schema::{
     single_entry::{SingleEntryKey, SingleEntrySchema},
-    BLOCK_CF_NAME, CERTIFIED_NODE_CF_NAME, DAG_VOTE_CF_NAME, NODE_CF_NAME, QC_CF_NAME,
-    SINGLE_ENTRY_CF_NAME,
+    BLOCK_CF_NAME, CERTIFIED_NODE_CF_NAME, DAG_VOTE_CF_NAME, EQUIVOCATION_EVIDENCE_CF_NAME,
+    NODE_CF_NAME, QC_CF_NAME, SINGLE_ENTRY_CF_NAME,
 };
 use std::{iter::Iterator, path::Path, time::Instant};
 
@@ -59,6 +61,7 @@ impl ConsensusDB {
             CERTIFIED_NODE_CF_NAME,
             DAG_VOTE_CF_NAME,
             "ordered_anchor_id", // deprecated CF
+            EQUIVOCATION_EVIDENCE_CF_NAME,
         ];
 
         let path = db_root_path.as_ref().join(CONSENSUS_DB_NAME);
@@ -152,6 +155,27 @@ impl ConsensusDB {
         self.commit(batch)
     }
 
+    /// Persists `evidence`, replacing any earlier evidence against the same author in the
+    /// same round.
+    pub fn save_equivocation_evidence(
+        &self,
+        evidence: &EquivocationEvidence,
+    ) -> Result<(), DbError> {
+        self.put::<EquivocationEvidenceSchema>(
+            &(evidence.epoch, evidence.round, evidence.author),
+            evidence,
+        )
+    }
+
+    /// Returns all persisted equivocation evidence, ordered by epoch and round.
+    pub fn get_equivocation_evidence(&self) -> Result<Vec<EquivocationEvidence>, DbError> {
+        Ok(self
+            .get_all::<EquivocationEvidenceSchema>()?
+            .into_iter()
+            .map(|(_, evidence)| evidence)
+            .collect())
+    }
+
     /// Write the whole schema batch including all data necessary to mutate the ledger
     /// state of some transaction by leveraging rocksdb atomicity support.
     fn commit(&self, batch: SchemaBatch) -> Result<(), DbError> {
@@ -0,0 +1,63 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for equivocation evidence.
+//!
+//! Serialized evidence identified by the epoch and round it is for, and the equivocating author.
+//! ```text
+//! |<-----------key----------->|<-------value-------->|
+//! | epoch | round |  author   | EquivocationEvidence |
+//! ```
+
+use crate::{consensusdb::schema::ensure_slice_len_eq, define_schema};
+use anyhow::Result;
+use byteorder::{BigEndian, ReadBytesExt};
+use lumio_consensus_types::common::{Author, Round};
+use lumio_schemadb::{
+    schema::{KeyCodec, ValueCodec},
+    ColumnFamilyName,
+};
+use lumio_types::equivocation::EquivocationEvidence;
+use std::mem::size_of;
+
+pub const EQUIVOCATION_EVIDENCE_CF_NAME: ColumnFamilyName = "equivocation_evidence";
+
+define_schema!(
+    EquivocationEvidenceSchema,
+    (u64, Round, Author),
+    EquivocationEvidence,
+    EQUIVOCATION_EVIDENCE_CF_NAME
+);
+
+impl KeyCodec<EquivocationEvidenceSchema> for (u64, Round, Author) {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (epoch, round, author) = self;
+        // Big endian, so that evidence is iterated in (epoch, round) order.
+        let mut encoded = epoch.to_be_bytes().to_vec();
+        encoded.extend_from_slice(&round.to_be_bytes());
+        encoded.extend_from_slice(author.as_ref());
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 2 * size_of::<u64>() + Author::LENGTH)?;
+        let mut reader = data;
+        let epoch = reader.read_u64::<BigEndian>()?;
+        let round = reader.read_u64::<BigEndian>()?;
+        let author = Author::try_from(reader)?;
+        Ok((epoch, round, author))
+    }
+}
+
+impl ValueCodec<EquivocationEvidenceSchema> for EquivocationEvidence {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
+
+#[cfg(test)]
+mod test;
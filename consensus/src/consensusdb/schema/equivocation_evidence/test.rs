@@ -0,0 +1,32 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use lumio_crypto::{bls12381::Signature, HashValue};
+use lumio_schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+use lumio_types::{
+    block_info::BlockInfo,
+    equivocation::{EquivocationProof, SignedVote},
+    ledger_info::LedgerInfo,
+};
+
+#[test]
+fn test_encode_decode() {
+    let vote = |id| SignedVote {
+        ledger_info: LedgerInfo::new(BlockInfo::empty(), HashValue::from_u64(id)),
+        vote_data: vec![],
+        signature: Signature::dummy_signature(),
+    };
+    let evidence = EquivocationEvidence {
+        author: Author::random(),
+        epoch: 2,
+        round: 7,
+        proof: EquivocationProof::Vote(vote(1), vote(2)),
+    };
+    assert_encode_decode::<EquivocationEvidenceSchema>(
+        &(evidence.epoch, evidence.round, evidence.author),
+        &evidence,
+    );
+}
+
+test_no_panic_decoding!(EquivocationEvidenceSchema);
@@ -150,6 +150,16 @@ pub static PROPOSAL_VOTE_ADDED: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of the equivocations for which this node collected evidence, by kind
+pub static EQUIVOCATION_EVIDENCE_COLLECTED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "lumio_consensus_equivocation_evidence_collected",
+        "Count of the equivocations for which evidence was collected, by kind",
+        &["kind"]
+    )
+    .unwrap()
+});
+
 pub static QC_AGGREGATED_FROM_VOTES: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
         "lumio_consensus_qc_aggregated_from_votes",
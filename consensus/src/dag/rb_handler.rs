@@ -122,7 +122,13 @@ impl NodeBroadcastHandler {
         for vtxn in node.validator_txns() {
             let vtxn_type_name = vtxn.type_name();
             ensure!(
-                is_vtxn_expected(&self.randomness_config, &self.jwk_consensus_config, vtxn),
+                // DAG does not report equivocations, so it never expects the evidence either.
+                is_vtxn_expected(
+                    &self.randomness_config,
+                    &self.jwk_consensus_config,
+                    false,
+                    vtxn
+                ),
                 "unexpected validator transaction: {:?}",
                 vtxn_type_name
             );
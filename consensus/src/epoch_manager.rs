@@ -14,10 +14,7 @@ use crate::{
     error::{error_kind, DbError},
     liveness::{
         cached_proposer_election::CachedProposerElection,
-        leader_reputation::{
-            extract_epoch_to_proposers, LumioDBBackend, LeaderReputation,
-            ProposerAndVoterHeuristic, ReputationHeuristic,
-        },
+        leader_reputation::{create_leader_reputation, extract_epoch_proposers},
         proposal_generator::{
             ChainHealthBackoffConfig, PipelineBackpressureConfig, ProposalGenerator,
         },
@@ -51,8 +48,10 @@ use crate::{
         storage::interface::RandStorage,
         types::{AugmentedData, RandConfig},
     },
+    record_replay::{self, EpochStart, TraceEvent},
     recovery_manager::RecoveryManager,
     round_manager::{RoundManager, UnverifiedEvent, VerifiedEvent},
+    round_timeline::{record_round_event, RoundTimelineRecorder, RoundTimelines},
     util::time_service::TimeService,
 };
 use anyhow::{anyhow, bail, ensure, Context};
@@ -67,6 +66,7 @@ use lumio_consensus_types::{
     common::{Author, Round},
     epoch_retrieval::EpochRetrievalRequest,
     proof_of_store::ProofCache,
+    round_timeline::RoundEvent,
     utils::PayloadTxnsSize,
 };
 use lumio_crypto::bls12381::PrivateKey;
@@ -89,10 +89,9 @@ use lumio_types::{
     epoch_state::EpochState,
     jwks::SupportedOIDCProviders,
     on_chain_config::{
-        Features, LeaderReputationType, OnChainConfigPayload, OnChainConfigProvider,
-        OnChainConsensusConfig, OnChainExecutionConfig, OnChainJWKConsensusConfig,
-        OnChainRandomnessConfig, ProposerElectionType, RandomnessConfigMoveStruct,
-        RandomnessConfigSeqNum, ValidatorSet,
+        Features, OnChainConfigPayload, OnChainConfigProvider, OnChainConsensusConfig,
+        OnChainExecutionConfig, OnChainJWKConsensusConfig, OnChainRandomnessConfig,
+        ProposerElectionType, RandomnessConfigMoveStruct, RandomnessConfigSeqNum, ValidatorSet,
     },
     randomness::{RandKeys, WvufPP, WVUF},
     validator_signer::ValidatorSigner,
@@ -104,12 +103,10 @@ use futures::{
     channel::{mpsc, mpsc::Sender, oneshot},
     SinkExt, StreamExt,
 };
-use itertools::Itertools;
 use mini_moka::sync::Cache;
 use rand::{prelude::StdRng, thread_rng, SeedableRng};
 use std::{
     cmp::Ordering,
-    collections::HashMap,
     hash::Hash,
     mem::{discriminant, Discriminant},
     sync::Arc,
@@ -119,9 +116,6 @@ use std::{
 /// Range of rounds (window) that we might be calling proposer election
 /// functions with at any given time, in addition to the proposer history length.
 const PROPOSER_ELECTION_CACHING_WINDOW_ADDITION: usize = 3;
-/// Number of rounds we expect storage to be behind the proposer round,
-/// used for fetching data from DB.
-const PROPOSER_ROUND_BEHIND_STORAGE_BUFFER: usize = 30;
 
 #[allow(clippy::large_enum_variant)]
 pub enum LivenessStorageData {
@@ -176,6 +170,9 @@ pub struct EpochManager<P: OnChainConfigProvider> {
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
     pending_blocks: Arc<Mutex<PendingBlocks>>,
     key_storage: PersistentSafetyStorage,
+    // The round timelines of the recent epochs, and the recorder of the current one
+    round_timelines: RoundTimelines,
+    round_timeline: Option<Arc<RoundTimelineRecorder>>,
 
     consensus_txn_filter_config: BlockTransactionFilterConfig,
     quorum_store_txn_filter_config: BatchTransactionFilterConfig,
@@ -252,11 +249,18 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             consensus_publisher,
             pending_blocks: Arc::new(Mutex::new(PendingBlocks::new())),
             key_storage,
+            round_timelines: RoundTimelines::default(),
+            round_timeline: None,
             consensus_txn_filter_config,
             quorum_store_txn_filter_config,
         }
     }
 
+    /// Returns the round timelines recorded by this node (e.g., for the admin service)
+    pub fn round_timelines(&self) -> RoundTimelines {
+        self.round_timelines.clone()
+    }
+
     fn epoch_state(&self) -> &EpochState {
         self.epoch_state
             .as_ref()
@@ -300,87 +304,14 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                 Arc::new(RotatingProposer::new(vec![proposer], *contiguous_rounds))
             },
             ProposerElectionType::LeaderReputation(leader_reputation_type) => {
-                let (
-                    heuristic,
-                    window_size,
-                    weight_by_voting_power,
-                    use_history_from_previous_epoch_max_count,
-                ) = match &leader_reputation_type {
-                    LeaderReputationType::ProposerAndVoter(proposer_and_voter_config)
-                    | LeaderReputationType::ProposerAndVoterV2(proposer_and_voter_config) => {
-                        let proposer_window_size = proposers.len()
-                            * proposer_and_voter_config.proposer_window_num_validators_multiplier;
-                        let voter_window_size = proposers.len()
-                            * proposer_and_voter_config.voter_window_num_validators_multiplier;
-                        let heuristic: Box<dyn ReputationHeuristic> =
-                            Box::new(ProposerAndVoterHeuristic::new(
-                                self.author,
-                                proposer_and_voter_config.active_weight,
-                                proposer_and_voter_config.inactive_weight,
-                                proposer_and_voter_config.failed_weight,
-                                proposer_and_voter_config.failure_threshold_percent,
-                                voter_window_size,
-                                proposer_window_size,
-                                leader_reputation_type.use_reputation_window_from_stale_end(),
-                            ));
-                        (
-                            heuristic,
-                            std::cmp::max(proposer_window_size, voter_window_size),
-                            proposer_and_voter_config.weight_by_voting_power,
-                            proposer_and_voter_config.use_history_from_previous_epoch_max_count,
-                        )
-                    },
-                };
-
-                let seek_len = onchain_config.leader_reputation_exclude_round() as usize
-                    + onchain_config.max_failed_authors_to_store()
-                    + PROPOSER_ROUND_BEHIND_STORAGE_BUFFER;
-
-                let backend = Arc::new(LumioDBBackend::new(
-                    window_size,
-                    seek_len,
-                    self.storage.lumio_db(),
-                ));
-                let voting_powers: Vec<_> = if weight_by_voting_power {
-                    proposers
-                        .iter()
-                        .map(|p| {
-                            epoch_state
-                                .verifier
-                                .get_voting_power(p)
-                                .expect("INVARIANT VIOLATION: proposer not in verifier set")
-                        })
-                        .collect()
-                } else {
-                    vec![1; proposers.len()]
-                };
-
-                let epoch_to_proposers = self.extract_epoch_proposers(
+                let proposer_election = Box::new(create_leader_reputation(
+                    self.author,
                     epoch_state,
-                    use_history_from_previous_epoch_max_count,
-                    proposers,
-                    (window_size + seek_len) as u64,
-                );
-
-                info!(
-                    "Starting epoch {}: proposers across epochs for leader election: {:?}",
-                    epoch_state.epoch,
-                    epoch_to_proposers
-                        .iter()
-                        .map(|(epoch, proposers)| (epoch, proposers.len()))
-                        .sorted()
-                        .collect::<Vec<_>>()
-                );
-
-                let proposer_election = Box::new(LeaderReputation::new(
-                    epoch_state.epoch,
-                    epoch_to_proposers,
-                    voting_powers,
-                    backend,
-                    heuristic,
-                    onchain_config.leader_reputation_exclude_round(),
-                    leader_reputation_type.use_root_hash_for_seed(),
+                    onchain_config,
+                    leader_reputation_type,
+                    self.storage.lumio_db(),
                     self.config.window_for_chain_health,
+                    0,
                 ));
                 // LeaderReputation is not cheap, so we can cache the amount of rounds round_manager needs.
                 Arc::new(CachedProposerElection::new(
@@ -403,48 +334,6 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         }
     }
 
-    fn extract_epoch_proposers(
-        &self,
-        epoch_state: &EpochState,
-        use_history_from_previous_epoch_max_count: u32,
-        proposers: Vec<AccountAddress>,
-        needed_rounds: u64,
-    ) -> HashMap<u64, Vec<AccountAddress>> {
-        // Genesis is epoch=0
-        // First block (after genesis) is epoch=1, and is the only block in that epoch.
-        // It has no votes, so we skip it unless we are in epoch 1, as otherwise it will
-        // skew leader elections for exclude_round number of rounds.
-        let first_epoch_to_consider = std::cmp::max(
-            if epoch_state.epoch == 1 { 1 } else { 2 },
-            epoch_state
-                .epoch
-                .saturating_sub(use_history_from_previous_epoch_max_count as u64),
-        );
-        // If we are considering beyond the current epoch, we need to fetch validators for those epochs
-        if epoch_state.epoch > first_epoch_to_consider {
-            self.storage
-                .lumio_db()
-                .get_epoch_ending_ledger_infos(first_epoch_to_consider - 1, epoch_state.epoch)
-                .map_err(Into::into)
-                .and_then(|proof| {
-                    ensure!(
-                        proof.ledger_info_with_sigs.len() as u64
-                            == (epoch_state.epoch - (first_epoch_to_consider - 1))
-                    );
-                    extract_epoch_to_proposers(proof, epoch_state.epoch, &proposers, needed_rounds)
-                })
-                .unwrap_or_else(|err| {
-                    error!(
-                        "Couldn't create leader reputation with history across epochs, {:?}",
-                        err
-                    );
-                    HashMap::from([(epoch_state.epoch, proposers)])
-                })
-        } else {
-            HashMap::from([(epoch_state.epoch, proposers)])
-        }
-    }
-
     fn process_epoch_retrieval(
         &mut self,
         request: EpochRetrievalRequest,
@@ -800,6 +689,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         onchain_execution_config: OnChainExecutionConfig,
         onchain_randomness_config: OnChainRandomnessConfig,
         onchain_jwk_consensus_config: OnChainJWKConsensusConfig,
+        features: Features,
         network_sender: Arc<NetworkSender>,
         payload_client: Arc<dyn PayloadClient>,
         payload_manager: Arc<dyn TPayloadManager>,
@@ -814,6 +704,17 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             root_block = %recovery_data.commit_root_block(),
             "Starting new epoch",
         );
+        record_replay::record_epoch_start(|| EpochStart {
+            author: self.author,
+            epoch_state: (*epoch_state).clone(),
+            recovery_data: recovery_data.clone(),
+            onchain_consensus_config: onchain_consensus_config.clone(),
+            onchain_randomness_config: onchain_randomness_config.clone(),
+            onchain_jwk_consensus_config: onchain_jwk_consensus_config.clone(),
+            features: features.clone(),
+            local_config_json: serde_json::to_string(&self.config)
+                .expect("ConsensusConfig must serialize to JSON"),
+        });
 
         info!(epoch = epoch, "Update SafetyRules");
 
@@ -871,7 +772,12 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         let consensus_sk = consensus_key;
 
         let signer = Arc::new(ValidatorSigner::new(self.author, consensus_sk));
-        let pipeline_builder = self.execution_client.pipeline_builder(signer);
+        let round_timeline = self.round_timelines.start_epoch(epoch);
+        self.round_timeline = Some(round_timeline.clone());
+        let pipeline_builder = self
+            .execution_client
+            .pipeline_builder(signer)
+            .with_round_timeline(round_timeline);
         info!(epoch = epoch, "Create BlockStore");
         // Read the last vote, before "moving" `recovery_data`
         let last_vote = recovery_data.last_vote();
@@ -974,9 +880,11 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             self.config.clone(),
             onchain_randomness_config,
             onchain_jwk_consensus_config,
+            features.is_equivocation_evidence_enabled(),
             fast_rand_config,
             failures_tracker,
             opt_proposal_loopback_tx,
+            self.vtxn_pool.clone(),
         );
 
         round_manager.init(last_vote).await;
@@ -1174,6 +1082,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             payload.get();
         let onchain_jwk_consensus_config: anyhow::Result<OnChainJWKConsensusConfig> = payload.get();
         let dkg_state = payload.get::<DKGState>();
+        let features = payload.get::<Features>().unwrap_or_default();
 
         if let Err(error) = &onchain_consensus_config {
             warn!("Failed to read on-chain consensus config {}", error);
@@ -1296,6 +1205,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                 execution_config,
                 onchain_randomness_config,
                 jwk_consensus_config,
+                features,
                 network_sender,
                 payload_client,
                 payload_manager,
@@ -1351,6 +1261,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         execution_config: OnChainExecutionConfig,
         onchain_randomness_config: OnChainRandomnessConfig,
         jwk_consensus_config: OnChainJWKConsensusConfig,
+        features: Features,
         network_sender: NetworkSender,
         payload_client: Arc<dyn PayloadClient>,
         payload_manager: Arc<dyn TPayloadManager>,
@@ -1372,6 +1283,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                     execution_config,
                     onchain_randomness_config,
                     jwk_consensus_config,
+                    features,
                     Arc::new(network_sender),
                     payload_client,
                     payload_manager,
@@ -1445,7 +1357,8 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             .await;
 
         let onchain_dag_consensus_config = onchain_consensus_config.unwrap_dag_config_v1();
-        let epoch_to_validators = self.extract_epoch_proposers(
+        let epoch_to_validators = extract_epoch_proposers(
+            self.storage.lumio_db().as_ref(),
             &epoch_state,
             onchain_dag_consensus_config.dag_ordering_causal_history_window as u32,
             epoch_state.verifier.get_ordered_account_addresses(),
@@ -1559,6 +1472,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                 self.config.quorum_store.batch_expiry_gap_when_init_usecs;
             let payload_manager = self.payload_manager.clone();
             let pending_blocks = self.pending_blocks.clone();
+            let round_timeline = self.round_timeline.clone();
             self.bounded_executor
                 .spawn(async move {
                     match monitor!(
@@ -1582,6 +1496,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                                 verified_event,
                                 payload_manager,
                                 pending_blocks,
+                                round_timeline,
                             );
                         },
                         Err(e) => {
@@ -1712,18 +1627,29 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         event: VerifiedEvent,
         payload_manager: Arc<dyn TPayloadManager>,
         pending_blocks: Arc<Mutex<PendingBlocks>>,
+        round_timeline: Option<Arc<RoundTimelineRecorder>>,
     ) {
         if let VerifiedEvent::ProposalMsg(proposal) = &event {
             observe_block(
                 proposal.proposal().timestamp_usecs(),
                 BlockStage::EPOCH_MANAGER_VERIFIED,
             );
+            record_round_event(
+                round_timeline.as_deref(),
+                proposal.proposal().round(),
+                RoundEvent::ProposalVerified,
+            );
         }
         if let VerifiedEvent::OptProposalMsg(proposal) = &event {
             observe_block(
                 proposal.timestamp_usecs(),
                 BlockStage::EPOCH_MANAGER_VERIFIED,
             );
+            record_round_event(
+                round_timeline.as_deref(),
+                proposal.round(),
+                RoundEvent::ProposalVerified,
+            );
             observe_block(
                 proposal.timestamp_usecs(),
                 BlockStage::EPOCH_MANAGER_VERIFIED_OPT_PROPOSAL,
@@ -1868,6 +1794,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             return;
         };
 
+        record_replay::record(|| TraceEvent::LocalTimeout(round));
         let peer_id = self.author;
         let event = VerifiedEvent::LocalTimeout(round);
         if let Err(e) = sender.push((peer_id, discriminant(&event)), (peer_id, event)) {
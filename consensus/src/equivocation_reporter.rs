@@ -0,0 +1,187 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{counters, persistent_liveness_storage::PersistentLivenessStorage};
+use anyhow::Context;
+use lumio_consensus_types::{
+    block::Block, common::Round, pipelined_block::PipelinedBlock, vote::Vote,
+};
+use lumio_crypto::HashValue;
+use lumio_logger::prelude::*;
+use lumio_types::{
+    equivocation::{
+        EquivocationEvidence, EquivocationKind, EquivocationProof, SignedProposal, SignedVote,
+    },
+    validator_txn::{Topic, ValidatorTransaction},
+    validator_verifier::ValidatorVerifier,
+};
+use lumio_validator_transaction_pool::{TxnGuard, VTxnPoolState};
+use std::{collections::HashMap, sync::Arc};
+
+/// Builds evidence out of two conflicting votes of the same author for the same round.
+pub fn vote_evidence(first: &Vote, second: &Vote) -> anyhow::Result<EquivocationEvidence> {
+    let signed_vote = |vote: &Vote| -> anyhow::Result<SignedVote> {
+        Ok(SignedVote {
+            ledger_info: vote.ledger_info().clone(),
+            vote_data: bcs::to_bytes(vote.vote_data())?,
+            signature: vote.signature().clone(),
+        })
+    };
+    let proposed = first.vote_data().proposed();
+    Ok(EquivocationEvidence {
+        author: first.author(),
+        epoch: proposed.epoch(),
+        round: proposed.round(),
+        proof: EquivocationProof::Vote(signed_vote(first)?, signed_vote(second)?),
+    })
+}
+
+/// Builds evidence out of two different proposals of the same author for the same round.
+pub fn proposal_evidence(first: &Block, second: &Block) -> anyhow::Result<EquivocationEvidence> {
+    let signed_proposal = |block: &Block| -> anyhow::Result<SignedProposal> {
+        Ok(SignedProposal {
+            block_data: bcs::to_bytes(block.block_data())?,
+            signature: block
+                .signature()
+                .context("Only signed proposals can equivocate")?
+                .clone(),
+        })
+    };
+    Ok(EquivocationEvidence {
+        author: first.author().context("Only proposals have an author")?,
+        epoch: first.epoch(),
+        round: first.round(),
+        proof: EquivocationProof::Proposal(signed_proposal(first)?, signed_proposal(second)?),
+    })
+}
+
+/// Persists the equivocation evidence collected by the round manager and, if enabled, submits
+/// it on-chain through the validator transaction pool until it makes it into a committed block.
+pub struct EquivocationReporter {
+    verifier: Arc<ValidatorVerifier>,
+    storage: Arc<dyn PersistentLivenessStorage>,
+    // `None` if the evidence isn't submitted on-chain.
+    vtxn_pool: Option<VTxnPoolState>,
+    pending: HashMap<Topic, TxnGuard>,
+    // The round and the pending evidence of the proposals that include any, by block id.
+    proposals: HashMap<HashValue, (Round, Vec<Topic>)>,
+    // The round of the commit root seen by the last `on_commit` call.
+    committed_round: Round,
+}
+
+impl EquivocationReporter {
+    pub fn new(
+        verifier: Arc<ValidatorVerifier>,
+        storage: Arc<dyn PersistentLivenessStorage>,
+        vtxn_pool: VTxnPoolState,
+        submit: bool,
+    ) -> Self {
+        Self {
+            verifier,
+            storage,
+            vtxn_pool: submit.then_some(vtxn_pool),
+            pending: HashMap::new(),
+            proposals: HashMap::new(),
+            committed_round: 0,
+        }
+    }
+
+    /// Records `evidence` if it holds up. Vote signatures may not have been verified yet, so
+    /// evidence against an author can also be a forgery by whoever relayed it.
+    pub fn report(&mut self, evidence: anyhow::Result<EquivocationEvidence>) {
+        let evidence = match evidence.and_then(|evidence| {
+            evidence.verify(&self.verifier)?;
+            Ok(evidence)
+        }) {
+            Ok(evidence) => evidence,
+            Err(e) => {
+                warn!(error = ?e, "Discarding invalid equivocation evidence");
+                return;
+            },
+        };
+        let kind = match evidence.kind() {
+            EquivocationKind::Vote => "vote",
+            EquivocationKind::Proposal => "proposal",
+        };
+        counters::EQUIVOCATION_EVIDENCE_COLLECTED
+            .with_label_values(&[kind])
+            .inc();
+        info!(
+            author = evidence.author,
+            epoch = evidence.epoch,
+            round = evidence.round,
+            "Collected {} equivocation evidence",
+            kind
+        );
+        if let Err(e) = self.storage.save_equivocation_evidence(&evidence) {
+            error!(error = ?e, "Failed to persist equivocation evidence");
+        }
+
+        if let Some(vtxn_pool) = &self.vtxn_pool {
+            let topic = Self::topic(&evidence);
+            if !self.pending.contains_key(&topic) {
+                let txn = Arc::new(ValidatorTransaction::EquivocationEvidence(evidence));
+                let guard = vtxn_pool.put(topic.clone(), txn, None);
+                self.pending.insert(topic, guard);
+            }
+        }
+    }
+
+    /// Remembers the pending evidence included in `block`. The evidence keeps being submitted
+    /// until the block is committed, as the proposal may not make it into the chain.
+    pub fn on_proposal(&mut self, block: &Block) {
+        let topics: Vec<_> = block
+            .validator_txns()
+            .into_iter()
+            .flatten()
+            .filter_map(|txn| match txn {
+                ValidatorTransaction::EquivocationEvidence(evidence) => Some(Self::topic(evidence)),
+                _ => None,
+            })
+            .filter(|topic| self.pending.contains_key(topic))
+            .collect();
+        if !topics.is_empty() {
+            self.proposals.insert(block.id(), (block.round(), topics));
+        }
+    }
+
+    /// Stops submitting the evidence included in the blocks committed since the last call,
+    /// removing it from the pool. The committed blocks are found by walking back from
+    /// `commit_root` through `get_block`.
+    pub fn on_commit(
+        &mut self,
+        commit_root: &Block,
+        get_block: impl Fn(HashValue) -> Option<Arc<PipelinedBlock>>,
+    ) {
+        let mut next = Some((
+            commit_root.id(),
+            commit_root.round(),
+            commit_root.parent_id(),
+        ));
+        while let Some((block_id, round, parent_id)) = next {
+            if round <= self.committed_round || self.proposals.is_empty() {
+                break;
+            }
+            if let Some((_, topics)) = self.proposals.remove(&block_id) {
+                for topic in topics {
+                    self.pending.remove(&topic);
+                }
+            }
+            next = get_block(parent_id).map(|block| (block.id(), block.round(), block.parent_id()));
+        }
+
+        self.committed_round = self.committed_round.max(commit_root.round());
+        // Proposals that are behind the commit root without being committed never will be.
+        let committed_round = self.committed_round;
+        self.proposals
+            .retain(|_, (round, _)| *round > committed_round);
+    }
+
+    fn topic(evidence: &EquivocationEvidence) -> Topic {
+        Topic::EQUIVOCATION {
+            author: evidence.author,
+            epoch: evidence.epoch,
+            round: evidence.round,
+        }
+    }
+}
@@ -0,0 +1,197 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    equivocation_reporter::{proposal_evidence, vote_evidence, EquivocationReporter},
+    test_utils::mock_storage::{MockSharedStorage, MockStorage},
+};
+use lumio_consensus_types::{
+    block::{block_test_utils::certificate_for_genesis, Block},
+    common::{Payload, Round},
+    pipelined_block::{OrderedBlockWindow, PipelinedBlock},
+    vote::Vote,
+    vote_data::VoteData,
+};
+use lumio_crypto::HashValue;
+use lumio_types::{
+    block_info::BlockInfo, equivocation::EquivocationKind, ledger_info::LedgerInfo,
+    on_chain_config::ValidatorSet, validator_signer::ValidatorSigner,
+    validator_txn::ValidatorTransaction, validator_verifier::random_validator_verifier,
+};
+use lumio_validator_transaction_pool::{TransactionFilter, VTxnPoolState};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+fn vote(signer: &ValidatorSigner, round: Round, block_id: HashValue) -> Vote {
+    let vote_data = VoteData::new(
+        BlockInfo::new(1, round, block_id, HashValue::zero(), 0, 0, None),
+        BlockInfo::random(round - 1),
+    );
+    let ledger_info = LedgerInfo::new(BlockInfo::empty(), HashValue::zero());
+    Vote::new(vote_data, signer.author(), ledger_info, signer).unwrap()
+}
+
+fn proposal(signer: &ValidatorSigner, round: Round, timestamp_usecs: u64) -> Block {
+    proposal_with_vtxns(signer, round, timestamp_usecs, vec![])
+}
+
+fn proposal_with_vtxns(
+    signer: &ValidatorSigner,
+    round: Round,
+    timestamp_usecs: u64,
+    vtxns: Vec<ValidatorTransaction>,
+) -> Block {
+    Block::new_proposal_ext(
+        vtxns,
+        Payload::empty(false, true),
+        round,
+        timestamp_usecs,
+        certificate_for_genesis(),
+        signer,
+        vec![],
+    )
+    .unwrap()
+}
+
+fn pull_all(vtxn_pool: &VTxnPoolState) -> Vec<ValidatorTransaction> {
+    vtxn_pool.pull(
+        Instant::now() + Duration::from_secs(1),
+        10,
+        1 << 20,
+        TransactionFilter::empty(),
+    )
+}
+
+#[test]
+fn test_vote_evidence() {
+    let (signers, verifier) = random_validator_verifier(2, None, false);
+    let first = vote(&signers[0], 5, HashValue::random());
+    let second = vote(&signers[0], 5, HashValue::random());
+
+    let evidence = vote_evidence(&first, &second).unwrap();
+    assert_eq!(evidence.author, signers[0].author());
+    assert_eq!((evidence.epoch, evidence.round), (1, 5));
+    assert_eq!(evidence.kind(), EquivocationKind::Vote);
+    evidence.verify(&verifier).unwrap();
+
+    // The same vote twice doesn't conflict.
+    assert!(vote_evidence(&first, &first)
+        .unwrap()
+        .verify(&verifier)
+        .is_err());
+    // Votes of different rounds don't conflict either.
+    let other_round = vote(&signers[0], 6, HashValue::random());
+    assert!(vote_evidence(&first, &other_round)
+        .unwrap()
+        .verify(&verifier)
+        .is_err());
+    // Nor do votes of different authors.
+    let other_author = vote(&signers[1], 5, HashValue::random());
+    assert!(vote_evidence(&first, &other_author)
+        .unwrap()
+        .verify(&verifier)
+        .is_err());
+}
+
+#[test]
+fn test_proposal_evidence() {
+    let (signers, verifier) = random_validator_verifier(2, None, false);
+    let first = proposal(&signers[0], 1, 1);
+    let second = proposal(&signers[0], 1, 2);
+
+    let evidence = proposal_evidence(&first, &second).unwrap();
+    assert_eq!(evidence.author, signers[0].author());
+    assert_eq!((evidence.epoch, evidence.round), (first.epoch(), 1));
+    assert_eq!(evidence.kind(), EquivocationKind::Proposal);
+    evidence.verify(&verifier).unwrap();
+
+    assert!(proposal_evidence(&first, &first)
+        .unwrap()
+        .verify(&verifier)
+        .is_err());
+    let other_round = proposal(&signers[0], 2, 2);
+    assert!(proposal_evidence(&first, &other_round)
+        .unwrap()
+        .verify(&verifier)
+        .is_err());
+    let other_author = proposal(&signers[1], 1, 2);
+    assert!(proposal_evidence(&first, &other_author)
+        .unwrap()
+        .verify(&verifier)
+        .is_err());
+}
+
+#[test]
+fn test_reporter_persists_and_submits_evidence() {
+    let (signers, verifier) = random_validator_verifier(2, None, false);
+    let shared_storage = Arc::new(MockSharedStorage::new(ValidatorSet::empty()));
+    let storage = Arc::new(MockStorage::new_with_ledger_info(
+        shared_storage.clone(),
+        LedgerInfo::mock_genesis(None),
+    ));
+    let vtxn_pool = VTxnPoolState::default();
+    let mut reporter =
+        EquivocationReporter::new(Arc::new(verifier), storage, vtxn_pool.clone(), true);
+
+    // Invalid evidence is dropped.
+    let first = vote(&signers[0], 5, HashValue::random());
+    reporter.report(vote_evidence(&first, &first));
+    assert!(shared_storage.equivocation_evidence.lock().is_empty());
+    assert!(pull_all(&vtxn_pool).is_empty());
+
+    let evidence = vote_evidence(&first, &vote(&signers[0], 5, HashValue::random())).unwrap();
+    reporter.report(Ok(evidence.clone()));
+    assert_eq!(*shared_storage.equivocation_evidence.lock(), vec![
+        evidence.clone()
+    ]);
+    let vtxns = pull_all(&vtxn_pool);
+    assert_eq!(vtxns, vec![ValidatorTransaction::EquivocationEvidence(
+        evidence
+    )]);
+
+    // The evidence keeps being submitted while the proposal including it isn't committed.
+    let forked = proposal_with_vtxns(&signers[1], 1, 1, vtxns.clone());
+    reporter.on_proposal(&forked);
+    assert_eq!(pull_all(&vtxn_pool).len(), 1);
+    reporter.on_commit(&proposal(&signers[1], 2, 2), |_| None);
+    assert_eq!(pull_all(&vtxn_pool).len(), 1);
+
+    // Once committed, the evidence is no longer submitted.
+    let included = proposal_with_vtxns(&signers[1], 3, 3, vtxns);
+    reporter.on_proposal(&included);
+    let committed = proposal(&signers[1], 4, 4);
+    let blocks: HashMap<_, _> = [(
+        committed.parent_id(),
+        Arc::new(PipelinedBlock::new_ordered(
+            included,
+            OrderedBlockWindow::empty(),
+        )),
+    )]
+    .into_iter()
+    .collect();
+    reporter.on_commit(&committed, |block_id| blocks.get(&block_id).cloned());
+    assert!(pull_all(&vtxn_pool).is_empty());
+}
+
+#[test]
+fn test_reporter_without_submission() {
+    let (signers, verifier) = random_validator_verifier(1, None, false);
+    let shared_storage = Arc::new(MockSharedStorage::new(ValidatorSet::empty()));
+    let storage = Arc::new(MockStorage::new_with_ledger_info(
+        shared_storage.clone(),
+        LedgerInfo::mock_genesis(None),
+    ));
+    let vtxn_pool = VTxnPoolState::default();
+    let mut reporter =
+        EquivocationReporter::new(Arc::new(verifier), storage, vtxn_pool.clone(), false);
+
+    reporter.report(proposal_evidence(
+        &proposal(&signers[0], 1, 1),
+        &proposal(&signers[0], 1, 2),
+    ));
+    assert_eq!(shared_storage.equivocation_evidence.lock().len(), 1);
+    assert!(pull_all(&vtxn_pool).is_empty());
+}
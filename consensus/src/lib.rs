@@ -21,6 +21,9 @@ mod block_storage;
 mod consensusdb;
 mod dag;
 mod epoch_manager;
+mod equivocation_reporter;
+#[cfg(test)]
+mod equivocation_reporter_test;
 mod error;
 mod liveness;
 mod logging;
@@ -37,8 +40,12 @@ pub mod persistent_liveness_storage;
 mod pipeline;
 pub mod quorum_store;
 mod rand;
+pub mod record_replay;
 mod recovery_manager;
 mod round_manager;
+mod round_timeline;
+#[cfg(test)]
+mod round_timeline_test;
 mod state_computer;
 mod state_replication;
 #[cfg(any(test, feature = "fuzzing"))]
@@ -65,6 +72,11 @@ mod txn_hash_and_authenticator_deduper;
 
 use lumio_metrics_core::IntGauge;
 pub use consensusdb::create_checkpoint;
+/// Required by the admin service
+pub use liveness::leader_reputation::{
+    explain_leader_reputation, CandidateReputation, ProposerElectionExplanation, ReputationMetrics,
+};
+pub use round_timeline::RoundTimelines;
 /// Required by the smoke tests
 pub use consensusdb::CONSENSUS_DB_NAME;
 pub use quorum_store::quorum_store_db::QUORUM_STORE_DB_NAME;
@@ -13,23 +13,41 @@ use crate::{
     },
     liveness::proposer_election::{choose_index, ProposerElection},
 };
-use anyhow::{ensure, Result};
+use anyhow::{bail, ensure, Context, Result};
 use lumio_bitvec::BitVec;
 use lumio_consensus_types::common::{Author, Round};
 use lumio_crypto::HashValue;
 use lumio_infallible::{Mutex, MutexGuard};
 use lumio_logger::prelude::*;
-use lumio_storage_interface::DbReader;
+use lumio_storage_interface::{
+    state_store::state_view::db_state_view::DbStateViewAtVersion, DbReader,
+};
 use lumio_types::{
-    account_config::NewBlockEvent, epoch_change::EpochChangeProof, epoch_state::EpochState,
+    account_config::NewBlockEvent,
+    epoch_change::EpochChangeProof,
+    epoch_state::EpochState,
+    on_chain_config::{
+        LeaderReputationType, OnChainConfig, OnChainConsensusConfig, ProposerElectionType,
+    },
 };
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use std::{
     cmp::max,
     collections::{HashMap, HashSet},
     convert::TryFrom,
+    ops::RangeInclusive,
     sync::Arc,
 };
 
+/// Number of rounds we expect storage to be behind the proposer round,
+/// used for fetching data from DB.
+const PROPOSER_ROUND_BEHIND_STORAGE_BUFFER: usize = 30;
+/// Maximum number of rounds explained at once.
+const MAX_EXPLAINED_ROUNDS: u64 = 1000;
+/// Maximum number of blocks the explained rounds can be behind the latest committed one.
+const MAX_EXPLAINED_HISTORY_LOOKBACK: u64 = 100_000;
+
 pub type VotingPowerRatio = f64;
 
 /// Interface to query committed NewBlockEvent.
@@ -224,6 +242,26 @@ pub trait ReputationHeuristic: Send + Sync {
         epoch_to_candidates: &HashMap<u64, Vec<Author>>,
         history: &[NewBlockEvent],
     ) -> Vec<u64>;
+
+    /// Return the weights of all candidates together with the metrics they are derived from,
+    /// without updating any counters.
+    fn get_weights_and_metrics(
+        &self,
+        epoch: u64,
+        epoch_to_candidates: &HashMap<u64, Vec<Author>>,
+        history: &[NewBlockEvent],
+    ) -> Vec<(u64, ReputationMetrics)>;
+}
+
+/// What a candidate did within the reputation windows of the history.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ReputationMetrics {
+    /// Committed blocks proposed, within the proposer window.
+    pub proposals: u32,
+    /// Rounds failed as the proposer, within the proposer window.
+    pub failed_proposals: u32,
+    /// Votes included in committed blocks, within the voter window.
+    pub votes: u32,
 }
 
 pub struct NewBlockEventAggregation {
@@ -517,6 +555,34 @@ impl ProposerAndVoterHeuristic {
             ),
         }
     }
+
+    fn candidate_metrics(
+        candidates: &[Author],
+        votes: &HashMap<Author, u32>,
+        proposals: &HashMap<Author, u32>,
+        failed_proposals: &HashMap<Author, u32>,
+    ) -> Vec<ReputationMetrics> {
+        candidates
+            .iter()
+            .map(|author| ReputationMetrics {
+                proposals: *proposals.get(author).unwrap_or(&0),
+                failed_proposals: *failed_proposals.get(author).unwrap_or(&0),
+                votes: *votes.get(author).unwrap_or(&0),
+            })
+            .collect()
+    }
+
+    fn weight(&self, metrics: &ReputationMetrics) -> u64 {
+        if metrics.failed_proposals * 100
+            > (metrics.proposals + metrics.failed_proposals) * self.failure_threshold_percent
+        {
+            self.failed_weight
+        } else if metrics.proposals > 0 || metrics.votes > 0 {
+            self.active_weight
+        } else {
+            self.inactive_weight
+        }
+    }
 }
 
 impl ReputationHeuristic for ProposerAndVoterHeuristic {
@@ -532,24 +598,38 @@ impl ReputationHeuristic for ProposerAndVoterHeuristic {
             self.aggregation
                 .get_aggregated_metrics(epoch_to_candidates, history, &self.author);
 
-        epoch_to_candidates[&epoch]
-            .iter()
-            .map(|author| {
-                let cur_votes = *votes.get(author).unwrap_or(&0);
-                let cur_proposals = *proposals.get(author).unwrap_or(&0);
-                let cur_failed_proposals = *failed_proposals.get(author).unwrap_or(&0);
-
-                if cur_failed_proposals * 100
-                    > (cur_proposals + cur_failed_proposals) * self.failure_threshold_percent
-                {
-                    self.failed_weight
-                } else if cur_proposals > 0 || cur_votes > 0 {
-                    self.active_weight
-                } else {
-                    self.inactive_weight
-                }
-            })
-            .collect()
+        Self::candidate_metrics(
+            &epoch_to_candidates[&epoch],
+            &votes,
+            &proposals,
+            &failed_proposals,
+        )
+        .iter()
+        .map(|metrics| self.weight(metrics))
+        .collect()
+    }
+
+    fn get_weights_and_metrics(
+        &self,
+        epoch: u64,
+        epoch_to_candidates: &HashMap<u64, Vec<Author>>,
+        history: &[NewBlockEvent],
+    ) -> Vec<(u64, ReputationMetrics)> {
+        assert!(epoch_to_candidates.contains_key(&epoch));
+
+        Self::candidate_metrics(
+            &epoch_to_candidates[&epoch],
+            &self.aggregation.count_votes(epoch_to_candidates, history),
+            &self
+                .aggregation
+                .count_proposals(epoch_to_candidates, history),
+            &self
+                .aggregation
+                .count_failed_proposals(epoch_to_candidates, history),
+        )
+        .into_iter()
+        .map(|metrics| (self.weight(&metrics), metrics))
+        .collect()
     }
 }
 
@@ -691,31 +771,18 @@ impl LeaderReputation {
             )
         })
     }
-}
-
-impl ProposerElection for LeaderReputation {
-    fn get_valid_proposer_and_voting_power_participation_ratio(
-        &self,
-        round: Round,
-    ) -> (Author, VotingPowerRatio) {
-        let target_round = round.saturating_sub(self.exclude_round);
-        let (sliding_window, root_hash) = self.backend.get_block_metadata(self.epoch, target_round);
-        let voting_power_participation_ratio =
-            self.compute_chain_health_and_add_metrics(&sliding_window, round);
-        let mut weights =
-            self.heuristic
-                .get_weights(self.epoch, &self.epoch_to_proposers, &sliding_window);
-        let proposers = &self.epoch_to_proposers[&self.epoch];
-        assert_eq!(weights.len(), proposers.len());
 
-        // Multiply weights by voting power:
-        let stake_weights: Vec<u128> = weights
-            .iter_mut()
+    // Multiply weights by voting power.
+    fn stake_weights(&self, weights: &[u64]) -> Vec<u128> {
+        weights
+            .iter()
             .enumerate()
             .map(|(i, w)| *w as u128 * self.voting_powers[i] as u128)
-            .collect();
+            .collect()
+    }
 
-        let state = if self.use_root_hash {
+    fn seed(&self, root_hash: HashValue, round: Round) -> Vec<u8> {
+        if self.use_root_hash {
             [
                 root_hash.to_vec(),
                 self.epoch.to_le_bytes().to_vec(),
@@ -728,9 +795,99 @@ impl ProposerElection for LeaderReputation {
                 round.to_le_bytes().to_vec(),
             ]
             .concat()
-        };
+        }
+    }
+
+    /// Explains how the proposer of `round` is elected: the history it is based on, and the
+    /// reputation of every candidate. Elects the same proposer as `get_valid_proposer`, but
+    /// doesn't update any metrics.
+    pub fn explain(&self, round: Round) -> ProposerElectionExplanation {
+        let target_round = round.saturating_sub(self.exclude_round);
+        let (sliding_window, root_hash) = self.backend.get_block_metadata(self.epoch, target_round);
+        let (weights, metrics): (Vec<_>, Vec<_>) = self
+            .heuristic
+            .get_weights_and_metrics(self.epoch, &self.epoch_to_proposers, &sliding_window)
+            .into_iter()
+            .unzip();
+        let proposers = &self.epoch_to_proposers[&self.epoch];
+        assert_eq!(weights.len(), proposers.len());
+
+        let stake_weights = self.stake_weights(&weights);
+        let chosen_index = choose_index(stake_weights.clone(), self.seed(root_hash, round));
+        let candidates = proposers
+            .iter()
+            .zip(metrics)
+            .enumerate()
+            .map(|(i, (author, metrics))| CandidateReputation {
+                author: *author,
+                metrics,
+                reputation_weight: weights[i],
+                voting_power: self.voting_powers[i],
+                stake_weight: stake_weights[i],
+            })
+            .collect();
+
+        ProposerElectionExplanation {
+            epoch: self.epoch,
+            round,
+            target_round,
+            history_newest: sliding_window.first().map(|e| (e.epoch(), e.round())),
+            history_oldest: sliding_window.last().map(|e| (e.epoch(), e.round())),
+            history_len: sliding_window.len(),
+            root_hash: self.use_root_hash.then_some(root_hash),
+            proposer: proposers[chosen_index],
+            candidates,
+        }
+    }
+}
+
+/// The reputation of a candidate in the election of a round.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CandidateReputation {
+    pub author: Author,
+    #[serde(flatten)]
+    pub metrics: ReputationMetrics,
+    /// The weight the heuristic derives from the metrics.
+    pub reputation_weight: u64,
+    /// One, unless weighting by voting power.
+    pub voting_power: u64,
+    /// The weight the proposer is chosen by: reputation weight times voting power.
+    pub stake_weight: u128,
+}
+
+/// How the proposer of a round is elected, see `LeaderReputation::explain`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ProposerElectionExplanation {
+    pub epoch: u64,
+    pub round: Round,
+    /// The round the history ends at, `round` minus the excluded rounds.
+    pub target_round: Round,
+    /// The (epoch, round) of the newest and oldest committed blocks in the history.
+    pub history_newest: Option<(u64, Round)>,
+    pub history_oldest: Option<(u64, Round)>,
+    pub history_len: usize,
+    /// The accumulator root hash seeding the election, if the election uses it.
+    pub root_hash: Option<HashValue>,
+    pub proposer: Author,
+    pub candidates: Vec<CandidateReputation>,
+}
+
+impl ProposerElection for LeaderReputation {
+    fn get_valid_proposer_and_voting_power_participation_ratio(
+        &self,
+        round: Round,
+    ) -> (Author, VotingPowerRatio) {
+        let target_round = round.saturating_sub(self.exclude_round);
+        let (sliding_window, root_hash) = self.backend.get_block_metadata(self.epoch, target_round);
+        let voting_power_participation_ratio =
+            self.compute_chain_health_and_add_metrics(&sliding_window, round);
+        let weights =
+            self.heuristic
+                .get_weights(self.epoch, &self.epoch_to_proposers, &sliding_window);
+        let proposers = &self.epoch_to_proposers[&self.epoch];
+        assert_eq!(weights.len(), proposers.len());
 
-        let chosen_index = choose_index(stake_weights, state);
+        let chosen_index = choose_index(self.stake_weights(&weights), self.seed(root_hash, round));
         (proposers[chosen_index], voting_power_participation_ratio)
     }
 
@@ -820,3 +977,232 @@ pub fn extract_epoch_to_proposers(
         needed_rounds,
     )
 }
+
+/// Builds the leader reputation election for `epoch_state`, as configured by `onchain_config`,
+/// on top of the committed history in `lumio_db`. The history is read back from the latest
+/// committed block, `extra_seek_len` blocks further than needed for electing upcoming rounds.
+pub fn create_leader_reputation(
+    author: Author,
+    epoch_state: &EpochState,
+    onchain_config: &OnChainConsensusConfig,
+    leader_reputation_type: &LeaderReputationType,
+    lumio_db: Arc<dyn DbReader>,
+    window_for_chain_health: usize,
+    extra_seek_len: usize,
+) -> LeaderReputation {
+    let proposers = epoch_state
+        .verifier
+        .get_ordered_account_addresses_iter()
+        .collect::<Vec<_>>();
+    let (heuristic, window_size, weight_by_voting_power, use_history_from_previous_epoch_max_count) =
+        match &leader_reputation_type {
+            LeaderReputationType::ProposerAndVoter(proposer_and_voter_config)
+            | LeaderReputationType::ProposerAndVoterV2(proposer_and_voter_config) => {
+                let proposer_window_size = proposers.len()
+                    * proposer_and_voter_config.proposer_window_num_validators_multiplier;
+                let voter_window_size = proposers.len()
+                    * proposer_and_voter_config.voter_window_num_validators_multiplier;
+                let heuristic: Box<dyn ReputationHeuristic> =
+                    Box::new(ProposerAndVoterHeuristic::new(
+                        author,
+                        proposer_and_voter_config.active_weight,
+                        proposer_and_voter_config.inactive_weight,
+                        proposer_and_voter_config.failed_weight,
+                        proposer_and_voter_config.failure_threshold_percent,
+                        voter_window_size,
+                        proposer_window_size,
+                        leader_reputation_type.use_reputation_window_from_stale_end(),
+                    ));
+                (
+                    heuristic,
+                    std::cmp::max(proposer_window_size, voter_window_size),
+                    proposer_and_voter_config.weight_by_voting_power,
+                    proposer_and_voter_config.use_history_from_previous_epoch_max_count,
+                )
+            },
+        };
+
+    let seek_len = onchain_config.leader_reputation_exclude_round() as usize
+        + onchain_config.max_failed_authors_to_store()
+        + PROPOSER_ROUND_BEHIND_STORAGE_BUFFER
+        + extra_seek_len;
+
+    let backend = Arc::new(LumioDBBackend::new(window_size, seek_len, lumio_db.clone()));
+    let voting_powers: Vec<_> = if weight_by_voting_power {
+        proposers
+            .iter()
+            .map(|p| {
+                epoch_state
+                    .verifier
+                    .get_voting_power(p)
+                    .expect("INVARIANT VIOLATION: proposer not in verifier set")
+            })
+            .collect()
+    } else {
+        vec![1; proposers.len()]
+    };
+
+    let epoch_to_proposers = extract_epoch_proposers(
+        lumio_db.as_ref(),
+        epoch_state,
+        use_history_from_previous_epoch_max_count,
+        proposers,
+        (window_size + seek_len) as u64,
+    );
+
+    info!(
+        "Leader reputation for epoch {}: proposers across epochs for leader election: {:?}",
+        epoch_state.epoch,
+        epoch_to_proposers
+            .iter()
+            .map(|(epoch, proposers)| (epoch, proposers.len()))
+            .sorted()
+            .collect::<Vec<_>>()
+    );
+
+    LeaderReputation::new(
+        epoch_state.epoch,
+        epoch_to_proposers,
+        voting_powers,
+        backend,
+        heuristic,
+        onchain_config.leader_reputation_exclude_round(),
+        leader_reputation_type.use_root_hash_for_seed(),
+        window_for_chain_health,
+    )
+}
+
+pub(crate) fn extract_epoch_proposers(
+    lumio_db: &dyn DbReader,
+    epoch_state: &EpochState,
+    use_history_from_previous_epoch_max_count: u32,
+    proposers: Vec<Author>,
+    needed_rounds: u64,
+) -> HashMap<u64, Vec<Author>> {
+    // Genesis is epoch=0
+    // First block (after genesis) is epoch=1, and is the only block in that epoch.
+    // It has no votes, so we skip it unless we are in epoch 1, as otherwise it will
+    // skew leader elections for exclude_round number of rounds.
+    let first_epoch_to_consider = std::cmp::max(
+        if epoch_state.epoch == 1 { 1 } else { 2 },
+        epoch_state
+            .epoch
+            .saturating_sub(use_history_from_previous_epoch_max_count as u64),
+    );
+    // If we are considering beyond the current epoch, we need to fetch validators for those epochs
+    if epoch_state.epoch > first_epoch_to_consider {
+        lumio_db
+            .get_epoch_ending_ledger_infos(first_epoch_to_consider - 1, epoch_state.epoch)
+            .map_err(Into::into)
+            .and_then(|proof| {
+                ensure!(
+                    proof.ledger_info_with_sigs.len() as u64
+                        == (epoch_state.epoch - (first_epoch_to_consider - 1))
+                );
+                extract_epoch_to_proposers(proof, epoch_state.epoch, &proposers, needed_rounds)
+            })
+            .unwrap_or_else(|err| {
+                error!(
+                    "Couldn't create leader reputation with history across epochs, {:?}",
+                    err
+                );
+                HashMap::from([(epoch_state.epoch, proposers)])
+            })
+    } else {
+        HashMap::from([(epoch_state.epoch, proposers)])
+    }
+}
+
+/// Explains the election of the proposers of `rounds` in `epoch`, reconstructing the leader
+/// reputation of the epoch from the on-chain config and the committed history in `lumio_db`.
+pub fn explain_leader_reputation(
+    lumio_db: Arc<dyn DbReader>,
+    epoch: u64,
+    rounds: RangeInclusive<Round>,
+    window_for_chain_health: usize,
+) -> Result<Vec<ProposerElectionExplanation>> {
+    ensure!(epoch > 0, "Genesis has no proposers");
+    ensure!(
+        !rounds.is_empty() && rounds.end() - rounds.start() < MAX_EXPLAINED_ROUNDS,
+        "Expected between 1 and {} rounds, got {:?}",
+        MAX_EXPLAINED_ROUNDS,
+        rounds
+    );
+    let latest_ledger_info = lumio_db.get_latest_ledger_info()?;
+    let latest = latest_ledger_info.ledger_info();
+    ensure!(
+        epoch <= latest.next_block_epoch(),
+        "Epoch {} hasn't started, the latest is {}",
+        epoch,
+        latest.next_block_epoch()
+    );
+
+    // The validator set and config of an epoch are the ones at the end of the previous one.
+    let epoch_start = lumio_db
+        .get_epoch_ending_ledger_infos(epoch - 1, epoch)?
+        .ledger_info_with_sigs
+        .pop()
+        .context("Missing the ledger info starting the epoch")?;
+    let epoch_state = epoch_start
+        .ledger_info()
+        .next_epoch_state()
+        .context("The ledger info starting the epoch has no validator set")?;
+    let onchain_config = OnChainConsensusConfig::fetch_config(
+        &lumio_db.state_view_at_version(Some(epoch_start.ledger_info().version()))?,
+    )
+    .unwrap_or_default();
+    let ProposerElectionType::LeaderReputation(leader_reputation_type) =
+        onchain_config.proposer_election_type()
+    else {
+        bail!(
+            "Epoch {} doesn't elect proposers by leader reputation: {:?}",
+            epoch,
+            onchain_config.proposer_election_type()
+        );
+    };
+
+    // The history is read back from the latest committed block, so it has to reach past the
+    // blocks committed after the first explained round.
+    let (epoch_end_version, epoch_end_round) = if latest.epoch() > epoch {
+        let epoch_end = lumio_db
+            .get_epoch_ending_ledger_infos(epoch, epoch + 1)?
+            .ledger_info_with_sigs
+            .pop()
+            .context("Missing the ledger info ending the epoch")?;
+        (
+            epoch_end.ledger_info().version(),
+            epoch_end.ledger_info().round(),
+        )
+    } else if latest.epoch() == epoch {
+        (latest.version(), latest.round())
+    } else {
+        (latest.version(), 0)
+    };
+    let block_height =
+        |version| -> Result<u64> { Ok(lumio_db.get_block_info_by_version(version)?.2.height()) };
+    let first_target_round = rounds
+        .start()
+        .saturating_sub(onchain_config.leader_reputation_exclude_round());
+    let extra_seek_len = (block_height(latest.version())? - block_height(epoch_end_version)?)
+        + epoch_end_round.saturating_sub(first_target_round);
+    ensure!(
+        extra_seek_len <= MAX_EXPLAINED_HISTORY_LOOKBACK,
+        "Rounds are too far in the past, {} blocks behind the latest",
+        extra_seek_len
+    );
+
+    // The author only labels the metrics of the node's own reputation, which explaining
+    // doesn't update.
+    let leader_reputation = create_leader_reputation(
+        Author::ZERO,
+        epoch_state,
+        &onchain_config,
+        leader_reputation_type,
+        lumio_db,
+        window_for_chain_health,
+        extra_seek_len as usize,
+    );
+    Ok(rounds
+        .map(|round| leader_reputation.explain(round))
+        .collect())
+}
@@ -3,11 +3,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::leader_reputation::{
-    extract_epoch_to_proposers_impl, LumioDBBackend, ProposerAndVoterHeuristic,
+    create_leader_reputation, explain_leader_reputation, extract_epoch_to_proposers_impl,
+    LumioDBBackend, ProposerAndVoterHeuristic,
 };
 use crate::liveness::{
     leader_reputation::{
         LeaderReputation, MetadataBackend, NewBlockEventAggregation, ReputationHeuristic,
+        ReputationMetrics,
     },
     proposer_election::{choose_index, ProposerElection},
 };
@@ -20,8 +22,14 @@ use lumio_storage_interface::DbReader;
 use lumio_types::{
     account_address::AccountAddress,
     account_config::{new_block_event_key, NewBlockEvent},
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
     contract_event::{ContractEvent, EventWithVersion},
+    epoch_change::EpochChangeProof,
     epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    on_chain_config::{OnChainConsensusConfig, ProposerElectionType},
+    state_store::{state_key::StateKey, state_value::StateValue},
     transaction::Version,
     validator_verifier::{ValidatorConsensusInfo, ValidatorVerifier},
 };
@@ -387,6 +395,103 @@ fn test_api(use_root_hash: bool) {
     }
 }
 
+#[test]
+fn test_explain_matches_election() {
+    let mut example1 = Example1::new(5);
+    example1.step1();
+    example1.step2();
+    let validators0 = example1.validators0.clone();
+    let epoch_to_validators0 = HashMap::from([(0u64, validators0.clone())]);
+    let new_heuristic =
+        || ProposerAndVoterHeuristic::new(validators0[0], 100, 10, 1, 49, 2, 5, false);
+
+    let leader_reputation = LeaderReputation::new(
+        0,
+        epoch_to_validators0.clone(),
+        vec![1, 2, 3, 4],
+        Arc::new(LumioDBBackend::new(5, 0, example1.lumio_db.clone())),
+        Box::new(new_heuristic()),
+        0,
+        true,
+        30,
+    );
+
+    let explanation = leader_reputation.explain(10);
+    assert_eq!(
+        (
+            explanation.epoch,
+            explanation.round,
+            explanation.target_round
+        ),
+        (0, 10, 10)
+    );
+    assert_eq!(
+        (
+            explanation.history_newest,
+            explanation.history_oldest,
+            explanation.history_len
+        ),
+        (Some((0, 10)), Some((0, 3)), 5)
+    );
+    assert_eq!(explanation.root_hash, Some(HashValue::zero()));
+    let metrics = |proposals, failed_proposals, votes| ReputationMetrics {
+        proposals,
+        failed_proposals,
+        votes,
+    };
+    assert_eq!(
+        explanation
+            .candidates
+            .iter()
+            .map(|c| (
+                c.author,
+                c.metrics.clone(),
+                c.reputation_weight,
+                c.voting_power,
+                c.stake_weight
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            (validators0[0], metrics(1, 0, 2), 100, 1, 100),
+            (validators0[1], metrics(1, 2, 2), 1, 2, 2),
+            (validators0[2], metrics(1, 1, 0), 1, 3, 3),
+            (validators0[3], metrics(2, 0, 0), 100, 4, 400),
+        ]
+    );
+    // The explained weights are the ones of the heuristic.
+    assert_eq!(
+        explanation
+            .candidates
+            .iter()
+            .map(|c| c.reputation_weight)
+            .collect::<Vec<_>>(),
+        new_heuristic().get_weights(0, &epoch_to_validators0, &example1.history())
+    );
+
+    for round in 10..200 {
+        let explanation = leader_reputation.explain(round);
+        assert_eq!(
+            explanation.proposer,
+            leader_reputation.get_valid_proposer(round)
+        );
+        let seed = [
+            HashValue::zero().to_vec(),
+            0u64.to_le_bytes().to_vec(),
+            round.to_le_bytes().to_vec(),
+        ]
+        .concat();
+        let stake_weights = explanation
+            .candidates
+            .iter()
+            .map(|c| c.stake_weight)
+            .collect();
+        assert_eq!(
+            explanation.proposer,
+            validators0[choose_index(stake_weights, seed)]
+        );
+    }
+}
+
 struct MockDbReader {
     events: Mutex<Vec<EventWithVersion>>,
     random_address: Author,
@@ -520,6 +625,167 @@ impl DbReader for MockDbReader {
     }
 }
 
+/// History of epoch 1, started with `epoch_state` and the default on-chain consensus config.
+struct EpochDbReader {
+    history: MockDbReader,
+    epoch_state: EpochState,
+}
+
+impl DbReader for EpochDbReader {
+    fn get_latest_block_events(
+        &self,
+        num_events: usize,
+    ) -> lumio_storage_interface::Result<Vec<EventWithVersion>> {
+        self.history.get_latest_block_events(num_events)
+    }
+
+    fn get_latest_ledger_info_version(&self) -> lumio_storage_interface::Result<Version> {
+        self.history.get_latest_ledger_info_version()
+    }
+
+    fn get_accumulator_root_hash(
+        &self,
+        version: Version,
+    ) -> lumio_storage_interface::Result<HashValue> {
+        self.history.get_accumulator_root_hash(version)
+    }
+
+    fn get_latest_ledger_info_option(
+        &self,
+    ) -> lumio_storage_interface::Result<Option<LedgerInfoWithSignatures>> {
+        let block_info = BlockInfo::new(
+            1,
+            *self.history.round.lock(),
+            HashValue::zero(),
+            HashValue::zero(),
+            *self.history.idx.lock(),
+            0,
+            None,
+        );
+        Ok(Some(LedgerInfoWithSignatures::new(
+            LedgerInfo::new(block_info, HashValue::zero()),
+            AggregateSignature::empty(),
+        )))
+    }
+
+    fn get_epoch_ending_ledger_infos(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> lumio_storage_interface::Result<EpochChangeProof> {
+        assert_eq!((start_epoch, end_epoch), (0, 1));
+        let block_info = BlockInfo::new(
+            0,
+            0,
+            HashValue::zero(),
+            HashValue::zero(),
+            0,
+            0,
+            Some(self.epoch_state.clone()),
+        );
+        Ok(EpochChangeProof::new(
+            vec![LedgerInfoWithSignatures::new(
+                LedgerInfo::new(block_info, HashValue::zero()),
+                AggregateSignature::empty(),
+            )],
+            false,
+        ))
+    }
+
+    fn get_state_value_with_version_by_version(
+        &self,
+        _state_key: &StateKey,
+        _version: Version,
+    ) -> lumio_storage_interface::Result<Option<(Version, StateValue)>> {
+        Ok(None)
+    }
+
+    fn get_block_info_by_version(
+        &self,
+        version: Version,
+    ) -> lumio_storage_interface::Result<(Version, Version, NewBlockEvent)> {
+        let block = NewBlockEvent::new(
+            AccountAddress::ZERO,
+            1,
+            0,
+            version,
+            vec![],
+            AccountAddress::ZERO,
+            vec![],
+            0,
+        );
+        Ok((version, version, block))
+    }
+}
+
+#[test]
+fn test_explain_leader_reputation() {
+    let private_key = KeyGen::from_os_rng().generate_bls12381_private_key();
+    let public_key = bls12381::PublicKey::from(&private_key);
+    let authors: Vec<AccountAddress> = (0..4).map(|_| AccountAddress::random()).sorted().collect();
+    let epoch_state = EpochState::new(
+        1,
+        ValidatorVerifier::new(
+            authors
+                .iter()
+                .map(|author| ValidatorConsensusInfo::new(*author, public_key.clone(), 1))
+                .collect(),
+        ),
+    );
+    let lumio_db = Arc::new(EpochDbReader {
+        history: MockDbReader::new(),
+        epoch_state: epoch_state.clone(),
+    });
+    lumio_db.history.new_epoch();
+    for i in 0..50 {
+        // The first validator never votes, and the last one fails every other proposal.
+        let failed_proposers = if i % 2 == 0 { vec![3] } else { vec![] };
+        lumio_db
+            .history
+            .add_event_with_data(authors[i % 3], vec![1, 2, 3], failed_proposers);
+    }
+    let latest_round = *lumio_db.history.round.lock();
+
+    let onchain_config = OnChainConsensusConfig::default();
+    let ProposerElectionType::LeaderReputation(leader_reputation_type) =
+        onchain_config.proposer_election_type()
+    else {
+        unreachable!("The default config elects proposers by leader reputation");
+    };
+    let leader_reputation = create_leader_reputation(
+        authors[0],
+        &epoch_state,
+        &onchain_config,
+        leader_reputation_type,
+        lumio_db.clone(),
+        30,
+        0,
+    );
+
+    let rounds = latest_round - 5..=latest_round + 5;
+    let explanations = explain_leader_reputation(lumio_db.clone(), 1, rounds.clone(), 30).unwrap();
+    assert_eq!(explanations.len(), 11);
+    for (round, explanation) in rounds.zip(explanations) {
+        assert_eq!((explanation.epoch, explanation.round), (1, round));
+        assert_eq!(
+            explanation
+                .candidates
+                .iter()
+                .map(|c| c.author)
+                .collect::<Vec<_>>(),
+            authors
+        );
+        assert_eq!(
+            explanation.proposer,
+            leader_reputation.get_valid_proposer(round)
+        );
+    }
+
+    assert_err!(explain_leader_reputation(lumio_db.clone(), 0, 1..=1, 30));
+    assert_err!(explain_leader_reputation(lumio_db.clone(), 2, 1..=1, 30));
+    assert_err!(explain_leader_reputation(lumio_db, 1, 1..=1000, 30));
+}
+
 #[test]
 fn backend_wrapper_test() {
     let lumio_db = Arc::new(MockDbReader::new());
@@ -8,7 +8,7 @@ use crate::{
     util::time_service::{SendTask, TimeService},
 };
 use lumio_consensus_types::{
-    common::Round,
+    common::{Author, Round},
     round_timeout::{RoundTimeout, RoundTimeoutReason},
     sync_info::SyncInfo,
     timeout_2chain::TwoChainTimeoutWithPartialSignatures,
@@ -304,6 +304,11 @@ impl RoundState {
         }
     }
 
+    /// Returns the vote of `author` received in the current round, if any.
+    pub fn pending_vote_of(&self, author: &Author) -> Option<&Vote> {
+        self.pending_votes.vote_of(author)
+    }
+
     pub fn insert_round_timeout(
         &mut self,
         timeout: &RoundTimeout,
@@ -6,7 +6,6 @@ use lumio_consensus_types::{
     block::Block,
     common::{Author, Round},
 };
-use lumio_crypto::HashValue;
 use lumio_infallible::Mutex;
 use lumio_logger::{error, warn, SecurityEvent};
 use std::{cmp::Ordering, sync::Arc};
@@ -17,7 +16,8 @@ use std::{cmp::Ordering, sync::Arc};
 // the same leader proposes multiple blocks.
 pub struct UnequivocalProposerElection {
     proposer_election: Arc<dyn ProposerElection + Send + Sync>,
-    already_proposed: Mutex<(Round, HashValue)>,
+    // The first proposal of the highest round seen, kept as equivocation evidence.
+    already_proposed: Mutex<Option<Block>>,
 }
 
 impl ProposerElection for UnequivocalProposerElection {
@@ -35,7 +35,7 @@ impl UnequivocalProposerElection {
     pub fn new(proposer_election: Arc<dyn ProposerElection + Send + Sync>) -> Self {
         Self {
             proposer_election,
-            already_proposed: Mutex::new((0, HashValue::zero())),
+            already_proposed: Mutex::new(None),
         }
     }
 
@@ -59,21 +59,24 @@ impl UnequivocalProposerElection {
                 return false;
             }
             let mut already_proposed = self.already_proposed.lock();
+            let (proposed_round, proposed_id) =
+                already_proposed.as_ref().map_or((0, None), |proposed| {
+                    (proposed.round(), Some(proposed.id()))
+                });
             // detect if the leader proposes more than once in this round
-            match block.round().cmp(&already_proposed.0) {
+            match block.round().cmp(&proposed_round) {
                 Ordering::Greater => {
-                    already_proposed.0 = block.round();
-                    already_proposed.1 = block.id();
+                    *already_proposed = Some(block.clone());
                     true
                 },
                 Ordering::Equal => {
-                    if already_proposed.1 != block.id() {
+                    if proposed_id != Some(block.id()) {
                         error!(
                             SecurityEvent::InvalidConsensusProposal,
-                            "Multiple proposals from {} for round {}: {} and {}",
+                            "Multiple proposals from {} for round {}: {:?} and {}",
                             author,
                             block.round(),
-                            already_proposed.1,
+                            proposed_id,
                             block.id()
                         );
                         false
@@ -85,4 +88,18 @@ impl UnequivocalProposerElection {
             }
         })
     }
+
+    // Return the first proposal of the round of `block`, if `block` is a different proposal of
+    // the same author for that round.
+    pub fn equivocating_proposal(&self, block: &Block) -> Option<Block> {
+        self.already_proposed
+            .lock()
+            .as_ref()
+            .filter(|proposed| {
+                proposed.round() == block.round()
+                    && proposed.id() != block.id()
+                    && proposed.author() == block.author()
+            })
+            .cloned()
+    }
 }
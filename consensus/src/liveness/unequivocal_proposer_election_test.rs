@@ -94,12 +94,20 @@ fn test_is_valid_proposal() {
 
     // another proposal from the valid proposer should fail
     assert!(!pe.is_valid_proposal(&bad_duplicate_proposal));
+    // and is reported along with the first one
+    assert_eq!(
+        pe.equivocating_proposal(&bad_duplicate_proposal),
+        Some(good_proposal.clone())
+    );
+    assert_eq!(pe.equivocating_proposal(&good_proposal), None);
+    assert_eq!(pe.equivocating_proposal(&bad_author_proposal), None);
     // good proposal still passes
     assert!(pe.is_valid_proposal(&good_proposal));
 
     // going to the next round:
     assert!(pe.is_valid_proposal(&next_good_proposal));
     assert!(!pe.is_valid_proposal(&next_bad_duplicate_proposal));
+    assert_eq!(pe.equivocating_proposal(&bad_duplicate_proposal), None);
 
     // Proposal from previous round is not valid any more:
     assert!(!pe.is_valid_proposal(&good_proposal));
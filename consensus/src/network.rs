@@ -18,6 +18,7 @@ use crate::{
         network_messages::{RandGenMessage, RandMessage},
         types::{AugmentedData, FastShare, Share},
     },
+    record_replay::{self, TraceEvent},
 };
 use anyhow::{anyhow, bail, ensure};
 use lumio_channels::{self, lumio_channel, message_queues::QueueStyle};
@@ -312,16 +313,29 @@ impl NetworkSender {
         counters::CONSENSUS_SENT_MSGS
             .with_label_values(&[msg.name()])
             .inc();
-        if receiver == self.author() {
+        let recorded_request = record_replay::is_recording().then(|| msg.clone());
+        let response = if receiver == self.author() {
             self.send_rpc_to_self(msg, timeout_duration).await
         } else {
-            Ok(monitor!(
+            monitor!(
                 "send_rpc",
                 self.consensus_network_client
                     .send_rpc(receiver, msg, timeout_duration)
                     .await
-            )?)
+            )
+            .map_err(anyhow::Error::from)
+        };
+        if let Some(request) = recorded_request {
+            record_replay::record(|| TraceEvent::Rpc {
+                to: receiver,
+                request,
+                response: response
+                    .as_ref()
+                    .map(Clone::clone)
+                    .map_err(|e| e.to_string()),
+            });
         }
+        response
     }
 
     /// Tries to send the given msg to all the participants.
@@ -355,6 +369,10 @@ impl NetworkSender {
 
     pub fn broadcast_without_self(&self, msg: ConsensusMsg) {
         fail_point!("consensus::send::any", |_| ());
+        record_replay::record(|| TraceEvent::Outbound {
+            to: None,
+            msg: msg.clone(),
+        });
 
         let self_author = self.author;
         let mut other_validators: Vec<_> = self
@@ -379,6 +397,10 @@ impl NetworkSender {
     /// Tries to send msg to given recipients.
     async fn send(&self, msg: ConsensusMsg, recipients: Vec<Author>) {
         fail_point!("consensus::send::any", |_| ());
+        record_replay::record(|| TraceEvent::Outbound {
+            to: Some(recipients.clone()),
+            msg: msg.clone(),
+        });
         let network_sender = self.consensus_network_client.clone();
         let mut self_sender = self.self_sender.clone();
         for peer in recipients {
@@ -764,6 +786,10 @@ impl NetworkTask {
                     counters::CONSENSUS_RECEIVED_MSGS
                         .with_label_values(&[msg.name()])
                         .inc();
+                    record_replay::record(|| TraceEvent::Inbound {
+                        from: peer_id,
+                        msg: msg.clone(),
+                    });
                     match msg {
                         quorum_store_msg @ (ConsensusMsg::SignedBatchInfo(_)
                         | ConsensusMsg::BatchMsg(_)
@@ -871,6 +897,10 @@ impl NetworkTask {
                     counters::CONSENSUS_RECEIVED_MSGS
                         .with_label_values(&[msg.name()])
                         .inc();
+                    record_replay::record(|| TraceEvent::Inbound {
+                        from: peer_id,
+                        msg: msg.clone(),
+                    });
                     let req = match msg {
                         // TODO @bchocho @hariria revisit deprecation later once BlockRetrievalRequest enum is released
                         ConsensusMsg::DeprecatedBlockRetrievalRequest(request) => {
@@ -23,6 +23,7 @@ use lumio_network::{
     application::storage::PeersAndMetadata,
     peer_manager::{ConnectionRequestSender, PeerManagerRequest, PeerManagerRequestSender},
     protocols::{
+        direct_send::Message,
         network::{NewNetworkEvents, ReceivedMessage, RpcError, SerializedRequest},
         wire::{
             handshake::v1::ProtocolIdSet,
@@ -32,7 +33,10 @@ use lumio_network::{
     ProtocolId,
 };
 use lumio_types::{block_info::BlockInfo, PeerId};
-use futures::{channel::mpsc, SinkExt, StreamExt};
+use futures::{
+    channel::{mpsc, oneshot},
+    SinkExt, StreamExt,
+};
 use std::{
     collections::{HashMap, HashSet},
     iter::FromIterator,
@@ -51,6 +55,14 @@ pub struct TwinId {
     pub author: Author,
 }
 
+/// Rewrites the messages sent by a node, direct-send and rpc requests alike, e.g. to turn it
+/// Byzantine in tests.
+pub trait MessageMutator: Send + Sync {
+    /// Returns the messages to deliver to `dst` in place of `msg`: none to drop it, or several
+    /// to duplicate it or to release messages held back earlier.
+    fn mutate(&self, dst: &TwinId, msg: ConsensusMsg) -> Vec<ConsensusMsg>;
+}
+
 /// `NetworkPlayground` mocks the network implementation and provides convenience
 /// methods for testing. Test clients can use `wait_for_messages` or
 /// `deliver_messages` to inspect the direct-send messages sent between peers.
@@ -77,6 +89,8 @@ pub struct NetworkPlayground {
     drop_config: Arc<RwLock<DropConfig>>,
     /// Allow test code to drop direct-send messages between peers per round.
     drop_config_round: DropConfigRound,
+    /// Allow test code to rewrite the messages sent by a peer.
+    message_mutators: Arc<RwLock<HashMap<TwinId, Arc<dyn MessageMutator>>>>,
     /// An executor for spawning node outbound network event handlers
     executor: Handle,
     /// Maps authors to twins IDs
@@ -97,6 +111,7 @@ impl NetworkPlayground {
             timeout_config: Arc::new(RwLock::new(TimeoutConfig::default())),
             drop_config: Arc::new(RwLock::new(DropConfig::default())),
             drop_config_round: DropConfigRound::default(),
+            message_mutators: Arc::new(RwLock::new(HashMap::new())),
             executor,
             author_to_twin_ids: Arc::new(RwLock::new(AuthorToTwinIds::default())),
             peers_and_metadata: PeersAndMetadata::new(&[NetworkId::Validator]),
@@ -119,7 +134,9 @@ impl NetworkPlayground {
     /// `deliver_messages` and `wait_for_messages` API's.
     ///
     /// Rpc messages are immediately sent to the destination for handling, so
-    /// they don't block.
+    /// they don't block. The message mutator of the node applies to them too:
+    /// only the first message it returns gets the response, the others are
+    /// delivered with their response dropped.
     async fn start_node_outbound_handler(
         timeout_config: Arc<RwLock<TimeoutConfig>>,
         drop_config: Arc<RwLock<DropConfig>>,
@@ -130,6 +147,7 @@ impl NetworkPlayground {
             Mutex<HashMap<TwinId, lumio_channel::Sender<(PeerId, ProtocolId), ReceivedMessage>>>,
         >,
         author_to_twin_ids: Arc<RwLock<AuthorToTwinIds>>,
+        message_mutators: Arc<RwLock<HashMap<TwinId, Arc<dyn MessageMutator>>>>,
     ) {
         while let Some(net_req) = network_reqs_rx.next().await {
             match net_req {
@@ -166,25 +184,42 @@ impl NetworkPlayground {
                     let node_consensus_tx =
                         node_consensus_txs.lock().get(dst_twin_id).unwrap().clone();
 
-                    node_consensus_tx
-                        .push(
-                            (src_twin_id.author, ProtocolId::ConsensusRpcBcs),
-                            ReceivedMessage {
-                                message: NetworkMessage::RpcRequest(RpcRequest {
-                                    protocol_id: outbound_req.protocol_id,
-                                    request_id: 123,
-                                    priority: 0,
-                                    raw_request: outbound_req.data.into(),
-                                }),
-                                sender: PeerNetworkId::new(
-                                    NetworkId::Validator,
-                                    src_twin_id.author,
-                                ),
-                                receive_timestamp_micros: 0,
-                                rpc_replier: Some(Arc::new(outbound_req.res_tx)),
-                            },
-                        )
-                        .unwrap();
+                    let protocol_id = outbound_req.protocol_id;
+                    let mutator = message_mutators.read().get(&src_twin_id).cloned();
+                    let raw_requests = match mutator.as_ref().and_then(|mutator| {
+                        let msg = protocol_id.from_bytes(&outbound_req.data).ok()?;
+                        Some(mutator.mutate(dst_twin_id, msg))
+                    }) {
+                        Some(msgs) => msgs
+                            .iter()
+                            .map(|msg| protocol_id.to_bytes(msg).unwrap())
+                            .collect(),
+                        None => vec![outbound_req.data.into()],
+                    };
+                    // A dropped rpc request never gets a response.
+                    let mut res_tx = Some(outbound_req.res_tx);
+                    for raw_request in raw_requests {
+                        let rpc_replier = res_tx.take().unwrap_or_else(|| oneshot::channel().0);
+                        node_consensus_tx
+                            .push(
+                                (src_twin_id.author, ProtocolId::ConsensusRpcBcs),
+                                ReceivedMessage {
+                                    message: NetworkMessage::RpcRequest(RpcRequest {
+                                        protocol_id,
+                                        request_id: 123,
+                                        priority: 0,
+                                        raw_request,
+                                    }),
+                                    sender: PeerNetworkId::new(
+                                        NetworkId::Validator,
+                                        src_twin_id.author,
+                                    ),
+                                    receive_timestamp_micros: 0,
+                                    rpc_replier: Some(Arc::new(rpc_replier)),
+                                },
+                            )
+                            .unwrap();
+                    }
                 },
                 // Other PeerManagerRequest get buffered for `deliver_messages` to
                 // synchronously drain.
@@ -217,6 +252,7 @@ impl NetworkPlayground {
             self.outbound_msgs_tx.clone(),
             self.node_consensus_txs.clone(),
             self.author_to_twin_ids.clone(),
+            self.message_mutators.clone(),
         );
         let fut2 = conn_mgr_reqs_rx.map(Ok).forward(::futures::sink::drain());
         self.executor.spawn(futures::future::join(fut1, fut2));
@@ -291,25 +327,16 @@ impl NetworkPlayground {
 
             let dst_twin_ids = self.get_twin_ids(dst);
             for (idx, dst_twin_id) in dst_twin_ids.iter().enumerate() {
-                let consensus_msg = msg.to_message().unwrap();
-
-                // Deliver and copy message if it's not dropped
-                if !self.is_message_dropped(&src_twin_id, dst_twin_id, consensus_msg) {
-                    let rmsg = ReceivedMessage {
-                        message: NetworkMessage::DirectSendMsg(DirectSendMsg {
-                            protocol_id: msg.protocol_id,
-                            priority: 0,
-                            raw_msg: msg.mdata.clone().into(),
-                        }),
-                        sender: PeerNetworkId::new(NetworkId::Validator, src_twin_id.author),
-                        receive_timestamp_micros: 0,
-                        rpc_replier: None,
-                    };
-                    let msg_copy = self.deliver_message(src_twin_id, *dst_twin_id, rmsg).await;
-
-                    // Only insert msg_copy once for twins (if delivered)
-                    if idx == 0 && msg_inspector(&msg_copy) {
-                        msg_copies.push(msg_copy);
+                for (consensus_msg, rmsg) in self.outgoing_messages(&src_twin_id, dst_twin_id, &msg)
+                {
+                    // Deliver and copy message if it's not dropped
+                    if !self.is_message_dropped(&src_twin_id, dst_twin_id, consensus_msg) {
+                        let msg_copy = self.deliver_message(src_twin_id, *dst_twin_id, rmsg).await;
+
+                        // Only insert msg_copy once for twins (if delivered)
+                        if idx == 0 && msg_inspector(&msg_copy) {
+                            msg_copies.push(msg_copy);
+                        }
                     }
                 }
             }
@@ -318,6 +345,42 @@ impl NetworkPlayground {
         msg_copies
     }
 
+    /// Returns the messages to deliver from `src` to `dst` for a sent message, after applying
+    /// the message mutator of `src` if there is one.
+    fn outgoing_messages(
+        &self,
+        src: &TwinId,
+        dst: &TwinId,
+        msg: &Message,
+    ) -> Vec<(ConsensusMsg, ReceivedMessage)> {
+        let consensus_msg: ConsensusMsg = msg.to_message().unwrap();
+        let mutator = self.message_mutators.read().get(src).cloned();
+        let consensus_msgs = match &mutator {
+            Some(mutator) => mutator.mutate(dst, consensus_msg),
+            None => vec![consensus_msg],
+        };
+        consensus_msgs
+            .into_iter()
+            .map(|consensus_msg| {
+                let raw_msg = match &mutator {
+                    Some(_) => msg.protocol_id.to_bytes(&consensus_msg).unwrap(),
+                    None => msg.mdata.clone().into(),
+                };
+                let rmsg = ReceivedMessage {
+                    message: NetworkMessage::DirectSendMsg(DirectSendMsg {
+                        protocol_id: msg.protocol_id,
+                        priority: 0,
+                        raw_msg,
+                    }),
+                    sender: PeerNetworkId::new(NetworkId::Validator, src.author),
+                    receive_timestamp_micros: 0,
+                    rpc_replier: None,
+                };
+                (consensus_msg, rmsg)
+            })
+            .collect()
+    }
+
     /// Return the round of a given message
     fn get_message_round(msg: ConsensusMsg) -> Option<u64> {
         match msg {
@@ -394,6 +457,11 @@ impl NetworkPlayground {
         ret
     }
 
+    /// Rewrites all the messages sent by `twin_id` with `mutator`.
+    pub fn set_message_mutator(&mut self, twin_id: TwinId, mutator: Arc<dyn MessageMutator>) {
+        self.message_mutators.write().insert(twin_id, mutator);
+    }
+
     pub fn timeout_config(&self) -> Arc<RwLock<TimeoutConfig>> {
         self.timeout_config.clone()
     }
@@ -416,21 +484,12 @@ impl NetworkPlayground {
             let dst_twin_ids = self.get_twin_ids(dst);
 
             for dst_twin_id in dst_twin_ids.iter() {
-                let rmsg = ReceivedMessage {
-                    message: NetworkMessage::DirectSendMsg(DirectSendMsg {
-                        protocol_id: msg.protocol_id,
-                        priority: 0,
-                        raw_msg: msg.mdata.clone().into(),
-                    }),
-                    sender: PeerNetworkId::new(NetworkId::Validator, src_twin_id.author),
-                    receive_timestamp_micros: 0,
-                    rpc_replier: None,
-                };
-                let consensus_msg = msg.to_message().unwrap();
-
-                // Deliver and copy message it if it's not dropped
-                if !self.is_message_dropped(&src_twin_id, dst_twin_id, consensus_msg) {
-                    self.deliver_message(src_twin_id, *dst_twin_id, rmsg).await;
+                for (consensus_msg, rmsg) in self.outgoing_messages(&src_twin_id, dst_twin_id, &msg)
+                {
+                    // Deliver and copy message it if it's not dropped
+                    if !self.is_message_dropped(&src_twin_id, dst_twin_id, consensus_msg) {
+                        self.deliver_message(src_twin_id, *dst_twin_id, rmsg).await;
+                    }
                 }
             }
         }
@@ -554,7 +613,7 @@ mod tests {
     };
     use lumio_types::validator_verifier::random_validator_verifier;
     use bytes::Bytes;
-    use futures::{channel::oneshot, future};
+    use futures::future;
     use maplit::hashmap;
 
     #[test]
@@ -187,6 +187,11 @@ impl PendingVotes {
         }
     }
 
+    /// Returns the vote of `author` in this round, if any.
+    pub fn vote_of(&self, author: &Author) -> Option<&Vote> {
+        self.author_to_vote.get(author).map(|(vote, _)| vote)
+    }
+
     /// Insert a RoundTimeout and return a TimeoutCertificate if it can be formed
     pub fn insert_round_timeout(
         &mut self,
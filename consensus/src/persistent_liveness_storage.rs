@@ -16,9 +16,11 @@ use lumio_crypto::HashValue;
 use lumio_logger::prelude::*;
 use lumio_storage_interface::DbReader;
 use lumio_types::{
-    block_info::Round, epoch_change::EpochChangeProof, ledger_info::LedgerInfoWithSignatures,
-    proof::TransactionAccumulatorSummary, transaction::Version,
+    block_info::Round, epoch_change::EpochChangeProof, equivocation::EquivocationEvidence,
+    ledger_info::LedgerInfoWithSignatures, proof::TransactionAccumulatorSummary,
+    transaction::Version,
 };
+use serde::{Deserialize, Serialize};
 use std::{
     cmp::max,
     collections::{HashMap, HashSet},
@@ -54,6 +56,9 @@ pub trait PersistentLivenessStorage: Send + Sync {
         highest_timeout_cert: &TwoChainTimeoutCertificate,
     ) -> Result<()>;
 
+    /// Persist evidence that a validator signed conflicting messages in a round.
+    fn save_equivocation_evidence(&self, evidence: &EquivocationEvidence) -> Result<()>;
+
     /// Retrieve a epoch change proof for SafetyRules so it can instantiate its
     /// ValidatorVerifier.
     fn retrieve_epoch_change_proof(&self, version: u64) -> Result<EpochChangeProof>;
@@ -65,7 +70,7 @@ pub trait PersistentLivenessStorage: Send + Sync {
     fn consensus_db(&self) -> Arc<ConsensusDB>;
 }
 
-#[derive(Clone)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct RootInfo {
     pub commit_root_block: Box<Block>,
     /// Genesis `window_root_block` will be None
@@ -297,6 +302,7 @@ impl LedgerRecoveryData {
     }
 }
 
+#[derive(Clone, Deserialize, Serialize)]
 pub struct RootMetadata {
     pub accu_hash: HashValue,
     pub frozen_root_hashes: Vec<HashValue>,
@@ -330,6 +336,7 @@ impl From<TransactionAccumulatorSummary> for RootMetadata {
 
 /// The recovery data constructed from raw consensusdb data, it'll find the root value and
 /// blocks that need cleanup or return error if the input data is inconsistent.
+#[derive(Clone, Deserialize, Serialize)]
 pub struct RecoveryData {
     // The last vote message sent by this validator.
     last_vote: Option<Vote>,
@@ -605,6 +612,10 @@ impl PersistentLivenessStorage for StorageWriteProxy {
             .save_highest_2chain_timeout_certificate(bcs::to_bytes(highest_timeout_cert)?)?)
     }
 
+    fn save_equivocation_evidence(&self, evidence: &EquivocationEvidence) -> Result<()> {
+        Ok(self.db.save_equivocation_evidence(evidence)?)
+    }
+
     fn retrieve_epoch_change_proof(&self, version: u64) -> Result<EpochChangeProof> {
         let (_, proofs) = self
             .lumio_db
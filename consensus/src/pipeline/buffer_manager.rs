@@ -22,6 +22,7 @@ use crate::{
         pipeline_phase::CountedRequest,
         signing_phase::{SigningRequest, SigningResponse},
     },
+    record_replay::{self, TraceEvent},
 };
 use lumio_bounded_executor::BoundedExecutor;
 use lumio_config::config::ConsensusObserverConfig;
@@ -611,6 +612,16 @@ impl BufferManager {
     #[allow(clippy::unwrap_used)]
     async fn process_execution_response(&mut self, response: ExecutionResponse) {
         let ExecutionResponse { block_id, inner } = response;
+        record_replay::record(|| TraceEvent::ExecutionResult {
+            block_id,
+            result: match &inner {
+                Ok(blocks) => blocks
+                    .last()
+                    .map(|block| block.block_info())
+                    .ok_or_else(|| "No block executed".to_string()),
+                Err(e) => Err(e.to_string()),
+            },
+        });
         // find the corresponding item, may not exist if a reset or aggregated happened
         let current_cursor = self.buffer.find_elem_by_key(self.execution_root, block_id);
         if current_cursor.is_none() {
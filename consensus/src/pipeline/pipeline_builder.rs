@@ -8,6 +8,7 @@ use crate::{
     monitor,
     network::NetworkSender,
     payload_manager::TPayloadManager,
+    round_timeline::{record_round_event, RoundTimelineRecorder},
     txn_notifier::TxnNotifier,
     IntGaugeGuard,
 };
@@ -24,6 +25,7 @@ use lumio_consensus_types::{
         TaskError, TaskFuture, TaskResult,
     },
     quorum_cert::QuorumCert,
+    round_timeline::RoundEvent,
     wrapped_ledger_info::WrappedLedgerInfo,
 };
 use lumio_crypto::HashValue;
@@ -137,6 +139,7 @@ pub struct PipelineBuilder {
     rand_check_enabled: bool,
     module_cache: Arc<Mutex<Option<ValidationState<CachedStateView>>>>,
     network_sender: Arc<NetworkSender>,
+    round_timeline: Option<Arc<RoundTimelineRecorder>>,
 }
 
 fn spawn_shared_fut<
@@ -278,13 +281,24 @@ impl PipelineBuilder {
             rand_check_enabled: consensus_onchain_config.rand_check_enabled(),
             module_cache,
             network_sender,
+            round_timeline: None,
         }
     }
 
+    /// Records the round events of the pipeline in the given (epoch) round timeline
+    pub fn with_round_timeline(mut self, round_timeline: Arc<RoundTimelineRecorder>) -> Self {
+        self.round_timeline = Some(round_timeline);
+        self
+    }
+
     pub fn pre_commit_status(&self) -> Arc<Mutex<PreCommitStatus>> {
         self.pre_commit_status.clone()
     }
 
+    pub fn round_timeline(&self) -> Option<Arc<RoundTimelineRecorder>> {
+        self.round_timeline.clone()
+    }
+
     fn channel(abort_handles: &mut Vec<AbortHandle>) -> (PipelineInputTx, PipelineInputRx) {
         let (qc_tx, qc_rx) = oneshot::channel();
         let (rand_tx, rand_rx) = oneshot::channel();
@@ -424,6 +438,7 @@ impl PipelineBuilder {
                 self.validators.clone(),
                 self.block_executor_onchain_config.clone(),
                 self.persisted_auxiliary_info_version,
+                self.round_timeline.clone(),
             ),
             None,
         );
@@ -434,6 +449,7 @@ impl PipelineBuilder {
                 parent.ledger_update_fut.clone(),
                 self.executor.clone(),
                 block.clone(),
+                self.round_timeline.clone(),
             ),
             None,
         );
@@ -447,6 +463,7 @@ impl PipelineBuilder {
                 block.clone(),
                 self.order_vote_enabled,
                 self.network_sender.clone(),
+                self.round_timeline.clone(),
             ),
             Some(&mut abort_handles),
         );
@@ -502,6 +519,7 @@ impl PipelineBuilder {
                 self.payload_manager.clone(),
                 block_store_callback,
                 block.clone(),
+                self.round_timeline.clone(),
             ),
             None,
         );
@@ -687,6 +705,7 @@ impl PipelineBuilder {
         validator: Arc<[AccountAddress]>,
         onchain_execution_config: BlockExecutorConfigFromOnchain,
         persisted_auxiliary_info_version: u8,
+        round_timeline: Option<Arc<RoundTimelineRecorder>>,
     ) -> TaskResult<ExecuteResult> {
         let mut tracker = Tracker::start_waiting("execute", &block);
         parent_block_execute_fut.await?;
@@ -771,6 +790,7 @@ impl PipelineBuilder {
         parent_block_ledger_update_fut: TaskFuture<LedgerUpdateResult>,
         executor: Arc<dyn BlockExecutorTrait>,
         block: Arc<Block>,
+        round_timeline: Option<Arc<RoundTimelineRecorder>>,
     ) -> TaskResult<LedgerUpdateResult> {
         let mut tracker = Tracker::start_waiting("ledger_update", &block);
         let (_, _, prev_epoch_end_timestamp) = parent_block_ledger_update_fut.await?;
@@ -787,6 +807,11 @@ impl PipelineBuilder {
         .expect("spawn blocking failed")?;
         let timestamp = block.timestamp_usecs();
         observe_block(timestamp, BlockStage::EXECUTED);
+        record_round_event(
+            round_timeline.as_deref(),
+            block.round(),
+            RoundEvent::Executed,
+        );
         let epoch_end_timestamp =
             if result.has_reconfiguration() && !result.compute_status_for_input_txns().is_empty() {
                 Some(timestamp)
@@ -875,6 +900,7 @@ impl PipelineBuilder {
         block: Arc<Block>,
         order_vote_enabled: bool,
         network_sender: Arc<NetworkSender>,
+        round_timeline: Option<Arc<RoundTimelineRecorder>>,
     ) -> TaskResult<CommitVoteResult> {
         let mut tracker = Tracker::start_waiting("sign_commit_vote", &block);
         let (compute_result, _, epoch_end_timestamp) = ledger_update_fut.await?;
@@ -913,6 +939,7 @@ impl PipelineBuilder {
         let ledger_info = LedgerInfo::new(block_info, consensus_data_hash);
         info!("[Pipeline] Signed ledger info {ledger_info}");
         let signature = signer.sign(&ledger_info).expect("Signing should succeed");
+        record_round_event(round_timeline.as_deref(), block.round(), RoundEvent::Signed);
         let commit_vote = CommitVote::new_with_signature(signer.author(), ledger_info, signature);
         network_sender
             .broadcast_commit_vote(commit_vote.clone())
@@ -1009,6 +1036,7 @@ impl PipelineBuilder {
             dyn FnOnce(WrappedLedgerInfo, LedgerInfoWithSignatures) + Send + Sync,
         >,
         block: Arc<Block>,
+        round_timeline: Option<Arc<RoundTimelineRecorder>>,
     ) -> TaskResult<PostCommitResult> {
         let mut tracker = Tracker::start_waiting("post_commit_ledger", &block);
         parent_post_commit.await?;
@@ -1019,6 +1047,11 @@ impl PipelineBuilder {
         tracker.start_working();
         update_counters_for_block(&block);
         update_counters_for_compute_result(&compute_result);
+        record_round_event(
+            round_timeline.as_deref(),
+            block.round(),
+            RoundEvent::Committed,
+        );
 
         let payload = block.payload().cloned();
         let timestamp = block.timestamp_usecs();
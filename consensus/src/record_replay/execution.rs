@@ -0,0 +1,685 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::{MempoolError, StateSyncError},
+    network::{IncomingCommitRequest, IncomingRandGenRequest},
+    payload_manager::TPayloadManager,
+    pipeline::{
+        buffer_manager::OrderedBlocks,
+        execution_client::{ExecutionProxyClient, TExecutionClient},
+        pipeline_builder::PipelineBuilder,
+        signing_phase::CommitSignerProvider,
+    },
+    rand::rand_gen::types::RandConfig,
+    record_replay::{TraceEvent, TraceRecord},
+    txn_notifier::TxnNotifier,
+};
+use anyhow::{bail, ensure};
+use lumio_bitvec::BitVec;
+use lumio_channels::lumio_channel;
+use lumio_config::config::BlockTransactionFilterConfig;
+use lumio_consensus_notifications::{ConsensusNotificationSender, Error as NotificationError};
+use lumio_consensus_types::{
+    block::Block,
+    common::{Author, Payload, Round},
+    pipelined_block::PipelinedBlock,
+    wrapped_ledger_info::WrappedLedgerInfo,
+};
+use lumio_crypto::{bls12381::PrivateKey, HashValue};
+use lumio_executor_types::{BlockExecutorTrait, ExecutorError, ExecutorResult, StateComputeResult};
+use lumio_infallible::Mutex;
+use lumio_storage_interface::state_store::state_view::cached_state_view::CachedStateView;
+use lumio_types::{
+    block_executor::{config::BlockExecutorConfigFromOnchain, partitioner::ExecutableBlock},
+    block_info::BlockInfo,
+    contract_event::ContractEvent,
+    epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::{OnChainConsensusConfig, OnChainExecutionConfig, OnChainRandomnessConfig},
+    transaction::{ExecutionStatus, SignedTransaction, Transaction, TransactionStatus},
+    validator_signer::ValidatorSigner,
+};
+use futures::channel::mpsc::UnboundedSender;
+use move_core_types::account_address::AccountAddress;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Condvar, Mutex as StdMutex, MutexGuard},
+    time::Duration,
+};
+
+struct ExecutedBlock {
+    parent_id: HashValue,
+    /// The timestamp of the block metadata transaction.
+    timestamp_usecs: Option<u64>,
+}
+
+#[derive(Default)]
+struct ExecutorState {
+    /// Blocks the pipeline of the replayed node executed.
+    executed: HashMap<HashValue, ExecutedBlock>,
+    /// Recorded execution results released by the replay so far, by the id of the last block
+    /// executed along with them.
+    results: HashMap<HashValue, BlockInfo>,
+    /// Bumped on every reset, failing the ledger updates waiting across it.
+    resets: u64,
+    /// Set once no more results will be released.
+    finished: bool,
+}
+
+/// The block executor of the replayed node. Blocks aren't executed: the ledger update of a block
+/// waits until the replay reaches the execution result the recorded node got for it, and returns
+/// a compute result matching it. The buffer manager executes ordered blocks together and records
+/// the result of the last one, which then resolves the blocks executed along with it too.
+pub(super) struct ReplayBlockExecutor {
+    committed_block_id: HashValue,
+    state: StdMutex<ExecutorState>,
+    results_released: Condvar,
+}
+
+impl ReplayBlockExecutor {
+    pub fn new(committed_block_id: HashValue) -> Self {
+        Self {
+            committed_block_id,
+            state: StdMutex::new(ExecutorState::default()),
+            results_released: Condvar::new(),
+        }
+    }
+
+    /// Releases the recorded execution result of the blocks ending with `block_id` to the
+    /// pipeline.
+    pub fn release_result(
+        &self,
+        block_id: HashValue,
+        result: &Result<BlockInfo, String>,
+    ) -> anyhow::Result<()> {
+        let mut state = self.lock();
+        ensure!(
+            state.executed.contains_key(&block_id),
+            "Block {} was executed by the recorded node, but not by the replayed one",
+            block_id
+        );
+        // The buffer manager retries failed executions, which show up as another record.
+        let Ok(block_info) = result else {
+            return Ok(());
+        };
+        ensure!(
+            block_info.id() == block_id,
+            "Recorded execution result {} is for another block than {}",
+            block_info,
+            block_id
+        );
+        state.results.insert(block_id, block_info.clone());
+        self.results_released.notify_all();
+        Ok(())
+    }
+
+    /// Fails the ledger updates still waiting for a result, once the trace is fully replayed.
+    pub fn finish_replay(&self) {
+        self.lock().finished = true;
+        self.results_released.notify_all();
+    }
+
+    /// Fails the ledger updates waiting for a result, as the blocks they're for are dropped.
+    pub fn abort_waiting(&self) {
+        self.lock().resets += 1;
+        self.results_released.notify_all();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ExecutorState> {
+        self.state.lock().expect("Replay executor lock poisoned")
+    }
+
+    /// The recorded result for `block_id`, or else for the lowest descendant executed along with
+    /// it.
+    fn find_result(state: &ExecutorState, block_id: HashValue) -> Option<&BlockInfo> {
+        if let Some(block_info) = state.results.get(&block_id) {
+            return Some(block_info);
+        }
+        state
+            .results
+            .iter()
+            .filter(|(id, _)| Self::descends_from(state, **id, block_id))
+            .map(|(_, block_info)| block_info)
+            .min_by_key(|block_info| block_info.round())
+    }
+
+    fn descends_from(state: &ExecutorState, mut block_id: HashValue, ancestor: HashValue) -> bool {
+        while let Some(block) = state.executed.get(&block_id) {
+            if block.parent_id == ancestor {
+                return true;
+            }
+            block_id = block.parent_id;
+        }
+        false
+    }
+
+    fn block_timestamp(block: &ExecutableBlock) -> Option<u64> {
+        match block.transactions.txns().first()?.borrow_into_inner() {
+            Transaction::BlockMetadata(metadata) => Some(metadata.timestamp_usecs()),
+            Transaction::BlockMetadataExt(metadata) => Some(metadata.timestamp_usecs()),
+            _ => None,
+        }
+    }
+}
+
+impl BlockExecutorTrait for ReplayBlockExecutor {
+    fn committed_block_id(&self) -> HashValue {
+        self.committed_block_id
+    }
+
+    fn reset(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn execute_and_update_state(
+        &self,
+        block: ExecutableBlock,
+        parent_block_id: HashValue,
+        _onchain_config: BlockExecutorConfigFromOnchain,
+    ) -> ExecutorResult<()> {
+        self.lock().executed.insert(block.block_id, ExecutedBlock {
+            parent_id: parent_block_id,
+            timestamp_usecs: Self::block_timestamp(&block),
+        });
+        Ok(())
+    }
+
+    fn ledger_update(
+        &self,
+        block_id: HashValue,
+        _parent_block_id: HashValue,
+    ) -> ExecutorResult<StateComputeResult> {
+        let mut state = self.lock();
+        let resets = state.resets;
+        loop {
+            if let Some(block_info) = Self::find_result(&state, block_id) {
+                // The block ending the epoch executes its transactions, while the reconfiguration
+                // suffix following it takes its timestamp without executing anything.
+                let ends_epoch = block_info.has_reconfiguration()
+                    && state
+                        .executed
+                        .get(&block_id)
+                        .and_then(|block| block.timestamp_usecs)
+                        == Some(block_info.timestamp_usecs());
+                let statuses = if ends_epoch {
+                    vec![TransactionStatus::Keep(ExecutionStatus::Success)]
+                } else {
+                    vec![]
+                };
+                return Ok(StateComputeResult::new_dummy_with_block_info(
+                    block_info, statuses,
+                ));
+            }
+            if state.finished || state.resets != resets {
+                return Err(ExecutorError::InternalError {
+                    error: format!("No recorded execution result for block {}", block_id),
+                });
+            }
+            state = self
+                .results_released
+                .wait(state)
+                .expect("Replay executor lock poisoned");
+        }
+    }
+
+    fn pre_commit_block(&self, _block_id: HashValue) -> ExecutorResult<()> {
+        Ok(())
+    }
+
+    fn commit_ledger(
+        &self,
+        _ledger_info_with_sigs: LedgerInfoWithSignatures,
+    ) -> ExecutorResult<()> {
+        Ok(())
+    }
+
+    fn finish(&self) {}
+
+    fn state_view(&self, block_id: HashValue) -> ExecutorResult<CachedStateView> {
+        Err(ExecutorError::InternalError {
+            error: format!("No state to view for block {} in a replay", block_id),
+        })
+    }
+}
+
+#[derive(Default)]
+struct StateSyncState {
+    sync_for_duration_results: VecDeque<Result<LedgerInfoWithSignatures, String>>,
+    sync_to_target_results: VecDeque<Result<(), String>>,
+    /// Syncs requested by the replayed node, not matched with a recorded one yet.
+    requested_syncs_for_duration: VecDeque<Duration>,
+    requested_syncs_to_target: VecDeque<LedgerInfoWithSignatures>,
+}
+
+/// Stands in for state sync. Syncs requested by the replayed node get the recorded results, in
+/// order, and are checked against the recorded ones as the replay reaches them.
+pub(super) struct ReplayStateSync {
+    state: Mutex<StateSyncState>,
+}
+
+impl ReplayStateSync {
+    pub fn new(records: &[TraceRecord]) -> Self {
+        let mut state = StateSyncState::default();
+        for record in records {
+            match &record.event {
+                TraceEvent::SyncForDuration { result, .. } => {
+                    state.sync_for_duration_results.push_back(result.clone())
+                },
+                TraceEvent::SyncToTarget { result, .. } => {
+                    state.sync_to_target_results.push_back(result.clone())
+                },
+                _ => (),
+            }
+        }
+        Self {
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Checks the replayed node requested the sync the recorded one did.
+    pub fn process_sync_for_duration(&self, duration_ms: u64) -> anyhow::Result<()> {
+        let requested = self.state.lock().requested_syncs_for_duration.pop_front();
+        match requested {
+            Some(duration) => {
+                ensure!(
+                    duration.as_millis() == duration_ms as u128,
+                    "Recorded node synced for {}ms, replayed node for {}ms",
+                    duration_ms,
+                    duration.as_millis()
+                );
+                Ok(())
+            },
+            None => bail!(
+                "Recorded node synced for {}ms, replayed node didn't",
+                duration_ms
+            ),
+        }
+    }
+
+    /// Checks the replayed node requested the sync the recorded one did.
+    pub fn process_sync_to_target(&self, target: &LedgerInfoWithSignatures) -> anyhow::Result<()> {
+        let requested = self.state.lock().requested_syncs_to_target.pop_front();
+        match requested {
+            Some(requested) => {
+                ensure!(
+                    requested.commit_info() == target.commit_info(),
+                    "Recorded node synced to {}, replayed node to {}",
+                    target.commit_info(),
+                    requested.commit_info()
+                );
+                Ok(())
+            },
+            None => bail!(
+                "Recorded node synced to {}, replayed node didn't",
+                target.commit_info()
+            ),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ConsensusNotificationSender for ReplayStateSync {
+    async fn notify_new_commit(
+        &self,
+        _transactions: Vec<Transaction>,
+        _subscribable_events: Vec<ContractEvent>,
+    ) -> Result<(), NotificationError> {
+        Ok(())
+    }
+
+    async fn sync_for_duration(
+        &self,
+        duration: Duration,
+    ) -> Result<LedgerInfoWithSignatures, NotificationError> {
+        let mut state = self.state.lock();
+        state.requested_syncs_for_duration.push_back(duration);
+        match state.sync_for_duration_results.pop_front() {
+            Some(result) => result.map_err(NotificationError::UnexpectedErrorEncountered),
+            None => Err(NotificationError::UnexpectedErrorEncountered(
+                "No recorded sync_for_duration() result left".into(),
+            )),
+        }
+    }
+
+    async fn sync_to_target(
+        &self,
+        target: LedgerInfoWithSignatures,
+    ) -> Result<(), NotificationError> {
+        let mut state = self.state.lock();
+        state.requested_syncs_to_target.push_back(target);
+        match state.sync_to_target_results.pop_front() {
+            Some(result) => result.map_err(NotificationError::UnexpectedErrorEncountered),
+            None => Err(NotificationError::UnexpectedErrorEncountered(
+                "No recorded sync_to_target() result left".into(),
+            )),
+        }
+    }
+}
+
+/// Blocks aren't executed in a replay, so there are never failed transactions to notify.
+pub(super) struct ReplayTxnNotifier;
+
+#[async_trait::async_trait]
+impl TxnNotifier for ReplayTxnNotifier {
+    async fn notify_failed_txn(
+        &self,
+        _txns: &[SignedTransaction],
+        _statuses: &[TransactionStatus],
+    ) -> Result<(), MempoolError> {
+        Ok(())
+    }
+}
+
+/// Resolves every payload to no transactions, as the execution results come from the trace. This
+/// also spares fetching the quorum store batches the recorded node had.
+pub(super) struct ReplayPayloadManager;
+
+#[async_trait::async_trait]
+impl TPayloadManager for ReplayPayloadManager {
+    fn notify_commit(&self, _block_timestamp: u64, _payloads: Vec<Payload>) {}
+
+    fn prefetch_payload_data(&self, _payload: &Payload, _author: Author, _timestamp: u64) {}
+
+    fn check_denied_inline_transactions(
+        &self,
+        _block: &Block,
+        _block_txn_filter_config: &BlockTransactionFilterConfig,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn check_payload_availability(&self, _block: &Block) -> Result<(), BitVec> {
+        Ok(())
+    }
+
+    async fn get_transactions(
+        &self,
+        _block: &Block,
+        _block_voters: Option<BitVec>,
+    ) -> ExecutorResult<(Vec<SignedTransaction>, Option<u64>, Option<u64>)> {
+        Ok((vec![], None, None))
+    }
+}
+
+/// The execution client of the replayed node, running the real buffer manager and execution
+/// pipeline. Resets first fail the ledger updates waiting for a recorded result: the buffer
+/// manager waits for the pipelines of the blocks it drops, while the results they wait for may
+/// only come later in the trace.
+pub(super) struct ReplayExecutionClient {
+    inner: ExecutionProxyClient,
+    executor: Arc<ReplayBlockExecutor>,
+}
+
+impl ReplayExecutionClient {
+    pub fn new(inner: ExecutionProxyClient, executor: Arc<ReplayBlockExecutor>) -> Self {
+        Self { inner, executor }
+    }
+}
+
+#[async_trait::async_trait]
+impl TExecutionClient for ReplayExecutionClient {
+    async fn start_epoch(
+        &self,
+        maybe_consensus_key: Arc<PrivateKey>,
+        epoch_state: Arc<EpochState>,
+        commit_signer_provider: Arc<dyn CommitSignerProvider>,
+        payload_manager: Arc<dyn TPayloadManager>,
+        onchain_consensus_config: &OnChainConsensusConfig,
+        onchain_execution_config: &OnChainExecutionConfig,
+        onchain_randomness_config: &OnChainRandomnessConfig,
+        rand_config: Option<RandConfig>,
+        fast_rand_config: Option<RandConfig>,
+        rand_msg_rx: lumio_channel::Receiver<AccountAddress, IncomingRandGenRequest>,
+        highest_committed_round: Round,
+    ) {
+        self.inner
+            .start_epoch(
+                maybe_consensus_key,
+                epoch_state,
+                commit_signer_provider,
+                payload_manager,
+                onchain_consensus_config,
+                onchain_execution_config,
+                onchain_randomness_config,
+                rand_config,
+                fast_rand_config,
+                rand_msg_rx,
+                highest_committed_round,
+            )
+            .await
+    }
+
+    fn get_execution_channel(&self) -> Option<UnboundedSender<OrderedBlocks>> {
+        self.inner.get_execution_channel()
+    }
+
+    async fn finalize_order(
+        &self,
+        blocks: Vec<Arc<PipelinedBlock>>,
+        ordered_proof: WrappedLedgerInfo,
+    ) -> ExecutorResult<()> {
+        self.inner.finalize_order(blocks, ordered_proof).await
+    }
+
+    fn send_commit_msg(
+        &self,
+        peer_id: AccountAddress,
+        commit_msg: IncomingCommitRequest,
+    ) -> anyhow::Result<()> {
+        self.inner.send_commit_msg(peer_id, commit_msg)
+    }
+
+    async fn sync_for_duration(
+        &self,
+        duration: Duration,
+    ) -> Result<LedgerInfoWithSignatures, StateSyncError> {
+        self.executor.abort_waiting();
+        self.inner.sync_for_duration(duration).await
+    }
+
+    async fn sync_to_target(&self, target: LedgerInfoWithSignatures) -> Result<(), StateSyncError> {
+        self.executor.abort_waiting();
+        self.inner.sync_to_target(target).await
+    }
+
+    async fn reset(&self, target: &LedgerInfoWithSignatures) -> anyhow::Result<()> {
+        self.executor.abort_waiting();
+        self.inner.reset(target).await
+    }
+
+    async fn end_epoch(&self) {
+        self.executor.abort_waiting();
+        self.inner.end_epoch().await
+    }
+
+    fn pipeline_builder(&self, signer: Arc<ValidatorSigner>) -> PipelineBuilder {
+        self.inner.pipeline_builder(signer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumio_types::{
+        aggregate_signature::AggregateSignature, block_metadata::BlockMetadata,
+        ledger_info::LedgerInfo,
+        transaction::signature_verified_transaction::into_signature_verified_block,
+        validator_verifier::ValidatorVerifier,
+    };
+    use std::thread;
+
+    /// Executes a block at `round` with the given parent, returning the block info of a recorded
+    /// result for it.
+    fn execute(
+        executor: &ReplayBlockExecutor,
+        round: Round,
+        parent_id: HashValue,
+        timestamp_usecs: u64,
+        next_epoch_state: Option<EpochState>,
+    ) -> BlockInfo {
+        let block_id = HashValue::random();
+        let metadata = BlockMetadata::new(
+            block_id,
+            1,
+            round,
+            Author::ZERO,
+            vec![],
+            vec![],
+            timestamp_usecs,
+        );
+        executor
+            .execute_and_update_state(
+                (
+                    block_id,
+                    into_signature_verified_block(vec![Transaction::BlockMetadata(metadata)]),
+                )
+                    .into(),
+                parent_id,
+                BlockExecutorConfigFromOnchain::new_no_block_limit(),
+            )
+            .unwrap();
+        BlockInfo::new(
+            1,
+            round,
+            block_id,
+            HashValue::random(),
+            round * 10,
+            timestamp_usecs,
+            next_epoch_state,
+        )
+    }
+
+    #[test]
+    fn test_ledger_update_waits_for_recorded_result() {
+        let executor = Arc::new(ReplayBlockExecutor::new(HashValue::zero()));
+        let first = execute(&executor, 1, HashValue::zero(), 1, None);
+        let second = execute(&executor, 2, first.id(), 2, None);
+
+        let waiting = {
+            let executor = executor.clone();
+            let first_id = first.id();
+            thread::spawn(move || executor.ledger_update(first_id, HashValue::zero()))
+        };
+        // Failed executions are retried, and results are only recorded for the last block
+        // executed together.
+        executor
+            .release_result(second.id(), &Err("retry".into()))
+            .unwrap();
+        executor
+            .release_result(second.id(), &Ok(second.clone()))
+            .unwrap();
+
+        let results = [
+            waiting.join().unwrap().unwrap(),
+            executor.ledger_update(second.id(), first.id()).unwrap(),
+        ];
+        for result in results {
+            assert_eq!(result.root_hash(), second.executed_state_id());
+            assert_eq!(result.last_version_or_0(), second.version());
+            assert!(!result.has_reconfiguration());
+        }
+    }
+
+    #[test]
+    fn test_unknown_and_mismatched_results() {
+        let executor = ReplayBlockExecutor::new(HashValue::zero());
+        let executed = execute(&executor, 1, HashValue::zero(), 1, None);
+        let other = BlockInfo::new(1, 2, HashValue::random(), HashValue::zero(), 1, 2, None);
+
+        assert!(executor
+            .release_result(other.id(), &Err("not executed".into()))
+            .is_err());
+        assert!(executor.release_result(executed.id(), &Ok(other)).is_err());
+    }
+
+    #[test]
+    fn test_waiting_ledger_updates_fail_on_reset_and_end() {
+        let executor = Arc::new(ReplayBlockExecutor::new(HashValue::zero()));
+        let executed = execute(&executor, 1, HashValue::zero(), 1, None);
+        let waiting = {
+            let executor = executor.clone();
+            let block_id = executed.id();
+            thread::spawn(move || executor.ledger_update(block_id, HashValue::zero()))
+        };
+        thread::sleep(Duration::from_millis(100));
+        executor.abort_waiting();
+        assert!(waiting.join().unwrap().is_err());
+
+        executor.finish_replay();
+        assert!(executor
+            .ledger_update(executed.id(), HashValue::zero())
+            .is_err());
+    }
+
+    #[test]
+    fn test_reconfiguration_suffix_executes_nothing() {
+        let executor = ReplayBlockExecutor::new(HashValue::zero());
+        let next_epoch_state = EpochState::new(2, ValidatorVerifier::new(vec![]));
+        let ends_epoch = execute(
+            &executor,
+            1,
+            HashValue::zero(),
+            1,
+            Some(next_epoch_state.clone()),
+        );
+        // The suffix is recorded with the timestamp of the block ending the epoch.
+        let suffix = execute(&executor, 2, ends_epoch.id(), 2, None);
+        let suffix = BlockInfo::new(
+            1,
+            2,
+            suffix.id(),
+            ends_epoch.executed_state_id(),
+            ends_epoch.version(),
+            ends_epoch.timestamp_usecs(),
+            Some(next_epoch_state),
+        );
+        for block_info in [&ends_epoch, &suffix] {
+            executor
+                .release_result(block_info.id(), &Ok(block_info.clone()))
+                .unwrap();
+        }
+
+        let result = executor
+            .ledger_update(ends_epoch.id(), HashValue::zero())
+            .unwrap();
+        assert!(result.has_reconfiguration());
+        assert!(!result.compute_status_for_input_txns().is_empty());
+        let result = executor
+            .ledger_update(suffix.id(), ends_epoch.id())
+            .unwrap();
+        assert!(result.has_reconfiguration());
+        assert!(result.compute_status_for_input_txns().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sync_results_replayed_in_order() {
+        let target =
+            LedgerInfoWithSignatures::new(LedgerInfo::dummy(), AggregateSignature::empty());
+        let records = vec![
+            TraceRecord {
+                timestamp_usecs: 1,
+                event: TraceEvent::SyncToTarget {
+                    target: target.clone(),
+                    result: Err("failed".into()),
+                },
+            },
+            TraceRecord {
+                timestamp_usecs: 2,
+                event: TraceEvent::SyncToTarget {
+                    target: target.clone(),
+                    result: Ok(()),
+                },
+            },
+        ];
+        let state_sync = ReplayStateSync::new(&records);
+
+        // The recorded sync must have been requested by the replayed node.
+        assert!(state_sync.process_sync_to_target(&target).is_err());
+        assert!(state_sync.sync_to_target(target.clone()).await.is_err());
+        assert!(state_sync.sync_to_target(target.clone()).await.is_ok());
+        assert!(state_sync.sync_to_target(target.clone()).await.is_err());
+        assert!(state_sync.process_sync_to_target(&target).is_ok());
+        assert!(state_sync.process_sync_for_duration(1_000).is_err());
+    }
+}
@@ -0,0 +1,228 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Recording of consensus traces, and their offline replay.
+//!
+//! When `ConsensusConfig::trace_recording_dir` is set, the node writes one trace file per epoch:
+//! the state consensus started the epoch from, followed by every consensus message sent and
+//! received, every local round timeout and every state computer result, in the order they
+//! happened. [`replay::TraceReplayer`] feeds such a trace back through a `RoundManager` and the
+//! execution pipeline with a simulated clock, and reports where the replayed node starts sending
+//! different messages than the recorded one. Operators replay traces with
+//! `lumio-debugger replay-consensus-trace`.
+//!
+//! A trace file is a sequence of BCS serialized [`TraceRecord`]s, each prefixed with its length
+//! as a little endian u32. The first record is always a [`TraceEvent::EpochStart`].
+
+#[cfg(any(test, feature = "fuzzing"))]
+mod execution;
+mod recorder;
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod replay;
+
+use crate::{network_interface::ConsensusMsg, persistent_liveness_storage::RecoveryData};
+use anyhow::{bail, ensure, Context};
+use lumio_config::config::ConsensusConfig;
+use lumio_consensus_types::common::{Author, Round};
+use lumio_crypto::HashValue;
+use lumio_infallible::duration_since_epoch;
+use lumio_types::{
+    block_info::BlockInfo,
+    epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::{
+        Features, OnChainConsensusConfig, OnChainJWKConsensusConfig, OnChainRandomnessConfig,
+    },
+};
+use once_cell::sync::OnceCell;
+pub use recorder::TraceRecorder;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{BufReader, ErrorKind, Read},
+    path::Path,
+};
+
+static RECORDER: OnceCell<TraceRecorder> = OnceCell::new();
+
+/// Everything needed to rebuild the `RoundManager` of an epoch as the recording node started it.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct EpochStart {
+    pub author: Author,
+    pub epoch_state: EpochState,
+    pub recovery_data: RecoveryData,
+    pub onchain_consensus_config: OnChainConsensusConfig,
+    pub onchain_randomness_config: OnChainRandomnessConfig,
+    pub onchain_jwk_consensus_config: OnChainJWKConsensusConfig,
+    pub features: Features,
+    /// The local `ConsensusConfig` as JSON, as BCS doesn't support its floats.
+    pub local_config_json: String,
+}
+
+impl EpochStart {
+    pub fn local_config(&self) -> anyhow::Result<ConsensusConfig> {
+        Ok(serde_json::from_str(&self.local_config_json)?)
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub enum TraceEvent {
+    EpochStart(Box<EpochStart>),
+    /// A direct send message or rpc request received, including the ones sent to self.
+    Inbound {
+        from: Author,
+        msg: ConsensusMsg,
+    },
+    /// A direct send message sent, `to` is `None` for a broadcast to all other validators.
+    Outbound {
+        to: Option<Vec<Author>>,
+        msg: ConsensusMsg,
+    },
+    /// An rpc request sent, and the response to it.
+    Rpc {
+        to: Author,
+        request: ConsensusMsg,
+        response: Result<ConsensusMsg, String>,
+    },
+    LocalTimeout(Round),
+    /// Blocks executed for the buffer manager, with the block info of the last one.
+    ExecutionResult {
+        block_id: HashValue,
+        result: Result<BlockInfo, String>,
+    },
+    SyncForDuration {
+        duration_ms: u64,
+        result: Result<LedgerInfoWithSignatures, String>,
+    },
+    SyncToTarget {
+        target: LedgerInfoWithSignatures,
+        result: Result<(), String>,
+    },
+    /// The recorder fell behind and dropped records, so the trace can't be replayed.
+    Truncated {
+        num_dropped_records: u64,
+    },
+}
+
+impl TraceEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::EpochStart(_) => "epoch_start",
+            Self::Inbound { .. } => "inbound",
+            Self::Outbound { .. } => "outbound",
+            Self::Rpc { .. } => "rpc",
+            Self::LocalTimeout(_) => "local_timeout",
+            Self::ExecutionResult { .. } => "execution_result",
+            Self::SyncForDuration { .. } => "sync_for_duration",
+            Self::SyncToTarget { .. } => "sync_to_target",
+            Self::Truncated { .. } => "truncated",
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct TraceRecord {
+    /// Wall clock time of the recording node, in microseconds since the unix epoch.
+    pub timestamp_usecs: u64,
+    pub event: TraceEvent,
+}
+
+/// A trace of one epoch, as read back from a trace file.
+#[derive(Clone)]
+pub struct Trace {
+    pub epoch_start: EpochStart,
+    /// Time the epoch started at, in microseconds since the unix epoch.
+    pub start_timestamp_usecs: u64,
+    /// All records following the epoch start.
+    pub records: Vec<TraceRecord>,
+}
+
+impl Trace {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+        let mut reader = BufReader::new(file);
+        let mut records = vec![];
+        loop {
+            let mut len_bytes = [0u8; 4];
+            match reader.read_exact(&mut len_bytes) {
+                Ok(()) => (),
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e.into()),
+            }
+            let mut bytes = vec![0u8; u32::from_le_bytes(len_bytes) as usize];
+            // A record cut short means the node stopped while writing it, ignore it.
+            if let Err(e) = reader.read_exact(&mut bytes) {
+                if e.kind() == ErrorKind::UnexpectedEof {
+                    break;
+                }
+                return Err(e.into());
+            }
+            records.push(
+                bcs::from_bytes::<TraceRecord>(&bytes)
+                    .with_context(|| format!("Bad record #{} in {:?}", records.len(), path))?,
+            );
+        }
+
+        ensure!(!records.is_empty(), "Empty trace file {:?}", path);
+        if let Some(num_dropped_records) = records.iter().find_map(|record| match record.event {
+            TraceEvent::Truncated {
+                num_dropped_records,
+            } => Some(num_dropped_records),
+            _ => None,
+        }) {
+            bail!(
+                "Trace file {:?} is truncated, the recording node dropped {} records",
+                path,
+                num_dropped_records
+            );
+        }
+        let first = records.remove(0);
+        match first.event {
+            TraceEvent::EpochStart(epoch_start) => Ok(Self {
+                epoch_start: *epoch_start,
+                start_timestamp_usecs: first.timestamp_usecs,
+                records,
+            }),
+            event => bail!(
+                "Trace file {:?} starts with {} instead of epoch_start",
+                path,
+                event.name()
+            ),
+        }
+    }
+}
+
+/// Starts recording traces to `dir`, for the rest of the process lifetime.
+pub fn enable_recording(dir: &Path) -> anyhow::Result<()> {
+    let recorder = TraceRecorder::new(dir)?;
+    ensure!(
+        RECORDER.set(recorder).is_ok(),
+        "Consensus trace recording is already enabled"
+    );
+    Ok(())
+}
+
+pub(crate) fn is_recording() -> bool {
+    RECORDER.get().is_some()
+}
+
+/// Starts a new trace file for the epoch, if recording is enabled.
+pub(crate) fn record_epoch_start(epoch_start: impl FnOnce() -> EpochStart) {
+    if let Some(recorder) = RECORDER.get() {
+        recorder.start_epoch(TraceRecord {
+            timestamp_usecs: duration_since_epoch().as_micros() as u64,
+            event: TraceEvent::EpochStart(Box::new(epoch_start())),
+        });
+    }
+}
+
+/// Records an event to the trace of the current epoch, if recording is enabled. The event is only
+/// built when recording, so that messages aren't cloned otherwise.
+pub(crate) fn record(event: impl FnOnce() -> TraceEvent) {
+    if let Some(recorder) = RECORDER.get() {
+        recorder.record(TraceRecord {
+            timestamp_usecs: duration_since_epoch().as_micros() as u64,
+            event: event(),
+        });
+    }
+}
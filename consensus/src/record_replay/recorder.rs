@@ -0,0 +1,263 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::record_replay::{TraceEvent, TraceRecord};
+use anyhow::Context;
+use lumio_infallible::duration_since_epoch;
+use lumio_logger::prelude::*;
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// Records queued for the writer thread before new ones get dropped.
+const MAX_PENDING_RECORDS: usize = 10_000;
+
+enum WriterCommand {
+    StartEpoch { path: PathBuf, record: TraceRecord },
+    Record(TraceRecord),
+}
+
+/// Writes trace records to one file per epoch. The files are written by a dedicated thread, so
+/// recording never blocks consensus on disk IO. Failing to write never affects consensus either:
+/// the error is logged, and recording stops until the next epoch. If the writer falls behind,
+/// new records are dropped, and the trace is marked as truncated for replay to refuse it.
+pub struct TraceRecorder {
+    dir: PathBuf,
+    sender: Option<SyncSender<WriterCommand>>,
+    writer: Option<JoinHandle<()>>,
+    /// Records dropped since the writer last wrote a truncation marker.
+    dropped: Arc<AtomicU64>,
+}
+
+impl TraceRecorder {
+    pub fn new(dir: &Path) -> anyhow::Result<Self> {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+        let (sender, receiver) = mpsc::sync_channel(MAX_PENDING_RECORDS);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let writer_dropped = dropped.clone();
+        let writer = thread::Builder::new()
+            .name("consensus-trace".into())
+            .spawn(move || Self::write_loop(receiver, &writer_dropped))
+            .context("Failed to spawn the consensus trace writer")?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            sender: Some(sender),
+            writer: Some(writer),
+            dropped,
+        })
+    }
+
+    /// Closes the trace of the previous epoch, and starts a new one with `epoch_start`.
+    pub fn start_epoch(&self, epoch_start: TraceRecord) {
+        let epoch = match &epoch_start.event {
+            TraceEvent::EpochStart(start) => start.epoch_state.epoch,
+            event => {
+                error!("Trace must start with epoch_start, got {}.", event.name());
+                return;
+            },
+        };
+        // The start time tells apart the traces of an epoch recorded across restarts.
+        let path = self.dir.join(format!(
+            "consensus_epoch_{}_{}.trace",
+            epoch, epoch_start.timestamp_usecs
+        ));
+        self.send(WriterCommand::StartEpoch {
+            path,
+            record: epoch_start,
+        });
+    }
+
+    pub fn record(&self, record: TraceRecord) {
+        self.send(WriterCommand::Record(record));
+    }
+
+    fn send(&self, command: WriterCommand) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        match sender.try_send(command) {
+            Ok(()) => (),
+            Err(TrySendError::Full(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(10)),
+                    warn!("Consensus trace writer is falling behind, dropping records.")
+                );
+            },
+            Err(TrySendError::Disconnected(_)) => {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(10)),
+                    error!("Consensus trace writer is gone, dropping records.")
+                );
+            },
+        }
+    }
+
+    fn write_loop(receiver: Receiver<WriterCommand>, dropped: &AtomicU64) {
+        let mut file = None;
+        while let Ok(command) = receiver.recv() {
+            // The records queued before the dropped ones may still be written after the marker,
+            // it only needs to make it into the trace the records were dropped from.
+            let num_dropped = dropped.swap(0, Ordering::Relaxed);
+            if num_dropped > 0 {
+                Self::write(&mut file, &TraceRecord {
+                    timestamp_usecs: duration_since_epoch().as_micros() as u64,
+                    event: TraceEvent::Truncated {
+                        num_dropped_records: num_dropped,
+                    },
+                });
+            }
+            match command {
+                WriterCommand::StartEpoch { path, record } => {
+                    file = match File::create(&path) {
+                        Ok(f) => {
+                            info!(path = ?path, "Recording consensus trace.");
+                            Some(BufWriter::new(f))
+                        },
+                        Err(e) => {
+                            error!(
+                                path = ?path,
+                                error = ?e,
+                                "Failed to create consensus trace file."
+                            );
+                            None
+                        },
+                    };
+                    Self::write(&mut file, &record);
+                },
+                WriterCommand::Record(record) => Self::write(&mut file, &record),
+            }
+        }
+    }
+
+    fn write(file: &mut Option<BufWriter<File>>, record: &TraceRecord) {
+        let Some(writer) = file.as_mut() else {
+            return;
+        };
+        let result = bcs::to_bytes(record)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| {
+                writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+                writer.write_all(&bytes)?;
+                // Flushed right away, so the trace is complete even if the node gets stuck.
+                writer.flush()?;
+                Ok(())
+            });
+        if let Err(e) = result {
+            error!(
+                error = ?e,
+                event = record.event.name(),
+                "Failed to record consensus trace, stopping until the next epoch."
+            );
+            *file = None;
+        }
+    }
+}
+
+impl Drop for TraceRecorder {
+    /// Writes out the pending records before returning.
+    fn drop(&mut self) {
+        self.sender = None;
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        record_replay::{EpochStart, Trace},
+        test_utils::MockStorage,
+    };
+    use lumio_config::config::ConsensusConfig;
+    use lumio_types::{
+        epoch_state::EpochState,
+        on_chain_config::{
+            Features, OnChainConsensusConfig, OnChainJWKConsensusConfig, OnChainRandomnessConfig,
+        },
+        validator_signer::ValidatorSigner,
+        validator_verifier::ValidatorVerifier,
+    };
+    use std::fs::OpenOptions;
+
+    fn epoch_start() -> EpochStart {
+        let signer = ValidatorSigner::from_int(1);
+        let verifier = ValidatorVerifier::new_single(signer.author(), signer.public_key());
+        let (recovery_data, storage) = MockStorage::start_for_testing((&verifier).into());
+        EpochStart {
+            author: signer.author(),
+            epoch_state: EpochState::new(1, storage.get_validator_set().into()),
+            recovery_data,
+            onchain_consensus_config: OnChainConsensusConfig::default(),
+            onchain_randomness_config: OnChainRandomnessConfig::default_enabled(),
+            onchain_jwk_consensus_config: OnChainJWKConsensusConfig::default_enabled(),
+            features: Features::default(),
+            local_config_json: serde_json::to_string(&ConsensusConfig::default()).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_trace_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = TraceRecorder::new(dir.path()).unwrap();
+        recorder.start_epoch(TraceRecord {
+            timestamp_usecs: 1,
+            event: TraceEvent::EpochStart(Box::new(epoch_start())),
+        });
+        for round in 1..=3 {
+            recorder.record(TraceRecord {
+                timestamp_usecs: round + 1,
+                event: TraceEvent::LocalTimeout(round),
+            });
+        }
+
+        // Dropping the recorder waits for the writer to catch up.
+        drop(recorder);
+
+        let path = dir.path().join("consensus_epoch_1_1.trace");
+        let trace = Trace::load(&path).unwrap();
+        assert_eq!(trace.start_timestamp_usecs, 1);
+        assert_eq!(trace.epoch_start.epoch_state.epoch, 1);
+        assert!(trace.epoch_start.local_config().is_ok());
+        assert_eq!(trace.records.len(), 3);
+
+        // A record cut short by a crash is dropped.
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&100u32.to_le_bytes()).unwrap();
+        file.write_all(&[0u8; 10]).unwrap();
+        assert_eq!(Trace::load(&path).unwrap().records.len(), 3);
+    }
+
+    #[test]
+    fn test_truncated_trace_is_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = TraceRecorder::new(dir.path()).unwrap();
+        recorder.start_epoch(TraceRecord {
+            timestamp_usecs: 1,
+            event: TraceEvent::EpochStart(Box::new(epoch_start())),
+        });
+        // Stands in for the writer falling behind.
+        recorder.dropped.fetch_add(2, Ordering::Relaxed);
+        recorder.record(TraceRecord {
+            timestamp_usecs: 2,
+            event: TraceEvent::LocalTimeout(1),
+        });
+        drop(recorder);
+
+        let error = Trace::load(&dir.path().join("consensus_epoch_1_1.trace"))
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("truncated"));
+    }
+}
@@ -0,0 +1,947 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    block_storage::{pending_blocks::PendingBlocks, BlockReader, BlockStore},
+    counters,
+    liveness::{
+        proposal_generator::{
+            ChainHealthBackoffConfig, PipelineBackpressureConfig, ProposalGenerator,
+        },
+        round_proposer_election::RoundProposer,
+        round_state::{ExponentialTimeInterval, RoundState},
+    },
+    metrics_safety_rules::MetricsSafetyRules,
+    network::{IncomingCommitRequest, NetworkSender},
+    network_interface::{ConsensusMsg, ConsensusNetworkClient, DIRECT_SEND, RPC},
+    payload_manager::TPayloadManager,
+    pipeline::{
+        commit_reliable_broadcast::CommitMessage,
+        execution_client::{ExecutionProxyClient, TExecutionClient},
+    },
+    rand::rand_gen::{storage::in_memory::InMemRandDb, types::AugmentedData},
+    record_replay::{
+        execution::{
+            ReplayBlockExecutor, ReplayExecutionClient, ReplayPayloadManager, ReplayStateSync,
+            ReplayTxnNotifier,
+        },
+        Trace, TraceEvent, TraceRecord,
+    },
+    round_manager::{RoundManager, UnverifiedEvent, VerifiedEvent},
+    state_computer::ExecutionProxy,
+    test_utils::{
+        EmptyStorage, MockOptQSPayloadProvider, MockPastProposalStatusTracker, MockPayloadManager,
+    },
+    util::{mock_time_service::SimulatedTimeService, time_service::TimeService},
+};
+use anyhow::{anyhow, ensure};
+use bytes::Bytes;
+use lumio_bounded_executor::BoundedExecutor;
+use lumio_channels::{lumio_channel, message_queues::QueueStyle};
+use lumio_config::{
+    config::{BlockTransactionFilterConfig, ConsensusObserverConfig, IdentityBlob},
+    network_id::{NetworkId, PeerNetworkId},
+};
+use lumio_consensus_types::{
+    common::{Author, Round},
+    utils::PayloadTxnsSize,
+};
+use lumio_crypto::{bls12381, HashValue};
+use lumio_infallible::Mutex;
+use lumio_network::{
+    application::{interface::NetworkClient, storage::PeersAndMetadata},
+    peer_manager::{ConnectionRequestSender, PeerManagerRequest, PeerManagerRequestSender},
+    protocols::{
+        network::{Event, NewNetworkSender, SerializedRequest},
+        rpc::error::RpcError,
+        wire::handshake::v1::{ProtocolId, ProtocolIdSet},
+    },
+    transport::ConnectionMetadata,
+};
+use lumio_safety_rules::{PersistentSafetyStorage, SafetyRules, TSafetyRules};
+use lumio_secure_storage::{InMemoryStorage, Storage};
+use lumio_types::{
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    epoch_change::EpochChangeProof,
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    on_chain_config::{OnChainExecutionConfig, OnChainRandomnessConfig},
+    validator_signer::ValidatorSigner,
+    waypoint::Waypoint,
+    PeerId,
+};
+use lumio_validator_transaction_pool::VTxnPoolState;
+use clap::Parser;
+use futures::{channel::oneshot, FutureExt, StreamExt};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    future::Future,
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::runtime::{Handle, Runtime};
+
+/// What replayed and recorded messages are compared by. Proposals are compared without their
+/// block id, as the replayed node pulls empty payloads instead of the recorded ones.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MessageSummary {
+    pub name: &'static str,
+    pub epoch: u64,
+    pub round: Round,
+    pub block_id: Option<HashValue>,
+}
+
+impl MessageSummary {
+    /// Returns `None` for the messages not sent by the round manager.
+    pub fn new(msg: &ConsensusMsg) -> Option<Self> {
+        let (epoch, round, block_id) = match msg {
+            ConsensusMsg::ProposalMsg(m) => (m.epoch(), m.proposal().round(), None),
+            ConsensusMsg::OptProposalMsg(m) => (m.epoch(), m.round(), None),
+            ConsensusMsg::VoteMsg(m) => {
+                let proposed = m.vote().vote_data().proposed();
+                (proposed.epoch(), proposed.round(), Some(proposed.id()))
+            },
+            ConsensusMsg::OrderVoteMsg(m) => {
+                let commit_info = m.order_vote().ledger_info().commit_info();
+                (m.epoch(), commit_info.round(), Some(commit_info.id()))
+            },
+            ConsensusMsg::RoundTimeoutMsg(m) => (m.epoch(), m.round(), None),
+            ConsensusMsg::SyncInfo(m) => (m.epoch(), m.highest_round(), None),
+            _ => return None,
+        };
+        Some(Self {
+            name: msg.name(),
+            epoch,
+            round,
+            block_id,
+        })
+    }
+}
+
+impl fmt::Display for MessageSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} (epoch {}, round {}",
+            self.name, self.epoch, self.round
+        )?;
+        if let Some(block_id) = self.block_id {
+            write!(f, ", block {}", block_id)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// The first point where the replayed node sent something else than the recorded one. Each side
+/// is the message sent, with the index of the record being replayed when it was sent, or `None`
+/// if that side sent nothing more.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Divergence {
+    pub recorded: Option<(usize, MessageSummary)>,
+    pub replayed: Option<(usize, MessageSummary)>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let side = |s: &Option<(usize, MessageSummary)>| match s {
+            Some((index, summary)) => format!("{} at record #{}", summary, index),
+            None => "nothing".to_string(),
+        };
+        write!(
+            f,
+            "recorded node sent {}, replayed node sent {}",
+            side(&self.recorded),
+            side(&self.replayed)
+        )
+    }
+}
+
+pub struct ReplayReport {
+    /// Inbound messages, local timeouts, execution results and state syncs replayed.
+    pub events_replayed: usize,
+    /// Records not replayed, e.g. messages for quorum store or randomness.
+    pub events_skipped: usize,
+    /// Errors from verifying or processing an event, with the index of its record.
+    pub errors: Vec<(usize, String)>,
+    pub recorded_outbound: Vec<(usize, MessageSummary)>,
+    pub replayed_outbound: Vec<(usize, MessageSummary)>,
+    pub divergence: Option<Divergence>,
+    /// The round of the last block committed by the replayed node.
+    pub committed_round: Round,
+}
+
+/// Replays a recorded [`Trace`] through a fresh `RoundManager`, with a simulated clock advanced to
+/// the time of each record, and rpc requests answered with the recorded responses. Ordered blocks
+/// go through the buffer manager and execution pipeline, where they get the recorded execution
+/// results and are committed by the recorded commit messages. State syncs return the recorded
+/// results.
+///
+/// The pipeline runs on its own tasks, so records feeding it are followed by a short wait for it
+/// to settle. Randomness isn't replayed.
+pub struct TraceReplayer {
+    trace: Trace,
+    consensus_key: Arc<bls12381::PrivateKey>,
+    signer: ValidatorSigner,
+}
+
+impl TraceReplayer {
+    /// `consensus_key` must be the key the trace was recorded with, for the replayed node to sign
+    /// the same votes.
+    pub fn new(trace: Trace, consensus_key: bls12381::PrivateKey) -> Self {
+        let consensus_key = Arc::new(consensus_key);
+        let signer = ValidatorSigner::new(trace.epoch_start.author, consensus_key.clone());
+        Self {
+            trace,
+            consensus_key,
+            signer,
+        }
+    }
+
+    pub fn replay(self) -> anyhow::Result<ReplayReport> {
+        Runtime::new()?.block_on(self.replay_async())
+    }
+
+    async fn replay_async(self) -> anyhow::Result<ReplayReport> {
+        let Trace {
+            epoch_start,
+            start_timestamp_usecs,
+            records,
+        } = self.trace;
+        let author = self.signer.author();
+        let local_config = epoch_start.local_config()?;
+        let epoch_state = Arc::new(epoch_start.epoch_state.clone());
+        let onchain_config = epoch_start.onchain_consensus_config.clone();
+
+        let time_service = Arc::new(SimulatedTimeService::new());
+        time_service
+            .sleep(Duration::from_micros(start_timestamp_usecs))
+            .await;
+
+        let storage = Arc::new(EmptyStorage::new());
+        let safety_rules = Arc::new(Mutex::new(MetricsSafetyRules::new(
+            Box::new(create_safety_rules(&self.signer, &epoch_state)?),
+            storage.clone(),
+        )));
+        let (network_client, self_sender, mut replay_network) =
+            create_network(author, &epoch_state);
+        replay_network.rpc_responses = records
+            .iter()
+            .filter_map(|record| match &record.event {
+                TraceEvent::Rpc { response, .. } => Some(response.clone()),
+                _ => None,
+            })
+            .collect();
+        let network = Arc::new(NetworkSender::new(
+            author,
+            network_client.clone(),
+            self_sender.clone(),
+            epoch_state.verifier.clone(),
+        ));
+
+        // Ordered blocks go through the real buffer manager and execution pipeline, with the
+        // executor and state sync answering from the trace.
+        let recovery_data = epoch_start.recovery_data.clone();
+        let executor = Arc::new(ReplayBlockExecutor::new(
+            recovery_data.commit_root_block().id(),
+        ));
+        let state_sync = Arc::new(ReplayStateSync::new(&records));
+        let execution_proxy = Arc::new(ExecutionProxy::new(
+            executor.clone(),
+            Arc::new(ReplayTxnNotifier),
+            state_sync.clone(),
+            BlockTransactionFilterConfig::default(),
+            local_config.enable_pre_commit,
+        ));
+        let execution_client = Arc::new(ReplayExecutionClient::new(
+            ExecutionProxyClient::new(
+                local_config.clone(),
+                execution_proxy,
+                author,
+                self_sender,
+                network_client,
+                BoundedExecutor::new(BOUNDED_EXECUTOR_CAPACITY, Handle::current()),
+                Arc::new(InMemRandDb::<AugmentedData>::new()),
+                ConsensusObserverConfig::default(),
+                None,
+            ),
+            executor.clone(),
+        ));
+        let payload_manager: Arc<dyn TPayloadManager> = Arc::new(ReplayPayloadManager);
+        // Randomness isn't replayed, so no rand manager gets started.
+        let (_rand_msg_tx, rand_msg_rx) = lumio_channel::new(QueueStyle::FIFO, 1, None);
+        execution_client
+            .start_epoch(
+                self.consensus_key.clone(),
+                epoch_state.clone(),
+                safety_rules.clone(),
+                payload_manager.clone(),
+                &onchain_config,
+                &OnChainExecutionConfig::default_if_missing(),
+                &OnChainRandomnessConfig::default_disabled(),
+                None,
+                None,
+                rand_msg_rx,
+                recovery_data.commit_root_block().round(),
+            )
+            .await;
+        let pipeline_builder = execution_client.pipeline_builder(Arc::new(self.signer.clone()));
+
+        let last_vote = recovery_data.last_vote();
+        let block_store = Arc::new(BlockStore::new(
+            storage.clone(),
+            recovery_data,
+            execution_client.clone(),
+            local_config.max_pruned_blocks_in_mem,
+            time_service.clone(),
+            local_config.vote_back_pressure_limit,
+            payload_manager,
+            onchain_config.order_vote_enabled(),
+            onchain_config.window_size(),
+            Arc::new(Mutex::new(PendingBlocks::new())),
+            Some(pipeline_builder),
+        ));
+
+        let proposal_generator = ProposalGenerator::new(
+            author,
+            block_store.clone(),
+            Arc::new(MockPayloadManager::new(None)),
+            time_service.clone(),
+            Duration::ZERO,
+            PayloadTxnsSize::new(
+                local_config.max_sending_block_txns,
+                local_config.max_sending_block_bytes,
+            ),
+            local_config.max_sending_block_txns_after_filtering,
+            PayloadTxnsSize::new(
+                local_config.max_sending_inline_txns,
+                local_config.max_sending_inline_bytes,
+            ),
+            onchain_config.max_failed_authors_to_store(),
+            local_config.min_max_txns_in_block_after_filtering_from_backpressure,
+            None,
+            PipelineBackpressureConfig::new_no_backoff(),
+            ChainHealthBackoffConfig::new_no_backoff(),
+            onchain_config.quorum_store_enabled(),
+            onchain_config.effective_validator_txn_config(),
+            local_config
+                .quorum_store
+                .allow_batches_without_pos_in_proposal,
+            Arc::new(MockOptQSPayloadProvider {}),
+        );
+
+        // Local timeouts are replayed from the trace, so the scheduled ones never need to fire.
+        let (round_timeout_sender, _round_timeout_receiver) = lumio_channels::new_test(1_024);
+        let round_state = RoundState::new(
+            Box::new(ExponentialTimeInterval::new(
+                Duration::from_millis(local_config.round_initial_timeout_ms),
+                local_config.round_timeout_backoff_exponent_base,
+                local_config.round_timeout_backoff_max_exponent,
+            )),
+            time_service.clone(),
+            round_timeout_sender,
+        );
+        let (buffered_proposal_tx, _buffered_proposal_rx) =
+            lumio_channel::new(QueueStyle::KLAST, 10, None);
+        let (opt_proposal_loopback_tx, _opt_proposal_loopback_rx) =
+            lumio_channels::new_unbounded(&counters::OP_COUNTERS.gauge("opt_proposal_queue"));
+
+        let quorum_store_enabled = onchain_config.quorum_store_enabled();
+        let mut round_manager = RoundManager::new(
+            epoch_state.clone(),
+            block_store.clone(),
+            round_state,
+            create_proposer_election(author, &epoch_state, &records),
+            proposal_generator,
+            safety_rules,
+            network,
+            storage,
+            onchain_config,
+            buffered_proposal_tx,
+            BlockTransactionFilterConfig::default(),
+            local_config.clone(),
+            epoch_start.onchain_randomness_config.clone(),
+            epoch_start.onchain_jwk_consensus_config.clone(),
+            epoch_start.features.is_equivocation_evidence_enabled(),
+            None,
+            Arc::new(MockPastProposalStatusTracker {}),
+            opt_proposal_loopback_tx,
+            VTxnPoolState::default(),
+        );
+
+        replay_network
+            .drive(async {
+                round_manager.init(last_vote).await;
+                Ok(())
+            })
+            .await?;
+
+        let proof_cache = mini_moka::sync::Cache::new(local_config.proof_cache_capacity);
+        let mut report = ReplayReport {
+            events_replayed: 0,
+            events_skipped: 0,
+            errors: vec![],
+            recorded_outbound: vec![],
+            replayed_outbound: vec![],
+            divergence: None,
+            committed_round: 0,
+        };
+        for (index, record) in records.into_iter().enumerate() {
+            let now = time_service.get_current_timestamp();
+            let record_time = Duration::from_micros(record.timestamp_usecs);
+            if record_time > now {
+                time_service.sleep(record_time - now).await;
+            }
+            replay_network.record_index = index;
+
+            let result = match record.event {
+                TraceEvent::Inbound { from, msg } if is_commit_message(&msg) => {
+                    // The buffer manager verifies commit messages itself, and its responses
+                    // aren't part of the trace.
+                    let (response_sender, _response_receiver) = oneshot::channel();
+                    let result = execution_client.send_commit_msg(from, IncomingCommitRequest {
+                        req: to_commit_message(msg),
+                        protocol: RPC[0],
+                        response_sender,
+                    });
+                    replay_network.settle().await;
+                    result
+                },
+                TraceEvent::Inbound { from, msg } => {
+                    let Some(event) = to_round_manager_event(msg, epoch_state.epoch) else {
+                        report.events_skipped += 1;
+                        continue;
+                    };
+                    match event.verify(
+                        from,
+                        &epoch_state.verifier,
+                        &proof_cache,
+                        quorum_store_enabled,
+                        from == author,
+                        local_config.quorum_store.receiver_max_num_batches,
+                        local_config.quorum_store.batch_expiry_gap_when_init_usecs,
+                    ) {
+                        Ok(event) => {
+                            replay_network
+                                .drive(process_event(&mut round_manager, from, event))
+                                .await
+                        },
+                        Err(e) => Err(e.into()),
+                    }
+                },
+                TraceEvent::LocalTimeout(round) => {
+                    replay_network
+                        .drive(round_manager.process_local_timeout(round))
+                        .await
+                },
+                TraceEvent::ExecutionResult { block_id, result } => {
+                    // Lets the pipeline catch up with the blocks ordered so far first.
+                    replay_network.settle().await;
+                    let result = executor.release_result(block_id, &result);
+                    replay_network.settle().await;
+                    result
+                },
+                TraceEvent::SyncForDuration { duration_ms, .. } => {
+                    state_sync.process_sync_for_duration(duration_ms)
+                },
+                TraceEvent::SyncToTarget { target, .. } => {
+                    state_sync.process_sync_to_target(&target)
+                },
+                TraceEvent::Outbound { msg, .. } => {
+                    if let Some(summary) = MessageSummary::new(&msg) {
+                        report.recorded_outbound.push((index, summary));
+                    }
+                    continue;
+                },
+                TraceEvent::EpochStart(_)
+                | TraceEvent::Rpc { .. }
+                | TraceEvent::Truncated { .. } => {
+                    report.events_skipped += 1;
+                    continue;
+                },
+            };
+            report.events_replayed += 1;
+            if let Err(e) = result {
+                report.errors.push((index, e.to_string()));
+            }
+        }
+
+        // Blocks still waiting for an execution result never get one.
+        executor.finish_replay();
+        report.committed_round = block_store.commit_root().round();
+        report.replayed_outbound = std::mem::take(&mut replay_network.outbound);
+        dedup_summaries(&mut report.recorded_outbound);
+        dedup_summaries(&mut report.replayed_outbound);
+        report.divergence = find_divergence(&report.recorded_outbound, &report.replayed_outbound);
+        Ok(report)
+    }
+}
+
+/// Replays a trace recorded by a validator with `consensus.trace_recording_dir` set.
+#[derive(Parser)]
+#[clap(about = "Replay a recorded consensus trace, and report where the replay diverges from it")]
+pub struct Command {
+    /// The trace file of one epoch.
+    #[clap(long, value_parser)]
+    pub trace_file: PathBuf,
+
+    /// The identity file of the validator that recorded the trace, holding its consensus key.
+    #[clap(long, value_parser)]
+    pub identity_file: PathBuf,
+}
+
+impl Command {
+    pub async fn run(self) -> anyhow::Result<()> {
+        let trace = Trace::load(&self.trace_file)?;
+        let consensus_key = IdentityBlob::from_file(&self.identity_file)?
+            .consensus_private_key
+            .ok_or_else(|| anyhow!("No consensus key in {:?}", self.identity_file))?;
+        let report = TraceReplayer::new(trace, consensus_key)
+            .replay_async()
+            .await?;
+
+        println!(
+            "Replayed {} events, skipped {}, committed up to round {}.",
+            report.events_replayed, report.events_skipped, report.committed_round
+        );
+        for (index, error) in &report.errors {
+            println!("Error at record #{}: {}", index, error);
+        }
+        match &report.divergence {
+            Some(divergence) => println!("Diverged: {}", divergence),
+            None => println!(
+                "No divergence in the {} messages sent.",
+                report.recorded_outbound.len()
+            ),
+        }
+        Ok(())
+    }
+}
+
+/// How long the network has to stay quiet for the execution pipeline to count as settled.
+const SETTLE_INTERVAL: Duration = Duration::from_millis(10);
+/// Caps the wait for the pipeline to settle, e.g. while reliable broadcasts keep retrying.
+const MAX_SETTLE_INTERVALS: usize = 50;
+const BOUNDED_EXECUTOR_CAPACITY: usize = 16;
+
+/// Captures what the replayed node sends, and answers its rpc requests.
+struct ReplayNetwork {
+    network_reqs_rx: lumio_channel::Receiver<(PeerId, ProtocolId), PeerManagerRequest>,
+    self_receiver: lumio_channels::UnboundedReceiver<Event<ConsensusMsg>>,
+    rpc_responses: VecDeque<Result<ConsensusMsg, String>>,
+    record_index: usize,
+    outbound: Vec<(usize, MessageSummary)>,
+    /// Requests and self messages handled so far.
+    handled: usize,
+}
+
+impl ReplayNetwork {
+    /// Runs `fut` to completion while serving the network, then collects everything it sent.
+    async fn drive(&mut self, fut: impl Future<Output = anyhow::Result<()>>) -> anyhow::Result<()> {
+        tokio::pin!(fut);
+        let result = loop {
+            tokio::select! {
+                biased;
+                result = &mut fut => break result,
+                Some(request) = self.network_reqs_rx.next() => self.handle_request(request),
+                Some(event) = self.self_receiver.next() => self.handle_self_event(event),
+            }
+        };
+        while let Some(Some(request)) = self.network_reqs_rx.next().now_or_never() {
+            self.handle_request(request);
+        }
+        while let Some(Some(event)) = self.self_receiver.next().now_or_never() {
+            self.handle_self_event(event);
+        }
+        result
+    }
+
+    /// Serves the network until the execution pipeline, which runs on its own tasks, has been
+    /// quiet for a while.
+    async fn settle(&mut self) {
+        for _ in 0..MAX_SETTLE_INTERVALS {
+            let handled = self.handled;
+            let _ = self
+                .drive(async {
+                    tokio::time::sleep(SETTLE_INTERVAL).await;
+                    Ok(())
+                })
+                .await;
+            if self.handled == handled {
+                return;
+            }
+        }
+    }
+
+    fn handle_request(&mut self, request: PeerManagerRequest) {
+        self.handled += 1;
+        match request {
+            PeerManagerRequest::SendDirectSend(_, message) => {
+                if let Ok(msg) = message.to_message::<ConsensusMsg>() {
+                    self.capture(&msg);
+                }
+            },
+            PeerManagerRequest::SendRpc(_, request) => {
+                let msg = request
+                    .protocol_id
+                    .from_bytes::<ConsensusMsg>(&request.data)
+                    .ok();
+                self.answer_rpc(msg.as_ref(), request.protocol_id, request.res_tx)
+            },
+        }
+    }
+
+    /// Messages sent to self are captured, but not processed: the recorded node received them
+    /// as inbound messages, which are replayed from the trace.
+    fn handle_self_event(&mut self, event: Event<ConsensusMsg>) {
+        self.handled += 1;
+        match event {
+            Event::Message(_, msg) => self.capture(&msg),
+            Event::RpcRequest(_, msg, protocol, callback) => {
+                self.answer_rpc(Some(&msg), protocol, callback)
+            },
+            _ => (),
+        }
+    }
+
+    fn capture(&mut self, msg: &ConsensusMsg) {
+        if let Some(summary) = MessageSummary::new(msg) {
+            self.outbound.push((self.record_index, summary));
+        }
+    }
+
+    /// The reliable broadcasts of commit messages aren't recorded, and get acked right away.
+    /// Other rpcs get the recorded responses, in order.
+    fn answer_rpc(
+        &mut self,
+        request: Option<&ConsensusMsg>,
+        protocol: ProtocolId,
+        callback: oneshot::Sender<Result<Bytes, RpcError>>,
+    ) {
+        let response = match request {
+            Some(ConsensusMsg::CommitMessage(_)) => Some(Ok(ConsensusMsg::CommitMessage(
+                Box::new(CommitMessage::Ack(())),
+            ))),
+            _ => self.rpc_responses.pop_front(),
+        };
+        let response = match response {
+            Some(Ok(msg)) => protocol
+                .to_bytes(&msg)
+                .map(Bytes::from)
+                .map_err(RpcError::Error),
+            Some(Err(e)) => Err(RpcError::Error(anyhow!(e))),
+            None => Err(RpcError::Error(anyhow!("No recorded rpc response left"))),
+        };
+        let _ = callback.send(response);
+    }
+}
+
+fn create_network(
+    author: Author,
+    epoch_state: &EpochState,
+) -> (
+    ConsensusNetworkClient<NetworkClient<ConsensusMsg>>,
+    lumio_channels::UnboundedSender<Event<ConsensusMsg>>,
+    ReplayNetwork,
+) {
+    let (network_reqs_tx, network_reqs_rx) = lumio_channel::new(QueueStyle::FIFO, 1_024, None);
+    let (connection_reqs_tx, _) = lumio_channel::new(QueueStyle::FIFO, 8, None);
+    let network_sender = NewNetworkSender::new(
+        PeerManagerRequestSender::new(network_reqs_tx),
+        ConnectionRequestSender::new(connection_reqs_tx),
+    );
+
+    let peers_and_metadata = PeersAndMetadata::new(&[NetworkId::Validator]);
+    for peer in epoch_state.verifier.get_ordered_account_addresses_iter() {
+        if peer == author {
+            continue;
+        }
+        let mut metadata = ConnectionMetadata::mock(peer);
+        metadata.application_protocols =
+            ProtocolIdSet::from_iter(DIRECT_SEND.iter().chain(RPC.iter()));
+        peers_and_metadata
+            .insert_connection_metadata(PeerNetworkId::new(NetworkId::Validator, peer), metadata)
+            .expect("Peers are for the validator network");
+    }
+    let network_client = NetworkClient::new(
+        DIRECT_SEND.into(),
+        RPC.into(),
+        HashMap::from([(NetworkId::Validator, network_sender)]),
+        peers_and_metadata,
+    );
+
+    let (self_sender, self_receiver) = lumio_channels::new_unbounded_test();
+    (
+        ConsensusNetworkClient::new(network_client),
+        self_sender,
+        ReplayNetwork {
+            network_reqs_rx,
+            self_receiver,
+            rpc_responses: VecDeque::new(),
+            record_index: 0,
+            outbound: vec![],
+            handled: 0,
+        },
+    )
+}
+
+/// Initializes safety rules from scratch for the traced epoch, with a waypoint on a made up
+/// ledger info ending the previous epoch.
+fn create_safety_rules(
+    signer: &ValidatorSigner,
+    epoch_state: &EpochState,
+) -> anyhow::Result<SafetyRules> {
+    ensure!(epoch_state.epoch > 0, "Can't replay the genesis epoch");
+    let ledger_info = LedgerInfo::new(
+        BlockInfo::new(
+            epoch_state.epoch - 1,
+            0,
+            HashValue::zero(),
+            HashValue::zero(),
+            0,
+            0,
+            Some(epoch_state.clone()),
+        ),
+        HashValue::zero(),
+    );
+    let waypoint = Waypoint::new_epoch_boundary(&ledger_info)?;
+    let storage = PersistentSafetyStorage::initialize(
+        Storage::from(InMemoryStorage::new()),
+        signer.author(),
+        signer.private_key().clone(),
+        waypoint,
+        true,
+    );
+    let proof = EpochChangeProof::new(
+        vec![LedgerInfoWithSignatures::new(
+            ledger_info,
+            AggregateSignature::empty(),
+        )],
+        false,
+    );
+    let mut safety_rules = SafetyRules::new(storage, false);
+    safety_rules.initialize(&proof)?;
+    Ok(safety_rules)
+}
+
+/// The trace doesn't say which proposer election the recorded node used, so proposers are taken
+/// from the proposals in the trace.
+fn create_proposer_election(
+    author: Author,
+    epoch_state: &EpochState,
+    records: &[TraceRecord],
+) -> Arc<RoundProposer> {
+    let mut proposers = HashMap::new();
+    for record in records {
+        let (proposer, msg) = match &record.event {
+            TraceEvent::Inbound { from, msg } => (*from, msg),
+            TraceEvent::Outbound { msg, .. } => (author, msg),
+            _ => continue,
+        };
+        match msg {
+            ConsensusMsg::ProposalMsg(m) => proposers.insert(m.proposal().round(), proposer),
+            ConsensusMsg::OptProposalMsg(m) => proposers.insert(m.round(), proposer),
+            _ => continue,
+        };
+    }
+    // Rounds without a proposal in the trace go to someone else, so the replayed node doesn't
+    // propose in rounds the recorded one didn't.
+    let default_proposer = epoch_state
+        .verifier
+        .get_ordered_account_addresses_iter()
+        .find(|peer| *peer != author)
+        .unwrap_or(author);
+    Arc::new(RoundProposer::new(proposers, default_proposer))
+}
+
+/// Returns `None` for the messages the epoch manager doesn't pass to the round manager.
+fn to_round_manager_event(msg: ConsensusMsg, epoch: u64) -> Option<UnverifiedEvent> {
+    let event = match msg {
+        ConsensusMsg::ProposalMsg(_)
+        | ConsensusMsg::OptProposalMsg(_)
+        | ConsensusMsg::VoteMsg(_)
+        | ConsensusMsg::OrderVoteMsg(_)
+        | ConsensusMsg::RoundTimeoutMsg(_)
+        | ConsensusMsg::SyncInfo(_) => UnverifiedEvent::from(msg),
+        _ => return None,
+    };
+    (event.epoch().ok()? == epoch).then_some(event)
+}
+
+fn is_commit_message(msg: &ConsensusMsg) -> bool {
+    matches!(
+        msg,
+        ConsensusMsg::CommitVoteMsg(_)
+            | ConsensusMsg::CommitDecisionMsg(_)
+            | ConsensusMsg::CommitMessage(_)
+    )
+}
+
+/// Converts the direct send commit messages like the network task does.
+fn to_commit_message(msg: ConsensusMsg) -> CommitMessage {
+    match msg {
+        ConsensusMsg::CommitVoteMsg(vote) => CommitMessage::Vote(*vote),
+        ConsensusMsg::CommitDecisionMsg(decision) => CommitMessage::Decision(*decision),
+        ConsensusMsg::CommitMessage(msg) => *msg,
+        msg => unreachable!("{} isn't a commit message", msg.name()),
+    }
+}
+
+async fn process_event(
+    round_manager: &mut RoundManager,
+    from: Author,
+    event: VerifiedEvent,
+) -> anyhow::Result<()> {
+    match event {
+        VerifiedEvent::ProposalMsg(p) => round_manager.process_proposal_msg(*p).await,
+        VerifiedEvent::OptProposalMsg(p) => round_manager.process_opt_proposal_msg(*p).await,
+        event => round_manager.process_event(from, event).await,
+    }
+}
+
+/// Broadcasts show up as one message per recipient, so consecutive duplicates are merged.
+fn dedup_summaries(summaries: &mut Vec<(usize, MessageSummary)>) {
+    summaries.dedup_by(|(_, a), (_, b)| a == b);
+}
+
+fn find_divergence(
+    recorded: &[(usize, MessageSummary)],
+    replayed: &[(usize, MessageSummary)],
+) -> Option<Divergence> {
+    (0..recorded.len().max(replayed.len())).find_map(|i| {
+        let (recorded, replayed) = (recorded.get(i), replayed.get(i));
+        let same = matches!((recorded, replayed), (Some((_, a)), Some((_, b))) if a == b);
+        (!same).then(|| Divergence {
+            recorded: recorded.cloned(),
+            replayed: replayed.cloned(),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{record_replay::EpochStart, test_utils::MockStorage};
+    use lumio_config::config::ConsensusConfig;
+    use lumio_types::{
+        on_chain_config::{
+            Features, OnChainConsensusConfig, OnChainJWKConsensusConfig, OnChainRandomnessConfig,
+        },
+        validator_verifier::{ValidatorConsensusInfo, ValidatorVerifier},
+    };
+
+    fn create_trace(signers: &[ValidatorSigner], records: Vec<TraceRecord>) -> Trace {
+        let verifier = ValidatorVerifier::new(
+            signers
+                .iter()
+                .map(|s| ValidatorConsensusInfo::new(s.author(), s.public_key(), 1))
+                .collect(),
+        );
+        let (recovery_data, storage) = MockStorage::start_for_testing((&verifier).into());
+        Trace {
+            epoch_start: EpochStart {
+                author: signers[0].author(),
+                epoch_state: EpochState::new(1, storage.get_validator_set().into()),
+                recovery_data,
+                onchain_consensus_config: OnChainConsensusConfig::default(),
+                onchain_randomness_config: OnChainRandomnessConfig::default_enabled(),
+                onchain_jwk_consensus_config: OnChainJWKConsensusConfig::default_enabled(),
+                features: Features::default(),
+                local_config_json: serde_json::to_string(&ConsensusConfig::default()).unwrap(),
+            },
+            start_timestamp_usecs: 1_000_000,
+            records,
+        }
+    }
+
+    fn local_timeout(round: Round) -> TraceRecord {
+        TraceRecord {
+            timestamp_usecs: 2_000_000,
+            event: TraceEvent::LocalTimeout(round),
+        }
+    }
+
+    #[test]
+    fn test_replay_reports_divergence() {
+        let signers = vec![ValidatorSigner::from_int(1), ValidatorSigner::from_int(2)];
+        let trace = create_trace(&signers, vec![local_timeout(1)]);
+
+        let report = TraceReplayer::new(trace, signers[0].private_key().clone())
+            .replay()
+            .unwrap();
+        assert_eq!(report.events_replayed, 1);
+        assert!(report.recorded_outbound.is_empty());
+        let divergence = report.divergence.unwrap();
+        assert_eq!(divergence.recorded, None);
+        let (index, summary) = divergence.replayed.unwrap();
+        assert_eq!(index, 0);
+        assert_eq!(summary.round, 1);
+    }
+
+    #[test]
+    fn test_replay_is_deterministic() {
+        let signers = vec![ValidatorSigner::from_int(1), ValidatorSigner::from_int(2)];
+        let first = TraceReplayer::new(
+            create_trace(&signers, vec![local_timeout(1)]),
+            signers[0].private_key().clone(),
+        )
+        .replay()
+        .unwrap();
+        let second = TraceReplayer::new(
+            create_trace(&signers, vec![local_timeout(1)]),
+            signers[0].private_key().clone(),
+        )
+        .replay()
+        .unwrap();
+        assert!(!first.replayed_outbound.is_empty());
+        assert_eq!(first.replayed_outbound, second.replayed_outbound);
+        assert_eq!(
+            find_divergence(&first.replayed_outbound, &second.replayed_outbound),
+            None
+        );
+    }
+
+    #[test]
+    fn test_replay_execution_and_sync_records() {
+        let signers = vec![ValidatorSigner::from_int(1), ValidatorSigner::from_int(2)];
+        let block_id = HashValue::random();
+        let target =
+            LedgerInfoWithSignatures::new(LedgerInfo::dummy(), AggregateSignature::empty());
+        let records = vec![
+            TraceRecord {
+                timestamp_usecs: 2_000_000,
+                event: TraceEvent::ExecutionResult {
+                    block_id,
+                    result: Err("not executed".into()),
+                },
+            },
+            TraceRecord {
+                timestamp_usecs: 2_000_000,
+                event: TraceEvent::SyncToTarget {
+                    target,
+                    result: Ok(()),
+                },
+            },
+        ];
+
+        let report = TraceReplayer::new(
+            create_trace(&signers, records),
+            signers[0].private_key().clone(),
+        )
+        .replay()
+        .unwrap();
+        // Neither the block nor the sync were asked for by the replayed node.
+        assert_eq!(report.events_replayed, 2);
+        assert_eq!(report.events_skipped, 0);
+        assert_eq!(
+            report
+                .errors
+                .iter()
+                .map(|(index, _)| *index)
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+    }
+}
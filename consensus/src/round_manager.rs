@@ -13,6 +13,7 @@ use crate::{
         PROPOSAL_VOTE_ADDED, PROPOSAL_VOTE_BROADCASTED, PROPOSED_VTXN_BYTES, PROPOSED_VTXN_COUNT,
         QC_AGGREGATED_FROM_VOTES, SYNC_INFO_RECEIVED_WITH_NEWER_CERT,
     },
+    equivocation_reporter::{proposal_evidence, vote_evidence, EquivocationReporter},
     error::{error_kind, VerifyError},
     liveness::{
         proposal_generator::ProposalGenerator,
@@ -31,6 +32,7 @@ use crate::{
     persistent_liveness_storage::PersistentLivenessStorage,
     quorum_store::types::BatchMsg,
     rand::rand_gen::types::{FastShare, RandConfig, Share, TShare},
+    round_timeline::record_round_event,
     util::is_vtxn_expected,
 };
 use anyhow::{bail, ensure, Context};
@@ -48,6 +50,7 @@ use lumio_consensus_types::{
     proof_of_store::{ProofCache, ProofOfStoreMsg, SignedBatchInfoMsg},
     proposal_msg::ProposalMsg,
     quorum_cert::QuorumCert,
+    round_timeline::RoundEvent,
     round_timeout::{RoundTimeout, RoundTimeoutMsg, RoundTimeoutReason},
     sync_info::SyncInfo,
     timeout_2chain::{TwoChainTimeout, TwoChainTimeoutCertificate},
@@ -74,6 +77,7 @@ use lumio_types::{
     validator_verifier::ValidatorVerifier,
     PeerId,
 };
+use lumio_validator_transaction_pool::VTxnPoolState;
 use fail::fail_point;
 use futures::{channel::oneshot, stream::FuturesUnordered, Future, FutureExt, SinkExt, StreamExt};
 use lru::LruCache;
@@ -278,6 +282,7 @@ pub struct RoundManager {
     local_config: ConsensusConfig,
     randomness_config: OnChainRandomnessConfig,
     jwk_consensus_config: OnChainJWKConsensusConfig,
+    equivocation_evidence_enabled: bool,
     fast_rand_config: Option<RandConfig>,
     // Stores the order votes from all the rounds above highest_ordered_round
     pending_order_votes: PendingOrderVotes,
@@ -291,6 +296,7 @@ pub struct RoundManager {
     proposal_status_tracker: Arc<dyn TPastProposalStatusTracker>,
     pending_opt_proposals: BTreeMap<Round, OptBlockData>,
     opt_proposal_loopback_tx: lumio_channels::UnboundedSender<OptBlockData>,
+    equivocation_reporter: EquivocationReporter,
 }
 
 impl RoundManager {
@@ -310,9 +316,11 @@ impl RoundManager {
         local_config: ConsensusConfig,
         randomness_config: OnChainRandomnessConfig,
         jwk_consensus_config: OnChainJWKConsensusConfig,
+        equivocation_evidence_enabled: bool,
         fast_rand_config: Option<RandConfig>,
         proposal_status_tracker: Arc<dyn TPastProposalStatusTracker>,
         opt_proposal_loopback_tx: lumio_channels::UnboundedSender<OptBlockData>,
+        vtxn_pool: VTxnPoolState,
     ) -> Self {
         // when decoupled execution is false,
         // the counter is still static.
@@ -324,6 +332,13 @@ impl RoundManager {
             .set(onchain_config.decoupled_execution() as i64);
         let vtxn_config = onchain_config.effective_validator_txn_config();
         debug!("vtxn_config={:?}", vtxn_config);
+        let equivocation_reporter = EquivocationReporter::new(
+            epoch_state.verifier.clone(),
+            storage.clone(),
+            vtxn_pool,
+            // Evidence is only useful once the chain accepts it as a validator transaction.
+            local_config.submit_equivocation_evidence && equivocation_evidence_enabled,
+        );
         Self {
             epoch_state,
             block_store,
@@ -340,6 +355,7 @@ impl RoundManager {
             local_config,
             randomness_config,
             jwk_consensus_config,
+            equivocation_evidence_enabled,
             fast_rand_config,
             pending_order_votes: PendingOrderVotes::new(),
             blocks_with_broadcasted_fast_shares: LruCache::new(
@@ -349,6 +365,7 @@ impl RoundManager {
             proposal_status_tracker,
             pending_opt_proposals: BTreeMap::new(),
             opt_proposal_loopback_tx,
+            equivocation_reporter,
         }
     }
 
@@ -694,6 +711,11 @@ impl RoundManager {
             proposal_msg.proposal().timestamp_usecs(),
             BlockStage::ROUND_MANAGER_RECEIVED,
         );
+        record_round_event(
+            self.block_store.round_timeline().as_deref(),
+            proposal_msg.proposal().round(),
+            RoundEvent::ProposalReceived,
+        );
         info!(
             self.new_log(LogEvent::ReceiveProposal)
                 .remote_peer(proposal_msg.proposer()),
@@ -764,6 +786,11 @@ impl RoundManager {
             proposal_msg.block_data().timestamp_usecs(),
             BlockStage::ROUND_MANAGER_RECEIVED_OPT_PROPOSAL,
         );
+        record_round_event(
+            self.block_store.round_timeline().as_deref(),
+            proposal_msg.round(),
+            RoundEvent::ProposalReceived,
+        );
         info!(
             self.new_log(LogEvent::ReceiveOptProposal),
             block_author = proposal_msg.proposer(),
@@ -1089,7 +1116,12 @@ impl RoundManager {
             for vtxn in vtxns {
                 let vtxn_type_name = vtxn.type_name();
                 ensure!(
-                    is_vtxn_expected(&self.randomness_config, &self.jwk_consensus_config, vtxn),
+                    is_vtxn_expected(
+                        &self.randomness_config,
+                        &self.jwk_consensus_config,
+                        self.equivocation_evidence_enabled,
+                        vtxn
+                    ),
                     "unexpected validator txn: {:?}",
                     vtxn_type_name
                 );
@@ -1154,12 +1186,22 @@ impl RoundManager {
             self.local_config.max_receiving_block_bytes,
         );
 
-        ensure!(
-            self.proposer_election.is_valid_proposal(&proposal),
-            "[RoundManager] Proposer {} for block {} is not a valid proposer for this round or created duplicate proposal",
-            author,
-            proposal,
-        );
+        if !self.proposer_election.is_valid_proposal(&proposal) {
+            if let Some(first) = self.proposer_election.equivocating_proposal(&proposal) {
+                self.equivocation_reporter
+                    .report(proposal_evidence(&first, &proposal));
+            }
+            bail!(
+                "[RoundManager] Proposer {} for block {} is not a valid proposer for this round or created duplicate proposal",
+                author,
+                proposal,
+            );
+        }
+        self.equivocation_reporter.on_proposal(&proposal);
+        self.equivocation_reporter
+            .on_commit(self.block_store.commit_root().block(), |block_id| {
+                self.block_store.get_block(block_id)
+            });
 
         // If the proposal contains any inline transactions that need to be denied
         // (e.g., due to filtering) drop the message and do not vote for the block.
@@ -1490,6 +1532,11 @@ impl RoundManager {
         if !block_arc.block().is_nil_block() {
             observe_block(block_arc.block().timestamp_usecs(), BlockStage::VOTED);
         }
+        record_round_event(
+            self.block_store.round_timeline().as_deref(),
+            block_arc.block().round(),
+            RoundEvent::VoteSent,
+        );
 
         if block_arc.block().is_opt_block() {
             observe_block(
@@ -1788,6 +1835,16 @@ impl RoundManager {
                 Ok(())
             },
             VoteReceptionResult::EchoTimeout(_) | VoteReceptionResult::DuplicateVote => Ok(()),
+            e @ VoteReceptionResult::EquivocateVote => {
+                let evidence = self
+                    .round_state
+                    .pending_vote_of(&vote.author())
+                    .map(|previous_vote| vote_evidence(previous_vote, vote));
+                if let Some(evidence) = evidence {
+                    self.equivocation_reporter.report(evidence);
+                }
+                Err(anyhow::anyhow!("{:?}", e))
+            },
             e => Err(anyhow::anyhow!("{:?}", e)),
         }
     }
@@ -2018,6 +2075,38 @@ impl RoundManager {
         LogSchema::new(event).round(round).epoch(epoch)
     }
 
+    /// Processes a vote, timeout, sync info or local timeout event, as received from the epoch
+    /// manager. Proposals go through the buffered proposal channel instead.
+    pub(crate) async fn process_event(
+        &mut self,
+        peer_id: Author,
+        event: VerifiedEvent,
+    ) -> anyhow::Result<()> {
+        match event {
+            VerifiedEvent::VoteMsg(vote_msg) => {
+                monitor!("process_vote", self.process_vote_msg(*vote_msg).await)
+            },
+            VerifiedEvent::RoundTimeoutMsg(timeout_msg) => monitor!(
+                "process_round_timeout",
+                self.process_round_timeout_msg(*timeout_msg).await
+            ),
+            VerifiedEvent::OrderVoteMsg(order_vote_msg) => monitor!(
+                "process_order_vote",
+                self.process_order_vote_msg(*order_vote_msg).await
+            ),
+            VerifiedEvent::UnverifiedSyncInfo(sync_info) => monitor!(
+                "process_sync_info",
+                self.process_sync_info_msg(*sync_info, peer_id).await
+            ),
+            VerifiedEvent::LocalTimeout(round) => monitor!(
+                "process_local_timeout",
+                self.process_local_timeout(round).await
+            ),
+            unexpected_event => unreachable!("Unexpected event: {:?}", unexpected_event),
+        }
+        .with_context(|| format!("from peer {}", peer_id))
+    }
+
     /// Mainloop of processing messages.
     #[allow(clippy::unwrap_used)]
     pub async fn start(
@@ -2121,29 +2210,7 @@ impl RoundManager {
                     };
                 },
                 (peer_id, event) = event_rx.select_next_some() => {
-                    let result = match event {
-                        VerifiedEvent::VoteMsg(vote_msg) => {
-                            monitor!("process_vote", self.process_vote_msg(*vote_msg).await)
-                        }
-                        VerifiedEvent::RoundTimeoutMsg(timeout_msg) => {
-                            monitor!("process_round_timeout", self.process_round_timeout_msg(*timeout_msg).await)
-                        }
-                        VerifiedEvent::OrderVoteMsg(order_vote_msg) => {
-                            monitor!("process_order_vote", self.process_order_vote_msg(*order_vote_msg).await)
-                        }
-                        VerifiedEvent::UnverifiedSyncInfo(sync_info) => {
-                            monitor!(
-                                "process_sync_info",
-                                self.process_sync_info_msg(*sync_info, peer_id).await
-                            )
-                        }
-                        VerifiedEvent::LocalTimeout(round) => monitor!(
-                            "process_local_timeout",
-                            self.process_local_timeout(round).await
-                        ),
-                        unexpected_event => unreachable!("Unexpected event: {:?}", unexpected_event),
-                    }
-                    .with_context(|| format!("from peer {}", peer_id));
+                    let result = self.process_event(peer_id, event).await;
 
                     let round_state = self.round_state();
                     match result {
@@ -52,6 +52,7 @@ use lumio_types::{
     validator_signer::ValidatorSigner,
     validator_verifier::ValidatorVerifier,
 };
+use lumio_validator_transaction_pool::VTxnPoolState;
 use futures::{channel::mpsc, executor::block_on};
 use maplit::hashmap;
 use once_cell::sync::Lazy;
@@ -222,9 +223,11 @@ fn create_node_for_fuzzing() -> RoundManager {
         ConsensusConfig::default(),
         OnChainRandomnessConfig::default_enabled(),
         OnChainJWKConsensusConfig::default_enabled(),
+        false,
         None,
         Arc::new(MockPastProposalStatusTracker {}),
         opt_proposal_loopback_tx,
+        VTxnPoolState::default(),
     )
 }
 
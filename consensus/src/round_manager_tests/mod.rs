@@ -80,6 +80,7 @@ use lumio_types::{
     waypoint::Waypoint,
     PeerId,
 };
+use lumio_validator_transaction_pool::VTxnPoolState;
 use futures::{
     channel::mpsc, executor::block_on, future::Shared, stream::select, FutureExt, Stream, StreamExt,
 };
@@ -477,9 +478,11 @@ impl NodeSetup {
             local_config,
             onchain_randomness_config.clone(),
             onchain_jwk_consensus_config.clone(),
+            false,
             None,
             Arc::new(MockPastProposalStatusTracker {}),
             opt_proposal_loopback_tx,
+            VTxnPoolState::default(),
         );
         block_on(round_manager.init(last_vote_sent));
         Self {
@@ -3,6 +3,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 
 use crate::{
+    equivocation_reporter::vote_evidence,
     network_tests::NetworkPlayground,
     round_manager::round_manager_tests::NodeSetup,
     test_utils::{consensus_runtime, create_vec_signed_transactions, timed_block_on},
@@ -11,10 +12,15 @@ use lumio_config::config::ConsensusConfig;
 use lumio_consensus_types::{
     block::{block_test_utils::certificate_for_genesis, Block},
     common::Payload,
+    vote::Vote,
+    vote_data::VoteData,
 };
+use lumio_crypto::HashValue;
 use lumio_types::{
+    block_info::BlockInfo,
     dkg::{real_dkg::RealDKG, DKGSessionMetadata, DKGTrait, DKGTranscript},
     jwks::QuorumCertifiedUpdate,
+    ledger_info::LedgerInfo,
     on_chain_config::{
         ConsensusAlgorithmConfig, ConsensusConfigV1, OnChainConsensusConfig,
         OnChainJWKConsensusConfig, OnChainRandomnessConfig, RandomnessConfigMoveStruct,
@@ -114,6 +120,59 @@ fn no_vote_on_proposal_with_unexpected_vtxns() {
     );
 }
 
+#[test]
+fn no_vote_on_proposal_with_equivocation_evidence_when_feature_disabled() {
+    for enabled in [false, true] {
+        let runtime = consensus_runtime();
+        let mut playground = NetworkPlayground::new(runtime.handle().clone());
+        let mut nodes = NodeSetup::create_nodes(
+            &mut playground,
+            runtime.handle().clone(),
+            1,
+            None,
+            Some(OnChainConsensusConfig::default_for_genesis()),
+            None,
+            None,
+            None,
+            None,
+            false,
+        );
+        let node = &mut nodes[0];
+        node.round_manager.equivocation_evidence_enabled = enabled;
+
+        let vote = |block_id| {
+            let vote_data = VoteData::new(
+                BlockInfo::new(1, 5, block_id, HashValue::zero(), 0, 0, None),
+                BlockInfo::random(4),
+            );
+            let ledger_info = LedgerInfo::new(BlockInfo::empty(), HashValue::zero());
+            Vote::new(vote_data, node.signer.author(), ledger_info, &node.signer).unwrap()
+        };
+        let evidence =
+            vote_evidence(&vote(HashValue::random()), &vote(HashValue::random())).unwrap();
+        let block = Block::new_proposal_ext(
+            vec![ValidatorTransaction::EquivocationEvidence(evidence)],
+            Payload::empty(false, true),
+            1,
+            1,
+            certificate_for_genesis(),
+            &node.signer,
+            Vec::new(),
+        )
+        .unwrap();
+
+        timed_block_on(&runtime, async {
+            // clear the message queue
+            node.next_proposal().await;
+
+            assert_eq!(
+                enabled,
+                node.round_manager.process_proposal(block).await.is_ok()
+            );
+        });
+    }
+}
+
 #[test]
 fn no_vote_on_proposal_with_uncertified_dkg_result() {
     test_dkg_result_handling(
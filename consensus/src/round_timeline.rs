@@ -0,0 +1,121 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use lumio_consensus_types::{
+    common::Round,
+    round_timeline::{RoundEvent, RoundTimeline},
+};
+use lumio_infallible::{duration_since_epoch, Mutex};
+use std::{collections::VecDeque, sync::Arc};
+
+/// Number of most recent rounds kept in the timeline of each epoch
+const MAX_RECORDED_ROUNDS: usize = 1000;
+
+/// Number of most recent epochs whose timelines are kept
+const MAX_RECORDED_EPOCHS: usize = 2;
+
+/// Records the current time as the time of the given round event, if there's a recorder
+pub fn record_round_event(
+    recorder: Option<&RoundTimelineRecorder>,
+    round: Round,
+    event: RoundEvent,
+) {
+    if let Some(recorder) = recorder {
+        recorder.record_event(round, event);
+    }
+}
+
+/// The round timelines of the most recent epochs observed by this node. The epoch manager starts
+/// a new recorder for every epoch, and the admin service reads the timelines of all of them.
+#[derive(Clone, Default)]
+pub struct RoundTimelines {
+    epochs: Arc<Mutex<VecDeque<Arc<RoundTimelineRecorder>>>>,
+}
+
+impl RoundTimelines {
+    /// Starts recording the timeline of the given epoch, dropping the timelines of the oldest
+    /// epochs, and returns the recorder of the epoch.
+    pub fn start_epoch(&self, epoch: u64) -> Arc<RoundTimelineRecorder> {
+        let recorder = Arc::new(RoundTimelineRecorder::new(epoch, MAX_RECORDED_ROUNDS));
+        let mut epochs = self.epochs.lock();
+        epochs.retain(|recorder| recorder.epoch() != epoch);
+        epochs.push_back(recorder.clone());
+        while epochs.len() > MAX_RECORDED_EPOCHS {
+            epochs.pop_front();
+        }
+        recorder
+    }
+
+    /// Returns the recorded timelines, ordered by epoch and round
+    pub fn timelines(&self) -> Vec<RoundTimeline> {
+        let mut epochs: Vec<_> = self.epochs.lock().iter().cloned().collect();
+        epochs.sort_by_key(|recorder| recorder.epoch());
+        epochs
+            .iter()
+            .flat_map(|recorder| recorder.timelines())
+            .collect()
+    }
+}
+
+/// A bounded ring buffer of the round timelines of a single epoch, ordered by round. When full,
+/// the timelines of the oldest rounds are dropped.
+pub struct RoundTimelineRecorder {
+    epoch: u64,
+    capacity: usize,
+    rounds: Mutex<VecDeque<RoundTimeline>>,
+}
+
+impl RoundTimelineRecorder {
+    pub fn new(epoch: u64, capacity: usize) -> Self {
+        Self {
+            epoch,
+            capacity,
+            rounds: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Records the current time as the time of the given round event
+    pub fn record_event(&self, round: Round, event: RoundEvent) {
+        self.record(round, event, duration_since_epoch().as_micros() as u64);
+    }
+
+    /// Records the time of the given round event, unless it was already recorded
+    pub fn record(&self, round: Round, event: RoundEvent, timestamp_usecs: u64) {
+        let mut rounds = self.rounds.lock();
+
+        // Events are mostly for the latest round (or a new one), so check the back first
+        let index = match rounds.back() {
+            Some(latest) if latest.round == round => rounds.len() - 1,
+            Some(latest) if latest.round > round => {
+                match rounds.binary_search_by_key(&round, |timeline| timeline.round) {
+                    Ok(index) => index,
+                    // The round is older than all the recorded ones (and the buffer is full)
+                    Err(0) if rounds.len() == self.capacity => return,
+                    Err(index) => {
+                        rounds.insert(index, RoundTimeline::new(self.epoch, round));
+                        index
+                    },
+                }
+            },
+            _ => {
+                rounds.push_back(RoundTimeline::new(self.epoch, round));
+                rounds.len() - 1
+            },
+        };
+        rounds[index].events.entry(event).or_insert(timestamp_usecs);
+
+        // Drop the oldest round, if the buffer is over capacity
+        if rounds.len() > self.capacity {
+            rounds.pop_front();
+        }
+    }
+
+    /// Returns the recorded timelines, ordered by round
+    pub fn timelines(&self) -> Vec<RoundTimeline> {
+        self.rounds.lock().iter().cloned().collect()
+    }
+}
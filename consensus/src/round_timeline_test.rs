@@ -0,0 +1,97 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::round_timeline::{RoundTimelineRecorder, RoundTimelines};
+use lumio_consensus_types::round_timeline::{RoundEvent, RoundTimeline};
+
+fn recorded_rounds(timelines: Vec<RoundTimeline>) -> Vec<(u64, u64)> {
+    timelines
+        .iter()
+        .map(|timeline| (timeline.epoch, timeline.round))
+        .collect()
+}
+
+#[test]
+fn test_record_round_events() {
+    let recorder = RoundTimelineRecorder::new(1, 10);
+    recorder.record(5, RoundEvent::ProposalReceived, 100);
+    recorder.record(5, RoundEvent::VoteSent, 120);
+    // Only the first occurrence of an event is kept
+    recorder.record(5, RoundEvent::ProposalReceived, 150);
+
+    let timelines = recorder.timelines();
+    assert_eq!(timelines.len(), 1);
+    assert_eq!(timelines[0].events.iter().collect::<Vec<_>>(), vec![
+        (&RoundEvent::ProposalReceived, &100),
+        (&RoundEvent::VoteSent, &120)
+    ]);
+}
+
+#[test]
+fn test_rounds_are_ordered() {
+    let recorder = RoundTimelineRecorder::new(1, 10);
+    recorder.record(7, RoundEvent::Committed, 200);
+    recorder.record(9, RoundEvent::Executed, 250);
+    recorder.record(3, RoundEvent::ProposalReceived, 310);
+    recorder.record(8, RoundEvent::Committed, 260);
+    recorder.record(9, RoundEvent::Committed, 270);
+
+    assert_eq!(recorded_rounds(recorder.timelines()), vec![
+        (1, 3),
+        (1, 7),
+        (1, 8),
+        (1, 9)
+    ]);
+}
+
+#[test]
+fn test_oldest_rounds_are_dropped() {
+    let recorder = RoundTimelineRecorder::new(1, 3);
+    for round in 1..=5 {
+        recorder.record(round, RoundEvent::ProposalReceived, round);
+    }
+    assert_eq!(recorded_rounds(recorder.timelines()), vec![
+        (1, 3),
+        (1, 4),
+        (1, 5)
+    ]);
+
+    // Events of rounds older than the recorded ones are ignored
+    recorder.record(2, RoundEvent::Committed, 10);
+    assert_eq!(recorded_rounds(recorder.timelines()), vec![
+        (1, 3),
+        (1, 4),
+        (1, 5)
+    ]);
+
+    // While events of recorded rounds are added to them
+    recorder.record(3, RoundEvent::Committed, 10);
+    assert_eq!(recorded_rounds(recorder.timelines()), vec![
+        (1, 3),
+        (1, 4),
+        (1, 5)
+    ]);
+    assert_eq!(
+        recorder.timelines()[0].events.get(&RoundEvent::Committed),
+        Some(&10)
+    );
+}
+
+#[test]
+fn test_oldest_epochs_are_dropped() {
+    let round_timelines = RoundTimelines::default();
+    for epoch in 1..=3 {
+        let recorder = round_timelines.start_epoch(epoch);
+        recorder.record(epoch * 10, RoundEvent::ProposalReceived, epoch);
+    }
+
+    // Only the latest epochs are kept
+    assert_eq!(recorded_rounds(round_timelines.timelines()), vec![
+        (2, 20),
+        (3, 30)
+    ]);
+
+    // Restarting an epoch replaces its timeline
+    round_timelines.start_epoch(3);
+    assert_eq!(recorded_rounds(round_timelines.timelines()), vec![(2, 20)]);
+}
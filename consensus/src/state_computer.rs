@@ -3,10 +3,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    block_preparer::BlockPreparer, error::StateSyncError, monitor, network::NetworkSender,
-    payload_manager::TPayloadManager, pipeline::pipeline_builder::PipelineBuilder,
-    state_replication::StateComputer, transaction_deduper::TransactionDeduper,
-    transaction_shuffler::TransactionShuffler, txn_notifier::TxnNotifier,
+    block_preparer::BlockPreparer,
+    error::StateSyncError,
+    monitor,
+    network::NetworkSender,
+    payload_manager::TPayloadManager,
+    pipeline::pipeline_builder::PipelineBuilder,
+    record_replay::{self, TraceEvent},
+    state_replication::StateComputer,
+    transaction_deduper::TransactionDeduper,
+    transaction_shuffler::TransactionShuffler,
+    txn_notifier::TxnNotifier,
 };
 use anyhow::Result;
 use lumio_config::config::BlockTransactionFilterConfig;
@@ -150,6 +157,10 @@ impl StateComputer for ExecutionProxy {
             "sync_for_duration",
             self.state_sync_notifier.sync_for_duration(duration).await
         );
+        record_replay::record(|| TraceEvent::SyncForDuration {
+            duration_ms: duration.as_millis() as u64,
+            result: result.as_ref().map(Clone::clone).map_err(|e| e.to_string()),
+        });
 
         // Update the latest logical time
         if let Ok(latest_synced_ledger_info) = &result {
@@ -209,10 +220,17 @@ impl StateComputer for ExecutionProxy {
         // block execution and commits, the internal state of the ChunkExecutor may
         // not be up to date. So, it is required to reset the cache of the
         // ChunkExecutor in state sync when requested to sync.
+        let recorded_target = record_replay::is_recording().then(|| target.clone());
         let result = monitor!(
             "sync_to_target",
             self.state_sync_notifier.sync_to_target(target).await
         );
+        if let Some(target) = recorded_target {
+            record_replay::record(|| TraceEvent::SyncToTarget {
+                target,
+                result: result.as_ref().map(|_| ()).map_err(|e| e.to_string()),
+            });
+        }
 
         // Update the latest logical time
         *latest_logical_time = target_logical_time;
@@ -18,6 +18,7 @@ use lumio_storage_interface::DbReader;
 use lumio_types::{
     aggregate_signature::AggregateSignature,
     epoch_change::EpochChangeProof,
+    equivocation::EquivocationEvidence,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     on_chain_config::{ValidatorSet, DEFAULT_ENABLED_WINDOW_SIZE},
 };
@@ -32,6 +33,7 @@ pub struct MockSharedStorage {
 
     // Liveness state
     pub highest_2chain_timeout_certificate: Mutex<Option<TwoChainTimeoutCertificate>>,
+    pub equivocation_evidence: Mutex<Vec<EquivocationEvidence>>,
     pub validator_set: ValidatorSet,
 }
 
@@ -43,6 +45,7 @@ impl MockSharedStorage {
             lis: Mutex::new(HashMap::new()),
             last_vote: Mutex::new(None),
             highest_2chain_timeout_certificate: Mutex::new(None),
+            equivocation_evidence: Mutex::new(vec![]),
             validator_set,
         }
     }
@@ -229,6 +232,14 @@ impl PersistentLivenessStorage for MockStorage {
         Ok(())
     }
 
+    fn save_equivocation_evidence(&self, evidence: &EquivocationEvidence) -> Result<()> {
+        self.shared_storage
+            .equivocation_evidence
+            .lock()
+            .push(evidence.clone());
+        Ok(())
+    }
+
     fn retrieve_epoch_change_proof(&self, version: u64) -> Result<EpochChangeProof> {
         let lis = self
             .shared_storage
@@ -310,6 +321,10 @@ impl PersistentLivenessStorage for EmptyStorage {
         Ok(())
     }
 
+    fn save_equivocation_evidence(&self, _: &EquivocationEvidence) -> Result<()> {
+        Ok(())
+    }
+
     fn retrieve_epoch_change_proof(&self, _version: u64) -> Result<EpochChangeProof> {
         Ok(EpochChangeProof::new(vec![], false))
     }
@@ -0,0 +1,28 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::transaction_shuffler::TransactionShuffler;
+pub use lumio_block_partitioner::conflict_aware::{Config, ConflictAwareShuffler};
+use lumio_types::transaction::{
+    signature_verified_transaction::SignatureVerifiedTransaction, SignedTransaction,
+};
+
+impl TransactionShuffler for ConflictAwareShuffler {
+    fn shuffle(&self, txns: Vec<SignedTransaction>) -> Vec<SignedTransaction> {
+        self.shuffle_generic(txns)
+    }
+
+    fn signed_transaction_iterator(
+        &self,
+        txns: Vec<SignedTransaction>,
+    ) -> Box<dyn Iterator<Item = SignedTransaction> + 'static> {
+        Box::new(self.shuffle_generic(txns).into_iter())
+    }
+
+    fn signature_verified_transaction_iterator(
+        &self,
+        txns: Vec<SignatureVerifiedTransaction>,
+    ) -> Box<dyn Iterator<Item = SignatureVerifiedTransaction> + 'static> {
+        Box::new(self.shuffle_generic(txns).into_iter())
+    }
+}
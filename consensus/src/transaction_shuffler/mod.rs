@@ -10,6 +10,7 @@ use lumio_types::{
 };
 use std::sync::Arc;
 
+mod conflict_aware;
 mod use_case_aware;
 // re-export use case aware shuffler for fuzzer.
 #[cfg(feature = "fuzzing")]
@@ -97,5 +98,19 @@ pub fn create_transaction_shuffler(
             );
             Arc::new(use_case_aware::UseCaseAwareShuffler { config })
         },
+        ConflictAware {
+            conflict_window,
+            max_lookahead,
+        } => {
+            let config = conflict_aware::Config {
+                conflict_window,
+                max_lookahead,
+            };
+            info!(
+                config = ?config,
+                "Using conflict aware transaction shuffling."
+            );
+            Arc::new(conflict_aware::ConflictAwareShuffler { config })
+        },
     }
 }
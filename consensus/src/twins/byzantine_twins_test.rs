@@ -0,0 +1,172 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::twins::scenario::{
+    Action::{Delay, Drop, Duplicate, Equivocate, ForgeTimeoutCertificate},
+    Adversary,
+    MessageKind::{CommitVote, Proposal, Vote},
+    Rule, Scenario,
+};
+use lumio_consensus_types::common::Round;
+use std::collections::HashMap;
+
+#[test]
+/// This test checks that a proposer sending conflicting proposals to different
+/// nodes can't break safety.
+///
+/// Setup:
+///
+/// 4 nodes, n0 is Byzantine and the proposer of rounds 1..10.
+/// n0 sends n1 a different proposal than the one it sends to n2 and n3.
+///
+/// Test:
+///
+/// The honest nodes never commit different blocks in the same round, and keep
+/// committing.
+///
+/// Run the test:
+/// cargo xtest -p consensus equivocating_proposer_test -- --nocapture
+fn equivocating_proposer_test() {
+    let round_proposers: HashMap<Round, usize> = (1..10).map(|round| (round, 0)).collect();
+    let outcome = Scenario::new(4)
+        .round_proposers(round_proposers)
+        .adversary(
+            0,
+            Adversary::new().rule(Rule::new(Proposal, Equivocate).to_nodes([1])),
+        )
+        .run_until_round(10);
+
+    outcome.assert_safety();
+    outcome.assert_liveness(15);
+}
+
+#[test]
+/// This test checks that consensus makes progress when a validator withholds
+/// all its votes.
+///
+/// Setup:
+///
+/// 4 nodes, n3 is Byzantine and never sends a vote.
+///
+/// Test:
+///
+/// The 3 honest nodes form quorums on their own and commit.
+///
+/// Run the test:
+/// cargo xtest -p consensus withheld_votes_test -- --nocapture
+fn withheld_votes_test() {
+    let outcome = Scenario::new(4)
+        .adversary(3, Adversary::new().rule(Rule::new(Vote, Drop)))
+        .run_until_round(5);
+
+    outcome.assert_safety();
+    outcome.assert_liveness(10);
+}
+
+#[test]
+/// This test checks that duplicated and reordered votes are harmless.
+///
+/// Setup:
+///
+/// 4 nodes, n2 is Byzantine: it sends each of its votes to n0 twice, and
+/// holds its votes to the other nodes back until it sent them 2 more messages.
+///
+/// Test:
+///
+/// The honest nodes commit consistently.
+///
+/// Run the test:
+/// cargo xtest -p consensus delayed_and_duplicated_votes_test -- --nocapture
+fn delayed_and_duplicated_votes_test() {
+    let outcome = Scenario::new(4)
+        .adversary(
+            2,
+            Adversary::new()
+                .rule(Rule::new(Vote, Duplicate(2)).to_nodes([0]))
+                .rule(Rule::new(Vote, Delay(2))),
+        )
+        .run_until_round(5);
+
+    outcome.assert_safety();
+    outcome.assert_liveness(10);
+}
+
+#[test]
+/// This test checks that a timeout certificate signed by a single validator
+/// is rejected.
+///
+/// Setup:
+///
+/// 4 nodes, n1 is Byzantine and attaches a timeout certificate for 5 rounds
+/// ahead, signed only by itself, to each vote it sends in rounds 2..6.
+///
+/// Test:
+///
+/// The honest nodes don't jump ahead on the forged certificate, and keep
+/// committing consistently.
+///
+/// Run the test:
+/// cargo xtest -p consensus forged_timeout_certificate_test -- --nocapture
+fn forged_timeout_certificate_test() {
+    let outcome = Scenario::new(4)
+        .adversary(
+            1,
+            Adversary::new().rule(Rule::new(Vote, ForgeTimeoutCertificate(5)).in_rounds(2..=6)),
+        )
+        .run_until_round(8);
+
+    outcome.assert_safety();
+    outcome.assert_liveness(12);
+}
+
+#[test]
+/// This test checks that commit votes arriving late don't stall or fork the
+/// execution pipeline.
+///
+/// Setup:
+///
+/// 4 nodes, n3 is Byzantine and holds each of its commit votes back until it
+/// sent 3 more messages to the same node.
+///
+/// Test:
+///
+/// The honest nodes aggregate commit decisions without waiting for n3, take
+/// its late votes for already committed blocks in stride, and commit
+/// consistently.
+///
+/// Run the test:
+/// cargo xtest -p consensus late_commit_votes_test -- --nocapture
+fn late_commit_votes_test() {
+    let outcome = Scenario::new(4)
+        .adversary(3, Adversary::new().rule(Rule::new(CommitVote, Delay(3))))
+        .run_until_round(6);
+
+    outcome.assert_safety();
+    outcome.assert_liveness(10);
+}
+
+#[test]
+/// This test checks that duplicated commit votes are harmless.
+///
+/// Setup:
+///
+/// 4 nodes, n3 is Byzantine and sends each of its commit votes 3 times.
+///
+/// Test:
+///
+/// The buffer managers of the honest nodes take the copies in stride, and
+/// commit consistently.
+///
+/// Run the test:
+/// cargo xtest -p consensus duplicated_commit_votes_test -- --nocapture
+fn duplicated_commit_votes_test() {
+    let outcome = Scenario::new(4)
+        .adversary(
+            3,
+            Adversary::new().rule(Rule::new(CommitVote, Duplicate(3))),
+        )
+        .run_until_round(6);
+
+    outcome.assert_safety();
+    outcome.assert_liveness(10);
+}
@@ -3,4 +3,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod basic_twins_test;
+mod byzantine_twins_test;
+mod scenario;
 mod twins_node;
@@ -0,0 +1,546 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Scripted Byzantine scenarios on top of twins.
+//!
+//! A [`Scenario`] starts a set of validators on the [`NetworkPlayground`], and turns up to f of
+//! them into adversaries. An [`Adversary`] runs the honest consensus code, but every message and
+//! rpc request it sends goes through its [`Rule`]s first, which can drop, duplicate, delay or
+//! rewrite it, e.g. to equivocate or to attach forged certificates. The [`ScenarioOutcome`] holds
+//! what the honest validators committed, with helpers to assert safety and liveness.
+
+use crate::{
+    network_interface::ConsensusMsg,
+    network_tests::{MessageMutator, NetworkPlayground, TwinId},
+    pipeline::commit_reliable_broadcast::CommitMessage,
+    test_utils::consensus_runtime,
+    twins::twins_node::SMRNode,
+};
+use lumio_consensus_types::{
+    block::Block,
+    common::Round,
+    proposal_msg::ProposalMsg,
+    sync_info::SyncInfo,
+    timeout_2chain::{TwoChainTimeout, TwoChainTimeoutWithPartialSignatures},
+    vote_msg::VoteMsg,
+};
+use lumio_crypto::HashValue;
+use lumio_infallible::Mutex;
+use lumio_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::ProposerElectionType::{self, RotatingProposer},
+    validator_signer::ValidatorSigner,
+    validator_verifier::ValidatorVerifier,
+};
+use futures::StreamExt;
+use std::{
+    collections::{HashMap, HashSet},
+    ops::RangeInclusive,
+    sync::Arc,
+    time::Duration,
+};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MessageKind {
+    Proposal,
+    Vote,
+    OrderVote,
+    RoundTimeout,
+    SyncInfo,
+    CommitVote,
+    CommitDecision,
+}
+
+impl MessageKind {
+    fn of(msg: &ConsensusMsg) -> Option<Self> {
+        Some(match msg {
+            ConsensusMsg::ProposalMsg(_) | ConsensusMsg::OptProposalMsg(_) => Self::Proposal,
+            ConsensusMsg::VoteMsg(_) => Self::Vote,
+            ConsensusMsg::OrderVoteMsg(_) => Self::OrderVote,
+            ConsensusMsg::RoundTimeoutMsg(_) => Self::RoundTimeout,
+            ConsensusMsg::SyncInfo(_) => Self::SyncInfo,
+            ConsensusMsg::CommitVoteMsg(_) => Self::CommitVote,
+            ConsensusMsg::CommitDecisionMsg(_) => Self::CommitDecision,
+            // The buffer manager sends its commit messages as rpc requests.
+            ConsensusMsg::CommitMessage(m) => match m.as_ref() {
+                CommitMessage::Vote(_) => Self::CommitVote,
+                CommitMessage::Decision(_) => Self::CommitDecision,
+                CommitMessage::Ack(_) | CommitMessage::Nack => return None,
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// Returns the round a message is about, if it's about one.
+fn message_round(msg: &ConsensusMsg) -> Option<Round> {
+    Some(match msg {
+        ConsensusMsg::ProposalMsg(m) => m.proposal().round(),
+        ConsensusMsg::OptProposalMsg(m) => m.round(),
+        ConsensusMsg::VoteMsg(m) => m.vote().vote_data().proposed().round(),
+        ConsensusMsg::OrderVoteMsg(m) => m.order_vote().ledger_info().commit_info().round(),
+        ConsensusMsg::RoundTimeoutMsg(m) => m.round(),
+        ConsensusMsg::SyncInfo(m) => m.highest_round(),
+        ConsensusMsg::CommitVoteMsg(m) => m.commit_info().round(),
+        ConsensusMsg::CommitDecisionMsg(m) => m.ledger_info().commit_info().round(),
+        ConsensusMsg::CommitMessage(m) => match m.as_ref() {
+            CommitMessage::Vote(vote) => vote.commit_info().round(),
+            CommitMessage::Decision(decision) => decision.ledger_info().commit_info().round(),
+            CommitMessage::Ack(_) | CommitMessage::Nack => return None,
+        },
+        _ => return None,
+    })
+}
+
+pub type MutateFn = Arc<dyn Fn(&TwinId, ConsensusMsg) -> Vec<ConsensusMsg> + Send + Sync>;
+
+#[derive(Clone)]
+pub enum Action {
+    /// Doesn't send the message.
+    Drop,
+    /// Sends the message this many times.
+    Duplicate(usize),
+    /// Holds the message back until this many more messages were sent to the same peer. A held
+    /// back rpc request is delivered without its response, so the sender retries it.
+    Delay(usize),
+    /// Sends a conflicting proposal for the same round instead. Only applies to proposals.
+    Equivocate,
+    /// Attaches a timeout certificate signed by the adversary alone, this many rounds above the
+    /// highest quorum cert. Only applies to votes and sync infos.
+    ForgeTimeoutCertificate(Round),
+    /// Any other rewrite of the message.
+    Mutate(MutateFn),
+}
+
+/// Applies an [`Action`] to the messages of a kind, optionally only in some rounds and to some
+/// nodes.
+#[derive(Clone)]
+pub struct Rule {
+    kind: MessageKind,
+    rounds: Option<RangeInclusive<Round>>,
+    to: Option<HashSet<usize>>,
+    action: Action,
+}
+
+impl Rule {
+    pub fn new(kind: MessageKind, action: Action) -> Self {
+        Self {
+            kind,
+            rounds: None,
+            to: None,
+            action,
+        }
+    }
+
+    pub fn in_rounds(mut self, rounds: RangeInclusive<Round>) -> Self {
+        self.rounds = Some(rounds);
+        self
+    }
+
+    /// Only applies to messages sent to the nodes with these indices.
+    pub fn to_nodes(mut self, nodes: impl IntoIterator<Item = usize>) -> Self {
+        self.to = Some(nodes.into_iter().collect());
+        self
+    }
+
+    fn matches(&self, dst: &TwinId, msg: &ConsensusMsg) -> bool {
+        MessageKind::of(msg) == Some(self.kind)
+            && self.to.as_ref().map_or(true, |to| to.contains(&dst.id))
+            && self.rounds.as_ref().map_or(true, |rounds| {
+                message_round(msg).is_some_and(|round| rounds.contains(&round))
+            })
+    }
+}
+
+/// The behaviour of a Byzantine validator: the first matching rule applies to each message it
+/// sends, and the ones no rule matches are sent unchanged.
+#[derive(Clone, Default)]
+pub struct Adversary {
+    rules: Vec<Rule>,
+}
+
+impl Adversary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+}
+
+/// An [`Adversary`] installed on a node, with what it needs to sign the messages it makes up.
+struct AdversaryMutator {
+    rules: Vec<Rule>,
+    signer: ValidatorSigner,
+    verifier: ValidatorVerifier,
+    /// Delayed messages per destination, with the number of messages still to send before them.
+    delayed: Mutex<HashMap<TwinId, Vec<(usize, ConsensusMsg)>>>,
+}
+
+impl AdversaryMutator {
+    fn apply(&self, dst: &TwinId, msg: ConsensusMsg) -> Vec<ConsensusMsg> {
+        let Some(rule) = self.rules.iter().find(|rule| rule.matches(dst, &msg)) else {
+            return vec![msg];
+        };
+        match &rule.action {
+            Action::Drop => vec![],
+            Action::Duplicate(copies) => vec![msg; *copies],
+            Action::Delay(messages) => {
+                self.delayed
+                    .lock()
+                    .entry(*dst)
+                    .or_default()
+                    .push((*messages, msg));
+                vec![]
+            },
+            Action::Equivocate => match &msg {
+                ConsensusMsg::ProposalMsg(proposal) => self
+                    .conflicting_proposal(proposal)
+                    .map_or(vec![msg.clone()], |conflicting| vec![conflicting]),
+                _ => vec![msg],
+            },
+            Action::ForgeTimeoutCertificate(rounds_ahead) => match &msg {
+                ConsensusMsg::VoteMsg(vote) => self
+                    .forge_sync_info(vote.sync_info(), *rounds_ahead)
+                    .map_or(vec![msg.clone()], |sync_info| {
+                        vec![ConsensusMsg::VoteMsg(Box::new(VoteMsg::new(
+                            vote.vote().clone(),
+                            sync_info,
+                        )))]
+                    }),
+                ConsensusMsg::SyncInfo(sync_info) => self
+                    .forge_sync_info(sync_info, *rounds_ahead)
+                    .map_or(vec![msg.clone()], |sync_info| {
+                        vec![ConsensusMsg::SyncInfo(Box::new(sync_info))]
+                    }),
+                _ => vec![msg],
+            },
+            Action::Mutate(mutate) => mutate(dst, msg),
+        }
+    }
+
+    /// Counts one more message sent to `dst`, and returns the delayed ones that are now due.
+    fn release_delayed(&self, dst: &TwinId) -> Vec<ConsensusMsg> {
+        let mut delayed = self.delayed.lock();
+        let Some(pending) = delayed.get_mut(dst) else {
+            return vec![];
+        };
+        let mut due = vec![];
+        pending.retain_mut(|(remaining, msg)| {
+            *remaining = remaining.saturating_sub(1);
+            if *remaining == 0 {
+                due.push(msg.clone());
+            }
+            *remaining > 0
+        });
+        due
+    }
+
+    /// A proposal for the same round and parent, with a different timestamp so it has another id.
+    fn conflicting_proposal(&self, proposal: &ProposalMsg) -> Option<ConsensusMsg> {
+        let block = proposal.proposal();
+        let conflicting = Block::new_proposal(
+            block.payload()?.clone(),
+            block.round(),
+            block.timestamp_usecs() + 1,
+            block.quorum_cert().clone(),
+            &self.signer,
+            block.block_data().failed_authors()?.clone(),
+        )
+        .ok()?;
+        Some(ConsensusMsg::ProposalMsg(Box::new(ProposalMsg::new(
+            conflicting,
+            proposal.sync_info().clone(),
+        ))))
+    }
+
+    fn forge_sync_info(&self, sync_info: &SyncInfo, rounds_ahead: Round) -> Option<SyncInfo> {
+        let qc = sync_info.highest_quorum_cert().clone();
+        let timeout = TwoChainTimeout::new(
+            qc.certified_block().epoch(),
+            qc.certified_block().round() + rounds_ahead,
+            qc.clone(),
+        );
+        let signature = timeout.sign(&self.signer).ok()?;
+        let mut partial_tc = TwoChainTimeoutWithPartialSignatures::new(timeout.clone());
+        partial_tc.add(self.signer.author(), timeout, signature);
+        let forged_tc = partial_tc.aggregate_signatures(&self.verifier).ok()?;
+        Some(SyncInfo::new_decoupled(
+            qc,
+            sync_info.highest_ordered_cert(),
+            sync_info.highest_commit_cert().clone(),
+            Some(forged_tc),
+        ))
+    }
+}
+
+impl MessageMutator for AdversaryMutator {
+    fn mutate(&self, dst: &TwinId, msg: ConsensusMsg) -> Vec<ConsensusMsg> {
+        // Released after the message that made them due, which doesn't count itself.
+        let released = self.release_delayed(dst);
+        let mut msgs = self.apply(dst, msg);
+        msgs.extend(released);
+        msgs
+    }
+}
+
+/// A set of validators, some of them adversaries, run until the honest ones commit a round.
+pub struct Scenario {
+    num_nodes: usize,
+    proposer_type: ProposerElectionType,
+    round_proposers: Option<HashMap<Round, usize>>,
+    adversaries: HashMap<usize, Adversary>,
+    timeout: Duration,
+}
+
+impl Scenario {
+    pub fn new(num_nodes: usize) -> Self {
+        Self {
+            num_nodes,
+            proposer_type: RotatingProposer(1),
+            round_proposers: None,
+            adversaries: HashMap::new(),
+            timeout: Duration::from_secs(60),
+        }
+    }
+
+    /// Picks the proposer of each round by node index, other rounds go to the first node.
+    pub fn round_proposers(mut self, round_proposers: HashMap<Round, usize>) -> Self {
+        self.proposer_type = ProposerElectionType::RoundProposer(HashMap::new());
+        self.round_proposers = Some(round_proposers);
+        self
+    }
+
+    /// Turns the node with index `node` into `adversary`.
+    pub fn adversary(mut self, node: usize, adversary: Adversary) -> Self {
+        assert!(node < self.num_nodes, "No node {}", node);
+        self.adversaries.insert(node, adversary);
+        self
+    }
+
+    /// How long to wait for the honest nodes to reach the target round.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Runs consensus until every honest node committed a block of `round` or higher, or the
+    /// timeout expires.
+    pub fn run_until_round(self, round: Round) -> ScenarioOutcome {
+        let max_faulty = (self.num_nodes - 1) / 3;
+        assert!(
+            self.adversaries.len() <= max_faulty,
+            "{} adversaries among {} nodes, at most {} are tolerated",
+            self.adversaries.len(),
+            self.num_nodes,
+            max_faulty
+        );
+
+        let honest: Vec<_> = (0..self.num_nodes)
+            .filter(|index| !self.adversaries.contains_key(index))
+            .collect();
+
+        let runtime = consensus_runtime();
+        let mut playground = NetworkPlayground::new(runtime.handle().clone());
+        let mut nodes = SMRNode::start_num_nodes_with_twins(
+            self.num_nodes,
+            0,
+            &mut playground,
+            self.proposer_type,
+            self.round_proposers,
+        );
+        // Nothing is delivered before the playground starts, so no message skips the mutators.
+        for (index, adversary) in self.adversaries {
+            let node = &nodes[index];
+            playground.set_message_mutator(
+                node.id,
+                Arc::new(AdversaryMutator {
+                    rules: adversary.rules,
+                    signer: node.signer.clone(),
+                    verifier: node.storage.get_validator_set().into(),
+                    delayed: Mutex::new(HashMap::new()),
+                }),
+            );
+        }
+        runtime.spawn(playground.start());
+
+        let mut commits: HashMap<usize, Vec<LedgerInfoWithSignatures>> = HashMap::new();
+        runtime.block_on(async {
+            let _ = tokio::time::timeout(self.timeout, async {
+                for index in &honest {
+                    let node_commits = commits.entry(*index).or_default();
+                    while node_commits
+                        .last()
+                        .map_or(true, |commit| commit.commit_info().round() < round)
+                    {
+                        match nodes[*index].commit_cb_receiver.next().await {
+                            Some(commit) => node_commits.push(commit),
+                            None => break,
+                        }
+                    }
+                }
+            })
+            .await;
+        });
+        // Also collect what was committed after each node was waited for.
+        for index in &honest {
+            while let Ok(Some(commit)) = nodes[*index].commit_cb_receiver.try_next() {
+                commits.entry(*index).or_default().push(commit);
+            }
+        }
+
+        ScenarioOutcome { honest, commits }
+    }
+}
+
+/// What the honest nodes committed in a [`Scenario`], by node index.
+pub struct ScenarioOutcome {
+    pub honest: Vec<usize>,
+    pub commits: HashMap<usize, Vec<LedgerInfoWithSignatures>>,
+}
+
+impl ScenarioOutcome {
+    /// Panics if two honest nodes committed different blocks in the same round.
+    pub fn assert_safety(&self) {
+        let mut committed: HashMap<(u64, Round), (usize, HashValue)> = HashMap::new();
+        for (index, commits) in &self.commits {
+            for commit in commits {
+                let info = commit.commit_info();
+                let (other, other_id) = *committed
+                    .entry((info.epoch(), info.round()))
+                    .or_insert((*index, info.id()));
+                assert_eq!(
+                    other_id,
+                    info.id(),
+                    "Conflicting commits in epoch {} round {}: node {} committed {}, node {} committed {}",
+                    info.epoch(),
+                    info.round(),
+                    other,
+                    other_id,
+                    index,
+                    info.id(),
+                );
+            }
+        }
+    }
+
+    /// Panics if an honest node didn't commit any block of round `within_rounds` or lower.
+    pub fn assert_liveness(&self, within_rounds: Round) {
+        for index in &self.honest {
+            assert!(
+                self.commits.get(index).is_some_and(|commits| commits
+                    .iter()
+                    .any(|commit| commit.commit_info().round() <= within_rounds)),
+                "Node {} committed nothing within {} rounds",
+                index,
+                within_rounds
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumio_consensus_types::{
+        block::block_test_utils::certificate_for_genesis, pipeline::commit_vote::CommitVote,
+    };
+    use lumio_types::{
+        account_address::AccountAddress, block_info::BlockInfo, ledger_info::LedgerInfo,
+        validator_verifier::random_validator_verifier,
+    };
+
+    fn sync_info_msg() -> ConsensusMsg {
+        let genesis_qc = certificate_for_genesis();
+        ConsensusMsg::SyncInfo(Box::new(SyncInfo::new(
+            genesis_qc.clone(),
+            genesis_qc.into_wrapped_ledger_info(),
+            None,
+        )))
+    }
+
+    fn mutator(adversary: Adversary) -> AdversaryMutator {
+        let (signers, verifier) = random_validator_verifier(1, None, false);
+        AdversaryMutator {
+            rules: adversary.rules,
+            signer: signers[0].clone(),
+            verifier,
+            delayed: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn twin(id: usize) -> TwinId {
+        TwinId {
+            id,
+            author: AccountAddress::random(),
+        }
+    }
+
+    #[test]
+    fn test_rules_apply_to_matching_messages() {
+        let mutator = mutator(
+            Adversary::new()
+                .rule(Rule::new(MessageKind::SyncInfo, Action::Drop).to_nodes([1]))
+                .rule(Rule::new(MessageKind::SyncInfo, Action::Duplicate(3)).in_rounds(0..=0))
+                .rule(Rule::new(MessageKind::Vote, Action::Drop)),
+        );
+        assert!(mutator.mutate(&twin(1), sync_info_msg()).is_empty());
+        assert_eq!(mutator.mutate(&twin(2), sync_info_msg()).len(), 3);
+
+        let mutator = mutator(
+            Adversary::new().rule(Rule::new(MessageKind::SyncInfo, Action::Drop).in_rounds(1..=5)),
+        );
+        assert_eq!(mutator.mutate(&twin(2), sync_info_msg()).len(), 1);
+    }
+
+    #[test]
+    fn test_delayed_messages_are_released_in_order() {
+        let mutated: MutateFn = Arc::new(|_: &TwinId, msg: ConsensusMsg| vec![msg.clone(), msg]);
+        let mutator = mutator(
+            Adversary::new()
+                .rule(Rule::new(MessageKind::SyncInfo, Action::Delay(2)).to_nodes([1]))
+                .rule(Rule::new(MessageKind::SyncInfo, Action::Mutate(mutated))),
+        );
+        let (n1, n2) = (twin(1), twin(2));
+
+        // Delayed until 2 more messages are sent to n1, messages to n2 don't count.
+        assert!(mutator.mutate(&n1, sync_info_msg()).is_empty());
+        assert_eq!(mutator.mutate(&n2, sync_info_msg()).len(), 2);
+        assert!(mutator.mutate(&n1, sync_info_msg()).is_empty());
+        // Each of the next messages to n1 is delayed itself, and releases the one from 2 before.
+        assert_eq!(mutator.mutate(&n1, sync_info_msg()).len(), 1);
+        assert_eq!(mutator.mutate(&n1, sync_info_msg()).len(), 1);
+        assert_eq!(mutator.mutate(&n1, sync_info_msg()).len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 1 are tolerated")]
+    fn test_scenario_rejects_too_many_adversaries() {
+        Scenario::new(4)
+            .adversary(0, Adversary::new())
+            .adversary(1, Adversary::new())
+            .timeout(Duration::from_secs(1))
+            .run_until_round(1);
+    }
+
+    #[test]
+    fn test_rules_apply_to_commit_rpcs() {
+        let (signers, _) = random_validator_verifier(1, None, false);
+        let ledger_info = LedgerInfo::new(BlockInfo::random(3), HashValue::zero());
+        let commit_vote = CommitVote::new(signers[0].author(), ledger_info, &signers[0]).unwrap();
+        let commit_rpc = ConsensusMsg::CommitMessage(Box::new(CommitMessage::Vote(commit_vote)));
+        let ack = ConsensusMsg::CommitMessage(Box::new(CommitMessage::Ack(())));
+        assert_eq!(MessageKind::of(&commit_rpc), Some(MessageKind::CommitVote));
+        assert_eq!(message_round(&commit_rpc), Some(3));
+        assert_eq!(MessageKind::of(&ack), None);
+
+        let mutator = mutator(
+            Adversary::new()
+                .rule(Rule::new(MessageKind::CommitVote, Action::Duplicate(2)).in_rounds(3..=3)),
+        );
+        assert_eq!(mutator.mutate(&twin(1), commit_rpc).len(), 2);
+        assert_eq!(mutator.mutate(&twin(1), ack).len(), 1);
+    }
+}
@@ -46,6 +46,7 @@ use lumio_types::{
     },
     transaction::SignedTransaction,
     validator_info::ValidatorInfo,
+    validator_signer::ValidatorSigner,
     waypoint::Waypoint,
 };
 use lumio_validator_transaction_pool::VTxnPoolState;
@@ -58,6 +59,7 @@ use tokio::runtime::Runtime;
 pub struct SMRNode {
     pub id: TwinId,
     pub storage: Arc<MockStorage>,
+    pub signer: ValidatorSigner,
     pub commit_cb_receiver: mpsc::UnboundedReceiver<LedgerInfoWithSignatures>,
     _runtime: Runtime,
     _shared_mempool: MockSharedMempool,
@@ -82,6 +84,12 @@ impl SMRNode {
         let runtime = lumio_runtimes::spawn_named_runtime(thread_name, None);
         let _entered_runtime = runtime.enter();
 
+        let sr_test_config = config.consensus.safety_rules.test.as_ref().unwrap();
+        let signer = ValidatorSigner::new(
+            sr_test_config.author,
+            Arc::new(sr_test_config.consensus_key.as_ref().unwrap().private_key()),
+        );
+
         // Setup the network and SMR node
         let (network_reqs_tx, network_reqs_rx) = lumio_channel::new(QueueStyle::FIFO, 8, None);
         let (connection_reqs_tx, _) = lumio_channel::new(QueueStyle::FIFO, 8, None);
@@ -190,6 +198,7 @@ impl SMRNode {
             _runtime: runtime,
             commit_cb_receiver,
             storage,
+            signer,
             _shared_mempool: shared_mempool,
             _state_sync: state_sync,
         }
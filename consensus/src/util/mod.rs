@@ -16,11 +16,13 @@ pub mod time_service;
 pub fn is_vtxn_expected(
     randomness_config: &OnChainRandomnessConfig,
     jwk_consensus_config: &OnChainJWKConsensusConfig,
+    equivocation_evidence_enabled: bool,
     vtxn: &ValidatorTransaction,
 ) -> bool {
     match vtxn {
         ValidatorTransaction::DKGResult(_) => randomness_config.randomness_enabled(),
         ValidatorTransaction::ObservedJWKUpdate(_) => jwk_consensus_config.jwk_consensus_enabled(),
+        ValidatorTransaction::EquivocationEvidence(_) => equivocation_evidence_enabled,
     }
 }
 
@@ -3,13 +3,16 @@
 
 use anyhow::{bail, Error};
 use lumio_consensus::{
-    persistent_liveness_storage::PersistentLivenessStorage,
+    explain_leader_reputation, persistent_liveness_storage::PersistentLivenessStorage,
     quorum_store::quorum_store_db::QuorumStoreStorage, util::db_tool::extract_txns_from_block,
+    RoundTimelines,
 };
+use lumio_consensus_types::round_timeline::RoundTimelineDump;
 use lumio_crypto::HashValue;
 use lumio_logger::info;
+use lumio_storage_interface::DbReader;
 use lumio_system_utils::utils::{reply_with, reply_with_status, spawn_blocking};
-use lumio_types::transaction::Transaction;
+use lumio_types::{equivocation::EquivocationEvidence, transaction::Transaction, PeerId};
 use http::header::{HeaderValue, CONTENT_LENGTH};
 use hyper::{Body, Request, Response, StatusCode};
 use std::{collections::HashMap, sync::Arc};
@@ -127,6 +130,158 @@ pub async fn handle_dump_block_request(
     }
 }
 
+pub async fn handle_dump_equivocation_evidence_request(
+    req: Request<Body>,
+    consensus_db: Arc<dyn PersistentLivenessStorage>,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let bcs: bool = match query_pairs.get("bcs") {
+        Some(val) => match val.parse() {
+            Ok(val) => val,
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => false,
+    };
+
+    info!("Dumping equivocation evidence.");
+
+    match spawn_blocking(move || {
+        let evidence = consensus_db.consensus_db().get_equivocation_evidence()?;
+        if bcs {
+            bcs::to_bytes(&evidence)
+                .map(Into::<Body>::into)
+                .map_err(Error::msg)
+        } else {
+            Ok(dump_equivocation_evidence(&evidence).into())
+        }
+    })
+    .await
+    {
+        Ok(result) => {
+            info!("Finished dumping equivocation evidence.");
+            Ok(reply_with(vec![], result))
+        },
+        Err(e) => {
+            info!("Failed to dump equivocation evidence: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+pub async fn handle_leader_reputation_request(
+    req: Request<Body>,
+    lumio_db: Arc<dyn DbReader>,
+    window_for_chain_health: usize,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let parse_u64 = |name: &str| -> Result<Option<u64>, String> {
+        query_pairs
+            .get(name)
+            .map(|val| val.parse().map_err(|e| format!("Invalid {name}: {e}")))
+            .transpose()
+    };
+    let (epoch, start_round, end_round) = match (
+        parse_u64("epoch"),
+        parse_u64("start_round"),
+        parse_u64("end_round"),
+    ) {
+        (Ok(Some(epoch)), Ok(Some(start_round)), Ok(end_round)) => {
+            (epoch, start_round, end_round.unwrap_or(start_round))
+        },
+        (Err(err), _, _) | (_, Err(err), _) | (_, _, Err(err)) => {
+            return Ok(reply_with_status(StatusCode::BAD_REQUEST, err))
+        },
+        _ => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                "epoch and start_round are required.",
+            ))
+        },
+    };
+
+    info!("Explaining leader reputation of epoch {epoch}, rounds {start_round} to {end_round}.");
+
+    match spawn_blocking(move || {
+        let explanations = explain_leader_reputation(
+            lumio_db,
+            epoch,
+            start_round..=end_round,
+            window_for_chain_health,
+        )?;
+        Ok(serde_json::to_string_pretty(&explanations)?)
+    })
+    .await
+    {
+        Ok(result) => {
+            info!("Finished explaining leader reputation.");
+            Ok(reply_with(vec![], result))
+        },
+        Err(e) => {
+            info!("Failed to explain leader reputation: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+pub async fn handle_round_timeline_request(
+    req: Request<Body>,
+    author: Option<PeerId>,
+    round_timelines: RoundTimelines,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let parse_u64 = |name: &str| -> Result<Option<u64>, String> {
+        query_pairs
+            .get(name)
+            .map(|val| val.parse().map_err(|e| format!("Invalid {name}: {e}")))
+            .transpose()
+    };
+    let (epoch, start_round, end_round) = match (
+        parse_u64("epoch"),
+        parse_u64("start_round"),
+        parse_u64("end_round"),
+    ) {
+        (Ok(epoch), Ok(start_round), Ok(end_round)) => (epoch, start_round, end_round),
+        (Err(err), _, _) | (_, Err(err), _) | (_, _, Err(err)) => {
+            return Ok(reply_with_status(StatusCode::BAD_REQUEST, err))
+        },
+    };
+
+    info!("Dumping round timeline.");
+
+    let rounds = round_timelines
+        .timelines()
+        .into_iter()
+        .filter(|timeline| {
+            epoch.is_none_or(|epoch| timeline.epoch == epoch)
+                && start_round.is_none_or(|start_round| timeline.round >= start_round)
+                && end_round.is_none_or(|end_round| timeline.round <= end_round)
+        })
+        .collect();
+    let dump = RoundTimelineDump { author, rounds };
+    match serde_json::to_string_pretty(&dump) {
+        Ok(result) => Ok(reply_with(vec![], result)),
+        Err(e) => {
+            info!("Failed to dump round timeline: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
 fn dump_consensus_db(consensus_db: &dyn PersistentLivenessStorage) -> anyhow::Result<String> {
     let mut body = String::new();
 
@@ -238,3 +393,22 @@ fn dump_blocks_bcs(
 
     bcs::to_bytes(&all_txns).map_err(Error::msg)
 }
+
+fn dump_equivocation_evidence(evidence: &[EquivocationEvidence]) -> String {
+    if evidence.is_empty() {
+        return "Done, no equivocation is found.".to_string();
+    }
+
+    let mut body = String::new();
+    for evidence in evidence {
+        body.push_str(&format!(
+            "[author: {}, epoch: {}, round: {}, kind: {:?}]\n{:?}\n\n",
+            evidence.author,
+            evidence.epoch,
+            evidence.round,
+            evidence.kind(),
+            evidence.proof,
+        ));
+    }
+    body
+}
@@ -0,0 +1,170 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Error};
+use lumio_block_executor::conflict_analytics::{
+    is_conflict_analytics_enabled, recent_block_conflict_analytics,
+};
+use lumio_logger::info;
+use lumio_move_debugger::lumio_debugger::trace_user_transaction;
+use lumio_storage_interface::{
+    state_store::state_view::db_state_view::DbStateViewAtVersion, DbReader,
+};
+use lumio_system_utils::utils::{reply_with, reply_with_status, spawn_blocking};
+use lumio_types::transaction::{AuxiliaryInfo, PersistedAuxiliaryInfo, Transaction, Version};
+use hyper::{Body, Request, Response, StatusCode};
+use std::{collections::HashMap, sync::Arc};
+
+pub async fn handle_execution_trace_request(
+    req: Request<Body>,
+    lumio_db: Arc<dyn DbReader>,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let version: Version = match query_pairs.get("version") {
+        Some(val) => match val.parse() {
+            Ok(val) => val,
+            Err(err) => {
+                return Ok(reply_with_status(
+                    StatusCode::BAD_REQUEST,
+                    format!("Invalid version: {err}"),
+                ))
+            },
+        },
+        None => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                "version is required.",
+            ))
+        },
+    };
+
+    info!("Tracing the transaction at version {version}.");
+
+    match spawn_blocking(move || trace_transaction(lumio_db, version)).await {
+        Ok(result) => {
+            info!("Finished tracing the transaction.");
+            Ok(reply_with(vec![], result))
+        },
+        Err(e) => {
+            info!("Failed to trace the transaction: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+/// Re-executes the committed user transaction at the given version on top of the state right
+/// before it, and returns its execution trace as JSON.
+fn trace_transaction(lumio_db: Arc<dyn DbReader>, version: Version) -> Result<String, Error> {
+    let ledger_version = lumio_db.get_latest_ledger_info_version()?;
+    if version > ledger_version {
+        bail!("Version {version} is not committed yet (latest version is {ledger_version}).");
+    }
+
+    let txn = match lumio_db
+        .get_transaction_by_version(version, ledger_version, false)?
+        .transaction
+    {
+        Transaction::UserTransaction(txn) => txn,
+        txn => bail!(
+            "Only user transactions can be traced, found {}.",
+            txn.type_name()
+        ),
+    };
+    let hash = txn.committed_hash();
+    let persisted_auxiliary_info = lumio_db
+        .get_persisted_auxiliary_info_iterator(version, 1)?
+        .next()
+        .transpose()?
+        .unwrap_or(PersistedAuxiliaryInfo::None);
+
+    // Version 0 is the genesis transaction, so a user transaction always has a parent state.
+    let state_view = lumio_db.state_view_at_version(Some(version - 1))?;
+    let (vm_status, _, trace) = trace_user_transaction(
+        &state_view,
+        txn,
+        AuxiliaryInfo::new(persisted_auxiliary_info, None),
+    )?;
+
+    Ok(serde_json::to_string_pretty(&serde_json::json!({
+        "version": version,
+        "hash": hash,
+        "vm_status": vm_status.to_string(),
+        "trace": trace,
+    }))?)
+}
+
+pub async fn handle_block_conflicts_request(req: Request<Body>) -> hyper::Result<Response<Body>> {
+    if !is_conflict_analytics_enabled() {
+        return Ok(reply_with_status(
+            StatusCode::NOT_FOUND,
+            "Conflict analytics is not enabled (see execution.blockstm_conflict_analytics).",
+        ));
+    }
+
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let limit: Option<usize> = match query_pairs.get("limit") {
+        Some(val) => match val.parse() {
+            Ok(val) => Some(val),
+            Err(err) => {
+                return Ok(reply_with_status(
+                    StatusCode::BAD_REQUEST,
+                    format!("Invalid limit: {err}"),
+                ))
+            },
+        },
+        None => None,
+    };
+
+    // Most recent blocks first.
+    let mut blocks = recent_block_conflict_analytics();
+    blocks.reverse();
+    if let Some(limit) = limit {
+        blocks.truncate(limit);
+    }
+
+    match serde_json::to_string_pretty(&blocks) {
+        Ok(result) => Ok(reply_with(vec![], result)),
+        Err(e) => Ok(reply_with_status(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            e.to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumio_block_executor::conflict_analytics::set_conflict_analytics_enabled_once;
+
+    async fn get_block_conflicts(uri: &str) -> (StatusCode, String) {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let response = handle_block_conflicts_request(request).await.unwrap();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_block_conflicts_request() {
+        let (status, _) = get_block_conflicts("/debug/execution/conflicts").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        set_conflict_analytics_enabled_once(true);
+        let (status, body) = get_block_conflicts("/debug/execution/conflicts?limit=-1").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.starts_with("Invalid limit"));
+
+        // No blocks were executed.
+        let (status, body) = get_block_conflicts("/debug/execution/conflicts?limit=5").await;
+        assert_eq!(status, StatusCode::OK);
+        let blocks: Vec<serde_json::Value> = serde_json::from_str(&body).unwrap();
+        assert!(blocks.is_empty());
+    }
+}
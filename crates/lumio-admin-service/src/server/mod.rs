@@ -4,6 +4,7 @@
 use lumio_config::config::{AdminServiceConfig, AuthenticationConfig, NodeConfig};
 use lumio_consensus::{
     persistent_liveness_storage::StorageWriteProxy, quorum_store::quorum_store_db::QuorumStoreDB,
+    RoundTimelines,
 };
 use lumio_infallible::RwLock;
 use lumio_logger::info;
@@ -14,6 +15,7 @@ use lumio_system_utils::utils::reply_with_status;
 use lumio_system_utils::{
     profiling::handle_cpu_profiling_request, thread_dump::handle_thread_dump_request,
 };
+use lumio_types::PeerId;
 use hyper::{
     service::{make_service_fn, service_fn},
     Body, Request, Response, Server, StatusCode,
@@ -27,18 +29,26 @@ use std::{
 use tokio::runtime::Runtime;
 
 mod consensus;
+mod execution;
 #[cfg(unix)]
 mod malloc;
 mod mempool;
+mod storage;
 
 #[derive(Default)]
 pub struct Context {
     config: AdminServiceConfig,
+    window_for_chain_health: usize,
+    // The validator address of the node, if it's a validator
+    author: Option<PeerId>,
 
     lumio_db: RwLock<Option<Arc<DbReaderWriter>>>,
     consensus_db: RwLock<Option<Arc<StorageWriteProxy>>>,
     quorum_store_db: RwLock<Option<Arc<QuorumStoreDB>>>,
+    round_timelines: RwLock<Option<RoundTimelines>>,
     mempool_client_sender: RwLock<Option<MempoolClientSender>>,
+
+    checkpoint_status: Arc<RwLock<storage::CheckpointStatus>>,
 }
 
 impl Context {
@@ -55,6 +65,10 @@ impl Context {
         *self.quorum_store_db.write() = Some(quorum_store_db);
     }
 
+    fn set_round_timelines(&self, round_timelines: RoundTimelines) {
+        *self.round_timelines.write() = Some(round_timelines);
+    }
+
     fn set_mempool_client_sender(&self, mempool_client_sender: MempoolClientSender) {
         *self.mempool_client_sender.write() = Some(mempool_client_sender);
     }
@@ -95,6 +109,11 @@ impl AdminService {
             runtime,
             context: Arc::new(Context {
                 config,
+                window_for_chain_health: node_config.consensus.window_for_chain_health,
+                author: node_config
+                    .validator_network
+                    .as_ref()
+                    .map(|network_config| network_config.peer_id()),
                 ..Default::default()
             }),
         };
@@ -116,6 +135,10 @@ impl AdminService {
             .set_consensus_dbs(consensus_db, quorum_store_db)
     }
 
+    pub fn set_round_timelines(&self, round_timelines: RoundTimelines) {
+        self.context.set_round_timelines(round_timelines)
+    }
+
     pub fn set_mempool_client_sender(&self, mempool_client_sender: MempoolClientSender) {
         self.context
             .set_mempool_client_sender(mempool_client_sender)
@@ -230,6 +253,83 @@ impl AdminService {
                     ))
                 }
             },
+            (hyper::Method::GET, "/debug/consensus/round_timeline") => {
+                let round_timelines = context.round_timelines.read().clone();
+                if let Some(round_timelines) = round_timelines {
+                    consensus::handle_round_timeline_request(req, context.author, round_timelines)
+                        .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Round timelines are not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/consensus/equivocations") => {
+                let consensus_db = context.consensus_db.read().clone();
+                if let Some(consensus_db) = consensus_db {
+                    consensus::handle_dump_equivocation_evidence_request(req, consensus_db).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Consensus db is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/consensus/leader_reputation") => {
+                let lumio_db = context.lumio_db.read().clone();
+                if let Some(lumio_db) = lumio_db {
+                    consensus::handle_leader_reputation_request(
+                        req,
+                        lumio_db.reader.clone(),
+                        context.window_for_chain_health,
+                    )
+                    .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Lumio db is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/execution/trace") => {
+                let lumio_db = context.lumio_db.read().clone();
+                if let Some(lumio_db) = lumio_db {
+                    execution::handle_execution_trace_request(req, lumio_db.reader.clone()).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Lumio db is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/execution/conflicts") => {
+                execution::handle_block_conflicts_request(req).await
+            },
+            (hyper::Method::GET, "/debug/storage/checkpoint") => {
+                storage::handle_get_checkpoint_status_request(
+                    req,
+                    context.checkpoint_status.clone(),
+                )
+                .await
+            },
+            (hyper::Method::POST, "/debug/storage/checkpoint") => {
+                let lumio_db = context.lumio_db.read().clone();
+                if let Some(lumio_db) = lumio_db {
+                    storage::handle_create_checkpoint_request(
+                        req,
+                        &context.config,
+                        lumio_db.writer.clone(),
+                        context.checkpoint_status.clone(),
+                    )
+                    .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Lumio db is not available.",
+                    ))
+                }
+            },
             (hyper::Method::GET, "/debug/mempool/parking-lot/addresses") => {
                 let mempool_client_sender = context.mempool_client_sender.read().clone();
                 if mempool_client_sender.is_some() {
@@ -0,0 +1,315 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, Result};
+use lumio_config::config::AdminServiceConfig;
+use lumio_infallible::{duration_since_epoch, RwLock};
+use lumio_logger::{error, info};
+use lumio_storage_interface::DbWriter;
+use lumio_system_utils::utils::{reply_with, reply_with_status};
+use lumio_types::transaction::Version;
+use http::header::{HeaderValue, CONTENT_TYPE};
+use hyper::{Body, Request, Response, StatusCode};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+
+/// Prefix of the directories holding finished checkpoints, followed by
+/// `<unix timestamp secs>-v<version>`.
+const CHECKPOINT_PREFIX: &str = "checkpoint-";
+/// Prefix of the directories holding checkpoints that are being made. A directory with this
+/// prefix that is not being worked on is left over by a failed attempt or a crash.
+const IN_PROGRESS_PREFIX: &str = "in-progress-checkpoint-";
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckpointState {
+    #[default]
+    Idle,
+    InProgress,
+    Succeeded,
+    Failed,
+}
+
+/// Progress of the latest online checkpoint requested through the admin service.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct CheckpointStatus {
+    state: CheckpointState,
+    // The database being checkpointed, or "retention" while old checkpoints are removed.
+    phase: Option<String>,
+    path: Option<PathBuf>,
+    // The committed version the checkpoint is consistent at.
+    version: Option<Version>,
+    started_at_secs: Option<u64>,
+    elapsed_ms: Option<u64>,
+    removed_checkpoints: Vec<PathBuf>,
+    error: Option<String>,
+}
+
+pub async fn handle_get_checkpoint_status_request(
+    _req: Request<Body>,
+    checkpoint_status: Arc<RwLock<CheckpointStatus>>,
+) -> hyper::Result<Response<Body>> {
+    let status = checkpoint_status.read().clone();
+    Ok(reply_with_json(StatusCode::OK, &status))
+}
+
+pub async fn handle_create_checkpoint_request(
+    req: Request<Body>,
+    config: &AdminServiceConfig,
+    lumio_db: Arc<dyn DbWriter>,
+    checkpoint_status: Arc<RwLock<CheckpointStatus>>,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let wait: bool = match query_pairs.get("wait") {
+        Some(val) => match val.parse() {
+            Ok(val) => val,
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => false,
+    };
+
+    let checkpoint_dir = match &config.checkpoint_dir {
+        Some(checkpoint_dir) => checkpoint_dir.clone(),
+        None => {
+            return Ok(reply_with_status(
+                StatusCode::NOT_FOUND,
+                "Online checkpoints are not enabled, checkpoint_dir is not set.",
+            ))
+        },
+    };
+    let retention = config.checkpoint_retention;
+
+    {
+        let mut status = checkpoint_status.write();
+        if status.state == CheckpointState::InProgress {
+            return Ok(reply_with_json(StatusCode::CONFLICT, &*status));
+        }
+        *status = CheckpointStatus {
+            state: CheckpointState::InProgress,
+            started_at_secs: Some(duration_since_epoch().as_secs()),
+            ..Default::default()
+        };
+    }
+
+    info!("Creating online DB checkpoint under {checkpoint_dir:?}.");
+
+    let status = checkpoint_status.clone();
+    let checkpoint_handle = tokio::task::spawn_blocking(move || {
+        let start = Instant::now();
+        let result = create_checkpoint(lumio_db.as_ref(), &checkpoint_dir, retention, &status);
+
+        let mut status = status.write();
+        status.elapsed_ms = Some(start.elapsed().as_millis() as u64);
+        status.phase = None;
+        match result {
+            Ok(()) => {
+                info!(
+                    "Finished creating online DB checkpoint at {:?}.",
+                    status.path
+                );
+                status.state = CheckpointState::Succeeded;
+            },
+            Err(e) => {
+                error!("Failed to create online DB checkpoint: {e:?}");
+                status.state = CheckpointState::Failed;
+                status.error = Some(e.to_string());
+            },
+        }
+    });
+
+    // If the checkpoint task panics, it never gets to record the outcome, so do it here.
+    // Otherwise the checkpoint would be reported as in progress forever.
+    let status = checkpoint_status.clone();
+    let handle = tokio::spawn(async move {
+        if let Err(e) = checkpoint_handle.await {
+            error!("Online DB checkpoint task failed: {e:?}");
+            let mut status = status.write();
+            status.state = CheckpointState::Failed;
+            status.phase = None;
+            status.error = Some(e.to_string());
+        }
+    });
+
+    if wait {
+        if let Err(e) = handle.await {
+            return Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ));
+        }
+        let status = checkpoint_status.read().clone();
+        Ok(reply_with_json(StatusCode::OK, &status))
+    } else {
+        let status = checkpoint_status.read().clone();
+        Ok(reply_with_json(StatusCode::ACCEPTED, &status))
+    }
+}
+
+fn create_checkpoint(
+    lumio_db: &dyn DbWriter,
+    checkpoint_dir: &Path,
+    retention: usize,
+    status: &RwLock<CheckpointStatus>,
+) -> Result<()> {
+    fs::create_dir_all(checkpoint_dir)?;
+    // Only one checkpoint is made at a time, so anything in progress is stale.
+    for name in list_dir_names(checkpoint_dir)? {
+        if name.starts_with(IN_PROGRESS_PREFIX) {
+            info!("Removing stale checkpoint {name}.");
+            fs::remove_dir_all(checkpoint_dir.join(name))?;
+        }
+    }
+
+    let timestamp_secs = duration_since_epoch().as_secs();
+    let in_progress_path = checkpoint_dir.join(format!("{IN_PROGRESS_PREFIX}{timestamp_secs}"));
+    fs::create_dir(&in_progress_path)?;
+    status.write().path = Some(in_progress_path.clone());
+
+    let version = lumio_db.create_online_checkpoint(&in_progress_path, &mut |phase| {
+        status.write().phase = Some(phase.to_string());
+    })?;
+
+    let checkpoint_path =
+        checkpoint_dir.join(format!("{CHECKPOINT_PREFIX}{timestamp_secs}-v{version}"));
+    ensure!(
+        !checkpoint_path.exists(),
+        "Checkpoint {checkpoint_path:?} already exists."
+    );
+    fs::rename(&in_progress_path, &checkpoint_path)?;
+    {
+        let mut status = status.write();
+        status.path = Some(checkpoint_path);
+        status.version = Some(version);
+        status.phase = Some("retention".to_string());
+    }
+
+    for name in checkpoints_to_remove(list_dir_names(checkpoint_dir)?, retention) {
+        let path = checkpoint_dir.join(name);
+        info!("Removing checkpoint {path:?} beyond retention.");
+        fs::remove_dir_all(&path)?;
+        status.write().removed_checkpoints.push(path);
+    }
+
+    Ok(())
+}
+
+fn list_dir_names(dir: &Path) -> Result<Vec<String>> {
+    let mut names = vec![];
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            names.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    Ok(names)
+}
+
+/// Returns the finished checkpoints (by directory name) older than the `retention` most recent
+/// ones. Directories that don't look like checkpoints are never touched.
+fn checkpoints_to_remove(names: Vec<String>, retention: usize) -> Vec<String> {
+    let mut checkpoints: Vec<(u64, Version, String)> = names
+        .into_iter()
+        .filter_map(|name| {
+            let (timestamp_secs, version) =
+                name.strip_prefix(CHECKPOINT_PREFIX)?.split_once("-v")?;
+            Some((timestamp_secs.parse().ok()?, version.parse().ok()?, name))
+        })
+        .collect();
+    // Newest first.
+    checkpoints.sort_unstable_by(|a, b| b.cmp(a));
+    checkpoints
+        .into_iter()
+        .skip(retention)
+        .map(|(_, _, name)| name)
+        .collect()
+}
+
+fn reply_with_json<T: Serialize>(status_code: StatusCode, value: &T) -> Response<Body> {
+    match serde_json::to_string_pretty(value) {
+        Ok(body) => {
+            let mut response = reply_with(
+                vec![(CONTENT_TYPE, HeaderValue::from_static("application/json"))],
+                body,
+            );
+            *response.status_mut() = status_code;
+            response
+        },
+        Err(e) => reply_with_status(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumio_temppath::TempPath;
+
+    struct PanickingDbWriter;
+
+    impl DbWriter for PanickingDbWriter {
+        fn create_online_checkpoint(
+            &self,
+            _cp_root_path: &Path,
+            _on_progress: &mut dyn FnMut(&str),
+        ) -> lumio_storage_interface::Result<Version> {
+            panic!("Checkpoint panicked!");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_panic_is_reported_as_failed() {
+        let checkpoint_dir = TempPath::new();
+        let config = AdminServiceConfig {
+            checkpoint_dir: Some(checkpoint_dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        let checkpoint_status = Arc::new(RwLock::new(CheckpointStatus::default()));
+
+        let request = Request::builder()
+            .uri("/debug/storage/checkpoint?wait=true")
+            .body(Body::empty())
+            .unwrap();
+        let response = handle_create_checkpoint_request(
+            request,
+            &config,
+            Arc::new(PanickingDbWriter),
+            checkpoint_status.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let status = checkpoint_status.read().clone();
+        assert_eq!(status.state, CheckpointState::Failed);
+        assert!(status.phase.is_none());
+        assert!(status.error.is_some());
+    }
+
+    #[test]
+    fn test_checkpoints_to_remove() {
+        let names = vec![
+            "checkpoint-100-v5".to_string(),
+            "checkpoint-300-v20".to_string(),
+            "checkpoint-200-v10".to_string(),
+            "in-progress-checkpoint-400".to_string(),
+            "checkpoint-garbage".to_string(),
+            "something_else".to_string(),
+        ];
+
+        assert_eq!(checkpoints_to_remove(names.clone(), 1), vec![
+            "checkpoint-200-v10".to_string(),
+            "checkpoint-100-v5".to_string(),
+        ]);
+        assert_eq!(checkpoints_to_remove(names.clone(), 2), vec![
+            "checkpoint-100-v5".to_string()
+        ]);
+        assert!(checkpoints_to_remove(names, 3).is_empty());
+    }
+}
@@ -13,6 +13,9 @@ pub enum Cmd {
 
     DumpPendingTxns(lumio_consensus::util::db_tool::Command),
 
+    #[cfg(feature = "consensus-replay")]
+    ReplayConsensusTrace(lumio_consensus::record_replay::replay::Command),
+
     #[clap(subcommand)]
     Move(lumio_move_debugger::common::Command),
 }
@@ -23,6 +26,8 @@ impl Cmd {
             Cmd::LumioDb(cmd) => cmd.run().await,
             Cmd::Decode(cmd) => cmd.run().await,
             Cmd::DumpPendingTxns(cmd) => cmd.run().await,
+            #[cfg(feature = "consensus-replay")]
+            Cmd::ReplayConsensusTrace(cmd) => cmd.run().await,
             Cmd::Move(cmd) => cmd.run().await,
         }
     }
@@ -6,6 +6,7 @@ use lumio_config::config::NodeConfig;
 use lumio_data_client::client::LumioDataClient;
 use lumio_logger::debug;
 use lumio_network::application::storage::PeersAndMetadata;
+use lumio_types::state_sync_progress::SharedStateSyncProgress;
 use hyper::{
     service::{make_service_fn, service_fn},
     Body, Method, Request, Response, Server, StatusCode,
@@ -23,6 +24,7 @@ mod index;
 mod json_encoder;
 mod metrics;
 mod peer_information;
+mod state_sync_progress;
 mod system_information;
 pub mod utils;
 
@@ -38,6 +40,7 @@ pub const INDEX_PATH: &str = "/";
 pub const JSON_METRICS_PATH: &str = "/json_metrics";
 pub const METRICS_PATH: &str = "/metrics";
 pub const PEER_INFORMATION_PATH: &str = "/peer_information";
+pub const STATE_SYNC_PROGRESS_PATH: &str = "/state_sync_progress";
 pub const SYSTEM_INFORMATION_PATH: &str = "/system_information";
 
 // Useful string constants
@@ -51,6 +54,7 @@ pub fn start_inspection_service(
     node_config: NodeConfig,
     lumio_data_client: LumioDataClient,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    state_sync_progress: SharedStateSyncProgress,
 ) {
     // Fetch the service port and address
     let service_port = node_config.inspection_service.port;
@@ -78,6 +82,7 @@ pub fn start_inspection_service(
             let node_config = node_config.clone();
             let lumio_data_client = lumio_data_client.clone();
             let peers_and_metadata = peers_and_metadata.clone();
+            let state_sync_progress = state_sync_progress.clone();
             async move {
                 Ok::<_, Infallible>(service_fn(move |request| {
                     serve_requests(
@@ -85,6 +90,7 @@ pub fn start_inspection_service(
                         node_config.clone(),
                         lumio_data_client.clone(),
                         peers_and_metadata.clone(),
+                        state_sync_progress.clone(),
                     )
                 }))
             }
@@ -106,6 +112,7 @@ async fn serve_requests(
     node_config: NodeConfig,
     lumio_data_client: LumioDataClient,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    state_sync_progress: SharedStateSyncProgress,
 ) -> Result<Response<Body>, hyper::Error> {
     // Process the request and get the response components
     let (status_code, body, content_type) = match req.uri().path() {
@@ -153,6 +160,11 @@ async fn serve_requests(
                 peers_and_metadata,
             )
         },
+        STATE_SYNC_PROGRESS_PATH => {
+            // /state_sync_progress
+            // Exposes the state sync progress of the node
+            state_sync_progress::handle_state_sync_progress_request(&state_sync_progress)
+        },
         SYSTEM_INFORMATION_PATH => {
             // /system_information
             // Exposes the system and build information
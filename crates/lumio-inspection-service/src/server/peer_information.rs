@@ -268,6 +268,27 @@ fn display_state_sync_metadata(
             ));
         }
     }
+
+    // Fetch and display the reputations of all peers (including disconnected peers)
+    let peer_reputations = lumio_data_client.get_peer_states().get_peer_reputations();
+    peer_information_output.push("\t- Peer reputations (including disconnected peers):".into());
+    for (peer, reputation) in peer_reputations.get_all_reputations() {
+        peer_information_output.push(format!(
+            "\t\t- Peer: {}, score: {}, banned: {}, banned until (secs): {:?}, number of bans: {}",
+            peer,
+            reputation.get_score(),
+            peer_reputations.is_banned(&peer),
+            reputation.get_banned_until_secs(),
+            reputation.get_num_bans()
+        ));
+        peer_information_output.push(format!(
+            "\t\t\t- Proof verification failures: {}, invalid responses: {}, timeouts: {}, other failures: {}",
+            reputation.get_num_proof_verification_failures(),
+            reputation.get_num_invalid_responses(),
+            reputation.get_num_timeouts(),
+            reputation.get_num_other_failures()
+        ));
+    }
 }
 
 /// Displays the entire set of trusted peers
@@ -0,0 +1,38 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::server::utils::{CONTENT_TYPE_JSON, CONTENT_TYPE_TEXT};
+use lumio_types::state_sync_progress::SharedStateSyncProgress;
+use hyper::{Body, StatusCode};
+
+// The message to display when no sync progress has been reported
+pub const NO_SYNC_PROGRESS_MESSAGE: &str =
+    "No state sync progress has been reported yet! Please try again later.";
+
+/// Handles a new state sync progress request
+pub fn handle_state_sync_progress_request(
+    state_sync_progress: &SharedStateSyncProgress,
+) -> (StatusCode, Body, String) {
+    match state_sync_progress.get_progress() {
+        Some(progress) => match serde_json::to_string(&progress) {
+            Ok(progress_json) => (
+                StatusCode::OK,
+                Body::from(progress_json),
+                CONTENT_TYPE_JSON.into(),
+            ),
+            Err(error) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Body::from(format!(
+                    "Failed to serialize the state sync progress! Error: {}",
+                    error
+                )),
+                CONTENT_TYPE_TEXT.into(),
+            ),
+        },
+        None => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Body::from(NO_SYNC_PROGRESS_MESSAGE),
+            CONTENT_TYPE_TEXT.into(),
+        ),
+    }
+}
@@ -6,10 +6,12 @@ use crate::{
         configuration::CONFIGURATION_DISABLED_MESSAGE,
         identity_information::IDENTITY_INFO_DISABLED_MESSAGE,
         peer_information::PEER_INFO_DISABLED_MESSAGE, serve_requests,
+        state_sync_progress::NO_SYNC_PROGRESS_MESSAGE,
         system_information::SYS_INFO_DISABLED_MESSAGE, utils::get_all_metrics,
     },
     CONFIGURATION_PATH, FORGE_METRICS_PATH, IDENTITY_INFORMATION_PATH, INDEX_PATH,
-    JSON_METRICS_PATH, METRICS_PATH, PEER_INFORMATION_PATH, SYSTEM_INFORMATION_PATH,
+    JSON_METRICS_PATH, METRICS_PATH, PEER_INFORMATION_PATH, STATE_SYNC_PROGRESS_PATH,
+    SYSTEM_INFORMATION_PATH,
 };
 use lumio_config::config::{LumioDataClientConfig, BaseConfig, Identity, NodeConfig};
 use lumio_data_client::client::LumioDataClient;
@@ -17,6 +19,7 @@ use lumio_network::application::{interface::NetworkClient, storage::PeersAndMeta
 use lumio_storage_interface::DbReader;
 use lumio_storage_service_client::StorageServiceClient;
 use lumio_time_service::TimeService;
+use lumio_types::state_sync_progress::{SharedStateSyncProgress, StateSyncPhase, StateSyncProgress};
 use assert_approx_eq::assert_approx_eq;
 use futures::executor::block_on;
 use hyper::{body, Body, Method, Request, Response, StatusCode};
@@ -92,6 +95,7 @@ async fn test_inspect_index() {
     assert!(response_body_string.contains(JSON_METRICS_PATH));
     assert!(response_body_string.contains(METRICS_PATH));
     assert!(response_body_string.contains(PEER_INFORMATION_PATH));
+    assert!(response_body_string.contains(STATE_SYNC_PROGRESS_PATH));
     assert!(response_body_string.contains(SYSTEM_INFORMATION_PATH));
 }
 
@@ -159,6 +163,51 @@ async fn test_inspect_metrics() {
     assert!(response_body_string.contains(INT_COUNTER_NAME));
 }
 
+#[tokio::test]
+async fn test_inspect_state_sync_progress() {
+    // Create a PFN config
+    let config = NodeConfig::get_default_pfn_config();
+
+    // Ping the endpoint before any progress has been reported
+    let state_sync_progress = SharedStateSyncProgress::new();
+    let mut response = send_get_request_to_path_with_progress(
+        &config,
+        STATE_SYNC_PROGRESS_PATH,
+        state_sync_progress.clone(),
+    )
+    .await;
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+
+    // Verify that the response contains an error
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response_body, NO_SYNC_PROGRESS_MESSAGE);
+
+    // Report some progress and ping the endpoint again
+    state_sync_progress.update_progress(StateSyncProgress {
+        phase: StateSyncPhase::States,
+        synced_version: 0,
+        target_version: Some(5000),
+        num_states_synced: Some(10_000),
+        num_states_total: Some(100_000),
+        throughput_per_second: Some(1000),
+        estimated_seconds_remaining: Some(90),
+    });
+    let mut response = send_get_request_to_path_with_progress(
+        &config,
+        STATE_SYNC_PROGRESS_PATH,
+        state_sync_progress,
+    )
+    .await;
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+    let response_body_string = read_to_string(response_body.as_ref()).unwrap();
+
+    // Verify that the response contains the expected information
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response_body_string.contains("\"phase\":\"states\""));
+    assert!(response_body_string.contains("\"num_states_total\":100000"));
+    assert!(response_body_string.contains("\"estimated_seconds_remaining\":90"));
+}
+
 #[tokio::test]
 async fn test_inspect_system_information() {
     // Create a validator node config
@@ -282,6 +331,16 @@ fn test_publish_metrics() {
 
 // Exercise the serve_requests() handler with a GET request to the given path
 async fn send_get_request_to_path(config: &NodeConfig, endpoint: &str) -> Response<Body> {
+    send_get_request_to_path_with_progress(config, endpoint, SharedStateSyncProgress::new()).await
+}
+
+// Exercise the serve_requests() handler with a GET request to the given path
+// (using the given state sync progress).
+async fn send_get_request_to_path_with_progress(
+    config: &NodeConfig,
+    endpoint: &str,
+    state_sync_progress: SharedStateSyncProgress,
+) -> Response<Body> {
     // Build the URI
     let uri = format!("http://127.0.0.1:9201{}", endpoint);
 
@@ -310,6 +369,7 @@ async fn send_get_request_to_path(config: &NodeConfig, endpoint: &str) -> Respon
         config.clone(),
         lumio_data_client,
         peers_and_metadata,
+        state_sync_progress,
     )
     .await
     .unwrap()
@@ -13,7 +13,7 @@ use lumio_vm_types::{
     module_and_script_storage::AsLumioCodeStorage, output::VMOutput, resolver::StateStorageView,
 };
 use move_core_types::vm_status::VMStatus;
-use std::{path::Path, time::Instant};
+use std::{fs, path::Path, time::Instant};
 
 pub fn run_transaction_using_debugger(
     debugger: &LumioDebugger,
@@ -172,3 +172,28 @@ pub fn profile_transaction_using_debugger(
 
     Ok((vm_status, vm_output))
 }
+
+pub fn trace_transaction_using_debugger(
+    debugger: &LumioDebugger,
+    version: u64,
+    transaction: SignedTransaction,
+    hash: HashValue,
+    auxiliary_info: AuxiliaryInfo,
+) -> CliTypedResult<(VMStatus, VMOutput)> {
+    let (vm_status, vm_output, trace) = debugger
+        .execute_transaction_at_version_with_tracer(version, transaction, auxiliary_info)
+        .map_err(|err| {
+            CliError::UnexpectedError(format!("failed to simulate txn with tracer: {}", err))
+        })?;
+
+    let dir = Path::new("execution-traces");
+    fs::create_dir_all(dir).map_err(|err| CliError::IO(dir.display().to_string(), err))?;
+    let path = dir.join(format!("txn-{}.json", hash));
+    let json = serde_json::to_string_pretty(&trace)
+        .map_err(|err| CliError::UnexpectedError(format!("failed to serialize trace: {}", err)))?;
+    fs::write(&path, json).map_err(|err| CliError::IO(path.display().to_string(), err))?;
+
+    println!("Execution trace saved to {}.", path.display());
+
+    Ok((vm_status, vm_output))
+}
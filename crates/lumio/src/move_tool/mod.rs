@@ -2284,6 +2284,11 @@ pub struct Replay {
     #[clap(long)]
     pub(crate) profile_gas: bool,
 
+    /// If this option is set, record the call tree of the transaction (with the arguments,
+    /// return values, resource accesses, events and writes of each call) and save it as JSON.
+    #[clap(long)]
+    pub(crate) trace: bool,
+
     /// If present, skip the comparison against the expected transaction output.
     #[clap(long)]
     pub(crate) skip_comparison: bool,
@@ -2319,6 +2324,11 @@ impl CliCommand<TransactionSummary> for Replay {
                 "Cannot perform benchmarking and gas profiling at the same time.".to_string(),
             ));
         }
+        if self.trace && (self.profile_gas || self.benchmark) {
+            return Err(CliError::UnexpectedError(
+                "Cannot trace the transaction while benchmarking or profiling gas.".to_string(),
+            ));
+        }
 
         // Build the client
         let client = Client::builder(self.network.to_base_url()?);
@@ -2358,6 +2368,16 @@ impl CliCommand<TransactionSummary> for Replay {
                 txn.clone(),
                 hash,
             )?
+        } else if self.trace {
+            println!("Tracing transaction...");
+            let auxiliary_info = debugger.get_auxiliary_info_at_version(self.txn_id).await?;
+            local_simulation::trace_transaction_using_debugger(
+                &debugger,
+                self.txn_id,
+                txn.clone(),
+                hash,
+                auxiliary_info,
+            )?
         } else if self.benchmark {
             println!("Benchmarking transaction...");
             local_simulation::benchmark_transaction_using_debugger(
@@ -2,15 +2,37 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    common::types::{CliCommand, CliResult, CliTypedResult},
+    common::{
+        types::{
+            CliCommand, CliError, CliResult, CliTypedResult, EntryFunctionArguments,
+            TransactionSummary, DEFAULT_EXPIRATION_SECS,
+        },
+        utils::read_from_file,
+    },
     move_tool::ReplayNetworkSelection,
 };
 use lumio_rest_client::Client;
-use lumio_transaction_simulation_session::Session;
+use lumio_sdk::transaction_builder::TransactionFactory;
+use lumio_transaction_simulation::SimulationStateStore;
+use lumio_transaction_simulation_session::{serve, Session};
+use lumio_types::{
+    account_config::AccountResource,
+    on_chain_config::CurrentTimeMicroseconds,
+    transaction::{
+        authenticator::{AccountAuthenticator, TransactionAuthenticator},
+        SignedTransaction, TransactionPayload, TransactionStatus,
+    },
+};
 use async_trait::async_trait;
 use clap::{Parser, Subcommand};
-use move_core_types::account_address::AccountAddress;
-use std::path::PathBuf;
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{ModuleId, StructTag},
+};
+use std::{
+    net::{Ipv4Addr, SocketAddrV4},
+    path::PathBuf,
+};
 
 /// Initializes a new simulation session
 #[derive(Debug, Parser)]
@@ -99,6 +121,393 @@ impl CliCommand<()> for Fund {
     }
 }
 
+/// Saves the current state of a session as a named snapshot
+///
+/// The session can later be rolled back to the snapshot with `lumio move sim rollback`.
+#[derive(Debug, Parser)]
+pub struct Snapshot {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// Name of the snapshot, overwriting any existing snapshot with the same name
+    #[clap(long)]
+    name: String,
+}
+
+#[async_trait]
+impl CliCommand<()> for Snapshot {
+    fn command_name(&self) -> &'static str {
+        "snapshot"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let mut session = Session::load(&self.session)?;
+
+        session.create_snapshot(&self.name)?;
+
+        Ok(())
+    }
+}
+
+/// Rolls the state of a session back to a named snapshot
+#[derive(Debug, Parser)]
+pub struct Rollback {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// Name of the snapshot
+    #[clap(long)]
+    name: String,
+}
+
+#[async_trait]
+impl CliCommand<()> for Rollback {
+    fn command_name(&self) -> &'static str {
+        "rollback"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let mut session = Session::load(&self.session)?;
+
+        session.restore_snapshot(&self.name)?;
+
+        Ok(())
+    }
+}
+
+/// Lists the snapshots of a session
+#[derive(Debug, Parser)]
+pub struct ListSnapshots {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+}
+
+#[async_trait]
+impl CliCommand<Vec<String>> for ListSnapshots {
+    fn command_name(&self) -> &'static str {
+        "list-snapshots"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<String>> {
+        let session = Session::load(&self.session)?;
+
+        Ok(session.snapshots()?)
+    }
+}
+
+/// Sets a resource at an address, overriding its current value
+#[derive(Debug, Parser)]
+pub struct SetResource {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// Account to set the resource at, can be an address or a CLI profile name
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    account: AccountAddress,
+
+    /// Type of the resource, e.g. `0x1::account::Account`
+    #[clap(long)]
+    resource_type: StructTag,
+
+    /// Value of the resource, in the JSON format of the node API
+    ///
+    /// Example: `{"id": 4}` for `0x1::chain_id::ChainId`
+    #[clap(long)]
+    value: String,
+}
+
+#[async_trait]
+impl CliCommand<()> for SetResource {
+    fn command_name(&self) -> &'static str {
+        "set-resource"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let value = serde_json::from_str(&self.value)
+            .map_err(|err| CliError::UnableToParse("value", err.to_string()))?;
+        let mut session = Session::load(&self.session)?;
+
+        session.set_resource(self.account, self.resource_type, Some(value))?;
+
+        Ok(())
+    }
+}
+
+/// Deletes a resource at an address
+#[derive(Debug, Parser)]
+pub struct DeleteResource {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// Account to delete the resource from, can be an address or a CLI profile name
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    account: AccountAddress,
+
+    /// Type of the resource, e.g. `0x1::account::Account`
+    #[clap(long)]
+    resource_type: StructTag,
+}
+
+#[async_trait]
+impl CliCommand<()> for DeleteResource {
+    fn command_name(&self) -> &'static str {
+        "delete-resource"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let mut session = Session::load(&self.session)?;
+
+        session.set_resource(self.account, self.resource_type, None)?;
+
+        Ok(())
+    }
+}
+
+/// Publishes a compiled module, overriding any module with the same ID
+///
+/// Unlike publishing a package, compatibility with the existing module is not checked, and the
+/// package metadata is left untouched.
+#[derive(Debug, Parser)]
+pub struct SetModule {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// Path to the compiled module (`.mv` file)
+    #[clap(long)]
+    module_file: PathBuf,
+}
+
+#[async_trait]
+impl CliCommand<String> for SetModule {
+    fn command_name(&self) -> &'static str {
+        "set-module"
+    }
+
+    async fn execute(self) -> CliTypedResult<String> {
+        let module_bytes = read_from_file(&self.module_file)?;
+        let mut session = Session::load(&self.session)?;
+
+        let module_id = session.set_module(module_bytes)?;
+
+        Ok(module_id.short_str_lossless())
+    }
+}
+
+/// Deletes a module
+#[derive(Debug, Parser)]
+pub struct DeleteModule {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// ID of the module as `<ADDRESS>::<MODULE_NAME>`
+    #[clap(long)]
+    module_id: ModuleId,
+}
+
+#[async_trait]
+impl CliCommand<()> for DeleteModule {
+    fn command_name(&self) -> &'static str {
+        "delete-module"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let mut session = Session::load(&self.session)?;
+
+        session.delete_module(&self.module_id)?;
+
+        Ok(())
+    }
+}
+
+/// Sets the on-chain timestamp observed by the next transactions
+#[derive(Debug, Parser)]
+pub struct SetTimestamp {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// Timestamp, in microseconds since the Unix epoch
+    #[clap(long)]
+    timestamp_usecs: u64,
+}
+
+#[async_trait]
+impl CliCommand<()> for SetTimestamp {
+    fn command_name(&self) -> &'static str {
+        "set-timestamp"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let mut session = Session::load(&self.session)?;
+
+        session.set_timestamp(self.timestamp_usecs)?;
+
+        Ok(())
+    }
+}
+
+/// Sets the seed of the on-chain randomness used by the next transactions
+#[derive(Debug, Parser)]
+pub struct SetRandomnessSeed {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// Seed, as a hex string (usually 32 bytes)
+    #[clap(long)]
+    seed: String,
+}
+
+#[async_trait]
+impl CliCommand<()> for SetRandomnessSeed {
+    fn command_name(&self) -> &'static str {
+        "set-randomness-seed"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let seed = hex::decode(self.seed.trim_start_matches("0x"))
+            .map_err(|err| CliError::UnableToParse("seed", err.to_string()))?;
+        let mut session = Session::load(&self.session)?;
+
+        session.set_randomness_seed(seed)?;
+
+        Ok(())
+    }
+}
+
+/// Runs a Move function as any account, without its private key
+///
+/// Neither the signature nor the authentication key of the sender are checked, which allows
+/// acting as e.g. a governance or framework account of a forked network.
+#[derive(Debug, Parser)]
+pub struct RunAs {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// Account to run the function as, can be an address or a CLI profile name
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    sender: AccountAddress,
+
+    #[clap(flatten)]
+    entry_function_args: EntryFunctionArguments,
+
+    /// Maximum amount of gas units to be used
+    #[clap(long, default_value_t = 2_000_000)]
+    max_gas: u64,
+
+    /// Gas unit price, in Octa
+    #[clap(long, default_value_t = 100)]
+    gas_unit_price: u64,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for RunAs {
+    fn command_name(&self) -> &'static str {
+        "run-as"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let mut session = Session::load(&self.session)?;
+
+        let state_store = session.state_store();
+        let sequence_number = state_store
+            .get_resource::<AccountResource>(self.sender)?
+            .map_or(0, |account| account.sequence_number());
+        // The session time may be far from the wall-clock time (e.g., after `set-timestamp`), so
+        // the expiration is relative to it
+        let now_secs = state_store
+            .get_on_chain_config::<CurrentTimeMicroseconds>()?
+            .microseconds
+            / 1_000_000;
+        let raw_txn = TransactionFactory::new(state_store.get_chain_id()?)
+            .with_gas_unit_price(self.gas_unit_price)
+            .with_max_gas_amount(self.max_gas)
+            .payload(TransactionPayload::EntryFunction(
+                self.entry_function_args.try_into()?,
+            ))
+            .sender(self.sender)
+            .sequence_number(sequence_number)
+            .expiration_timestamp_secs(now_secs + DEFAULT_EXPIRATION_SECS)
+            .build();
+        // The hash of the unsigned transaction the session executes
+        let hash = SignedTransaction::new_signed_transaction(
+            raw_txn.clone(),
+            TransactionAuthenticator::single_sender(AccountAuthenticator::NoAccountAuthenticator),
+        )
+        .committed_hash();
+
+        let (vm_status, txn_output) = session.execute_impersonated_transaction(raw_txn)?;
+
+        let success = match txn_output.status() {
+            TransactionStatus::Keep(exec_status) => Some(exec_status.is_success()),
+            TransactionStatus::Discard(_) | TransactionStatus::Retry => None,
+        };
+
+        Ok(TransactionSummary {
+            transaction_hash: hash.into(),
+            gas_used: Some(txn_output.gas_used()),
+            gas_unit_price: Some(self.gas_unit_price),
+            pending: None,
+            sender: Some(self.sender),
+            sequence_number: Some(sequence_number),
+            replay_protector: None,
+            success,
+            timestamp_us: None,
+            version: None,
+            vm_status: Some(vm_status.to_string()),
+        })
+    }
+}
+
+/// Serves a session through the routes of the node REST API
+///
+/// Clients and SDKs can then run against the session by pointing at `http://<address>:<port>/v1`.
+/// Submitted transactions are executed right away and applied to the session state. Only the
+/// account, resource, view and transaction routes are served.
+#[derive(Debug, Parser)]
+pub struct Serve {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// The address to listen on
+    #[clap(long, default_value_t = Ipv4Addr::LOCALHOST)]
+    bind_to: Ipv4Addr,
+
+    /// The port to listen on
+    #[clap(long, default_value_t = 8080)]
+    port: u16,
+}
+
+#[async_trait]
+impl CliCommand<()> for Serve {
+    fn command_name(&self) -> &'static str {
+        "serve"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let session = Session::load(&self.session)?;
+
+        eprintln!(
+            "Serving session {} at http://{}:{}/v1",
+            self.session.display(),
+            self.bind_to,
+            self.port
+        );
+        serve(session, SocketAddrV4::new(self.bind_to, self.port).into()).await?;
+
+        Ok(())
+    }
+}
+
 /// BETA: Commands for interacting with a local simulation session
 ///
 /// BETA: Subject to change
@@ -106,6 +515,17 @@ impl CliCommand<()> for Fund {
 pub enum Sim {
     Init(Init),
     Fund(Fund),
+    Snapshot(Snapshot),
+    Rollback(Rollback),
+    ListSnapshots(ListSnapshots),
+    SetResource(SetResource),
+    DeleteResource(DeleteResource),
+    SetModule(SetModule),
+    DeleteModule(DeleteModule),
+    SetTimestamp(SetTimestamp),
+    SetRandomnessSeed(SetRandomnessSeed),
+    RunAs(RunAs),
+    Serve(Serve),
 }
 
 impl Sim {
@@ -113,6 +533,17 @@ impl Sim {
         match self {
             Sim::Init(init) => init.execute_serialized_success().await,
             Sim::Fund(fund) => fund.execute_serialized_success().await,
+            Sim::Snapshot(snapshot) => snapshot.execute_serialized_success().await,
+            Sim::Rollback(rollback) => rollback.execute_serialized_success().await,
+            Sim::ListSnapshots(list) => list.execute_serialized().await,
+            Sim::SetResource(set) => set.execute_serialized_success().await,
+            Sim::DeleteResource(delete) => delete.execute_serialized_success().await,
+            Sim::SetModule(set) => set.execute_serialized().await,
+            Sim::DeleteModule(delete) => delete.execute_serialized_success().await,
+            Sim::SetTimestamp(set) => set.execute_serialized_success().await,
+            Sim::SetRandomnessSeed(set) => set.execute_serialized_success().await,
+            Sim::RunAs(run) => run.execute_serialized().await,
+            Sim::Serve(serve) => serve.execute_serialized_success().await,
         }
     }
 }
@@ -9,7 +9,8 @@ use crate::{
     common::{
         types::{
             CliCommand, CliError, CliResult, CliTypedResult, OptionalPoolAddressArgs,
-            PoolAddressArgs, ProfileOptions, RestOptions, TransactionOptions, TransactionSummary,
+            PoolAddressArgs, ProfileOptions, RestOptions, SaveFile, TransactionOptions,
+            TransactionSummary,
         },
         utils::read_from_file,
     },
@@ -25,6 +26,10 @@ use lumio_backup_cli::{
     utils::GlobalRestoreOpt,
 };
 use lumio_cached_packages::lumio_stdlib;
+use lumio_config::config::NodeConfig;
+use lumio_consensus_types::round_timeline::{
+    merge_round_timelines, MergedRoundTimeline, RoundTimelineDump,
+};
 use lumio_crypto::{bls12381, bls12381::PublicKey, x25519, ValidCryptoMaterialStringExt};
 use lumio_genesis::config::{HostAndPort, OperatorConfiguration};
 use lumio_logger::Level;
@@ -32,6 +37,9 @@ use lumio_network_checker::args::{
     validate_address, CheckEndpointArgs, HandshakeArgs, NodeAddressArgs,
 };
 use lumio_rest_client::{lumio_api_types::VersionedEvent, Client, State};
+use lumio_safety_rules::{
+    interchange::SafetyDataExport, safety_rules_manager, SafetyDataInterchange,
+};
 use lumio_types::{
     account_address::AccountAddress,
     account_config::{BlockResource, CORE_CODE_ADDRESS},
@@ -67,12 +75,16 @@ pub enum NodeTool {
     AnalyzeValidatorPerformance(AnalyzeValidatorPerformance),
     BootstrapDb(BootstrapDb),
     CheckNetworkConnectivity(CheckNetworkConnectivity),
+    ExportSafetyData(ExportSafetyData),
     GetPerformance(GetPerformance),
     GetStakePool(GetStakePool),
+    ImportSafetyData(ImportSafetyData),
     InitializeValidator(InitializeValidator),
     JoinValidatorSet(JoinValidatorSet),
     LeaveValidatorSet(LeaveValidatorSet),
+    MergeRoundTimelines(MergeRoundTimelines),
     ShowEpochInfo(ShowEpochInfo),
+    ShowLeaderReputation(ShowLeaderReputation),
     ShowValidatorConfig(ShowValidatorConfig),
     ShowValidatorSet(ShowValidatorSet),
     ShowValidatorStake(ShowValidatorStake),
@@ -92,12 +104,16 @@ impl NodeTool {
                     .await
             },
             CheckNetworkConnectivity(tool) => tool.execute_serialized().await,
+            ExportSafetyData(tool) => tool.execute_serialized().await,
             GetPerformance(tool) => tool.execute_serialized().await,
             GetStakePool(tool) => tool.execute_serialized().await,
+            ImportSafetyData(tool) => tool.execute_serialized().await,
             InitializeValidator(tool) => tool.execute_serialized().await,
             JoinValidatorSet(tool) => tool.execute_serialized().await,
             LeaveValidatorSet(tool) => tool.execute_serialized().await,
+            MergeRoundTimelines(tool) => tool.execute_serialized().await,
             ShowEpochInfo(tool) => tool.execute_serialized().await,
+            ShowLeaderReputation(tool) => tool.execute_serialized().await,
             ShowValidatorSet(tool) => tool.execute_serialized().await,
             ShowValidatorStake(tool) => tool.execute_serialized().await,
             ShowValidatorConfig(tool) => tool.execute_serialized().await,
@@ -1356,6 +1372,236 @@ impl CliCommand<()> for BootstrapDb {
     }
 }
 
+/// Export the consensus safety data of a validator
+///
+/// Writes the last voted, preferred and highest timeout rounds of the validator, with its
+/// waypoint, from the node's secure storage to a file signed with its consensus key. Use it
+/// with `import-safety-data` to move a validator to new hardware. Stop the validator first, so
+/// it can't vote past the exported rounds.
+#[derive(Parser)]
+pub struct ExportSafetyData {
+    /// Path to the node config of the validator
+    #[clap(long, value_parser)]
+    pub(crate) node_config: PathBuf,
+
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<SafetyDataExport> for ExportSafetyData {
+    fn command_name(&self) -> &'static str {
+        "ExportSafetyData"
+    }
+
+    async fn execute(self) -> CliTypedResult<SafetyDataExport> {
+        self.save_file.check_file()?;
+        let config = NodeConfig::load_from_path(&self.node_config)?;
+        let interchange = safety_rules_manager::export_safety_data(&config.consensus.safety_rules)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        let bytes = interchange
+            .to_json()
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        self.save_file.save_to_file("Safety data", &bytes)?;
+        Ok(interchange.data)
+    }
+}
+
+/// Import the consensus safety data of a validator
+///
+/// Reads a file written by `export-safety-data` into the node's secure storage. The storage
+/// must belong to the same validator, and the import fails if it would lower any round or the
+/// waypoint already in it.
+#[derive(Parser)]
+pub struct ImportSafetyData {
+    /// Path to the node config of the validator
+    #[clap(long, value_parser)]
+    pub(crate) node_config: PathBuf,
+
+    /// Safety data file written by `export-safety-data`
+    #[clap(long, value_parser)]
+    pub(crate) input_file: PathBuf,
+}
+
+#[async_trait]
+impl CliCommand<SafetyDataExport> for ImportSafetyData {
+    fn command_name(&self) -> &'static str {
+        "ImportSafetyData"
+    }
+
+    async fn execute(self) -> CliTypedResult<SafetyDataExport> {
+        let config = NodeConfig::load_from_path(&self.node_config)?;
+        let interchange = SafetyDataInterchange::from_json(&read_from_file(&self.input_file)?)
+            .map_err(|err| CliError::UnableToParse("Safety data", err.to_string()))?;
+        safety_rules_manager::import_safety_data(&config.consensus.safety_rules, &interchange)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        Ok(interchange.data)
+    }
+}
+
+/// Show how leader reputation elects the proposers of a range of rounds
+///
+/// Queries the admin service of a node, which recomputes the elections from its committed
+/// history. For every round, shows the proposals, failed proposals and votes of each validator
+/// within the reputation windows, the weights derived from them, and the elected proposer.
+#[derive(Parser)]
+pub struct ShowLeaderReputation {
+    /// URL of the node's admin service
+    #[clap(long, default_value = "http://localhost:9102")]
+    pub(crate) admin_url: reqwest::Url,
+
+    /// Passcode of the admin service, if it requires authentication
+    #[clap(long)]
+    pub(crate) passcode: Option<String>,
+
+    /// Epoch of the rounds
+    #[clap(long)]
+    pub(crate) epoch: u64,
+
+    /// First round to show
+    #[clap(long)]
+    pub(crate) start_round: u64,
+
+    /// Last round to show, defaults to `start_round`
+    #[clap(long)]
+    pub(crate) end_round: Option<u64>,
+}
+
+#[async_trait]
+impl CliCommand<serde_json::Value> for ShowLeaderReputation {
+    fn command_name(&self) -> &'static str {
+        "ShowLeaderReputation"
+    }
+
+    async fn execute(self) -> CliTypedResult<serde_json::Value> {
+        let mut url = self
+            .admin_url
+            .join("/debug/consensus/leader_reputation")
+            .map_err(|err| CliError::CommandArgumentError(err.to_string()))?;
+        url.query_pairs_mut()
+            .append_pair("epoch", &self.epoch.to_string())
+            .append_pair("start_round", &self.start_round.to_string())
+            .append_pair(
+                "end_round",
+                &self.end_round.unwrap_or(self.start_round).to_string(),
+            );
+
+        let body = query_admin_service(url, self.passcode.as_deref()).await?;
+        serde_json::from_str(&body)
+            .map_err(|err| CliError::UnableToParse("Leader reputation", err.to_string()))
+    }
+}
+
+/// Merges the round timelines of several nodes into a single cross-validator timeline
+///
+/// Round timelines record when each node received and verified the proposal of a round, voted,
+/// formed the QC, and ordered, executed, signed and committed the block. They're fetched from the
+/// admin service of each node, or read from dumps previously saved from it. Timestamps come
+/// from the clock of each node.
+#[derive(Parser)]
+pub struct MergeRoundTimelines {
+    /// URLs of the admin services of the nodes
+    #[clap(long, num_args = 0..)]
+    pub(crate) admin_urls: Vec<reqwest::Url>,
+
+    /// Round timeline dumps saved from the `/debug/consensus/round_timeline` admin endpoint
+    #[clap(long, num_args = 0..)]
+    pub(crate) dump_files: Vec<PathBuf>,
+
+    /// Passcode of the admin services, if they require authentication
+    #[clap(long)]
+    pub(crate) passcode: Option<String>,
+
+    /// Only merge the rounds of this epoch
+    #[clap(long)]
+    pub(crate) epoch: Option<u64>,
+
+    /// Only merge rounds starting at this one
+    #[clap(long)]
+    pub(crate) start_round: Option<u64>,
+
+    /// Only merge rounds up to this one
+    #[clap(long)]
+    pub(crate) end_round: Option<u64>,
+}
+
+#[async_trait]
+impl CliCommand<Vec<MergedRoundTimeline>> for MergeRoundTimelines {
+    fn command_name(&self) -> &'static str {
+        "MergeRoundTimelines"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<MergedRoundTimeline>> {
+        if self.admin_urls.is_empty() && self.dump_files.is_empty() {
+            return Err(CliError::CommandArgumentError(
+                "At least one of --admin-urls or --dump-files is required".to_string(),
+            ));
+        }
+
+        let parse_dump = |dump: &str| {
+            serde_json::from_str::<RoundTimelineDump>(dump)
+                .map_err(|err| CliError::UnableToParse("Round timeline", err.to_string()))
+        };
+        let mut dumps = vec![];
+        for admin_url in &self.admin_urls {
+            let url = admin_url
+                .join("/debug/consensus/round_timeline")
+                .map_err(|err| CliError::CommandArgumentError(err.to_string()))?;
+            dumps.push(parse_dump(
+                &query_admin_service(url, self.passcode.as_deref()).await?,
+            )?);
+        }
+        for dump_file in &self.dump_files {
+            let dump = String::from_utf8(read_from_file(dump_file)?).map_err(CliError::from)?;
+            dumps.push(parse_dump(&dump)?);
+        }
+
+        for dump in &mut dumps {
+            dump.rounds.retain(|timeline| {
+                self.epoch.is_none_or(|epoch| timeline.epoch == epoch)
+                    && self
+                        .start_round
+                        .is_none_or(|start_round| timeline.round >= start_round)
+                    && self
+                        .end_round
+                        .is_none_or(|end_round| timeline.round <= end_round)
+            });
+        }
+        Ok(merge_round_timelines(dumps))
+    }
+}
+
+/// Queries the given admin service endpoint, returning the response body
+async fn query_admin_service(
+    mut url: reqwest::Url,
+    passcode: Option<&str>,
+) -> CliTypedResult<String> {
+    // Keep the passcode out of error messages.
+    let endpoint = url.to_string();
+    if let Some(passcode) = passcode {
+        url.query_pairs_mut().append_pair("passcode", passcode);
+    }
+    let response = reqwest::get(url).await.map_err(|err| {
+        CliError::ApiError(format!(
+            "Failed to query admin service {}: {}",
+            endpoint,
+            err.without_url()
+        ))
+    })?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|err| CliError::ApiError(err.to_string()))?;
+    if !status.is_success() {
+        return Err(CliError::ApiError(format!(
+            "Admin service responded with {}: {}",
+            status, body
+        )));
+    }
+    Ok(body)
+}
+
 /// Checks the network connectivity of a node
 ///
 /// Checks network connectivity by dialing the node and attempting
@@ -1493,6 +1739,11 @@ impl Time {
 mod tests {
     use crate::{CliResult, Tool};
     use clap::Parser;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        task::JoinHandle,
+    };
 
     // TODO: there have to be cleaner ways to test things. Maybe a CLI test framework?
 
@@ -1524,6 +1775,71 @@ mod tests {
         assert_contains(error_message, "Timed out while checking endpoint");
     }
 
+    #[tokio::test]
+    async fn test_show_leader_reputation_sends_passcode() {
+        let (admin_url, server) = spawn_admin_service("[]").await;
+        let args = &[
+            "lumio",
+            "node",
+            "show-leader-reputation",
+            "--admin-url",
+            admin_url.as_str(),
+            "--passcode",
+            "secret",
+            "--epoch",
+            "2",
+            "--start-round",
+            "10",
+        ];
+        run_tool_with_args(args).await.unwrap();
+        let request_line = server.await.unwrap().lines().next().unwrap().to_string();
+        assert_eq!(
+            request_line,
+            "GET /debug/consensus/leader_reputation?epoch=2&start_round=10&end_round=10\
+             &passcode=secret HTTP/1.1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_round_timelines_sends_passcode() {
+        let (admin_url, server) = spawn_admin_service(r#"{"author":null,"rounds":[]}"#).await;
+        let args = &[
+            "lumio",
+            "node",
+            "merge-round-timelines",
+            "--admin-urls",
+            admin_url.as_str(),
+            "--passcode",
+            "secret",
+        ];
+        run_tool_with_args(args).await.unwrap();
+        let request_line = server.await.unwrap().lines().next().unwrap().to_string();
+        assert_eq!(
+            request_line,
+            "GET /debug/consensus/round_timeline?passcode=secret HTTP/1.1"
+        );
+    }
+
+    /// Spawns a fake admin service that answers a single request with the given body, and
+    /// returns its URL and the request it received
+    async fn spawn_admin_service(body: &'static str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let admin_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 4096];
+            let len = stream.read(&mut request).await.unwrap();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request[..len]).into_owned()
+        });
+        (admin_url, server)
+    }
+
     async fn run_tool_with_args(args: &[&str]) -> CliResult {
         let tool: Tool = Tool::try_parse_from(args).map_err(|msg| msg.to_string())?;
         tool.execute().await
@@ -995,6 +995,8 @@ fn convert_validator_transaction(
                     )
                 )
             },
+            // Not in the protobuf schema yet.
+            ApiValidatorTransactionEnum::EquivocationEvidence(_) => None,
         },
         events: convert_events(api_validator_txn.events()),
     })
@@ -0,0 +1,131 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Spreads out transactions that are predicted to conflict, so that parallel execution doesn't
+//! run them next to each other and re-execute one of them.
+//!
+//! Conflicts are predicted from the same read and write hints as the partitioners, which only
+//! cover coin transfers and `create_account`. A transaction conflicts with an earlier one if it
+//! reads or writes a location the earlier one writes. Transactions without hints are never
+//! delayed, and transactions of the same sender keep their relative order.
+
+use lumio_types::transaction::{
+    analyzed_transaction::{user_transaction_read_write_hints, StorageLocation},
+    signature_verified_transaction::SignatureVerifiedTransaction,
+    SignedTransaction, Transaction,
+};
+use move_core_types::account_address::AccountAddress;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Number of positions a transaction is kept apart from an earlier one it conflicts with,
+    /// where possible.
+    pub conflict_window: usize,
+    /// Number of pending transactions searched for one without conflicts, before giving up and
+    /// taking the oldest.
+    pub max_lookahead: usize,
+}
+
+/// A transaction whose conflicts can be predicted.
+pub trait ConflictHintedTransaction {
+    fn sender(&self) -> Option<AccountAddress>;
+
+    /// The (read, write) hints of the transaction, `None` if they can't be predicted.
+    fn read_write_hints(&self) -> Option<(Vec<StorageLocation>, Vec<StorageLocation>)>;
+}
+
+impl ConflictHintedTransaction for SignedTransaction {
+    fn sender(&self) -> Option<AccountAddress> {
+        Some(SignedTransaction::sender(self))
+    }
+
+    fn read_write_hints(&self) -> Option<(Vec<StorageLocation>, Vec<StorageLocation>)> {
+        user_transaction_read_write_hints(self)
+    }
+}
+
+impl ConflictHintedTransaction for SignatureVerifiedTransaction {
+    fn sender(&self) -> Option<AccountAddress> {
+        SignatureVerifiedTransaction::sender(self)
+    }
+
+    fn read_write_hints(&self) -> Option<(Vec<StorageLocation>, Vec<StorageLocation>)> {
+        match self {
+            SignatureVerifiedTransaction::Valid(Transaction::UserTransaction(txn)) => {
+                user_transaction_read_write_hints(txn)
+            },
+            _ => None,
+        }
+    }
+}
+
+struct PendingTransaction<Txn> {
+    txn: Txn,
+    sender: Option<AccountAddress>,
+    reads: Vec<StorageLocation>,
+    writes: Vec<StorageLocation>,
+}
+
+impl<Txn: ConflictHintedTransaction> PendingTransaction<Txn> {
+    fn new(txn: Txn) -> Self {
+        let (reads, writes) = txn.read_write_hints().unwrap_or_default();
+        Self {
+            sender: txn.sender(),
+            txn,
+            reads,
+            writes,
+        }
+    }
+}
+
+pub struct ConflictAwareShuffler {
+    pub config: Config,
+}
+
+impl ConflictAwareShuffler {
+    pub fn shuffle_generic<Txn: ConflictHintedTransaction>(&self, txns: Vec<Txn>) -> Vec<Txn> {
+        let mut pending: VecDeque<_> = txns.into_iter().map(PendingTransaction::new).collect();
+        let mut shuffled = Vec::with_capacity(pending.len());
+        // Position in `shuffled` of the last transaction writing to each location.
+        let mut last_writes: HashMap<StorageLocation, usize> = HashMap::new();
+
+        while !pending.is_empty() {
+            let position = shuffled.len();
+            let conflicts = |txn: &PendingTransaction<Txn>| {
+                txn.reads.iter().chain(txn.writes.iter()).any(|location| {
+                    last_writes
+                        .get(location)
+                        .is_some_and(|written| position - written < self.config.conflict_window)
+                })
+            };
+            // Senders of the pending transactions skipped over, whose later transactions can't
+            // be taken before them.
+            let mut skipped_senders = HashSet::new();
+            let chosen = pending
+                .iter()
+                .take(self.config.max_lookahead)
+                .position(|txn| {
+                    let sender_ready = txn
+                        .sender
+                        .is_none_or(|sender| !skipped_senders.contains(&sender));
+                    if sender_ready && !conflicts(txn) {
+                        return true;
+                    }
+                    skipped_senders.extend(txn.sender);
+                    false
+                })
+                .unwrap_or(0);
+
+            let txn = pending.remove(chosen).expect("Known to exist.");
+            for location in txn.writes {
+                last_writes.insert(location, position);
+            }
+            shuffled.push(txn.txn);
+        }
+        shuffled
+    }
+}
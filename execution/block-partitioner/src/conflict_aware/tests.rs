@@ -0,0 +1,167 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::conflict_aware::{Config, ConflictAwareShuffler, ConflictHintedTransaction};
+use lumio_types::{
+    state_store::state_key::StateKey, transaction::analyzed_transaction::StorageLocation,
+};
+use move_core_types::account_address::AccountAddress;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Txn {
+    id: usize,
+    sender: u8,
+    // `None` for transactions without hints.
+    writes: Option<Vec<u8>>,
+}
+
+fn location(key: u8) -> StorageLocation {
+    StorageLocation::Specific(StateKey::raw(&[key]))
+}
+
+impl ConflictHintedTransaction for Txn {
+    fn sender(&self) -> Option<AccountAddress> {
+        Some(AccountAddress::new([self.sender; AccountAddress::LENGTH]))
+    }
+
+    fn read_write_hints(&self) -> Option<(Vec<StorageLocation>, Vec<StorageLocation>)> {
+        self.writes
+            .as_ref()
+            .map(|writes| (vec![], writes.iter().copied().map(location).collect()))
+    }
+}
+
+fn shuffler(conflict_window: usize, max_lookahead: usize) -> ConflictAwareShuffler {
+    ConflictAwareShuffler {
+        config: Config {
+            conflict_window,
+            max_lookahead,
+        },
+    }
+}
+
+/// Number of transactions writing a location written by one of the previous `window` ones.
+fn num_conflicts(txns: &[Txn], window: usize) -> usize {
+    let mut last_writes = HashMap::new();
+    let mut conflicts = 0;
+    for (position, txn) in txns.iter().enumerate() {
+        let writes = txn.writes.clone().unwrap_or_default();
+        if writes.iter().any(|key| {
+            last_writes
+                .get(key)
+                .is_some_and(|written| position - written < window)
+        }) {
+            conflicts += 1;
+        }
+        for key in writes {
+            last_writes.insert(key, position);
+        }
+    }
+    conflicts
+}
+
+fn assert_sender_order_kept(original: &[Txn], shuffled: &[Txn]) {
+    let by_sender = |txns: &[Txn]| {
+        let mut by_sender: HashMap<u8, Vec<usize>> = HashMap::new();
+        for txn in txns {
+            by_sender.entry(txn.sender).or_default().push(txn.id);
+        }
+        by_sender
+    };
+    assert_eq!(by_sender(original), by_sender(shuffled));
+}
+
+/// Half of the transactions call a hot contract, and come in runs.
+fn hot_contract_workload(num_txns: usize) -> Vec<Txn> {
+    (0..num_txns)
+        .map(|id| Txn {
+            id,
+            sender: (id % 50) as u8,
+            writes: Some(
+                if (id / 4) % 2 == 0 {
+                    vec![0, id as u8 + 1]
+                } else {
+                    vec![id as u8 + 1]
+                },
+            ),
+        })
+        .collect()
+}
+
+#[test]
+fn test_no_conflicts() {
+    let txns: Vec<_> = (0..10)
+        .map(|id| Txn {
+            id,
+            sender: id as u8,
+            writes: Some(vec![id as u8]),
+        })
+        .collect();
+    assert_eq!(shuffler(4, 16).shuffle_generic(txns.clone()), txns);
+}
+
+#[test]
+fn test_spreads_conflicts() {
+    let txns = hot_contract_workload(200);
+    let shuffled = shuffler(2, 32).shuffle_generic(txns.clone());
+
+    assert_eq!(shuffled.len(), txns.len());
+    assert_sender_order_kept(&txns, &shuffled);
+    assert!(num_conflicts(&txns, 2) > 0);
+    assert_eq!(num_conflicts(&shuffled, 2), 0);
+}
+
+#[test]
+fn test_reduces_conflicts_with_limited_lookahead() {
+    let txns = hot_contract_workload(200);
+    let shuffled = shuffler(8, 4).shuffle_generic(txns.clone());
+
+    assert_sender_order_kept(&txns, &shuffled);
+    assert!(num_conflicts(&shuffled, 8) < num_conflicts(&txns, 8));
+}
+
+#[test]
+fn test_keeps_sender_order() {
+    // All transactions of the single sender conflict, so there's nothing to reorder.
+    let txns: Vec<_> = (0..10)
+        .map(|id| Txn {
+            id,
+            sender: 1,
+            writes: Some(vec![0]),
+        })
+        .collect();
+    assert_eq!(shuffler(4, 16).shuffle_generic(txns.clone()), txns);
+}
+
+#[test]
+fn test_transactions_without_hints_are_not_delayed() {
+    let txns = vec![
+        Txn {
+            id: 0,
+            sender: 0,
+            writes: Some(vec![0]),
+        },
+        Txn {
+            id: 1,
+            sender: 1,
+            writes: Some(vec![0]),
+        },
+        Txn {
+            id: 2,
+            sender: 2,
+            writes: None,
+        },
+        Txn {
+            id: 3,
+            sender: 3,
+            writes: None,
+        },
+    ];
+    let ids: Vec<_> = shuffler(2, 16)
+        .shuffle_generic(txns)
+        .into_iter()
+        .map(|txn| txn.id)
+        .collect();
+    assert_eq!(ids, vec![0, 2, 1, 3]);
+}
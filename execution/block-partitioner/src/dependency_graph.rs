@@ -0,0 +1,417 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use lumio_crypto::HashValue;
+use lumio_types::{
+    block_executor::partitioner::{PartitionedTransactions, RoundId, ShardId, TxnIndex},
+    state_store::state_key::StateKey,
+    transaction::analyzed_transaction::{AnalyzedTransaction, StorageLocation},
+};
+use move_core_types::account_address::AccountAddress;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::Write,
+};
+
+/// A transaction of a partitioned block, identified by its position in the execution order.
+#[derive(Clone, Debug, Serialize)]
+pub struct TxnNode {
+    pub txn_index: TxnIndex,
+    /// The shard the txn is assigned to, or `None` if it is executed by the global executor.
+    pub shard_id: Option<ShardId>,
+    /// The round the txn is assigned to, or `None` if it is executed by the global executor.
+    pub round_id: Option<RoundId>,
+    pub hash: HashValue,
+    pub sender: Option<AccountAddress>,
+    pub num_reads: usize,
+    pub num_writes: usize,
+    /// Whether the txn has read/write hints. Unhinted txns are assumed to read and write
+    /// everything.
+    pub hinted: bool,
+    /// Number of cross-shard edges the partitioner attached to the txn.
+    pub num_required_edges: usize,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum ConflictKind {
+    ReadAfterWrite,
+    WriteAfterWrite,
+}
+
+/// A conflict between a txn and the last txn that wrote a key it accesses before it, in the
+/// execution order.
+#[derive(Clone, Debug, Serialize)]
+pub struct ConflictEdge {
+    pub src: TxnIndex,
+    pub dst: TxnIndex,
+    pub kind: ConflictKind,
+    /// Whether the two txns are assigned to different shards.
+    pub cross_shard: bool,
+    /// The conflicting keys, in their debug representation.
+    pub keys: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ShardSummary {
+    /// The shard, or `None` for the global executor.
+    pub shard_id: Option<ShardId>,
+    /// Number of txns assigned to the shard in each round.
+    pub num_txns_by_round: Vec<usize>,
+    pub num_cross_shard_edges_in: usize,
+    pub num_cross_shard_edges_out: usize,
+}
+
+/// The read/write conflict graph of a partitioned block, with the shard and round assignment of
+/// every txn.
+///
+/// The graph is reconstructed from the read/write hints of the txns in their final execution order,
+/// so it works with the output of any `BlockPartitioner`. It only contains the edges that matter
+/// for sharded execution: from the last writer of a key to each subsequent txn accessing it.
+/// Wildcard locations and unhinted txns are modeled conservatively: they conflict with the last
+/// writer of every key (and, if they write, with every subsequent txn).
+#[derive(Clone, Debug, Serialize)]
+pub struct DependencyGraph {
+    pub num_shards: usize,
+    pub num_rounds: usize,
+    pub num_edges: usize,
+    pub num_cross_shard_edges: usize,
+    pub shards: Vec<ShardSummary>,
+    pub nodes: Vec<TxnNode>,
+    pub edges: Vec<ConflictEdge>,
+}
+
+impl DependencyGraph {
+    pub fn new(partitioned_txns: &PartitionedTransactions) -> Self {
+        let num_shards = partitioned_txns.num_shards();
+        let num_rounds = partitioned_txns
+            .sharded_txns()
+            .iter()
+            .map(|sub_blocks| sub_blocks.num_sub_blocks())
+            .max()
+            .unwrap_or(0);
+
+        // Collect the txns in the execution order: all shards of a round, round by round, and
+        // then the global txns.
+        let mut txns: Vec<(TxnNode, &AnalyzedTransaction)> = vec![];
+        for round_id in 0..num_rounds {
+            for (shard_id, sub_blocks) in partitioned_txns.sharded_txns().iter().enumerate() {
+                let Some(sub_block) = sub_blocks.get_sub_block(round_id) else {
+                    continue;
+                };
+                for (txn_index, txn_with_deps) in sub_block.txn_with_index_iter() {
+                    let node = Self::node(
+                        txn_index,
+                        Some(shard_id),
+                        Some(round_id),
+                        txn_with_deps.txn(),
+                        txn_with_deps
+                            .cross_shard_dependencies()
+                            .num_required_edges(),
+                    );
+                    txns.push((node, txn_with_deps.txn()));
+                }
+            }
+        }
+        let num_sharded_txns = partitioned_txns.num_sharded_txns();
+        for (i, txn_with_deps) in partitioned_txns.global_txns.iter().enumerate() {
+            let node = Self::node(
+                num_sharded_txns + i,
+                None,
+                None,
+                txn_with_deps.txn(),
+                txn_with_deps
+                    .cross_shard_dependencies()
+                    .num_required_edges(),
+            );
+            txns.push((node, txn_with_deps.txn()));
+        }
+        txns.sort_by_key(|(node, _)| node.txn_index);
+
+        let mut last_writers: HashMap<&StateKey, usize> = HashMap::new();
+        // The last txn that wrote a wildcard location (or was unhinted). It may have written any
+        // key, so every subsequent access conflicts with it.
+        let mut last_wildcard_writer: Option<usize> = None;
+        let mut edges: BTreeMap<(TxnIndex, TxnIndex, ConflictKind), Vec<String>> = BTreeMap::new();
+        for (pos, (node, txn)) in txns.iter().enumerate() {
+            let mut add_edge = |writer_pos: usize, is_write: bool, key: String| {
+                let kind = if is_write {
+                    ConflictKind::WriteAfterWrite
+                } else {
+                    ConflictKind::ReadAfterWrite
+                };
+                let keys = edges
+                    .entry((txns[writer_pos].0.txn_index, node.txn_index, kind))
+                    .or_default();
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            };
+
+            let reads = txn.read_hints().iter().map(|loc| (Some(loc), false));
+            let writes = txn.write_hints().iter().map(|loc| (Some(loc), true));
+            // An unhinted txn is modeled as a wildcard read and write.
+            let unhinted = (!txn.hinted())
+                .then_some([(None, false), (None, true)])
+                .into_iter()
+                .flatten();
+            for (location, is_write) in reads.chain(writes).chain(unhinted) {
+                let key = match location {
+                    Some(StorageLocation::Specific(key)) => key,
+                    wildcard => {
+                        let wildcard_key = wildcard
+                            .map_or_else(|| "unhinted".to_string(), |loc| format!("{:?}", loc));
+                        let writer_positions = last_writers
+                            .values()
+                            .copied()
+                            .chain(last_wildcard_writer)
+                            .collect::<BTreeSet<_>>();
+                        for writer_pos in writer_positions {
+                            add_edge(writer_pos, is_write, wildcard_key.clone());
+                        }
+                        continue;
+                    },
+                };
+                let writer_positions = last_writers
+                    .get(key)
+                    .copied()
+                    .into_iter()
+                    .chain(last_wildcard_writer)
+                    .collect::<BTreeSet<_>>();
+                for writer_pos in writer_positions {
+                    add_edge(writer_pos, is_write, format!("{:?}", key));
+                }
+            }
+            // Only update the last writers after all accesses are processed, so that a txn never
+            // depends on itself.
+            for location in txn.write_hints() {
+                match location {
+                    StorageLocation::Specific(key) => {
+                        last_writers.insert(key, pos);
+                    },
+                    _ => last_wildcard_writer = Some(pos),
+                }
+            }
+            if !txn.hinted() {
+                last_wildcard_writer = Some(pos);
+            }
+        }
+
+        let nodes = txns.into_iter().map(|(node, _)| node).collect::<Vec<_>>();
+        let position: HashMap<TxnIndex, &TxnNode> =
+            nodes.iter().map(|node| (node.txn_index, node)).collect();
+        let mut shards = (0..num_shards)
+            .map(|shard_id| ShardSummary {
+                shard_id: Some(shard_id),
+                num_txns_by_round: vec![0; num_rounds],
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        if !partitioned_txns.global_txns.is_empty() {
+            shards.push(ShardSummary {
+                shard_id: None,
+                num_txns_by_round: vec![partitioned_txns.global_txns.len()],
+                ..Default::default()
+            });
+        }
+        // The global executor, if any, is the last entry in `shards`.
+        let summary_idx = |shard_id: Option<ShardId>| shard_id.unwrap_or(num_shards);
+        for node in &nodes {
+            if let (Some(shard_id), Some(round_id)) = (node.shard_id, node.round_id) {
+                shards[shard_id].num_txns_by_round[round_id] += 1;
+            }
+        }
+
+        let edges = edges
+            .into_iter()
+            .map(|((src, dst, kind), keys)| {
+                let src_shard = position[&src].shard_id;
+                let dst_shard = position[&dst].shard_id;
+                let cross_shard = src_shard != dst_shard;
+                if cross_shard {
+                    shards[summary_idx(src_shard)].num_cross_shard_edges_out += 1;
+                    shards[summary_idx(dst_shard)].num_cross_shard_edges_in += 1;
+                }
+                ConflictEdge {
+                    src,
+                    dst,
+                    kind,
+                    cross_shard,
+                    keys,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            num_shards,
+            num_rounds,
+            num_edges: edges.len(),
+            num_cross_shard_edges: edges.iter().filter(|edge| edge.cross_shard).count(),
+            shards,
+            nodes,
+            edges,
+        }
+    }
+
+    fn node(
+        txn_index: TxnIndex,
+        shard_id: Option<ShardId>,
+        round_id: Option<RoundId>,
+        txn: &AnalyzedTransaction,
+        num_required_edges: usize,
+    ) -> TxnNode {
+        TxnNode {
+            txn_index,
+            shard_id,
+            round_id,
+            hash: txn.transaction().hash(),
+            sender: txn.sender(),
+            num_reads: txn.read_hints().len(),
+            num_writes: txn.write_hints().len(),
+            hinted: txn.hinted(),
+            num_required_edges,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Renders the graph in the DOT format, with one cluster per shard, cross-shard edges
+    /// highlighted in red and unhinted txns dashed.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph dependencies {{").unwrap();
+        writeln!(dot, "  rankdir=LR;").unwrap();
+        writeln!(dot, "  node [shape=box];").unwrap();
+        for summary in &self.shards {
+            let (name, label) = match summary.shard_id {
+                Some(shard_id) => (format!("shard_{}", shard_id), format!("shard {}", shard_id)),
+                None => ("global".to_string(), "global".to_string()),
+            };
+            writeln!(dot, "  subgraph cluster_{} {{", name).unwrap();
+            writeln!(dot, "    label=\"{}\";", label).unwrap();
+            for node in self
+                .nodes
+                .iter()
+                .filter(|node| node.shard_id == summary.shard_id)
+            {
+                let round = node
+                    .round_id
+                    .map_or_else(|| "global".to_string(), |round_id| round_id.to_string());
+                let style = if node.hinted { "" } else { ", style=dashed" };
+                writeln!(
+                    dot,
+                    "    t{} [label=\"txn {}\\nround {}\"{}];",
+                    node.txn_index, node.txn_index, round, style
+                )
+                .unwrap();
+            }
+            writeln!(dot, "  }}").unwrap();
+        }
+        for edge in &self.edges {
+            let (label, color) = match edge.kind {
+                ConflictKind::ReadAfterWrite => ("RAW", "black"),
+                ConflictKind::WriteAfterWrite => ("WAW", "gray"),
+            };
+            let color = if edge.cross_shard { "red" } else { color };
+            writeln!(
+                dot,
+                "  t{} -> t{} [label=\"{} ({})\", color={}];",
+                edge.src,
+                edge.dst,
+                label,
+                edge.keys.len(),
+                color
+            )
+            .unwrap();
+        }
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DependencyGraph;
+    use crate::{
+        test_utils::{
+            create_signed_generic_transaction, create_signed_p2p_transaction, generate_test_account,
+        },
+        v2::config::PartitionerV2Config,
+        PartitionerConfig,
+    };
+    use lumio_types::transaction::analyzed_transaction::AnalyzedTransaction;
+
+    #[test]
+    fn test_conflict_graph() {
+        // A sends to B, then B sends to C, so the second txn conflicts with the first one.
+        let mut sender = generate_test_account();
+        let mut receiver = generate_test_account();
+        let last_receiver = generate_test_account();
+        let mut transactions = create_signed_p2p_transaction(&mut sender, vec![&receiver]);
+        transactions.extend(create_signed_p2p_transaction(&mut receiver, vec![
+            &last_receiver,
+        ]));
+
+        let partitioner = PartitionerV2Config::default().build();
+        let partitioned_txns = partitioner.partition(transactions, 2);
+        let graph = DependencyGraph::new(&partitioned_txns);
+
+        assert_eq!(graph.nodes.len(), 2);
+        assert!(graph.num_edges > 0);
+        assert!(graph.edges.iter().all(|edge| edge.src < edge.dst));
+        assert_eq!(
+            graph.num_cross_shard_edges,
+            graph.edges.iter().filter(|edge| edge.cross_shard).count()
+        );
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph"));
+        assert!(dot.contains("t0 -> t1"));
+        graph.to_json().unwrap();
+    }
+
+    #[test]
+    fn test_conflict_graph_with_unhinted_txn() {
+        // A sends to B, C calls a generic entry function and D sends to E. The generic txn is
+        // unhinted, so it conflicts with both transfers.
+        let mut sender = generate_test_account();
+        let receiver = generate_test_account();
+        let mut generic_sender = generate_test_account();
+        let mut last_sender = generate_test_account();
+        let last_receiver = generate_test_account();
+        let mut transactions = create_signed_p2p_transaction(&mut sender, vec![&receiver]);
+        // Convert the txn the same way the exporter converts txns read from a DB
+        let generic_txn: AnalyzedTransaction =
+            create_signed_generic_transaction(&mut generic_sender, &receiver).into();
+        assert!(!generic_txn.hinted());
+        assert!(!generic_txn.predictable_transaction());
+        transactions.push(generic_txn);
+        transactions.extend(create_signed_p2p_transaction(&mut last_sender, vec![
+            &last_receiver,
+        ]));
+
+        let partitioner = PartitionerV2Config::default().build();
+        let partitioned_txns = partitioner.partition(transactions, 2);
+        let graph = DependencyGraph::new(&partitioned_txns);
+
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.nodes.iter().filter(|node| !node.hinted).count(), 1);
+        let unhinted_index = graph
+            .nodes
+            .iter()
+            .find(|node| !node.hinted)
+            .unwrap()
+            .txn_index;
+        // The unhinted txn conflicts with every other txn, in whichever order they execute
+        for node in graph.nodes.iter().filter(|node| node.hinted) {
+            assert!(graph.edges.iter().any(|edge| {
+                (edge.src, edge.dst) == (node.txn_index, unhinted_index)
+                    || (edge.src, edge.dst) == (unhinted_index, node.txn_index)
+            }));
+        }
+        assert!(graph.edges.iter().all(|edge| edge.src < edge.dst));
+        assert!(graph.to_dot().contains("style=dashed"));
+        graph.to_json().unwrap();
+    }
+}
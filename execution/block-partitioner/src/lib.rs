@@ -4,6 +4,8 @@
 
 pub mod v2;
 
+pub mod conflict_aware;
+pub mod dependency_graph;
 pub mod test_utils;
 
 use lumio_types::{
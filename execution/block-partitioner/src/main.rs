@@ -2,12 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use lumio_block_partitioner::{
-    test_utils::P2PBlockGenerator, v2::config::PartitionerV2Config, PartitionerConfig,
+    dependency_graph::DependencyGraph, test_utils::P2PBlockGenerator,
+    v2::config::PartitionerV2Config, PartitionerConfig,
 };
 use lumio_logger::info;
-use clap::Parser;
+use lumio_types::transaction::{analyzed_transaction::AnalyzedTransaction, Transaction};
+use clap::{Parser, Subcommand};
 use rand::thread_rng;
-use std::time::Instant;
+use std::{fs, path::PathBuf, time::Instant};
 
 #[cfg(unix)]
 #[global_allocator]
@@ -15,6 +17,9 @@ static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 
 #[derive(Debug, Parser)]
 struct Args {
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+
     #[clap(long, default_value_t = 1000000)]
     pub num_accounts: usize,
 
@@ -28,10 +33,99 @@ struct Args {
     pub num_shards: usize,
 }
 
-fn main() {
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Partitions a single block and exports its read/write conflict graph as `graph.dot` and
+    /// `graph.json`, with the shard assignment of every txn and cross-shard edge counts.
+    ExportGraph(ExportGraphArgs),
+}
+
+#[derive(Debug, Parser)]
+struct ExportGraphArgs {
+    /// BCS-serialized `Vec<Transaction>` to partition, e.g., exported from a DB with
+    /// `lumio-db-tool debug ledger export-transactions`. If not set, a random P2P block is
+    /// generated. Txns without read/write hints (i.e., anything but coin transfers and account
+    /// creations) are drawn as conflicting with every other txn.
+    #[clap(long)]
+    pub transactions_file: Option<PathBuf>,
+
+    #[clap(long, default_value_t = 1000)]
+    pub num_accounts: usize,
+
+    #[clap(long, default_value_t = 1000)]
+    pub block_size: usize,
+
+    #[clap(long, default_value_t = 4)]
+    pub num_shards: usize,
+
+    #[clap(long, default_value_t = 4)]
+    pub max_partitioning_rounds: usize,
+
+    #[clap(long, default_value_t = 0.9)]
+    pub cross_shard_dep_avoid_threshold: f32,
+
+    #[clap(long)]
+    pub partition_last_round: bool,
+
+    #[clap(long, default_value = "partitioner-graph")]
+    pub output_dir: PathBuf,
+}
+
+fn export_graph(args: ExportGraphArgs) -> anyhow::Result<()> {
+    let transactions: Vec<AnalyzedTransaction> = match &args.transactions_file {
+        Some(path) => {
+            let transactions: Vec<Transaction> = bcs::from_bytes(&fs::read(path)?)?;
+            transactions.into_iter().map(|txn| txn.into()).collect()
+        },
+        None => {
+            P2PBlockGenerator::new(args.num_accounts).rand_block(&mut thread_rng(), args.block_size)
+        },
+    };
+
+    let partitioner = PartitionerV2Config::default()
+        .max_partitioning_rounds(args.max_partitioning_rounds)
+        .cross_shard_dep_avoid_threshold(args.cross_shard_dep_avoid_threshold)
+        .partition_last_round(args.partition_last_round)
+        .build();
+    let partitioned = partitioner.partition(transactions, args.num_shards);
+    let graph = DependencyGraph::new(&partitioned);
+
+    fs::create_dir_all(&args.output_dir)?;
+    fs::write(args.output_dir.join("graph.dot"), graph.to_dot())?;
+    fs::write(args.output_dir.join("graph.json"), graph.to_json()?)?;
+
+    println!(
+        "{} txns in {} rounds, {} conflict edges, {} cross-shard",
+        graph.nodes.len(),
+        graph.num_rounds,
+        graph.num_edges,
+        graph.num_cross_shard_edges
+    );
+    for summary in &graph.shards {
+        let name = summary.shard_id.map_or_else(
+            || "global".to_string(),
+            |shard_id| format!("shard {}", shard_id),
+        );
+        println!(
+            "{}: txns by round {:?}, cross-shard edges in {}, out {}",
+            name,
+            summary.num_txns_by_round,
+            summary.num_cross_shard_edges_in,
+            summary.num_cross_shard_edges_out
+        );
+    }
+    println!("Graph saved to {}", args.output_dir.display());
+    Ok(())
+}
+
+fn main() -> anyhow::Result<()> {
     lumio_logger::Logger::new().init();
-    info!("Starting the block partitioning benchmark");
     let args = Args::parse();
+    if let Some(Command::ExportGraph(export_args)) = args.command {
+        return export_graph(export_args);
+    }
+
+    info!("Starting the block partitioning benchmark");
     let block_gen = P2PBlockGenerator::new(args.num_accounts);
     let partitioner = PartitionerV2Config::default()
         .max_partitioning_rounds(4)
@@ -49,6 +143,7 @@ fn main() {
         let elapsed = now.elapsed();
         info!("Time taken to partition: {:?}", elapsed);
     }
+    Ok(())
 }
 
 #[test]
@@ -91,27 +91,50 @@ pub fn create_signed_p2p_transaction(
                 bcs::to_bytes(&1u64).unwrap(),
             ],
         ));
-
-        let raw_transaction = RawTransaction::new(
-            sender.account_address,
-            sender.sequence_number,
-            transaction_payload,
-            0,
-            0,
-            0,
-            ChainId::new(10),
-        );
-        sender.sequence_number += 1;
-        let txn = Transaction::UserTransaction(SignedTransaction::new(
-            raw_transaction.clone(),
-            sender.private_key.public_key().clone(),
-            sender.private_key.sign(&raw_transaction).unwrap(),
-        ));
-        transactions.push(txn.into())
+        transactions.push(create_signed_transaction(sender, transaction_payload).into())
     }
     transactions
 }
 
+/// Creates a txn calling an entry function without read/write hints (i.e., neither a coin
+/// transfer nor an account creation).
+pub fn create_signed_generic_transaction(
+    sender: &mut TestAccount,
+    receiver: &TestAccount,
+) -> Transaction {
+    let transaction_payload = TransactionPayload::EntryFunction(EntryFunction::new(
+        ModuleId::new(AccountAddress::ONE, Identifier::new("object").unwrap()),
+        Identifier::new("transfer_call").unwrap(),
+        vec![],
+        vec![
+            bcs::to_bytes(&AccountAddress::random()).unwrap(),
+            bcs::to_bytes(&receiver.account_address).unwrap(),
+        ],
+    ));
+    create_signed_transaction(sender, transaction_payload)
+}
+
+fn create_signed_transaction(
+    sender: &mut TestAccount,
+    transaction_payload: TransactionPayload,
+) -> Transaction {
+    let raw_transaction = RawTransaction::new(
+        sender.account_address,
+        sender.sequence_number,
+        transaction_payload,
+        0,
+        0,
+        0,
+        ChainId::new(10),
+    );
+    sender.sequence_number += 1;
+    Transaction::UserTransaction(SignedTransaction::new(
+        raw_transaction.clone(),
+        sender.private_key.public_key().clone(),
+        sender.private_key.sign(&raw_transaction).unwrap(),
+    ))
+}
+
 pub struct P2PBlockGenerator {
     accounts: Arc<Vec<Mutex<TestAccount>>>,
 }
@@ -5,7 +5,10 @@ use crate::{
     metrics::{NUM_TXNS, TIMER},
     pipeline::ExecuteBlockMessage,
 };
-use lumio_block_partitioner::{BlockPartitioner, PartitionerConfig};
+use lumio_block_partitioner::{
+    conflict_aware::{self, ConflictAwareShuffler},
+    BlockPartitioner, PartitionerConfig,
+};
 use lumio_crypto::HashValue;
 use lumio_experimental_runtimes::thread_manager::optimal_min_len;
 use lumio_logger::info;
@@ -33,6 +36,8 @@ pub(crate) struct BlockPreparationStage {
     num_executor_shards: usize,
     /// When execution sharding is enabled, partitioner that splits block into shards
     maybe_partitioner: Option<Box<dyn BlockPartitioner>>,
+    /// Shuffler spreading out the transactions of the block predicted to conflict
+    maybe_shuffler: Option<ConflictAwareShuffler>,
 }
 
 impl BlockPreparationStage {
@@ -40,6 +45,7 @@ impl BlockPreparationStage {
         num_sig_verify_threads: usize,
         num_shards: usize,
         partitioner_config: &dyn PartitionerConfig,
+        conflict_aware_shuffling: Option<conflict_aware::Config>,
     ) -> Self {
         let maybe_partitioner = if num_shards == 0 {
             None
@@ -58,6 +64,7 @@ impl BlockPreparationStage {
             num_blocks_processed: 0,
             maybe_partitioner,
             sig_verify_pool,
+            maybe_shuffler: conflict_aware_shuffling.map(|config| ConflictAwareShuffler { config }),
         }
     }
 
@@ -81,6 +88,13 @@ impl BlockPreparationStage {
                     .map(|t| t.into())
                     .collect::<Vec<_>>()
             });
+        let sig_verified_txns = match &self.maybe_shuffler {
+            None => sig_verified_txns,
+            Some(shuffler) => {
+                let _timer = TIMER.timer_with(&["shuffle"]);
+                shuffler.shuffle_generic(sig_verified_txns)
+            },
+        };
         let block: ExecutableBlock = match &self.maybe_partitioner {
             None => (block_id, sig_verified_txns).into(),
             Some(partitioner) => {
@@ -680,6 +680,7 @@ mod tests {
         transaction_generator::TransactionGenerator,
         BenchmarkWorkload,
     };
+    use lumio_block_partitioner::conflict_aware;
     use lumio_config::config::NO_OP_STORAGE_PRUNER_CONFIG;
     use lumio_crypto::HashValue;
     use lumio_executor::block_executor::BlockExecutor;
@@ -1060,4 +1061,72 @@ mod tests {
             NativeParallelUncoordinatedBlockExecutor<NativeNoStorageRawTransactionExecutor>,
         >(Some(TransactionTypeArg::NoOp), false);
     }
+
+    #[test]
+    fn test_report_conflict_aware_shuffling_reexecutions() {
+        LumioVM::set_concurrency_level_once(4);
+        lumio_logger::Logger::new().init();
+
+        let storage_dir = TempPath::new();
+        let features = default_benchmark_features();
+        crate::db_generator::create_db_with_accounts::<LumioVMBlockExecutor>(
+            100,             /* num_accounts */
+            100_000_000_000, /* init_account_balance */
+            5,               /* block_size */
+            storage_dir.as_ref(),
+            NO_OP_STORAGE_PRUNER_CONFIG, /* prune_window */
+            true,
+            false,
+            PipelineConfig::default(),
+            features.clone(),
+            false,
+        );
+
+        let run = |conflict_aware_shuffling| {
+            super::run_benchmark::<LumioVMBlockExecutor>(
+                100, /* block_size */
+                10,  /* num_blocks */
+                BenchmarkWorkload::Transfer {
+                    connected_tx_grps: 0,
+                    shuffle_connected_txns: false,
+                    hotspot_probability: Some(0.5),
+                },
+                1,  /* transactions per sender */
+                50, /* num_main_signer_accounts */
+                30, /* num_dst_pool_accounts */
+                storage_dir.as_ref(),
+                TempPath::new(),
+                true,
+                NO_OP_STORAGE_PRUNER_CONFIG,
+                false,
+                PipelineConfig {
+                    conflict_aware_shuffling,
+                    ..Default::default()
+                },
+                features.clone(),
+                false,
+            )
+            .measurements
+        };
+
+        let unshuffled = run(None);
+        let shuffled = run(Some(conflict_aware::Config {
+            conflict_window: 8,
+            max_lookahead: 32,
+        }));
+
+        // Re-executions depend on thread scheduling, so they can't be asserted on, only reported.
+        // Sequence numbers are verified by both runs, so shuffling committed every transaction.
+        for (label, measurement) in [
+            ("without shuffling", &unshuffled),
+            ("with conflict aware shuffling", &shuffled),
+        ] {
+            println!(
+                "BlockSTM re-executions {}: {} ({:.4} per txn)",
+                label,
+                measurement.get_speculative_abort_count(),
+                measurement.get_speculative_abort_rate(),
+            );
+        }
+    }
 }
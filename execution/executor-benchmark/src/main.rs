@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use lumio_block_partitioner::{
+    conflict_aware,
     pre_partition::{
         connected_component::config::ConnectedComponentPartitionerConfig,
         default_pre_partitioner_config, uniform_partitioner::config::UniformPartitionerConfig,
@@ -150,6 +151,16 @@ pub struct PipelineOpt {
     /// Sharding configuration.
     #[clap(flatten)]
     sharding_opt: ShardingOpt,
+    /// Spread out the transactions of each block predicted to conflict by their read/write
+    /// hints before execution. Hints only cover coin transfers and `create_account`.
+    #[clap(long)]
+    conflict_aware_shuffling: bool,
+    /// For the conflict aware shuffler, how many positions conflicting transactions are kept apart.
+    #[clap(long, default_value = "8")]
+    conflict_window: usize,
+    /// For the conflict aware shuffler, number of transactions searched for one without conflicts.
+    #[clap(long, default_value = "32")]
+    conflict_lookahead: usize,
 }
 
 impl PipelineOpt {
@@ -165,6 +176,12 @@ impl PipelineOpt {
             num_generator_workers: self.num_generator_workers,
             partitioner_config: self.sharding_opt.partitioner_config(),
             num_sig_verify_threads: self.num_sig_verify_threads,
+            conflict_aware_shuffling: self.conflict_aware_shuffling.then(|| {
+                conflict_aware::Config {
+                    conflict_window: self.conflict_window,
+                    max_lookahead: self.conflict_lookahead,
+                }
+            }),
             print_transactions: false,
         }
     }
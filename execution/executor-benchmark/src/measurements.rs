@@ -252,6 +252,10 @@ impl OverallMeasurement {
         self.delta_gas.effective_block_gas / self.delta_gas.gas
     }
 
+    pub fn get_speculative_abort_count(&self) -> u64 {
+        self.delta_gas.speculative_abort_count
+    }
+
     pub fn get_speculative_abort_rate(&self) -> f64 {
         self.delta_gas.speculative_abort_count as f64 / self.num_txns as f64
     }
@@ -8,7 +8,7 @@ use crate::{
     metrics::NUM_TXNS,
     OverallMeasurement, TransactionCommitter, TransactionExecutor,
 };
-use lumio_block_partitioner::v2::config::PartitionerV2Config;
+use lumio_block_partitioner::{conflict_aware, v2::config::PartitionerV2Config};
 use lumio_crypto::HashValue;
 use lumio_executor::block_executor::BlockExecutor;
 use lumio_executor_types::{state_compute_result::StateComputeResult, BlockExecutorTrait};
@@ -49,6 +49,8 @@ pub struct PipelineConfig {
     pub partitioner_config: PartitionerV2Config,
     #[derivative(Default(value = "8"))]
     pub num_sig_verify_threads: usize,
+    /// Conflict aware shuffling applied to each block before execution, if any.
+    pub conflict_aware_shuffling: Option<conflict_aware::Config>,
 
     pub print_transactions: bool,
 }
@@ -126,6 +128,7 @@ where
             // Assume the distributed executor and the distributed partitioner share the same worker set.
             config.num_executor_shards,
             &config.partitioner_config,
+            config.conflict_aware_shuffling.clone(),
         );
 
         let mut exe = TransactionExecutor::new(executor_1, parent_block_id, ledger_update_sender);
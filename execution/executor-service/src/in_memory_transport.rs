@@ -0,0 +1,241 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+use crate::transport::ExecutorTransport;
+use lumio_logger::trace;
+use lumio_secure_net::network_controller::Message;
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+    net::SocketAddr,
+    sync::{Arc, Condvar, Mutex},
+    thread,
+    time::Duration,
+};
+
+/// Faults injected by the in-memory transport into the delivery of every message.
+#[derive(Clone, Debug, Default)]
+pub struct FaultConfig {
+    /// Delay applied to every message before it is delivered.
+    pub delay: Duration,
+    /// Upper bound of an additional random delay drawn for every message. Each channel still
+    /// delivers its messages in order, so random delays only reorder messages across channels,
+    /// which matches the guarantees of the network transport.
+    pub max_jitter: Duration,
+    /// Seed of the random number generators used to draw the delays.
+    pub seed: u64,
+}
+
+type ChannelId = (SocketAddr, String);
+
+#[derive(Default)]
+struct NetworkState {
+    // The senders of the inbound channels, keyed by the address of the receiving endpoint and the
+    // message type.
+    inbound_txs: HashMap<ChannelId, Sender<Message>>,
+    // The addresses of the endpoints that created an outbound channel to each inbound channel.
+    sources: HashMap<ChannelId, HashSet<SocketAddr>>,
+    crashed: HashSet<SocketAddr>,
+}
+
+/// An in-process network connecting executor shards and the coordinator, used to run sharded
+/// execution deterministically in tests and to inject delays, reordering and crashes.
+#[derive(Clone, Default)]
+pub struct InMemoryNetwork {
+    fault_config: FaultConfig,
+    state: Arc<(Mutex<NetworkState>, Condvar)>,
+}
+
+impl InMemoryNetwork {
+    pub fn new(fault_config: FaultConfig) -> Self {
+        Self {
+            fault_config,
+            state: Arc::new((Mutex::new(NetworkState::default()), Condvar::new())),
+        }
+    }
+
+    /// Creates the transport of the endpoint listening at the given address.
+    pub fn create_transport(&self, self_address: SocketAddr) -> InMemoryTransport {
+        InMemoryTransport {
+            network: self.clone(),
+            self_address,
+        }
+    }
+
+    /// Simulates a crash of the endpoint at the given address. Messages sent to or by the endpoint
+    /// are dropped from now on, its inbound channels are disconnected, and so are the inbound
+    /// channels of other endpoints whose senders have all crashed.
+    pub fn crash(&self, address: SocketAddr) {
+        let (lock, registered) = &*self.state;
+        let mut state = lock.lock().unwrap();
+        state.crashed.insert(address);
+
+        let NetworkState {
+            inbound_txs,
+            sources,
+            crashed,
+        } = &mut *state;
+        inbound_txs.retain(|channel_id, _| {
+            let all_sources_crashed = sources
+                .get(channel_id)
+                .is_some_and(|sources| sources.iter().all(|source| crashed.contains(source)));
+            channel_id.0 != address && !all_sources_crashed
+        });
+        registered.notify_all();
+    }
+
+    pub fn is_crashed(&self, address: &SocketAddr) -> bool {
+        self.state.0.lock().unwrap().crashed.contains(address)
+    }
+
+    fn register_inbound(&self, channel_id: ChannelId, tx: Sender<Message>) {
+        let (lock, registered) = &*self.state;
+        let mut state = lock.lock().unwrap();
+        if !state.crashed.contains(&channel_id.0) {
+            state.inbound_txs.insert(channel_id, tx);
+        }
+        registered.notify_all();
+    }
+
+    fn register_outbound(
+        &self,
+        source: SocketAddr,
+        channel_id: ChannelId,
+        outbound_rx: Receiver<Message>,
+    ) {
+        self.state
+            .0
+            .lock()
+            .unwrap()
+            .sources
+            .entry(channel_id.clone())
+            .or_default()
+            .insert(source);
+
+        // Derive a per-channel seed so that the delays do not depend on the order in which the
+        // channels are created.
+        let mut hasher = DefaultHasher::new();
+        (self.fault_config.seed, source, &channel_id).hash(&mut hasher);
+        let mut rng = StdRng::seed_from_u64(hasher.finish());
+
+        let network = self.clone();
+        thread::Builder::new()
+            .name(format!("in-memory-{}-{}", channel_id.1, channel_id.0))
+            .spawn(move || {
+                while let Ok(message) = outbound_rx.recv() {
+                    thread::sleep(network.draw_delay(&mut rng));
+                    network.deliver(source, &channel_id, message);
+                }
+            })
+            .expect("Failed to spawn thread");
+    }
+
+    fn draw_delay(&self, rng: &mut StdRng) -> Duration {
+        let max_jitter_micros = self.fault_config.max_jitter.as_micros() as u64;
+        let jitter = if max_jitter_micros == 0 {
+            0
+        } else {
+            rng.gen_range(0, max_jitter_micros + 1)
+        };
+        self.fault_config.delay + Duration::from_micros(jitter)
+    }
+
+    // Delivers the message to the inbound channel, waiting for the receiving endpoint to create it
+    // if needed. The message is dropped if either of the endpoints has crashed.
+    fn deliver(&self, source: SocketAddr, channel_id: &ChannelId, message: Message) {
+        let (lock, registered) = &*self.state;
+        let mut state = lock.lock().unwrap();
+        loop {
+            if state.crashed.contains(&source) || state.crashed.contains(&channel_id.0) {
+                trace!(
+                    "Dropping {} message from {} to {}",
+                    channel_id.1,
+                    source,
+                    channel_id.0
+                );
+                return;
+            }
+            if let Some(tx) = state.inbound_txs.get(channel_id) {
+                // The receiver might have been dropped already, in which case the message is lost.
+                let _ = tx.send(message);
+                return;
+            }
+            state = registered.wait(state).unwrap();
+        }
+    }
+}
+
+/// The endpoint of a single shard or coordinator on an [InMemoryNetwork].
+pub struct InMemoryTransport {
+    network: InMemoryNetwork,
+    self_address: SocketAddr,
+}
+
+impl ExecutorTransport for InMemoryTransport {
+    fn create_outbound_channel(
+        &mut self,
+        remote_addr: SocketAddr,
+        message_type: String,
+    ) -> Sender<Message> {
+        let (outbound_tx, outbound_rx) = unbounded();
+        self.network
+            .register_outbound(self.self_address, (remote_addr, message_type), outbound_rx);
+        outbound_tx
+    }
+
+    fn create_inbound_channel(&mut self, message_type: String) -> Receiver<Message> {
+        let (inbound_tx, inbound_rx) = unbounded();
+        self.network
+            .register_inbound((self.self_address, message_type), inbound_tx);
+        inbound_rx
+    }
+
+    fn start(&mut self) {}
+
+    // Shutting down an endpoint disconnects it from the network, same as a crash.
+    fn shutdown(&mut self) {
+        self.network.crash(self.self_address);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FaultConfig, InMemoryNetwork};
+    use crate::transport::ExecutorTransport;
+    use lumio_secure_net::network_controller::Message;
+    use std::{
+        net::{IpAddr, Ipv4Addr, SocketAddr},
+        time::Duration,
+    };
+
+    #[test]
+    fn test_in_order_delivery_and_crash() {
+        let network = InMemoryNetwork::new(FaultConfig {
+            delay: Duration::from_micros(10),
+            max_jitter: Duration::from_micros(500),
+            seed: 7,
+        });
+        let addr1 = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1);
+        let addr2 = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 2);
+        let mut transport1 = network.create_transport(addr1);
+        let mut transport2 = network.create_transport(addr2);
+
+        // The outbound channel is created before the inbound one, so messages must wait for it.
+        let tx = transport1.create_outbound_channel(addr2, "test".to_string());
+        for i in 0..20u8 {
+            tx.send(Message::new(vec![i])).unwrap();
+        }
+        let rx = transport2.create_inbound_channel("test".to_string());
+        for i in 0..20u8 {
+            assert_eq!(rx.recv().unwrap().to_bytes(), vec![i]);
+        }
+
+        // Once the only sender crashes, the inbound channel is disconnected.
+        network.crash(addr1);
+        tx.send(Message::new(vec![20])).unwrap();
+        assert!(rx.recv().is_err());
+        assert!(network.is_crashed(&addr1));
+        assert!(!network.is_crashed(&addr2));
+    }
+}
@@ -12,6 +12,7 @@ use lumio_types::{
 use serde::{Deserialize, Serialize};
 
 mod error;
+pub mod in_memory_transport;
 pub mod local_executor_helper;
 mod metrics;
 pub mod process_executor_service;
@@ -27,6 +28,7 @@ mod test_utils;
 mod tests;
 #[cfg(test)]
 mod thread_executor_service;
+pub mod transport;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct RemoteExecutionResult {
@@ -2,9 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
     metrics::REMOTE_EXECUTOR_TIMER, remote_state_view::RemoteStateViewClient, ExecuteBlockCommand,
-    RemoteExecutionRequest, RemoteExecutionResult,
+    transport::ExecutorTransport, RemoteExecutionRequest, RemoteExecutionResult,
 };
-use lumio_secure_net::network_controller::{Message, NetworkController};
+use lumio_secure_net::network_controller::Message;
 use lumio_types::{
     block_executor::partitioner::ShardId, state_store::state_key::StateKey,
     transaction::TransactionOutput, vm_status::VMStatus,
@@ -26,7 +26,7 @@ pub struct RemoteCoordinatorClient {
 impl RemoteCoordinatorClient {
     pub fn new(
         shard_id: ShardId,
-        controller: &mut NetworkController,
+        controller: &mut impl ExecutorTransport,
         coordinator_address: SocketAddr,
     ) -> Self {
         let execute_command_type = format!("execute_command_{}", shard_id);
@@ -1,6 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
-use lumio_secure_net::network_controller::{Message, NetworkController};
+use crate::transport::ExecutorTransport;
+use lumio_secure_net::network_controller::Message;
 use lumio_types::block_executor::partitioner::{RoundId, ShardId, MAX_ALLOWED_PARTITIONING_ROUNDS};
 use lumio_vm::sharded_block_executor::{
     cross_shard_client::CrossShardClient, messages::CrossShardMsg,
@@ -19,7 +20,7 @@ pub struct RemoteCrossShardClient {
 }
 
 impl RemoteCrossShardClient {
-    pub fn new(controller: &mut NetworkController, shard_addresses: Vec<SocketAddr>) -> Self {
+    pub fn new(controller: &mut impl ExecutorTransport, shard_addresses: Vec<SocketAddr>) -> Self {
         let mut message_txs = vec![];
         let mut message_rxs = vec![];
         // Create outbound channels for each shard per round.
@@ -2,8 +2,8 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    remote_state_view_service::RemoteStateViewService, ExecuteBlockCommand, RemoteExecutionRequest,
-    RemoteExecutionResult,
+    remote_state_view_service::RemoteStateViewService, transport::ExecutorTransport,
+    ExecuteBlockCommand, RemoteExecutionRequest, RemoteExecutionResult,
 };
 use lumio_logger::{info, trace};
 use lumio_secure_net::network_controller::{Message, NetworkController};
@@ -14,7 +14,7 @@ use lumio_types::{
     },
     state_store::StateView,
     transaction::TransactionOutput,
-    vm_status::VMStatus,
+    vm_status::{StatusCode, VMStatus},
 };
 use lumio_vm::sharded_block_executor::{
     executor_client::{ExecutorClient, ShardedExecutionOutput},
@@ -73,11 +73,14 @@ pub static REMOTE_SHARDED_BLOCK_EXECUTOR: Lazy<
 });
 
 #[allow(dead_code)]
-pub struct RemoteExecutorClient<S: StateView + Sync + Send + 'static> {
+pub struct RemoteExecutorClient<
+    S: StateView + Sync + Send + 'static,
+    T: ExecutorTransport = NetworkController,
+> {
     // The network controller used to create channels to send and receive messages. We want the
     // network controller to be owned by the executor client so that it is alive for the entire
     // lifetime of the executor client.
-    network_controller: NetworkController,
+    network_controller: T,
     state_view_service: Arc<RemoteStateViewService<S>>,
     // Channels to send execute block commands to the executor shards.
     command_txs: Arc<Vec<Mutex<Sender<Message>>>>,
@@ -91,10 +94,10 @@ pub struct RemoteExecutorClient<S: StateView + Sync + Send + 'static> {
 }
 
 #[allow(dead_code)]
-impl<S: StateView + Sync + Send + 'static> RemoteExecutorClient<S> {
+impl<S: StateView + Sync + Send + 'static, T: ExecutorTransport> RemoteExecutorClient<S, T> {
     pub fn new(
         remote_shard_addresses: Vec<SocketAddr>,
-        mut controller: NetworkController,
+        mut controller: T,
         num_threads: Option<usize>,
     ) -> Self {
         let num_threads = num_threads.unwrap_or_else(num_cpus::get);
@@ -145,6 +148,28 @@ impl<S: StateView + Sync + Send + 'static> RemoteExecutorClient<S> {
         }
     }
 
+    fn get_output_from_shards(&self) -> Result<Vec<Vec<Vec<TransactionOutput>>>, VMStatus> {
+        trace!("RemoteExecutorClient Waiting for results");
+        let mut results = vec![];
+        for (shard_id, rx) in self.result_rxs.iter().enumerate() {
+            // The channel is disconnected if the shard went away without sending a result.
+            let received_bytes = rx
+                .recv()
+                .map_err(|_| {
+                    VMStatus::error(
+                        StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+                        Some(format!("Executor shard {} is disconnected", shard_id)),
+                    )
+                })?
+                .to_bytes();
+            let result: RemoteExecutionResult = bcs::from_bytes(&received_bytes).unwrap();
+            results.push(result.inner?);
+        }
+        Ok(results)
+    }
+}
+
+impl<S: StateView + Sync + Send + 'static> RemoteExecutorClient<S> {
     pub fn create_remote_sharded_block_executor(
         coordinator_address: SocketAddr,
         remote_shard_addresses: Vec<SocketAddr>,
@@ -160,20 +185,11 @@ impl<S: StateView + Sync + Send + 'static> RemoteExecutorClient<S> {
             num_threads,
         ))
     }
-
-    fn get_output_from_shards(&self) -> Result<Vec<Vec<Vec<TransactionOutput>>>, VMStatus> {
-        trace!("RemoteExecutorClient Waiting for results");
-        let mut results = vec![];
-        for rx in self.result_rxs.iter() {
-            let received_bytes = rx.recv().unwrap().to_bytes();
-            let result: RemoteExecutionResult = bcs::from_bytes(&received_bytes).unwrap();
-            results.push(result.inner?);
-        }
-        Ok(results)
-    }
 }
 
-impl<S: StateView + Sync + Send + 'static> ExecutorClient<S> for RemoteExecutorClient<S> {
+impl<S: StateView + Sync + Send + 'static, T: ExecutorTransport> ExecutorClient<S>
+    for RemoteExecutorClient<S, T>
+{
     fn num_shards(&self) -> usize {
         self.command_txs.len()
     }
@@ -4,6 +4,7 @@
 use crate::{
     remote_cordinator_client::RemoteCoordinatorClient,
     remote_cross_shard_client::RemoteCrossShardClient, remote_state_view::RemoteStateViewClient,
+    transport::ExecutorTransport,
 };
 use lumio_secure_net::network_controller::NetworkController;
 use lumio_types::block_executor::partitioner::ShardId;
@@ -12,9 +13,9 @@ use std::{net::SocketAddr, sync::Arc, thread};
 
 /// A service that provides support for remote execution. Essentially, it reads a request from
 /// the remote executor client and executes the block locally and returns the result.
-pub struct ExecutorService {
+pub struct ExecutorService<T: ExecutorTransport = NetworkController> {
     shard_id: ShardId,
-    controller: NetworkController,
+    controller: T,
     executor_service: Arc<ShardedExecutorService<RemoteStateViewClient>>,
 }
 
@@ -28,7 +29,29 @@ impl ExecutorService {
         remote_shard_addresses: Vec<SocketAddr>,
     ) -> Self {
         let service_name = format!("executor_service-{}", shard_id);
-        let mut controller = NetworkController::new(service_name, self_address, 5000);
+        let controller = NetworkController::new(service_name, self_address, 5000);
+        Self::new_with_transport(
+            shard_id,
+            num_shards,
+            num_threads,
+            controller,
+            coordinator_address,
+            remote_shard_addresses,
+        )
+    }
+}
+
+impl<T: ExecutorTransport> ExecutorService<T> {
+    /// Creates an executor service that exchanges messages with the coordinator and the other
+    /// shards over the given transport.
+    pub fn new_with_transport(
+        shard_id: ShardId,
+        num_shards: usize,
+        num_threads: usize,
+        mut controller: T,
+        coordinator_address: SocketAddr,
+        remote_shard_addresses: Vec<SocketAddr>,
+    ) -> Self {
         let coordinator_client = Arc::new(RemoteCoordinatorClient::new(
             shard_id,
             &mut controller,
@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
-use crate::{RemoteKVRequest, RemoteKVResponse};
-use lumio_secure_net::network_controller::{Message, NetworkController};
+use crate::{transport::ExecutorTransport, RemoteKVRequest, RemoteKVResponse};
+use lumio_secure_net::network_controller::Message;
 use lumio_types::state_store::state_key::StateKey;
 use lumio_vm::sharded_block_executor::remote_state_value::RemoteStateValue;
 use crossbeam_channel::{Receiver, Sender};
@@ -78,7 +78,7 @@ pub struct RemoteStateViewClient {
 impl RemoteStateViewClient {
     pub fn new(
         shard_id: ShardId,
-        controller: &mut NetworkController,
+        controller: &mut impl ExecutorTransport,
         coordinator_address: SocketAddr,
     ) -> Self {
         let thread_pool = Arc::new(
@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
-use crate::{RemoteKVRequest, RemoteKVResponse};
-use lumio_secure_net::network_controller::{Message, NetworkController};
+use crate::{transport::ExecutorTransport, RemoteKVRequest, RemoteKVResponse};
+use lumio_secure_net::network_controller::Message;
 use crossbeam_channel::{Receiver, Sender};
 use std::{
     net::SocketAddr,
@@ -23,7 +23,7 @@ pub struct RemoteStateViewService<S: StateView + Sync + Send + 'static> {
 
 impl<S: StateView + Sync + Send + 'static> RemoteStateViewService<S> {
     pub fn new(
-        controller: &mut NetworkController,
+        controller: &mut impl ExecutorTransport,
         remote_shard_addresses: Vec<SocketAddr>,
         num_threads: Option<usize>,
     ) -> Self {
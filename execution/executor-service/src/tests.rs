@@ -2,14 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    remote_executor_client::RemoteExecutorClient, test_utils,
+    in_memory_transport::{FaultConfig, InMemoryNetwork, InMemoryTransport},
+    remote_executor_client::RemoteExecutorClient,
+    remote_executor_service::ExecutorService,
+    test_utils,
     thread_executor_service::ThreadExecutorService,
 };
+use lumio_block_partitioner::{v2::config::PartitionerV2Config, PartitionerConfig};
 use lumio_config::utils;
+use lumio_keygen::KeyGen;
 use lumio_secure_net::network_controller::NetworkController;
 use lumio_transaction_simulation::InMemoryStateStore;
+use lumio_types::block_executor::config::BlockExecutorConfigFromOnchain;
 use lumio_vm::sharded_block_executor::ShardedBlockExecutor;
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
 
 pub fn create_thread_remote_executor_shards(
     num_shards: usize,
@@ -94,3 +104,115 @@ fn test_sharded_block_executor_with_conflict() {
         executor_service.shutdown();
     });
 }
+
+pub fn create_in_memory_executor_shards(
+    num_shards: usize,
+    num_threads: usize,
+    fault_config: FaultConfig,
+) -> (
+    InMemoryNetwork,
+    RemoteExecutorClient<InMemoryStateStore, InMemoryTransport>,
+    Vec<ExecutorService<InMemoryTransport>>,
+) {
+    // Addresses only identify the endpoints on the in-memory network, so no ports are bound.
+    let network = InMemoryNetwork::new(fault_config);
+    let coordinator_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1);
+    let remote_shard_addresses = (0..num_shards)
+        .map(|shard_id| SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 2 + shard_id as u16))
+        .collect::<Vec<_>>();
+
+    let executor_services = remote_shard_addresses
+        .iter()
+        .enumerate()
+        .map(|(shard_id, address)| {
+            let mut executor_service = ExecutorService::new_with_transport(
+                shard_id,
+                num_shards,
+                num_threads,
+                network.create_transport(*address),
+                coordinator_address,
+                remote_shard_addresses.clone(),
+            );
+            executor_service.start();
+            executor_service
+        })
+        .collect::<Vec<_>>();
+
+    let executor_client = RemoteExecutorClient::new(
+        remote_shard_addresses,
+        network.create_transport(coordinator_address),
+        None,
+    );
+    (network, executor_client, executor_services)
+}
+
+#[test]
+fn test_in_memory_sharded_block_executor_no_conflict() {
+    let (_network, executor_client, mut executor_services) =
+        create_in_memory_executor_shards(4, 2, FaultConfig::default());
+    test_utils::test_sharded_block_executor_no_conflict(ShardedBlockExecutor::new(executor_client));
+
+    executor_services.iter_mut().for_each(|executor_service| {
+        executor_service.shutdown();
+    });
+}
+
+#[test]
+fn test_in_memory_sharded_block_executor_with_conflict_and_faults() {
+    // Different seeds deliver cross-shard messages in different orders, but the output must
+    // always match the unsharded execution.
+    for seed in 0..3 {
+        let fault_config = FaultConfig {
+            delay: Duration::from_micros(50),
+            max_jitter: Duration::from_micros(500),
+            seed,
+        };
+        let (_network, executor_client, mut executor_services) =
+            create_in_memory_executor_shards(4, 2, fault_config);
+        test_utils::sharded_block_executor_with_conflict(
+            ShardedBlockExecutor::new(executor_client),
+            2,
+        );
+
+        executor_services.iter_mut().for_each(|executor_service| {
+            executor_service.shutdown();
+        });
+    }
+}
+
+#[test]
+fn test_in_memory_sharded_block_executor_shard_crash() {
+    let num_shards = 4;
+    let (network, executor_client, mut executor_services) =
+        create_in_memory_executor_shards(num_shards, 2, FaultConfig::default());
+    let mut sharded_block_executor = ShardedBlockExecutor::new(executor_client);
+
+    // Non-conflicting transactions have no cross-shard dependencies, so the remaining shards can
+    // finish executing, and the coordinator must report the crashed one instead of hanging.
+    let state_store = InMemoryStateStore::from_head_genesis();
+    let mut rng = KeyGen::from_seed([9; 32]);
+    let transactions = (0..100)
+        .map(|_| test_utils::generate_non_conflicting_p2p(&mut rng, &state_store).0)
+        .collect::<Vec<_>>();
+    let partitioner = PartitionerV2Config::default()
+        .max_partitioning_rounds(2)
+        .cross_shard_dep_avoid_threshold(0.9)
+        .partition_last_round(true)
+        .build();
+    let partitioned_txns = partitioner.partition(transactions, num_shards);
+
+    // Crash the third shard before the block is sent.
+    network.crash(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 4));
+    let result = sharded_block_executor.execute_block(
+        Arc::new(state_store),
+        partitioned_txns,
+        2,
+        BlockExecutorConfigFromOnchain::new_no_block_limit(),
+    );
+    assert!(result.is_err());
+
+    sharded_block_executor.shutdown();
+    executor_services.iter_mut().for_each(|executor_service| {
+        executor_service.shutdown();
+    });
+}
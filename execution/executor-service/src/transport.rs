@@ -0,0 +1,49 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+use lumio_secure_net::network_controller::{Message, NetworkController};
+use crossbeam_channel::{Receiver, Sender};
+use std::net::SocketAddr;
+
+/// The transport used by the remote executor client and the executor shards to exchange messages.
+///
+/// Channels are identified by the address of the receiving endpoint and a message type. Messages
+/// sent over a single outbound channel must be delivered in order, but no ordering is guaranteed
+/// across channels.
+pub trait ExecutorTransport: Send + Sync + 'static {
+    /// Creates a channel to send messages of the given type to the endpoint at `remote_addr`.
+    fn create_outbound_channel(
+        &mut self,
+        remote_addr: SocketAddr,
+        message_type: String,
+    ) -> Sender<Message>;
+
+    /// Creates a channel to receive messages of the given type sent to this endpoint.
+    fn create_inbound_channel(&mut self, message_type: String) -> Receiver<Message>;
+
+    /// Starts delivering messages. All channels are expected to be created before this is called.
+    fn start(&mut self);
+
+    fn shutdown(&mut self);
+}
+
+impl ExecutorTransport for NetworkController {
+    fn create_outbound_channel(
+        &mut self,
+        remote_addr: SocketAddr,
+        message_type: String,
+    ) -> Sender<Message> {
+        NetworkController::create_outbound_channel(self, remote_addr, message_type)
+    }
+
+    fn create_inbound_channel(&mut self, message_type: String) -> Receiver<Message> {
+        NetworkController::create_inbound_channel(self, message_type)
+    }
+
+    fn start(&mut self) {
+        NetworkController::start(self)
+    }
+
+    fn shutdown(&mut self) {
+        NetworkController::shutdown(self)
+    }
+}
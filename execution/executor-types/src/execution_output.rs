@@ -106,6 +106,28 @@ impl ExecutionOutput {
         Self::new_dummy_with_input_txns(vec![])
     }
 
+    /// An output committing no transactions on top of `version`, e.g. for a block whose result is
+    /// taken from elsewhere instead of executing it.
+    pub fn new_dummy_at_version(
+        version: Version,
+        statuses_for_input_txns: Vec<TransactionStatus>,
+        next_epoch_state: Option<EpochState>,
+    ) -> Self {
+        Self::new_impl(Inner {
+            is_block: false,
+            first_version: version + 1,
+            statuses_for_input_txns,
+            to_commit: TransactionsToKeep::new_empty(),
+            to_discard: TransactionsWithOutput::new_empty(),
+            to_retry: TransactionsWithOutput::new_empty(),
+            result_state: LedgerState::new_empty(),
+            state_reads: ShardedStateCache::new_empty(Some(version)),
+            block_end_info: None,
+            next_epoch_state,
+            subscribable_events: Planned::ready(vec![]),
+        })
+    }
+
     pub fn reconfig_suffix(&self) -> Self {
         Self::new_impl(Inner {
             is_block: false,
@@ -11,6 +11,7 @@ use lumio_crypto::{
 };
 use lumio_storage_interface::chunk_to_commit::ChunkToCommit;
 use lumio_types::{
+    block_info::BlockInfo,
     contract_event::ContractEvent,
     epoch_state::EpochState,
     proof::{accumulator::InMemoryTransactionAccumulator, AccumulatorExtensionProof},
@@ -67,6 +68,27 @@ impl StateComputeResult {
         }
     }
 
+    /// generate a new dummy state compute result matching the given block info, without executing
+    /// the block. This is used to replay recorded consensus traces, where the execution results
+    /// come from the trace. Non empty `statuses_for_input_txns` mark the block as executing its
+    /// transactions, rather than being a reconfiguration suffix.
+    pub fn new_dummy_with_block_info(
+        block_info: &BlockInfo,
+        statuses_for_input_txns: Vec<TransactionStatus>,
+    ) -> Self {
+        Self {
+            execution_output: ExecutionOutput::new_dummy_at_version(
+                block_info.version(),
+                statuses_for_input_txns,
+                block_info.next_epoch_state().cloned(),
+            ),
+            state_checkpoint_output: StateCheckpointOutput::new_dummy(),
+            ledger_update_output: LedgerUpdateOutput::new_dummy_with_root_hash(
+                block_info.executed_state_id(),
+            ),
+        }
+    }
+
     /// generate a new dummy state compute result with ACCUMULATOR_PLACEHOLDER_HASH as the root hash.
     /// this function is used in ordering_state_computer as a dummy state compute result,
     /// where the real compute result is generated after ordering_state_computer.commit pushes
@@ -907,43 +907,48 @@ where
         self.incorrect_use
     }
 
-    fn validate_data_reads_impl<'a>(
-        &'a self,
-        iter: impl Iterator<Item = (&'a T::Key, &'a DataRead<T::Value>)>,
+    fn is_data_read_valid(
+        &self,
+        key: &T::Key,
+        read: &DataRead<T::Value>,
         data_map: &VersionedData<T::Key, T::Value>,
         idx_to_validate: TxnIndex,
     ) -> bool {
         use MVDataError::*;
         use MVDataOutput::*;
-        for (key, read) in iter {
-            // We use fetch_data even with BlockSTMv2, because we don't want to record reads.
-            if !match data_map.fetch_data_no_record(key, idx_to_validate) {
-                Ok(Versioned(version, value)) => {
-                    matches!(
-                        self.data_read_comparator.compare_data_reads(
-                            &DataRead::from_value_with_layout(version, value),
-                            read
-                        ),
-                        DataReadComparison::Contains
-                    )
-                },
-                Ok(Resolved(value)) => matches!(
-                    self.data_read_comparator
-                        .compare_data_reads(&DataRead::Resolved(value), read),
+        // We use fetch_data even with BlockSTMv2, because we don't want to record reads.
+        match data_map.fetch_data_no_record(key, idx_to_validate) {
+            Ok(Versioned(version, value)) => {
+                matches!(
+                    self.data_read_comparator.compare_data_reads(
+                        &DataRead::from_value_with_layout(version, value),
+                        read
+                    ),
                     DataReadComparison::Contains
-                ),
-                // Dependency implies a validation failure, and if the original read were to
-                // observe an unresolved delta, it would set the aggregator base value in the
-                // multi-versioned data-structure, resolve, and record the resolved value.
-                Err(Dependency(_))
-                | Err(Unresolved(_))
-                | Err(DeltaApplicationFailure)
-                | Err(Uninitialized) => false,
-            } {
-                return false;
-            }
+                )
+            },
+            Ok(Resolved(value)) => matches!(
+                self.data_read_comparator
+                    .compare_data_reads(&DataRead::Resolved(value), read),
+                DataReadComparison::Contains
+            ),
+            // Dependency implies a validation failure, and if the original read were to
+            // observe an unresolved delta, it would set the aggregator base value in the
+            // multi-versioned data-structure, resolve, and record the resolved value.
+            Err(Dependency(_))
+            | Err(Unresolved(_))
+            | Err(DeltaApplicationFailure)
+            | Err(Uninitialized) => false,
         }
-        true
+    }
+
+    fn validate_data_reads_impl<'a>(
+        &'a self,
+        mut iter: impl Iterator<Item = (&'a T::Key, &'a DataRead<T::Value>)>,
+        data_map: &VersionedData<T::Key, T::Value>,
+        idx_to_validate: TxnIndex,
+    ) -> bool {
+        iter.all(|(key, read)| self.is_data_read_valid(key, read, data_map, idx_to_validate))
     }
 
     pub(crate) fn validate_data_reads(
@@ -1091,48 +1096,79 @@ where
         group_map: &VersionedGroupData<T::Key, T::Tag, T::Value>,
         idx_to_validate: TxnIndex,
     ) -> bool {
-        use MVGroupError::*;
-
         if self.non_delayed_field_speculative_failure {
             return false;
         }
 
-        self.group_reads.iter().all(|(key, group)| {
-            let mut ret = true;
-            if let Some(size) = group.collected_size {
-                ret &= group_map.validate_group_size(key, idx_to_validate, size);
+        self.group_reads
+            .iter()
+            .all(|(key, group)| self.is_group_read_valid(key, group, group_map, idx_to_validate))
+    }
+
+    fn is_group_read_valid(
+        &self,
+        key: &T::Key,
+        group: &GroupRead<T>,
+        group_map: &VersionedGroupData<T::Key, T::Tag, T::Value>,
+        idx_to_validate: TxnIndex,
+    ) -> bool {
+        use MVGroupError::*;
+
+        let mut ret = true;
+        if let Some(size) = group.collected_size {
+            ret &= group_map.validate_group_size(key, idx_to_validate, size);
+        }
+
+        ret && group.inner_reads.iter().all(|(tag, r)| {
+            match group_map.fetch_tagged_data_no_record(key, tag, idx_to_validate) {
+                Ok((version, v)) => {
+                    matches!(
+                        self.data_read_comparator
+                            .compare_data_reads(&DataRead::from_value_with_layout(version, v), r,),
+                        DataReadComparison::Contains
+                    )
+                },
+                Err(TagNotFound) => {
+                    let sentinel_deletion =
+                        Arc::<T::Value>::new(TransactionWrite::from_state_value(None));
+                    assert!(sentinel_deletion.is_deletion());
+                    matches!(
+                        self.data_read_comparator.compare_data_reads(
+                            &DataRead::Versioned(Err(StorageVersion), sentinel_deletion, None),
+                            r,
+                        ),
+                        DataReadComparison::Contains
+                    )
+                },
+                Err(Dependency(_)) => false,
+                Err(Uninitialized) => {
+                    unreachable!("May not be uninitialized if captured for validation");
+                },
             }
+        })
+    }
 
-            ret && group.inner_reads.iter().all(|(tag, r)| {
-                match group_map.fetch_tagged_data_no_record(key, tag, idx_to_validate) {
-                    Ok((version, v)) => {
-                        matches!(
-                            self.data_read_comparator.compare_data_reads(
-                                &DataRead::from_value_with_layout(version, v),
-                                r,
-                            ),
-                            DataReadComparison::Contains
-                        )
-                    },
-                    Err(TagNotFound) => {
-                        let sentinel_deletion =
-                            Arc::<T::Value>::new(TransactionWrite::from_state_value(None));
-                        assert!(sentinel_deletion.is_deletion());
-                        matches!(
-                            self.data_read_comparator.compare_data_reads(
-                                &DataRead::Versioned(Err(StorageVersion), sentinel_deletion, None),
-                                r,
-                            ),
-                            DataReadComparison::Contains
-                        )
-                    },
-                    Err(Dependency(_)) => false,
-                    Err(Uninitialized) => {
-                        unreachable!("May not be uninitialized if captured for validation");
-                    },
-                }
+    /// Returns the keys of data and group reads that do not pass validation (anymore). Used to
+    /// attribute failed validations to state keys when collecting conflict analytics.
+    pub(crate) fn invalid_read_keys(
+        &self,
+        data_map: &VersionedData<T::Key, T::Value>,
+        group_map: &VersionedGroupData<T::Key, T::Tag, T::Value>,
+        idx_to_validate: TxnIndex,
+    ) -> Vec<&T::Key> {
+        let invalid_data_reads = self
+            .data_reads
+            .iter()
+            .filter(|(key, read)| !self.is_data_read_valid(key, read, data_map, idx_to_validate))
+            .map(|(key, _)| key);
+        let invalid_group_reads = self
+            .group_reads
+            .iter()
+            .filter(|(key, group)| {
+                !self.is_group_read_valid(key, group, group_map, idx_to_validate)
             })
-        })
+            .map(|(key, _)| key);
+        invalid_data_reads.chain(invalid_group_reads).collect()
     }
 
     // This validation needs to be called at commit time
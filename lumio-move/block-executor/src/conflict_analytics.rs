@@ -0,0 +1,319 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optional per-block analytics of the conflicts observed during parallel execution with
+//! BlockSTM (v1 and v2). When enabled, the scheduler and the executor record which state keys
+//! failed validation, which transactions depended on each other, and how many times every
+//! transaction was incarnated. With v1, a dependency means a transaction had to wait for a lower
+//! transaction to finish its execution, while with v2 it means the writes of the lower
+//! transaction aborted it. At the end of the block, the collected data is
+//! summarized, logged as a structured log and kept in memory for inspection (e.g., via the
+//! admin service), so that hot resources that serialize the execution can be identified.
+
+use lumio_crypto::HashValue;
+use lumio_logger::info;
+use lumio_mvhashmap::types::{Incarnation, TxnIndex};
+use lumio_types::{
+    block_executor::transaction_slice_metadata::TransactionSliceMetadata, transaction::Version,
+};
+use once_cell::sync::{Lazy, OnceCell};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::atomic::{AtomicU32, AtomicU64, Ordering},
+};
+
+/// Number of most recent block summaries kept in memory.
+const MAX_RECENT_BLOCKS: usize = 64;
+/// Number of state keys with the most validation failures reported per block.
+const MAX_TOP_KEYS: usize = 20;
+/// Number of the longest dependency chains reported per block.
+const MAX_DEPENDENCY_CHAINS: usize = 5;
+/// Number of transactions with the most incarnations reported per block.
+const MAX_REEXECUTED_TXNS: usize = 20;
+
+static CONFLICT_ANALYTICS_ENABLED: OnceCell<bool> = OnceCell::new();
+
+static RECENT_BLOCKS: Lazy<Mutex<VecDeque<BlockConflictAnalytics>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(MAX_RECENT_BLOCKS)));
+
+/// Enables or disables conflict analytics when invoked the first time.
+pub fn set_conflict_analytics_enabled_once(enable: bool) {
+    // Only the first call succeeds, due to OnceCell semantics.
+    CONFLICT_ANALYTICS_ENABLED.set(enable).ok();
+}
+
+/// Returns true if conflict analytics is enabled, by default it is disabled.
+pub fn is_conflict_analytics_enabled() -> bool {
+    CONFLICT_ANALYTICS_ENABLED.get().copied().unwrap_or(false)
+}
+
+/// Returns conflict analytics for the most recently executed blocks, from oldest to newest.
+pub fn recent_block_conflict_analytics() -> Vec<BlockConflictAnalytics> {
+    RECENT_BLOCKS.lock().iter().cloned().collect()
+}
+
+/// Number of validation failures caused by a single state key.
+#[derive(Clone, Debug, Serialize)]
+pub struct KeyConflicts {
+    pub key: String,
+    pub validation_failures: u64,
+}
+
+/// Number of incarnations (executions) of a single transaction.
+#[derive(Clone, Debug, Serialize)]
+pub struct TxnIncarnations {
+    pub txn_idx: TxnIndex,
+    pub incarnations: u32,
+}
+
+/// Summary of the conflicts observed when executing a block in parallel.
+#[derive(Clone, Debug, Serialize)]
+pub struct BlockConflictAnalytics {
+    /// Id of the block, if executed as a block.
+    pub block_id: Option<HashValue>,
+    /// Version of the first transaction, if executed as a chunk.
+    pub begin_version: Option<Version>,
+    pub num_txns: usize,
+    /// Total number of incarnations, equal to the number of transactions if there were no
+    /// re-executions.
+    pub total_incarnations: u64,
+    /// Number of failed validations caused by reads that were no longer valid.
+    pub validation_failures: u64,
+    /// Number of failed validations caused by speculative failures during execution.
+    pub speculative_failures: u64,
+    /// State keys with the most validation failures, from high to low.
+    pub top_conflicting_keys: Vec<KeyConflicts>,
+    /// The longest chains of transactions that waited on each other, every chain starts with the
+    /// lowest transaction index.
+    pub longest_dependency_chains: Vec<Vec<TxnIndex>>,
+    /// Transactions that were executed more than once, from the most incarnations to the least.
+    pub reexecuted_txns: Vec<TxnIncarnations>,
+}
+
+/// Collects conflicts for a single block. Shared by all worker threads.
+pub(crate) struct ConflictAnalyticsCollector {
+    incarnations: Vec<AtomicU32>,
+    validation_failures: AtomicU64,
+    speculative_failures: AtomicU64,
+    key_validation_failures: Mutex<HashMap<String, u64>>,
+    dependencies: Mutex<Vec<(TxnIndex, TxnIndex)>>,
+}
+
+impl ConflictAnalyticsCollector {
+    pub(crate) fn new(num_txns: TxnIndex) -> Self {
+        Self {
+            incarnations: (0..num_txns).map(|_| AtomicU32::new(0)).collect(),
+            validation_failures: AtomicU64::new(0),
+            speculative_failures: AtomicU64::new(0),
+            key_validation_failures: Mutex::new(HashMap::new()),
+            dependencies: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn record_incarnation(&self, txn_idx: TxnIndex, incarnation: Incarnation) {
+        if let Some(incarnations) = self.incarnations.get(txn_idx as usize) {
+            incarnations.fetch_max(incarnation + 1, Ordering::Relaxed);
+        }
+    }
+
+    /// Records that transaction txn_idx is suspended until dep_txn_idx finishes its execution
+    /// (BlockSTMv1), or that it was aborted by the writes of dep_txn_idx (BlockSTMv2).
+    pub(crate) fn record_dependency(&self, txn_idx: TxnIndex, dep_txn_idx: TxnIndex) {
+        self.dependencies.lock().push((txn_idx, dep_txn_idx));
+    }
+
+    /// Records a failed validation, caused by reads of the specified keys.
+    pub(crate) fn record_validation_failure(&self, keys: impl IntoIterator<Item = String>) {
+        self.validation_failures.fetch_add(1, Ordering::Relaxed);
+        let mut key_validation_failures = self.key_validation_failures.lock();
+        for key in keys {
+            *key_validation_failures.entry(key).or_insert(0) += 1;
+        }
+    }
+
+    pub(crate) fn record_speculative_failure(&self) {
+        self.speculative_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn longest_dependency_chains(&self) -> Vec<Vec<TxnIndex>> {
+        let num_txns = self.incarnations.len();
+        let mut deps: Vec<Vec<TxnIndex>> = vec![vec![]; num_txns];
+        for (txn_idx, dep_txn_idx) in self.dependencies.lock().iter() {
+            deps[*txn_idx as usize].push(*dep_txn_idx);
+        }
+
+        // Dependencies always point to lower indices, so chain lengths can be computed in order.
+        let mut chain_len = vec![1usize; num_txns];
+        let mut prev: Vec<Option<TxnIndex>> = vec![None; num_txns];
+        for (txn_idx, txn_deps) in deps.iter().enumerate() {
+            for dep_txn_idx in txn_deps {
+                let len = chain_len[*dep_txn_idx as usize] + 1;
+                if len > chain_len[txn_idx] {
+                    chain_len[txn_idx] = len;
+                    prev[txn_idx] = Some(*dep_txn_idx);
+                }
+            }
+        }
+
+        let mut ends = (0..num_txns)
+            .filter(|idx| chain_len[*idx] > 1)
+            .collect::<Vec<_>>();
+        ends.sort_by(|lhs, rhs| chain_len[*rhs].cmp(&chain_len[*lhs]).then(lhs.cmp(rhs)));
+
+        let mut reported = HashSet::new();
+        let mut chains = vec![];
+        for end in ends {
+            if chains.len() == MAX_DEPENDENCY_CHAINS {
+                break;
+            }
+            if reported.contains(&(end as TxnIndex)) {
+                continue;
+            }
+
+            let mut chain = vec![end as TxnIndex];
+            while let Some(dep_txn_idx) = prev[*chain.last().unwrap() as usize] {
+                chain.push(dep_txn_idx);
+            }
+            chain.reverse();
+            reported.extend(chain.iter().copied());
+            chains.push(chain);
+        }
+        chains
+    }
+
+    pub(crate) fn finish(
+        self,
+        transaction_slice_metadata: &TransactionSliceMetadata,
+    ) -> BlockConflictAnalytics {
+        let num_txns = self.incarnations.len();
+        let longest_dependency_chains = self.longest_dependency_chains();
+
+        let mut top_conflicting_keys = self
+            .key_validation_failures
+            .into_inner()
+            .into_iter()
+            .map(|(key, validation_failures)| KeyConflicts {
+                key,
+                validation_failures,
+            })
+            .collect::<Vec<_>>();
+        top_conflicting_keys.sort_by(|lhs, rhs| {
+            rhs.validation_failures
+                .cmp(&lhs.validation_failures)
+                .then_with(|| lhs.key.cmp(&rhs.key))
+        });
+        top_conflicting_keys.truncate(MAX_TOP_KEYS);
+
+        let incarnations = self
+            .incarnations
+            .into_iter()
+            .map(AtomicU32::into_inner)
+            .collect::<Vec<_>>();
+        let total_incarnations = incarnations.iter().map(|i| *i as u64).sum();
+
+        let mut reexecuted_txns = incarnations
+            .into_iter()
+            .enumerate()
+            .filter(|(_, incarnations)| *incarnations > 1)
+            .map(|(txn_idx, incarnations)| TxnIncarnations {
+                txn_idx: txn_idx as TxnIndex,
+                incarnations,
+            })
+            .collect::<Vec<_>>();
+        reexecuted_txns.sort_by(|lhs, rhs| {
+            rhs.incarnations
+                .cmp(&lhs.incarnations)
+                .then(lhs.txn_idx.cmp(&rhs.txn_idx))
+        });
+        reexecuted_txns.truncate(MAX_REEXECUTED_TXNS);
+
+        let block_id = match transaction_slice_metadata {
+            TransactionSliceMetadata::Block { child, .. } => Some(*child),
+            TransactionSliceMetadata::Chunk { .. } | TransactionSliceMetadata::Unknown => None,
+        };
+
+        BlockConflictAnalytics {
+            block_id,
+            begin_version: transaction_slice_metadata.begin_version(),
+            num_txns,
+            total_incarnations,
+            validation_failures: self.validation_failures.into_inner(),
+            speculative_failures: self.speculative_failures.into_inner(),
+            top_conflicting_keys,
+            longest_dependency_chains,
+            reexecuted_txns,
+        }
+    }
+}
+
+/// Logs the conflict analytics of a block and stores them for later inspection.
+pub(crate) fn report_block_conflict_analytics(analytics: BlockConflictAnalytics) {
+    info!(
+        block_conflict_analytics = analytics,
+        "[BlockSTM] Conflict analytics for a block of {} transactions: {} incarnations, {} \
+         validation failures",
+        analytics.num_txns,
+        analytics.total_incarnations,
+        analytics.validation_failures,
+    );
+
+    let mut recent_blocks = RECENT_BLOCKS.lock();
+    if recent_blocks.len() == MAX_RECENT_BLOCKS {
+        recent_blocks.pop_front();
+    }
+    recent_blocks.push_back(analytics);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conflict_analytics_summary() {
+        let collector = ConflictAnalyticsCollector::new(6);
+        for txn_idx in 0..6 {
+            collector.record_incarnation(txn_idx, 0);
+        }
+        collector.record_incarnation(3, 1);
+        collector.record_incarnation(3, 2);
+        collector.record_incarnation(4, 1);
+
+        collector.record_dependency(2, 0);
+        collector.record_dependency(3, 2);
+        collector.record_dependency(5, 1);
+        collector.record_dependency(4, 3);
+
+        collector.record_validation_failure(vec!["a".to_string(), "b".to_string()]);
+        collector.record_validation_failure(vec!["a".to_string()]);
+        collector.record_speculative_failure();
+
+        let analytics = collector.finish(&TransactionSliceMetadata::chunk(10, 16));
+        assert_eq!(analytics.block_id, None);
+        assert_eq!(analytics.begin_version, Some(10));
+        assert_eq!(analytics.num_txns, 6);
+        assert_eq!(analytics.total_incarnations, 9);
+        assert_eq!(analytics.validation_failures, 2);
+        assert_eq!(analytics.speculative_failures, 1);
+
+        let keys = analytics
+            .top_conflicting_keys
+            .iter()
+            .map(|k| (k.key.as_str(), k.validation_failures))
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec![("a", 2), ("b", 1)]);
+
+        assert_eq!(analytics.longest_dependency_chains, vec![
+            vec![0, 2, 3, 4],
+            vec![1, 5]
+        ]);
+
+        let reexecuted = analytics
+            .reexecuted_txns
+            .iter()
+            .map(|t| (t.txn_idx, t.incarnations))
+            .collect::<Vec<_>>();
+        assert_eq!(reexecuted, vec![(3, 3), (4, 2)]);
+    }
+}
@@ -6,6 +6,9 @@ use crate::{
     captured_reads::CapturedReads,
     code_cache_global::{add_module_write_to_module_cache, GlobalModuleCache},
     code_cache_global_manager::LumioModuleCacheManagerGuard,
+    conflict_analytics::{
+        is_conflict_analytics_enabled, report_block_conflict_analytics, ConflictAnalyticsCollector,
+    },
     counters::{
         self, BLOCK_EXECUTOR_INNER_EXECUTE_BLOCK, PARALLEL_EXECUTION_SECONDS,
         PARALLEL_FINALIZE_SECONDS, RAYON_EXECUTION_SECONDS, TASK_EXECUTE_SECONDS,
@@ -456,6 +459,19 @@ where
 
         last_input_output.record(idx_to_execute, read_set, execution_result);
 
+        if let Some(conflict_analytics) = scheduler.conflict_analytics() {
+            // Reads are validated by the writes (push validation), so every aborted transaction
+            // corresponds to a failed validation.
+            for aborted_txn_idx in abort_manager.aborted_txns() {
+                Self::record_validation_failure(
+                    aborted_txn_idx,
+                    last_input_output,
+                    versioned_cache,
+                    conflict_analytics,
+                );
+            }
+        }
+
         // It is important to call finish_execution after recording the input/output.
         // CAUTION: once any update has been applied to the shared data structures, there should
         // be no short circuits until the record succeeds and scheduler is notified that the
@@ -759,6 +775,35 @@ where
                 ))
     }
 
+    /// Attributes a failed validation of the transaction to the reads that are no longer valid.
+    fn record_validation_failure(
+        idx_to_validate: TxnIndex,
+        last_input_output: &TxnLastInputOutput<T, E::Output, E::Error>,
+        versioned_cache: &MVHashMap<T::Key, T::Tag, T::Value, DelayedFieldID>,
+        conflict_analytics: &ConflictAnalyticsCollector,
+    ) {
+        // With BlockSTMv2, the reads of the first incarnation may be invalidated before they
+        // are recorded, in which case the failure can't be attributed to any keys.
+        let Some((read_set, is_speculative_failure)) = last_input_output.read_set(idx_to_validate)
+        else {
+            conflict_analytics.record_validation_failure(vec![]);
+            return;
+        };
+
+        if is_speculative_failure {
+            conflict_analytics.record_speculative_failure();
+            return;
+        }
+
+        let invalid_keys = read_set.invalid_read_keys(
+            versioned_cache.data(),
+            versioned_cache.group_data(),
+            idx_to_validate,
+        );
+        conflict_analytics
+            .record_validation_failure(invalid_keys.into_iter().map(|key| format!("{:?}", key)));
+    }
+
     fn update_on_validation(
         txn_idx: TxnIndex,
         incarnation: Incarnation,
@@ -1312,6 +1357,16 @@ where
                         versioned_cache,
                         skip_module_reads_validation.load(Ordering::Relaxed),
                     );
+                    if !valid {
+                        if let Some(conflict_analytics) = scheduler.conflict_analytics() {
+                            Self::record_validation_failure(
+                                txn_idx,
+                                last_input_output,
+                                versioned_cache,
+                                conflict_analytics,
+                            );
+                        }
+                    }
                     Self::update_on_validation(
                         txn_idx,
                         incarnation,
@@ -1684,7 +1739,10 @@ where
         // +1 for potential BlockEpilogue txn.
         let last_input_output = TxnLastInputOutput::new(num_txns + 1);
         let mut versioned_cache = MVHashMap::new();
-        let scheduler = SchedulerV2::new(num_txns, num_workers);
+        let mut scheduler = SchedulerV2::new(num_txns, num_workers);
+        if is_conflict_analytics_enabled() {
+            scheduler = scheduler.with_conflict_analytics();
+        }
 
         let shared_sync_params: SharedSyncParams<'_, '_, T, E, S> = SharedSyncParams {
             base_view,
@@ -1768,6 +1826,14 @@ where
             }
         };
 
+        if !has_error {
+            if let Some(conflict_analytics) = scheduler.take_conflict_analytics() {
+                report_block_conflict_analytics(
+                    conflict_analytics.finish(transaction_slice_metadata),
+                );
+            }
+        }
+
         // Explicit async drops even when there is an error.
         DEFAULT_DROPPER.schedule_drop((last_input_output, scheduler, versioned_cache));
 
@@ -1831,7 +1897,10 @@ where
         let skip_module_reads_validation = AtomicBool::new(true);
         // +1 for potential BlockEpilogue txn.
         let last_input_output = TxnLastInputOutput::new(num_txns + 1);
-        let scheduler = Scheduler::new(num_txns);
+        let mut scheduler = Scheduler::new(num_txns);
+        if is_conflict_analytics_enabled() {
+            scheduler = scheduler.with_conflict_analytics();
+        }
 
         let timer = RAYON_EXECUTION_SECONDS.start_timer();
         let worker_ids: Vec<u32> = (0..num_workers as u32).collect();
@@ -1914,6 +1983,14 @@ where
             }
         };
 
+        if !has_error {
+            if let Some(conflict_analytics) = scheduler.take_conflict_analytics() {
+                report_block_conflict_analytics(
+                    conflict_analytics.finish(transaction_slice_metadata),
+                );
+            }
+        }
+
         // Explicit async drops even when there is an error.
         DEFAULT_DROPPER.schedule_drop((last_input_output, scheduler, versioned_cache));
 
@@ -2,7 +2,9 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::explicit_sync_wrapper::ExplicitSyncWrapper;
+use crate::{
+    conflict_analytics::ConflictAnalyticsCollector, explicit_sync_wrapper::ExplicitSyncWrapper,
+};
 use lumio_infallible::Mutex;
 use lumio_mvhashmap::types::{Incarnation, TxnIndex};
 use lumio_types::error::{code_invariant_error, PanicError};
@@ -314,6 +316,9 @@ pub struct Scheduler {
     queueing_commits_lock: CachePadded<ArmedLock>,
 
     commit_queue: ConcurrentQueue<u32>,
+
+    /// If set, records incarnations and read dependencies of transactions.
+    conflict_analytics: Option<ConflictAnalyticsCollector>,
 }
 
 /// Public Interfaces for the Scheduler
@@ -342,9 +347,24 @@ impl Scheduler {
             has_halted: CachePadded::new(AtomicBool::new(false)),
             queueing_commits_lock: CachePadded::new(ArmedLock::new()),
             commit_queue: ConcurrentQueue::<u32>::bounded(num_txns as usize),
+            conflict_analytics: None,
         }
     }
 
+    /// Enables collection of conflict analytics for the block.
+    pub(crate) fn with_conflict_analytics(mut self) -> Self {
+        self.conflict_analytics = Some(ConflictAnalyticsCollector::new(self.num_txns));
+        self
+    }
+
+    pub(crate) fn conflict_analytics(&self) -> Option<&ConflictAnalyticsCollector> {
+        self.conflict_analytics.as_ref()
+    }
+
+    pub(crate) fn take_conflict_analytics(&mut self) -> Option<ConflictAnalyticsCollector> {
+        self.conflict_analytics.take()
+    }
+
     pub fn add_to_commit_queue(&self, txn_idx: u32) {
         self.commit_queue
             .push(txn_idx)
@@ -747,6 +767,10 @@ impl TWaitForDependency for Scheduler {
         // dep_txn_idx is guaranteed to acquire the same lock later and clear the dependency.
         stored_deps.push(txn_idx);
 
+        if let Some(conflict_analytics) = &self.conflict_analytics {
+            conflict_analytics.record_dependency(txn_idx, dep_txn_idx);
+        }
+
         // Stored deps gets unlocked here.
 
         Ok(DependencyResult::Dependency(dep_condvar))
@@ -862,6 +886,9 @@ impl Scheduler {
         if let ExecutionStatus::Ready(incarnation, execution_task_type) = &*status {
             let ret: (u32, ExecutionTaskType) = (*incarnation, (*execution_task_type).clone());
             *status = ExecutionStatus::Executing(*incarnation, (*execution_task_type).clone());
+            if let Some(conflict_analytics) = &self.conflict_analytics {
+                conflict_analytics.record_incarnation(txn_idx, *incarnation);
+            }
             Some(ret)
         } else {
             None
@@ -3,6 +3,7 @@
 
 use crate::{
     cold_validation::{ColdValidationRequirements, ValidationRequirement},
+    conflict_analytics::ConflictAnalyticsCollector,
     scheduler::ArmedLock,
     scheduler_status::ExecutionStatuses,
 };
@@ -243,6 +244,14 @@ impl<'a> AbortManager<'a> {
             .then_some(incarnation))
     }
 
+    /// Returns the transactions whose reads were invalidated by the owner and that were
+    /// successfully aborted as a result.
+    pub(crate) fn aborted_txns(&self) -> impl Iterator<Item = TxnIndex> + '_ {
+        self.invalidated_dependencies
+            .iter()
+            .filter_map(|(txn_idx, maybe_incarnation)| maybe_incarnation.map(|_| *txn_idx))
+    }
+
     // For invalidated dependencies that are mapped to an incarnation, [SchedulerV2::start_abort]
     // was successful, and the [SchedulerV2::finish_abort] still needs to be performed.
     fn take(
@@ -531,6 +540,10 @@ pub(crate) struct SchedulerV2 {
     /// For each txn `i`, `committed_marker[i]` stores its [CommitMarkerFlag], indicating
     /// its current stage in the commit process (NotCommitted, CommitStarted, Committed).
     committed_marker: Vec<CachePadded<AtomicU8>>,
+
+    /// If set, records incarnations of transactions and the transactions aborted by each
+    /// transaction's writes.
+    conflict_analytics: Option<ConflictAnalyticsCollector>,
 }
 
 impl SchedulerV2 {
@@ -564,9 +577,24 @@ impl SchedulerV2 {
             committed_marker: (0..num_txns)
                 .map(|_| CachePadded::new(AtomicU8::new(CommitMarkerFlag::NotCommitted as u8)))
                 .collect(),
+            conflict_analytics: None,
         }
     }
 
+    /// Enables collection of conflict analytics for the block.
+    pub(crate) fn with_conflict_analytics(mut self) -> Self {
+        self.conflict_analytics = Some(ConflictAnalyticsCollector::new(self.num_txns));
+        self
+    }
+
+    pub(crate) fn conflict_analytics(&self) -> Option<&ConflictAnalyticsCollector> {
+        self.conflict_analytics.as_ref()
+    }
+
+    pub(crate) fn take_conflict_analytics(&mut self) -> Option<ConflictAnalyticsCollector> {
+        self.conflict_analytics.take()
+    }
+
     /// Attempts to acquire the `queueing_commits_lock` in a non-blocking way.
     ///
     /// Workers should call this to gain exclusive access to the critical section for
@@ -899,11 +927,14 @@ impl SchedulerV2 {
         }
 
         let mut stall_propagation_queue: BTreeSet<usize> = BTreeSet::new();
-        for (txn_idx, maybe_incarnation) in invalidated_set {
+        for (invalidated_txn_idx, maybe_incarnation) in invalidated_set {
             if let Some(incarnation) = maybe_incarnation {
+                if let Some(conflict_analytics) = &self.conflict_analytics {
+                    conflict_analytics.record_dependency(invalidated_txn_idx, txn_idx);
+                }
                 self.txn_statuses
-                    .finish_abort(txn_idx, incarnation, false)?;
-                stall_propagation_queue.insert(txn_idx as usize);
+                    .finish_abort(invalidated_txn_idx, incarnation, false)?;
+                stall_propagation_queue.insert(invalidated_txn_idx as usize);
             }
         }
 
@@ -1258,7 +1289,13 @@ impl SchedulerV2 {
     /// was not in a state to start execution (e.g., not `PendingScheduling`), or
     /// `Err(PanicError)` if an error occurs within `ExecutionStatuses`.
     fn start_executing(&self, txn_idx: TxnIndex) -> Result<Option<Incarnation>, PanicError> {
-        self.txn_statuses.start_executing(txn_idx)
+        let maybe_incarnation = self.txn_statuses.start_executing(txn_idx)?;
+        if let (Some(conflict_analytics), Some(incarnation)) =
+            (&self.conflict_analytics, maybe_incarnation)
+        {
+            conflict_analytics.record_incarnation(txn_idx, incarnation);
+        }
+        Ok(maybe_incarnation)
     }
 
     /// Attempts to advance the `executed_once_max_idx` watermark.
@@ -1335,6 +1372,7 @@ impl SchedulerV2 {
 mod tests {
     use super::*;
     use crate::scheduler_status::{ExecutionStatus, SchedulingStatus, StatusWithIncarnation};
+    use lumio_types::block_executor::transaction_slice_metadata::TransactionSliceMetadata;
     use claims::{assert_err, assert_none, assert_ok, assert_ok_eq, assert_some_eq};
     use fail::FailScenario;
     use rand::{rngs::StdRng, Rng, SeedableRng};
@@ -1695,6 +1733,37 @@ mod tests {
         scenario.teardown();
     }
 
+    #[test]
+    fn conflict_analytics() {
+        let mut scheduler = SchedulerV2::new(4, 2).with_conflict_analytics();
+
+        assert_some_eq!(scheduler.start_executing(1).unwrap(), 0);
+        assert_some_eq!(scheduler.start_executing(2).unwrap(), 0);
+        assert_ok!(scheduler.finish_execution(AbortManager::new(2, 0, &scheduler)));
+
+        // The writes of txn 1 invalidate a read of txn 2, which gets aborted.
+        let mut abort_manager = AbortManager::new(1, 0, &scheduler);
+        assert_ok!(abort_manager.invalidate_dependencies(BTreeMap::from([(2, 0)])));
+        assert_eq!(abort_manager.aborted_txns().collect::<Vec<_>>(), vec![2]);
+        assert_ok!(scheduler.finish_execution(abort_manager));
+        assert_some_eq!(scheduler.start_executing(2).unwrap(), 1);
+
+        let analytics = scheduler
+            .take_conflict_analytics()
+            .unwrap()
+            .finish(&TransactionSliceMetadata::unknown());
+        assert_eq!(analytics.num_txns, 4);
+        assert_eq!(analytics.total_incarnations, 3);
+        assert_eq!(analytics.longest_dependency_chains, vec![vec![1, 2]]);
+        let reexecuted = analytics
+            .reexecuted_txns
+            .iter()
+            .map(|t| (t.txn_idx, t.incarnations))
+            .collect::<Vec<_>>();
+        assert_eq!(reexecuted, vec![(2, 2)]);
+        assert!(scheduler.take_conflict_analytics().is_none());
+    }
+
     #[test]
     fn test_abort_manager_take() {
         let scheduler = SchedulerV2::new(10, 1);
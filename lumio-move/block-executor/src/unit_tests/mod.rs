@@ -6,6 +6,7 @@ mod code_cache_tests;
 
 use crate::{
     code_cache_global_manager::LumioModuleCacheManagerGuard,
+    conflict_analytics::{recent_block_conflict_analytics, set_conflict_analytics_enabled_once},
     combinatorial_tests::{
         baseline::BaselineOutput,
         mock_executor::{MockEvent, MockOutput, MockTask},
@@ -538,6 +539,89 @@ fn skip_rest_gas_limit() {
     );
 }
 
+#[test]
+fn conflict_analytics() {
+    set_conflict_analytics_enabled_once(true);
+
+    // Every transaction reads and writes the same key.
+    let key = KeyType::<u32>(1);
+    let num_txns = 50;
+    let transactions = (0..num_txns)
+        .map(|_| {
+            MockTransaction::from_behavior(MockIncarnation::<_, MockEvent>::new(
+                vec![(key, false)],
+                vec![(key, random_value(false), false)],
+                vec![],
+                vec![],
+                1,
+            ))
+        })
+        .collect::<Vec<_>>();
+    let txn_provider = DefaultTxnProvider::new_without_info(transactions);
+
+    let data_view = MockStateView::empty();
+    let executor_thread_pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap(),
+    );
+    let block_executor = BlockExecutor::<
+        MockTransaction<KeyType<u32>, MockEvent>,
+        MockTask<KeyType<u32>, MockEvent>,
+        MockStateView<KeyType<u32>>,
+        NoOpTransactionCommitHook<MockOutput<KeyType<u32>, MockEvent>, usize>,
+        DefaultTxnProvider<MockTransaction<KeyType<u32>, MockEvent>, AuxiliaryInfo>,
+        AuxiliaryInfo,
+    >::new(
+        BlockExecutorConfig::new_no_block_limit(4),
+        executor_thread_pool,
+        None,
+    );
+
+    for (begin_version, blockstm_v2) in [(1000, false), (2000, true)] {
+        // The begin version identifies the analytics of this block among those of other tests.
+        let transaction_slice_metadata =
+            TransactionSliceMetadata::chunk(begin_version, begin_version + num_txns);
+        let mut guard = LumioModuleCacheManagerGuard::none();
+        let output = if blockstm_v2 {
+            block_executor.execute_transactions_parallel_v2(
+                &txn_provider,
+                &data_view,
+                &transaction_slice_metadata,
+                &mut guard,
+            )
+        } else {
+            block_executor.execute_transactions_parallel(
+                &txn_provider,
+                &data_view,
+                &transaction_slice_metadata,
+                &mut guard,
+            )
+        };
+        assert_ok!(output);
+
+        let analytics = recent_block_conflict_analytics()
+            .into_iter()
+            .find(|analytics| analytics.begin_version == Some(begin_version))
+            .unwrap();
+        assert_eq!(analytics.block_id, None);
+        assert_eq!(analytics.num_txns, num_txns as usize);
+        assert!(analytics.total_incarnations >= num_txns);
+        assert_eq!(
+            analytics.reexecuted_txns.is_empty(),
+            analytics.total_incarnations == num_txns
+        );
+        // The shared key is the only one that can fail validation.
+        for key_conflicts in &analytics.top_conflicting_keys {
+            assert_eq!(key_conflicts.key, format!("{:?}", key));
+        }
+        for chain in &analytics.longest_dependency_chains {
+            assert!(chain.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
+}
+
 // TODO: add unit test for block gas limit!
 fn run_and_assert<K, E>(transactions: Vec<MockTransaction<K, E>>, use_delta_data_view: bool)
 where
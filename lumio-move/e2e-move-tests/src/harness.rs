@@ -4,7 +4,7 @@
 use crate::{assert_success, LumioPackageHooks};
 use lumio_cached_packages::lumio_stdlib;
 use lumio_framework::{natives::code::PackageMetadata, BuildOptions, BuiltPackage};
-use lumio_gas_profiling::TransactionGasLog;
+use lumio_gas_profiling::{TransactionGasLog, TransactionTrace};
 use lumio_gas_schedule::{
     LumioGasParameters, FromOnChainGasSchedule, InitialGasSchedule, ToOnChainGasSchedule,
 };
@@ -407,6 +407,24 @@ impl MoveHarness {
         )
     }
 
+    /// Runs a transaction with the execution tracer.
+    pub fn run_with_tracer(
+        &mut self,
+        account: &Account,
+        payload: TransactionPayload,
+    ) -> (TransactionStatus, TransactionTrace) {
+        let txn = self.create_transaction_payload(account, payload);
+        let (output, trace) = self
+            .executor
+            .execute_transaction_with_tracer(txn, &AuxiliaryInfo::default())
+            .unwrap();
+        if matches!(output.status(), TransactionStatus::Keep(_)) {
+            self.executor.apply_write_set(output.write_set());
+            self.executor.append_events(output.events().to_vec());
+        }
+        (output.status().to_owned(), trace)
+    }
+
     /// Creates a transaction which runs the specified entry point `fun`. Arguments need to be
     /// provided in bcs-serialized form.
     pub fn create_entry_function(
@@ -0,0 +1,126 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{assert_success, tests::common, MoveHarness};
+use lumio_gas_profiling::{CallTrace, ResourceAccessKind};
+use lumio_package_builder::PackageBuilder;
+use lumio_types::{
+    account_address::AccountAddress,
+    transaction::{EntryFunction, TransactionPayload},
+};
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+use serde_json::json;
+
+fn find_call<'a>(call: &'a CallTrace, function: &str) -> Option<&'a CallTrace> {
+    if call.function == function {
+        return Some(call);
+    }
+    call.calls.iter().find_map(|call| find_call(call, function))
+}
+
+#[test]
+fn test_execution_trace() {
+    let mut h = MoveHarness::new();
+    let account = h.new_account_at(AccountAddress::from_hex_literal("0xcafe").unwrap());
+
+    let source = r#"
+        module 0xcafe::traced {
+            use std::signer;
+            use lumio_framework::event;
+
+            struct Counter has key {
+                value: u64,
+            }
+
+            #[event]
+            struct Incremented has drop, store {
+                value: u64,
+            }
+
+            public fun add(a: u64, b: u64): u64 {
+                a + b
+            }
+
+            public entry fun increment(account: &signer, by: u64) acquires Counter {
+                let addr = signer::address_of(account);
+                if (!exists<Counter>(addr)) {
+                    move_to(account, Counter { value: 0 });
+                };
+                let counter = borrow_global_mut<Counter>(addr);
+                counter.value = add(counter.value, by);
+                event::emit(Incremented { value: counter.value });
+            }
+        }
+    "#;
+    let mut builder = PackageBuilder::new("Traced");
+    builder.add_source("traced.move", source);
+    builder.add_local_dep(
+        "LumioFramework",
+        &common::framework_dir_path("lumio-framework").to_string_lossy(),
+    );
+    let path = builder.write_to_temp().unwrap();
+    assert_success!(h.publish_package(&account, path.path()));
+
+    let payload = TransactionPayload::EntryFunction(EntryFunction::new(
+        ModuleId::new(*account.address(), Identifier::new("traced").unwrap()),
+        Identifier::new("increment").unwrap(),
+        vec![],
+        vec![bcs::to_bytes(&5u64).unwrap()],
+    ));
+    let (status, trace) = h.run_with_tracer(&account, payload);
+    assert_success!(status);
+
+    // The entry function, with its raw arguments
+    let root = &trace.call;
+    assert_eq!(root.function, "0xcafe::traced::increment");
+    assert_eq!(root.args, vec![json!("0x0500000000000000")]);
+    assert!(root.gas_used > 0);
+
+    // Resources accessed by the entry function itself
+    let counter_accesses: Vec<_> = root
+        .resources
+        .iter()
+        .filter(|access| access.ty == "0xcafe::traced::Counter")
+        .map(|access| access.kind)
+        .collect();
+    assert!(counter_accesses.contains(&ResourceAccessKind::Exists));
+    assert!(counter_accesses.contains(&ResourceAccessKind::MoveTo));
+    assert!(counter_accesses.contains(&ResourceAccessKind::BorrowMut));
+
+    // The mutable borrow carries the address and the value of the resource when borrowed
+    let borrow = root
+        .resources
+        .iter()
+        .find(|access| access.kind == ResourceAccessKind::BorrowMut)
+        .unwrap();
+    assert_eq!(borrow.address, Some(*account.address()));
+    assert_eq!(borrow.value, Some(json!({ "fields": ["0"] })));
+
+    // Nested calls, with their arguments and return values
+    let add = find_call(root, "0xcafe::traced::add").unwrap();
+    assert_eq!(add.args, vec![json!("0"), json!("5")]);
+    assert_eq!(add.return_values, vec![json!("5")]);
+    assert!(add.calls.is_empty());
+    let address_of = find_call(root, "0x1::signer::address_of").unwrap();
+    assert_eq!(address_of.return_values, vec![json!("0xcafe")]);
+    let native = address_of.calls.iter().find(|call| call.is_native).unwrap();
+    assert_eq!(native.return_values, vec![json!("0xcafe")]);
+
+    // Events are attributed to the call emitting them
+    let emit = find_call(root, "0x1::event::emit").unwrap();
+    assert_eq!(
+        emit.ty_args,
+        vec!["0xcafe::traced::Incremented".to_string()]
+    );
+    assert_eq!(emit.events.len(), 1);
+    assert_eq!(emit.events[0].ty, "0xcafe::traced::Incremented");
+    assert_eq!(emit.events[0].data, json!({ "fields": ["5"] }));
+
+    // The counter write is attributed to the entry function, which created and mutated it
+    assert!(!trace.write_set.is_empty());
+    assert!(root
+        .writes
+        .iter()
+        .any(|write| write.state_key.contains("Counter")));
+    assert!(add.writes.is_empty());
+}
@@ -22,6 +22,7 @@ mod enum_upgrade_fv;
 mod enum_variant_count;
 mod error_map;
 mod events;
+mod execution_trace;
 mod fee_payer;
 mod friends;
 mod function_value_capture_option;
@@ -18,7 +18,7 @@ use lumio_crypto::HashValue;
 use lumio_framework::ReleaseBundle;
 use lumio_gas_algebra::DynamicExpression;
 use lumio_gas_meter::{LumioGasMeter, GasAlgebra, StandardGasAlgebra, StandardGasMeter};
-use lumio_gas_profiling::{GasProfiler, TransactionGasLog};
+use lumio_gas_profiling::{ExecutionTracer, GasProfiler, TransactionGasLog, TransactionTrace};
 use lumio_keygen::KeyGen;
 use lumio_rest_client::LumioBaseUrl;
 use lumio_transaction_simulation::{
@@ -930,6 +930,53 @@ impl FakeExecutor {
         ))
     }
 
+    pub fn execute_transaction_with_tracer(
+        &self,
+        txn: SignedTransaction,
+        auxiliary_info: &AuxiliaryInfo,
+    ) -> anyhow::Result<(TransactionOutput, TransactionTrace)> {
+        let txn = txn
+            .check_signature()
+            .expect("invalid signature for transaction");
+
+        let log_context = AdapterLogSchema::new(self.state_store.id(), 0);
+
+        let env = LumioEnvironment::new(&self.state_store);
+        let vm = LumioVM::new(&env, self.get_state_view());
+
+        let resolver = self.state_store.as_move_resolver();
+        let code_storage = self.get_state_view().as_lumio_code_storage(&env);
+
+        let (_status, output, tracer) = vm.execute_user_transaction_with_modified_gas_meter(
+            &resolver,
+            &code_storage,
+            &txn,
+            &log_context,
+            |gas_meter| match txn.payload().executable_ref() {
+                Ok(TransactionExecutableRef::Script(_)) => ExecutionTracer::new_script(gas_meter),
+                Ok(TransactionExecutableRef::EntryFunction(entry_func))
+                    if !txn.payload().is_multisig() =>
+                {
+                    ExecutionTracer::new_function(
+                        gas_meter,
+                        entry_func.module().clone(),
+                        entry_func.function().to_owned(),
+                        entry_func.ty_args().to_vec(),
+                        entry_func.args(),
+                    )
+                },
+                Ok(_) => unimplemented!("multisig or empty payload not supported yet"),
+                Err(_) => unimplemented!("payload type is deprecated"),
+            },
+            auxiliary_info,
+        )?;
+
+        Ok((
+            output.try_materialize_into_transaction_output(&resolver)?,
+            tracer.finish(),
+        ))
+    }
+
     fn trace<P: AsRef<Path>, T: Serialize>(dir: P, item: &T) -> usize {
         let dir = dir.as_ref();
         let seq = fs::read_dir(dir).expect("Unable to read trace dir").count();
@@ -0,0 +1,87 @@
+/// On-chain record of validator equivocations.
+///
+/// Evidence is submitted by validators as validator transactions and verified by the VM
+/// against the validator set of its epoch before `record_equivocation` is called.
+module lumio_framework::equivocation {
+    use lumio_std::table::{Self, Table};
+    use lumio_framework::event;
+    use lumio_framework::system_addresses;
+
+    /// Two votes for different blocks in the same round.
+    const KIND_VOTE: u8 = 0;
+    /// Two different proposals for the same round.
+    const KIND_PROPOSAL: u8 = 1;
+
+    struct EquivocationReport has copy, drop, store {
+        author: address,
+        epoch: u64,
+        round: u64,
+        kind: u8,
+    }
+
+    #[event]
+    struct EquivocationReported has drop, store {
+        author: address,
+        epoch: u64,
+        round: u64,
+        kind: u8,
+    }
+
+    /// The equivocations recorded so far.
+    struct EquivocationReports has key {
+        reports: Table<EquivocationReport, bool>,
+    }
+
+    /// Called in genesis to initialize on-chain states.
+    public fun initialize(lumio_framework: &signer) {
+        system_addresses::assert_lumio_framework(lumio_framework);
+        if (!exists<EquivocationReports>(@lumio_framework)) {
+            move_to(lumio_framework, EquivocationReports { reports: table::new() });
+        }
+    }
+
+    /// Records a verified equivocation and emits an `EquivocationReported` event.
+    /// The same equivocation is only recorded once, however many validators report it.
+    fun record_equivocation(
+        lumio_framework: &signer,
+        author: address,
+        epoch: u64,
+        round: u64,
+        kind: u8,
+    ) acquires EquivocationReports {
+        // Chains that started before this module was added have no records yet.
+        if (!exists<EquivocationReports>(@lumio_framework)) {
+            move_to(lumio_framework, EquivocationReports { reports: table::new() });
+        };
+        let reports = &mut borrow_global_mut<EquivocationReports>(@lumio_framework).reports;
+        let report = EquivocationReport { author, epoch, round, kind };
+        if (table::contains(reports, report)) {
+            return
+        };
+        table::add(reports, report, true);
+        event::emit(EquivocationReported { author, epoch, round, kind });
+    }
+
+    #[view]
+    /// Returns whether `author` was reported for equivocating in `round` of `epoch`.
+    public fun is_reported(author: address, epoch: u64, round: u64): bool acquires EquivocationReports {
+        if (!exists<EquivocationReports>(@lumio_framework)) {
+            return false
+        };
+        let reports = &borrow_global<EquivocationReports>(@lumio_framework).reports;
+        table::contains(reports, EquivocationReport { author, epoch, round, kind: KIND_VOTE })
+            || table::contains(reports, EquivocationReport { author, epoch, round, kind: KIND_PROPOSAL })
+    }
+
+    #[test(fx = @lumio_framework)]
+    fun test_record_equivocation(fx: &signer) acquires EquivocationReports {
+        assert!(!is_reported(@0xcafe, 2, 7), 1);
+        record_equivocation(fx, @0xcafe, 2, 7, KIND_VOTE);
+        record_equivocation(fx, @0xcafe, 2, 7, KIND_VOTE);
+        record_equivocation(fx, @0xcafe, 2, 9, KIND_PROPOSAL);
+        assert!(is_reported(@0xcafe, 2, 7), 2);
+        assert!(is_reported(@0xcafe, 2, 9), 3);
+        assert!(!is_reported(@0xcafe, 3, 7), 4);
+        assert!(!is_reported(@0xbeef, 2, 7), 5);
+    }
+}
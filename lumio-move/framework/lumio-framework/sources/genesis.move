@@ -15,6 +15,7 @@ module lumio_framework::genesis {
     use lumio_framework::chain_status;
     use lumio_framework::coin;
     use lumio_framework::consensus_config;
+    use lumio_framework::equivocation;
     use lumio_framework::execution_config;
     use lumio_framework::create_signer::create_signer;
     use lumio_framework::gas_schedule;
@@ -132,6 +133,7 @@ module lumio_framework::genesis {
         block::initialize(&lumio_framework_account, epoch_interval_microsecs);
         state_storage::initialize(&lumio_framework_account);
         nonce_validation::initialize(&lumio_framework_account);
+        equivocation::initialize(&lumio_framework_account);
     }
 
     /// Genesis step 2: Initialize Lumio coin.
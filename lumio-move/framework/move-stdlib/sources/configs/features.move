@@ -741,6 +741,16 @@ module std::features {
         is_enabled(MONOTONICALLY_INCREASING_COUNTER)
     }
 
+    /// Whether validators accept equivocation evidence as validator transactions.
+    /// Lifetime: transient
+    const EQUIVOCATION_EVIDENCE: u64 = 100;
+
+    public fun get_equivocation_evidence_feature(): u64 { EQUIVOCATION_EVIDENCE }
+
+    public fun is_equivocation_evidence_enabled(): bool acquires Features {
+        is_enabled(EQUIVOCATION_EVIDENCE)
+    }
+
     // ============================================================================================
     // Feature Flag Implementation
 
@@ -26,6 +26,11 @@ pub struct Command {
 
     #[clap(long)]
     use_same_block_boundaries: bool,
+
+    /// Print the structured execution trace (as JSON) of each user transaction instead of
+    /// executing the transactions as blocks.
+    #[clap(long)]
+    trace: bool,
 }
 
 impl Command {
@@ -38,6 +43,22 @@ impl Command {
             unreachable!("Must provide one target.");
         };
 
+        if self.trace {
+            let traces = debugger
+                .trace_past_transactions(self.begin_version, self.limit)
+                .await?;
+            for (version, trace) in traces {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&serde_json::json!({
+                        "version": version,
+                        "trace": trace,
+                    }))?
+                );
+            }
+            return Ok(());
+        }
+
         let result = debugger
             .execute_past_transactions(
                 self.begin_version,
@@ -3,7 +3,7 @@
 
 use anyhow::{bail, format_err};
 use lumio_block_executor::txn_provider::{default::DefaultTxnProvider, TxnProvider};
-use lumio_gas_profiling::{GasProfiler, TransactionGasLog};
+use lumio_gas_profiling::{ExecutionTracer, GasProfiler, TransactionGasLog, TransactionTrace};
 use lumio_rest_client::Client;
 use lumio_types::{
     account_address::AccountAddress,
@@ -12,11 +12,11 @@ use lumio_types::{
         transaction_slice_metadata::TransactionSliceMetadata,
     },
     contract_event::ContractEvent,
-    state_store::TStateView,
+    state_store::{StateView, TStateView},
     transaction::{
         signature_verified_transaction::SignatureVerifiedTransaction, AuxiliaryInfo, BlockOutput,
-        SignedTransaction, Transaction, TransactionExecutableRef, TransactionInfo,
-        TransactionOutput, TransactionPayload, Version,
+        PersistedAuxiliaryInfo, SignedTransaction, Transaction, TransactionExecutableRef,
+        TransactionInfo, TransactionOutput, TransactionPayload, Version,
     },
     vm_status::VMStatus,
 };
@@ -59,6 +59,16 @@ impl LumioDebugger {
         self.debugger.get_committed_transactions(begin, limit).await
     }
 
+    pub async fn get_persisted_auxiliary_infos(
+        &self,
+        begin: Version,
+        limit: u64,
+    ) -> anyhow::Result<Vec<PersistedAuxiliaryInfo>> {
+        self.debugger
+            .get_persisted_auxiliary_infos(begin, limit)
+            .await
+    }
+
     pub fn execute_transactions_at_version(
         &self,
         version: Version,
@@ -123,51 +133,47 @@ impl LumioDebugger {
         auxiliary_info: AuxiliaryInfo,
     ) -> anyhow::Result<(VMStatus, VMOutput, TransactionGasLog)> {
         let state_view = DebuggerStateView::new(self.debugger.clone(), version);
-        let log_context = AdapterLogSchema::new(state_view.id(), 0);
-        let txn = txn
-            .check_signature()
-            .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?;
-
-        // Module bundle is deprecated!
-        if let TransactionPayload::ModuleBundle(_) = txn.payload() {
-            bail!("Module bundle payload has been removed")
-        }
+        profile_user_transaction(&state_view, txn, auxiliary_info)
+    }
 
-        let env = LumioEnvironment::new(&state_view);
-        let vm = LumioVM::new(&env, &state_view);
-        let resolver = state_view.as_move_resolver();
-        let code_storage = state_view.as_lumio_code_storage(&env);
-
-        let (status, output, gas_profiler) = vm.execute_user_transaction_with_modified_gas_meter(
-            &resolver,
-            &code_storage,
-            &txn,
-            &log_context,
-            |gas_meter| {
-                let gas_profiler = match txn
-                    .executable_ref()
-                    .expect("Module bundle payload has been removed")
-                {
-                    TransactionExecutableRef::Script(_) => GasProfiler::new_script(gas_meter),
-                    TransactionExecutableRef::EntryFunction(entry_func) => {
-                        GasProfiler::new_function(
-                            gas_meter,
-                            entry_func.module().clone(),
-                            entry_func.function().to_owned(),
-                            entry_func.ty_args().to_vec(),
-                        )
-                    },
-                    TransactionExecutableRef::Empty => {
-                        // TODO[Orderless]: Implement this
-                        unimplemented!("not supported yet")
-                    },
-                };
-                gas_profiler
-            },
-            &auxiliary_info,
-        )?;
+    /// Re-executes a committed user transaction, recording its structured execution trace.
+    pub fn execute_transaction_at_version_with_tracer(
+        &self,
+        version: Version,
+        txn: SignedTransaction,
+        auxiliary_info: AuxiliaryInfo,
+    ) -> anyhow::Result<(VMStatus, VMOutput, TransactionTrace)> {
+        let state_view = DebuggerStateView::new(self.debugger.clone(), version);
+        trace_user_transaction(&state_view, txn, auxiliary_info)
+    }
 
-        Ok((status, output, gas_profiler.finish()))
+    /// Traces the user transactions in the given range of versions, other transactions are
+    /// skipped.
+    pub async fn trace_past_transactions(
+        &self,
+        begin: Version,
+        limit: u64,
+    ) -> anyhow::Result<Vec<(Version, TransactionTrace)>> {
+        let (txns, _) = self.get_committed_transactions(begin, limit).await?;
+        let persisted_auxiliary_infos = self
+            .debugger
+            .get_persisted_auxiliary_infos(begin, limit)
+            .await?;
+
+        let mut traces = vec![];
+        for ((version, txn), persisted_auxiliary_info) in
+            (begin..).zip(txns).zip(persisted_auxiliary_infos)
+        {
+            if let Transaction::UserTransaction(txn) = txn {
+                let (_, _, trace) = self.execute_transaction_at_version_with_tracer(
+                    version,
+                    txn,
+                    AuxiliaryInfo::new(persisted_auxiliary_info, None),
+                )?;
+                traces.push((version, trace));
+            }
+        }
+        Ok(traces)
     }
 
     pub async fn execute_past_transactions(
@@ -364,11 +370,125 @@ impl LumioDebugger {
         Ok((txn, info))
     }
 
+    /// Returns the auxiliary info the committed transaction at the given version was executed
+    /// with, as far as it was persisted.
+    pub async fn get_auxiliary_info_at_version(
+        &self,
+        version: Version,
+    ) -> anyhow::Result<AuxiliaryInfo> {
+        let persisted_auxiliary_info = self
+            .debugger
+            .get_persisted_auxiliary_infos(version, 1)
+            .await?
+            .pop()
+            .unwrap_or(PersistedAuxiliaryInfo::None);
+        Ok(AuxiliaryInfo::new(persisted_auxiliary_info, None))
+    }
+
     pub fn state_view_at_version(&self, version: Version) -> DebuggerStateView {
         DebuggerStateView::new(self.debugger.clone(), version)
     }
 }
 
+/// Executes a user transaction on top of the given state, with a [`GasProfiler`] wrapping the gas
+/// meter.
+pub fn profile_user_transaction(
+    state_view: &impl StateView,
+    txn: SignedTransaction,
+    auxiliary_info: AuxiliaryInfo,
+) -> anyhow::Result<(VMStatus, VMOutput, TransactionGasLog)> {
+    let log_context = AdapterLogSchema::new(state_view.id(), 0);
+    let txn = txn
+        .check_signature()
+        .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?;
+
+    // Module bundle is deprecated!
+    if let TransactionPayload::ModuleBundle(_) = txn.payload() {
+        bail!("Module bundle payload has been removed")
+    }
+
+    let env = LumioEnvironment::new(state_view);
+    let vm = LumioVM::new(&env, state_view);
+    let resolver = state_view.as_move_resolver();
+    let code_storage = state_view.as_lumio_code_storage(&env);
+
+    let (status, output, gas_profiler) = vm.execute_user_transaction_with_modified_gas_meter(
+        &resolver,
+        &code_storage,
+        &txn,
+        &log_context,
+        |gas_meter| {
+            let gas_profiler = match txn
+                .executable_ref()
+                .expect("Module bundle payload has been removed")
+            {
+                TransactionExecutableRef::Script(_) => GasProfiler::new_script(gas_meter),
+                TransactionExecutableRef::EntryFunction(entry_func) => GasProfiler::new_function(
+                    gas_meter,
+                    entry_func.module().clone(),
+                    entry_func.function().to_owned(),
+                    entry_func.ty_args().to_vec(),
+                ),
+                TransactionExecutableRef::Empty => {
+                    // TODO[Orderless]: Implement this
+                    unimplemented!("not supported yet")
+                },
+            };
+            gas_profiler
+        },
+        &auxiliary_info,
+    )?;
+
+    Ok((status, output, gas_profiler.finish()))
+}
+
+/// Executes a user transaction on top of the given state, with an [`ExecutionTracer`] wrapping
+/// the gas meter.
+pub fn trace_user_transaction(
+    state_view: &impl StateView,
+    txn: SignedTransaction,
+    auxiliary_info: AuxiliaryInfo,
+) -> anyhow::Result<(VMStatus, VMOutput, TransactionTrace)> {
+    let log_context = AdapterLogSchema::new(state_view.id(), 0);
+    let txn = txn
+        .check_signature()
+        .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?;
+
+    let tracer_root = match txn.executable_ref() {
+        Ok(TransactionExecutableRef::Script(_)) => None,
+        Ok(TransactionExecutableRef::EntryFunction(entry_func)) if !txn.payload().is_multisig() => {
+            Some(entry_func.clone())
+        },
+        Ok(_) => bail!("Tracing multisig or empty payloads is not supported"),
+        Err(_) => bail!("Module bundle payload has been removed"),
+    };
+
+    let env = LumioEnvironment::new(state_view);
+    let vm = LumioVM::new(&env, state_view);
+    let resolver = state_view.as_move_resolver();
+    let code_storage = state_view.as_lumio_code_storage(&env);
+
+    let (status, output, tracer) = vm.execute_user_transaction_with_modified_gas_meter(
+        &resolver,
+        &code_storage,
+        &txn,
+        &log_context,
+        |gas_meter| match tracer_root {
+            None => ExecutionTracer::new_script(gas_meter),
+            Some(entry_func) => ExecutionTracer::new_function(
+                gas_meter,
+                entry_func.module().clone(),
+                entry_func.function().to_owned(),
+                entry_func.ty_args().to_vec(),
+                entry_func.args(),
+            ),
+        },
+        &auxiliary_info,
+    )?;
+
+    Ok((status, output, tracer.finish()))
+}
+
 fn print_transaction_stats(sig_verified_txns: &[SignatureVerifiedTransaction], version: u64) {
     let transaction_types = sig_verified_txns
         .iter()
@@ -0,0 +1,421 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{log::TransactionGasLog, report::ensure_dirs_exist};
+use anyhow::Result;
+use lumio_gas_algebra::{Fee, InternalGas};
+use handlebars::Handlebars;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::{collections::BTreeMap, fs, path::Path};
+
+const TEMPLATE: &str = include_str!("../templates/diff.html");
+
+/// A single gas quantity measured under two different configurations, referred to as the base and
+/// the other one.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct GasComparison {
+    pub base: f64,
+    pub other: f64,
+    pub delta: f64,
+}
+
+impl GasComparison {
+    fn new(base: f64, other: f64) -> Self {
+        Self {
+            base,
+            other,
+            delta: other - base,
+        }
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.base += other.base;
+        self.other += other.other;
+        self.delta += other.delta;
+    }
+
+    /// Returns the change relative to the base, or [None] if the base is zero.
+    pub fn relative_change(&self) -> Option<f64> {
+        if self.base == 0.0 {
+            None
+        } else {
+            Some(self.delta / self.base)
+        }
+    }
+}
+
+/// Gas used by a single function (including its callees), aggregated over all invocations.
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionGasDiff {
+    pub name: String,
+    pub base_calls: usize,
+    pub other_calls: usize,
+    pub gas: GasComparison,
+}
+
+/// Difference in gas usage of a single transaction executed under two configurations.
+///
+/// Execution and IO are expressed in (external) gas units, storage fees and refunds in LUM.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionGasDiff {
+    pub label: String,
+    pub execution: GasComparison,
+    pub io: GasComparison,
+    pub storage_fee: GasComparison,
+    pub storage_refund: GasComparison,
+    /// Per-function differences, sorted by the absolute delta from high to low.
+    pub functions: Vec<FunctionGasDiff>,
+}
+
+/// Execution, IO and storage costs of a transaction, in display units.
+struct GasBreakdown {
+    execution: f64,
+    io: f64,
+    storage_fee: f64,
+    storage_refund: f64,
+    functions: BTreeMap<String, (usize, f64)>,
+}
+
+fn internal_gas_to_units(gas: InternalGas, scaling_factor: f64) -> f64 {
+    u64::from(gas) as f64 / scaling_factor
+}
+
+fn fee_to_lum(fee: Fee) -> f64 {
+    u64::from(fee) as f64 / 1_0000_0000f64
+}
+
+impl GasBreakdown {
+    fn new(log: &TransactionGasLog) -> Self {
+        let aggregated = log.exec_io.aggregate_gas_events();
+        let scaling_factor = u64::from(aggregated.gas_scaling_factor) as f64;
+
+        let io = aggregated.transaction_write
+            + aggregated
+                .event_writes
+                .iter()
+                .chain(&aggregated.storage_reads)
+                .chain(&aggregated.storage_writes)
+                .fold(InternalGas::zero(), |acc, (_, _, cost)| acc + *cost);
+        let execution = aggregated
+            .total
+            .checked_sub(io)
+            .unwrap_or_else(InternalGas::zero);
+
+        let functions = aggregated
+            .methods
+            .into_iter()
+            .map(|(name, count, cost)| (name, (count, internal_gas_to_units(cost, scaling_factor))))
+            .collect();
+
+        Self {
+            execution: internal_gas_to_units(execution, scaling_factor),
+            io: internal_gas_to_units(io, scaling_factor),
+            storage_fee: fee_to_lum(log.storage.total),
+            storage_refund: fee_to_lum(log.storage.total_refund),
+            functions,
+        }
+    }
+}
+
+/// Merges per-function costs from two configurations, sorting the result by the absolute delta.
+fn diff_functions(
+    base: BTreeMap<String, (usize, f64)>,
+    other: BTreeMap<String, (usize, f64)>,
+) -> Vec<FunctionGasDiff> {
+    let mut merged: BTreeMap<String, ((usize, f64), (usize, f64))> = BTreeMap::new();
+    for (name, entry) in base {
+        merged.entry(name).or_default().0 = entry;
+    }
+    for (name, entry) in other {
+        merged.entry(name).or_default().1 = entry;
+    }
+
+    let mut functions = merged
+        .into_iter()
+        .map(
+            |(name, ((base_calls, base_gas), (other_calls, other_gas)))| FunctionGasDiff {
+                name,
+                base_calls,
+                other_calls,
+                gas: GasComparison::new(base_gas, other_gas),
+            },
+        )
+        .collect::<Vec<_>>();
+    functions.sort_by(|lhs, rhs| rhs.gas.delta.abs().total_cmp(&lhs.gas.delta.abs()));
+    functions
+}
+
+impl TransactionGasDiff {
+    /// Compares the gas logs of the same transaction executed under two configurations.
+    pub fn new(label: String, base: &TransactionGasLog, other: &TransactionGasLog) -> Self {
+        let base = GasBreakdown::new(base);
+        let other = GasBreakdown::new(other);
+
+        Self {
+            label,
+            execution: GasComparison::new(base.execution, other.execution),
+            io: GasComparison::new(base.io, other.io),
+            storage_fee: GasComparison::new(base.storage_fee, other.storage_fee),
+            storage_refund: GasComparison::new(base.storage_refund, other.storage_refund),
+            functions: diff_functions(base.functions, other.functions),
+        }
+    }
+}
+
+/// Report comparing gas usage of a set of transactions executed under two configurations, e.g.,
+/// different gas schedules or framework versions.
+#[derive(Debug, Serialize)]
+pub struct GasDiffReport {
+    pub base_name: String,
+    pub other_name: String,
+    pub execution: GasComparison,
+    pub io: GasComparison,
+    pub storage_fee: GasComparison,
+    pub storage_refund: GasComparison,
+    /// Per-function differences aggregated over all transactions, sorted by the absolute delta
+    /// from high to low.
+    pub functions: Vec<FunctionGasDiff>,
+    pub transactions: Vec<TransactionGasDiff>,
+}
+
+impl GasDiffReport {
+    pub fn new(
+        base_name: String,
+        other_name: String,
+        transactions: Vec<TransactionGasDiff>,
+    ) -> Self {
+        let mut execution = GasComparison::default();
+        let mut io = GasComparison::default();
+        let mut storage_fee = GasComparison::default();
+        let mut storage_refund = GasComparison::default();
+        let mut functions: BTreeMap<String, FunctionGasDiff> = BTreeMap::new();
+
+        for txn in &transactions {
+            execution.add(&txn.execution);
+            io.add(&txn.io);
+            storage_fee.add(&txn.storage_fee);
+            storage_refund.add(&txn.storage_refund);
+
+            for function in &txn.functions {
+                functions
+                    .entry(function.name.clone())
+                    .and_modify(|entry| {
+                        entry.base_calls += function.base_calls;
+                        entry.other_calls += function.other_calls;
+                        entry.gas.add(&function.gas);
+                    })
+                    .or_insert_with(|| function.clone());
+            }
+        }
+
+        let mut functions = functions.into_values().collect::<Vec<_>>();
+        functions.sort_by(|lhs, rhs| rhs.gas.delta.abs().total_cmp(&lhs.gas.delta.abs()));
+
+        Self {
+            base_name,
+            other_name,
+            execution,
+            io,
+            storage_fee,
+            storage_refund,
+            functions,
+            transactions,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Writes the report to the given directory, both as `diff.json` and `index.html`.
+    pub fn generate_report(&self, path: impl AsRef<Path>, header: String) -> Result<()> {
+        let fmt_amount = |amount: f64| -> String {
+            let scaled = format!("{:.8}", amount);
+            crate::misc::strip_trailing_zeros_and_decimal_point(&scaled).to_string()
+        };
+        let convert = |name: &str, comparison: &GasComparison| -> Value {
+            let delta = fmt_amount(comparison.delta);
+            json!({
+                "name": name,
+                "base": fmt_amount(comparison.base),
+                "other": fmt_amount(comparison.other),
+                "delta": if comparison.delta > 0.0 { format!("+{}", delta) } else { delta },
+                "percentage": comparison
+                    .relative_change()
+                    .map(|change| format!("{:+.2}%", change * 100.0))
+                    .unwrap_or_else(|| "/".to_string()),
+            })
+        };
+        let convert_functions = |functions: &[FunctionGasDiff]| -> Value {
+            Value::Array(
+                functions
+                    .iter()
+                    .map(|function| {
+                        let mut entry = convert(&function.name, &function.gas);
+                        entry["base-calls"] = json!(function.base_calls);
+                        entry["other-calls"] = json!(function.other_calls);
+                        entry
+                    })
+                    .collect(),
+            )
+        };
+
+        let mut data = Map::new();
+        data.insert("title".to_string(), Value::String(header));
+        data.insert("base-name".to_string(), json!(self.base_name));
+        data.insert("other-name".to_string(), json!(self.other_name));
+        data.insert(
+            "totals".to_string(),
+            json!([
+                convert("execution", &self.execution),
+                convert("io", &self.io),
+                convert("storage_fee", &self.storage_fee),
+                convert("storage_refund", &self.storage_refund),
+            ]),
+        );
+        data.insert("functions".to_string(), convert_functions(&self.functions));
+        data.insert(
+            "transactions".to_string(),
+            Value::Array(
+                self.transactions
+                    .iter()
+                    .map(|txn| {
+                        json!({
+                            "label": txn.label,
+                            "execution": convert("execution", &txn.execution),
+                            "io": convert("io", &txn.io),
+                            "storage-fee": convert("storage_fee", &txn.storage_fee),
+                            "storage-refund": convert("storage_refund", &txn.storage_refund),
+                            "functions": convert_functions(&txn.functions),
+                        })
+                    })
+                    .collect(),
+            ),
+        );
+
+        let mut handlebars = Handlebars::new();
+        handlebars.register_template_string("diff", TEMPLATE)?;
+        let html = handlebars.render("diff", &data)?;
+
+        let path_root = path.as_ref();
+        ensure_dirs_exist(path_root)?;
+        fs::write(path_root.join("diff.json"), self.to_json()?)?;
+        fs::write(path_root.join("index.html"), html)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn costs(entries: &[(&str, usize, f64)]) -> BTreeMap<String, (usize, f64)> {
+        entries
+            .iter()
+            .map(|(name, calls, gas)| (name.to_string(), (*calls, *gas)))
+            .collect()
+    }
+
+    fn function(
+        name: &str,
+        base_calls: usize,
+        other_calls: usize,
+        base: f64,
+        other: f64,
+    ) -> FunctionGasDiff {
+        FunctionGasDiff {
+            name: name.to_string(),
+            base_calls,
+            other_calls,
+            gas: GasComparison::new(base, other),
+        }
+    }
+
+    fn transaction(label: &str, functions: Vec<FunctionGasDiff>) -> TransactionGasDiff {
+        TransactionGasDiff {
+            label: label.to_string(),
+            execution: GasComparison::new(10.0, 12.0),
+            io: GasComparison::new(5.0, 4.0),
+            storage_fee: GasComparison::new(0.5, 0.5),
+            storage_refund: GasComparison::new(0.0, 0.25),
+            functions,
+        }
+    }
+
+    #[test]
+    fn test_relative_change() {
+        assert_eq!(GasComparison::new(4.0, 5.0).relative_change(), Some(0.25));
+        assert_eq!(GasComparison::new(4.0, 2.0).relative_change(), Some(-0.5));
+        assert_eq!(GasComparison::new(0.0, 3.0).relative_change(), None);
+        assert_eq!(GasComparison::default().relative_change(), None);
+    }
+
+    #[test]
+    fn test_diff_functions() {
+        let base = costs(&[("0x1::a::f", 1, 10.0), ("0x1::b::g", 2, 3.0)]);
+        let other = costs(&[("0x1::b::g", 4, 8.0), ("0x1::c::h", 1, 1.0)]);
+
+        let functions = diff_functions(base, other);
+        let names = functions
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect::<Vec<_>>();
+        // Sorted by the absolute delta: -10, +5, +1.
+        assert_eq!(names, vec!["0x1::a::f", "0x1::b::g", "0x1::c::h"]);
+
+        // Functions executed in only one of the configurations have no calls in the other one.
+        assert_eq!(functions[0].base_calls, 1);
+        assert_eq!(functions[0].other_calls, 0);
+        assert_eq!(functions[0].gas.delta, -10.0);
+
+        assert_eq!(functions[1].base_calls, 2);
+        assert_eq!(functions[1].other_calls, 4);
+        assert_eq!(functions[1].gas.base, 3.0);
+        assert_eq!(functions[1].gas.other, 8.0);
+        assert_eq!(functions[1].gas.delta, 5.0);
+
+        assert_eq!(functions[2].base_calls, 0);
+        assert_eq!(functions[2].other_calls, 1);
+        assert_eq!(functions[2].gas.relative_change(), None);
+    }
+
+    #[test]
+    fn test_report_aggregation() {
+        let transactions = vec![
+            transaction("Transaction 0", vec![
+                function("0x1::a::f", 1, 1, 2.0, 3.0),
+                function("0x1::b::g", 1, 0, 1.0, 0.0),
+            ]),
+            transaction("Transaction 1", vec![
+                function("0x1::b::g", 2, 2, 2.0, 6.0),
+                function("0x1::a::f", 1, 1, 2.0, 2.0),
+            ]),
+        ];
+        let report = GasDiffReport::new("base".to_string(), "other".to_string(), transactions);
+
+        assert_eq!(report.execution.base, 20.0);
+        assert_eq!(report.execution.other, 24.0);
+        assert_eq!(report.execution.delta, 4.0);
+        assert_eq!(report.io.delta, -2.0);
+        assert_eq!(report.storage_fee.delta, 0.0);
+        assert_eq!(report.storage_refund.base, 0.0);
+        assert_eq!(report.storage_refund.other, 0.5);
+        assert_eq!(report.storage_refund.relative_change(), None);
+
+        // Functions are aggregated over all transactions and sorted by the absolute delta.
+        assert_eq!(report.functions.len(), 2);
+        let g = &report.functions[0];
+        assert_eq!(g.name, "0x1::b::g");
+        assert_eq!((g.base_calls, g.other_calls), (3, 2));
+        assert_eq!((g.gas.base, g.gas.other, g.gas.delta), (3.0, 6.0, 3.0));
+        let f = &report.functions[1];
+        assert_eq!(f.name, "0x1::a::f");
+        assert_eq!((f.base_calls, f.other_calls), (2, 2));
+        assert_eq!((f.gas.base, f.gas.other, f.gas.delta), (4.0, 5.0, 1.0));
+
+        assert_eq!(report.transactions.len(), 2);
+        assert_eq!(report.transactions[1].label, "Transaction 1");
+    }
+}
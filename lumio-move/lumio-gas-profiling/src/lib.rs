@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod aggregate;
+mod diff;
 mod erased;
 mod flamegraph;
 mod log;
@@ -9,6 +10,12 @@ mod misc;
 mod profiler;
 mod render;
 mod report;
+mod trace;
 
-pub use log::{FrameName, TransactionGasLog};
+pub use diff::{FunctionGasDiff, GasComparison, GasDiffReport, TransactionGasDiff};
+pub use log::{FrameName, TransactionGasLog, WriteOpType};
 pub use profiler::GasProfiler;
+pub use trace::{
+    CallTrace, EventTrace, ExecutionTracer, ResourceAccess, ResourceAccessKind, TransactionTrace,
+    WriteTrace,
+};
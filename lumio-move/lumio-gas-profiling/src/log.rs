@@ -11,6 +11,7 @@ use move_core_types::{
     language_storage::{ModuleId, TypeTag},
 };
 use move_vm_types::gas::DependencyKind;
+use serde::Serialize;
 use smallvec::{smallvec, SmallVec};
 
 /// An event occurred during the execution of a function, along with the
@@ -67,7 +68,8 @@ pub struct CallFrame {
 /// The type of an operation performed on a storage item.
 ///
 /// Possible values: Creation, Modification & Deletion.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum WriteOpType {
     Creation,
     Modification,
@@ -235,6 +235,20 @@ where
             &mut self,
             locals: impl Iterator<Item = impl ValueView> + Clone,
         ) -> PartialVMResult<()>;
+
+        // Note: we don't use this to charge gas so no need to record anything.
+        fn charge_return(
+            &mut self,
+            ret_vals: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        // Note: we don't use this to charge gas so no need to record anything.
+        fn charge_global_write(
+            &mut self,
+            addr: AccountAddress,
+            ty: impl TypeView,
+            val: Option<impl ValueView>,
+        ) -> PartialVMResult<()>;
     }
 
     record_bytecode! {
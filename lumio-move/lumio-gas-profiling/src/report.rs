@@ -14,7 +14,7 @@ use std::{
 
 const TEMPLATE: &str = include_str!("../templates/index.html");
 
-fn ensure_dirs_exist(path: impl AsRef<Path>) -> Result<()> {
+pub(crate) fn ensure_dirs_exist(path: impl AsRef<Path>) -> Result<()> {
     if let Err(err) = fs::create_dir_all(&path) {
         match err.kind() {
             std::io::ErrorKind::AlreadyExists => (),
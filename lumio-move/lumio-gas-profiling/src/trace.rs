@@ -0,0 +1,1215 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::log::WriteOpType;
+use lumio_gas_algebra::{Fee, FeePerGasUnit, InternalGas, NumArgs, NumBytes, NumTypeNodes};
+use lumio_gas_meter::LumioGasMeter;
+use lumio_types::{
+    access_path::Path,
+    account_address::AccountAddress,
+    contract_event::ContractEvent,
+    state_store::state_key::{inner::StateKeyInner, StateKey},
+    write_set::WriteOpSize,
+};
+use move_binary_format::{
+    errors::{PartialVMResult, VMResult},
+    file_format::CodeOffset,
+};
+use move_core_types::{
+    identifier::{IdentStr, Identifier},
+    language_storage::{ModuleId, StructTag, TypeTag, CORE_CODE_ADDRESS},
+    u256::U256,
+};
+use move_vm_types::{
+    delayed_values::delayed_field_id::DelayedFieldID,
+    gas::{DependencyGasMeter, DependencyKind, GasMeter, NativeGasMeter, SimpleInstruction},
+    views::{TypeView, ValueView, ValueVisitor},
+};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+/// The kind of access a call made to a global resource.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceAccessKind {
+    /// The resource was loaded from storage (the first access within the transaction).
+    Load,
+    Exists,
+    Borrow,
+    BorrowMut,
+    MoveFrom,
+    MoveTo,
+}
+
+/// An access to a global resource made by a call.
+///
+/// The address is known when the resource is loaded from storage or written (i.e., borrowed
+/// mutably, moved from or moved to), other accesses only carry its type. The value of a mutable
+/// borrow is the value of the resource at the time it was borrowed.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ResourceAccess {
+    pub kind: ResourceAccessKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<AccountAddress>,
+    #[serde(rename = "type")]
+    pub ty: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<JsonValue>,
+}
+
+/// An event emitted by a call.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct EventTrace {
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub data: JsonValue,
+}
+
+/// A function call (or the script) executed by a transaction, along with the nested calls it
+/// made, in execution order.
+///
+/// Values are rendered as JSON without their types: structs as `{"fields": [...]}`, vectors as
+/// arrays, `vector<u8>` as hex strings and integers wider than 32 bits as strings. The arguments
+/// of the entry function are the raw BCS bytes of the transaction payload.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct CallTrace {
+    /// `<address>::<module>::<function>`, or `script`.
+    pub function: String,
+    pub ty_args: Vec<String>,
+    pub args: Vec<JsonValue>,
+    /// The values returned by the call. Empty for the entry function (or script) if the
+    /// execution aborted, and for calls that never returned.
+    pub return_values: Vec<JsonValue>,
+    pub is_native: bool,
+    /// Gas charged while the call was active, including its nested calls, in internal gas units.
+    /// For the entry function (or script), this covers the whole transaction.
+    pub gas_used: u64,
+    pub resources: Vec<ResourceAccess>,
+    pub events: Vec<EventTrace>,
+    /// The writes of the transaction to the resources this call wrote last (i.e., the last
+    /// mutable borrow, move from or move to). Writes that can't be attributed to a call (e.g.,
+    /// table items or the gas fee) are only part of the transaction's write set.
+    pub writes: Vec<WriteTrace>,
+    pub calls: Vec<CallTrace>,
+}
+
+/// A write produced by the transaction.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct WriteTrace {
+    pub state_key: String,
+    pub op: WriteOpType,
+}
+
+/// The structured execution trace of a single transaction.
+#[derive(Clone, Debug, Serialize)]
+pub struct TransactionTrace {
+    pub call: CallTrace,
+    pub write_set: Vec<WriteTrace>,
+}
+
+struct Frame {
+    trace: CallTrace,
+    start_balance: InternalGas,
+    /// The position of the call in the call tree, as the indices of the call and its ancestors
+    /// in the calls of their callers (empty for the entry function or script).
+    path: Vec<usize>,
+}
+
+/// A gas meter adapter that records the call tree of a transaction, along with the arguments,
+/// return values, resource accesses, events and writes of each call. Gas is charged by the
+/// underlying gas meter as usual.
+pub struct ExecutionTracer<G> {
+    base: G,
+
+    frames: Vec<Frame>,
+    /// The resources written by the calls, along with the position of the writing call in the
+    /// call tree, in execution order.
+    global_writes: Vec<(AccountAddress, StructTag, Vec<usize>)>,
+    write_set: Vec<(StateKey, WriteTrace)>,
+}
+
+// TODO: share with the gas profiler, or switch to a library like
+//       https://docs.rs/delegate/latest/delegate/.
+macro_rules! delegate_mut {
+    ($(
+        fn $fn: ident $(<$($lt: lifetime),*>)? (&mut self $(, $arg: ident : $ty: ty)* $(,)?) -> $ret_ty: ty;
+    )*) => {
+        $(fn $fn $(<$($lt)*>)? (&mut self, $($arg: $ty),*) -> $ret_ty {
+            self.base.$fn($($arg),*)
+        })*
+    };
+}
+
+impl<G> ExecutionTracer<G>
+where
+    G: LumioGasMeter,
+{
+    pub fn new_script(base: G) -> Self {
+        let root = Frame {
+            trace: CallTrace::new("script".to_string(), vec![], vec![]),
+            start_balance: base.balance_internal(),
+            path: vec![],
+        };
+        Self {
+            base,
+            frames: vec![root],
+            global_writes: vec![],
+            write_set: vec![],
+        }
+    }
+
+    pub fn new_function(
+        base: G,
+        module_id: ModuleId,
+        func_name: Identifier,
+        ty_args: Vec<TypeTag>,
+        args: &[Vec<u8>],
+    ) -> Self {
+        let root = Frame {
+            trace: CallTrace::new(
+                function_name(&module_id, func_name.as_str()),
+                ty_args.iter().map(|ty| ty.to_canonical_string()).collect(),
+                args.iter()
+                    .map(|arg| JsonValue::String(format!("0x{}", hex::encode(arg))))
+                    .collect(),
+            ),
+            start_balance: base.balance_internal(),
+            path: vec![],
+        };
+        Self {
+            base,
+            frames: vec![root],
+            global_writes: vec![],
+            write_set: vec![],
+        }
+    }
+
+    fn active_frame(&mut self) -> &mut CallTrace {
+        &mut self.frames.last_mut().expect("frame must exist").trace
+    }
+
+    fn record_resource_access(
+        &mut self,
+        kind: ResourceAccessKind,
+        address: Option<AccountAddress>,
+        ty: TypeTag,
+        value: Option<JsonValue>,
+    ) {
+        self.active_frame().resources.push(ResourceAccess {
+            kind,
+            address,
+            ty: ty.to_canonical_string(),
+            value,
+        });
+    }
+
+    fn push_frame(&mut self, trace: CallTrace) {
+        let start_balance = self.base.balance_internal();
+        let caller = self.frames.last().expect("frame must exist");
+        let mut path = caller.path.clone();
+        path.push(caller.trace.calls.len());
+        self.frames.push(Frame {
+            trace,
+            start_balance,
+            path,
+        });
+    }
+
+    /// Pops the active frame and attaches it to its caller.
+    fn pop_frame(&mut self) {
+        let frame = self.frames.pop().expect("frame must exist");
+        let trace = self.finish_frame(frame);
+        self.active_frame().calls.push(trace);
+    }
+
+    fn finish_frame(&self, frame: Frame) -> CallTrace {
+        let mut trace = frame.trace;
+        trace.gas_used = frame
+            .start_balance
+            .checked_sub(self.base.balance_internal())
+            .unwrap_or_else(|| 0.into())
+            .into();
+        trace
+    }
+
+    pub fn finish(mut self) -> TransactionTrace {
+        // Frames are left on the stack if the execution aborted.
+        while self.frames.len() > 1 {
+            self.pop_frame();
+        }
+        let root = self.frames.pop().expect("frame must exist");
+        let mut call = self.finish_frame(root);
+
+        // Attribute the writes to the calls that wrote the resources last
+        let standalone_resources = self
+            .write_set
+            .iter()
+            .filter_map(|(key, _)| match resource_path(key) {
+                Some((addr, Path::Resource(tag))) => Some((addr, tag)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        for (key, write) in &self.write_set {
+            let writer = match resource_path(key) {
+                Some((addr, Path::Resource(tag))) => self
+                    .global_writes
+                    .iter()
+                    .rev()
+                    .find(|(write_addr, write_tag, _)| *write_addr == addr && *write_tag == tag),
+                // Resource groups are written as a whole, so the group is attributed to the last
+                // call writing a resource at the address that is not stored on its own.
+                Some((addr, Path::ResourceGroup(_))) => {
+                    self.global_writes
+                        .iter()
+                        .rev()
+                        .find(|(write_addr, write_tag, _)| {
+                            *write_addr == addr
+                                && !standalone_resources
+                                    .iter()
+                                    .any(|(a, t)| *a == addr && t == write_tag)
+                        })
+                },
+                _ => None,
+            };
+            if let Some((_, _, path)) = writer {
+                let mut writer_call = &mut call;
+                for idx in path {
+                    writer_call = &mut writer_call.calls[*idx];
+                }
+                writer_call.writes.push(write.clone());
+            }
+        }
+
+        TransactionTrace {
+            call,
+            write_set: self.write_set.into_iter().map(|(_, write)| write).collect(),
+        }
+    }
+}
+
+impl CallTrace {
+    fn new(function: String, ty_args: Vec<String>, args: Vec<JsonValue>) -> Self {
+        Self {
+            function,
+            ty_args,
+            args,
+            return_values: vec![],
+            is_native: false,
+            gas_used: 0,
+            resources: vec![],
+            events: vec![],
+            writes: vec![],
+            calls: vec![],
+        }
+    }
+}
+
+fn function_name(module_id: &ModuleId, func_name: &str) -> String {
+    format!("{}::{}", module_id.short_str_lossless(), func_name)
+}
+
+/// Returns the address and path of a resource (or resource group) state key.
+fn resource_path(key: &StateKey) -> Option<(AccountAddress, Path)> {
+    match key.inner() {
+        StateKeyInner::AccessPath(access_path) => match access_path.get_path() {
+            Path::Code(_) => None,
+            path => Some((access_path.address, path)),
+        },
+        _ => None,
+    }
+}
+
+/// Returns whether the function is one of the natives every event is emitted through.
+fn is_event_native(module_id: &ModuleId, func_name: &str) -> bool {
+    module_id.address() == &CORE_CODE_ADDRESS
+        && module_id.name().as_str() == "event"
+        && matches!(
+            func_name,
+            "write_module_event_to_store" | "write_to_event_store"
+        )
+}
+
+impl<G> DependencyGasMeter for ExecutionTracer<G>
+where
+    G: LumioGasMeter,
+{
+    delegate_mut! {
+        fn charge_dependency(
+            &mut self,
+            kind: DependencyKind,
+            addr: &AccountAddress,
+            name: &IdentStr,
+            size: NumBytes,
+        ) -> PartialVMResult<()>;
+    }
+}
+
+impl<G> NativeGasMeter for ExecutionTracer<G>
+where
+    G: LumioGasMeter,
+{
+    delegate_mut! {
+        fn use_heap_memory_in_native_context(&mut self, amount: u64) -> PartialVMResult<()>;
+
+        fn charge_native_execution(&mut self, amount: InternalGas) -> PartialVMResult<()>;
+    }
+
+    fn legacy_gas_budget_in_native_context(&self) -> InternalGas {
+        self.base.legacy_gas_budget_in_native_context()
+    }
+}
+
+impl<G> GasMeter for ExecutionTracer<G>
+where
+    G: LumioGasMeter,
+{
+    delegate_mut! {
+        fn charge_br_true(&mut self, target_offset: Option<CodeOffset>) -> PartialVMResult<()>;
+
+        fn charge_br_false(&mut self, target_offset: Option<CodeOffset>) -> PartialVMResult<()>;
+
+        fn charge_branch(&mut self, target_offset: CodeOffset) -> PartialVMResult<()>;
+
+        fn charge_pop(&mut self, popped_val: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_ld_const(&mut self, size: NumBytes) -> PartialVMResult<()>;
+
+        fn charge_ld_const_after_deserialization(&mut self, val: impl ValueView)
+            -> PartialVMResult<()>;
+
+        fn charge_copy_loc(&mut self, val: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_move_loc(&mut self, val: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_store_loc(&mut self, val: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_pack(
+            &mut self,
+            is_generic: bool,
+            args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        fn charge_unpack(
+            &mut self,
+            is_generic: bool,
+            args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        fn charge_pack_closure(
+            &mut self,
+            is_generic: bool,
+            args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        fn charge_read_ref(&mut self, val: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_write_ref(
+            &mut self,
+            new_val: impl ValueView,
+            old_val: impl ValueView,
+        ) -> PartialVMResult<()>;
+
+        fn charge_eq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_neq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_vec_pack<'a>(
+            &mut self,
+            ty: impl TypeView + 'a,
+            args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        fn charge_vec_len(&mut self, ty: impl TypeView) -> PartialVMResult<()>;
+
+        fn charge_vec_borrow(
+            &mut self,
+            is_mut: bool,
+            ty: impl TypeView,
+            is_success: bool,
+        ) -> PartialVMResult<()>;
+
+        fn charge_vec_push_back(
+            &mut self,
+            ty: impl TypeView,
+            val: impl ValueView,
+        ) -> PartialVMResult<()>;
+
+        fn charge_vec_pop_back(
+            &mut self,
+            ty: impl TypeView,
+            val: Option<impl ValueView>,
+        ) -> PartialVMResult<()>;
+
+        fn charge_vec_unpack(
+            &mut self,
+            ty: impl TypeView,
+            expect_num_elements: NumArgs,
+            elems: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        fn charge_vec_swap(&mut self, ty: impl TypeView) -> PartialVMResult<()>;
+
+        fn charge_native_function_before_execution(
+            &mut self,
+            ty_args: impl ExactSizeIterator<Item = impl TypeView> + Clone,
+            args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        fn charge_drop_frame(
+            &mut self,
+            locals: impl Iterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        fn charge_create_ty(&mut self, num_nodes: NumTypeNodes) -> PartialVMResult<()>;
+
+        fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()>;
+    }
+
+    fn balance_internal(&self) -> InternalGas {
+        self.base.balance_internal()
+    }
+
+    fn charge_return(
+        &mut self,
+        ret_vals: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+    ) -> PartialVMResult<()> {
+        let return_values = ret_vals.clone().map(render_value).collect();
+
+        let res = self.base.charge_return(ret_vals);
+
+        // Like in the gas profiler, the frame of the entry function (or script) is kept until the
+        // end of the transaction.
+        self.active_frame().return_values = return_values;
+        if self.frames.len() > 1 {
+            self.pop_frame();
+        }
+
+        res
+    }
+
+    fn charge_global_write(
+        &mut self,
+        addr: AccountAddress,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        let ty_tag = ty.to_type_tag();
+        let value = val.as_ref().map(render_value);
+
+        let res = self.base.charge_global_write(addr, ty, val);
+
+        // Complete the access recorded when the write was charged (which lacks the address)
+        let ty_name = ty_tag.to_canonical_string();
+        if let Some(access) = self
+            .active_frame()
+            .resources
+            .iter_mut()
+            .rev()
+            .find(|access| {
+                access.address.is_none()
+                    && access.ty == ty_name
+                    && matches!(
+                        access.kind,
+                        ResourceAccessKind::BorrowMut
+                            | ResourceAccessKind::MoveFrom
+                            | ResourceAccessKind::MoveTo
+                    )
+            })
+        {
+            access.address = Some(addr);
+            if access.kind == ResourceAccessKind::BorrowMut {
+                access.value = value;
+            }
+        }
+        if let TypeTag::Struct(struct_tag) = ty_tag {
+            let path = self.frames.last().expect("frame must exist").path.clone();
+            self.global_writes.push((addr, *struct_tag, path));
+        }
+
+        res
+    }
+
+    fn charge_call(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        let trace = CallTrace::new(
+            function_name(module_id, func_name),
+            vec![],
+            args.clone().map(render_value).collect(),
+        );
+
+        let res = self
+            .base
+            .charge_call(module_id, func_name, args, num_locals);
+
+        self.push_frame(trace);
+
+        res
+    }
+
+    fn charge_call_generic(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        ty_args: impl ExactSizeIterator<Item = impl TypeView> + Clone,
+        args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        let ty_tags = ty_args
+            .clone()
+            .map(|ty| ty.to_type_tag())
+            .collect::<Vec<_>>();
+        let rendered_args = args.clone().map(render_value).collect::<Vec<_>>();
+
+        // Events are emitted by the callers of the event natives, with the event as the last
+        // argument.
+        if is_event_native(module_id, func_name) {
+            if let (Some(ty), Some(data)) = (ty_tags.first(), rendered_args.last()) {
+                let event = EventTrace {
+                    ty: ty.to_canonical_string(),
+                    data: data.clone(),
+                };
+                self.active_frame().events.push(event);
+            }
+        }
+
+        let res = self
+            .base
+            .charge_call_generic(module_id, func_name, ty_args, args, num_locals);
+
+        self.push_frame(CallTrace::new(
+            function_name(module_id, func_name),
+            ty_tags.iter().map(|ty| ty.to_canonical_string()).collect(),
+            rendered_args,
+        ));
+
+        res
+    }
+
+    fn charge_native_function(
+        &mut self,
+        amount: InternalGas,
+        ret_vals: Option<impl ExactSizeIterator<Item = impl ValueView> + Clone>,
+    ) -> PartialVMResult<()> {
+        // Similar to the gas profiler, the frame pushed by `charge_call/charge_call_generic`
+        // turns out to be the one of a native function.
+        let return_values = ret_vals
+            .clone()
+            .map(|vals| vals.map(render_value).collect())
+            .unwrap_or_default();
+
+        let res = self.base.charge_native_function(amount, ret_vals);
+
+        let frame = self.active_frame();
+        frame.is_native = true;
+        frame.return_values = return_values;
+        if self.frames.len() > 1 {
+            self.pop_frame();
+        }
+
+        res
+    }
+
+    fn charge_load_resource(
+        &mut self,
+        addr: AccountAddress,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+        bytes_loaded: NumBytes,
+    ) -> PartialVMResult<()> {
+        let ty_tag = ty.to_type_tag();
+        let value = val.as_ref().map(render_value);
+
+        let res = self.base.charge_load_resource(addr, ty, val, bytes_loaded);
+
+        self.record_resource_access(ResourceAccessKind::Load, Some(addr), ty_tag, value);
+
+        res
+    }
+
+    fn charge_borrow_global(
+        &mut self,
+        is_mut: bool,
+        is_generic: bool,
+        ty: impl TypeView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        let ty_tag = ty.to_type_tag();
+
+        let res = self
+            .base
+            .charge_borrow_global(is_mut, is_generic, ty, is_success);
+
+        if is_success {
+            let kind = if is_mut {
+                ResourceAccessKind::BorrowMut
+            } else {
+                ResourceAccessKind::Borrow
+            };
+            self.record_resource_access(kind, None, ty_tag, None);
+        }
+
+        res
+    }
+
+    fn charge_exists(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        exists: bool,
+    ) -> PartialVMResult<()> {
+        let ty_tag = ty.to_type_tag();
+
+        let res = self.base.charge_exists(is_generic, ty, exists);
+
+        self.record_resource_access(
+            ResourceAccessKind::Exists,
+            None,
+            ty_tag,
+            Some(JsonValue::Bool(exists)),
+        );
+
+        res
+    }
+
+    fn charge_move_from(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        let ty_tag = ty.to_type_tag();
+        let value = val.as_ref().map(render_value);
+
+        let res = self.base.charge_move_from(is_generic, ty, val);
+
+        if value.is_some() {
+            self.record_resource_access(ResourceAccessKind::MoveFrom, None, ty_tag, value);
+        }
+
+        res
+    }
+
+    fn charge_move_to(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        val: impl ValueView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        let ty_tag = ty.to_type_tag();
+        let value = render_value(&val);
+
+        let res = self.base.charge_move_to(is_generic, ty, val, is_success);
+
+        if is_success {
+            self.record_resource_access(ResourceAccessKind::MoveTo, None, ty_tag, Some(value));
+        }
+
+        res
+    }
+}
+
+impl<G> LumioGasMeter for ExecutionTracer<G>
+where
+    G: LumioGasMeter,
+{
+    type Algebra = G::Algebra;
+
+    delegate_mut! {
+        fn algebra_mut(&mut self) -> &mut Self::Algebra;
+
+        fn charge_storage_fee(
+            &mut self,
+            amount: Fee,
+            gas_unit_price: FeePerGasUnit,
+        ) -> PartialVMResult<()>;
+
+        fn charge_intrinsic_gas_for_transaction(&mut self, txn_size: NumBytes) -> VMResult<()>;
+
+        fn charge_keyless(&mut self) -> VMResult<()>;
+
+        fn charge_io_gas_for_transaction(&mut self, txn_size: NumBytes) -> VMResult<()>;
+
+        fn charge_io_gas_for_event(&mut self, event: &ContractEvent) -> VMResult<()>;
+    }
+
+    fn algebra(&self) -> &Self::Algebra {
+        self.base.algebra()
+    }
+
+    fn charge_io_gas_for_write(&mut self, key: &StateKey, op: &WriteOpSize) -> VMResult<()> {
+        let op_type = match op {
+            WriteOpSize::Creation { .. } => WriteOpType::Creation,
+            WriteOpSize::Modification { .. } => WriteOpType::Modification,
+            WriteOpSize::Deletion => WriteOpType::Deletion,
+        };
+        self.write_set.push((key.clone(), WriteTrace {
+            state_key: format!("{:?}", key),
+            op: op_type,
+        }));
+
+        self.base.charge_io_gas_for_write(key, op)
+    }
+}
+
+/// Renders a Move value as JSON.
+fn render_value(val: impl ValueView) -> JsonValue {
+    let mut renderer = JsonRenderer::default();
+    match val.visit(&mut renderer) {
+        Ok(()) => renderer
+            .result
+            .unwrap_or_else(|| JsonValue::String("<incomplete>".to_string())),
+        Err(err) => JsonValue::String(format!("<error: {}>", err)),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Container {
+    Struct,
+    Closure,
+    Vector,
+    Reference,
+}
+
+/// Builds a JSON value from the (depth-first) callbacks of a value visitor.
+#[derive(Default)]
+struct JsonRenderer {
+    // Open containers, along with their number of elements and the elements rendered so far.
+    stack: Vec<(Container, usize, Vec<JsonValue>)>,
+    result: Option<JsonValue>,
+}
+
+impl JsonRenderer {
+    fn open(&mut self, container: Container, len: usize) {
+        if len == 0 {
+            self.push(close(container, vec![]));
+        } else {
+            self.stack.push((container, len, vec![]));
+        }
+    }
+
+    fn push(&mut self, mut val: JsonValue) {
+        loop {
+            match self.stack.last_mut() {
+                None => {
+                    self.result = Some(val);
+                    return;
+                },
+                Some((_, len, elems)) => {
+                    elems.push(val);
+                    if elems.len() < *len {
+                        return;
+                    }
+                },
+            }
+            let (container, _, elems) = self.stack.pop().expect("container must exist");
+            val = close(container, elems);
+        }
+    }
+}
+
+fn close(container: Container, mut elems: Vec<JsonValue>) -> JsonValue {
+    match container {
+        Container::Struct => serde_json::json!({ "fields": elems }),
+        Container::Closure => serde_json::json!({ "captured": elems }),
+        Container::Vector => JsonValue::Array(elems),
+        Container::Reference => elems.pop().unwrap_or(JsonValue::Null),
+    }
+}
+
+impl ValueVisitor for JsonRenderer {
+    fn visit_delayed(&mut self, _depth: u64, id: DelayedFieldID) -> PartialVMResult<()> {
+        self.push(JsonValue::String(format!("<delayed {:?}>", id)));
+        Ok(())
+    }
+
+    fn visit_u8(&mut self, _depth: u64, val: u8) -> PartialVMResult<()> {
+        self.push(val.into());
+        Ok(())
+    }
+
+    fn visit_u16(&mut self, _depth: u64, val: u16) -> PartialVMResult<()> {
+        self.push(val.into());
+        Ok(())
+    }
+
+    fn visit_u32(&mut self, _depth: u64, val: u32) -> PartialVMResult<()> {
+        self.push(val.into());
+        Ok(())
+    }
+
+    fn visit_u64(&mut self, _depth: u64, val: u64) -> PartialVMResult<()> {
+        self.push(JsonValue::String(val.to_string()));
+        Ok(())
+    }
+
+    fn visit_u128(&mut self, _depth: u64, val: u128) -> PartialVMResult<()> {
+        self.push(JsonValue::String(val.to_string()));
+        Ok(())
+    }
+
+    fn visit_u256(&mut self, _depth: u64, val: U256) -> PartialVMResult<()> {
+        self.push(JsonValue::String(val.to_string()));
+        Ok(())
+    }
+
+    fn visit_bool(&mut self, _depth: u64, val: bool) -> PartialVMResult<()> {
+        self.push(val.into());
+        Ok(())
+    }
+
+    fn visit_address(&mut self, _depth: u64, val: AccountAddress) -> PartialVMResult<()> {
+        self.push(JsonValue::String(val.to_hex_literal()));
+        Ok(())
+    }
+
+    fn visit_struct(&mut self, _depth: u64, len: usize) -> PartialVMResult<bool> {
+        self.open(Container::Struct, len);
+        Ok(true)
+    }
+
+    fn visit_closure(&mut self, _depth: u64, len: usize) -> PartialVMResult<bool> {
+        self.open(Container::Closure, len);
+        Ok(true)
+    }
+
+    fn visit_vec(&mut self, _depth: u64, len: usize) -> PartialVMResult<bool> {
+        self.open(Container::Vector, len);
+        Ok(true)
+    }
+
+    fn visit_ref(&mut self, _depth: u64, _is_global: bool) -> PartialVMResult<bool> {
+        self.open(Container::Reference, 1);
+        Ok(true)
+    }
+
+    fn visit_vec_u8(&mut self, _depth: u64, vals: &[u8]) -> PartialVMResult<()> {
+        self.push(JsonValue::String(format!("0x{}", hex::encode(vals))));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumio_gas_meter::{StandardGasAlgebra, StandardGasMeter};
+    use lumio_gas_schedule::{InitialGasSchedule, VMGasParameters, LATEST_GAS_FEATURE_VERSION};
+    use lumio_vm_types::{
+        resolver::NoopBlockSynchronizationKillSwitch, storage::StorageGasParameters,
+    };
+    use move_core_types::language_storage::StructTag;
+    use move_vm_types::values::{Struct, Value};
+    use serde_json::json;
+
+    struct TestType(TypeTag);
+
+    impl TypeView for TestType {
+        fn to_type_tag(&self) -> TypeTag {
+            self.0.clone()
+        }
+    }
+
+    fn module_id(name: &str) -> ModuleId {
+        ModuleId::new(
+            AccountAddress::from_hex_literal("0xcafe").unwrap(),
+            Identifier::new(name).unwrap(),
+        )
+    }
+
+    fn resource_type() -> TestType {
+        TestType(TypeTag::Struct(Box::new(StructTag {
+            address: AccountAddress::from_hex_literal("0xcafe").unwrap(),
+            module: Identifier::new("m").unwrap(),
+            name: Identifier::new("R").unwrap(),
+            type_args: vec![],
+        })))
+    }
+
+    fn new_gas_meter(
+        kill_switch: &NoopBlockSynchronizationKillSwitch,
+    ) -> StandardGasMeter<StandardGasAlgebra<'_, NoopBlockSynchronizationKillSwitch>> {
+        StandardGasMeter::new(StandardGasAlgebra::new(
+            LATEST_GAS_FEATURE_VERSION,
+            VMGasParameters::initial(),
+            StorageGasParameters::unlimited(),
+            false,
+            1_000_000,
+            kill_switch,
+        ))
+    }
+
+    #[test]
+    fn test_render_value() {
+        assert_eq!(render_value(Value::u8(1)), json!(1));
+        assert_eq!(render_value(Value::u64(5)), json!("5"));
+        assert_eq!(render_value(Value::bool(true)), json!(true));
+        assert_eq!(
+            render_value(Value::address(AccountAddress::ONE)),
+            json!("0x1")
+        );
+        assert_eq!(render_value(Value::vector_u8(vec![1, 2])), json!("0x0102"));
+        assert_eq!(render_value(Value::vector_u64(vec![])), json!([]));
+        assert_eq!(
+            render_value(Value::struct_(Struct::pack(vec![
+                Value::u8(1),
+                Value::struct_(Struct::pack(vec![Value::vector_u64(vec![2, 3])])),
+                Value::bool(false),
+            ]))),
+            json!({ "fields": [1, { "fields": [["2", "3"]] }, false] })
+        );
+    }
+
+    #[test]
+    fn test_call_tree() {
+        let kill_switch = NoopBlockSynchronizationKillSwitch {};
+        let mut tracer = ExecutionTracer::new_function(
+            new_gas_meter(&kill_switch),
+            module_id("m"),
+            Identifier::new("entry").unwrap(),
+            vec![TypeTag::U64],
+            &[vec![5, 0]],
+        );
+
+        // entry -> outer -> (inner, native)
+        tracer
+            .charge_call(
+                &module_id("m"),
+                "outer",
+                vec![Value::u64(1)].into_iter(),
+                NumArgs::new(1),
+            )
+            .unwrap();
+        tracer
+            .charge_call(
+                &module_id("m"),
+                "inner",
+                vec![Value::u8(2)].into_iter(),
+                NumArgs::new(1),
+            )
+            .unwrap();
+        tracer
+            .charge_return(vec![Value::u8(4)].into_iter())
+            .unwrap();
+        tracer
+            .charge_call_generic(
+                &module_id("n"),
+                "native",
+                vec![TestType(TypeTag::Bool)].into_iter(),
+                vec![Value::bool(true)].into_iter(),
+                NumArgs::new(1),
+            )
+            .unwrap();
+        tracer
+            .charge_native_function(10.into(), Some(vec![Value::u64(3)].into_iter()))
+            .unwrap();
+        tracer
+            .charge_return(vec![Value::u64(9)].into_iter())
+            .unwrap();
+        // The return of the entry function keeps its frame
+        tracer
+            .charge_return(vec![Value::bool(true)].into_iter())
+            .unwrap();
+
+        let trace = tracer.finish();
+        let root = &trace.call;
+        assert_eq!(root.function, "0xcafe::m::entry");
+        assert_eq!(root.ty_args, vec!["u64".to_string()]);
+        assert_eq!(root.args, vec![json!("0x0500")]);
+        assert_eq!(root.return_values, vec![json!(true)]);
+        assert_eq!(root.calls.len(), 1);
+
+        let outer = &root.calls[0];
+        assert_eq!(outer.function, "0xcafe::m::outer");
+        assert_eq!(outer.args, vec![json!("1")]);
+        assert_eq!(outer.return_values, vec![json!("9")]);
+        assert_eq!(outer.calls[0].return_values, vec![json!(4)]);
+        assert_eq!(
+            outer
+                .calls
+                .iter()
+                .map(|call| call.function.as_str())
+                .collect::<Vec<_>>(),
+            vec!["0xcafe::m::inner", "0xcafe::n::native"]
+        );
+
+        let native = &outer.calls[1];
+        assert!(native.is_native);
+        assert_eq!(native.ty_args, vec!["bool".to_string()]);
+        assert_eq!(native.return_values, vec![json!("3")]);
+
+        // Gas of nested calls is included in the gas of their callers
+        assert!(native.gas_used >= 10);
+        assert!(outer.gas_used >= outer.calls.iter().map(|call| call.gas_used).sum::<u64>());
+        assert!(root.gas_used >= outer.gas_used);
+    }
+
+    #[test]
+    fn test_aborted_calls_are_kept() {
+        let kill_switch = NoopBlockSynchronizationKillSwitch {};
+        let mut tracer = ExecutionTracer::new_script(new_gas_meter(&kill_switch));
+        tracer
+            .charge_call(
+                &module_id("m"),
+                "aborts",
+                std::iter::empty::<Value>(),
+                NumArgs::new(0),
+            )
+            .unwrap();
+
+        // The frame of the aborted call is never returned from
+        let trace = tracer.finish();
+        assert_eq!(trace.call.function, "script");
+        assert_eq!(trace.call.calls.len(), 1);
+        assert_eq!(trace.call.calls[0].function, "0xcafe::m::aborts");
+    }
+
+    #[test]
+    fn test_resource_accesses() {
+        let kill_switch = NoopBlockSynchronizationKillSwitch {};
+        let mut tracer = ExecutionTracer::new_script(new_gas_meter(&kill_switch));
+        let value = || Value::struct_(Struct::pack(vec![Value::u64(7)]));
+
+        tracer
+            .charge_load_resource(
+                AccountAddress::ONE,
+                resource_type(),
+                Some(value()),
+                NumBytes::new(8),
+            )
+            .unwrap();
+        tracer.charge_exists(false, resource_type(), true).unwrap();
+        tracer
+            .charge_borrow_global(true, false, resource_type(), true)
+            .unwrap();
+        // Failed borrows and moves are not recorded
+        tracer
+            .charge_borrow_global(false, false, resource_type(), false)
+            .unwrap();
+        tracer
+            .charge_move_to(false, resource_type(), value(), false)
+            .unwrap();
+        tracer
+            .charge_move_from(false, resource_type(), None::<Value>)
+            .unwrap();
+        tracer
+            .charge_move_from(false, resource_type(), Some(value()))
+            .unwrap();
+
+        let resources = tracer.finish().call.resources;
+        assert_eq!(
+            resources
+                .iter()
+                .map(|access| access.kind)
+                .collect::<Vec<_>>(),
+            vec![
+                ResourceAccessKind::Load,
+                ResourceAccessKind::Exists,
+                ResourceAccessKind::BorrowMut,
+                ResourceAccessKind::MoveFrom,
+            ]
+        );
+        assert_eq!(resources[0].address, Some(AccountAddress::ONE));
+        assert_eq!(resources[0].ty, "0xcafe::m::R");
+        assert_eq!(resources[0].value, Some(json!({ "fields": ["7"] })));
+        assert_eq!(resources[1].value, Some(json!(true)));
+        assert_eq!(resources[3].value, Some(json!({ "fields": ["7"] })));
+    }
+
+    #[test]
+    fn test_writes() {
+        let kill_switch = NoopBlockSynchronizationKillSwitch {};
+        let mut tracer = ExecutionTracer::new_script(new_gas_meter(&kill_switch));
+        let value = || Value::struct_(Struct::pack(vec![Value::u64(7)]));
+
+        // script -> (reader, writer), where only the writer borrows the resource mutably
+        tracer
+            .charge_call(
+                &module_id("m"),
+                "reader",
+                std::iter::empty::<Value>(),
+                NumArgs::new(0),
+            )
+            .unwrap();
+        tracer
+            .charge_borrow_global(false, false, resource_type(), true)
+            .unwrap();
+        tracer.charge_return(std::iter::empty::<Value>()).unwrap();
+        tracer
+            .charge_call(
+                &module_id("m"),
+                "writer",
+                std::iter::empty::<Value>(),
+                NumArgs::new(0),
+            )
+            .unwrap();
+        tracer
+            .charge_borrow_global(true, false, resource_type(), true)
+            .unwrap();
+        tracer
+            .charge_global_write(AccountAddress::ONE, resource_type(), Some(value()))
+            .unwrap();
+        tracer.charge_return(std::iter::empty::<Value>()).unwrap();
+
+        // The write set is charged at the end of the transaction
+        let TypeTag::Struct(struct_tag) = resource_type().0 else {
+            unreachable!()
+        };
+        let resource_key = StateKey::resource(&AccountAddress::ONE, &struct_tag).unwrap();
+        tracer
+            .charge_io_gas_for_write(&resource_key, &WriteOpSize::Modification { write_len: 8 })
+            .unwrap();
+        let fee_key = StateKey::raw(b"fee");
+        tracer
+            .charge_io_gas_for_write(&fee_key, &WriteOpSize::Modification { write_len: 8 })
+            .unwrap();
+
+        let trace = tracer.finish();
+        assert_eq!(trace.write_set.len(), 2);
+        let root = &trace.call;
+        assert!(root.writes.is_empty());
+        assert!(root.calls[0].writes.is_empty());
+
+        let writer = &root.calls[1];
+        assert_eq!(writer.writes, vec![WriteTrace {
+            state_key: format!("{:?}", resource_key),
+            op: WriteOpType::Modification,
+        }]);
+        let access = &writer.resources[0];
+        assert_eq!(access.kind, ResourceAccessKind::BorrowMut);
+        assert_eq!(access.address, Some(AccountAddress::ONE));
+        assert_eq!(access.value, Some(json!({ "fields": ["7"] })));
+    }
+
+    #[test]
+    fn test_events() {
+        let kill_switch = NoopBlockSynchronizationKillSwitch {};
+        let mut tracer = ExecutionTracer::new_script(new_gas_meter(&kill_switch));
+        let event = Value::struct_(Struct::pack(vec![Value::u64(5)]));
+
+        tracer
+            .charge_call_generic(
+                &ModuleId::new(CORE_CODE_ADDRESS, Identifier::new("event").unwrap()),
+                "write_module_event_to_store",
+                vec![resource_type()].into_iter(),
+                vec![event].into_iter(),
+                NumArgs::new(1),
+            )
+            .unwrap();
+        tracer
+            .charge_native_function(0.into(), None::<std::iter::Empty<Value>>)
+            .unwrap();
+
+        // The event is attributed to the caller of the native
+        let call = tracer.finish().call;
+        assert_eq!(call.events, vec![EventTrace {
+            ty: "0xcafe::m::R".to_string(),
+            data: json!({ "fields": ["5"] }),
+        }]);
+        assert!(call.calls[0].is_native);
+    }
+}
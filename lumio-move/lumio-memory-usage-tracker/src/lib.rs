@@ -552,6 +552,24 @@ where
 
         self.base.charge_drop_frame(locals)
     }
+
+    #[inline]
+    fn charge_return(
+        &mut self,
+        ret_vals: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+    ) -> PartialVMResult<()> {
+        self.base.charge_return(ret_vals)
+    }
+
+    #[inline]
+    fn charge_global_write(
+        &mut self,
+        addr: AccountAddress,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.base.charge_global_write(addr, ty, val)
+    }
 }
 
 impl<G> LumioGasMeter for MemoryTrackedGasMeter<G>
@@ -151,6 +151,7 @@ pub enum FeatureFlag {
     DistributeTransactionFee,
     MonotonicallyIncreasingCounter,
     EnableCaptureOption,
+    EquivocationEvidence,
 }
 
 fn generate_features_blob(writer: &CodeWriter, data: &[u64]) {
@@ -401,6 +402,7 @@ impl From<FeatureFlag> for LumioFeatureFlag {
                 LumioFeatureFlag::MONOTONICALLY_INCREASING_COUNTER
             },
             FeatureFlag::EnableCaptureOption => LumioFeatureFlag::ENABLE_CAPTURE_OPTION,
+            FeatureFlag::EquivocationEvidence => LumioFeatureFlag::EQUIVOCATION_EVIDENCE,
         }
     }
 }
@@ -578,6 +580,7 @@ impl From<LumioFeatureFlag> for FeatureFlag {
                 FeatureFlag::MonotonicallyIncreasingCounter
             },
             LumioFeatureFlag::ENABLE_CAPTURE_OPTION => FeatureFlag::EnableCaptureOption,
+            LumioFeatureFlag::EQUIVOCATION_EVIDENCE => FeatureFlag::EquivocationEvidence,
         }
     }
 }
@@ -29,6 +29,18 @@ pub struct Config {
 
     /// The number of operations the session has performed.
     pub ops: u64,
+
+    /// The number of transactions the session has executed and kept.
+    #[serde(default)]
+    pub transactions: u64,
+
+    /// The gas unit price reported to clients estimating the gas price of their transactions.
+    #[serde(default = "default_gas_unit_price")]
+    pub gas_unit_price: u64,
+}
+
+fn default_gas_unit_price() -> u64 {
+    100
 }
 
 impl Config {
@@ -37,6 +49,8 @@ impl Config {
         Self {
             base: BaseState::Empty,
             ops: 0,
+            transactions: 0,
+            gas_unit_price: default_gas_unit_price(),
         }
     }
 
@@ -49,6 +63,8 @@ impl Config {
                 api_key,
             },
             ops: 0,
+            transactions: 0,
+            gas_unit_price: default_gas_unit_price(),
         }
     }
 
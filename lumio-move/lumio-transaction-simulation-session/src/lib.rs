@@ -3,8 +3,10 @@
 
 mod config;
 mod delta;
+mod server;
 mod session;
 mod state_store;
 mod txn_output;
 
+pub use server::serve;
 pub use session::Session;
@@ -0,0 +1,907 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serves a session over HTTP, through the routes and response types of the node REST API, so
+//! that existing clients and SDKs can run against it:
+//! - `GET /v1` returns the ledger info.
+//! - `GET /v1/accounts/:address` and `GET /v1/accounts/:address/resource/:resource_type` read
+//!   the session state (as JSON or BCS).
+//! - `GET /v1/estimate_gas_price` returns the gas unit price configured for the session (as JSON
+//!   or BCS).
+//! - `POST /v1/view` executes a view function.
+//! - `POST /v1/transactions` executes a transaction and applies it to the session state, after
+//!   which it can be fetched with `GET /v1/transactions/by_hash/:txn_hash` (as JSON or BCS).
+//! - `POST /v1/transactions/simulate` simulates a transaction, without applying it.
+//!
+//! Every transaction executed by the session advances the ledger version by one, starting from the
+//! version the session was forked from. The version is persisted along with the session, but the
+//! transactions themselves are only kept in memory, so they can't be fetched by hash after the
+//! server restarts.
+
+use crate::Session;
+use anyhow::Result;
+use lumio_api_types::{
+    mime_types, AccountData, Address, GasEstimation, IndexResponse, LedgerInfo, LumioError,
+    LumioErrorCode, SubmitTransactionRequest, Transaction, TransactionData, TransactionOnChainData,
+    UserTransaction, ViewFunction, ViewRequest, X_LUMIO_BLOCK_HEIGHT, X_LUMIO_CHAIN_ID,
+    X_LUMIO_EPOCH, X_LUMIO_LEDGER_OLDEST_VERSION, X_LUMIO_LEDGER_TIMESTAMP, X_LUMIO_LEDGER_VERSION,
+    X_LUMIO_OLDEST_BLOCK_HEIGHT,
+};
+use lumio_config::config::RoleType;
+use lumio_crypto::HashValue;
+use lumio_infallible::Mutex;
+use lumio_transaction_simulation::SimulationStateStore;
+use lumio_types::{
+    account_config::{AccountResource, CORE_CODE_ADDRESS},
+    on_chain_config::{ConfigurationResource, CurrentTimeMicroseconds},
+    transaction::{
+        ExecutionStatus, SignedTransaction, TransactionInfo, TransactionOutput, TransactionStatus,
+    },
+    vm_status::{StatusCode as VMStatusCode, VMStatus},
+};
+use move_core_types::{account_address::AccountAddress, language_storage::StructTag};
+use poem::{
+    error::ResponseError,
+    get, handler,
+    http::{header, HeaderMap, StatusCode},
+    listener::TcpListener,
+    post,
+    web::{Data, Path},
+    Body, EndpointExt, Response, ResponseBuilder, Route, Server,
+};
+use serde::Serialize;
+use std::{collections::HashMap, fmt, net::SocketAddr, str::FromStr, sync::Arc};
+
+/// Serves the session at the given address, until the server fails.
+pub async fn serve(session: Session, address: SocketAddr) -> Result<()> {
+    let state: SharedState = Arc::new(Mutex::new(ServerState::new(session)));
+    let api = Route::new()
+        .at("/", get(index))
+        .at("/accounts/:address", get(get_account))
+        .at(
+            "/accounts/:address/resource/:resource_type",
+            get(get_account_resource),
+        )
+        .at("/estimate_gas_price", get(estimate_gas_price))
+        .at("/view", post(view))
+        .at("/transactions", post(submit_transaction))
+        .at("/transactions/simulate", post(simulate_transaction))
+        .at(
+            "/transactions/by_hash/:txn_hash",
+            get(get_transaction_by_hash),
+        );
+    let app = Route::new().nest("/v1", api).data(state);
+
+    Server::new(TcpListener::bind(address))
+        .name("simulation-session")
+        .run(app)
+        .await?;
+    Ok(())
+}
+
+type SharedState = Arc<Mutex<ServerState>>;
+
+/// The session, along with the transactions submitted to the server
+struct ServerState {
+    session: Session,
+    /// The version of the state the session started from
+    base_version: u64,
+    /// The transactions submitted since the server started, by hash
+    transactions: HashMap<HashValue, SubmittedTransaction>,
+}
+
+/// A submitted transaction, both as stored on chain and as rendered by the node API
+struct SubmittedTransaction {
+    data: TransactionOnChainData,
+    rendered: Transaction,
+}
+
+impl ServerState {
+    fn new(session: Session) -> Self {
+        Self {
+            base_version: session.network_version().unwrap_or(0),
+            session,
+            transactions: HashMap::new(),
+        }
+    }
+
+    fn version(&self) -> u64 {
+        self.base_version + self.session.num_transactions()
+    }
+
+    fn ledger_info(&self) -> Result<LedgerInfo> {
+        let state_store = self.session.state_store();
+        let chain_id = state_store.get_chain_id()?;
+        let epoch = state_store
+            .get_resource::<ConfigurationResource>(CORE_CODE_ADDRESS)?
+            .map_or(0, |configuration| configuration.epoch());
+        let timestamp_usecs = state_store
+            .get_on_chain_config::<CurrentTimeMicroseconds>()?
+            .microseconds;
+        let version = self.version();
+        // Every transaction is treated as its own block
+        Ok(LedgerInfo::new_ledger_info(
+            &chain_id,
+            epoch,
+            version,
+            0,
+            0,
+            version,
+            timestamp_usecs,
+        ))
+    }
+
+    fn get_account(
+        &self,
+        address: AccountAddress,
+        accept_bcs: bool,
+    ) -> Result<Response, ServerError> {
+        let ledger_info = self.ledger_info()?;
+        let account = self
+            .session
+            .state_store()
+            .get_resource::<AccountResource>(address)?
+            .ok_or_else(|| {
+                ServerError::not_found(
+                    format!(
+                        "Account not found by Address({}) and Ledger version({})",
+                        address,
+                        ledger_info.version()
+                    ),
+                    LumioErrorCode::AccountNotFound,
+                )
+            })?;
+
+        if accept_bcs {
+            bcs_response(&account, &ledger_info)
+        } else {
+            json_response(StatusCode::OK, &AccountData::from(account), &ledger_info)
+        }
+    }
+
+    fn get_account_resource(
+        &self,
+        address: AccountAddress,
+        resource_type: StructTag,
+        accept_bcs: bool,
+    ) -> Result<Response, ServerError> {
+        let ledger_info = self.ledger_info()?;
+        let converter = self.session.converter();
+        let bytes = converter
+            .find_resource(self.session.state_store(), address.into(), &resource_type)?
+            .ok_or_else(|| {
+                ServerError::not_found(
+                    format!(
+                        "Resource not found by Address({}), Struct tag({}) and Ledger version({})",
+                        address,
+                        resource_type.to_canonical_string(),
+                        ledger_info.version()
+                    ),
+                    LumioErrorCode::ResourceNotFound,
+                )
+            })?;
+
+        if accept_bcs {
+            Ok(with_ledger_info_headers(Response::builder(), &ledger_info)
+                .content_type(mime_types::BCS)
+                .body(bytes.to_vec()))
+        } else {
+            let resource = converter.try_into_resource(&resource_type, &bytes)?;
+            json_response(StatusCode::OK, &resource, &ledger_info)
+        }
+    }
+
+    fn estimate_gas_price(&self, accept_bcs: bool) -> Result<Response, ServerError> {
+        let ledger_info = self.ledger_info()?;
+        // The session has no mempool to derive an estimate from, so every estimate is the
+        // configured gas unit price
+        let gas_unit_price = self.session.gas_unit_price();
+        let gas_estimation = GasEstimation {
+            deprioritized_gas_estimate: Some(gas_unit_price),
+            gas_estimate: gas_unit_price,
+            prioritized_gas_estimate: Some(gas_unit_price),
+        };
+
+        if accept_bcs {
+            bcs_response(&gas_estimation, &ledger_info)
+        } else {
+            json_response(StatusCode::OK, &gas_estimation, &ledger_info)
+        }
+    }
+
+    fn view(&mut self, content_bcs: bool, body: &[u8]) -> Result<Response, ServerError> {
+        let view_function = if content_bcs {
+            bcs::from_bytes::<ViewFunction>(body).map_err(|err| {
+                ServerError::bad_request(
+                    format!("Failed to deserialize input into ViewFunction: {}", err),
+                    LumioErrorCode::InvalidInput,
+                )
+            })?
+        } else {
+            let request = serde_json::from_slice::<ViewRequest>(body)
+                .map_err(|err| ServerError::bad_request(err, LumioErrorCode::InvalidInput))?;
+            self.session
+                .converter()
+                .convert_view_function(request)
+                .map_err(|err| ServerError::bad_request(err, LumioErrorCode::InvalidInput))?
+        };
+
+        let values = self
+            .session
+            .execute_view_function(
+                view_function.module,
+                view_function.function,
+                view_function.ty_args,
+                view_function.args,
+            )
+            .map_err(|err| ServerError::bad_request(err, LumioErrorCode::InvalidInput))?;
+        json_response(StatusCode::OK, &values, &self.ledger_info()?)
+    }
+
+    fn submit_transaction(
+        &mut self,
+        content_bcs: bool,
+        body: &[u8],
+    ) -> Result<Response, ServerError> {
+        let txn = self.parse_signed_transaction(content_bcs, body)?;
+        if let Err(err) = txn.verify_signature() {
+            return Err(ServerError::vm_error(
+                format!("Invalid transaction signature: {}", err),
+                VMStatusCode::INVALID_SIGNATURE,
+            ));
+        }
+
+        let (vm_status, output) = self.session.execute_transaction(txn.clone())?;
+        // Discarded transactions would have been rejected by the mempool of a node
+        if !matches!(output.status(), TransactionStatus::Keep(_)) {
+            return Err(ServerError::vm_error(
+                format!("Invalid transaction: {:?}", vm_status),
+                vm_status.status_code(),
+            ));
+        }
+
+        let version = self.version();
+        let info = transaction_info(&txn, &output);
+        let rendered = self.render_user_transaction(&txn, &output, &info, version)?;
+        let data = TransactionOnChainData {
+            version,
+            transaction: lumio_types::transaction::Transaction::UserTransaction(txn.clone()),
+            info,
+            events: output.events().to_vec(),
+            accumulator_root_hash: HashValue::zero(),
+            changes: output.write_set().clone(),
+        };
+        self.transactions
+            .insert(txn.committed_hash(), SubmittedTransaction {
+                data,
+                rendered: Transaction::UserTransaction(rendered),
+            });
+
+        let pending_transaction = self
+            .session
+            .converter()
+            .try_into_pending_transaction_poem(txn)?;
+        json_response(
+            StatusCode::ACCEPTED,
+            &pending_transaction,
+            &self.ledger_info()?,
+        )
+    }
+
+    fn simulate_transaction(
+        &self,
+        content_bcs: bool,
+        body: &[u8],
+    ) -> Result<Response, ServerError> {
+        let txn = self.parse_signed_transaction(content_bcs, body)?;
+        if txn.verify_signature().is_ok() {
+            return Err(ServerError::bad_request(
+                "Simulated transactions must not have a valid signature",
+                LumioErrorCode::InvalidInput,
+            ));
+        }
+
+        let ledger_info = self.ledger_info()?;
+        let (vm_status, output) = self.session.simulate_transaction(&txn);
+        let info = transaction_info(&txn, &output);
+        let mut transaction =
+            self.render_user_transaction(&txn, &output, &info, ledger_info.version())?;
+        if let VMStatus::Error {
+            message: Some(message),
+            ..
+        }
+        | VMStatus::ExecutionFailure {
+            message: Some(message),
+            ..
+        } = &vm_status
+        {
+            transaction.info.vm_status +=
+                format!("\nExecution failed with message: {}", message).as_str();
+        }
+        json_response(StatusCode::OK, &vec![transaction], &ledger_info)
+    }
+
+    fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        accept_bcs: bool,
+    ) -> Result<Response, ServerError> {
+        let ledger_info = self.ledger_info()?;
+        let transaction = self.transactions.get(&hash).ok_or_else(|| {
+            ServerError::not_found(
+                format!("Transaction not found by Transaction hash({})", hash),
+                LumioErrorCode::TransactionNotFound,
+            )
+        })?;
+
+        if accept_bcs {
+            bcs_response(
+                &TransactionData::OnChain(transaction.data.clone()),
+                &ledger_info,
+            )
+        } else {
+            json_response(StatusCode::OK, &transaction.rendered, &ledger_info)
+        }
+    }
+
+    fn parse_signed_transaction(
+        &self,
+        content_bcs: bool,
+        body: &[u8],
+    ) -> Result<SignedTransaction, ServerError> {
+        if content_bcs {
+            return bcs::from_bytes(body).map_err(|err| {
+                ServerError::bad_request(
+                    format!(
+                        "Failed to deserialize input into SignedTransaction: {}",
+                        err
+                    ),
+                    LumioErrorCode::InvalidInput,
+                )
+            });
+        }
+
+        let request = serde_json::from_slice::<SubmitTransactionRequest>(body)
+            .map_err(|err| ServerError::bad_request(err, LumioErrorCode::InvalidInput))?;
+        let chain_id = self.session.state_store().get_chain_id()?;
+        self.session
+            .converter()
+            .try_into_signed_transaction_poem(request, chain_id)
+            .map_err(|err| ServerError::bad_request(err, LumioErrorCode::InvalidInput))
+    }
+
+    /// Renders an executed (or simulated) transaction the way the node API does
+    fn render_user_transaction(
+        &self,
+        txn: &SignedTransaction,
+        output: &TransactionOutput,
+        info: &TransactionInfo,
+        version: u64,
+    ) -> Result<UserTransaction> {
+        let converter = self.session.converter();
+        let timestamp_usecs = self.ledger_info()?.timestamp();
+
+        Ok(UserTransaction {
+            info: converter.into_transaction_info(
+                version,
+                info,
+                HashValue::zero(),
+                output.write_set().clone(),
+                None,
+            ),
+            request: (
+                txn,
+                converter.try_into_transaction_payload(txn.payload().clone())?,
+            )
+                .into(),
+            events: converter.try_into_events(output.events())?,
+            timestamp: timestamp_usecs.into(),
+        })
+    }
+}
+
+/// Returns the info of an executed (or simulated) transaction. The state hashes aren't computed
+/// by the session, so they're left as zeros.
+fn transaction_info(txn: &SignedTransaction, output: &TransactionOutput) -> TransactionInfo {
+    let status = ExecutionStatus::conmbine_vm_status_for_simulation(
+        output.auxiliary_data(),
+        output.status().clone(),
+    );
+    TransactionInfo::new(
+        txn.committed_hash(),
+        HashValue::zero(),
+        HashValue::zero(),
+        None,
+        output.gas_used(),
+        status,
+        None,
+    )
+}
+
+#[handler]
+async fn index(state: Data<&SharedState>) -> poem::Result<Response> {
+    Ok(with_state(state.0, |state| {
+        let ledger_info = state.ledger_info()?;
+        let index = IndexResponse::new(ledger_info.clone(), RoleType::FullNode, None);
+        json_response(StatusCode::OK, &index, &ledger_info)
+    })
+    .await?)
+}
+
+#[handler]
+async fn get_account(
+    state: Data<&SharedState>,
+    Path(address): Path<String>,
+    headers: &HeaderMap,
+) -> poem::Result<Response> {
+    let address = parse_address(&address)?;
+    let accept_bcs = accepts_bcs(headers);
+    Ok(with_state(state.0, move |state| state.get_account(address, accept_bcs)).await?)
+}
+
+#[handler]
+async fn get_account_resource(
+    state: Data<&SharedState>,
+    Path((address, resource_type)): Path<(String, String)>,
+    headers: &HeaderMap,
+) -> poem::Result<Response> {
+    let address = parse_address(&address)?;
+    let resource_type = StructTag::from_str(&resource_type)
+        .map_err(|err| ServerError::bad_request(err, LumioErrorCode::InvalidInput))?;
+    let accept_bcs = accepts_bcs(headers);
+    Ok(with_state(state.0, move |state| {
+        state.get_account_resource(address, resource_type, accept_bcs)
+    })
+    .await?)
+}
+
+#[handler]
+async fn estimate_gas_price(
+    state: Data<&SharedState>,
+    headers: &HeaderMap,
+) -> poem::Result<Response> {
+    let accept_bcs = accepts_bcs(headers);
+    Ok(with_state(state.0, move |state| state.estimate_gas_price(accept_bcs)).await?)
+}
+
+#[handler]
+async fn view(
+    state: Data<&SharedState>,
+    headers: &HeaderMap,
+    body: Body,
+) -> poem::Result<Response> {
+    let content_bcs = has_content_type(headers, mime_types::BCS_VIEW_FUNCTION);
+    let body = body.into_vec().await?;
+    Ok(with_state(state.0, move |state| state.view(content_bcs, &body)).await?)
+}
+
+#[handler]
+async fn submit_transaction(
+    state: Data<&SharedState>,
+    headers: &HeaderMap,
+    body: Body,
+) -> poem::Result<Response> {
+    let content_bcs = has_content_type(headers, mime_types::BCS_SIGNED_TRANSACTION);
+    let body = body.into_vec().await?;
+    Ok(with_state(state.0, move |state| {
+        state.submit_transaction(content_bcs, &body)
+    })
+    .await?)
+}
+
+#[handler]
+async fn simulate_transaction(
+    state: Data<&SharedState>,
+    headers: &HeaderMap,
+    body: Body,
+) -> poem::Result<Response> {
+    let content_bcs = has_content_type(headers, mime_types::BCS_SIGNED_TRANSACTION);
+    let body = body.into_vec().await?;
+    Ok(with_state(state.0, move |state| {
+        state.simulate_transaction(content_bcs, &body)
+    })
+    .await?)
+}
+
+#[handler]
+async fn get_transaction_by_hash(
+    state: Data<&SharedState>,
+    Path(txn_hash): Path<String>,
+    headers: &HeaderMap,
+) -> poem::Result<Response> {
+    let hash: HashValue = lumio_api_types::HashValue::from_str(&txn_hash)
+        .map_err(|err| ServerError::bad_request(err, LumioErrorCode::InvalidInput))?
+        .into();
+    let accept_bcs = accepts_bcs(headers);
+    Ok(with_state(state.0, move |state| {
+        state.get_transaction_by_hash(hash, accept_bcs)
+    })
+    .await?)
+}
+
+/// Runs the given function on the server state. The session may fetch state from a remote
+/// network with blocking calls, so this runs on a blocking thread.
+async fn with_state<T: Send + 'static>(
+    state: &SharedState,
+    function: impl FnOnce(&mut ServerState) -> Result<T, ServerError> + Send + 'static,
+) -> Result<T, ServerError> {
+    let state = state.clone();
+    tokio::task::spawn_blocking(move || function(&mut state.lock()))
+        .await
+        .map_err(ServerError::internal)?
+}
+
+fn parse_address(address: &str) -> Result<AccountAddress, ServerError> {
+    Address::from_str(address)
+        .map(Into::into)
+        .map_err(|err| ServerError::bad_request(err, LumioErrorCode::InvalidInput))
+}
+
+fn accepts_bcs(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept == mime_types::BCS)
+}
+
+fn has_content_type(headers: &HeaderMap, content_type: &str) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value == content_type)
+}
+
+/// Adds the ledger info headers the REST client expects on every successful response
+fn with_ledger_info_headers(builder: ResponseBuilder, ledger_info: &LedgerInfo) -> ResponseBuilder {
+    builder
+        .header(X_LUMIO_CHAIN_ID, ledger_info.chain_id.to_string())
+        .header(X_LUMIO_EPOCH, ledger_info.epoch().to_string())
+        .header(X_LUMIO_LEDGER_VERSION, ledger_info.version().to_string())
+        .header(
+            X_LUMIO_LEDGER_OLDEST_VERSION,
+            ledger_info.oldest_version().to_string(),
+        )
+        .header(
+            X_LUMIO_LEDGER_TIMESTAMP,
+            ledger_info.timestamp().to_string(),
+        )
+        .header(X_LUMIO_BLOCK_HEIGHT, ledger_info.block_height.to_string())
+        .header(
+            X_LUMIO_OLDEST_BLOCK_HEIGHT,
+            ledger_info.oldest_block_height.to_string(),
+        )
+}
+
+fn json_response<T: Serialize>(
+    status: StatusCode,
+    value: &T,
+    ledger_info: &LedgerInfo,
+) -> Result<Response, ServerError> {
+    let body = serde_json::to_vec(value).map_err(ServerError::internal)?;
+    Ok(with_ledger_info_headers(Response::builder(), ledger_info)
+        .status(status)
+        .content_type(mime_types::JSON)
+        .body(body))
+}
+
+fn bcs_response<T: Serialize>(
+    value: &T,
+    ledger_info: &LedgerInfo,
+) -> Result<Response, ServerError> {
+    let body = bcs::to_bytes(value).map_err(ServerError::internal)?;
+    Ok(with_ledger_info_headers(Response::builder(), ledger_info)
+        .content_type(mime_types::BCS)
+        .body(body))
+}
+
+/// An error response, in the format of the node API
+#[derive(Debug)]
+struct ServerError {
+    status: StatusCode,
+    error: LumioError,
+}
+
+impl ServerError {
+    fn bad_request(error: impl fmt::Display, error_code: LumioErrorCode) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            error: LumioError::new_with_error_code(error, error_code),
+        }
+    }
+
+    fn not_found(error: impl fmt::Display, error_code: LumioErrorCode) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            error: LumioError::new_with_error_code(error, error_code),
+        }
+    }
+
+    fn vm_error(error: impl fmt::Display, vm_status_code: VMStatusCode) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            error: LumioError::new_with_vm_status(error, LumioErrorCode::VmError, vm_status_code),
+        }
+    }
+
+    fn internal(error: impl fmt::Display) -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            error: LumioError::new_with_error_code(error, LumioErrorCode::InternalError),
+        }
+    }
+}
+
+impl From<anyhow::Error> for ServerError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::internal(error)
+    }
+}
+
+impl fmt::Display for ServerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl std::error::Error for ServerError {}
+
+impl ResponseError for ServerError {
+    fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    fn as_response(&self) -> Response {
+        Response::builder()
+            .status(self.status)
+            .content_type(mime_types::JSON)
+            .body(serde_json::to_vec(&self.error).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumio_transaction_simulation::Account;
+    use lumio_types::transaction::{
+        authenticator::{AccountAuthenticator, TransactionAuthenticator},
+        EntryFunction,
+    };
+    use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+
+    /// Returns the server state of a new session, along with an account funded in it
+    fn new_state_with_account(session_path: &std::path::Path) -> Result<(ServerState, Account)> {
+        let state = ServerState::new(Session::init(session_path)?);
+        let account = Account::new();
+        state
+            .session
+            .state_store()
+            .store_and_fund_account(account.clone(), 1_000_000_000, 0)?;
+        Ok((state, account))
+    }
+
+    /// Returns a transaction transferring LUM from the given account
+    fn transfer(
+        state: &ServerState,
+        sender: &Account,
+        receiver: AccountAddress,
+        amount: u64,
+    ) -> Result<SignedTransaction> {
+        let transfer = EntryFunction::new(
+            ModuleId::new(CORE_CODE_ADDRESS, Identifier::new("lumio_account")?),
+            Identifier::new("transfer")?,
+            vec![],
+            vec![bcs::to_bytes(&receiver)?, bcs::to_bytes(&amount)?],
+        );
+        Ok(sender
+            .transaction()
+            .entry_function(transfer)
+            .sequence_number(0)
+            .gas_unit_price(100)
+            .chain_id(state.session.state_store().get_chain_id()?)
+            .sign())
+    }
+
+    async fn read_body(response: Response) -> Result<Vec<u8>> {
+        Ok(response.into_body().into_vec().await?)
+    }
+
+    #[test]
+    fn test_read_session_state() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let state = ServerState::new(Session::init(temp_dir.path())?);
+
+        let ledger_info = state.ledger_info()?;
+        assert_eq!(ledger_info.version(), 0);
+
+        let response = state.get_account(CORE_CODE_ADDRESS, false).unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[X_LUMIO_CHAIN_ID],
+            ledger_info.chain_id.to_string()
+        );
+
+        let error = state
+            .get_account(AccountAddress::random(), false)
+            .unwrap_err();
+        assert_eq!(error.status, StatusCode::NOT_FOUND);
+        assert_eq!(error.error.error_code, LumioErrorCode::AccountNotFound);
+
+        let error = state
+            .get_transaction_by_hash(HashValue::zero(), false)
+            .unwrap_err();
+        assert_eq!(error.error.error_code, LumioErrorCode::TransactionNotFound);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_snapshot() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let (mut state, sender) = new_state_with_account(temp_dir.path())?;
+        state.session.create_snapshot("before_transfer")?;
+
+        let txn = transfer(&state, &sender, AccountAddress::random(), 100)?;
+        state
+            .submit_transaction(true, &bcs::to_bytes(&txn)?)
+            .unwrap();
+        assert_eq!(state.ledger_info()?.version(), 1);
+
+        // Restoring the snapshot also rolls back the ledger version
+        state.session.restore_snapshot("before_transfer")?;
+        assert_eq!(state.ledger_info()?.version(), 0);
+        let state = ServerState::new(Session::load(temp_dir.path())?);
+        assert_eq!(state.ledger_info()?.version(), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_estimate_gas_price() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let state = ServerState::new(Session::init(temp_dir.path())?);
+        let expected = GasEstimation {
+            deprioritized_gas_estimate: Some(100),
+            gas_estimate: 100,
+            prioritized_gas_estimate: Some(100),
+        };
+
+        let response = state.estimate_gas_price(false).unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let gas_estimation: GasEstimation = serde_json::from_slice(&read_body(response).await?)?;
+        assert_eq!(gas_estimation, expected);
+
+        let response = state.estimate_gas_price(true).unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], mime_types::BCS);
+        let gas_estimation: GasEstimation = bcs::from_bytes(&read_body(response).await?)?;
+        assert_eq!(gas_estimation, expected);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_submit_transaction() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let (mut state, sender) = new_state_with_account(temp_dir.path())?;
+        let receiver = AccountAddress::random();
+        let txn = transfer(&state, &sender, receiver, 100)?;
+
+        let response = state
+            .submit_transaction(true, &bcs::to_bytes(&txn)?)
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert_eq!(response.headers()[X_LUMIO_LEDGER_VERSION], "1");
+
+        // The transaction is applied to the session state
+        let state_store = state.session.state_store();
+        assert_eq!(state_store.get_lum_balance(receiver)?, 100);
+        let account = state_store
+            .get_resource::<AccountResource>(*sender.address())?
+            .unwrap();
+        assert_eq!(account.sequence_number(), 1);
+
+        // And can be fetched by hash, in both formats
+        let response = state
+            .get_transaction_by_hash(txn.committed_hash(), false)
+            .unwrap();
+        let transaction: Transaction = serde_json::from_slice(&read_body(response).await?)?;
+        match transaction {
+            Transaction::UserTransaction(transaction) => {
+                assert_eq!(transaction.info.version.0, 1);
+                assert!(transaction.info.success);
+            },
+            transaction => panic!("Unexpected transaction: {:?}", transaction),
+        }
+
+        let response = state
+            .get_transaction_by_hash(txn.committed_hash(), true)
+            .unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], mime_types::BCS);
+        match bcs::from_bytes(&read_body(response).await?)? {
+            TransactionData::OnChain(data) => {
+                assert_eq!(data.version, 1);
+                assert_eq!(
+                    data.transaction,
+                    lumio_types::transaction::Transaction::UserTransaction(txn)
+                );
+            },
+            TransactionData::Pending(_) => panic!("Expected an on chain transaction"),
+        }
+
+        // The version is persisted along with the session
+        drop(state);
+        let state = ServerState::new(Session::load(temp_dir.path())?);
+        assert_eq!(state.ledger_info()?.version(), 1);
+        assert_eq!(state.session.state_store().get_lum_balance(receiver)?, 100);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_simulate_transaction() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let (state, sender) = new_state_with_account(temp_dir.path())?;
+        let receiver = AccountAddress::random();
+        let txn = transfer(&state, &sender, receiver, 100)?;
+
+        // Transactions with a valid signature are rejected
+        let error = state
+            .simulate_transaction(true, &bcs::to_bytes(&txn)?)
+            .unwrap_err();
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+
+        let unsigned_txn = SignedTransaction::new_signed_transaction(
+            txn.into_raw_transaction(),
+            TransactionAuthenticator::single_sender(AccountAuthenticator::NoAccountAuthenticator),
+        );
+        let response = state
+            .simulate_transaction(true, &bcs::to_bytes(&unsigned_txn)?)
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let transactions: Vec<UserTransaction> =
+            serde_json::from_slice(&read_body(response).await?)?;
+        assert_eq!(transactions.len(), 1);
+        assert!(
+            transactions[0].info.success,
+            "{}",
+            transactions[0].info.vm_status
+        );
+
+        // The simulation is not applied to the session state
+        assert_eq!(state.ledger_info()?.version(), 0);
+        assert_eq!(state.session.state_store().get_lum_balance(receiver)?, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_view() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let mut state = ServerState::new(Session::init(temp_dir.path())?);
+        let chain_id = state.session.state_store().get_chain_id()?;
+
+        let request = serde_json::json!({
+            "function": "0x1::chain_id::get",
+            "type_arguments": [],
+            "arguments": [],
+        });
+        let response = state.view(false, &serde_json::to_vec(&request)?).unwrap();
+        let values: Vec<serde_json::Value> = serde_json::from_slice(&read_body(response).await?)?;
+        assert_eq!(values, vec![serde_json::json!(chain_id.id())]);
+
+        let view_function = ViewFunction {
+            module: ModuleId::new(CORE_CODE_ADDRESS, Identifier::new("chain_id")?),
+            function: Identifier::new("get")?,
+            ty_args: vec![],
+            args: vec![],
+        };
+        let response = state.view(true, &bcs::to_bytes(&view_function)?).unwrap();
+        let values: Vec<serde_json::Value> = serde_json::from_slice(&read_body(response).await?)?;
+        assert_eq!(values, vec![serde_json::json!(chain_id.id())]);
+
+        // Invalid requests are rejected
+        let error = state.view(true, b"invalid").unwrap_err();
+        assert_eq!(error.error.error_code, LumioErrorCode::InvalidInput);
+
+        Ok(())
+    }
+}
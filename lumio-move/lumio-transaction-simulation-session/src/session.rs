@@ -6,33 +6,41 @@ use crate::{
     delta::{load_delta, save_delta},
     txn_output::{save_events, save_write_set},
 };
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
+use lumio_api_types::{AsConverter, MoveConverter};
 use lumio_resource_viewer::LumioValueAnnotator;
 use lumio_rest_client::{LumioBaseUrl, Client};
+use lumio_storage_interface::DbReader;
 use lumio_transaction_simulation::{
     DeltaStateStore, EitherStateView, EmptyStateView, SimulationStateStore, GENESIS_CHANGE_SET_HEAD,
 };
 use lumio_types::{
     account_address::AccountAddress,
     fee_statement::FeeStatement,
+    on_chain_config::CurrentTimeMicroseconds,
+    randomness::PerBlockRandomness,
+    state_store::{state_key::StateKey, state_value::StateValue, StateView, TStateView},
     transaction::{
-        AuxiliaryInfo, PersistedAuxiliaryInfo, SignedTransaction, TransactionExecutable,
-        TransactionOutput, TransactionPayload, TransactionPayloadInner, TransactionStatus,
+        authenticator::{AccountAuthenticator, TransactionAuthenticator},
+        AuxiliaryInfo, PersistedAuxiliaryInfo, RawTransaction, SignedTransaction,
+        TransactionExecutable, TransactionOutput, TransactionPayload, TransactionPayloadInner,
+        TransactionStatus,
     },
     vm_status::VMStatus,
 };
 use lumio_validator_interface::{DebuggerStateView, RestDebuggerInterface};
-use lumio_vm::{data_cache::AsMoveResolver, LumioVM};
+use lumio_vm::{data_cache::AsMoveResolver, LumioSimulationVM, LumioVM};
 use lumio_vm_environment::environment::LumioEnvironment;
 use lumio_vm_logging::log_schema::AdapterLogSchema;
 use lumio_vm_types::{module_and_script_storage::AsLumioCodeStorage, resolver::StateStorageView};
+use move_binary_format::CompiledModule;
 use move_core_types::{
     identifier::Identifier,
-    language_storage::{ModuleId, TypeTag},
+    language_storage::{ModuleId, StructTag, TypeTag},
 };
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -40,6 +48,9 @@ use url::Url;
 
 type SessionStateStore = DeltaStateStore<EitherStateView<EmptyStateView, DebuggerStateView>>;
 
+/// The directory of the session where snapshots are stored.
+const SNAPSHOTS_DIR: &str = "snapshots";
+
 /// Formats a module ID for display by adjusting the address for better readability.
 fn format_module_id(module_id: &ModuleId) -> String {
     let address = module_id.address();
@@ -85,8 +96,39 @@ enum Summary {
         result: ViewResult,
         gas_used: u64,
     },
+    CreateSnapshot {
+        name: String,
+    },
+    RestoreSnapshot {
+        name: String,
+    },
+    SetResource {
+        account: AccountAddress,
+        resource_type: String,
+        /// `None` if the resource was deleted
+        value: Option<serde_json::Value>,
+    },
+    SetModule {
+        module: String,
+    },
+    DeleteModule {
+        module: String,
+    },
+    SetTimestamp {
+        before: u64,
+        after: u64,
+    },
+    SetRandomnessSeed {
+        seed: String,
+    },
 }
 
+/// The API converter only reads from the database to render the transactions committed to it,
+/// which a session doesn't have.
+struct NoDbReader;
+
+impl DbReader for NoDbReader {}
+
 /// A session for simulating transactions, with data being persisted to a directory, allowing the session
 /// to be restored or continued in the future.
 ///
@@ -103,6 +145,28 @@ impl Session {
         &self.state_store
     }
 
+    /// Returns the version of the remote network state the session was forked from, or `None`
+    /// if the session started from an empty state.
+    pub fn network_version(&self) -> Option<u64> {
+        match &self.config.base {
+            BaseState::Empty => None,
+            BaseState::Remote {
+                network_version, ..
+            } => Some(*network_version),
+        }
+    }
+
+    /// Returns the number of transactions the session has executed and kept, which is persisted
+    /// along with the session.
+    pub fn num_transactions(&self) -> u64 {
+        self.config.transactions
+    }
+
+    /// Returns the gas unit price configured for the session.
+    pub fn gas_unit_price(&self) -> u64 {
+        self.config.gas_unit_price
+    }
+
     /// Creates a new session using an empty base state, then applies the Lumio genesis
     /// change set on top of it.
     ///
@@ -239,12 +303,231 @@ impl Session {
             before,
             after,
         };
+        self.record_operation("fund (fungible)", &summary)
+    }
+
+    /// Saves the current session state as a named snapshot, which can be restored later on.
+    /// An existing snapshot with the same name is overwritten.
+    pub fn create_snapshot(&mut self, name: &str) -> Result<()> {
+        let snapshot_path = self.snapshot_path(name)?;
+        std::fs::create_dir_all(self.path.join(SNAPSHOTS_DIR))?;
+        save_delta(&snapshot_path, &self.state_store.delta())?;
+        self.config
+            .save_to_file(&snapshot_path.with_extension("config"))?;
+
+        let summary = Summary::CreateSnapshot {
+            name: name.to_string(),
+        };
+        self.record_operation(&format!("snapshot {}", name), &summary)
+    }
+
+    /// Rolls the session state back to a named snapshot.
+    ///
+    /// The operations performed since the snapshot was taken are not removed from the session
+    /// directory, so they can still be inspected.
+    pub fn restore_snapshot(&mut self, name: &str) -> Result<()> {
+        let snapshot_path = self.snapshot_path(name)?;
+        if !snapshot_path.exists() {
+            bail!("Snapshot {} does not exist", name);
+        }
+        let snapshot_config = Config::load_from_file(&snapshot_path.with_extension("config"))?;
+        self.state_store.set_delta(load_delta(&snapshot_path)?);
+        // The operations keep counting up, as they are not removed from the session directory
+        self.config.transactions = snapshot_config.transactions;
+
+        let summary = Summary::RestoreSnapshot {
+            name: name.to_string(),
+        };
+        self.record_operation(&format!("restore {}", name), &summary)
+    }
+
+    /// Returns the names of the snapshots of the session, in alphabetical order.
+    pub fn snapshots(&self) -> Result<Vec<String>> {
+        let snapshots_path = self.path.join(SNAPSHOTS_DIR);
+        if !snapshots_path.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut names = vec![];
+        for entry in std::fs::read_dir(snapshots_path)? {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                if let Some(name) = path.file_stem().and_then(|name| name.to_str()) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    fn snapshot_path(&self, name: &str) -> Result<PathBuf> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            bail!(
+                "Invalid snapshot name {:?} -- only ASCII letters, digits, '-' and '_' are allowed.",
+                name
+            );
+        }
+        Ok(self.path.join(SNAPSHOTS_DIR).join(format!("{}.json", name)))
+    }
+
+    /// Sets a resource at an address, overriding its current value, or deletes it if no value is
+    /// given.
+    ///
+    /// The value uses the JSON format of the node API. Members of resource groups are updated
+    /// within their group.
+    pub fn set_resource(
+        &mut self,
+        account: AccountAddress,
+        resource_type: StructTag,
+        value: Option<serde_json::Value>,
+    ) -> Result<()> {
+        let bytes = match &value {
+            Some(value) => {
+                let type_tag = TypeTag::Struct(Box::new(resource_type.clone()));
+                let bytes = self
+                    .converter()
+                    .try_into_vm_value(&type_tag, value.clone())?
+                    .simple_serialize()
+                    .ok_or_else(|| anyhow!("Failed to serialize {}", resource_type))?;
+                Some(bytes)
+            },
+            None => None,
+        };
+
+        let group_tag =
+            LumioValueAnnotator::new(&self.state_store).view_resource_group_member(&resource_type);
+        match group_tag {
+            Some(group_tag) => {
+                let state_key = StateKey::resource_group(&account, &group_tag);
+                let mut group: BTreeMap<StructTag, Vec<u8>> =
+                    match self.state_store.get_state_value_bytes(&state_key)? {
+                        Some(group_bytes) => bcs::from_bytes(&group_bytes)?,
+                        None => BTreeMap::new(),
+                    };
+                match bytes {
+                    Some(bytes) => group.insert(resource_type.clone(), bytes),
+                    None => group.remove(&resource_type),
+                };
+
+                if group.is_empty() {
+                    self.state_store.remove_state_value(&state_key)?;
+                } else {
+                    self.state_store.set_state_value(
+                        state_key,
+                        StateValue::new_legacy(bcs::to_bytes(&group)?.into()),
+                    )?;
+                }
+            },
+            None => {
+                let state_key = StateKey::resource(&account, &resource_type)?;
+                match bytes {
+                    Some(bytes) => self
+                        .state_store
+                        .set_state_value(state_key, StateValue::new_legacy(bytes.into()))?,
+                    None => self.state_store.remove_state_value(&state_key)?,
+                }
+            },
+        }
+
+        let name = format!(
+            "{} resource {}::{}",
+            if value.is_some() { "set" } else { "delete" },
+            format_module_id(&resource_type.module_id()),
+            resource_type.name
+        );
+        let summary = Summary::SetResource {
+            account,
+            resource_type: resource_type.to_canonical_string(),
+            value,
+        };
+        self.record_operation(&name, &summary)
+    }
+
+    /// Publishes a compiled module at its address, overriding any module with the same ID.
+    ///
+    /// Unlike publishing a package, this doesn't check compatibility with the existing module,
+    /// nor update the package metadata.
+    pub fn set_module(&mut self, module_bytes: Vec<u8>) -> Result<ModuleId> {
+        let module = CompiledModule::deserialize(&module_bytes)
+            .map_err(|err| anyhow!("Failed to deserialize module: {:?}", err))?;
+        let module_id = module.self_id();
+        self.state_store.add_module_blob(&module_id, module_bytes)?;
+
+        let summary = Summary::SetModule {
+            module: module_id.short_str_lossless(),
+        };
+        self.record_operation(
+            &format!("set module {}", format_module_id(&module_id)),
+            &summary,
+        )?;
+        Ok(module_id)
+    }
+
+    /// Deletes a module, as if it had never been published.
+    pub fn delete_module(&mut self, module_id: &ModuleId) -> Result<()> {
+        self.state_store
+            .remove_state_value(&StateKey::module_id(module_id))?;
+
+        let summary = Summary::DeleteModule {
+            module: module_id.short_str_lossless(),
+        };
+        self.record_operation(
+            &format!("delete module {}", format_module_id(module_id)),
+            &summary,
+        )
+    }
+
+    /// Sets the on-chain timestamp, which subsequent transactions observe as the time of their
+    /// block.
+    pub fn set_timestamp(&mut self, timestamp_usecs: u64) -> Result<()> {
+        let before = self
+            .state_store
+            .get_on_chain_config::<CurrentTimeMicroseconds>()?
+            .microseconds;
+        self.state_store
+            .set_on_chain_config(&CurrentTimeMicroseconds {
+                microseconds: timestamp_usecs,
+            })?;
+
+        let summary = Summary::SetTimestamp {
+            before,
+            after: timestamp_usecs,
+        };
+        self.record_operation("set timestamp", &summary)
+    }
+
+    /// Sets the seed of the on-chain randomness, which subsequent transactions derive their
+    /// randomness from.
+    pub fn set_randomness_seed(&mut self, seed: Vec<u8>) -> Result<()> {
+        let mut randomness = self
+            .state_store
+            .get_on_chain_config::<PerBlockRandomness>()
+            .unwrap_or_default();
+        randomness.seed = Some(seed.clone());
+        self.state_store.set_on_chain_config(&randomness)?;
+
+        let summary = Summary::SetRandomnessSeed {
+            seed: hex::encode(seed),
+        };
+        self.record_operation("set randomness seed", &summary)
+    }
+
+    /// Saves the summary of an operation to a dedicated directory, then persists the session.
+    fn record_operation(&mut self, name: &str, summary: &Summary) -> Result<()> {
         let summary_path = self
             .path
-            .join(format!("[{}] fund (fungible)", self.config.ops))
+            .join(format!("[{}] {}", self.config.ops, name))
             .join("summary.json");
         std::fs::create_dir_all(summary_path.parent().unwrap())?;
-        std::fs::write(summary_path, serde_json::to_string_pretty(&summary)?)?;
+        std::fs::write(summary_path, serde_json::to_string_pretty(summary)?)?;
 
         self.config.ops += 1;
 
@@ -254,6 +537,12 @@ impl Session {
         Ok(())
     }
 
+    /// Returns a converter between the JSON format of the node API and Move values, reading from
+    /// the session state.
+    pub(crate) fn converter(&self) -> MoveConverter<impl StateView + '_> {
+        self.state_store.as_converter(Arc::new(NoDbReader), None)
+    }
+
     /// Executes a transaction and updates the session state.
     ///
     /// After execution, selected parts of the transaction output get saved to a dedicated directory for inspection:
@@ -284,6 +573,52 @@ impl Session {
         );
         let txn_output = vm_output.try_materialize_into_transaction_output(&resolver)?;
 
+        self.apply_transaction_output(&txn, &txn_output, "execute")?;
+
+        Ok((vm_status, txn_output))
+    }
+
+    /// Executes a transaction as its sender, without a signature, and updates the session state.
+    ///
+    /// The sender's authentication key is not checked either, so this can be used to act as any
+    /// account (e.g., a governance or framework account of a forked network). This relies on the
+    /// simulation support of the VM, so the `TRANSACTION_SIMULATION_ENHANCEMENT` feature must be
+    /// enabled.
+    pub fn execute_impersonated_transaction(
+        &mut self,
+        raw_txn: RawTransaction,
+    ) -> Result<(VMStatus, TransactionOutput)> {
+        if !self
+            .state_store
+            .get_features()?
+            .is_transaction_simulation_enhancement_enabled()
+        {
+            bail!("Impersonation requires the TRANSACTION_SIMULATION_ENHANCEMENT feature.");
+        }
+
+        let txn = SignedTransaction::new_signed_transaction(
+            raw_txn,
+            TransactionAuthenticator::single_sender(AccountAuthenticator::NoAccountAuthenticator),
+        );
+        let (vm_status, txn_output) =
+            LumioSimulationVM::create_vm_and_simulate_signed_transaction(&txn, &self.state_store);
+
+        self.apply_transaction_output(&txn, &txn_output, "execute (impersonated)")?;
+
+        Ok((vm_status, txn_output))
+    }
+
+    /// Applies the output of a transaction to the session state.
+    ///
+    /// Selected parts of the output get saved to a dedicated directory for inspection:
+    /// - Write set changes
+    /// - Emitted events
+    fn apply_transaction_output(
+        &mut self,
+        txn: &SignedTransaction,
+        txn_output: &TransactionOutput,
+        operation: &str,
+    ) -> Result<()> {
         self.state_store.apply_write_set(txn_output.write_set())?;
 
         fn name_from_executable(executable: &TransactionExecutable) -> String {
@@ -321,7 +656,7 @@ impl Session {
 
         let output_path = self
             .path
-            .join(format!("[{}] execute {}", self.config.ops, name));
+            .join(format!("[{}] {} {}", self.config.ops, operation, name));
         std::fs::create_dir_all(&output_path)?;
 
         let summary = Summary::ExecuteTransaction {
@@ -340,10 +675,21 @@ impl Session {
         save_write_set(&self.state_store, &write_set_path, txn_output.write_set())?;
 
         self.config.ops += 1;
+        if let TransactionStatus::Keep(_) = txn_output.status() {
+            self.config.transactions += 1;
+        }
         self.config.save_to_file(&self.path.join("config.json"))?;
         save_delta(&self.path.join("delta.json"), &self.state_store.delta())?;
 
-        Ok((vm_status, txn_output))
+        Ok(())
+    }
+
+    /// Simulates a transaction on top of the session state, without applying its output.
+    ///
+    /// Like the simulation endpoint of the node API, this expects the transaction not to carry a
+    /// valid signature. Simulations are not recorded as session operations.
+    pub fn simulate_transaction(&self, txn: &SignedTransaction) -> (VMStatus, TransactionOutput) {
+        LumioSimulationVM::create_vm_and_simulate_signed_transaction(txn, &self.state_store)
     }
 
     /// Executes a view function and returns the output values.
@@ -455,3 +801,162 @@ async fn test_init_then_load_session_remote() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_snapshot_and_restore() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let session_path = temp_dir.path();
+    let account = AccountAddress::random();
+
+    let mut session = Session::init(session_path)?;
+    session.create_snapshot("before_funding")?;
+    session.fund_account(account, 100)?;
+    assert_eq!(session.state_store.get_lum_balance(account)?, 100);
+
+    session.restore_snapshot("before_funding")?;
+    assert_eq!(session.state_store.get_lum_balance(account)?, 0);
+    assert_eq!(session.snapshots()?, vec!["before_funding".to_string()]);
+
+    assert!(session.restore_snapshot("missing").is_err());
+    assert!(session.create_snapshot("../outside").is_err());
+
+    // The restored state is persisted
+    let session_loaded = Session::load(session_path)?;
+    assert_eq!(
+        session.state_store.delta(),
+        session_loaded.state_store.delta()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_state_overrides() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let mut session = Session::init(temp_dir.path())?;
+
+    let chain_id_tag: StructTag = "0x1::chain_id::ChainId".parse()?;
+    session.set_resource(
+        AccountAddress::ONE,
+        chain_id_tag.clone(),
+        Some(serde_json::json!({ "id": 42 })),
+    )?;
+    assert_eq!(session.state_store.get_chain_id()?.id(), 42);
+    session.set_resource(AccountAddress::ONE, chain_id_tag, None)?;
+    assert!(session.state_store.get_chain_id().is_err());
+
+    session.set_timestamp(1_000_000)?;
+    assert_eq!(
+        session
+            .state_store
+            .get_on_chain_config::<CurrentTimeMicroseconds>()?
+            .microseconds,
+        1_000_000
+    );
+
+    session.set_randomness_seed(vec![7; 32])?;
+    assert_eq!(
+        session
+            .state_store
+            .get_on_chain_config::<PerBlockRandomness>()?
+            .seed,
+        Some(vec![7; 32])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_module_overrides() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let mut session = Session::init(temp_dir.path())?;
+
+    let module_id = ModuleId::new(AccountAddress::ONE, Identifier::new("chain_id")?);
+    let module_bytes = session
+        .state_store
+        .get_state_value_bytes(&StateKey::module_id(&module_id))?
+        .unwrap();
+
+    session.delete_module(&module_id)?;
+    assert!(session.state_store.get_module(&module_id)?.is_none());
+    assert!(session
+        .execute_view_function(module_id.clone(), Identifier::new("get")?, vec![], vec![])
+        .is_err());
+
+    assert_eq!(session.set_module(module_bytes.to_vec())?, module_id);
+    assert!(session.state_store.get_module(&module_id)?.is_some());
+    assert_eq!(
+        session.execute_view_function(module_id, Identifier::new("get")?, vec![], vec![])?,
+        vec![serde_json::json!(session.state_store.get_chain_id()?.id())]
+    );
+
+    assert!(session.set_module(vec![1, 2, 3]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_execute_impersonated_transaction() -> Result<()> {
+    use lumio_transaction_simulation::Account;
+    use lumio_types::{
+        on_chain_config::FeatureFlag,
+        transaction::{EntryFunction, ExecutionStatus},
+        vm_status::StatusCode,
+    };
+
+    let temp_dir = tempfile::tempdir()?;
+    let mut session = Session::init(temp_dir.path())?;
+    let sender = *session
+        .state_store
+        .store_and_fund_account(Account::new(), 1_000_000_000, 0)?
+        .address();
+    let receiver = AccountAddress::random();
+    let chain_id = session.state_store.get_chain_id()?;
+
+    // The session time is far from the wall-clock time
+    let now_secs = 1_000;
+    session.set_timestamp(now_secs * 1_000_000)?;
+
+    let transfer = |sequence_number, expiration_timestamp_secs| -> Result<RawTransaction> {
+        Ok(RawTransaction::new(
+            sender,
+            sequence_number,
+            TransactionPayload::EntryFunction(EntryFunction::new(
+                ModuleId::new(AccountAddress::ONE, Identifier::new("lumio_account")?),
+                Identifier::new("transfer")?,
+                vec![],
+                vec![bcs::to_bytes(&receiver)?, bcs::to_bytes(&100u64)?],
+            )),
+            2_000_000,
+            100,
+            expiration_timestamp_secs,
+            chain_id,
+        ))
+    };
+
+    // Transactions expire relative to the session time
+    let (_, output) = session.execute_impersonated_transaction(transfer(0, now_secs - 1)?)?;
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Discard(StatusCode::TRANSACTION_EXPIRED)
+    );
+
+    // The sender doesn't sign the transaction
+    let (_, output) = session.execute_impersonated_transaction(transfer(0, now_secs + 30)?)?;
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(ExecutionStatus::Success)
+    );
+    assert_eq!(session.state_store.get_lum_balance(receiver)?, 100);
+    assert_eq!(session.num_transactions(), 1);
+
+    // Impersonation requires the simulation enhancement feature
+    let mut features = session.state_store.get_features()?;
+    features.disable(FeatureFlag::TRANSACTION_SIMULATION_ENHANCEMENT);
+    session.state_store.set_features(features)?;
+    assert!(session
+        .execute_impersonated_transaction(transfer(1, now_secs + 30)?)
+        .is_err());
+
+    Ok(())
+}
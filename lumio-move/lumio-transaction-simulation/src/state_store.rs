@@ -541,6 +541,12 @@ impl<V> DeltaStateStore<V> {
     pub fn delta(&self) -> HashMap<StateKey, Option<StateValue>> {
         self.states.read().clone()
     }
+
+    /// Replaces the state changes stacked on top of the base state view (e.g., to roll back to
+    /// an earlier [`Self::delta`]).
+    pub fn set_delta(&self, delta: HashMap<StateKey, Option<StateValue>>) {
+        *self.states.write() = delta;
+    }
 }
 
 impl<V> Clone for DeltaStateStore<V>
@@ -14,7 +14,7 @@ use lumio_types::{
         state_key::StateKey, state_slot::StateSlot, state_storage_usage::StateStorageUsage,
         state_value::StateValue, StateViewId, StateViewResult, TStateView,
     },
-    transaction::{Transaction, TransactionInfo, Version},
+    transaction::{PersistedAuxiliaryInfo, Transaction, TransactionInfo, Version},
 };
 use lru::LruCache;
 use move_core_types::language_storage::ModuleId;
@@ -49,6 +49,14 @@ pub trait LumioValidatorInterface: Sync {
         limit: u64,
     ) -> Result<(Vec<Transaction>, Vec<TransactionInfo>)>;
 
+    /// Returns the persisted auxiliary info of the committed transactions in the given range, or
+    /// `PersistedAuxiliaryInfo::None` where it isn't available.
+    async fn get_persisted_auxiliary_infos(
+        &self,
+        start: Version,
+        limit: u64,
+    ) -> Result<Vec<PersistedAuxiliaryInfo>>;
+
     async fn get_and_filter_committed_transactions(
         &self,
         start: Version,
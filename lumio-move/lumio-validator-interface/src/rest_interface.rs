@@ -16,8 +16,8 @@ use lumio_types::{
     account_address::AccountAddress,
     state_store::{state_key::StateKey, state_value::StateValue},
     transaction::{
-        EntryFunction, ExecutionStatus::MiscellaneousError, Transaction, TransactionExecutableRef,
-        TransactionInfo, TransactionPayload, Version,
+        EntryFunction, ExecutionStatus::MiscellaneousError, PersistedAuxiliaryInfo, Transaction,
+        TransactionExecutableRef, TransactionInfo, TransactionPayload, Version,
     },
 };
 use async_recursion::async_recursion;
@@ -245,6 +245,15 @@ impl LumioValidatorInterface for RestDebuggerInterface {
         Ok((txns, txn_infos))
     }
 
+    async fn get_persisted_auxiliary_infos(
+        &self,
+        _start: Version,
+        limit: u64,
+    ) -> Result<Vec<PersistedAuxiliaryInfo>> {
+        // The REST API doesn't serve the auxiliary info of transactions.
+        Ok(vec![PersistedAuxiliaryInfo::None; limit as usize])
+    }
+
     async fn get_and_filter_committed_transactions(
         &self,
         start: Version,
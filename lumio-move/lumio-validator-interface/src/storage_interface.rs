@@ -14,7 +14,7 @@ use lumio_storage_interface::DbReader;
 use lumio_types::{
     account_address::AccountAddress,
     state_store::{state_key::StateKey, state_value::StateValue},
-    transaction::{Transaction, TransactionInfo, Version},
+    transaction::{PersistedAuxiliaryInfo, Transaction, TransactionInfo, Version},
 };
 use move_core_types::language_storage::ModuleId;
 use std::{collections::HashMap, path::Path, sync::Arc};
@@ -68,6 +68,17 @@ impl LumioValidatorInterface for DBDebuggerInterface {
         Ok((txns, txn_infos))
     }
 
+    async fn get_persisted_auxiliary_infos(
+        &self,
+        start: Version,
+        limit: u64,
+    ) -> Result<Vec<PersistedAuxiliaryInfo>> {
+        self.0
+            .get_persisted_auxiliary_info_iterator(start, limit as usize)?
+            .map(|res| res.map_err(Into::into))
+            .collect()
+    }
+
     async fn get_and_filter_committed_transactions(
         &self,
         _start: Version,
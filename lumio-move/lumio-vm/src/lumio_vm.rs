@@ -31,6 +31,7 @@ use crate::{
 };
 use lumio_block_executor::{
     code_cache_global_manager::LumioModuleCacheManager,
+    conflict_analytics::set_conflict_analytics_enabled_once,
     txn_commit_hook::NoOpTransactionCommitHook,
     txn_provider::{default::DefaultTxnProvider, TxnProvider},
 };
@@ -333,7 +334,7 @@ impl LumioVM {
     }
 
     #[inline(always)]
-    fn features(&self) -> &Features {
+    pub(crate) fn features(&self) -> &Features {
         self.move_vm.env.features()
     }
 
@@ -459,6 +460,11 @@ impl LumioVM {
         }
     }
 
+    /// Enables per-block conflict analytics for parallel execution when invoked the first time.
+    pub fn set_blockstm_conflict_analytics_once(enable: bool) {
+        set_conflict_analytics_enabled_once(enable);
+    }
+
     /// Returns the internal gas schedule if it has been loaded, or an error if it hasn't.
     #[cfg(any(test, feature = "testing"))]
     pub fn gas_params_for_test(&self) -> Result<&LumioGasParameters, VMStatus> {
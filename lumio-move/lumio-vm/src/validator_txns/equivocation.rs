@@ -0,0 +1,165 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    lumio_vm::get_system_transaction_output,
+    errors::expect_only_successful_execution,
+    move_vm_ext::{LumioMoveResolver, SessionId},
+    system_module_names::{EQUIVOCATION_MODULE, RECORD_EQUIVOCATION},
+    validator_txns::equivocation::{
+        ExecutionFailure::{Expected, Unexpected},
+        ExpectedFailure::*,
+    },
+    LumioVM,
+};
+use lumio_logger::debug;
+use lumio_types::{
+    equivocation::EquivocationEvidence,
+    on_chain_config::{ConfigurationResource, OnChainConfig, ValidatorSet},
+    transaction::TransactionStatus,
+    validator_verifier::ValidatorVerifier,
+};
+use lumio_vm_logging::log_schema::AdapterLogSchema;
+use lumio_vm_types::{
+    module_and_script_storage::module_storage::LumioModuleStorage, output::VMOutput,
+};
+use move_core_types::{
+    account_address::AccountAddress,
+    value::{serialize_values, MoveValue},
+    vm_status::{AbortLocation, StatusCode, VMStatus},
+};
+use move_vm_runtime::{
+    module_traversal::{TraversalContext, TraversalStorage},
+    ModuleStorage,
+};
+use move_vm_types::gas::UnmeteredGasMeter;
+
+#[derive(Debug)]
+enum ExpectedFailure {
+    // Move equivalent: `errors::invalid_argument(*)`
+    EpochNotCurrent = 0x10201,
+    EvidenceVerificationFailed = 0x10202,
+
+    // Move equivalent: `errors::invalid_state(*)`
+    MissingResourceValidatorSet = 0x30201,
+    MissingResourceConfiguration = 0x30202,
+    MissingModuleEquivocation = 0x30203,
+    FeatureDisabled = 0x30204,
+}
+
+enum ExecutionFailure {
+    Expected(ExpectedFailure),
+    Unexpected(VMStatus),
+}
+
+impl LumioVM {
+    pub(crate) fn process_equivocation_evidence(
+        &self,
+        resolver: &impl LumioMoveResolver,
+        module_storage: &impl LumioModuleStorage,
+        log_context: &AdapterLogSchema,
+        session_id: SessionId,
+        evidence: EquivocationEvidence,
+    ) -> Result<(VMStatus, VMOutput), VMStatus> {
+        match self.process_equivocation_evidence_inner(
+            resolver,
+            module_storage,
+            log_context,
+            session_id,
+            evidence,
+        ) {
+            Ok((vm_status, vm_output)) => Ok((vm_status, vm_output)),
+            Err(Expected(failure)) => {
+                // Pretend we are inside Move, and expected failures are like Move aborts.
+                debug!(
+                    "Processing equivocation evidence expected failure: {:?}",
+                    failure
+                );
+                Ok((
+                    VMStatus::MoveAbort(AbortLocation::Script, failure as u64),
+                    VMOutput::empty_with_status(TransactionStatus::Discard(StatusCode::ABORTED)),
+                ))
+            },
+            Err(Unexpected(vm_status)) => Err(vm_status),
+        }
+    }
+
+    fn process_equivocation_evidence_inner(
+        &self,
+        resolver: &impl LumioMoveResolver,
+        module_storage: &impl LumioModuleStorage,
+        log_context: &AdapterLogSchema,
+        session_id: SessionId,
+        evidence: EquivocationEvidence,
+    ) -> Result<(VMStatus, VMOutput), ExecutionFailure> {
+        if !self.features().is_equivocation_evidence_enabled() {
+            return Err(Expected(FeatureDisabled));
+        }
+
+        // Load resources.
+        let validator_set =
+            ValidatorSet::fetch_config(resolver).ok_or(Expected(MissingResourceValidatorSet))?;
+        let config_resource = ConfigurationResource::fetch_config(resolver)
+            .ok_or(Expected(MissingResourceConfiguration))?;
+
+        // Evidence can only be checked against the validator set of its own epoch.
+        if evidence.epoch != config_resource.epoch() {
+            return Err(Expected(EpochNotCurrent));
+        }
+
+        let verifier = ValidatorVerifier::from(&validator_set);
+        evidence
+            .verify(&verifier)
+            .map_err(|_| Expected(EvidenceVerificationFailed))?;
+
+        // Networks whose framework predates the module drop the evidence.
+        let module_exists = module_storage
+            .unmetered_check_module_exists(
+                EQUIVOCATION_MODULE.address(),
+                EQUIVOCATION_MODULE.name(),
+            )
+            .map_err(|e| Unexpected(e.into_vm_status()))?;
+        if !module_exists {
+            return Err(Expected(MissingModuleEquivocation));
+        }
+
+        // All verification passed. Record the equivocation.
+        let mut gas_meter = UnmeteredGasMeter;
+        let mut session = self.new_session(resolver, session_id, None);
+        let args = vec![
+            MoveValue::Signer(AccountAddress::ONE),
+            MoveValue::Address(evidence.author),
+            MoveValue::U64(evidence.epoch),
+            MoveValue::U64(evidence.round),
+            MoveValue::U8(evidence.kind() as u8),
+        ];
+
+        let traversal_storage = TraversalStorage::new();
+        session
+            .execute_function_bypass_visibility(
+                &EQUIVOCATION_MODULE,
+                RECORD_EQUIVOCATION,
+                vec![],
+                serialize_values(&args),
+                &mut gas_meter,
+                &mut TraversalContext::new(&traversal_storage),
+                module_storage,
+            )
+            .map_err(|e| {
+                expect_only_successful_execution(e, RECORD_EQUIVOCATION.as_str(), log_context)
+            })
+            .map_err(|r| Unexpected(r.unwrap_err()))?;
+
+        let output = get_system_transaction_output(
+            session,
+            module_storage,
+            &self
+                .storage_gas_params(log_context)
+                .map_err(Unexpected)?
+                .change_set_configs,
+        )
+        .map_err(Unexpected)?;
+
+        Ok((VMStatus::Executed, output))
+    }
+}
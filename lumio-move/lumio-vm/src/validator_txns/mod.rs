@@ -32,9 +32,18 @@ impl LumioVM {
                 session_id,
                 jwk_update,
             ),
+            ValidatorTransaction::EquivocationEvidence(evidence) => self
+                .process_equivocation_evidence(
+                    resolver,
+                    module_storage,
+                    log_context,
+                    session_id,
+                    evidence,
+                ),
         }
     }
 }
 
 mod dkg;
+mod equivocation;
 mod jwk;
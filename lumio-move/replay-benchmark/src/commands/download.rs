@@ -6,7 +6,7 @@ use crate::{
     workload::TransactionBlock,
 };
 use anyhow::{anyhow, bail};
-use lumio_types::transaction::{Transaction, Version};
+use lumio_types::transaction::{PersistedAuxiliaryInfo, Transaction, Version};
 use clap::Parser;
 use std::path::PathBuf;
 use tokio::fs;
@@ -53,6 +53,16 @@ impl DownloadCommand {
         let (mut txns, _) = debugger
             .get_committed_transactions(self.begin_version, limit)
             .await?;
+        let mut persisted_auxiliary_infos = debugger
+            .get_persisted_auxiliary_infos(self.begin_version, limit)
+            .await?;
+        if persisted_auxiliary_infos.len() != txns.len() {
+            bail!(
+                "Downloaded {} transactions, but {} persisted auxiliary infos",
+                txns.len(),
+                persisted_auxiliary_infos.len()
+            );
+        }
 
         if !txns[0].is_block_start() {
             bail!(
@@ -67,8 +77,9 @@ impl DownloadCommand {
                 self.end_version - 1
             );
         }
+        persisted_auxiliary_infos.pop();
 
-        let txn_blocks = partition(self.begin_version, txns);
+        let txn_blocks = partition(self.begin_version, txns, persisted_auxiliary_infos);
         println!(
             "Downloaded {} blocks with {} transactions in total: versions [{}, {})",
             txn_blocks.len(),
@@ -84,28 +95,37 @@ impl DownloadCommand {
     }
 }
 
-/// Partitions a sequence of transactions into blocks.
-fn partition(begin_version: Version, txns: Vec<Transaction>) -> Vec<TransactionBlock> {
+/// Partitions a sequence of transactions (and their persisted auxiliary infos) into blocks.
+fn partition(
+    begin_version: Version,
+    txns: Vec<Transaction>,
+    persisted_auxiliary_infos: Vec<PersistedAuxiliaryInfo>,
+) -> Vec<TransactionBlock> {
+    assert_eq!(txns.len(), persisted_auxiliary_infos.len());
     let mut begin_versions_and_blocks = Vec::with_capacity(txns.len());
 
     let mut curr_begin = begin_version;
     let mut curr_block = Vec::with_capacity(txns.len());
+    let mut curr_infos = Vec::with_capacity(txns.len());
 
-    for txn in txns {
+    for (txn, info) in txns.into_iter().zip(persisted_auxiliary_infos) {
         if txn.is_block_start() && !curr_block.is_empty() {
             let block_size = curr_block.len();
             begin_versions_and_blocks.push(TransactionBlock {
                 begin_version: curr_begin,
                 transactions: std::mem::take(&mut curr_block),
+                persisted_auxiliary_infos: std::mem::take(&mut curr_infos),
             });
             curr_begin += block_size as Version;
         }
         curr_block.push(txn);
+        curr_infos.push(info);
     }
     if !curr_block.is_empty() {
         begin_versions_and_blocks.push(TransactionBlock {
             begin_version: curr_begin,
             transactions: curr_block,
+            persisted_auxiliary_infos: curr_infos,
         });
     }
 
@@ -175,10 +195,16 @@ mod tests {
         Transaction::BlockMetadata(block_metadata)
     }
 
+    fn infos(n: u32) -> Vec<PersistedAuxiliaryInfo> {
+        (0..n)
+            .map(|transaction_index| PersistedAuxiliaryInfo::V1 { transaction_index })
+            .collect()
+    }
+
     #[test]
     fn test_block_partition_1() {
         let txns = vec![block_metadata(), block_metadata(), block_metadata()];
-        let blocks = partition(1, txns);
+        let blocks = partition(1, txns, infos(3));
         assert_eq!(blocks.len(), 3);
 
         assert_eq!(blocks[0].begin_version, 1);
@@ -203,7 +229,7 @@ mod tests {
             user_transaction(),
         ];
 
-        let blocks = partition(0, txns);
+        let blocks = partition(0, txns, infos(7));
         assert_eq!(blocks.len(), 2);
 
         assert_eq!(blocks[0].begin_version, 0);
@@ -211,12 +237,26 @@ mod tests {
 
         assert_eq!(blocks[1].begin_version, 3);
         assert_eq!(blocks[1].transactions.len(), 4);
+        assert_eq!(blocks[1].persisted_auxiliary_infos, vec![
+            PersistedAuxiliaryInfo::V1 {
+                transaction_index: 3
+            },
+            PersistedAuxiliaryInfo::V1 {
+                transaction_index: 4
+            },
+            PersistedAuxiliaryInfo::V1 {
+                transaction_index: 5
+            },
+            PersistedAuxiliaryInfo::V1 {
+                transaction_index: 6
+            },
+        ]);
     }
 
     #[test]
     fn test_block_partition_3() {
         let txns = vec![user_transaction(), user_transaction(), user_transaction()];
-        let blocks = partition(10, txns);
+        let blocks = partition(10, txns, infos(3));
         assert_eq!(blocks.len(), 1);
 
         assert_eq!(blocks[0].begin_version, 10);
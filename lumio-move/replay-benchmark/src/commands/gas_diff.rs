@@ -0,0 +1,174 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    commands::init_logger_and_metrics,
+    execution::execute_workload,
+    state_view::{ReadSet, ReadSetWithWrites},
+    workload::{TransactionBlock, Workload},
+};
+use anyhow::{anyhow, bail};
+use lumio_gas_profiling::{GasDiffReport, TransactionGasDiff, TransactionGasLog};
+use lumio_logger::Level;
+use lumio_move_debugger::lumio_debugger::profile_user_transaction;
+use lumio_types::transaction::{AuxiliaryInfo, Transaction, TransactionOutput, Version};
+use lumio_vm::lumio_vm::LumioVMBlockExecutor;
+use clap::Parser;
+use move_core_types::vm_status::VMStatus;
+use std::path::PathBuf;
+use tokio::fs;
+
+#[derive(Parser)]
+#[command(
+    about = "Profiles gas usage of transactions executed on different states, and generates a \
+             per-transaction and per-function report of the differences"
+)]
+pub struct GasDiffCommand {
+    #[clap(long, default_value_t = Level::Error)]
+    log_level: Level,
+
+    #[clap(long, help = "File where the transactions are saved")]
+    transactions_file: String,
+
+    #[clap(long, help = "File where the input states are saved")]
+    inputs_file: String,
+
+    #[clap(long, help = "File where the other input states are saved")]
+    other_inputs_file: String,
+
+    #[clap(
+        long,
+        default_value = "gas-diff",
+        help = "Directory where the HTML and JSON reports will be saved"
+    )]
+    report_dir: String,
+}
+
+impl GasDiffCommand {
+    pub async fn diff_gas(self) -> anyhow::Result<()> {
+        init_logger_and_metrics(self.log_level);
+
+        let txn_blocks_bytes = fs::read(PathBuf::from(&self.transactions_file)).await?;
+        let txn_blocks: Vec<TransactionBlock> = bcs::from_bytes(&txn_blocks_bytes)
+            .map_err(|err| anyhow!("Error when deserializing blocks of transactions: {:?}", err))?;
+        if txn_blocks.is_empty() {
+            bail!("There must be at least one transaction to execute");
+        }
+
+        let inputs_read_set_bytes = fs::read(PathBuf::from(&self.inputs_file)).await?;
+        let inputs_read_set: Vec<ReadSet> = bcs::from_bytes(&inputs_read_set_bytes)
+            .map_err(|err| anyhow!("Error when deserializing inputs: {:?}", err))?;
+
+        let other_inputs_read_set_bytes = fs::read(PathBuf::from(&self.other_inputs_file)).await?;
+        let other_inputs_read_set: Vec<ReadSet> = bcs::from_bytes(&other_inputs_read_set_bytes)
+            .map_err(|err| anyhow!("Error when deserializing other inputs: {:?}", err))?;
+
+        // Ensure the number of blocks matches.
+        if txn_blocks.len() != inputs_read_set.len()
+            || inputs_read_set.len() != other_inputs_read_set.len()
+        {
+            bail!(
+                "Number of blocks of transactions does not match the number of pre-block states: \
+                there {} blocks, but {} and {} input states",
+                txn_blocks.len(),
+                inputs_read_set.len(),
+                other_inputs_read_set.len()
+            );
+        }
+
+        let mut diffs = vec![];
+        for ((txn_block, input), other_input) in txn_blocks
+            .into_iter()
+            .zip(&inputs_read_set)
+            .zip(&other_inputs_read_set)
+        {
+            let begin_version = txn_block.begin_version;
+            let transactions = txn_block.transactions.clone();
+            let auxiliary_infos = txn_block.auxiliary_infos();
+            let workload = Workload::from(txn_block);
+
+            let logs = profile_block(
+                begin_version,
+                &transactions,
+                &auxiliary_infos,
+                &workload,
+                input,
+            )?;
+            let other_logs = profile_block(
+                begin_version,
+                &transactions,
+                &auxiliary_infos,
+                &workload,
+                other_input,
+            )?;
+
+            for ((version, status, log), (_, other_status, other_log)) in
+                logs.into_iter().zip(other_logs)
+            {
+                if status != other_status {
+                    println!(
+                        "Transaction {} has different statuses: {:?} vs {:?}",
+                        version, status, other_status
+                    );
+                }
+                diffs.push(TransactionGasDiff::new(
+                    format!("Transaction {}", version),
+                    &log,
+                    &other_log,
+                ));
+            }
+        }
+
+        let report = GasDiffReport::new(self.inputs_file, self.other_inputs_file, diffs);
+        println!(
+            "Total execution gas: {:+}, IO gas: {:+}, storage fee: {:+} LUM",
+            report.execution.delta, report.io.delta, report.storage_fee.delta
+        );
+        report.generate_report(&self.report_dir, "Gas Diff Report".to_string())?;
+        println!("Gas diff report saved to {}", self.report_dir);
+
+        Ok(())
+    }
+}
+
+/// Profiles every user transaction in the block. Transactions are replayed one by one on top of
+/// the pre-block state, with the writes of all previous transactions in the block (obtained by
+/// executing the whole block) applied to it. Each transaction is replayed with the auxiliary info
+/// it was executed with on-chain.
+fn profile_block(
+    begin_version: Version,
+    transactions: &[Transaction],
+    auxiliary_infos: &[AuxiliaryInfo],
+    workload: &Workload,
+    input: &ReadSet,
+) -> anyhow::Result<Vec<(Version, VMStatus, TransactionGasLog)>> {
+    let executor = LumioVMBlockExecutor::new();
+    let outputs: Vec<TransactionOutput> = execute_workload(&executor, workload, input, 1);
+
+    let mut state_view = ReadSetWithWrites::new(input);
+    let mut logs = vec![];
+    for (((version, txn), auxiliary_info), output) in (begin_version..)
+        .zip(transactions)
+        .zip(auxiliary_infos)
+        .zip(&outputs)
+    {
+        if let Transaction::UserTransaction(txn) = txn {
+            let (status, _, log) =
+                profile_user_transaction(&state_view, txn.clone(), auxiliary_info.clone())?;
+            logs.push((version, status, log));
+        }
+        state_view.apply_write_set(output.write_set());
+    }
+    Ok(logs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_tool() {
+        use clap::CommandFactory;
+        GasDiffCommand::command().debug_assert();
+    }
+}
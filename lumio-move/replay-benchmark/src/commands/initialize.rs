@@ -55,6 +55,12 @@ pub struct InitializeCommand {
     )]
     gas_feature_version: Option<u64>,
 
+    #[clap(
+        long,
+        help = "If set, overrides the gas schedule with the one from the specified JSON file"
+    )]
+    gas_schedule_file: Option<String>,
+
     #[clap(
         long,
         num_args = 1..,
@@ -77,14 +83,14 @@ impl InitializeCommand {
         })?;
 
         // TODO:
-        //   1. Override gas schedule, to track the costs of charging gas or tracking limits.
-        //   2. BlockExecutorConfigFromOnchain to experiment with different block cutting based
+        //   1. BlockExecutorConfigFromOnchain to experiment with different block cutting based
         //      on gas limits?.
-        //   3. Build options for package overrides.
+        //   2. Build options for package overrides.
         let override_config = OverrideConfig::new(
             self.enable_features,
             self.disable_features,
             self.gas_feature_version,
+            self.gas_schedule_file,
             self.override_packages,
         )?;
 
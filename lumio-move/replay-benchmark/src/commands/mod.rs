@@ -9,12 +9,14 @@ pub use benchmark::BenchmarkCommand;
 use clap::Parser;
 pub use diff::DiffCommand;
 pub use download::DownloadCommand;
+pub use gas_diff::GasDiffCommand;
 pub use initialize::InitializeCommand;
 use url::Url;
 
 mod benchmark;
 mod diff;
 mod download;
+mod gas_diff;
 mod initialize;
 
 pub(crate) fn init_logger_and_metrics(log_level: Level) {
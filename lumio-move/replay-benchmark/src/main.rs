@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use lumio_replay_benchmark::commands::{
-    BenchmarkCommand, DiffCommand, DownloadCommand, InitializeCommand,
+    BenchmarkCommand, DiffCommand, DownloadCommand, GasDiffCommand, InitializeCommand,
 };
 use clap::Parser;
 
@@ -12,6 +12,7 @@ pub enum Command {
     Download(DownloadCommand),
     Initialize(InitializeCommand),
     Diff(DiffCommand),
+    GasDiff(GasDiffCommand),
     Benchmark(BenchmarkCommand),
 }
 
@@ -22,6 +23,7 @@ async fn main() -> anyhow::Result<()> {
         Command::Download(command) => command.download_transactions().await,
         Command::Initialize(command) => command.initialize_inputs().await,
         Command::Diff(command) => command.diff_outputs().await,
+        Command::GasDiff(command) => command.diff_gas().await,
         Command::Benchmark(command) => command.benchmark().await,
     }
 }
@@ -7,9 +7,10 @@
 //!   1. enabling feature flags,
 //!   2. disabling feature flags,
 //!   3. overriding gas feature version,
-//!   4. changing modules (bytecode, metadata, etc.) and package information.
+//!   4. overriding the whole gas schedule,
+//!   5. changing modules (bytecode, metadata, etc.) and package information.
 
-use anyhow::bail;
+use anyhow::{anyhow, bail};
 use lumio_framework::{natives::code::PackageRegistry, BuildOptions, BuiltPackage};
 use lumio_gas_schedule::LATEST_GAS_FEATURE_VERSION;
 use lumio_logger::{error, warn};
@@ -20,6 +21,7 @@ use lumio_types::{
 use serde::Serialize;
 use std::{
     collections::{BTreeSet, HashMap},
+    fs,
     path::PathBuf,
 };
 
@@ -53,6 +55,9 @@ pub struct OverrideConfig {
     additional_disabled_features: Vec<FeatureFlag>,
     /// Gas feature version to use. Invariant: must be at most the latest version.
     gas_feature_version: Option<u64>,
+    /// Gas schedule to use instead of the on-chain one. If the gas feature version is also set,
+    /// it takes precedence over the version stored in this schedule.
+    gas_schedule: Option<GasScheduleV2>,
     /// Information about overridden packages.
     package_override: PackageOverride,
 }
@@ -62,6 +67,7 @@ impl OverrideConfig {
         additional_enabled_features: Vec<FeatureFlag>,
         additional_disabled_features: Vec<FeatureFlag>,
         gas_feature_version: Option<u64>,
+        gas_schedule_file: Option<String>,
         override_packages: Vec<String>,
     ) -> anyhow::Result<Self> {
         let build_options = BuildOptions::move_2();
@@ -80,10 +86,19 @@ impl OverrideConfig {
             );
         }
 
+        let gas_schedule = gas_schedule_file
+            .map(|path| -> anyhow::Result<GasScheduleV2> {
+                let bytes = fs::read(PathBuf::from(&path))?;
+                serde_json::from_slice(&bytes)
+                    .map_err(|err| anyhow!("Error when parsing gas schedule {}: {:?}", path, err))
+            })
+            .transpose()?;
+
         Ok(Self {
             additional_enabled_features,
             additional_disabled_features,
             gas_feature_version,
+            gas_schedule,
             package_override,
         })
     }
@@ -116,14 +131,19 @@ impl OverrideConfig {
             state_override.insert(features_state_key, features_state_value);
         }
 
-        // Gas feature override.
-        if let Some(gas_feature_version) = self.gas_feature_version {
+        // Gas schedule and gas feature override.
+        if self.gas_feature_version.is_some() || self.gas_schedule.is_some() {
             // Only support V2 gas schedule which has gas feature versions. Otherwise, V1 has 0
             // version at all times, and most likely it has been so long ago we will not replay
             // these transactions.
             let (gas_schedule_state_key, gas_schedule_state_value) =
                 config_override::<GasScheduleV2, _>(state_view, |gas_schedule| {
-                    gas_schedule.feature_version = gas_feature_version;
+                    if let Some(new_gas_schedule) = &self.gas_schedule {
+                        *gas_schedule = new_gas_schedule.clone();
+                    }
+                    if let Some(gas_feature_version) = self.gas_feature_version {
+                        gas_schedule.feature_version = gas_feature_version;
+                    }
                 });
             state_override.insert(gas_schedule_state_key, gas_schedule_state_value);
         }
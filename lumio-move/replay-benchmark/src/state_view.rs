@@ -7,6 +7,7 @@ use lumio_types::{
         state_value::StateValue, StateView, StateViewResult, TStateView,
     },
     transaction::Version,
+    write_set::WriteSet,
 };
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
@@ -41,6 +42,53 @@ impl TStateView for ReadSet {
     }
 }
 
+/// [StateView] implementation that applies writes of already executed transactions on top of a
+/// [ReadSet]. Used to replay transactions of a block one by one.
+pub(crate) struct ReadSetWithWrites<'s> {
+    read_set: &'s ReadSet,
+    writes: HashMap<StateKey, Option<StateValue>>,
+}
+
+impl<'s> ReadSetWithWrites<'s> {
+    pub(crate) fn new(read_set: &'s ReadSet) -> Self {
+        Self {
+            read_set,
+            writes: HashMap::new(),
+        }
+    }
+
+    /// Records the writes so that they are visible to subsequent reads.
+    pub(crate) fn apply_write_set(&mut self, write_set: &WriteSet) {
+        for (state_key, write_op) in write_set.write_op_iter() {
+            self.writes
+                .insert(state_key.clone(), write_op.as_state_value_opt().cloned());
+        }
+    }
+}
+
+impl TStateView for ReadSetWithWrites<'_> {
+    type Key = StateKey;
+
+    fn get_state_slot(&self, state_key: &Self::Key) -> StateViewResult<StateSlot> {
+        match self.writes.get(state_key) {
+            Some(Some(state_value)) => Ok(StateSlot::ColdOccupied {
+                value_version: 0,
+                value: state_value.clone(),
+            }),
+            Some(None) => Ok(StateSlot::ColdVacant),
+            None => self.read_set.get_state_slot(state_key),
+        }
+    }
+
+    fn next_version(&self) -> Version {
+        0
+    }
+
+    fn get_usage(&self) -> StateViewResult<StateStorageUsage> {
+        unreachable!("Should not be called when benchmarking")
+    }
+}
+
 /// [StateView] implementation that records all execution reads. Captured reads can be converted
 /// into a [ReadSet].
 pub(crate) struct ReadSetCapturingStateView<'s, S> {
@@ -8,7 +8,7 @@ use lumio_types::{
         signature_verified_transaction::{
             into_signature_verified_block, SignatureVerifiedTransaction,
         },
-        AuxiliaryInfo, Transaction, Version,
+        AuxiliaryInfo, PersistedAuxiliaryInfo, Transaction, Version,
     },
 };
 use serde::{Deserialize, Serialize};
@@ -30,18 +30,35 @@ pub(crate) struct TransactionBlock {
     pub(crate) begin_version: Version,
     /// Non-empty list of transactions in a block.
     pub(crate) transactions: Vec<Transaction>,
+    /// Persisted auxiliary information the transactions were executed with, one per transaction.
+    pub(crate) persisted_auxiliary_infos: Vec<PersistedAuxiliaryInfo>,
+}
+
+impl TransactionBlock {
+    /// Returns the auxiliary information to execute the transactions in the block with.
+    pub(crate) fn auxiliary_infos(&self) -> Vec<AuxiliaryInfo> {
+        self.persisted_auxiliary_infos
+            .iter()
+            .map(|info| AuxiliaryInfo::new(*info, None))
+            .collect()
+    }
 }
 
 impl From<TransactionBlock> for Workload {
     fn from(txn_block: TransactionBlock) -> Self {
         assert!(!txn_block.transactions.is_empty());
+        assert_eq!(
+            txn_block.transactions.len(),
+            txn_block.persisted_auxiliary_infos.len()
+        );
 
         let end = txn_block.begin_version + txn_block.transactions.len() as Version;
         let transaction_slice_metadata =
             TransactionSliceMetadata::chunk(txn_block.begin_version, end);
 
+        let auxiliary_infos = txn_block.auxiliary_infos();
         let signature_verified_txns = into_signature_verified_block(txn_block.transactions);
-        let txn_provider = DefaultTxnProvider::new_without_info(signature_verified_txns);
+        let txn_provider = DefaultTxnProvider::new(signature_verified_txns, auxiliary_infos);
 
         Self {
             txn_provider,
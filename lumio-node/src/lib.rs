@@ -27,8 +27,13 @@ use lumio_framework::ReleaseBundle;
 use lumio_genesis::builder::GenesisConfiguration;
 use lumio_logger::{prelude::*, telemetry_log_writer::TelemetryLog, Level, LoggerFilterUpdater};
 use lumio_state_sync_driver::driver_factory::StateSyncRuntimes;
+use lumio_storage_interface::{
+    partial_state::{PartialStateDbReader, PartialStateStore, PARTIAL_STATE_FILE_NAME},
+    DbReaderWriter,
+};
 use lumio_types::{
     chain_id::ChainId, keyless::Groth16VerificationKey, on_chain_config::OnChainJWKConsensusConfig,
+    state_sync_progress::SharedStateSyncProgress,
 };
 use clap::Parser;
 use futures::channel::{mpsc, oneshot};
@@ -759,6 +764,22 @@ pub fn setup_environment_and_start_node(
         db_rw.reader.clone(),
     );
 
+    // Create the partial state store (if we're a partial state node)
+    let partial_state_sync_config = &node_config.state_sync.partial_state_sync;
+    let partial_state_store = if partial_state_sync_config.enable_partial_state_sync {
+        let tracked_key_prefixes = partial_state_sync_config.get_tracked_key_prefixes()?;
+        let snapshot_file = node_config.storage.dir().join(PARTIAL_STATE_FILE_NAME);
+        Some(Arc::new(PartialStateStore::new(
+            tracked_key_prefixes,
+            Some(snapshot_file),
+        )))
+    } else {
+        None
+    };
+
+    // Create the shared state sync progress report (served by the APIs)
+    let state_sync_progress = SharedStateSyncProgress::new();
+
     // Start state sync and get the notification endpoints for mempool and consensus
     let (lumio_data_client, state_sync_runtimes, mempool_listener, consensus_notifier) =
         state_sync::start_state_sync_and_get_notification_handles(
@@ -767,6 +788,9 @@ pub fn setup_environment_and_start_node(
             genesis_waypoint,
             event_subscription_service,
             db_rw.clone(),
+            indexer_db_opt.clone(),
+            partial_state_store.clone(),
+            state_sync_progress.clone(),
         )?;
 
     // Start the node inspection service
@@ -774,9 +798,20 @@ pub fn setup_environment_and_start_node(
         &node_config,
         lumio_data_client,
         peers_and_metadata.clone(),
+        state_sync_progress.clone(),
     );
 
-    // Bootstrap the API and indexer
+    // Bootstrap the API and indexer (partial state nodes serve reads from the partial state store)
+    let api_db_rw = match &partial_state_store {
+        Some(partial_state_store) => DbReaderWriter {
+            reader: Arc::new(PartialStateDbReader::new(
+                db_rw.reader.clone(),
+                partial_state_store.clone(),
+            )),
+            writer: db_rw.writer.clone(),
+        },
+        None => db_rw.clone(),
+    };
     let (
         mempool_client_receiver,
         api_runtime,
@@ -787,10 +822,12 @@ pub fn setup_environment_and_start_node(
         mempool_client_sender,
     ) = services::bootstrap_api_and_indexer(
         &node_config,
-        db_rw.clone(),
+        api_db_rw,
         chain_id,
         indexer_db_opt,
         update_receiver,
+        state_sync_progress,
+        partial_state_store,
         api_port_tx,
         indexer_grpc_port_tx,
     )?;
@@ -8,7 +8,7 @@ use lumio_config::config::NodeConfig;
 use lumio_consensus::{
     consensus_observer::publisher::consensus_publisher::ConsensusPublisher,
     network_interface::ConsensusMsg, persistent_liveness_storage::StorageWriteProxy,
-    quorum_store::quorum_store_db::QuorumStoreDB,
+    quorum_store::quorum_store_db::QuorumStoreDB, RoundTimelines,
 };
 use lumio_consensus_notifications::ConsensusNotifier;
 use lumio_data_client::client::LumioDataClient;
@@ -30,10 +30,11 @@ use lumio_peer_monitoring_service_server::{
     PeerMonitoringServiceServer,
 };
 use lumio_peer_monitoring_service_types::PeerMonitoringServiceMessage;
-use lumio_storage_interface::{DbReader, DbReaderWriter};
+use lumio_storage_interface::{partial_state::PartialStateStore, DbReader, DbReaderWriter};
 use lumio_time_service::TimeService;
 use lumio_types::{
-    chain_id::ChainId, indexer::indexer_db_reader::IndexerReader, transaction::Version,
+    chain_id::ChainId, indexer::indexer_db_reader::IndexerReader,
+    state_sync_progress::SharedStateSyncProgress, transaction::Version,
 };
 use lumio_validator_transaction_pool::VTxnPoolState;
 use futures::channel::{mpsc, mpsc::Sender, oneshot};
@@ -54,6 +55,8 @@ pub fn bootstrap_api_and_indexer(
     chain_id: ChainId,
     internal_indexer_db: Option<InternalIndexerDB>,
     update_receiver: Option<WatchReceiver<(Instant, Version)>>,
+    state_sync_progress: SharedStateSyncProgress,
+    partial_state_store: Option<Arc<PartialStateStore>>,
     api_port_tx: Option<oneshot::Sender<u16>>,
     indexer_grpc_port_tx: Option<oneshot::Sender<u16>>,
 ) -> anyhow::Result<(
@@ -104,6 +107,8 @@ pub fn bootstrap_api_and_indexer(
             db_rw.reader.clone(),
             mempool_client_sender.clone(),
             indexer_reader.clone(),
+            state_sync_progress,
+            partial_state_store,
             api_port_tx,
         )?)
     } else {
@@ -149,7 +154,12 @@ pub fn start_consensus_runtime(
     consensus_to_mempool_sender: Sender<QuorumStoreRequest>,
     vtxn_pool: VTxnPoolState,
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
-) -> (Runtime, Arc<StorageWriteProxy>, Arc<QuorumStoreDB>) {
+) -> (
+    Runtime,
+    Arc<StorageWriteProxy>,
+    Arc<QuorumStoreDB>,
+    RoundTimelines,
+) {
     let instant = Instant::now();
 
     let reconfig_subscription = consensus_reconfig_subscription
@@ -213,11 +223,13 @@ pub fn start_node_inspection_service(
     node_config: &NodeConfig,
     lumio_data_client: LumioDataClient,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    state_sync_progress: SharedStateSyncProgress,
 ) {
     lumio_inspection_service::start_inspection_service(
         node_config.clone(),
         lumio_data_client,
         peers_and_metadata,
+        state_sync_progress,
     )
 }
 
@@ -9,12 +9,17 @@ use lumio_data_streaming_service::{
     streaming_client::{new_streaming_service_client_listener_pair, StreamingServiceClient},
     streaming_service::DataStreamingService,
 };
+use lumio_db_indexer::{
+    db_indexer::{DBIndexer, InternalIndexerDB},
+    indexer_reader::IndexerReaders,
+};
 use lumio_event_notifications::{
     DbBackedOnChainConfig, EventNotificationListener, EventSubscriptionService,
     ReconfigNotificationListener,
 };
 use lumio_executor::chunk_executor::ChunkExecutor;
 use lumio_infallible::RwLock;
+use lumio_logger::warn;
 use lumio_mempool_notifications::MempoolNotificationListener;
 use lumio_network::application::{
     interface::{NetworkClient, NetworkClientInterface, NetworkServiceEvents},
@@ -24,7 +29,7 @@ use lumio_state_sync_driver::{
     driver_factory::{DriverFactory, StateSyncRuntimes},
     metadata_storage::PersistentMetadataStorage,
 };
-use lumio_storage_interface::{DbReader, DbReaderWriter};
+use lumio_storage_interface::{partial_state::PartialStateStore, DbReader, DbReaderWriter};
 use lumio_storage_service_client::StorageServiceClient;
 use lumio_storage_service_notifications::StorageServiceNotificationListener;
 use lumio_storage_service_server::{
@@ -32,7 +37,7 @@ use lumio_storage_service_server::{
 };
 use lumio_storage_service_types::StorageServiceMessage;
 use lumio_time_service::TimeService;
-use lumio_types::waypoint::Waypoint;
+use lumio_types::{state_sync_progress::SharedStateSyncProgress, waypoint::Waypoint};
 use lumio_vm::lumio_vm::LumioVMBlockExecutor;
 use std::sync::Arc;
 use tokio::runtime::Runtime;
@@ -131,6 +136,9 @@ pub fn start_state_sync_and_get_notification_handles(
     waypoint: Waypoint,
     event_subscription_service: EventSubscriptionService,
     db_rw: DbReaderWriter,
+    internal_indexer_db: Option<InternalIndexerDB>,
+    partial_state_store: Option<Arc<PartialStateStore>>,
+    state_sync_progress: SharedStateSyncProgress,
 ) -> anyhow::Result<(
     LumioDataClient,
     StateSyncRuntimes,
@@ -147,9 +155,9 @@ pub fn start_state_sync_and_get_notification_handles(
         setup_lumio_data_client(node_config, network_client, db_rw.reader.clone())?;
 
     // Start the data streaming service
-    let state_sync_config = node_config.state_sync;
+    let state_sync_config = node_config.state_sync.clone();
     let (streaming_service_client, streaming_service_runtime) =
-        setup_data_streaming_service(state_sync_config, lumio_data_client.clone())?;
+        setup_data_streaming_service(state_sync_config.clone(), lumio_data_client.clone())?;
 
     // Create the chunk executor and persistent storage
     let chunk_executor = Arc::new(ChunkExecutor::<LumioVMBlockExecutor>::new(db_rw.clone()));
@@ -173,10 +181,11 @@ pub fn start_state_sync_and_get_notification_handles(
 
     // Start the state sync storage service
     let storage_service_runtime = setup_state_sync_storage_service(
-        state_sync_config,
+        node_config,
         peers_and_metadata,
         network_service_events,
         &db_rw,
+        internal_indexer_db,
         storage_service_listener,
     )?;
 
@@ -194,6 +203,8 @@ pub fn start_state_sync_and_get_notification_handles(
         event_subscription_service,
         lumio_data_client.clone(),
         streaming_service_client,
+        partial_state_store,
+        state_sync_progress,
         TimeService::real(),
     );
 
@@ -264,21 +275,40 @@ fn setup_lumio_data_client(
 
 /// Sets up the state sync storage service runtime
 fn setup_state_sync_storage_service(
-    config: StateSyncConfig,
+    node_config: &NodeConfig,
     peers_and_metadata: Arc<PeersAndMetadata>,
     network_service_events: NetworkServiceEvents<StorageServiceMessage>,
     db_rw: &DbReaderWriter,
+    internal_indexer_db: Option<InternalIndexerDB>,
     storage_service_listener: StorageServiceNotificationListener,
 ) -> anyhow::Result<Runtime> {
     // Create a new state sync storage service runtime
     let storage_service_runtime = lumio_runtimes::spawn_named_runtime("stor-server".into(), None);
 
-    // Spawn the state sync storage service servers on the runtime
-    let storage_reader = StorageReader::new(
+    // Create the storage reader
+    let config = node_config.state_sync.clone();
+    let mut storage_reader = StorageReader::new(
         config.storage_service,
         Arc::clone(&db_rw.reader),
         TimeService::real(),
     );
+
+    // If storage sharding is enabled, prefixed state values must be read via the internal indexer
+    if node_config.storage.rocksdb_configs.enable_storage_sharding {
+        let db_indexer = internal_indexer_db
+            .map(|indexer_db| Arc::new(DBIndexer::new(indexer_db, db_rw.reader.clone())));
+        match IndexerReaders::new(None, db_indexer) {
+            Some(indexer_reader) => {
+                storage_reader = storage_reader.with_indexer_reader(Arc::new(indexer_reader));
+            },
+            None => warn!(
+                "Storage sharding is enabled, but the internal indexer is not! \
+                Filtered state value requests will not be served."
+            ),
+        }
+    }
+
+    // Spawn the state sync storage service servers on the runtime
     let service = StorageServiceServer::new(
         config,
         storage_service_runtime.handle().clone(),
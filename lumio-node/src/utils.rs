@@ -64,6 +64,9 @@ pub fn set_lumio_vm_configurations(node_config: &NodeConfig) {
     LumioVM::set_num_proof_reading_threads_once(
         node_config.execution.num_proof_reading_threads as usize,
     );
+    LumioVM::set_blockstm_conflict_analytics_once(
+        node_config.execution.blockstm_conflict_analytics,
+    );
 
     if node_config
         .execution
@@ -16,14 +16,17 @@ use lumio_infallible::Mutex;
 use lumio_logger::Level;
 use lumio_storage_service_types::{
     requests::{
-        DataRequest, EpochEndingLedgerInfoRequest, NewTransactionOutputsWithProofRequest,
-        NewTransactionsOrOutputsWithProofRequest, NewTransactionsWithProofRequest,
-        StateValuesWithProofRequest, SubscribeTransactionOutputsWithProofRequest,
+        DataRequest, EpochEndingLedgerInfoRequest, FilteredStateValuesWithProofRequest,
+        NewTransactionOutputsWithProofRequest, NewTransactionsOrOutputsWithProofRequest,
+        NewTransactionsWithProofRequest, StateValuesWithProofRequest,
+        SubscribeTransactionOutputsWithProofRequest,
         SubscribeTransactionsOrOutputsWithProofRequest, SubscribeTransactionsWithProofRequest,
         SubscriptionStreamMetadata, TransactionOutputsWithProofRequest,
         TransactionsOrOutputsWithProofRequest, TransactionsWithProofRequest,
     },
-    responses::{CompleteDataRange, TransactionOrOutputListWithProofV2},
+    responses::{
+        CompleteDataRange, FilteredStateValuesWithProof, TransactionOrOutputListWithProofV2,
+    },
     Epoch,
 };
 use lumio_types::{
@@ -35,7 +38,7 @@ use lumio_types::{
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     proof::SparseMerkleRangeProof,
     state_store::{
-        state_key::StateKey,
+        state_key::{prefix::StateKeyPrefix, StateKey},
         state_value::{StateValue, StateValueChunkWithProof},
     },
     transaction::{
@@ -327,6 +330,33 @@ impl LumioDataClientInterface for MockLumioDataClient {
         Ok(create_data_client_response(state_value_chunk_with_proof))
     }
 
+    async fn get_filtered_state_values_with_proof(
+        &self,
+        version: Version,
+        key_prefix: StateKeyPrefix,
+        start_key: Option<StateKey>,
+        known_keys: Vec<StateKey>,
+        request_timeout_ms: u64,
+    ) -> Result<Response<FilteredStateValuesWithProof>, lumio_data_client::error::Error> {
+        // Verify the request timeout
+        let data_request =
+            DataRequest::GetFilteredStateValuesWithProof(FilteredStateValuesWithProofRequest {
+                version,
+                key_prefix,
+                start_key,
+                known_keys,
+            });
+        self.verify_request_timeout_value(request_timeout_ms, false, false, data_request);
+
+        // Emulate network latencies
+        self.emulate_network_latencies().await;
+
+        // Partial state syncing is not supported by the data streaming service
+        Err(lumio_data_client::error::Error::DataIsUnavailable(
+            "Filtered state values are not supported by the mock data client!".into(),
+        ))
+    }
+
     async fn get_epoch_ending_ledger_infos(
         &self,
         start_epoch: Epoch,
@@ -13,7 +13,8 @@ use crate::{
     metrics::{
         increment_request_counter, set_gauge, start_request_timer, PRIORITIZED_PEER, REGULAR_PEER,
     },
-    peer_states::{ErrorType, PeerStates},
+    peer_reputation::{PeerFailure, PeerReputations, PEER_REPUTATION_FILE_NAME},
+    peer_states::PeerStates,
     poller::DataSummaryPoller,
     priority,
     priority::PeerPriority,
@@ -34,22 +35,28 @@ use lumio_storage_interface::DbReader;
 use lumio_storage_service_client::StorageServiceClient;
 use lumio_storage_service_types::{
     requests::{
-        DataRequest, EpochEndingLedgerInfoRequest, NewTransactionOutputsWithProofRequest,
-        NewTransactionsOrOutputsWithProofRequest, NewTransactionsWithProofRequest,
-        StateValuesWithProofRequest, StorageServiceRequest,
+        DataRequest, EpochEndingLedgerInfoRequest, FilteredStateValuesWithProofRequest,
+        NewTransactionOutputsWithProofRequest, NewTransactionsOrOutputsWithProofRequest,
+        NewTransactionsWithProofRequest, StateValuesWithProofRequest, StorageServiceRequest,
         SubscribeTransactionOutputsWithProofRequest,
         SubscribeTransactionsOrOutputsWithProofRequest, SubscribeTransactionsWithProofRequest,
         SubscriptionStreamMetadata, TransactionOutputsWithProofRequest,
         TransactionsOrOutputsWithProofRequest, TransactionsWithProofRequest,
     },
-    responses::{StorageServerSummary, StorageServiceResponse, TransactionOrOutputListWithProofV2},
-    Epoch, StorageServiceMessage,
+    responses::{
+        FilteredStateValuesWithProof, StorageServerSummary, StorageServiceResponse,
+        TransactionOrOutputListWithProofV2,
+    },
+    Epoch, StorageServiceError, StorageServiceMessage,
 };
 use lumio_time_service::TimeService;
 use lumio_types::{
     epoch_change::EpochChangeProof,
     ledger_info::LedgerInfoWithSignatures,
-    state_store::state_value::StateValueChunkWithProof,
+    state_store::{
+        state_key::{prefix::StateKeyPrefix, StateKey},
+        state_value::StateValueChunkWithProof,
+    },
     transaction::{TransactionListWithProofV2, TransactionOutputListWithProofV2, Version},
 };
 use arc_swap::ArcSwap;
@@ -120,13 +127,23 @@ impl LumioDataClient {
         let base_config = Arc::new(base_config);
         let data_client_config = Arc::new(data_client_config);
 
+        // Load the peer reputations (persisted in the data directory)
+        let peer_reputations = Arc::new(PeerReputations::new(
+            data_client_config.peer_reputation_config,
+            Some(base_config.data_dir.join(PEER_REPUTATION_FILE_NAME)),
+            time_service.clone(),
+        ));
+
         // Create the data client
         let data_client = Self {
             base_config,
             data_client_config: data_client_config.clone(),
             storage_service_client: storage_service_client.clone(),
             active_subscription_state: Arc::new(Mutex::new(None)),
-            peer_states: Arc::new(PeerStates::new(data_client_config.clone())),
+            peer_states: Arc::new(PeerStates::new(
+                data_client_config.clone(),
+                peer_reputations,
+            )),
             global_summary_cache: Arc::new(ArcSwap::from(Arc::new(GlobalDataSummary::empty()))),
             response_id_generator: Arc::new(U64IdGenerator::new()),
             time_service: time_service.clone(),
@@ -172,6 +189,9 @@ impl LumioDataClient {
         // Update the peer request logs and metrics
         self.peer_states.update_peer_request_logs_and_metrics();
 
+        // Persist the peer reputations (if required)
+        self.peer_states.persist_peer_reputations();
+
         // Update the peer priority metrics and logs (infrequently)
         sample!(
             SampleRate::Duration(Duration::from_secs(PEER_METRICS_FREQ_SECS)),
@@ -841,8 +861,14 @@ impl LumioDataClient {
                         },
                         _ => Error::UnexpectedErrorEncountered(rpc_error.to_string()),
                     },
-                    lumio_storage_service_client::Error::StorageServiceError(err) => {
-                        Error::UnexpectedErrorEncountered(err.to_string())
+                    lumio_storage_service_client::Error::StorageServiceError(err) => match err {
+                        // Servers may also throttle requests using the legacy error
+                        // (to support older clients). Both require us to back off.
+                        StorageServiceError::RequestThrottled(_)
+                        | StorageServiceError::TooManyInvalidRequests(_) => {
+                            Error::RequestThrottled(err.to_string())
+                        },
+                        _ => Error::UnexpectedErrorEncountered(err.to_string()),
                     },
                     _ => Error::UnexpectedErrorEncountered(error.to_string()),
                 };
@@ -862,7 +888,22 @@ impl LumioDataClient {
                     peer,
                 );
 
-                self.notify_bad_response(id, peer, &request, ErrorType::NotUseful);
+                // Record timeouts separately from other failures. Throttled
+                // requests aren't failures (the peer is overloaded and
+                // requires us to back off), so the peer isn't penalized.
+                // Instead, the peer is skipped until the cooldown expires.
+                let failure = match client_error {
+                    Error::RequestThrottled(_) => {
+                        self.peer_states
+                            .update_throttled_peer(peer, self.time_service.clone());
+                        None
+                    },
+                    Error::TimeoutWaitingForResponse(_) => Some(PeerFailure::Timeout),
+                    _ => Some(PeerFailure::Other),
+                };
+                if let Some(failure) = failure {
+                    self.notify_bad_response(id, peer, &request, failure);
+                }
                 Err(client_error)
             },
         }
@@ -874,9 +915,9 @@ impl LumioDataClient {
         _id: ResponseId,
         peer: PeerNetworkId,
         _request: &StorageServiceRequest,
-        error_type: ErrorType,
+        failure: PeerFailure,
     ) {
-        self.peer_states.update_score_error(peer, error_type);
+        self.peer_states.update_score_error(peer, failure);
     }
 
     /// Creates a storage service request using the given data request
@@ -1053,6 +1094,25 @@ impl LumioDataClientInterface for LumioDataClient {
             .await
     }
 
+    async fn get_filtered_state_values_with_proof(
+        &self,
+        version: Version,
+        key_prefix: StateKeyPrefix,
+        start_key: Option<StateKey>,
+        known_keys: Vec<StateKey>,
+        request_timeout_ms: u64,
+    ) -> crate::error::Result<Response<FilteredStateValuesWithProof>> {
+        let data_request =
+            DataRequest::GetFilteredStateValuesWithProof(FilteredStateValuesWithProofRequest {
+                version,
+                key_prefix,
+                start_key,
+                known_keys,
+            });
+        self.create_and_send_storage_request(request_timeout_ms, data_request)
+            .await
+    }
+
     async fn get_transaction_outputs_with_proof(
         &self,
         proof_version: Version,
@@ -1239,9 +1299,9 @@ struct LumioNetResponseCallback {
 
 impl ResponseCallback for LumioNetResponseCallback {
     fn notify_bad_response(&self, error: ResponseError) {
-        let error_type = ErrorType::from(error);
+        let failure = PeerFailure::from(error);
         self.data_client
-            .notify_bad_response(self.id, self.peer, &self.request, error_type);
+            .notify_bad_response(self.id, self.peer, &self.request, failure);
     }
 }
 
@@ -19,6 +19,8 @@ pub enum Error {
     InvalidResponse(String),
     #[error("No connected peers: {0}")]
     NoConnectedPeers(String),
+    #[error("The request was throttled by the peer! Back off required: {0}")]
+    RequestThrottled(String),
     #[error("The subscription stream is lagging behind the data advertisements: {0}")]
     SubscriptionStreamIsLagging(String),
     #[error("Timed out waiting for a response: {0}")]
@@ -36,6 +38,7 @@ impl Error {
             Self::InvalidRequest(_) => "invalid_request",
             Self::InvalidResponse(_) => "invalid_response",
             Self::NoConnectedPeers(_) => "no_connected_peers",
+            Self::RequestThrottled(_) => "request_throttled",
             Self::SubscriptionStreamIsLagging(_) => "subscription_stream_is_lagging",
             Self::TimeoutWaitingForResponse(_) => "timeout_waiting_for_response",
             Self::UnexpectedErrorEncountered(_) => "unexpected_error_encountered",
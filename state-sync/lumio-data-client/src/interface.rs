@@ -2,10 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{error, error::Error, global_summary::GlobalDataSummary};
-use lumio_storage_service_types::{responses::TransactionOrOutputListWithProofV2, Epoch};
+use lumio_storage_service_types::{
+    responses::{FilteredStateValuesWithProof, TransactionOrOutputListWithProofV2},
+    Epoch,
+};
 use lumio_types::{
     ledger_info::LedgerInfoWithSignatures,
-    state_store::state_value::StateValueChunkWithProof,
+    state_store::{
+        state_key::{prefix::StateKeyPrefix, StateKey},
+        state_value::StateValueChunkWithProof,
+    },
     transaction::{TransactionListWithProofV2, TransactionOutputListWithProofV2, Version},
 };
 use async_trait::async_trait;
@@ -87,6 +93,22 @@ pub trait LumioDataClientInterface {
         request_timeout_ms: u64,
     ) -> error::Result<Response<StateValueChunkWithProof>>;
 
+    /// Fetches the state values (each with a proof) of all state keys with
+    /// the given key prefix at the specified version, starting at the start
+    /// key (inclusive). In some cases, fewer state values may be returned
+    /// (e.g., to tolerate network or chunk limits), in which case the next
+    /// start key is also returned. The known keys that no longer exist are
+    /// returned with non-inclusion proofs. If the data cannot be fetched, an
+    /// error is returned.
+    async fn get_filtered_state_values_with_proof(
+        &self,
+        version: Version,
+        key_prefix: StateKeyPrefix,
+        start_key: Option<StateKey>,
+        known_keys: Vec<StateKey>,
+        request_timeout_ms: u64,
+    ) -> error::Result<Response<FilteredStateValuesWithProof>>;
+
     /// Fetches a transaction output list with proof, with transaction
     /// outputs from start to end versions (inclusive). The proof is relative
     /// to the specified `proof_version`. In some cases, fewer outputs may be
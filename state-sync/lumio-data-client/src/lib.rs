@@ -10,6 +10,7 @@ pub mod interface;
 mod latency_monitor;
 mod logging;
 mod metrics;
+pub mod peer_reputation;
 pub mod peer_states;
 pub mod poller;
 pub mod priority;
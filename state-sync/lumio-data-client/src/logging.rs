@@ -54,9 +54,11 @@ pub enum LogEvent {
     AggregateSummary,
     CaughtUpToLatest,
     NoPeersToPoll,
+    PeerBanned,
     PeerIgnored,
     PeerNoLongerIgnored,
     PeerPollingError,
+    PeerReputationPersistence,
     PeerRequestResponseCounts,
     PeerSelectionError,
     PriorityAndRegularPeers,
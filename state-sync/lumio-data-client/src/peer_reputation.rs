@@ -0,0 +1,408 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    interface::ResponseError,
+    logging::{LogEntry, LogEvent, LogSchema},
+    peer_states::{ErrorType, STARTING_SCORE},
+};
+use lumio_config::{config::LumioPeerReputationConfig, network_id::PeerNetworkId};
+use lumio_logger::prelude::*;
+use lumio_time_service::{TimeService, TimeServiceTrait};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+// The name of the file (in the data directory) holding the peer reputations
+pub const PEER_REPUTATION_FILE_NAME: &str = "state_sync_peer_reputations.json";
+
+// The frequency (in seconds) at which to log persistence errors
+const PERSISTENCE_LOG_FREQ_SECS: u64 = 300;
+
+/// The failures that are recorded for a peer
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PeerFailure {
+    /// The response was invalid (e.g., it held the wrong data type)
+    InvalidResponse,
+    /// The response failed proof verification
+    ProofVerification,
+    /// The peer didn't respond in time
+    Timeout,
+    /// Any other failure (e.g., a network or storage service error)
+    Other,
+}
+
+impl PeerFailure {
+    /// Returns the error type (used for peer scoring) of the failure
+    pub fn get_error_type(&self) -> ErrorType {
+        match self {
+            PeerFailure::ProofVerification => ErrorType::Malicious,
+            PeerFailure::InvalidResponse | PeerFailure::Timeout | PeerFailure::Other => {
+                ErrorType::NotUseful
+            },
+        }
+    }
+}
+
+impl From<ResponseError> for PeerFailure {
+    fn from(error: ResponseError) -> Self {
+        match error {
+            ResponseError::InvalidData | ResponseError::InvalidPayloadDataType => {
+                PeerFailure::InvalidResponse
+            },
+            ResponseError::ProofVerificationError => PeerFailure::ProofVerification,
+        }
+    }
+}
+
+/// The reputation of a single peer. Unlike the rest of the peer state, the
+/// reputation outlives the peer's connection (and node restarts, if persisted).
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PeerReputation {
+    /// The latest score of the peer
+    score: f64,
+
+    /// The number of invalid responses received from the peer
+    num_invalid_responses: u64,
+
+    /// The number of responses from the peer that failed proof verification
+    num_proof_verification_failures: u64,
+
+    /// The number of requests to the peer that timed out
+    num_timeouts: u64,
+
+    /// The number of other failed requests to the peer
+    num_other_failures: u64,
+
+    /// The number of times the peer has been banned
+    num_bans: u64,
+
+    /// The number of proof verification failures since the peer was last banned
+    num_proof_verification_failures_since_ban: u64,
+
+    /// The unix time (in seconds) at which the current ban expires (if any)
+    banned_until_secs: Option<u64>,
+
+    /// The unix time (in seconds) at which the peer was last seen connected
+    /// (this is missing for reputations persisted before it was tracked).
+    #[serde(default)]
+    last_seen_secs: Option<u64>,
+}
+
+impl Default for PeerReputation {
+    fn default() -> Self {
+        Self {
+            score: STARTING_SCORE,
+            num_invalid_responses: 0,
+            num_proof_verification_failures: 0,
+            num_timeouts: 0,
+            num_other_failures: 0,
+            num_bans: 0,
+            num_proof_verification_failures_since_ban: 0,
+            banned_until_secs: None,
+            last_seen_secs: None,
+        }
+    }
+}
+
+impl PeerReputation {
+    /// Returns the score of the peer
+    pub fn get_score(&self) -> f64 {
+        self.score
+    }
+
+    /// Returns the number of invalid responses received from the peer
+    pub fn get_num_invalid_responses(&self) -> u64 {
+        self.num_invalid_responses
+    }
+
+    /// Returns the number of proof verification failures of the peer
+    pub fn get_num_proof_verification_failures(&self) -> u64 {
+        self.num_proof_verification_failures
+    }
+
+    /// Returns the number of requests to the peer that timed out
+    pub fn get_num_timeouts(&self) -> u64 {
+        self.num_timeouts
+    }
+
+    /// Returns the number of other failed requests to the peer
+    pub fn get_num_other_failures(&self) -> u64 {
+        self.num_other_failures
+    }
+
+    /// Returns the number of times the peer has been banned
+    pub fn get_num_bans(&self) -> u64 {
+        self.num_bans
+    }
+
+    /// Returns the unix time (in seconds) at which the latest ban expires (if any)
+    pub fn get_banned_until_secs(&self) -> Option<u64> {
+        self.banned_until_secs
+    }
+
+    /// Returns the unix time (in seconds) at which the peer was last seen connected
+    pub fn get_last_seen_secs(&self) -> Option<u64> {
+        self.last_seen_secs
+    }
+
+    /// Returns true iff the peer is banned at the given unix time (in seconds)
+    pub fn is_banned(&self, now_secs: u64) -> bool {
+        self.banned_until_secs
+            .is_some_and(|banned_until_secs| now_secs < banned_until_secs)
+    }
+
+    /// Records the given failure and bans the peer if it has failed proof
+    /// verification too many times. Returns the ban duration if the peer
+    /// was banned.
+    fn record_failure(
+        &mut self,
+        config: &LumioPeerReputationConfig,
+        failure: PeerFailure,
+        now_secs: u64,
+    ) -> Option<Duration> {
+        match failure {
+            PeerFailure::InvalidResponse => self.num_invalid_responses += 1,
+            PeerFailure::Timeout => self.num_timeouts += 1,
+            PeerFailure::Other => self.num_other_failures += 1,
+            PeerFailure::ProofVerification => {
+                self.num_proof_verification_failures += 1;
+                self.num_proof_verification_failures_since_ban += 1;
+            },
+        }
+
+        // Check if the peer should be banned
+        if self.is_banned(now_secs)
+            || self.num_proof_verification_failures_since_ban
+                < config.max_proof_verification_failures
+        {
+            return None;
+        }
+
+        // Ban the peer (the ban duration doubles with each ban)
+        let ban_duration_secs = calculate_ban_duration_secs(config, self.num_bans);
+        self.num_bans += 1;
+        self.num_proof_verification_failures_since_ban = 0;
+        self.banned_until_secs = Some(now_secs.saturating_add(ban_duration_secs));
+        Some(Duration::from_secs(ban_duration_secs))
+    }
+}
+
+/// Returns the duration (in seconds) of the next ban, given the number of
+/// previous bans of the peer.
+pub(crate) fn calculate_ban_duration_secs(
+    config: &LumioPeerReputationConfig,
+    num_previous_bans: u64,
+) -> u64 {
+    let multiplier = 1u64
+        .checked_shl(num_previous_bans as u32)
+        .unwrap_or(u64::MAX);
+    config
+        .initial_ban_duration_secs
+        .saturating_mul(multiplier)
+        .min(config.max_ban_duration_secs)
+}
+
+/// The reputations of all peers seen by the data client (including
+/// disconnected peers). The reputations are periodically persisted to
+/// disk, so that misbehaving peers are remembered across restarts.
+#[derive(Debug)]
+pub struct PeerReputations {
+    /// The peer reputation config
+    config: LumioPeerReputationConfig,
+
+    /// The unix time (in seconds) at which the reputations were last persisted
+    last_persisted_secs: AtomicU64,
+
+    /// The reputations of all peers
+    peer_to_reputation: DashMap<PeerNetworkId, PeerReputation>,
+
+    /// The file holding the persisted reputations (if persistence is enabled)
+    reputation_file: Option<PathBuf>,
+
+    /// The time service used to track bans
+    time_service: TimeService,
+}
+
+impl PeerReputations {
+    pub fn new(
+        config: LumioPeerReputationConfig,
+        reputation_file: Option<PathBuf>,
+        time_service: TimeService,
+    ) -> Self {
+        // Load any persisted reputations
+        let reputation_file = reputation_file.filter(|_| config.enable_persistence);
+        let peer_to_reputation = DashMap::new();
+        if let Some(reputation_file) = &reputation_file {
+            for (peer, reputation) in load_peer_reputations(reputation_file) {
+                peer_to_reputation.insert(peer, reputation);
+            }
+        }
+
+        Self {
+            config,
+            last_persisted_secs: AtomicU64::new(time_service.now_secs()),
+            peer_to_reputation,
+            reputation_file,
+            time_service,
+        }
+    }
+
+    /// Returns the score of the given peer (or the starting score if the peer is unknown)
+    pub fn get_score(&self, peer: &PeerNetworkId) -> f64 {
+        self.peer_to_reputation
+            .get(peer)
+            .map(|reputation| reputation.score)
+            .unwrap_or(STARTING_SCORE)
+    }
+
+    /// Returns the reputation of the given peer (if one exists)
+    pub fn get_reputation(&self, peer: &PeerNetworkId) -> Option<PeerReputation> {
+        self.peer_to_reputation
+            .get(peer)
+            .map(|reputation| reputation.clone())
+    }
+
+    /// Returns a sorted copy of all peer reputations
+    pub fn get_all_reputations(&self) -> BTreeMap<PeerNetworkId, PeerReputation> {
+        self.peer_to_reputation
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect()
+    }
+
+    /// Returns true iff the given peer is currently banned
+    pub fn is_banned(&self, peer: &PeerNetworkId) -> bool {
+        let now_secs = self.time_service.now_secs();
+        self.peer_to_reputation
+            .get(peer)
+            .is_some_and(|reputation| reputation.is_banned(now_secs))
+    }
+
+    /// Records the latest score of the given peer
+    pub fn record_score(&self, peer: PeerNetworkId, score: f64) {
+        let now_secs = self.time_service.now_secs();
+        let mut reputation = self.peer_to_reputation.entry(peer).or_default();
+        reputation.score = score;
+        reputation.last_seen_secs = Some(now_secs);
+    }
+
+    /// Records a failure for the given peer (and bans the peer if required)
+    pub fn record_failure(&self, peer: PeerNetworkId, failure: PeerFailure) {
+        let now_secs = self.time_service.now_secs();
+        let ban_duration = {
+            let mut reputation = self.peer_to_reputation.entry(peer).or_default();
+            reputation.last_seen_secs = Some(now_secs);
+            reputation.record_failure(&self.config, failure, now_secs)
+        };
+
+        // Log if the peer was banned
+        if let Some(ban_duration) = ban_duration {
+            warn!(
+                (LogSchema::new(LogEntry::PeerStates)
+                    .event(LogEvent::PeerBanned)
+                    .message(&format!(
+                        "Peer has been banned for {:?} (too many proof verification failures)",
+                        ban_duration
+                    ))
+                    .peer(&peer))
+            );
+        }
+    }
+
+    /// Refreshes the last seen time of the given connected peers, and removes
+    /// the reputations of disconnected peers that haven't been seen for longer
+    /// than the configured TTL. The reputations of banned peers are kept until
+    /// their bans expire, so that disconnecting doesn't lift a ban early.
+    /// Peers without a last seen time (e.g., loaded from an older file) are
+    /// treated as seen now.
+    pub fn prune_disconnected_peers(&self, connected_peers: &HashSet<PeerNetworkId>) {
+        let now_secs = self.time_service.now_secs();
+        let disconnected_peer_ttl_secs = self.config.disconnected_peer_ttl_secs;
+        self.peer_to_reputation.retain(|peer, reputation| {
+            if connected_peers.contains(peer) {
+                reputation.last_seen_secs = Some(now_secs);
+                return true;
+            }
+            let last_seen_secs = *reputation.last_seen_secs.get_or_insert(now_secs);
+            reputation.is_banned(now_secs)
+                || now_secs < last_seen_secs.saturating_add(disconnected_peer_ttl_secs)
+        });
+    }
+
+    /// Persists the peer reputations to disk (if persistence is enabled
+    /// and the persistence interval has elapsed).
+    pub fn persist_if_required(&self) {
+        let Some(reputation_file) = &self.reputation_file else {
+            return; // Persistence is disabled
+        };
+
+        // Check if the persistence interval has elapsed
+        let now_secs = self.time_service.now_secs();
+        let last_persisted_secs = self.last_persisted_secs.load(Ordering::Relaxed);
+        if now_secs < last_persisted_secs.saturating_add(self.config.persistence_interval_secs) {
+            return;
+        }
+        self.last_persisted_secs.store(now_secs, Ordering::Relaxed);
+
+        // Persist the reputations
+        let peer_reputations: Vec<_> = self.get_all_reputations().into_iter().collect();
+        if let Err(error) = store_peer_reputations(reputation_file, &peer_reputations) {
+            sample!(
+                SampleRate::Duration(Duration::from_secs(PERSISTENCE_LOG_FREQ_SECS)),
+                warn!(
+                    (LogSchema::new(LogEntry::PeerStates)
+                        .event(LogEvent::PeerReputationPersistence)
+                        .message(&format!(
+                            "Failed to persist the peer reputations to {:?}! Error: {:?}",
+                            reputation_file, error
+                        )))
+                );
+            );
+        }
+    }
+}
+
+/// Loads the peer reputations from the given file. If the file doesn't
+/// exist or can't be parsed, no reputations are returned.
+fn load_peer_reputations(reputation_file: &Path) -> Vec<(PeerNetworkId, PeerReputation)> {
+    let result = fs::read(reputation_file)
+        .map_err(|error| error.to_string())
+        .and_then(|bytes| serde_json::from_slice(&bytes).map_err(|error| error.to_string()));
+    match result {
+        Ok(peer_reputations) => peer_reputations,
+        Err(error) => {
+            if reputation_file.exists() {
+                warn!(
+                    (LogSchema::new(LogEntry::PeerStates)
+                        .event(LogEvent::PeerReputationPersistence)
+                        .message(&format!(
+                            "Failed to load the peer reputations from {:?}! Error: {:?}",
+                            reputation_file, error
+                        )))
+                );
+            }
+            vec![]
+        },
+    }
+}
+
+/// Stores the peer reputations in the given file. The reputations are first
+/// written to a temporary file, so that the file is never left half-written.
+fn store_peer_reputations(
+    reputation_file: &Path,
+    peer_reputations: &[(PeerNetworkId, PeerReputation)],
+) -> std::io::Result<()> {
+    let bytes = serde_json::to_vec(peer_reputations)
+        .map_err(|error| std::io::Error::new(ErrorKind::InvalidData, error))?;
+    let temp_file = reputation_file.with_extension("json.tmp");
+    fs::write(&temp_file, bytes)?;
+    fs::rename(&temp_file, reputation_file)
+}
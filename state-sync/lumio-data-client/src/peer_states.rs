@@ -3,9 +3,9 @@
 
 use crate::{
     global_summary::{AdvertisedData, GlobalDataSummary, OptimalChunkSizes},
-    interface::ResponseError,
     logging::{LogEntry, LogEvent, LogSchema},
     metrics,
+    peer_reputation::{PeerFailure, PeerReputations},
 };
 use lumio_config::{
     config::LumioDataClientConfig,
@@ -15,13 +15,13 @@ use lumio_logger::prelude::*;
 use lumio_storage_service_types::{
     requests::StorageServiceRequest, responses::StorageServerSummary,
 };
-use lumio_time_service::TimeService;
+use lumio_time_service::{TimeService, TimeServiceTrait};
 use dashmap::DashMap;
 use std::{
     cmp::min,
     collections::{BTreeMap, HashSet},
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 // Useful constants
@@ -32,7 +32,7 @@ const NUM_PEER_BUCKETS_FOR_METRICS: u8 = 4; // To avoid metric explosion, we buc
 /// Scores for peer rankings based on preferences and behavior.
 const MAX_SCORE: f64 = 100.0;
 const MIN_SCORE: f64 = 0.0;
-const STARTING_SCORE: f64 = 50.0;
+pub(crate) const STARTING_SCORE: f64 = 50.0;
 /// Add this score on a successful response.
 const SUCCESSFUL_RESPONSE_DELTA: f64 = 1.0;
 /// Not necessarily a malicious response, but not super useful.
@@ -51,17 +51,6 @@ pub enum ErrorType {
     Malicious,
 }
 
-impl From<ResponseError> for ErrorType {
-    fn from(error: ResponseError) -> Self {
-        match error {
-            ResponseError::InvalidData | ResponseError::InvalidPayloadDataType => {
-                ErrorType::NotUseful
-            },
-            ResponseError::ProofVerificationError => ErrorType::Malicious,
-        }
-    }
-}
-
 #[derive(Clone, Debug)]
 pub struct PeerState {
     /// The data client configuration
@@ -79,16 +68,21 @@ pub struct PeerState {
 
     /// For now, a simplified port of the original state-sync v1 scoring system.
     score: f64,
+
+    /// The time until which the peer should not be sent (non-realtime) requests,
+    /// because the peer recently throttled one of our requests.
+    throttled_until: Option<Instant>,
 }
 
 impl PeerState {
-    pub fn new(data_client_config: Arc<LumioDataClientConfig>) -> Self {
+    pub fn new(data_client_config: Arc<LumioDataClientConfig>, score: f64) -> Self {
         Self {
             data_client_config,
             received_responses_by_type: Arc::new(DashMap::new()),
             sent_requests_by_type: Arc::new(DashMap::new()),
             storage_summary: None,
-            score: STARTING_SCORE,
+            score,
+            throttled_until: None,
         }
     }
 }
@@ -159,6 +153,18 @@ impl PeerState {
         self.score <= IGNORE_PEER_THRESHOLD
     }
 
+    /// Returns true iff the peer is cooling down after throttling our requests
+    fn is_throttled(&self, time_service: &TimeService) -> bool {
+        self.throttled_until
+            .is_some_and(|throttled_until| time_service.now() < throttled_until)
+    }
+
+    /// Starts the throttling cooldown for the peer
+    fn update_throttled(&mut self, time_service: &TimeService) {
+        let cooldown = Duration::from_millis(self.data_client_config.throttled_peer_cooldown_ms);
+        self.throttled_until = Some(time_service.now() + cooldown);
+    }
+
     /// Updates the score of the peer according to a successful operation
     fn update_score_success(&mut self) {
         self.score = f64::min(self.score + SUCCESSFUL_RESPONSE_DELTA, MAX_SCORE);
@@ -184,17 +190,27 @@ impl PeerState {
 #[derive(Clone, Debug)]
 pub struct PeerStates {
     data_client_config: Arc<LumioDataClientConfig>,
+    peer_reputations: Arc<PeerReputations>,
     peer_to_state: Arc<DashMap<PeerNetworkId, PeerState>>,
 }
 
 impl PeerStates {
-    pub fn new(data_client_config: Arc<LumioDataClientConfig>) -> Self {
+    pub fn new(
+        data_client_config: Arc<LumioDataClientConfig>,
+        peer_reputations: Arc<PeerReputations>,
+    ) -> Self {
         Self {
             data_client_config,
+            peer_reputations,
             peer_to_state: Arc::new(DashMap::new()),
         }
     }
 
+    /// Returns true iff the peer is currently banned (and peers should be ignored)
+    fn is_banned(&self, peer: &PeerNetworkId) -> bool {
+        self.data_client_config.ignore_low_score_peers && self.peer_reputations.is_banned(peer)
+    }
+
     /// Returns true if a connected storage service peer can actually fulfill a
     /// request, given our current view of their advertised data summary.
     pub fn can_service_request(
@@ -212,8 +228,23 @@ impl PeerStates {
             return true;
         }
 
+        // Banned peers cannot service any other requests
+        if self.is_banned(peer) {
+            return false;
+        }
+
         // Check if the peer can service the request
         if let Some(peer_state) = self.peer_to_state.get(peer) {
+            // Peers that recently throttled our requests are skipped until
+            // the cooldown expires. Optimistic fetches and subscriptions
+            // are never throttled, so these are still sent to the peer.
+            if !request.data_request.is_optimistic_fetch()
+                && !request.data_request.is_subscription_request()
+                && peer_state.is_throttled(&time_service)
+            {
+                return false;
+            }
+
             return match peer_state.get_storage_summary_if_not_ignored() {
                 Some(storage_summary) => {
                     storage_summary.can_service(&self.data_client_config, time_service, request)
@@ -273,10 +304,15 @@ impl PeerStates {
         // Periodically update the metrics for ignored peers
         sample!(
             SampleRate::Duration(Duration::from_secs(METRICS_FREQUENCY_SECS)),
-            update_peer_ignored_metrics(self.peer_to_state.clone());
+            update_peer_ignored_metrics(self.clone());
         );
     }
 
+    /// Persists the peer reputations (if required)
+    pub fn persist_peer_reputations(&self) {
+        self.peer_reputations.persist_if_required();
+    }
+
     /// Updates the score of the peer according to a successful operation
     pub fn update_score_success(&self, peer: PeerNetworkId) {
         if let Some(mut entry) = self.peer_to_state.get_mut(&peer) {
@@ -285,9 +321,10 @@ impl PeerStates {
 
             // Update the peer's score with a successful operation
             entry.update_score_success();
+            let new_score = entry.score;
+            self.peer_reputations.record_score(peer, new_score);
 
             // Log if the peer is no longer ignored
-            let new_score = entry.score;
             if old_score <= IGNORE_PEER_THRESHOLD && new_score > IGNORE_PEER_THRESHOLD {
                 info!(
                     (LogSchema::new(LogEntry::PeerStates)
@@ -299,17 +336,21 @@ impl PeerStates {
         }
     }
 
-    /// Updates the score of the peer according to an error
-    pub fn update_score_error(&self, peer: PeerNetworkId, error: ErrorType) {
+    /// Updates the score (and reputation) of the peer according to a failure
+    pub fn update_score_error(&self, peer: PeerNetworkId, failure: PeerFailure) {
+        // Record the failure in the peer's reputation
+        self.peer_reputations.record_failure(peer, failure);
+
         if let Some(mut entry) = self.peer_to_state.get_mut(&peer) {
             // Get the peer's old score
             let old_score = entry.score;
 
             // Update the peer's score with an error
-            entry.update_score_error(error);
+            entry.update_score_error(failure.get_error_type());
+            let new_score = entry.score;
+            self.peer_reputations.record_score(peer, new_score);
 
             // Log if the peer is now ignored
-            let new_score = entry.score;
             if old_score > IGNORE_PEER_THRESHOLD && new_score <= IGNORE_PEER_THRESHOLD {
                 info!(
                     (LogSchema::new(LogEntry::PeerStates)
@@ -321,11 +362,22 @@ impl PeerStates {
         }
     }
 
+    /// Updates the state of the peer after it throttled one of our requests
+    pub fn update_throttled_peer(&self, peer: PeerNetworkId, time_service: TimeService) {
+        if let Some(mut entry) = self.peer_to_state.get_mut(&peer) {
+            entry.update_throttled(&time_service);
+        }
+    }
+
     /// Updates the storage summary for the given peer
     pub fn update_summary(&self, peer: PeerNetworkId, storage_summary: StorageServerSummary) {
         self.peer_to_state
             .entry(peer)
-            .or_insert(PeerState::new(self.data_client_config.clone()))
+            .or_insert_with(|| {
+                // Initialize the peer's score from its reputation
+                let score = self.peer_reputations.get_score(&peer);
+                PeerState::new(self.data_client_config.clone(), score)
+            })
             .update_storage_summary(storage_summary);
     }
 
@@ -333,14 +385,19 @@ impl PeerStates {
     pub fn garbage_collect_peer_states(&self, connected_peers: HashSet<PeerNetworkId>) {
         self.peer_to_state
             .retain(|peer_network_id, _| connected_peers.contains(peer_network_id));
+
+        // Prune the reputations of peers that have been disconnected for too long
+        self.peer_reputations
+            .prune_disconnected_peers(&connected_peers);
     }
 
     /// Calculates a global data summary using all known storage summaries
     pub fn calculate_global_data_summary(&self) -> GlobalDataSummary {
-        // Gather all storage summaries, but exclude peers that are ignored
+        // Gather all storage summaries, but exclude peers that are ignored or banned
         let storage_summaries: Vec<StorageServerSummary> = self
             .peer_to_state
             .iter()
+            .filter(|peer_state| !self.is_banned(peer_state.key()))
             .filter_map(|peer_state| {
                 peer_state
                     .value()
@@ -411,6 +468,11 @@ impl PeerStates {
     pub fn get_peer_to_states(&self) -> Arc<DashMap<PeerNetworkId, PeerState>> {
         self.peer_to_state.clone()
     }
+
+    /// Returns the peer reputations
+    pub fn get_peer_reputations(&self) -> Arc<PeerReputations> {
+        self.peer_reputations.clone()
+    }
 }
 
 /// To calculate the optimal chunk size, we take the median for each
@@ -462,11 +524,11 @@ pub fn get_bucket_id_for_peer(peer: PeerNetworkId) -> u8 {
     peer_id_bytes[0] % NUM_PEER_BUCKETS_FOR_METRICS
 }
 
-/// Updates the metrics for the number of ignored peers
-fn update_peer_ignored_metrics(peer_to_state: Arc<DashMap<PeerNetworkId, PeerState>>) {
+/// Updates the metrics for the number of ignored (and banned) peers
+fn update_peer_ignored_metrics(peer_states: PeerStates) {
     // Collect the ignored peer counts by network
     let mut ignored_peer_counts_by_network: BTreeMap<NetworkId, u64> = BTreeMap::new();
-    for peer_state_entry in peer_to_state.iter() {
+    for peer_state_entry in peer_states.peer_to_state.iter() {
         // Get the peer and state
         let peer = *peer_state_entry.key();
         let network_id = peer.network_id();
@@ -477,8 +539,8 @@ fn update_peer_ignored_metrics(peer_to_state: Arc<DashMap<PeerNetworkId, PeerSta
             .entry(network_id)
             .or_default();
 
-        // If the peer is ignored, increment the count
-        if peer_state.is_ignored() {
+        // If the peer is ignored or banned, increment the count
+        if peer_state.is_ignored() || peer_states.is_banned(&peer) {
             *network_count_entry += 1;
         }
     }
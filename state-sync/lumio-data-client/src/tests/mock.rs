@@ -31,12 +31,16 @@ use lumio_storage_interface::DbReader;
 use lumio_storage_service_client::StorageServiceClient;
 use lumio_storage_service_server::network::{NetworkRequest, ResponseSender};
 use lumio_storage_service_types::{
-    responses::TransactionOrOutputListWithProofV2, Epoch, StorageServiceMessage,
+    responses::{FilteredStateValuesWithProof, TransactionOrOutputListWithProofV2},
+    Epoch, StorageServiceMessage,
 };
 use lumio_time_service::{MockTimeService, TimeService};
 use lumio_types::{
     ledger_info::LedgerInfoWithSignatures,
-    state_store::state_value::StateValueChunkWithProof,
+    state_store::{
+        state_key::{prefix::StateKeyPrefix, StateKey},
+        state_value::StateValueChunkWithProof,
+    },
     transaction::{TransactionListWithProofV2, TransactionOutputListWithProofV2, Version},
     PeerId,
 };
@@ -315,6 +319,15 @@ mock! {
             request_timeout_ms: u64,
         ) -> Result<Response<StateValueChunkWithProof>>;
 
+        async fn get_filtered_state_values_with_proof(
+            &self,
+            version: Version,
+            key_prefix: StateKeyPrefix,
+            start_key: Option<StateKey>,
+            known_keys: Vec<StateKey>,
+            request_timeout_ms: u64,
+        ) -> Result<Response<FilteredStateValuesWithProof>>;
+
         async fn get_transaction_outputs_with_proof(
             &self,
             proof_version: Version,
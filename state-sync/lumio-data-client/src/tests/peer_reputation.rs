@@ -0,0 +1,211 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    peer_reputation::{
+        calculate_ban_duration_secs, PeerFailure, PeerReputations, PEER_REPUTATION_FILE_NAME,
+    },
+    peer_states::STARTING_SCORE,
+};
+use lumio_config::{config::LumioPeerReputationConfig, network_id::PeerNetworkId};
+use lumio_temppath::TempPath;
+use lumio_time_service::TimeService;
+use std::collections::HashSet;
+
+#[test]
+fn test_ban_duration_doubles() {
+    // Create a peer reputation config
+    let peer_reputation_config = LumioPeerReputationConfig {
+        initial_ban_duration_secs: 10,
+        max_ban_duration_secs: 100,
+        ..Default::default()
+    };
+
+    // Verify the ban durations double with each ban (up to the maximum)
+    for (num_previous_bans, expected_ban_duration_secs) in [
+        (0, 10),
+        (1, 20),
+        (2, 40),
+        (3, 80),
+        (4, 100),
+        (5, 100),
+        (100, 100),
+    ] {
+        assert_eq!(
+            calculate_ban_duration_secs(&peer_reputation_config, num_previous_bans),
+            expected_ban_duration_secs
+        );
+    }
+}
+
+#[test]
+fn test_proof_verification_failures_ban_peer() {
+    // Create the peer reputations
+    let peer_reputation_config = LumioPeerReputationConfig {
+        enable_persistence: false,
+        ..Default::default()
+    };
+    let time_service = TimeService::mock();
+    let peer_reputations = PeerReputations::new(peer_reputation_config, None, time_service.clone());
+
+    // Record many timeouts and invalid responses, and verify the peer is not banned
+    let peer = PeerNetworkId::random();
+    for _ in 0..100 {
+        peer_reputations.record_failure(peer, PeerFailure::Timeout);
+        peer_reputations.record_failure(peer, PeerFailure::InvalidResponse);
+    }
+    assert!(!peer_reputations.is_banned(&peer));
+
+    // Record proof verification failures and verify the peer is eventually banned
+    let max_proof_verification_failures = peer_reputation_config.max_proof_verification_failures;
+    for _ in 0..max_proof_verification_failures - 1 {
+        peer_reputations.record_failure(peer, PeerFailure::ProofVerification);
+        assert!(!peer_reputations.is_banned(&peer));
+    }
+    peer_reputations.record_failure(peer, PeerFailure::ProofVerification);
+    assert!(peer_reputations.is_banned(&peer));
+
+    // Verify the failures are recorded separately
+    let reputation = peer_reputations.get_reputation(&peer).unwrap();
+    assert_eq!(reputation.get_num_timeouts(), 100);
+    assert_eq!(reputation.get_num_invalid_responses(), 100);
+    assert_eq!(
+        reputation.get_num_proof_verification_failures(),
+        max_proof_verification_failures
+    );
+    assert_eq!(reputation.get_num_bans(), 1);
+
+    // Elapse the ban and verify the peer is no longer banned
+    let mock_time = time_service.into_mock();
+    mock_time.advance_secs(peer_reputation_config.initial_ban_duration_secs);
+    assert!(!peer_reputations.is_banned(&peer));
+
+    // Ban the peer again and verify the ban duration has doubled
+    for _ in 0..max_proof_verification_failures {
+        peer_reputations.record_failure(peer, PeerFailure::ProofVerification);
+    }
+    assert!(peer_reputations.is_banned(&peer));
+    mock_time.advance_secs(peer_reputation_config.initial_ban_duration_secs);
+    assert!(peer_reputations.is_banned(&peer));
+    mock_time.advance_secs(peer_reputation_config.initial_ban_duration_secs);
+    assert!(!peer_reputations.is_banned(&peer));
+    assert_eq!(
+        peer_reputations
+            .get_reputation(&peer)
+            .unwrap()
+            .get_num_bans(),
+        2
+    );
+}
+
+#[test]
+fn test_peer_reputations_are_persisted() {
+    // Create a temporary directory for the reputation file
+    let temp_dir = TempPath::new();
+    temp_dir.create_as_dir().unwrap();
+    let reputation_file = temp_dir.path().join(PEER_REPUTATION_FILE_NAME);
+
+    // Create the peer reputations
+    let peer_reputation_config = LumioPeerReputationConfig::default();
+    let time_service = TimeService::mock();
+    let peer_reputations = PeerReputations::new(
+        peer_reputation_config,
+        Some(reputation_file.clone()),
+        time_service.clone(),
+    );
+
+    // Update the reputations of several peers and ban one of them
+    let good_peer = PeerNetworkId::random();
+    let bad_peer = PeerNetworkId::random();
+    peer_reputations.record_score(good_peer, 75.0);
+    for _ in 0..peer_reputation_config.max_proof_verification_failures {
+        peer_reputations.record_failure(bad_peer, PeerFailure::ProofVerification);
+    }
+    assert!(peer_reputations.is_banned(&bad_peer));
+
+    // Verify the reputations aren't persisted before the persistence interval elapses
+    peer_reputations.persist_if_required();
+    assert!(!reputation_file.exists());
+
+    // Elapse the persistence interval and verify the reputations are persisted
+    let mock_time = time_service.clone().into_mock();
+    mock_time.advance_secs(peer_reputation_config.persistence_interval_secs);
+    peer_reputations.persist_if_required();
+    assert!(reputation_file.exists());
+
+    // Reload the reputations and verify they're identical
+    let reloaded_peer_reputations =
+        PeerReputations::new(peer_reputation_config, Some(reputation_file), time_service);
+    assert_eq!(
+        reloaded_peer_reputations.get_all_reputations(),
+        peer_reputations.get_all_reputations()
+    );
+    assert_eq!(reloaded_peer_reputations.get_score(&good_peer), 75.0);
+    assert!(reloaded_peer_reputations.is_banned(&bad_peer));
+
+    // Verify unknown peers have the starting score
+    let unknown_peer = PeerNetworkId::random();
+    assert_eq!(
+        reloaded_peer_reputations.get_score(&unknown_peer),
+        STARTING_SCORE
+    );
+    assert!(!reloaded_peer_reputations.is_banned(&unknown_peer));
+}
+
+#[test]
+fn test_disconnected_peer_reputations_are_pruned() {
+    // Create the peer reputations
+    let peer_reputation_config = LumioPeerReputationConfig {
+        disconnected_peer_ttl_secs: 1000,
+        enable_persistence: false,
+        initial_ban_duration_secs: 5000,
+        max_ban_duration_secs: 5000,
+        ..Default::default()
+    };
+    let time_service = TimeService::mock();
+    let peer_reputations = PeerReputations::new(peer_reputation_config, None, time_service.clone());
+
+    // Record the reputations of a connected, a disconnected and a banned peer
+    let connected_peer = PeerNetworkId::random();
+    let disconnected_peer = PeerNetworkId::random();
+    let banned_peer = PeerNetworkId::random();
+    peer_reputations.record_score(connected_peer, 75.0);
+    peer_reputations.record_score(disconnected_peer, 75.0);
+    for _ in 0..peer_reputation_config.max_proof_verification_failures {
+        peer_reputations.record_failure(banned_peer, PeerFailure::ProofVerification);
+    }
+    assert!(peer_reputations.is_banned(&banned_peer));
+
+    // Verify no reputations are pruned before the TTL elapses
+    let connected_peers = HashSet::from([connected_peer]);
+    let mock_time = time_service.into_mock();
+    mock_time.advance_secs(peer_reputation_config.disconnected_peer_ttl_secs - 1);
+    peer_reputations.prune_disconnected_peers(&connected_peers);
+    assert_eq!(peer_reputations.get_all_reputations().len(), 3);
+
+    // Elapse the TTL and verify only the disconnected (unbanned) peer is pruned
+    mock_time.advance_secs(1);
+    peer_reputations.prune_disconnected_peers(&connected_peers);
+    assert!(peer_reputations
+        .get_reputation(&disconnected_peer)
+        .is_none());
+    assert_eq!(
+        peer_reputations.get_score(&disconnected_peer),
+        STARTING_SCORE
+    );
+    assert_eq!(peer_reputations.get_score(&connected_peer), 75.0);
+    assert!(peer_reputations.is_banned(&banned_peer));
+
+    // Verify the connected peer was marked as seen
+    let connected_reputation = peer_reputations.get_reputation(&connected_peer).unwrap();
+    assert_eq!(
+        connected_reputation.get_last_seen_secs(),
+        Some(peer_reputation_config.disconnected_peer_ttl_secs)
+    );
+
+    // Elapse the ban and verify the banned peer is pruned (it was last seen long ago)
+    mock_time.advance_secs(peer_reputation_config.initial_ban_duration_secs);
+    peer_reputations.prune_disconnected_peers(&connected_peers);
+    assert!(peer_reputations.get_reputation(&banned_peer).is_none());
+    assert!(peer_reputations.get_reputation(&connected_peer).is_some());
+}
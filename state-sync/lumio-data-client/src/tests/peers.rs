@@ -358,6 +358,16 @@ async fn bad_peer_is_eventually_added_back() {
             .transactions
             .contains(&transaction_range));
 
+        // Elapse the ban duration (the peer was banned for failing
+        // proof verification too many times).
+        mock_time
+            .advance_secs_async(
+                data_client_config
+                    .peer_reputation_config
+                    .initial_ban_duration_secs,
+            )
+            .await;
+
         // Keep elapsing time so the peer is eventually added back (it
         // will still respond to the storage summary requests).
         for _ in 0..10 {
@@ -679,6 +689,68 @@ async fn single_good_peer_across_priorities() {
     }
 }
 
+#[tokio::test]
+async fn throttled_peer_is_not_banned() {
+    // Ensure the properties hold for all peer priorities
+    for peer_priority in PeerPriority::get_all_ordered_priorities() {
+        // Create a base config for a validator
+        let base_config = utils::create_validator_base_config();
+
+        // Create a data client config with peer ignoring enabled
+        let data_client_config = LumioDataClientConfig {
+            ignore_low_score_peers: true,
+            ..Default::default()
+        };
+
+        // Create the mock network and client
+        let (mut mock_network, mock_time, client, _) =
+            MockNetwork::new(Some(base_config), Some(data_client_config), None);
+
+        // Add a peer that advertises txns 0 -> 200
+        let (throttled_peer, network_id) =
+            utils::add_peer_to_network(peer_priority, &mut mock_network);
+        client.update_peer_storage_summary(throttled_peer, utils::create_storage_summary(200));
+        client.update_global_summary_cache().unwrap();
+
+        // Spawn a handler for the peer that throttles all requests
+        // (alternating between the legacy and the new throttling errors).
+        tokio::spawn(async move {
+            let mut legacy_throttling_error = false;
+            while let Some(network_request) = mock_network.next_request(network_id).await {
+                send_throttled_response(network_request, legacy_throttling_error);
+                legacy_throttling_error = !legacy_throttling_error;
+            }
+        });
+
+        // Send a bunch of requests and verify they are all throttled
+        let response_timeout_ms = data_client_config.response_timeout_ms;
+        for _ in 0..20 {
+            let result = client
+                .get_transactions_with_proof(200, 200, 200, false, response_timeout_ms)
+                .await;
+            assert_matches!(result, Err(Error::RequestThrottled(_)));
+
+            // Verify the peer is skipped during the cooldown
+            let result = client
+                .get_transactions_with_proof(200, 200, 200, false, response_timeout_ms)
+                .await;
+            assert_matches!(result, Err(Error::DataIsUnavailable(_)));
+
+            // Elapse the cooldown
+            mock_time.advance_ms(data_client_config.throttled_peer_cooldown_ms);
+        }
+
+        // Verify the global summary still contains the peer's advertisement
+        client.update_global_summary_cache().unwrap();
+        let global_summary = client.get_global_data_summary();
+        let transaction_range = CompleteDataRange::new(0, 200).unwrap();
+        assert!(global_summary
+            .advertised_data
+            .transactions
+            .contains(&transaction_range));
+    }
+}
+
 /// Emulates network latencies by sleeping for some amount of time.
 /// If no duration is specified, the sleep duration is randomly chosen.
 async fn emulate_network_latencies(sleep_duration_ms: Option<u64>) {
@@ -720,6 +792,17 @@ fn send_error_response(network_request: NetworkRequest) {
         )));
 }
 
+/// Sends a request throttled response to the specified network request
+fn send_throttled_response(network_request: NetworkRequest, legacy_throttling_error: bool) {
+    let error_message = "Too many requests! Back off!".to_string();
+    let error = if legacy_throttling_error {
+        StorageServiceError::TooManyInvalidRequests(error_message)
+    } else {
+        StorageServiceError::RequestThrottled(error_message)
+    };
+    network_request.response_sender.send(Err(error));
+}
+
 /// Sends a transaction response to the specified network request
 fn send_transaction_response(network_request: NetworkRequest) {
     // Create the storage service response
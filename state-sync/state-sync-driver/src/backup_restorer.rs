@@ -0,0 +1,823 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::Error,
+    logging::{LogEntry, LogSchema},
+};
+use anyhow::{anyhow, ensure};
+use async_trait::async_trait;
+use lumio_accumulator::{HashReader, MerkleAccumulator};
+use lumio_backup_cli::{
+    backup_types::{
+        state_snapshot::{
+            manifest::{StateSnapshotBackup, StateSnapshotChunk},
+            restore::StateSnapshotRestoreController,
+        },
+        transaction::{
+            manifest::{TransactionBackup, TransactionChunk},
+            restore::LoadedChunk,
+        },
+    },
+    metadata::{
+        cache::{sync_and_load, MetadataCacheOpt},
+        view::MetadataView,
+    },
+    storage::{
+        command_adapter::{config::CommandAdapterConfig, CommandAdapter},
+        local_fs::LocalFs,
+        BackupStorage,
+    },
+    utils::storage_ext::BackupStorageExt,
+};
+use lumio_config::config::BackupRestoreConfig;
+use lumio_crypto::{
+    hash::{CryptoHash, TransactionAccumulatorHasher, ACCUMULATOR_PLACEHOLDER_HASH},
+    HashValue,
+};
+use lumio_data_streaming_service::{
+    data_notification::{DataNotification, DataPayload, NotificationId},
+    data_stream::{DataStreamId, DataStreamListener},
+};
+use lumio_logger::prelude::*;
+use lumio_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{
+        accumulator::InMemoryTransactionAccumulator,
+        position::{FrozenSubTreeIterator, Position},
+        SparseMerkleRangeProof, TransactionAccumulatorRangeProof, TransactionInfoListWithProof,
+    },
+    state_store::{
+        state_key::StateKey,
+        state_value::{StateValue, StateValueChunkWithProof},
+    },
+    transaction::{
+        TransactionAuxiliaryData, TransactionOutput, TransactionOutputListWithAuxiliaryInfos,
+        TransactionOutputListWithProof, TransactionOutputListWithProofV2, TransactionStatus,
+        Version,
+    },
+};
+use futures::{channel::mpsc, stream, SinkExt, StreamExt};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+// The data stream IDs used for backup streams. These are disjoint from the
+// IDs of the data streaming service (which start at 0).
+const FIRST_BACKUP_DATA_STREAM_ID: DataStreamId = u64::MAX / 2;
+
+/// The interface used by the bootstrapper to restore state snapshots (and
+/// the transaction outputs they're taken at) from backup storage, and to
+/// replay the backed up transaction outputs after the snapshot.
+#[async_trait]
+pub trait BackupRestorerInterface {
+    /// Returns true iff the given data stream was created by the restorer
+    fn is_backup_stream(&self, data_stream_id: DataStreamId) -> bool;
+
+    /// Returns the versions of all state snapshots in the backup storage
+    /// (ordered from highest to lowest).
+    async fn get_state_snapshot_versions(&mut self) -> Result<Vec<Version>, Error>;
+
+    /// Returns a data stream holding the single transaction output (with
+    /// proof) at the version of the given ledger info.
+    async fn get_transaction_output(
+        &mut self,
+        target_ledger_info: LedgerInfoWithSignatures,
+    ) -> Result<DataStreamListener, Error>;
+
+    /// Returns a data stream holding all state values (with proofs) of the
+    /// state snapshot at the given version, starting at the specified index.
+    async fn get_state_values(
+        &mut self,
+        version: Version,
+        start_index: u64,
+    ) -> Result<DataStreamListener, Error>;
+
+    /// Returns the highest transaction version in the backup storage (if
+    /// any transactions have been backed up).
+    async fn get_highest_transaction_version(&mut self) -> Result<Option<Version>, Error>;
+
+    /// Returns a data stream holding all transaction outputs (with proofs)
+    /// from the start version to the version of the given epoch ending
+    /// ledger info. The outputs are proven against the ledger info.
+    async fn get_transaction_outputs(
+        &mut self,
+        start_version: Version,
+        epoch_ending_ledger_info: LedgerInfoWithSignatures,
+    ) -> Result<DataStreamListener, Error>;
+}
+
+/// A simple component that restores state snapshots (and the transaction
+/// outputs they're taken at) from backup storage, and replays the backed up
+/// transaction outputs after them. The data is served to the bootstrapper as
+/// data streams, so it can be verified and committed exactly like data
+/// fetched from the network.
+pub struct BackupRestorer {
+    // The backup storage and the metadata view of all backups (loaded lazily)
+    backup_storage_and_metadata: Option<(Arc<dyn BackupStorage>, Arc<MetadataView>)>,
+
+    // The config of the backup storage
+    config: BackupRestoreConfig,
+
+    // The ID of the next data stream created by the restorer
+    next_data_stream_id: DataStreamId,
+
+    // The ID of the next data notification sent by the restorer
+    next_notification_id: Arc<AtomicU64>,
+}
+
+impl BackupRestorer {
+    pub fn new(config: BackupRestoreConfig) -> Self {
+        Self {
+            backup_storage_and_metadata: None,
+            config,
+            next_data_stream_id: FIRST_BACKUP_DATA_STREAM_ID,
+            next_notification_id: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Initializes the backup storage and loads the metadata of all backups
+    /// (if this hasn't already been done).
+    async fn get_backup_storage_and_metadata(
+        &mut self,
+    ) -> Result<(Arc<dyn BackupStorage>, Arc<MetadataView>), Error> {
+        if let Some((backup_storage, metadata_view)) = &self.backup_storage_and_metadata {
+            return Ok((backup_storage.clone(), metadata_view.clone()));
+        }
+
+        // Initialize the backup storage
+        let backup_storage: Arc<dyn BackupStorage> =
+            if let Some(local_fs_dir) = &self.config.local_fs_dir {
+                Arc::new(LocalFs::new(local_fs_dir.clone()))
+            } else if let Some(config_path) = &self.config.command_adapter_config {
+                let config = CommandAdapterConfig::load_from_file(config_path)
+                    .await
+                    .map_err(|error| {
+                        Error::BackupStorageError(format!(
+                            "Failed to load the command adapter config! Error: {:?}",
+                            error
+                        ))
+                    })?;
+                Arc::new(CommandAdapter::new(config))
+            } else {
+                return Err(Error::BackupStorageError(
+                    "No backup storage has been configured!".into(),
+                ));
+            };
+
+        // Sync and load the backup metadata
+        info!(LogSchema::new(LogEntry::BackupRestorer)
+            .message("Loading the backup metadata from backup storage!"));
+        let metadata_cache_opt = MetadataCacheOpt::new(self.config.metadata_cache_dir.as_ref());
+        let metadata_view = sync_and_load(
+            &metadata_cache_opt,
+            backup_storage.clone(),
+            self.config.max_concurrent_downloads as usize,
+        )
+        .await
+        .map_err(|error| {
+            Error::BackupStorageError(format!(
+                "Failed to load the backup metadata! Error: {:?}",
+                error
+            ))
+        })?;
+
+        let metadata_view = Arc::new(metadata_view);
+        self.backup_storage_and_metadata = Some((backup_storage.clone(), metadata_view.clone()));
+        Ok((backup_storage, metadata_view))
+    }
+
+    /// Creates a new data stream and returns the listener and notification sender
+    fn create_data_stream(&mut self) -> (DataStreamListener, mpsc::Sender<DataNotification>) {
+        let data_stream_id = self.next_data_stream_id;
+        self.next_data_stream_id += 1;
+
+        let (notification_sender, notification_receiver) =
+            mpsc::channel(self.config.max_concurrent_downloads as usize);
+        (
+            DataStreamListener::new(data_stream_id, notification_receiver),
+            notification_sender,
+        )
+    }
+}
+
+#[async_trait]
+impl BackupRestorerInterface for BackupRestorer {
+    fn is_backup_stream(&self, data_stream_id: DataStreamId) -> bool {
+        (FIRST_BACKUP_DATA_STREAM_ID..self.next_data_stream_id).contains(&data_stream_id)
+    }
+
+    async fn get_state_snapshot_versions(&mut self) -> Result<Vec<Version>, Error> {
+        let (_, metadata_view) = self.get_backup_storage_and_metadata().await?;
+        let mut versions: Vec<_> = metadata_view
+            .all_state_snapshots()
+            .iter()
+            .map(|snapshot| snapshot.version)
+            .collect();
+        versions.sort_unstable_by(|a, b| b.cmp(a));
+        Ok(versions)
+    }
+
+    async fn get_transaction_output(
+        &mut self,
+        target_ledger_info: LedgerInfoWithSignatures,
+    ) -> Result<DataStreamListener, Error> {
+        // Load the output before creating the stream, so that errors are surfaced
+        let (backup_storage, metadata_view) = self.get_backup_storage_and_metadata().await?;
+        let version = target_ledger_info.ledger_info().version();
+        let output_with_proof =
+            load_transaction_output(backup_storage, metadata_view, &target_ledger_info)
+                .await
+                .map_err(|error| {
+                    Error::BackupStorageError(format!(
+                        "Failed to load the transaction output at version {} from backup \
+                        storage! Error: {:?}",
+                        version, error
+                    ))
+                })?;
+
+        let (data_stream, mut notification_sender) = self.create_data_stream();
+        let next_notification_id = self.next_notification_id.clone();
+        tokio::spawn(async move {
+            let data_payload = DataPayload::TransactionOutputsWithProof(output_with_proof);
+            let notification_id = next_notification_id.fetch_add(1, Ordering::Relaxed);
+            if notification_sender
+                .send(DataNotification::new(notification_id, data_payload))
+                .await
+                .is_err()
+            {
+                return; // The stream has already been dropped
+            }
+            send_end_of_stream(notification_sender, next_notification_id).await;
+        });
+
+        Ok(data_stream)
+    }
+
+    async fn get_state_values(
+        &mut self,
+        version: Version,
+        start_index: u64,
+    ) -> Result<DataStreamListener, Error> {
+        let (backup_storage, metadata_view) = self.get_backup_storage_and_metadata().await?;
+        let snapshot = metadata_view
+            .expect_state_snapshot(version)
+            .map_err(|error| Error::BackupStorageError(error.to_string()))?;
+        let manifest: StateSnapshotBackup = backup_storage
+            .load_json_file(&snapshot.manifest)
+            .await
+            .map_err(|error| {
+                Error::BackupStorageError(format!(
+                    "Failed to load the state snapshot manifest at version {}! Error: {:?}",
+                    version, error
+                ))
+            })?;
+
+        let (data_stream, notification_sender) = self.create_data_stream();
+        tokio::spawn(stream_state_values(
+            backup_storage,
+            manifest,
+            start_index,
+            self.config.max_concurrent_downloads as usize,
+            notification_sender,
+            self.next_notification_id.clone(),
+        ));
+
+        Ok(data_stream)
+    }
+
+    async fn get_highest_transaction_version(&mut self) -> Result<Option<Version>, Error> {
+        let (_, metadata_view) = self.get_backup_storage_and_metadata().await?;
+        metadata_view
+            .max_transaction_version()
+            .map_err(|error| Error::BackupStorageError(error.to_string()))
+    }
+
+    async fn get_transaction_outputs(
+        &mut self,
+        start_version: Version,
+        epoch_ending_ledger_info: LedgerInfoWithSignatures,
+    ) -> Result<DataStreamListener, Error> {
+        // Load the transaction chunks before creating the stream, so that errors are surfaced
+        let (backup_storage, metadata_view) = self.get_backup_storage_and_metadata().await?;
+        let end_version = epoch_ending_ledger_info.ledger_info().version();
+        let chunks = load_transaction_chunks(
+            backup_storage.clone(),
+            metadata_view,
+            start_version,
+            end_version,
+        )
+        .await
+        .map_err(|error| {
+            Error::BackupStorageError(format!(
+                "Failed to load the transaction chunks for versions [{}, {}] from backup \
+                storage! Error: {:?}",
+                start_version, end_version, error
+            ))
+        })?;
+
+        let (data_stream, notification_sender) = self.create_data_stream();
+        tokio::spawn(stream_transaction_outputs(
+            backup_storage,
+            chunks,
+            start_version,
+            epoch_ending_ledger_info,
+            self.config.max_concurrent_downloads as usize,
+            notification_sender,
+            self.next_notification_id.clone(),
+        ));
+
+        Ok(data_stream)
+    }
+}
+
+/// Streams the state value chunks of the given state snapshot (starting at
+/// the specified index) to the notification sender. The stream always ends
+/// with an end of stream notification, so that errors are surfaced quickly.
+async fn stream_state_values(
+    backup_storage: Arc<dyn BackupStorage>,
+    manifest: StateSnapshotBackup,
+    start_index: u64,
+    max_concurrent_downloads: usize,
+    mut notification_sender: mpsc::Sender<DataNotification>,
+    next_notification_id: Arc<AtomicU64>,
+) {
+    let version = manifest.version;
+    let root_hash = manifest.root_hash;
+
+    // Download the chunks (in order) that hold state values at or after the start index
+    let chunks = manifest
+        .chunks
+        .into_iter()
+        .filter(|chunk| chunk.last_idx as u64 >= start_index);
+    let mut chunk_stream = stream::iter(chunks.map(|chunk| {
+        let backup_storage = backup_storage.clone();
+        async move {
+            let raw_values = StateSnapshotRestoreController::read_state_value(
+                &backup_storage,
+                chunk.blobs.clone(),
+            )
+            .await?;
+            let proof: SparseMerkleRangeProof = backup_storage.load_bcs_file(&chunk.proof).await?;
+            anyhow::Ok((chunk, raw_values, proof))
+        }
+    }))
+    .buffered(max_concurrent_downloads);
+
+    while let Some(result) = chunk_stream.next().await {
+        let state_value_chunk = result.and_then(|(chunk, raw_values, proof)| {
+            create_state_value_chunk(chunk, raw_values, proof, root_hash, start_index)
+        });
+        match state_value_chunk {
+            Ok(state_value_chunk) => {
+                let data_payload = DataPayload::StateValuesWithProof(state_value_chunk);
+                let notification_id = next_notification_id.fetch_add(1, Ordering::Relaxed);
+                if notification_sender
+                    .send(DataNotification::new(notification_id, data_payload))
+                    .await
+                    .is_err()
+                {
+                    return; // The stream has already been dropped
+                }
+            },
+            Err(error) => {
+                error!(LogSchema::new(LogEntry::BackupRestorer).message(&format!(
+                    "Failed to load a state value chunk at version {} from backup storage! Error: {:?}",
+                    version, error
+                )));
+                break;
+            },
+        }
+    }
+    send_end_of_stream(notification_sender, next_notification_id).await;
+}
+
+/// Streams the transaction outputs from the start version to the version of
+/// the given epoch ending ledger info (one notification per backup chunk).
+/// The stream always ends with an end of stream notification, so that errors
+/// are surfaced quickly.
+///
+/// The chunks are downloaded twice: first to rebuild the transaction
+/// accumulator up to the ledger info (which is verified against its root
+/// hash), and then to prove each chunk against the ledger info.
+async fn stream_transaction_outputs(
+    backup_storage: Arc<dyn BackupStorage>,
+    chunks: Vec<TransactionChunk>,
+    start_version: Version,
+    epoch_ending_ledger_info: LedgerInfoWithSignatures,
+    max_concurrent_downloads: usize,
+    mut notification_sender: mpsc::Sender<DataNotification>,
+    next_notification_id: Arc<AtomicU64>,
+) {
+    let end_version = epoch_ending_ledger_info.ledger_info().version();
+
+    // Rebuild the transaction accumulator up to the epoch ending ledger info
+    let accumulator = match create_transaction_accumulator(
+        backup_storage.clone(),
+        chunks.clone(),
+        &epoch_ending_ledger_info,
+        max_concurrent_downloads,
+    )
+    .await
+    {
+        Ok(accumulator) => accumulator,
+        Err(error) => {
+            error!(LogSchema::new(LogEntry::BackupRestorer).message(&format!(
+                "Failed to rebuild the transaction accumulator up to version {} from backup storage! Error: {:?}",
+                end_version, error
+            )));
+            send_end_of_stream(notification_sender, next_notification_id).await;
+            return;
+        },
+    };
+
+    // Download the chunks (in order) and prove their outputs against the ledger info
+    let mut chunk_stream = stream::iter(chunks.into_iter().map(|chunk| {
+        let backup_storage = backup_storage.clone();
+        async move { LoadedChunk::load(chunk, &backup_storage, None).await }
+    }))
+    .buffered(max_concurrent_downloads);
+
+    while let Some(result) = chunk_stream.next().await {
+        let output_with_proof = result.and_then(|loaded_chunk| {
+            let first_version = loaded_chunk.manifest.first_version.max(start_version);
+            let last_version = loaded_chunk.manifest.last_version.min(end_version);
+            let range_proof = accumulator.get_range_proof(first_version, last_version)?;
+            let output_with_proof = create_transaction_outputs_with_proof(
+                loaded_chunk,
+                first_version,
+                last_version,
+                range_proof,
+            );
+            output_with_proof
+                .verify(epoch_ending_ledger_info.ledger_info(), Some(first_version))?;
+            anyhow::Ok(output_with_proof)
+        });
+        match output_with_proof {
+            Ok(output_with_proof) => {
+                let data_payload = DataPayload::TransactionOutputsWithProof(output_with_proof);
+                let notification_id = next_notification_id.fetch_add(1, Ordering::Relaxed);
+                if notification_sender
+                    .send(DataNotification::new(notification_id, data_payload))
+                    .await
+                    .is_err()
+                {
+                    return; // The stream has already been dropped
+                }
+            },
+            Err(error) => {
+                error!(LogSchema::new(LogEntry::BackupRestorer).message(&format!(
+                    "Failed to load a transaction chunk up to version {} from backup storage! Error: {:?}",
+                    end_version, error
+                )));
+                break;
+            },
+        }
+    }
+    send_end_of_stream(notification_sender, next_notification_id).await;
+}
+
+/// Sends an end of stream notification to the given notification sender
+async fn send_end_of_stream(
+    mut notification_sender: mpsc::Sender<DataNotification>,
+    next_notification_id: Arc<AtomicU64>,
+) {
+    let notification_id: NotificationId = next_notification_id.fetch_add(1, Ordering::Relaxed);
+    let _ = notification_sender
+        .send(DataNotification::new(
+            notification_id,
+            DataPayload::EndOfStream,
+        ))
+        .await;
+}
+
+/// Creates a state value chunk with proof from the given backup chunk. Any
+/// state values before the start index are skipped (they've already been
+/// persisted by a previous restore attempt).
+pub(crate) fn create_state_value_chunk(
+    chunk: StateSnapshotChunk,
+    mut raw_values: Vec<(StateKey, StateValue)>,
+    proof: SparseMerkleRangeProof,
+    root_hash: HashValue,
+    start_index: u64,
+) -> anyhow::Result<StateValueChunkWithProof> {
+    let first_index = chunk.first_idx as u64;
+    let last_index = chunk.last_idx as u64;
+    ensure!(
+        raw_values.len() as u64 == last_index - first_index + 1,
+        "The number of state values in the chunk ({}) doesn't match the chunk indices: [{}, {}]",
+        raw_values.len(),
+        first_index,
+        last_index
+    );
+
+    // Skip the state values that have already been persisted
+    let (first_index, first_key) = if first_index < start_index {
+        raw_values.drain(..(start_index - first_index) as usize);
+        let (first_state_key, _) = raw_values
+            .first()
+            .ok_or_else(|| anyhow!("The chunk holds no state values after the start index!"))?;
+        (start_index, first_state_key.hash())
+    } else {
+        (first_index, chunk.first_key)
+    };
+
+    Ok(StateValueChunkWithProof {
+        first_index,
+        last_index,
+        first_key,
+        last_key: chunk.last_key,
+        raw_values,
+        proof,
+        root_hash,
+    })
+}
+
+/// Loads the manifests of the transaction backups holding the given version
+/// range, and returns the (ordered) chunks that overlap the range.
+async fn load_transaction_chunks(
+    backup_storage: Arc<dyn BackupStorage>,
+    metadata_view: Arc<MetadataView>,
+    start_version: Version,
+    end_version: Version,
+) -> anyhow::Result<Vec<TransactionChunk>> {
+    let mut chunks = vec![];
+    for transaction_backup in
+        metadata_view.select_transaction_backups(start_version, end_version)?
+    {
+        let manifest: TransactionBackup = backup_storage
+            .load_json_file(&transaction_backup.manifest)
+            .await?;
+        manifest.verify()?;
+        chunks.extend(manifest.chunks.into_iter().filter(|chunk| {
+            chunk.last_version >= start_version && chunk.first_version <= end_version
+        }));
+    }
+
+    // Verify the chunks hold the entire range
+    let last_version = chunks.last().map(|chunk| chunk.last_version);
+    ensure!(
+        last_version.is_some_and(|last_version| last_version >= end_version),
+        "The transaction backups end before version {}! Last backed up version: {:?}",
+        end_version,
+        last_version
+    );
+    Ok(chunks)
+}
+
+/// Rebuilds the transaction accumulator up to the version of the given
+/// ledger info from the transaction chunks, and verifies it against the
+/// accumulator root hash of the ledger info.
+async fn create_transaction_accumulator(
+    backup_storage: Arc<dyn BackupStorage>,
+    chunks: Vec<TransactionChunk>,
+    ledger_info: &LedgerInfoWithSignatures,
+    max_concurrent_downloads: usize,
+) -> anyhow::Result<TransactionAccumulatorNodes> {
+    let end_version = ledger_info.ledger_info().version();
+    let mut chunk_stream = stream::iter(chunks.into_iter().map(|chunk| {
+        let backup_storage = backup_storage.clone();
+        async move { LoadedChunk::load(chunk, &backup_storage, None).await }
+    }))
+    .buffered(max_concurrent_downloads);
+
+    let mut accumulator: Option<TransactionAccumulatorNodes> = None;
+    while let Some(loaded_chunk) = chunk_stream.next().await {
+        let loaded_chunk = loaded_chunk?;
+        let accumulator = accumulator.get_or_insert_with(|| {
+            TransactionAccumulatorNodes::new(
+                loaded_chunk.range_proof.left_siblings(),
+                loaded_chunk.manifest.first_version,
+            )
+        });
+        let num_transaction_infos =
+            (end_version + 1).saturating_sub(loaded_chunk.manifest.first_version) as usize;
+        let transaction_info_hashes: Vec<_> = loaded_chunk
+            .txn_infos
+            .iter()
+            .take(num_transaction_infos)
+            .map(CryptoHash::hash)
+            .collect();
+        accumulator.append(&transaction_info_hashes)?;
+    }
+
+    let accumulator = accumulator.ok_or_else(|| anyhow!("No transaction chunks to load!"))?;
+    let root_hash = accumulator.root_hash()?;
+    ensure!(
+        root_hash == ledger_info.ledger_info().transaction_accumulator_hash(),
+        "The transaction accumulator root hash ({}) doesn't match the ledger info at version {}!",
+        root_hash,
+        end_version
+    );
+    Ok(accumulator)
+}
+
+/// The frozen nodes of the transaction accumulator (from the first restored
+/// chunk onwards), used to prove ranges of the restored transactions.
+pub(crate) struct TransactionAccumulatorNodes {
+    frozen_nodes: HashMap<Position, HashValue>,
+    num_leaves: u64,
+}
+
+impl TransactionAccumulatorNodes {
+    /// Creates the accumulator before the given chunk, from the left siblings
+    /// of its range proof (i.e., the frozen subtrees before the chunk, ordered
+    /// from the leaves to the root).
+    pub(crate) fn new(chunk_left_siblings: &[HashValue], chunk_first_version: Version) -> Self {
+        let frozen_nodes = FrozenSubTreeIterator::new(chunk_first_version)
+            .zip(chunk_left_siblings.iter().rev().cloned())
+            .collect();
+        Self {
+            frozen_nodes,
+            num_leaves: chunk_first_version,
+        }
+    }
+
+    /// Appends the given transaction info hashes to the accumulator
+    pub(crate) fn append(&mut self, transaction_info_hashes: &[HashValue]) -> anyhow::Result<()> {
+        let (_, frozen_nodes) = MerkleAccumulator::<Self, TransactionAccumulatorHasher>::append(
+            self,
+            self.num_leaves,
+            transaction_info_hashes,
+        )?;
+        self.frozen_nodes.extend(frozen_nodes);
+        self.num_leaves += transaction_info_hashes.len() as u64;
+        Ok(())
+    }
+
+    /// Returns the root hash of the accumulator
+    pub(crate) fn root_hash(&self) -> anyhow::Result<HashValue> {
+        MerkleAccumulator::<Self, TransactionAccumulatorHasher>::get_root_hash(
+            self,
+            self.num_leaves,
+        )
+    }
+
+    /// Returns the range proof for the given versions, against the root hash
+    /// of the accumulator.
+    pub(crate) fn get_range_proof(
+        &self,
+        first_version: Version,
+        last_version: Version,
+    ) -> anyhow::Result<TransactionAccumulatorRangeProof> {
+        ensure!(
+            first_version <= last_version && last_version < self.num_leaves,
+            "Invalid range [{}, {}] for an accumulator with {} leaves!",
+            first_version,
+            last_version,
+            self.num_leaves
+        );
+        MerkleAccumulator::<Self, TransactionAccumulatorHasher>::get_range_proof(
+            self,
+            self.num_leaves,
+            Some(first_version),
+            last_version - first_version + 1,
+        )
+    }
+}
+
+impl HashReader for TransactionAccumulatorNodes {
+    fn get(&self, position: Position) -> anyhow::Result<HashValue> {
+        self.frozen_nodes
+            .get(&position)
+            .cloned()
+            .ok_or_else(|| anyhow!("Missing transaction accumulator node at {:?}!", position))
+    }
+}
+
+/// Creates the transaction outputs (with proof) for the given version range
+/// of the loaded chunk.
+fn create_transaction_outputs_with_proof(
+    loaded_chunk: LoadedChunk,
+    first_version: Version,
+    last_version: Version,
+    range_proof: TransactionAccumulatorRangeProof,
+) -> TransactionOutputListWithProofV2 {
+    let start_index = (first_version - loaded_chunk.manifest.first_version) as usize;
+    let num_outputs = (last_version - first_version + 1) as usize;
+    let mut transactions_and_outputs = Vec::with_capacity(num_outputs);
+    let mut transaction_infos = Vec::with_capacity(num_outputs);
+    let mut persisted_auxiliary_infos = Vec::with_capacity(num_outputs);
+    for ((((transaction, persisted_auxiliary_info), transaction_info), events), write_set) in
+        loaded_chunk
+            .txns
+            .into_iter()
+            .zip(loaded_chunk.persisted_aux_info)
+            .zip(loaded_chunk.txn_infos)
+            .zip(loaded_chunk.event_vecs)
+            .zip(loaded_chunk.write_sets)
+            .skip(start_index)
+            .take(num_outputs)
+    {
+        let transaction_output = TransactionOutput::new(
+            write_set,
+            events,
+            transaction_info.gas_used(),
+            TransactionStatus::Keep(transaction_info.status().clone()),
+            TransactionAuxiliaryData::default(),
+        );
+        transactions_and_outputs.push((transaction, transaction_output));
+        transaction_infos.push(transaction_info);
+        persisted_auxiliary_infos.push(persisted_auxiliary_info);
+    }
+
+    TransactionOutputListWithProofV2::new(TransactionOutputListWithAuxiliaryInfos::new(
+        TransactionOutputListWithProof::new(
+            transactions_and_outputs,
+            Some(first_version),
+            TransactionInfoListWithProof::new(range_proof, transaction_infos),
+        ),
+        persisted_auxiliary_infos,
+    ))
+}
+
+/// Loads the transaction output at the version of the given ledger info from
+/// the transaction backups, and proves it against the ledger info.
+async fn load_transaction_output(
+    backup_storage: Arc<dyn BackupStorage>,
+    metadata_view: Arc<MetadataView>,
+    target_ledger_info: &LedgerInfoWithSignatures,
+) -> anyhow::Result<TransactionOutputListWithProofV2> {
+    // Find the transaction chunk holding the target version
+    let version = target_ledger_info.ledger_info().version();
+    let transaction_backup = metadata_view
+        .select_transaction_backups(version, version)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("No transaction backup holds version {}!", version))?;
+    let manifest: TransactionBackup = backup_storage
+        .load_json_file(&transaction_backup.manifest)
+        .await?;
+    manifest.verify()?;
+    let chunk = manifest
+        .chunks
+        .into_iter()
+        .find(|chunk| chunk.first_version <= version && version <= chunk.last_version)
+        .ok_or_else(|| anyhow!("No transaction chunk holds version {}!", version))?;
+    let first_version = chunk.first_version;
+    let loaded_chunk = LoadedChunk::load(chunk, &backup_storage, None).await?;
+
+    // Create the range proof for the target version
+    let index = (version - first_version) as usize;
+    let transaction_info_hashes: Vec<_> = loaded_chunk.txn_infos[..index]
+        .iter()
+        .map(CryptoHash::hash)
+        .collect();
+    let range_proof = create_range_proof_at_version(
+        loaded_chunk.range_proof.left_siblings(),
+        first_version,
+        &transaction_info_hashes,
+    )?;
+
+    // Create the transaction output with proof
+    let output_with_proof =
+        create_transaction_outputs_with_proof(loaded_chunk, version, version, range_proof);
+
+    // Verify the output against the target ledger info
+    output_with_proof.verify(target_ledger_info.ledger_info(), Some(version))?;
+    Ok(output_with_proof)
+}
+
+/// Creates the range proof for the transaction at the end of the given chunk
+/// prefix, against the accumulator root at that version (i.e., where the
+/// transaction is the last leaf of the accumulator).
+///
+/// The chunk is identified by the left siblings of its range proof and its
+/// first version. The prefix holds the transaction info hashes of the chunk
+/// before the target version.
+pub(crate) fn create_range_proof_at_version(
+    chunk_left_siblings: &[HashValue],
+    chunk_first_version: Version,
+    transaction_info_hashes: &[HashValue],
+) -> anyhow::Result<TransactionAccumulatorRangeProof> {
+    // The left siblings of the chunk proof are the frozen subtrees of the
+    // accumulator before the chunk (ordered from the leaves to the root).
+    // Append the transaction infos before the target version to get the
+    // frozen subtrees of the accumulator before the target version.
+    let frozen_subtrees = chunk_left_siblings.iter().rev().cloned().collect();
+    let accumulator = InMemoryTransactionAccumulator::new(frozen_subtrees, chunk_first_version)?
+        .append(transaction_info_hashes);
+    let version = accumulator.num_leaves();
+
+    // The left siblings of the target are the frozen subtrees, and all
+    // siblings on the right are placeholders.
+    let left_siblings = accumulator
+        .frozen_subtree_roots()
+        .iter()
+        .rev()
+        .cloned()
+        .collect();
+    let root_level = Position::root_from_leaf_count(version + 1).level();
+    let right_siblings = (0..root_level)
+        .filter(|level| (version >> level) & 1 == 0)
+        .map(|_| *ACCUMULATOR_PLACEHOLDER_HASH)
+        .collect();
+    Ok(TransactionAccumulatorRangeProof::new(
+        left_siblings,
+        right_siblings,
+    ))
+}
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    backup_restorer::{BackupRestorer, BackupRestorerInterface},
     driver::DriverConfiguration,
     error::Error,
     logging::{LogEntry, LogSchema},
@@ -79,6 +80,11 @@ impl VerifiedEpochStates {
         self.fetched_epoch_ending_ledger_infos = true;
     }
 
+    /// Returns the latest epoch state that has been verified by the node
+    pub fn latest_epoch_state(&self) -> &EpochState {
+        &self.latest_epoch_state
+    }
+
     /// Returns true iff the node has verified the waypoint
     pub fn verified_waypoint(&self) -> bool {
         self.verified_waypoint
@@ -291,6 +297,9 @@ pub struct Bootstrapper<MetadataStorage, StorageSyncer, StreamingClient> {
     // The currently active data stream (provided by the data streaming service)
     active_data_stream: Option<DataStreamListener>,
 
+    // The component used to restore state snapshots (if restoring from backups)
+    backup_restorer: Option<Box<dyn BackupRestorerInterface + Send + Sync>>,
+
     // The channel used to notify a listener of successful bootstrapping
     bootstrap_notifier_channel: Option<oneshot::Sender<Result<(), Error>>>,
 
@@ -344,9 +353,23 @@ impl<
             .expect("Unable to fetch latest epoch state!");
         let verified_epoch_states = VerifiedEpochStates::new(latest_epoch_state);
 
+        // Create the backup restorer (if we're restoring from backups)
+        let backup_restorer = if driver_configuration
+            .config
+            .bootstrapping_mode
+            .is_restore_from_backup()
+        {
+            let backup_restorer =
+                BackupRestorer::new(driver_configuration.backup_restore_config.clone());
+            Some(Box::new(backup_restorer) as Box<dyn BackupRestorerInterface + Send + Sync>)
+        } else {
+            None
+        };
+
         Self {
             state_value_syncer: StateValueSyncer::new(),
             active_data_stream: None,
+            backup_restorer,
             bootstrap_notifier_channel: None,
             bootstrapped: false,
             driver_configuration,
@@ -365,11 +388,44 @@ impl<
         self.driver_configuration.config.bootstrapping_mode
     }
 
+    /// Returns the version to bootstrap to (if one has been pinned)
+    fn get_bootstrapping_target_version(&self) -> Option<Version> {
+        self.driver_configuration
+            .config
+            .bootstrapping_target_version
+    }
+
     /// Returns true iff the node has already completed bootstrapping
     pub fn is_bootstrapped(&self) -> bool {
         self.bootstrapped
     }
 
+    /// Returns true iff the bootstrapper is still fetching epoch ending ledger infos
+    pub fn is_fetching_epoch_ending_ledger_infos(&self) -> bool {
+        self.should_fetch_epoch_ending_ledger_infos()
+    }
+
+    /// Returns the version of the highest known ledger info (if any)
+    pub fn get_highest_known_version(&self) -> Option<Version> {
+        self.get_highest_known_ledger_info()
+            .ok()
+            .map(|ledger_info| ledger_info.ledger_info().version())
+    }
+
+    /// Returns the version of the state snapshot being synced and the number
+    /// of states processed thus far (if the node is syncing a state snapshot).
+    pub fn get_state_snapshot_progress(&self) -> Option<(Version, u64)> {
+        self.state_value_syncer
+            .ledger_info_to_sync
+            .as_ref()
+            .map(|ledger_info| {
+                (
+                    ledger_info.ledger_info().version(),
+                    self.state_value_syncer.next_state_index_to_process,
+                )
+            })
+    }
+
     /// Marks bootstrapping as complete and notifies any listeners
     pub async fn bootstrapping_complete(&mut self) -> Result<(), Error> {
         info!(LogSchema::new(LogEntry::Bootstrapper)
@@ -503,6 +559,7 @@ impl<
             self.fetch_missing_state_snapshot_data(
                 highest_synced_version,
                 highest_known_ledger_info,
+                global_data_summary,
             )
             .await
         } else {
@@ -517,10 +574,24 @@ impl<
         &mut self,
         highest_synced_version: Version,
         highest_known_ledger_info: LedgerInfoWithSignatures,
+        global_data_summary: &GlobalDataSummary,
     ) -> Result<(), Error> {
         if highest_synced_version == GENESIS_TRANSACTION_VERSION {
             // We're syncing a new node. Check the progress and fetch any missing data
             if let Some(target) = self.metadata_storage.previous_snapshot_sync_target()? {
+                // Verify the previous target matches any bootstrapping target version
+                if let Some(target_version) = self.get_bootstrapping_target_version() {
+                    if target.ledger_info().version() != target_version {
+                        return Err(Error::UnexpectedError(format!(
+                            "A snapshot sync to version: {:?} was previously started, but the \
+                            bootstrapping target version is: {:?}! Delete your storage to sync \
+                            to the new target version.",
+                            target.ledger_info().version(),
+                            target_version
+                        )));
+                    }
+                }
+
                 if self.metadata_storage.is_snapshot_sync_complete(&target)? {
                     // Fast syncing to the target is complete. Verify that the
                     // highest synced version matches the target.
@@ -542,12 +613,40 @@ impl<
                     // Continue snapshot syncing to the target
                     self.fetch_missing_state_values(target, true).await
                 }
+            } else if self.get_bootstrapping_mode().is_restore_from_backup() {
+                // No snapshot restore has started. Start a new restore for the
+                // highest state snapshot in the backup storage.
+                let target_ledger_info = self.get_backup_restore_target().await?;
+                self.fetch_missing_state_values(target_ledger_info, false)
+                    .await
+            } else if let Some(target_version) = self.get_bootstrapping_target_version() {
+                // No snapshot sync has started. Start a new sync for the target version.
+                let target_ledger_info =
+                    self.get_pinned_snapshot_target(target_version, global_data_summary)?;
+                self.fetch_missing_state_values(target_ledger_info, false)
+                    .await
             } else {
                 // No snapshot sync has started. Start a new sync for the highest known ledger info.
                 self.fetch_missing_state_values(highest_known_ledger_info, false)
                     .await
             }
         } else {
+            // If we've restored from backups, replay the backed up transaction
+            // outputs (epoch by epoch) before handing over to our peers.
+            if self.get_bootstrapping_mode().is_restore_from_backup() {
+                if let Some(epoch_ending_ledger_info) = self
+                    .get_backup_replay_target(highest_synced_version)
+                    .await?
+                {
+                    return self
+                        .replay_backup_transaction_outputs(
+                            highest_synced_version,
+                            epoch_ending_ledger_info,
+                        )
+                        .await;
+                }
+            }
+
             // This node has already synced some state. Ensure the node is not too far behind.
             let highest_known_ledger_version = highest_known_ledger_info.ledger_info().version();
             let num_versions_behind = highest_known_ledger_version
@@ -560,12 +659,33 @@ impl<
                 .config
                 .num_versions_to_skip_snapshot_sync;
 
-            // Check if the node is too far behind to fast sync
-            if num_versions_behind < max_num_versions_behind {
-                info!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
-                    "The node is only {} versions behind, will skip bootstrapping.",
+            // Check if the node is too far behind to fast sync. If we've restored from
+            // backups (or synced to a target version), the node is expected to be behind.
+            let skip_reason = if num_versions_behind < max_num_versions_behind {
+                Some(format!(
+                    "The node is only {} versions behind",
                     num_versions_behind
-                )));
+                ))
+            } else if self.get_bootstrapping_mode().is_restore_from_backup() {
+                Some(format!(
+                    "The node has already restored a state snapshot from backup storage \
+                    (it is {} versions behind)",
+                    num_versions_behind
+                ))
+            } else {
+                self.get_bootstrapping_target_version()
+                    .map(|target_version| {
+                        format!(
+                            "The node has already synced to the bootstrapping target version: {} \
+                            (it is {} versions behind)",
+                            target_version, num_versions_behind
+                        )
+                    })
+            };
+
+            if let Some(skip_reason) = skip_reason {
+                info!(LogSchema::new(LogEntry::Bootstrapper)
+                    .message(&format!("{}, will skip bootstrapping.", skip_reason)));
                 // We've already bootstrapped to an initial state snapshot. If this a fullnode, the
                 // continuous syncer will take control and get the node up-to-date. If this is a
                 // validator, consensus will take control and sync depending on how it sees fit.
@@ -580,6 +700,162 @@ impl<
         }
     }
 
+    /// Returns the ledger info of the highest state snapshot in the backup
+    /// storage that is taken at a verified epoch ending ledger info.
+    async fn get_backup_restore_target(&mut self) -> Result<LedgerInfoWithSignatures, Error> {
+        let backup_restorer = self
+            .backup_restorer
+            .as_mut()
+            .ok_or_else(|| Error::UnexpectedError("The backup restorer does not exist!".into()))?;
+        let snapshot_versions = backup_restorer.get_state_snapshot_versions().await?;
+
+        // If a target version is specified, only the snapshot at that version can be used
+        if let Some(target_version) = self.get_bootstrapping_target_version() {
+            if !snapshot_versions.contains(&target_version) {
+                return Err(Error::UnservableBootstrappingTarget(format!(
+                    "No state snapshot in backup storage is at the target version: {:?}",
+                    target_version
+                )));
+            }
+            return self.get_verified_target_ledger_info(target_version);
+        }
+
+        // Find the highest snapshot that can be verified
+        for snapshot_version in snapshot_versions {
+            if let Some(ledger_info) = self
+                .verified_epoch_states
+                .get_epoch_ending_ledger_info(snapshot_version)
+            {
+                info!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+                    "Found a verified state snapshot in backup storage at version: {:?}",
+                    snapshot_version
+                )));
+                return Ok(ledger_info);
+            }
+        }
+
+        Err(Error::BackupStorageError(
+            "No state snapshot in backup storage matches a verified epoch ending ledger info!"
+                .into(),
+        ))
+    }
+
+    /// Returns the verified epoch ending ledger info of the epoch after the
+    /// highest synced version, if the backup storage holds all transactions
+    /// up to it (i.e., the transaction outputs to replay from backups next).
+    async fn get_backup_replay_target(
+        &mut self,
+        highest_synced_version: Version,
+    ) -> Result<Option<LedgerInfoWithSignatures>, Error> {
+        let Some(epoch_ending_ledger_info) = self
+            .verified_epoch_states
+            .next_epoch_ending_version(highest_synced_version)
+            .and_then(|version| {
+                self.verified_epoch_states
+                    .get_epoch_ending_ledger_info(version)
+            })
+        else {
+            return Ok(None); // No higher epoch ending ledger info is known
+        };
+
+        let backup_restorer = self
+            .backup_restorer
+            .as_mut()
+            .ok_or_else(|| Error::UnexpectedError("The backup restorer does not exist!".into()))?;
+        let highest_backup_version = backup_restorer.get_highest_transaction_version().await?;
+        if highest_backup_version
+            .is_some_and(|version| version >= epoch_ending_ledger_info.ledger_info().version())
+        {
+            Ok(Some(epoch_ending_ledger_info))
+        } else {
+            Ok(None) // The rest of the epoch hasn't been backed up
+        }
+    }
+
+    /// Replays the backed up transaction outputs from the highest synced
+    /// version to the given epoch ending ledger info (which proves them).
+    async fn replay_backup_transaction_outputs(
+        &mut self,
+        highest_synced_version: Version,
+        epoch_ending_ledger_info: LedgerInfoWithSignatures,
+    ) -> Result<(), Error> {
+        let next_version = highest_synced_version.checked_add(1).ok_or_else(|| {
+            Error::IntegerOverflow("The next output version has overflown!".into())
+        })?;
+        info!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+            "Replaying the transaction outputs in backup storage from version {} to the epoch \
+            ending version {}.",
+            next_version,
+            epoch_ending_ledger_info.ledger_info().version()
+        )));
+
+        let backup_restorer = self
+            .backup_restorer
+            .as_mut()
+            .ok_or_else(|| Error::UnexpectedError("The backup restorer does not exist!".into()))?;
+        let data_stream = backup_restorer
+            .get_transaction_outputs(next_version, epoch_ending_ledger_info.clone())
+            .await?;
+        self.speculative_stream_state = Some(SpeculativeStreamState::new(
+            utils::fetch_latest_epoch_state(self.storage.clone())?,
+            Some(epoch_ending_ledger_info),
+            highest_synced_version,
+        ));
+        self.active_data_stream = Some(data_stream);
+
+        Ok(())
+    }
+
+    /// Returns true iff the bootstrapper is replaying transaction outputs
+    /// from backup storage (after restoring the state snapshot).
+    fn is_replaying_backup_transaction_outputs(&self) -> bool {
+        self.get_bootstrapping_mode().is_restore_from_backup()
+            && self.speculative_stream_state.is_some()
+    }
+
+    /// Returns the verified epoch ending ledger info at the bootstrapping
+    /// target version, and ensures the state snapshot at that version is
+    /// advertised by our peers.
+    fn get_pinned_snapshot_target(
+        &self,
+        target_version: Version,
+        global_data_summary: &GlobalDataSummary,
+    ) -> Result<LedgerInfoWithSignatures, Error> {
+        let target_ledger_info = self.get_verified_target_ledger_info(target_version)?;
+
+        // Verify that the states at the target version are advertised
+        let states_advertised = global_data_summary
+            .advertised_data
+            .states
+            .iter()
+            .any(|states| states.contains(target_version));
+        if !states_advertised {
+            return Err(Error::UnservableBootstrappingTarget(format!(
+                "No peer advertises the states at the target version: {:?}. Advertised states: {:?}",
+                target_version, global_data_summary.advertised_data.states
+            )));
+        }
+
+        Ok(target_ledger_info)
+    }
+
+    /// Returns the verified epoch ending ledger info at the given bootstrapping
+    /// target version. State snapshots can only be synced at epoch endings.
+    fn get_verified_target_ledger_info(
+        &self,
+        target_version: Version,
+    ) -> Result<LedgerInfoWithSignatures, Error> {
+        self.verified_epoch_states
+            .get_epoch_ending_ledger_info(target_version)
+            .ok_or_else(|| {
+                Error::UnservableBootstrappingTarget(format!(
+                    "The target version: {:?} is not a verified epoch ending version! \
+                    State snapshots can only be synced at the end of an epoch.",
+                    target_version
+                ))
+            })
+    }
+
     /// Attempts to fetch a data notification from the active stream
     async fn fetch_next_data_notification(&mut self) -> Result<DataNotification, Error> {
         let max_stream_wait_time_ms = self.driver_configuration.config.max_stream_wait_time_ms;
@@ -688,13 +964,19 @@ impl<
         let target_ledger_info_version = target_ledger_info.ledger_info().version();
         let data_stream = if self.state_value_syncer.transaction_output_to_sync.is_none() {
             // Fetch the transaction info first, before the states
-            self.streaming_client
-                .get_all_transaction_outputs(
-                    target_ledger_info_version,
-                    target_ledger_info_version,
-                    target_ledger_info_version,
-                )
-                .await?
+            if let Some(backup_restorer) = self.backup_restorer.as_mut() {
+                backup_restorer
+                    .get_transaction_output(target_ledger_info.clone())
+                    .await?
+            } else {
+                self.streaming_client
+                    .get_all_transaction_outputs(
+                        target_ledger_info_version,
+                        target_ledger_info_version,
+                        target_ledger_info_version,
+                    )
+                    .await?
+            }
         } else {
             // Identify the next state index to fetch
             let next_state_index_to_process = if existing_snapshot_progress {
@@ -718,12 +1000,18 @@ impl<
             // Fetch the missing state values
             self.state_value_syncer
                 .update_next_state_index_to_process(next_state_index_to_process);
-            self.streaming_client
-                .get_all_state_values(
-                    target_ledger_info_version,
-                    Some(next_state_index_to_process),
-                )
-                .await?
+            if let Some(backup_restorer) = self.backup_restorer.as_mut() {
+                backup_restorer
+                    .get_state_values(target_ledger_info_version, next_state_index_to_process)
+                    .await?
+            } else {
+                self.streaming_client
+                    .get_all_state_values(
+                        target_ledger_info_version,
+                        Some(next_state_index_to_process),
+                    )
+                    .await?
+            }
         };
         self.active_data_stream = Some(data_stream);
 
@@ -988,6 +1276,14 @@ impl<
             let version_to_sync = ledger_info_to_sync.ledger_info().version();
             let epoch_change_proofs = if version_to_sync == GENESIS_TRANSACTION_VERSION {
                 vec![ledger_info_to_sync.clone()] // Sync to genesis
+            } else if bootstrapping_mode.is_restore_from_backup() {
+                // The backup target may be older than the highest known ledger
+                // info, so only the proofs up to the target can be committed.
+                self.verified_epoch_states
+                    .all_epoch_ending_ledger_infos()
+                    .into_iter()
+                    .filter(|ledger_info| ledger_info.ledger_info().version() <= version_to_sync)
+                    .collect()
             } else {
                 self.verified_epoch_states.all_epoch_ending_ledger_infos() // Sync beyond genesis
             };
@@ -1122,8 +1418,10 @@ impl<
     ) -> Result<(), Error> {
         // Verify that we're expecting transaction or output payloads
         let bootstrapping_mode = self.get_bootstrapping_mode();
+        let replaying_backup_outputs = self.is_replaying_backup_transaction_outputs();
         if self.should_fetch_epoch_ending_ledger_infos()
             || (bootstrapping_mode.is_fast_sync()
+                && !replaying_backup_outputs
                 && self.state_value_syncer.transaction_output_to_sync.is_some())
         {
             self.reset_active_stream(Some(NotificationAndFeedback::new(
@@ -1137,7 +1435,7 @@ impl<
         }
 
         // If we're fast syncing, we expect a single transaction info
-        if bootstrapping_mode.is_fast_sync() {
+        if bootstrapping_mode.is_fast_sync() && !replaying_backup_outputs {
             return self
                 .verify_transaction_info_to_sync(
                     notification_metadata.notification_id,
@@ -1176,7 +1474,8 @@ impl<
 
         // Execute/apply and commit the transactions/outputs
         let num_transactions_or_outputs = match bootstrapping_mode {
-            BootstrappingMode::ApplyTransactionOutputsFromGenesis => {
+            BootstrappingMode::ApplyTransactionOutputsFromGenesis
+            | BootstrappingMode::RestoreFromBackup => {
                 if let Some(transaction_outputs_with_proof) = transaction_outputs_with_proof {
                     utils::apply_transaction_outputs(
                         &mut self.storage_synchronizer,
@@ -1381,7 +1680,8 @@ impl<
     ) -> Result<Option<LedgerInfoWithSignatures>, Error> {
         // Calculate the payload end version
         let num_versions = match self.get_bootstrapping_mode() {
-            BootstrappingMode::ApplyTransactionOutputsFromGenesis => {
+            BootstrappingMode::ApplyTransactionOutputsFromGenesis
+            | BootstrappingMode::RestoreFromBackup => {
                 if let Some(transaction_outputs_with_proof) = transaction_outputs_with_proof {
                     transaction_outputs_with_proof.get_num_outputs()
                 } else {
@@ -1542,13 +1842,21 @@ impl<
         notification_and_feedback: Option<NotificationAndFeedback>,
     ) -> Result<(), Error> {
         if let Some(active_data_stream) = &self.active_data_stream {
+            // Streams from backup storage are simply dropped (the data
+            // streaming service doesn't know about them).
             let data_stream_id = active_data_stream.data_stream_id;
-            utils::terminate_stream_with_feedback(
-                &mut self.streaming_client,
-                data_stream_id,
-                notification_and_feedback,
-            )
-            .await?;
+            let is_backup_stream = self
+                .backup_restorer
+                .as_ref()
+                .is_some_and(|backup_restorer| backup_restorer.is_backup_stream(data_stream_id));
+            if !is_backup_stream {
+                utils::terminate_stream_with_feedback(
+                    &mut self.streaming_client,
+                    data_stream_id,
+                    notification_and_feedback,
+                )
+                .await?;
+            }
         }
 
         self.active_data_stream = None;
@@ -1573,4 +1881,13 @@ impl<
     pub(crate) fn set_waypoint(&mut self, waypoint: Waypoint) {
         self.driver_configuration.waypoint = waypoint;
     }
+
+    /// Manually sets the backup restorer for testing purposes
+    #[cfg(test)]
+    pub(crate) fn set_backup_restorer(
+        &mut self,
+        backup_restorer: Box<dyn BackupRestorerInterface + Send + Sync>,
+    ) {
+        self.backup_restorer = Some(backup_restorer);
+    }
 }
@@ -79,7 +79,14 @@ impl<
         &mut self,
         consensus_sync_request: Arc<Mutex<Option<ConsensusSyncRequest>>>,
     ) -> Result<(), Error> {
-        if self.active_data_stream.is_some() {
+        if self.get_continuous_syncing_mode().is_frozen() {
+            // The node is frozen at the bootstrapped version. There's nothing to sync!
+            sample!(
+                SampleRate::Duration(Duration::from_secs(PENDING_DATA_LOG_FREQ_SECS)),
+                info!("The node is frozen! Skipping continuous syncing.")
+            );
+            Ok(())
+        } else if self.active_data_stream.is_some() {
             // We have an active data stream. Process any notifications!
             self.process_active_stream_notifications(consensus_sync_request)
                 .await
@@ -169,6 +176,11 @@ impl<
                         .await?
                 }
             },
+            ContinuousSyncingMode::Frozen => {
+                return Err(Error::UnexpectedError(
+                    "Frozen nodes should not initialize a data stream!".into(),
+                ));
+            },
         };
         self.speculative_stream_state = Some(SpeculativeStreamState::new(
             highest_epoch_state,
@@ -373,6 +385,11 @@ impl<
                     ));
                 }
             },
+            ContinuousSyncingMode::Frozen => {
+                return Err(Error::UnexpectedError(
+                    "Frozen nodes should not process transaction data!".into(),
+                ));
+            },
         };
         let synced_version = payload_start_version
             .checked_add(num_transactions_or_outputs as u64)
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    bootstrapper::Bootstrapper,
+    bootstrapper::{Bootstrapper, GENESIS_TRANSACTION_VERSION},
     continuous_syncer::ContinuousSyncer,
     driver_client::{ClientNotificationListener, DriverNotification},
     error::Error,
@@ -16,16 +16,20 @@ use crate::{
         ConsensusNotificationHandler, ErrorNotification, ErrorNotificationListener,
         MempoolNotificationHandler, StorageServiceNotificationHandler,
     },
+    partial_state_syncer::PartialStateSyncer,
+    progress_tracker::ProgressTracker,
     storage_synchronizer::StorageSynchronizerInterface,
     utils,
     utils::{OutputFallbackHandler, PENDING_DATA_LOG_FREQ_SECS},
 };
-use lumio_config::config::{ConsensusObserverConfig, RoleType, StateSyncDriverConfig};
+use lumio_config::config::{
+    BackupRestoreConfig, ConsensusObserverConfig, RoleType, StateSyncDriverConfig,
+};
 use lumio_consensus_notifications::{
     ConsensusCommitNotification, ConsensusNotification, ConsensusSyncDurationNotification,
     ConsensusSyncTargetNotification,
 };
-use lumio_data_client::interface::LumioDataClientInterface;
+use lumio_data_client::{global_summary::GlobalDataSummary, interface::LumioDataClientInterface};
 use lumio_data_streaming_service::streaming_client::{
     DataStreamingClient, NotificationAndFeedback, NotificationFeedback,
 };
@@ -36,7 +40,11 @@ use lumio_mempool_notifications::MempoolNotificationSender;
 use lumio_storage_interface::DbReader;
 use lumio_storage_service_notifications::StorageServiceNotificationSender;
 use lumio_time_service::{TimeService, TimeServiceTrait};
-use lumio_types::{contract_event::ContractEvent, waypoint::Waypoint};
+use lumio_types::{
+    contract_event::ContractEvent,
+    state_sync_progress::{SharedStateSyncProgress, StateSyncPhase},
+    waypoint::Waypoint,
+};
 use futures::StreamExt;
 use std::{sync::Arc, time::Instant};
 use tokio::{
@@ -52,6 +60,9 @@ const DRIVER_ERROR_LOG_FREQ_SECS: u64 = 3;
 /// The configuration of the state sync driver
 #[derive(Clone)]
 pub struct DriverConfiguration {
+    // The config of the backup storage (used when restoring from backups)
+    pub backup_restore_config: BackupRestoreConfig,
+
     // The config file of the driver
     pub config: StateSyncDriverConfig,
 
@@ -67,12 +78,14 @@ pub struct DriverConfiguration {
 
 impl DriverConfiguration {
     pub fn new(
+        backup_restore_config: BackupRestoreConfig,
         config: StateSyncDriverConfig,
         consensus_observer_config: ConsensusObserverConfig,
         role: RoleType,
         waypoint: Waypoint,
     ) -> Self {
         Self {
+            backup_restore_config,
             config,
             consensus_observer_config,
             role,
@@ -120,6 +133,12 @@ pub struct StateSyncDriver<
     // The handler for notifications to mempool
     mempool_notification_handler: MempoolNotificationHandler<MempoolNotifier>,
 
+    // The component that syncs the tracked states (only for partial state nodes)
+    partial_state_syncer: Option<PartialStateSyncer<DataClient>>,
+
+    // The component that tracks and publishes the sync progress of the node
+    progress_tracker: ProgressTracker,
+
     // The timestamp at which the driver started executing
     start_time: Option<Instant>,
 
@@ -163,6 +182,8 @@ impl<
         event_subscription_service: Arc<Mutex<EventSubscriptionService>>,
         mempool_notification_handler: MempoolNotificationHandler<MempoolNotifier>,
         metadata_storage: MetadataStorage,
+        partial_state_syncer: Option<PartialStateSyncer<DataClient>>,
+        shared_sync_progress: SharedStateSyncProgress,
         storage_service_notification_handler: StorageServiceNotificationHandler<
             StorageServiceNotifier,
         >,
@@ -189,6 +210,7 @@ impl<
             storage.clone(),
             storage_synchronizer.clone(),
         );
+        let progress_tracker = ProgressTracker::new(shared_sync_progress, time_service.clone());
 
         Self {
             bootstrapper,
@@ -201,6 +223,8 @@ impl<
             error_notification_listener,
             event_subscription_service,
             mempool_notification_handler,
+            partial_state_syncer,
+            progress_tracker,
             start_time: None,
             storage,
             storage_service_notification_handler,
@@ -251,6 +275,18 @@ impl<
                 "Received consensus notification: {:?}",
                 notification
             )))
+        } else if self
+            .driver_configuration
+            .config
+            .continuous_syncing_mode
+            .is_frozen()
+        {
+            // Frozen nodes never sync beyond the bootstrapped version, so
+            // the notification must be rejected (instead of left unanswered).
+            Err(Error::FrozenNodeConsensusNotification(format!(
+                "Received consensus notification: {:?}",
+                notification
+            )))
         } else if !self.bootstrapper.is_bootstrapped() {
             Err(Error::BootstrapNotComplete(format!(
                 "Received consensus notification: {:?}",
@@ -678,6 +714,9 @@ impl<
             return self.check_auto_bootstrapping().await;
         }
 
+        // Update the sync progress report of the node
+        self.update_sync_progress(&global_data_summary);
+
         // Check the progress of any sync requests
         if let Err(error) = self.check_sync_request_progress().await {
             warn!(LogSchema::new(LogEntry::Driver)
@@ -690,6 +729,35 @@ impl<
             return;
         }
 
+        // If we're a partial state node, only refresh the tracked states
+        if let Some(partial_state_syncer) = self.partial_state_syncer.as_mut() {
+            if let Err(error) = partial_state_syncer
+                .drive_progress(&global_data_summary)
+                .await
+            {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(DRIVER_ERROR_LOG_FREQ_SECS)),
+                    warn!(LogSchema::new(LogEntry::Driver)
+                        .error(&error)
+                        .message("Error found when driving progress of the partial state syncer!"));
+                );
+                metrics::increment_counter(
+                    &metrics::PARTIAL_STATE_SYNCER_ERRORS,
+                    error.get_label(),
+                );
+            }
+
+            // Once the tracked states have been synced, bootstrapping is complete
+            if partial_state_syncer.has_synced_states() && !self.bootstrapper.is_bootstrapped() {
+                if let Err(error) = self.bootstrapper.bootstrapping_complete().await {
+                    warn!(LogSchema::new(LogEntry::Driver)
+                        .error(&error)
+                        .message("Failed to mark bootstrapping as complete!"));
+                }
+            }
+            return;
+        }
+
         // Drive progress depending on if we're bootstrapping or continuously syncing
         if self.bootstrapper.is_bootstrapped() {
             // Fetch any consensus sync requests
@@ -720,6 +788,84 @@ impl<
         };
     }
 
+    /// Updates the sync progress report of the node
+    fn update_sync_progress(&mut self, global_data_summary: &GlobalDataSummary) {
+        // Partial state nodes only sync the tracked states
+        if self.partial_state_syncer.is_some() {
+            return;
+        }
+
+        // Fetch the highest synced version
+        let synced_version = match utils::fetch_pre_committed_version(self.storage.clone()) {
+            Ok(synced_version) => synced_version,
+            Err(error) => {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(DRIVER_ERROR_LOG_FREQ_SECS)),
+                    warn!(LogSchema::new(LogEntry::Driver)
+                        .error(&error)
+                        .message("Failed to fetch the synced version for the progress report!"));
+                );
+                return;
+            },
+        };
+
+        // Identify the current phase and the target version
+        let highest_advertised_version = global_data_summary
+            .advertised_data
+            .highest_synced_ledger_info()
+            .map(|ledger_info| ledger_info.ledger_info().version());
+        let state_snapshot_progress = self
+            .bootstrapper
+            .get_state_snapshot_progress()
+            .filter(|_| synced_version == GENESIS_TRANSACTION_VERSION);
+        let (phase, target_version) = if self.bootstrapper.is_bootstrapped() {
+            // Frozen nodes don't sync beyond the bootstrapped version
+            let target_version = if self
+                .driver_configuration
+                .config
+                .continuous_syncing_mode
+                .is_frozen()
+            {
+                Some(synced_version)
+            } else {
+                highest_advertised_version
+            };
+            (StateSyncPhase::ContinuousSyncing, target_version)
+        } else if self.bootstrapper.is_fetching_epoch_ending_ledger_infos() {
+            (
+                StateSyncPhase::EpochEndingLedgerInfos,
+                highest_advertised_version,
+            )
+        } else if let Some((snapshot_version, _)) = state_snapshot_progress {
+            (StateSyncPhase::States, Some(snapshot_version))
+        } else {
+            (
+                StateSyncPhase::Transactions,
+                self.bootstrapper.get_highest_known_version(),
+            )
+        };
+
+        // Identify the state snapshot progress (if we're syncing states)
+        let (num_states_synced, num_states_total) = match state_snapshot_progress {
+            Some((snapshot_version, num_states_synced)) => {
+                let num_states_total = self
+                    .progress_tracker
+                    .get_number_of_states(&self.lumio_data_client, snapshot_version);
+                (Some(num_states_synced), num_states_total)
+            },
+            None => (None, None),
+        };
+
+        // Update the progress report
+        self.progress_tracker.update_progress(
+            phase,
+            synced_version,
+            target_version,
+            num_states_synced,
+            num_states_total,
+        );
+    }
+
     /// Updates the executing component metrics for the driver
     fn update_executing_component_metrics(&self) {
         // Determine the executing component
@@ -729,6 +875,8 @@ impl<
             } else {
                 ExecutingComponent::ConsensusObserver
             }
+        } else if self.partial_state_syncer.is_some() {
+            ExecutingComponent::PartialStateSyncer
         } else if self.bootstrapper.is_bootstrapped() {
             ExecutingComponent::ContinuousSyncer
         } else {
@@ -10,6 +10,7 @@ use crate::{
         CommitNotification, CommitNotificationListener, ConsensusNotificationHandler,
         ErrorNotificationListener, MempoolNotificationHandler, StorageServiceNotificationHandler,
     },
+    partial_state_syncer::PartialStateSyncer,
     storage_synchronizer::StorageSynchronizer,
 };
 use lumio_config::config::NodeConfig;
@@ -20,10 +21,10 @@ use lumio_event_notifications::{EventNotificationSender, EventSubscriptionServic
 use lumio_executor_types::ChunkExecutorTrait;
 use lumio_infallible::Mutex;
 use lumio_mempool_notifications::MempoolNotificationSender;
-use lumio_storage_interface::DbReaderWriter;
+use lumio_storage_interface::{partial_state::PartialStateStore, DbReaderWriter};
 use lumio_storage_service_notifications::StorageServiceNotificationSender;
 use lumio_time_service::TimeService;
-use lumio_types::waypoint::Waypoint;
+use lumio_types::{state_sync_progress::SharedStateSyncProgress, waypoint::Waypoint};
 use futures::{
     channel::{mpsc, mpsc::UnboundedSender},
     executor::block_on,
@@ -57,6 +58,8 @@ impl DriverFactory {
         event_subscription_service: EventSubscriptionService,
         lumio_data_client: LumioDataClient,
         streaming_service_client: StreamingServiceClient,
+        partial_state_store: Option<Arc<PartialStateStore>>,
+        shared_sync_progress: SharedStateSyncProgress,
         time_service: TimeService,
     ) -> Self {
         let (driver_factory, _) = Self::create_and_spawn_driver_internal(
@@ -72,6 +75,8 @@ impl DriverFactory {
             event_subscription_service,
             lumio_data_client,
             streaming_service_client,
+            partial_state_store,
+            shared_sync_progress,
             time_service,
         );
         driver_factory
@@ -98,6 +103,8 @@ impl DriverFactory {
         mut event_subscription_service: EventSubscriptionService,
         lumio_data_client: LumioDataClient,
         streaming_service_client: StreamingServiceClient,
+        partial_state_store: Option<Arc<PartialStateStore>>,
+        shared_sync_progress: SharedStateSyncProgress,
         time_service: TimeService,
     ) -> (Self, UnboundedSender<CommitNotification>) {
         // Notify subscribers of the initial on-chain config values
@@ -158,12 +165,25 @@ impl DriverFactory {
 
         // Create the driver configuration
         let driver_configuration = DriverConfiguration::new(
+            node_config.state_sync.backup_restore.clone(),
             node_config.state_sync.state_sync_driver,
             node_config.consensus_observer,
             node_config.base.role,
             waypoint,
         );
 
+        // Create the partial state syncer (if we're a partial state node)
+        let partial_state_syncer = partial_state_store.map(|partial_state_store| {
+            PartialStateSyncer::new(
+                node_config.state_sync.partial_state_sync.clone(),
+                lumio_data_client.clone(),
+                partial_state_store,
+                storage.reader.clone(),
+                time_service.clone(),
+                waypoint,
+            )
+        });
+
         // Create the state sync driver
         let state_sync_driver = StateSyncDriver::new(
             client_notification_listener,
@@ -174,6 +194,8 @@ impl DriverFactory {
             event_subscription_service,
             mempool_notification_handler,
             metadata_storage,
+            partial_state_syncer,
+            shared_sync_progress,
             storage_service_notification_handler,
             storage_synchronizer,
             lumio_data_client,
@@ -13,16 +13,22 @@ pub enum Error {
     AlreadyBootstrapped(String),
     #[error("Advertised data error: {0}")]
     AdvertisedDataError(String),
+    #[error("Backup storage error: {0}")]
+    BackupStorageError(String),
     #[error("State sync has not yet finished bootstrapping! Error: {0}")]
     BootstrapNotComplete(String),
     #[error("Failed to send callback: {0}")]
     CallbackSendFailed(String),
     #[error("Timed-out waiting for a data stream too many times. Times: {0}")]
     CriticalDataStreamTimeout(String),
+    #[error("Error returned by the data client: {0}")]
+    DataClientError(String),
     #[error("Timed-out waiting for a notification from the data stream. Timeout: {0}")]
     DataStreamNotificationTimeout(String),
     #[error("Error encountered in the event subscription service: {0}")]
     EventNotificationError(String),
+    #[error("A consensus notification was sent to a frozen node: {0}")]
+    FrozenNodeConsensusNotification(String),
     #[error("A consensus notification was sent to a full node: {0}")]
     FullNodeConsensusNotification(String),
     #[error("An integer overflow has occurred: {0}")]
@@ -49,6 +55,8 @@ pub enum Error {
     VerificationError(String),
     #[error("Unexpected error: {0}")]
     UnexpectedError(String),
+    #[error("Failed to find a state snapshot at the bootstrapping target version: {0}")]
+    UnservableBootstrappingTarget(String),
     #[error("Failed to verify waypoint satisfiability: {0}")]
     UnsatisfiableWaypoint(String),
 }
@@ -59,11 +67,14 @@ impl Error {
         match self {
             Error::AlreadyBootstrapped(_) => "already_boostrapped",
             Error::AdvertisedDataError(_) => "advertised_data_error",
+            Error::BackupStorageError(_) => "backup_storage_error",
             Error::BootstrapNotComplete(_) => "bootstrap_not_complete",
             Error::CallbackSendFailed(_) => "callback_send_failed",
             Error::CriticalDataStreamTimeout(_) => "critical_data_stream_timeout",
+            Error::DataClientError(_) => "data_client_error",
             Error::DataStreamNotificationTimeout(_) => "data_stream_notification_timeout",
             Error::EventNotificationError(_) => "event_notification_error",
+            Error::FrozenNodeConsensusNotification(_) => "frozen_node_consensus_notification",
             Error::FullNodeConsensusNotification(_) => "full_node_consensus_notification",
             Error::IntegerOverflow(_) => "integer_overflow",
             Error::InvalidSyncRequest(_, _) => "invalid_sync_request",
@@ -76,6 +87,7 @@ impl Error {
             Error::SyncedBeyondTarget(_, _) => "synced_beyond_target",
             Error::VerificationError(_) => "verification_error",
             Error::UnexpectedError(_) => "unexpected_error",
+            Error::UnservableBootstrappingTarget(_) => "unservable_bootstrapping_target",
             Error::UnsatisfiableWaypoint(_) => "unsatisfiable_waypoint",
         }
     }
@@ -87,6 +99,12 @@ impl From<Canceled> for Error {
     }
 }
 
+impl From<lumio_data_client::error::Error> for Error {
+    fn from(error: lumio_data_client::error::Error) -> Self {
+        Error::DataClientError(error.to_string())
+    }
+}
+
 impl From<lumio_data_streaming_service::error::Error> for Error {
     fn from(error: lumio_data_streaming_service::error::Error) -> Self {
         Error::UnexpectedError(error.to_string())
@@ -4,6 +4,7 @@
 
 #![forbid(unsafe_code)]
 
+mod backup_restorer;
 mod bootstrapper;
 mod continuous_syncer;
 mod driver;
@@ -14,6 +15,8 @@ mod logging;
 pub mod metadata_storage;
 pub mod metrics;
 mod notification_handlers;
+mod partial_state_syncer;
+mod progress_tracker;
 mod storage_synchronizer;
 mod utils;
 
@@ -28,11 +28,14 @@ impl LogSchema<'_> {
 #[serde(rename_all = "snake_case")]
 pub enum LogEntry {
     AutoBootstrapping,
+    BackupRestorer,
     Bootstrapper,
     ClientNotification,
     ConsensusNotification,
     Driver,
     NotificationHandler,
+    PartialStateSyncer,
+    ProgressTracker,
     StorageSynchronizer,
     SynchronizerNotification,
 }
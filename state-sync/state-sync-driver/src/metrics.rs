@@ -49,6 +49,7 @@ pub enum ExecutingComponent {
     Consensus,
     ConsensusObserver,
     ContinuousSyncer,
+    PartialStateSyncer,
 }
 
 impl ExecutingComponent {
@@ -58,6 +59,7 @@ impl ExecutingComponent {
             ExecutingComponent::Consensus => "consensus",
             ExecutingComponent::ConsensusObserver => "consensus_observer",
             ExecutingComponent::ContinuousSyncer => "continuous_syncer",
+            ExecutingComponent::PartialStateSyncer => "partial_state_syncer",
         }
     }
 }
@@ -167,6 +169,16 @@ pub static EXECUTING_COMPONENT: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for state sync partial state syncer errors
+pub static PARTIAL_STATE_SYNCER_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "lumio_state_sync_partial_state_syncer_errors",
+        "Counters related to state sync partial state syncer errors",
+        &["error_label"]
+    )
+    .unwrap()
+});
+
 /// Counter for tracking sizes of data chunks sent to the storage synchronizer
 pub static STORAGE_SYNCHRONIZER_CHUNK_SIZES: Lazy<HistogramVec> = Lazy::new(|| {
     let histogram_opts = histogram_opts!(
@@ -0,0 +1,299 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    bootstrapper::VerifiedEpochStates,
+    error::Error,
+    logging::{LogEntry, LogSchema},
+    utils,
+};
+use lumio_config::config::PartialStateSyncConfig;
+use lumio_data_client::{
+    global_summary::GlobalDataSummary,
+    interface::{LumioDataClientInterface, ResponseError},
+};
+use lumio_logger::prelude::*;
+use lumio_storage_interface::{partial_state::PartialStateStore, DbReader};
+use lumio_time_service::{TimeService, TimeServiceTrait};
+use lumio_types::{
+    epoch_change::Verifier,
+    ledger_info::LedgerInfoWithSignatures,
+    state_store::{
+        state_key::{prefix::StateKeyPrefix, StateKey},
+        state_value::StateValue,
+    },
+    waypoint::Waypoint,
+};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// A simple component that syncs the states of the tracked accounts for
+/// partial state nodes. Instead of executing (or applying) transactions,
+/// the syncer periodically fetches the tracked states (with proofs) at the
+/// highest advertised ledger info, verifies them and updates the partial
+/// state store.
+pub struct PartialStateSyncer<DataClient> {
+    // The config for partial state syncing
+    config: PartialStateSyncConfig,
+
+    // The client used to fetch data from our peers
+    lumio_data_client: DataClient,
+
+    // The time at which the tracked states were last refreshed
+    last_refresh_time: Option<Instant>,
+
+    // The store holding the verified states of the tracked accounts
+    partial_state_store: Arc<PartialStateStore>,
+
+    // The time service
+    time_service: TimeService,
+
+    // The epoch states (and epoch ending ledger infos) verified by the node
+    verified_epoch_states: VerifiedEpochStates,
+
+    // The trusted waypoint for the node
+    waypoint: Waypoint,
+}
+
+impl<DataClient: LumioDataClientInterface + Send + Clone + 'static> PartialStateSyncer<DataClient> {
+    pub fn new(
+        config: PartialStateSyncConfig,
+        lumio_data_client: DataClient,
+        partial_state_store: Arc<PartialStateStore>,
+        storage: Arc<dyn DbReader>,
+        time_service: TimeService,
+        waypoint: Waypoint,
+    ) -> Self {
+        // Load the latest epoch state from storage
+        let latest_epoch_state = utils::fetch_latest_epoch_state(storage.clone())
+            .expect("Unable to fetch latest epoch state!");
+        let mut verified_epoch_states = VerifiedEpochStates::new(latest_epoch_state);
+
+        // If storage has already synced beyond the waypoint, it is trivially verified
+        let latest_ledger_info = utils::fetch_latest_synced_ledger_info(storage)
+            .expect("Unable to fetch latest synced ledger info!");
+        if latest_ledger_info.ledger_info().version() >= waypoint.version() {
+            verified_epoch_states.set_verified_waypoint(waypoint.version());
+        }
+
+        Self {
+            config,
+            lumio_data_client,
+            last_refresh_time: None,
+            partial_state_store,
+            time_service,
+            verified_epoch_states,
+            waypoint,
+        }
+    }
+
+    /// Returns true iff the tracked states have been synced at least once
+    pub fn has_synced_states(&self) -> bool {
+        self.partial_state_store.get_synced_version().is_some()
+    }
+
+    /// Refreshes the tracked states (if the refresh interval has elapsed)
+    pub async fn drive_progress(
+        &mut self,
+        global_data_summary: &GlobalDataSummary,
+    ) -> Result<(), Error> {
+        // Check if it's time to refresh the tracked states
+        let now = self.time_service.now();
+        if let Some(last_refresh_time) = self.last_refresh_time {
+            let refresh_interval = Duration::from_millis(self.config.refresh_interval_ms);
+            if now.duration_since(last_refresh_time) < refresh_interval {
+                return Ok(());
+            }
+        }
+        self.last_refresh_time = Some(now);
+
+        // Identify the highest ledger info advertised by our peers
+        let target_ledger_info = match global_data_summary
+            .advertised_data
+            .highest_synced_ledger_info()
+        {
+            Some(ledger_info) => ledger_info,
+            None => {
+                return Err(Error::AdvertisedDataError(
+                    "No synced ledger infos are advertised by our peers!".into(),
+                ))
+            },
+        };
+
+        // If we've already synced the tracked states at the target, there's nothing to do
+        let target_version = target_ledger_info.ledger_info().version();
+        if let Some(synced_version) = self.partial_state_store.get_synced_version() {
+            if target_version <= synced_version {
+                return Ok(());
+            }
+        }
+
+        // Verify the target ledger info (after syncing to the target epoch)
+        self.sync_to_epoch(target_ledger_info.ledger_info().epoch())
+            .await?;
+        if !self.verified_epoch_states.verified_waypoint() {
+            return Err(Error::UnsatisfiableWaypoint(format!(
+                "The waypoint has not been verified! Waypoint: {:?}, target version: {:?}",
+                self.waypoint, target_version
+            )));
+        }
+        self.verified_epoch_states
+            .latest_epoch_state()
+            .verify(&target_ledger_info)
+            .map_err(|error| {
+                Error::VerificationError(format!("Ledger info failed verification: {:?}", error))
+            })?;
+
+        // Fetch and verify the tracked states at the target version
+        let mut state_values = vec![];
+        for key_prefix in self.partial_state_store.get_tracked_key_prefixes() {
+            let prefixed_state_values = self
+                .fetch_prefixed_state_values(&target_ledger_info, key_prefix)
+                .await?;
+            state_values.extend(prefixed_state_values);
+        }
+
+        // Update the partial state store
+        info!(
+            LogSchema::new(LogEntry::PartialStateSyncer).message(&format!(
+                "Synced the tracked states at version: {:?}. Number of state values: {:?}",
+                target_version,
+                state_values.len()
+            ))
+        );
+        self.partial_state_store
+            .update_synced_state(target_ledger_info, state_values);
+
+        Ok(())
+    }
+
+    /// Fetches and verifies the epoch ending ledger infos up to the given
+    /// epoch, and updates the latest verified epoch state.
+    async fn sync_to_epoch(&mut self, target_epoch: u64) -> Result<(), Error> {
+        while self.verified_epoch_states.latest_epoch_state().epoch < target_epoch {
+            // Fetch the epoch ending ledger infos
+            let start_epoch = self.verified_epoch_states.latest_epoch_state().epoch;
+            let response = self
+                .lumio_data_client
+                .get_epoch_ending_ledger_infos(
+                    start_epoch,
+                    target_epoch - 1,
+                    self.config.request_timeout_ms,
+                )
+                .await?;
+            if response.payload.is_empty() {
+                return Err(Error::InvalidPayload(format!(
+                    "Received no epoch ending ledger infos for epoch: {:?}",
+                    start_epoch
+                )));
+            }
+
+            // Verify the epoch ending ledger infos and update the epoch states
+            for epoch_ending_ledger_info in &response.payload {
+                if let Err(error) = self
+                    .verified_epoch_states
+                    .update_verified_epoch_states(epoch_ending_ledger_info, &self.waypoint)
+                {
+                    response
+                        .context
+                        .response_callback
+                        .notify_bad_response(ResponseError::ProofVerificationError);
+                    return Err(error);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fetches and verifies all state values with the given key prefix at
+    /// the version of the (verified) target ledger info. The absence of any
+    /// previously synced keys (e.g., deleted keys) must be proven by the peer.
+    async fn fetch_prefixed_state_values(
+        &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+        key_prefix: &StateKeyPrefix,
+    ) -> Result<Vec<(StateKey, StateValue)>, Error> {
+        let ledger_info = target_ledger_info.ledger_info();
+        let known_keys = self.get_known_keys(key_prefix)?;
+        let mut state_values = vec![];
+        let mut start_key: Option<StateKey> = None;
+        loop {
+            // Identify the known keys that remain to be fetched
+            let remaining_known_keys: Vec<_> = known_keys
+                .iter()
+                .filter(|known_key| {
+                    start_key
+                        .as_ref()
+                        .is_none_or(|start_key| known_key.encoded() >= start_key.encoded())
+                })
+                .cloned()
+                .collect();
+
+            // Fetch the next chunk of state values
+            let response = self
+                .lumio_data_client
+                .get_filtered_state_values_with_proof(
+                    ledger_info.version(),
+                    key_prefix.clone(),
+                    start_key.clone(),
+                    remaining_known_keys.clone(),
+                    self.config.request_timeout_ms,
+                )
+                .await?;
+
+            // Verify the chunk against the transaction info at the target version
+            let filtered_state_values = response.payload;
+            if let Err(error) = filtered_state_values.verify(
+                ledger_info,
+                key_prefix,
+                start_key.as_ref(),
+                &remaining_known_keys,
+            ) {
+                response
+                    .context
+                    .response_callback
+                    .notify_bad_response(ResponseError::ProofVerificationError);
+                return Err(Error::VerificationError(format!(
+                    "Filtered state values failed verification: {:?}",
+                    error
+                )));
+            }
+
+            // Store the state values and check if there are more to fetch
+            state_values.extend(
+                filtered_state_values
+                    .state_values
+                    .into_iter()
+                    .map(|(state_key, state_value, _)| (state_key, state_value)),
+            );
+            match filtered_state_values.next_start_key {
+                Some(next_start_key) => start_key = Some(next_start_key),
+                None => return Ok(state_values),
+            }
+        }
+    }
+
+    /// Returns the previously synced state keys with the given key prefix
+    fn get_known_keys(&self, key_prefix: &StateKeyPrefix) -> Result<Vec<StateKey>, Error> {
+        let synced_version = match self.partial_state_store.get_synced_version() {
+            Some(synced_version) => synced_version,
+            None => return Ok(vec![]), // No states have been synced yet
+        };
+        let synced_state_values = self
+            .partial_state_store
+            .get_prefixed_state_values(key_prefix, None, synced_version)
+            .map_err(|error| {
+                Error::StorageError(format!(
+                    "Failed to fetch the synced state values: {:?}",
+                    error
+                ))
+            })?;
+        Ok(synced_state_values
+            .into_iter()
+            .map(|(state_key, _)| state_key)
+            .collect())
+    }
+}
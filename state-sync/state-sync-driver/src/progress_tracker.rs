@@ -0,0 +1,200 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::logging::{LogEntry, LogSchema};
+use lumio_data_client::interface::LumioDataClientInterface;
+use lumio_infallible::Mutex;
+use lumio_logger::prelude::*;
+use lumio_time_service::{TimeService, TimeServiceTrait};
+use lumio_types::{
+    state_sync_progress::{SharedStateSyncProgress, StateSyncPhase, StateSyncProgress},
+    transaction::Version,
+};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+// The timeout (in ms) for requests that fetch the number of states at a version
+const NUMBER_OF_STATES_REQUEST_TIMEOUT_MS: u64 = 10_000;
+
+// The time (in ms) to wait before retrying a failed number of states request
+const NUMBER_OF_STATES_RETRY_BACKOFF_MS: u64 = 10_000;
+
+// The interval (in secs) at which to log number of states request failures
+const NUMBER_OF_STATES_ERROR_LOG_FREQ_SECS: u64 = 30;
+
+/// The progress of the node at the start of the current phase
+/// (used to calculate the throughput of the phase).
+struct PhaseStart {
+    phase: StateSyncPhase,
+    start_progress: u64,
+    start_time: Instant,
+}
+
+/// The number of states at a state snapshot version
+#[derive(Clone, Copy, Debug)]
+enum NumberOfStates {
+    Failed(Version, Instant), // The last request failed (at the given time)
+    Fetched(Version, u64),    // The number of states has been fetched from our peers
+    Requested(Version),       // A request to fetch the number of states is in-flight
+}
+
+/// A simple component that tracks the sync progress of the node and
+/// publishes progress reports (e.g., for the inspection service and API).
+pub struct ProgressTracker {
+    // The number of states at the state snapshot version being synced
+    number_of_states: Arc<Mutex<Option<NumberOfStates>>>,
+
+    // The progress of the node at the start of the current phase
+    phase_start: Option<PhaseStart>,
+
+    // The progress report shared with the rest of the node
+    shared_progress: SharedStateSyncProgress,
+
+    // The time service
+    time_service: TimeService,
+}
+
+impl ProgressTracker {
+    pub fn new(shared_progress: SharedStateSyncProgress, time_service: TimeService) -> Self {
+        Self {
+            number_of_states: Arc::new(Mutex::new(None)),
+            phase_start: None,
+            shared_progress,
+            time_service,
+        }
+    }
+
+    /// Returns the total number of states at the given snapshot version (if
+    /// known). Otherwise, a request is sent to our peers to fetch the number
+    /// of states, and None is returned until the request completes.
+    pub fn get_number_of_states<DataClient: LumioDataClientInterface + Send + Clone + 'static>(
+        &self,
+        lumio_data_client: &DataClient,
+        version: Version,
+    ) -> Option<u64> {
+        // Check if the number of states is already known (or being fetched)
+        let mut number_of_states = self.number_of_states.lock();
+        match *number_of_states {
+            Some(NumberOfStates::Fetched(fetched_version, num_states))
+                if fetched_version == version =>
+            {
+                return Some(num_states);
+            },
+            Some(NumberOfStates::Requested(requested_version)) if requested_version == version => {
+                return None;
+            },
+            Some(NumberOfStates::Failed(failed_version, failure_time))
+                if failed_version == version =>
+            {
+                // Back off before retrying the failed request
+                let retry_backoff = Duration::from_millis(NUMBER_OF_STATES_RETRY_BACKOFF_MS);
+                if self.time_service.now().duration_since(failure_time) < retry_backoff {
+                    return None;
+                }
+            },
+            _ => {},
+        }
+
+        // Spawn a task to fetch the number of states (so that we don't block the driver)
+        *number_of_states = Some(NumberOfStates::Requested(version));
+        let lumio_data_client = lumio_data_client.clone();
+        let number_of_states = self.number_of_states.clone();
+        let time_service = self.time_service.clone();
+        tokio::spawn(async move {
+            let result = lumio_data_client
+                .get_number_of_states(version, NUMBER_OF_STATES_REQUEST_TIMEOUT_MS)
+                .await;
+
+            // Only update the number of states if the request is still relevant
+            let mut number_of_states = number_of_states.lock();
+            if let Some(NumberOfStates::Requested(requested_version)) = *number_of_states {
+                if requested_version == version {
+                    *number_of_states = match result {
+                        Ok(response) => Some(NumberOfStates::Fetched(version, response.payload)),
+                        Err(error) => {
+                            sample!(
+                                SampleRate::Duration(Duration::from_secs(
+                                    NUMBER_OF_STATES_ERROR_LOG_FREQ_SECS
+                                )),
+                                warn!(LogSchema::new(LogEntry::ProgressTracker).message(&format!(
+                                    "Failed to fetch the number of states at version: {:?}. Error: {:?}",
+                                    version, error
+                                )))
+                            );
+                            // The request will be retried once the backoff has elapsed
+                            Some(NumberOfStates::Failed(version, time_service.now()))
+                        },
+                    };
+                }
+            }
+        });
+
+        None
+    }
+
+    /// Updates the progress report of the node. When syncing states, the
+    /// progress is measured in states. Otherwise, it is measured in versions.
+    pub fn update_progress(
+        &mut self,
+        phase: StateSyncPhase,
+        synced_version: Version,
+        target_version: Option<Version>,
+        num_states_synced: Option<u64>,
+        num_states_total: Option<u64>,
+    ) {
+        // Identify the current and total progress for the phase
+        let (current_progress, total_progress) = if phase == StateSyncPhase::States {
+            (num_states_synced.unwrap_or(0), num_states_total)
+        } else {
+            (synced_version, target_version)
+        };
+
+        // Reset the phase start if the phase has changed (or the progress has been reset)
+        let now = self.time_service.now();
+        let phase_changed = match &self.phase_start {
+            Some(phase_start) => {
+                phase_start.phase != phase || current_progress < phase_start.start_progress
+            },
+            None => true,
+        };
+        if phase_changed {
+            self.phase_start = Some(PhaseStart {
+                phase,
+                start_progress: current_progress,
+                start_time: now,
+            });
+        }
+
+        // Calculate the throughput since the start of the phase. Epoch ending
+        // ledger infos don't advance the synced version, so there's no throughput.
+        let throughput_per_second = self.phase_start.as_ref().and_then(|phase_start| {
+            let elapsed_secs = now.duration_since(phase_start.start_time).as_secs();
+            if phase == StateSyncPhase::EpochEndingLedgerInfos || elapsed_secs == 0 {
+                None
+            } else {
+                Some(current_progress.saturating_sub(phase_start.start_progress) / elapsed_secs)
+            }
+        });
+
+        // Estimate the time remaining for the phase
+        let estimated_seconds_remaining = match (throughput_per_second, total_progress) {
+            (Some(throughput_per_second), Some(total_progress)) if throughput_per_second > 0 => {
+                Some(total_progress.saturating_sub(current_progress) / throughput_per_second)
+            },
+            _ => None,
+        };
+
+        // Publish the progress report
+        self.shared_progress.update_progress(StateSyncProgress {
+            phase,
+            synced_version,
+            target_version,
+            num_states_synced,
+            num_states_total,
+            throughput_per_second,
+            estimated_seconds_remaining,
+        });
+    }
+}
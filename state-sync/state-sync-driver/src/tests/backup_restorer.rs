@@ -0,0 +1,206 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::backup_restorer::{
+    create_range_proof_at_version, create_state_value_chunk, TransactionAccumulatorNodes,
+};
+use lumio_backup_cli::backup_types::state_snapshot::manifest::StateSnapshotChunk;
+use lumio_crypto::{hash::CryptoHash, HashValue};
+use lumio_types::{
+    proof::{accumulator::InMemoryTransactionAccumulator, SparseMerkleRangeProof},
+    state_store::{state_key::StateKey, state_value::StateValue},
+};
+use bytes::Bytes;
+
+#[test]
+fn test_create_range_proof_at_version() {
+    // Create a set of transaction info hashes
+    let num_transaction_infos = 50;
+    let transaction_info_hashes: Vec<_> = (0..num_transaction_infos)
+        .map(|_| HashValue::random())
+        .collect();
+
+    // Verify the proof for every version in every chunk
+    for chunk_first_version in [0, 1, 7, 16, 23] {
+        // Identify the left siblings of the chunk
+        let accumulator_before_chunk = InMemoryTransactionAccumulator::from_leaves(
+            &transaction_info_hashes[..chunk_first_version],
+        );
+        let chunk_left_siblings: Vec<_> = accumulator_before_chunk
+            .frozen_subtree_roots()
+            .iter()
+            .rev()
+            .cloned()
+            .collect();
+
+        for version in chunk_first_version..num_transaction_infos {
+            // Create the range proof for the version
+            let range_proof = create_range_proof_at_version(
+                &chunk_left_siblings,
+                chunk_first_version as u64,
+                &transaction_info_hashes[chunk_first_version..version],
+            )
+            .unwrap();
+
+            // Verify the proof against the accumulator root at the version
+            let expected_root_hash =
+                InMemoryTransactionAccumulator::from_leaves(&transaction_info_hashes[..=version])
+                    .root_hash();
+            range_proof
+                .verify(expected_root_hash, Some(version as u64), &[
+                    transaction_info_hashes[version],
+                ])
+                .unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_transaction_accumulator_nodes() {
+    // Create a set of transaction info hashes
+    let num_transaction_infos = 50;
+    let transaction_info_hashes: Vec<_> = (0..num_transaction_infos)
+        .map(|_| HashValue::random())
+        .collect();
+    let expected_root_hash =
+        InMemoryTransactionAccumulator::from_leaves(&transaction_info_hashes).root_hash();
+
+    // Rebuild the accumulator from every first chunk
+    for chunk_first_version in [0, 1, 7, 16, 23] {
+        // Identify the left siblings of the chunk
+        let accumulator_before_chunk = InMemoryTransactionAccumulator::from_leaves(
+            &transaction_info_hashes[..chunk_first_version],
+        );
+        let chunk_left_siblings: Vec<_> = accumulator_before_chunk
+            .frozen_subtree_roots()
+            .iter()
+            .rev()
+            .cloned()
+            .collect();
+
+        // Append the transaction info hashes (in two chunks) and verify the root hash
+        let mut accumulator =
+            TransactionAccumulatorNodes::new(&chunk_left_siblings, chunk_first_version as u64);
+        let second_chunk_first_version = (chunk_first_version + num_transaction_infos) / 2;
+        accumulator
+            .append(&transaction_info_hashes[chunk_first_version..second_chunk_first_version])
+            .unwrap();
+        accumulator
+            .append(&transaction_info_hashes[second_chunk_first_version..])
+            .unwrap();
+        assert_eq!(accumulator.root_hash().unwrap(), expected_root_hash);
+
+        // Verify the range proofs for the restored versions
+        for first_version in chunk_first_version..num_transaction_infos {
+            for last_version in [first_version, num_transaction_infos - 1] {
+                let range_proof = accumulator
+                    .get_range_proof(first_version as u64, last_version as u64)
+                    .unwrap();
+                range_proof
+                    .verify(
+                        expected_root_hash,
+                        Some(first_version as u64),
+                        &transaction_info_hashes[first_version..=last_version],
+                    )
+                    .unwrap();
+            }
+        }
+
+        // Verify that ranges beyond the accumulator are rejected
+        accumulator
+            .get_range_proof(chunk_first_version as u64, num_transaction_infos as u64)
+            .unwrap_err();
+    }
+}
+
+#[test]
+fn test_create_state_value_chunk() {
+    // Create a backup chunk of state values
+    let first_index = 10;
+    let raw_values = create_state_values(10);
+    let chunk = create_state_snapshot_chunk(first_index, &raw_values);
+    let (last_index, first_key, last_key) =
+        (chunk.last_idx as u64, chunk.first_key, chunk.last_key);
+
+    // Create a state value chunk that starts before the chunk
+    let root_hash = HashValue::random();
+    let state_value_chunk = create_state_value_chunk(
+        chunk,
+        raw_values.clone(),
+        SparseMerkleRangeProof::new(vec![]),
+        root_hash,
+        0,
+    )
+    .unwrap();
+
+    // Verify the chunk is unchanged
+    assert_eq!(state_value_chunk.first_index, first_index as u64);
+    assert_eq!(state_value_chunk.last_index, last_index);
+    assert_eq!(state_value_chunk.first_key, first_key);
+    assert_eq!(state_value_chunk.last_key, last_key);
+    assert_eq!(state_value_chunk.raw_values, raw_values);
+    assert_eq!(state_value_chunk.root_hash, root_hash);
+
+    // Create a state value chunk that starts in the middle of the chunk
+    let start_index = 15;
+    let state_value_chunk = create_state_value_chunk(
+        create_state_snapshot_chunk(first_index, &raw_values),
+        raw_values.clone(),
+        SparseMerkleRangeProof::new(vec![]),
+        root_hash,
+        start_index,
+    )
+    .unwrap();
+
+    // Verify the chunk was trimmed
+    let num_skipped_values = (start_index - first_index as u64) as usize;
+    assert_eq!(state_value_chunk.first_index, start_index);
+    assert_eq!(state_value_chunk.last_index, last_index);
+    assert_eq!(
+        state_value_chunk.first_key,
+        raw_values[num_skipped_values].0.hash()
+    );
+    assert_eq!(state_value_chunk.last_key, last_key);
+    assert_eq!(
+        state_value_chunk.raw_values,
+        raw_values[num_skipped_values..].to_vec()
+    );
+
+    // Verify that chunks with missing state values are rejected
+    create_state_value_chunk(
+        create_state_snapshot_chunk(first_index, &raw_values),
+        raw_values[1..].to_vec(),
+        SparseMerkleRangeProof::new(vec![]),
+        root_hash,
+        0,
+    )
+    .unwrap_err();
+}
+
+/// Creates a backup chunk holding the given state values
+fn create_state_snapshot_chunk(
+    first_index: usize,
+    raw_values: &[(StateKey, StateValue)],
+) -> StateSnapshotChunk {
+    StateSnapshotChunk {
+        first_idx: first_index,
+        last_idx: first_index + raw_values.len() - 1,
+        first_key: raw_values.first().unwrap().0.hash(),
+        last_key: raw_values.last().unwrap().0.hash(),
+        blobs: "blobs".into(),
+        proof: "proof".into(),
+    }
+}
+
+/// Creates the specified number of state values (sorted by key hash)
+fn create_state_values(num_values: u64) -> Vec<(StateKey, StateValue)> {
+    let mut raw_values: Vec<_> = (0..num_values)
+        .map(|index| {
+            let state_key = StateKey::raw(&index.to_le_bytes());
+            let state_value = StateValue::new_legacy(Bytes::from(index.to_le_bytes().to_vec()));
+            (state_key, state_value)
+        })
+        .collect();
+    raw_values.sort_by_key(|(state_key, _)| state_key.hash());
+    raw_values
+}
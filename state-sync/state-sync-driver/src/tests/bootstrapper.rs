@@ -2,35 +2,58 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    backup_restorer::create_range_proof_at_version,
     bootstrapper::{Bootstrapper, GENESIS_TRANSACTION_VERSION},
     driver::DriverConfiguration,
     error::Error,
     tests::{
         mocks::{
-            create_mock_db_reader, create_mock_streaming_client, create_ready_storage_synchronizer,
-            MockMetadataStorage, MockStorageSynchronizer, MockStreamingClient,
+            create_mock_backup_restorer, create_mock_db_reader, create_mock_streaming_client,
+            create_ready_storage_synchronizer, MockBackupRestorer, MockMetadataStorage,
+            MockStorageSynchronizer, MockStreamingClient,
         },
         utils::{
             create_data_stream_listener, create_empty_epoch_state, create_epoch_ending_ledger_info,
             create_epoch_ending_ledger_info_for_epoch, create_full_node_driver_configuration,
             create_global_summary, create_global_summary_with_version,
             create_output_list_with_proof, create_random_epoch_ending_ledger_info,
-            create_transaction_list_with_proof,
+            create_transaction, create_transaction_list_with_proof, create_transaction_output,
         },
     },
     utils::OutputFallbackHandler,
 };
 use lumio_config::config::BootstrappingMode;
+use lumio_crypto::{hash::CryptoHash, HashValue};
 use lumio_data_client::global_summary::GlobalDataSummary;
 use lumio_data_streaming_service::{
     data_notification::{DataNotification, DataPayload, NotificationId},
     streaming_client::{NotificationAndFeedback, NotificationFeedback},
 };
+use lumio_storage_service_types::responses::CompleteDataRange;
 use lumio_time_service::TimeService;
 use lumio_types::{
-    transaction::{TransactionOutputListWithProofV2, Version},
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    proof::{
+        accumulator::{InMemoryEventAccumulator, InMemoryTransactionAccumulator},
+        SparseMerkleRangeProof, TransactionInfoListWithProof,
+    },
+    state_store::{
+        state_key::StateKey,
+        state_value::{StateValue, StateValueChunkWithProof},
+    },
+    transaction::{
+        ExecutionStatus, TransactionAuxiliaryData, TransactionInfo, TransactionOutput,
+        TransactionOutputListWithProof, TransactionOutputListWithProofV2, TransactionStatus,
+        Version,
+    },
+    validator_verifier::ValidatorVerifier,
     waypoint::Waypoint,
+    write_set::WriteSet,
 };
+use bytes::Bytes;
 use claims::{assert_matches, assert_none, assert_ok};
 use futures::{channel::oneshot, FutureExt, SinkExt};
 use mockall::{predicate::eq, Sequence};
@@ -953,6 +976,331 @@ async fn test_fetch_epoch_ending_ledger_infos_waypoint_mismatch() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn test_restore_from_backup() {
+    // Create the epoch ending ledger infos advertised by peers. The ledger info
+    // at version 200 proves the transaction output of the state snapshot.
+    let state_checkpoint_hash = HashValue::random();
+    let (target_ledger_info, output_with_proof) =
+        create_epoch_ending_output_with_proof(200, 2, state_checkpoint_hash);
+    let epoch_ending_ledger_infos = vec![
+        create_random_epoch_ending_ledger_info(0, 0),
+        create_random_epoch_ending_ledger_info(100, 1),
+        target_ledger_info.clone(),
+        create_random_epoch_ending_ledger_info(300, 3),
+    ];
+
+    // Create a driver configuration that restores from backups
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::RestoreFromBackup;
+
+    // Create the mock streaming client (the epoch ending ledger infos are fetched from peers)
+    let mut mock_streaming_client = create_mock_streaming_client();
+    let (mut notification_sender_1, data_stream_listener_1) = create_data_stream_listener();
+    let data_stream_id_1 = data_stream_listener_1.data_stream_id;
+    mock_streaming_client
+        .expect_get_all_epoch_ending_ledger_infos()
+        .times(1)
+        .with(eq(1))
+        .return_once(move |_| Ok(data_stream_listener_1));
+    mock_streaming_client
+        .expect_terminate_stream_with_feedback()
+        .times(1)
+        .with(
+            eq(data_stream_id_1),
+            eq(Some(NotificationAndFeedback::new(
+                1,
+                NotificationFeedback::EndOfStream,
+            ))),
+        )
+        .return_const(Ok(()));
+
+    // Create the mock backup restorer. The highest snapshot (at version 400)
+    // can't be verified, so the snapshot at version 200 should be restored.
+    let mut mock_backup_restorer = create_mock_backup_restorer();
+    let (mut notification_sender_2, data_stream_listener_2) = create_data_stream_listener();
+    let (mut notification_sender_3, data_stream_listener_3) = create_data_stream_listener();
+    let backup_stream_ids = [
+        data_stream_listener_2.data_stream_id,
+        data_stream_listener_3.data_stream_id,
+    ];
+    mock_backup_restorer
+        .expect_is_backup_stream()
+        .returning(move |data_stream_id| backup_stream_ids.contains(&data_stream_id));
+    mock_backup_restorer
+        .expect_get_state_snapshot_versions()
+        .times(1)
+        .returning(|| Ok(vec![400, 200]));
+    mock_backup_restorer
+        .expect_get_transaction_output()
+        .times(1)
+        .with(eq(target_ledger_info.clone()))
+        .return_once(move |_| Ok(data_stream_listener_2));
+    mock_backup_restorer
+        .expect_get_state_values()
+        .times(1)
+        .with(eq(200), eq(0))
+        .return_once(move |_, _| Ok(data_stream_listener_3));
+
+    // Create the mock storage synchronizer and verify the snapshot is only
+    // committed with the epoch change proofs up to the restored version.
+    let mut mock_storage_synchronizer = create_ready_storage_synchronizer(true);
+    let expected_epoch_change_proofs = epoch_ending_ledger_infos[..3].to_vec();
+    let expected_target_ledger_info = target_ledger_info.clone();
+    let expected_output_with_proof = output_with_proof.clone();
+    mock_storage_synchronizer
+        .expect_initialize_state_synchronizer()
+        .times(1)
+        .withf(
+            move |epoch_change_proofs, target_ledger_info, target_output_with_proof| {
+                epoch_change_proofs == &expected_epoch_change_proofs
+                    && target_ledger_info == &expected_target_ledger_info
+                    && target_output_with_proof == &expected_output_with_proof
+            },
+        )
+        .return_once(|_, _, _| Ok(tokio::spawn(async {})));
+    mock_storage_synchronizer
+        .expect_save_state_values()
+        .times(1)
+        .return_const(Ok(()));
+
+    // Create the bootstrapper
+    let mut bootstrapper = create_bootstrapper_with_backup_restorer(
+        driver_configuration,
+        mock_streaming_client,
+        mock_storage_synchronizer,
+        mock_backup_restorer,
+    );
+    let (bootstrap_notification_sender, bootstrap_notification_receiver) = oneshot::channel();
+    bootstrapper
+        .subscribe_to_bootstrap_notifications(bootstrap_notification_sender)
+        .await
+        .unwrap();
+
+    // Drive progress to initialize the epoch ending data stream
+    let global_data_summary = create_global_summary(3);
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+
+    // Send the epoch ending ledger infos and drive progress to verify them
+    let data_notification = DataNotification::new(
+        0,
+        DataPayload::EpochEndingLedgerInfos(epoch_ending_ledger_infos.clone()),
+    );
+    notification_sender_1.send(data_notification).await.unwrap();
+    let data_notification = DataNotification::new(1, DataPayload::EndOfStream);
+    notification_sender_1.send(data_notification).await.unwrap();
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+
+    // Drive progress to mark the epoch ending ledger infos as fetched
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+    let verified_epoch_states = bootstrapper.get_verified_epoch_states().clone();
+    assert!(verified_epoch_states.fetched_epoch_ending_ledger_infos());
+    assert_eq!(
+        verified_epoch_states.all_epoch_ending_ledger_infos(),
+        epoch_ending_ledger_infos
+    );
+
+    // Drive progress to fetch the transaction output from backup storage
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+
+    // Send the transaction output and drive progress to verify it against the target
+    let data_notification = DataNotification::new(
+        2,
+        DataPayload::TransactionOutputsWithProof(output_with_proof.clone()),
+    );
+    notification_sender_2.send(data_notification).await.unwrap();
+    let data_notification = DataNotification::new(3, DataPayload::EndOfStream);
+    notification_sender_2.send(data_notification).await.unwrap();
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+
+    // Drive progress to fetch the state values from backup storage
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+
+    // Send the state values and drive progress to save them
+    let state_value_chunk_with_proof = StateValueChunkWithProof {
+        first_index: 0,
+        last_index: 1,
+        first_key: HashValue::random(),
+        last_key: HashValue::random(),
+        raw_values: vec![
+            (
+                StateKey::raw(&[0]),
+                StateValue::new_legacy(Bytes::from_static(&[0])),
+            ),
+            (
+                StateKey::raw(&[1]),
+                StateValue::new_legacy(Bytes::from_static(&[1])),
+            ),
+        ],
+        proof: SparseMerkleRangeProof::new(vec![]),
+        root_hash: state_checkpoint_hash,
+    };
+    let data_notification = DataNotification::new(
+        4,
+        DataPayload::StateValuesWithProof(state_value_chunk_with_proof),
+    );
+    notification_sender_3.send(data_notification).await.unwrap();
+    let error = drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::DataStreamNotificationTimeout(_));
+    assert_eq!(bootstrapper.get_state_snapshot_progress(), Some((200, 2)));
+
+    // Complete bootstrapping (as the driver does once the snapshot is committed)
+    assert!(!bootstrapper.is_bootstrapped());
+    bootstrapper.bootstrapping_complete().await.unwrap();
+    verify_bootstrap_notification(bootstrap_notification_receiver);
+
+    // Verify the bootstrapper hands over to the continuous syncer
+    let error = drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::AlreadyBootstrapped(_));
+}
+
+#[tokio::test]
+async fn test_restore_from_backup_completed() {
+    // Create test data
+    let restored_version = 200;
+    let highest_version = 1_000_000;
+    let highest_ledger_info = create_random_epoch_ending_ledger_info(highest_version, 1);
+
+    // Create a driver configuration that restores from backups (the restored
+    // snapshot is further behind than the fast sync limit).
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::RestoreFromBackup;
+    driver_configuration
+        .config
+        .num_versions_to_skip_snapshot_sync = 1000;
+
+    // Create the bootstrapper for a node that has already restored a snapshot
+    let mut bootstrapper = create_bootstrapper_with_storage(
+        driver_configuration,
+        create_mock_streaming_client(),
+        MockMetadataStorage::new(),
+        None,
+        restored_version,
+        true,
+    );
+
+    // Set a backup restorer that holds no transactions after the snapshot
+    let mut mock_backup_restorer = create_mock_backup_restorer();
+    mock_backup_restorer
+        .expect_get_highest_transaction_version()
+        .times(1)
+        .returning(move || Ok(Some(restored_version)));
+    bootstrapper.set_backup_restorer(Box::new(mock_backup_restorer));
+
+    // Insert an epoch ending ledger info into the verified states of the bootstrapper
+    manipulate_verified_epoch_states(&mut bootstrapper, true, true, Some(highest_version));
+
+    // Create a global data summary
+    let mut global_data_summary = create_global_summary(1);
+    global_data_summary.advertised_data.synced_ledger_infos = vec![highest_ledger_info];
+
+    // Drive progress and verify the bootstrapper hands over to the continuous syncer
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+    assert!(bootstrapper.is_bootstrapped());
+}
+
+#[tokio::test]
+async fn test_restore_from_backup_replays_transaction_outputs() {
+    // Create test data
+    let restored_version = 200;
+    let epoch_ending_version = 300;
+    let highest_ledger_info = create_random_epoch_ending_ledger_info(epoch_ending_version, 1);
+
+    // Create a driver configuration that restores from backups
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::RestoreFromBackup;
+
+    // Create the mock storage synchronizer and verify the outputs are proven
+    // against the epoch ending ledger info.
+    let mut mock_storage_synchronizer = create_ready_storage_synchronizer(true);
+    mock_storage_synchronizer
+        .expect_apply_transaction_outputs()
+        .times(1)
+        .withf(
+            move |_, output_list_with_proof, target_ledger_info, end_of_epoch_ledger_info| {
+                output_list_with_proof.get_first_output_version() == Some(restored_version + 1)
+                    && target_ledger_info.ledger_info().version() == epoch_ending_version
+                    && end_of_epoch_ledger_info.is_none()
+            },
+        )
+        .return_const(Ok(()));
+
+    // Create the bootstrapper for a node that has already restored a snapshot
+    let mut bootstrapper = create_bootstrapper_with_storage_synchronizer(
+        driver_configuration,
+        create_mock_streaming_client(),
+        MockMetadataStorage::new(),
+        mock_storage_synchronizer,
+        None,
+        restored_version,
+    );
+
+    // Insert an epoch ending ledger info into the verified states of the bootstrapper
+    manipulate_verified_epoch_states(&mut bootstrapper, true, true, Some(epoch_ending_version));
+
+    // Set a backup restorer that holds the rest of the epoch
+    let mut mock_backup_restorer = create_mock_backup_restorer();
+    let (mut notification_sender, data_stream_listener) = create_data_stream_listener();
+    mock_backup_restorer
+        .expect_get_highest_transaction_version()
+        .times(1)
+        .returning(move || Ok(Some(epoch_ending_version + 100)));
+    mock_backup_restorer
+        .expect_get_transaction_outputs()
+        .times(1)
+        .withf(move |start_version, epoch_ending_ledger_info| {
+            *start_version == restored_version + 1
+                && epoch_ending_ledger_info.ledger_info().version() == epoch_ending_version
+        })
+        .return_once(move |_, _| Ok(data_stream_listener));
+    bootstrapper.set_backup_restorer(Box::new(mock_backup_restorer));
+
+    // Create a global data summary
+    let mut global_data_summary = create_global_summary(1);
+    global_data_summary.advertised_data.synced_ledger_infos = vec![highest_ledger_info];
+
+    // Drive progress to start replaying the transaction outputs from backup storage
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+    assert!(!bootstrapper.is_bootstrapped());
+
+    // Send the first transaction outputs and drive progress to apply them
+    let output_list_with_proof =
+        TransactionOutputListWithProofV2::new_from_v1(TransactionOutputListWithProof::new(
+            vec![(create_transaction(), create_transaction_output())],
+            Some(restored_version + 1),
+            TransactionInfoListWithProof::new_empty(),
+        ));
+    let data_notification = DataNotification::new(
+        0,
+        DataPayload::TransactionOutputsWithProof(output_list_with_proof),
+    );
+    notification_sender.send(data_notification).await.unwrap();
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+    assert!(!bootstrapper.is_bootstrapped());
+}
+
 #[tokio::test]
 async fn test_snapshot_sync_epoch_change() {
     // Create test data
@@ -1300,6 +1648,109 @@ async fn test_snapshot_sync_fresh_state() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn test_snapshot_sync_pinned_target() {
+    // Create test data
+    let synced_version = GENESIS_TRANSACTION_VERSION; // Genesis is the highest synced
+    let target_version = 500;
+    let highest_version = 1000;
+    let highest_ledger_info = create_random_epoch_ending_ledger_info(highest_version, 1);
+
+    // Create a driver configuration with a pinned bootstrapping target version
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::DownloadLatestStates;
+    driver_configuration.config.bootstrapping_target_version = Some(target_version);
+
+    // Create the mock streaming client (expecting a stream at the target version)
+    let mut mock_streaming_client = create_mock_streaming_client();
+    let (_notification_sender_1, data_stream_listener_1) = create_data_stream_listener();
+    mock_streaming_client
+        .expect_get_all_state_values()
+        .times(1)
+        .with(eq(target_version), eq(Some(0)))
+        .return_once(move |_, _| Ok(data_stream_listener_1));
+
+    // Create the mock metadata storage
+    let mut metadata_storage = MockMetadataStorage::new();
+    metadata_storage
+        .expect_previous_snapshot_sync_target()
+        .returning(move || Ok(None));
+
+    // Create the bootstrapper
+    let mut bootstrapper = create_bootstrapper_with_storage(
+        driver_configuration,
+        mock_streaming_client,
+        metadata_storage,
+        None,
+        synced_version,
+        true,
+    );
+
+    // Insert an epoch ending ledger info (at the target) into the verified states
+    manipulate_verified_epoch_states(&mut bootstrapper, true, true, Some(target_version));
+
+    // Manually insert a transaction output to sync
+    bootstrapper
+        .get_state_value_syncer()
+        .set_transaction_output_to_sync(create_output_list_with_proof());
+
+    // Create a global data summary where the states at the target are advertised
+    let mut global_data_summary = create_global_summary(1);
+    global_data_summary.advertised_data.synced_ledger_infos = vec![highest_ledger_info.clone()];
+    global_data_summary.advertised_data.states =
+        vec![CompleteDataRange::new(target_version, highest_version).unwrap()];
+
+    // Drive progress to start the state value stream
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_snapshot_sync_pinned_target_unservable() {
+    // Create test data
+    let synced_version = GENESIS_TRANSACTION_VERSION; // Genesis is the highest synced
+    let target_version = 500;
+    let highest_version = 1000;
+    let highest_ledger_info = create_random_epoch_ending_ledger_info(highest_version, 1);
+
+    // Create a driver configuration with a pinned bootstrapping target version
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::DownloadLatestStates;
+    driver_configuration.config.bootstrapping_target_version = Some(target_version);
+
+    // Create the mock metadata storage
+    let mut metadata_storage = MockMetadataStorage::new();
+    metadata_storage
+        .expect_previous_snapshot_sync_target()
+        .returning(move || Ok(None));
+
+    // Create the bootstrapper
+    let mut bootstrapper = create_bootstrapper_with_storage(
+        driver_configuration,
+        create_mock_streaming_client(),
+        metadata_storage,
+        None,
+        synced_version,
+        true,
+    );
+
+    // Insert an epoch ending ledger info (at the target) into the verified states
+    manipulate_verified_epoch_states(&mut bootstrapper, true, true, Some(target_version));
+
+    // Create a global data summary where only states above the target are advertised
+    let mut global_data_summary = create_global_summary(1);
+    global_data_summary.advertised_data.synced_ledger_infos = vec![highest_ledger_info.clone()];
+    global_data_summary.advertised_data.states =
+        vec![CompleteDataRange::new(target_version + 1, highest_version).unwrap()];
+
+    // Drive progress and verify an error is returned
+    let error = drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::UnservableBootstrappingTarget(_));
+}
+
 #[tokio::test]
 #[should_panic(
     expected = "The snapshot sync for the target was marked as complete but the highest synced version is genesis!"
@@ -1646,13 +2097,31 @@ fn create_bootstrapper_with_storage(
     latest_synced_epoch: Option<u64>,
     latest_synced_version: Version,
     expect_reset_executor: bool,
+) -> Bootstrapper<MockMetadataStorage, MockStorageSynchronizer, MockStreamingClient> {
+    let mock_storage_synchronizer = create_ready_storage_synchronizer(expect_reset_executor);
+    create_bootstrapper_with_storage_synchronizer(
+        driver_configuration,
+        mock_streaming_client,
+        mock_metadata_storage,
+        mock_storage_synchronizer,
+        latest_synced_epoch,
+        latest_synced_version,
+    )
+}
+
+/// Creates a bootstrapper for testing (with the given synced epoch and
+/// version in storage) that uses the given mock storage synchronizer.
+fn create_bootstrapper_with_storage_synchronizer(
+    driver_configuration: DriverConfiguration,
+    mock_streaming_client: MockStreamingClient,
+    mock_metadata_storage: MockMetadataStorage,
+    mock_storage_synchronizer: MockStorageSynchronizer,
+    latest_synced_epoch: Option<u64>,
+    latest_synced_version: Version,
 ) -> Bootstrapper<MockMetadataStorage, MockStorageSynchronizer, MockStreamingClient> {
     // Initialize the logger for tests
     lumio_logger::Logger::init_for_testing();
 
-    // Create the mock storage synchronizer
-    let mock_storage_synchronizer = create_ready_storage_synchronizer(expect_reset_executor);
-
     // Determine the epoch state and ledger info
     let (epoch_state, epoch_ending_ledger_info) = match latest_synced_epoch {
         Some(latest_synced_epoch) => (
@@ -1694,6 +2163,117 @@ fn create_bootstrapper_with_storage(
     )
 }
 
+/// Creates a bootstrapper for testing (with only genesis in storage) that
+/// restores state snapshots using the given mock backup restorer.
+fn create_bootstrapper_with_backup_restorer(
+    driver_configuration: DriverConfiguration,
+    mock_streaming_client: MockStreamingClient,
+    mock_storage_synchronizer: MockStorageSynchronizer,
+    mock_backup_restorer: MockBackupRestorer,
+) -> Bootstrapper<MockMetadataStorage, MockStorageSynchronizer, MockStreamingClient> {
+    // Initialize the logger for tests
+    lumio_logger::Logger::init_for_testing();
+
+    // Create the mock metadata storage
+    let mut metadata_storage = MockMetadataStorage::new();
+    metadata_storage
+        .expect_previous_snapshot_sync_target()
+        .returning(|| Ok(None));
+
+    // Create the mock db reader with only genesis loaded
+    let mut mock_database_reader = create_mock_db_reader();
+    mock_database_reader
+        .expect_get_latest_epoch_state()
+        .returning(|| Ok(create_empty_epoch_state()));
+    mock_database_reader
+        .expect_get_latest_ledger_info()
+        .returning(|| Ok(create_epoch_ending_ledger_info()));
+    mock_database_reader
+        .expect_get_synced_version()
+        .returning(|| Ok(Some(0)));
+    mock_database_reader
+        .expect_get_pre_committed_version()
+        .returning(|| Ok(Some(0)));
+
+    // Create the output fallback handler
+    let output_fallback_handler =
+        OutputFallbackHandler::new(driver_configuration.clone(), TimeService::mock());
+
+    // Create the bootstrapper
+    let mut bootstrapper = Bootstrapper::new(
+        driver_configuration,
+        metadata_storage,
+        output_fallback_handler,
+        mock_streaming_client,
+        Arc::new(mock_database_reader),
+        mock_storage_synchronizer,
+    );
+    bootstrapper.set_backup_restorer(Box::new(mock_backup_restorer));
+
+    bootstrapper
+}
+
+/// Creates an epoch ending ledger info at the given version and epoch, and
+/// a transaction output at the same version with a valid proof against the
+/// ledger info. The transaction info holds the given state checkpoint hash.
+fn create_epoch_ending_output_with_proof(
+    version: Version,
+    epoch: u64,
+    state_checkpoint_hash: HashValue,
+) -> (LedgerInfoWithSignatures, TransactionOutputListWithProofV2) {
+    // Create the transaction output and the matching transaction info
+    let transaction = create_transaction();
+    let transaction_output = TransactionOutput::new(
+        WriteSet::default(),
+        vec![],
+        0,
+        TransactionStatus::Keep(ExecutionStatus::Success),
+        TransactionAuxiliaryData::default(),
+    );
+    let transaction_info = TransactionInfo::new(
+        CryptoHash::hash(&transaction),
+        CryptoHash::hash(transaction_output.write_set()),
+        InMemoryEventAccumulator::from_leaves(&[]).root_hash(),
+        Some(state_checkpoint_hash),
+        0,
+        ExecutionStatus::Success,
+        None,
+    );
+
+    // Create the transaction accumulator with the transaction info as the last leaf
+    let mut transaction_info_hashes: Vec<_> = (0..version).map(|_| HashValue::random()).collect();
+    let range_proof = create_range_proof_at_version(&[], 0, &transaction_info_hashes).unwrap();
+    transaction_info_hashes.push(CryptoHash::hash(&transaction_info));
+    let accumulator_root_hash =
+        InMemoryTransactionAccumulator::from_leaves(&transaction_info_hashes).root_hash();
+
+    // Create the epoch ending ledger info that commits to the accumulator
+    let next_epoch_state = EpochState::new(epoch + 1, ValidatorVerifier::new(vec![]));
+    let block_info = BlockInfo::new(
+        epoch,
+        0,
+        HashValue::zero(),
+        accumulator_root_hash,
+        version,
+        version,
+        Some(next_epoch_state),
+    );
+    let ledger_info = LedgerInfoWithSignatures::new(
+        LedgerInfo::new(block_info, HashValue::random()),
+        AggregateSignature::empty(),
+    );
+
+    // Create the transaction output with proof
+    let output_with_proof =
+        TransactionOutputListWithProofV2::new_from_v1(TransactionOutputListWithProof::new(
+            vec![(transaction, transaction_output)],
+            Some(version),
+            TransactionInfoListWithProof::new(range_proof, vec![transaction_info]),
+        ));
+
+    (ledger_info, output_with_proof)
+}
+
 /// Drives progress for the given bootstrapper. If `until_bootstrapped`
 /// is true this method will continue to drive the bootstrapper until
 /// bootstrapping is complete.
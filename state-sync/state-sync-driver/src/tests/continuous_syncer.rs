@@ -783,6 +783,29 @@ async fn test_data_stream_transactions_or_outputs_with_sync_target_fallback() {
     assert!(!output_fallback_handler.in_fallback_mode());
 }
 
+#[tokio::test]
+async fn test_frozen_continuous_syncing() {
+    // Create a driver configuration with frozen continuous syncing
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.continuous_syncing_mode = ContinuousSyncingMode::Frozen;
+
+    // Create the continuous syncer (the streaming client should never be called)
+    let (mut continuous_syncer, _) = create_continuous_syncer(
+        driver_configuration,
+        create_mock_streaming_client(),
+        None,
+        false,
+        5,
+        1,
+    );
+
+    // Drive progress several times and verify that no data stream is initialized
+    let no_sync_request = Arc::new(Mutex::new(None));
+    for _ in 0..5 {
+        drive_progress(&mut continuous_syncer, &no_sync_request).await;
+    }
+}
+
 /// Creates a continuous syncer for testing
 fn create_continuous_syncer(
     driver_configuration: DriverConfiguration,
@@ -11,7 +11,7 @@ use crate::{
         verify_commit_notification,
     },
 };
-use lumio_config::config::{NodeConfig, RoleType, StateSyncDriverConfig};
+use lumio_config::config::{ContinuousSyncingMode, NodeConfig, RoleType, StateSyncDriverConfig};
 use lumio_consensus_notifications::{ConsensusNotificationSender, ConsensusNotifier};
 use lumio_data_client::client::LumioDataClient;
 use lumio_data_streaming_service::streaming_client::new_streaming_service_client_listener_pair;
@@ -31,6 +31,7 @@ use lumio_storage_service_notifications::StorageServiceNotificationListener;
 use lumio_time_service::TimeService;
 use lumio_types::{
     event::EventKey,
+    state_sync_progress::SharedStateSyncProgress,
     transaction::{Transaction, WriteSetPayload},
     waypoint::Waypoint,
 };
@@ -38,7 +39,7 @@ use lumio_vm::lumio_vm::LumioVMBlockExecutor;
 use claims::{assert_err, assert_none};
 use futures::{channel::mpsc::UnboundedSender, FutureExt, SinkExt, StreamExt};
 use ntest::timeout;
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{collections::HashMap, fmt::Debug, sync::Arc, time::Duration};
 use tokio::time::sleep;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -272,6 +273,33 @@ async fn test_consensus_sync_request() {
     assert_err!(result);
 }
 
+#[tokio::test]
+#[timeout(120_000)]
+async fn test_frozen_node_sync_requests() {
+    // Create a driver for a frozen full node that runs consensus observer
+    let mut node_config = NodeConfig::default();
+    node_config.base.role = RoleType::FullNode;
+    node_config.consensus_observer.observer_enabled = true;
+    node_config
+        .state_sync
+        .state_sync_driver
+        .continuous_syncing_mode = ContinuousSyncingMode::Frozen;
+    let (_full_node_driver, _, consensus_notifier, _, _, _, _, _) =
+        create_driver_for_tests(node_config, Waypoint::default(), None).await;
+
+    // Send a sync target request and verify it is rejected
+    let result = consensus_notifier
+        .sync_to_target(create_ledger_info_at_version(0))
+        .await;
+    verify_frozen_node_error(result);
+
+    // Send a sync duration request and verify it is rejected
+    let result = consensus_notifier
+        .sync_for_duration(Duration::from_secs(1))
+        .await;
+    verify_frozen_node_error(result);
+}
+
 /// Creates a state sync driver for a validator node
 async fn create_validator_driver(
     event_key_subscriptions: Option<Vec<EventKey>>,
@@ -314,6 +342,16 @@ async fn create_full_node_driver(
     create_driver_for_tests(node_config, Waypoint::default(), event_key_subscriptions).await
 }
 
+/// Verifies that the given result contains a frozen node error
+fn verify_frozen_node_error<T: Debug>(result: Result<T, lumio_consensus_notifications::Error>) {
+    match result {
+        Err(lumio_consensus_notifications::Error::UnexpectedErrorEncountered(error)) => {
+            assert!(error.contains("FrozenNodeConsensusNotification"));
+        },
+        result => panic!("Expected a frozen node error, but got: {:?}", result),
+    }
+}
+
 /// Creates a state sync driver using the given node config and waypoint
 async fn create_driver_for_tests(
     node_config: NodeConfig,
@@ -405,6 +443,8 @@ async fn create_driver_for_tests(
             event_subscription_service,
             lumio_data_client,
             streaming_service_client,
+            None,
+            SharedStateSyncProgress::new(),
             time_service.clone(),
         );
 
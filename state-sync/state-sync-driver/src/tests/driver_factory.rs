@@ -24,6 +24,7 @@ use lumio_storage_interface::DbReaderWriter;
 use lumio_storage_service_client::StorageServiceClient;
 use lumio_temppath::TempPath;
 use lumio_time_service::TimeService;
+use lumio_types::state_sync_progress::SharedStateSyncProgress;
 use lumio_vm::lumio_vm::LumioVMBlockExecutor;
 use futures::{FutureExt, StreamExt};
 use std::{collections::HashMap, sync::Arc};
@@ -100,6 +101,8 @@ fn test_new_initialized_configs() {
         event_subscription_service,
         lumio_data_client,
         streaming_service_client,
+        None,
+        SharedStateSyncProgress::new(),
         TimeService::mock(),
     );
 
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    backup_restorer::BackupRestorerInterface,
     error::Error,
     metadata_storage::MetadataStorageInterface,
     storage_synchronizer::{NotificationMetadata, StorageSynchronizerInterface},
@@ -104,6 +105,11 @@ pub fn create_mock_receiver() -> MockSnapshotReceiver {
     MockSnapshotReceiver::new()
 }
 
+/// Creates a mock backup restorer
+pub fn create_mock_backup_restorer() -> MockBackupRestorer {
+    MockBackupRestorer::new()
+}
+
 /// Creates a mock data streaming client
 pub fn create_mock_streaming_client() -> MockStreamingClient {
     MockStreamingClient::new()
@@ -373,6 +379,36 @@ mock! {
     }
 }
 
+// This automatically creates a MockBackupRestorer.
+mock! {
+    pub BackupRestorer {}
+    #[async_trait]
+    impl BackupRestorerInterface for BackupRestorer {
+        fn is_backup_stream(&self, data_stream_id: DataStreamId) -> bool;
+
+        async fn get_state_snapshot_versions(&mut self) -> AnyhowResult<Vec<Version>, Error>;
+
+        async fn get_transaction_output(
+            &mut self,
+            target_ledger_info: LedgerInfoWithSignatures,
+        ) -> AnyhowResult<DataStreamListener, Error>;
+
+        async fn get_state_values(
+            &mut self,
+            version: Version,
+            start_index: u64,
+        ) -> AnyhowResult<DataStreamListener, Error>;
+
+        async fn get_highest_transaction_version(&mut self) -> AnyhowResult<Option<Version>, Error>;
+
+        async fn get_transaction_outputs(
+            &mut self,
+            start_version: Version,
+            epoch_ending_ledger_info: LedgerInfoWithSignatures,
+        ) -> AnyhowResult<DataStreamListener, Error>;
+    }
+}
+
 // This automatically creates a MockStreamingClient.
 mock! {
     pub StreamingClient {}
@@ -2,11 +2,13 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+mod backup_restorer;
 mod bootstrapper;
 mod continuous_syncer;
 mod driver;
 mod driver_factory;
 mod metadata_storage;
 mod mocks;
+mod progress_tracker;
 mod storage_synchronizer;
 mod utils;
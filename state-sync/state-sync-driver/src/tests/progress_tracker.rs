@@ -0,0 +1,114 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::progress_tracker::ProgressTracker;
+use lumio_time_service::TimeService;
+use lumio_types::state_sync_progress::{SharedStateSyncProgress, StateSyncPhase};
+use std::time::Duration;
+
+#[test]
+fn test_transaction_progress() {
+    // Create the progress tracker
+    let shared_progress = SharedStateSyncProgress::new();
+    let time_service = TimeService::mock();
+    let mut progress_tracker = ProgressTracker::new(shared_progress.clone(), time_service.clone());
+
+    // Verify that no progress has been published yet
+    assert!(shared_progress.get_progress().is_none());
+
+    // Update the progress at the start of the transaction phase
+    let target_version = 1000;
+    progress_tracker.update_progress(
+        StateSyncPhase::Transactions,
+        0,
+        Some(target_version),
+        None,
+        None,
+    );
+
+    // Verify that the throughput and estimated time remaining are unknown
+    let progress = shared_progress.get_progress().unwrap();
+    assert_eq!(progress.phase, StateSyncPhase::Transactions);
+    assert_eq!(progress.synced_version, 0);
+    assert_eq!(progress.target_version, Some(target_version));
+    assert_eq!(progress.throughput_per_second, None);
+    assert_eq!(progress.estimated_seconds_remaining, None);
+
+    // Elapse some time and update the progress
+    time_service.into_mock().advance(Duration::from_secs(10));
+    progress_tracker.update_progress(
+        StateSyncPhase::Transactions,
+        200,
+        Some(target_version),
+        None,
+        None,
+    );
+
+    // Verify that the throughput and estimated time remaining are calculated
+    let progress = shared_progress.get_progress().unwrap();
+    assert_eq!(progress.synced_version, 200);
+    assert_eq!(progress.throughput_per_second, Some(20));
+    assert_eq!(progress.estimated_seconds_remaining, Some(40));
+
+    // Move to continuous syncing and verify the throughput is reset
+    progress_tracker.update_progress(
+        StateSyncPhase::ContinuousSyncing,
+        200,
+        Some(target_version),
+        None,
+        None,
+    );
+    let progress = shared_progress.get_progress().unwrap();
+    assert_eq!(progress.phase, StateSyncPhase::ContinuousSyncing);
+    assert_eq!(progress.throughput_per_second, None);
+    assert_eq!(progress.estimated_seconds_remaining, None);
+}
+
+#[test]
+fn test_state_progress() {
+    // Create the progress tracker
+    let shared_progress = SharedStateSyncProgress::new();
+    let time_service = TimeService::mock();
+    let mut progress_tracker = ProgressTracker::new(shared_progress.clone(), time_service.clone());
+
+    // Update the progress at the start of the state phase (without a known total)
+    let snapshot_version = 5000;
+    progress_tracker.update_progress(
+        StateSyncPhase::States,
+        0,
+        Some(snapshot_version),
+        Some(0),
+        None,
+    );
+
+    // Elapse some time and verify the throughput is measured in states
+    time_service
+        .clone()
+        .into_mock()
+        .advance(Duration::from_secs(5));
+    progress_tracker.update_progress(
+        StateSyncPhase::States,
+        0,
+        Some(snapshot_version),
+        Some(5000),
+        None,
+    );
+    let progress = shared_progress.get_progress().unwrap();
+    assert_eq!(progress.num_states_synced, Some(5000));
+    assert_eq!(progress.throughput_per_second, Some(1000));
+    assert_eq!(progress.estimated_seconds_remaining, None);
+
+    // Update the progress with the total number of states and verify the estimate
+    time_service.into_mock().advance(Duration::from_secs(5));
+    progress_tracker.update_progress(
+        StateSyncPhase::States,
+        0,
+        Some(snapshot_version),
+        Some(10_000),
+        Some(100_000),
+    );
+    let progress = shared_progress.get_progress().unwrap();
+    assert_eq!(progress.num_states_total, Some(100_000));
+    assert_eq!(progress.throughput_per_second, Some(1000));
+    assert_eq!(progress.estimated_seconds_remaining, Some(90));
+}
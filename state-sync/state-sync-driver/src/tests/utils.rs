@@ -3,7 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::driver::DriverConfiguration;
-use lumio_config::config::{ConsensusObserverConfig, RoleType, StateSyncDriverConfig};
+use lumio_config::config::{
+    BackupRestoreConfig, ConsensusObserverConfig, RoleType, StateSyncDriverConfig,
+};
 use lumio_crypto::{
     ed25519::{Ed25519PrivateKey, Ed25519Signature},
     HashValue, PrivateKey, Uniform,
@@ -94,12 +96,14 @@ pub fn create_reconfig_event() -> ContractEvent {
 
 /// Creates a test driver configuration for full nodes
 pub fn create_full_node_driver_configuration() -> DriverConfiguration {
+    let backup_restore_config = BackupRestoreConfig::default();
     let config = StateSyncDriverConfig::default();
     let consensus_observer_config = ConsensusObserverConfig::default();
     let role = RoleType::FullNode;
     let waypoint = Waypoint::default();
 
     DriverConfiguration {
+        backup_restore_config,
         config,
         consensus_observer_config,
         role,
@@ -8,6 +8,8 @@ use thiserror::Error;
 pub enum Error {
     #[error("Invalid request received: {0}")]
     InvalidRequest(String),
+    #[error("Request throttled: {0}")]
+    RequestThrottled(String),
     #[error("Storage error encountered: {0}")]
     StorageErrorEncountered(String),
     #[error("Too many invalid requests: {0}")]
@@ -21,6 +23,7 @@ impl Error {
     pub fn get_label(&self) -> &'static str {
         match self {
             Error::InvalidRequest(_) => "invalid_request",
+            Error::RequestThrottled(_) => "request_throttled",
             Error::StorageErrorEncountered(_) => "storage_error",
             Error::TooManyInvalidRequests(_) => "too_many_invalid_requests",
             Error::UnexpectedErrorEncountered(_) => "unexpected_error",
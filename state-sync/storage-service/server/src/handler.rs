@@ -12,6 +12,7 @@ use crate::{
     moderator::RequestModerator,
     network::ResponseSender,
     optimistic_fetch::OptimisticFetchRequest,
+    scheduler::RequestPermit,
     storage::StorageReaderInterface,
     subscription::{SubscriptionRequest, SubscriptionStreamRequests},
     utils,
@@ -21,9 +22,10 @@ use lumio_logger::{debug, sample, sample::SampleRate, trace, warn};
 use lumio_network::protocols::wire::handshake::v1::ProtocolId;
 use lumio_storage_service_types::{
     requests::{
-        DataRequest, EpochEndingLedgerInfoRequest, GetTransactionDataWithProofRequest,
-        StateValuesWithProofRequest, StorageServiceRequest, TransactionOutputsWithProofRequest,
-        TransactionsOrOutputsWithProofRequest, TransactionsWithProofRequest,
+        DataRequest, EpochEndingLedgerInfoRequest, FilteredStateValuesWithProofRequest,
+        GetTransactionDataWithProofRequest, StateValuesWithProofRequest, StorageServiceRequest,
+        TransactionOutputsWithProofRequest, TransactionsOrOutputsWithProofRequest,
+        TransactionsWithProofRequest,
     },
     responses::{
         DataResponse, ServerProtocolVersion, StorageServerSummary, StorageServiceResponse,
@@ -86,6 +88,7 @@ impl<T: StorageReaderInterface> Handler<T> {
         protocol_id: ProtocolId,
         request: StorageServiceRequest,
         response_sender: ResponseSender,
+        request_permit: RequestPermit,
     ) {
         // Log the request
         trace!(LogSchema::new(LogEntry::ReceivedStorageRequest)
@@ -135,7 +138,10 @@ impl<T: StorageReaderInterface> Handler<T> {
 
         // Process the request and return the response to the client
         let response = self.process_request(&peer_network_id, request.clone(), false);
-        self.send_response(request, response, response_sender);
+        let num_response_bytes = self.send_response(request, response, response_sender);
+
+        // Release the request permit (charging the response bytes to the peer)
+        request_permit.release(num_response_bytes);
     }
 
     /// Processes the given request and returns the response
@@ -195,6 +201,7 @@ impl<T: StorageReaderInterface> Handler<T> {
         // Transform the request error into a storage service error (for the client)
         process_result.map_err(|error| match error {
             Error::InvalidRequest(error) => StorageServiceError::InvalidRequest(error),
+            Error::RequestThrottled(error) => StorageServiceError::RequestThrottled(error),
             Error::TooManyInvalidRequests(error) => {
                 StorageServiceError::TooManyInvalidRequests(error)
             },
@@ -228,15 +235,15 @@ impl<T: StorageReaderInterface> Handler<T> {
         }
     }
 
-    /// Sends a response via the provided sender
+    /// Sends a response via the provided sender and returns the number of bytes sent
     pub(crate) fn send_response(
         &self,
         request: StorageServiceRequest,
         response: lumio_storage_service_types::Result<StorageServiceResponse>,
         response_sender: ResponseSender,
-    ) {
+    ) -> u64 {
         log_storage_response(request, &response);
-        response_sender.send(response);
+        response_sender.send(response)
     }
 
     /// Handles the given optimistic fetch request
@@ -426,6 +433,9 @@ impl<T: StorageReaderInterface> Handler<T> {
             DataRequest::GetTransactionDataWithProof(request) => {
                 self.get_transaction_data_with_proof(request)
             },
+            DataRequest::GetFilteredStateValuesWithProof(request) => {
+                self.get_filtered_state_values_with_proof(request)
+            },
             _ => Err(Error::UnexpectedErrorEncountered(format!(
                 "Received an unexpected request: {:?}",
                 request
@@ -486,6 +496,22 @@ impl<T: StorageReaderInterface> Handler<T> {
         Ok(DataResponse::EpochEndingLedgerInfos(epoch_change_proof))
     }
 
+    fn get_filtered_state_values_with_proof(
+        &self,
+        request: &FilteredStateValuesWithProofRequest,
+    ) -> lumio_storage_service_types::Result<DataResponse, Error> {
+        let filtered_state_values_with_proof = self.storage.get_filtered_state_values_with_proof(
+            request.version,
+            &request.key_prefix,
+            request.start_key.as_ref(),
+            &request.known_keys,
+        )?;
+
+        Ok(DataResponse::FilteredStateValuesWithProof(
+            filtered_state_values_with_proof,
+        ))
+    }
+
     fn get_number_of_states_at_version(
         &self,
         version: Version,
@@ -20,6 +20,7 @@ use lumio_storage_service_notifications::StorageServiceNotificationListener;
 use lumio_storage_service_types::{
     requests::StorageServiceRequest,
     responses::{ProtocolMetadata, StorageServerSummary, StorageServiceResponse},
+    StorageServiceError,
 };
 use lumio_time_service::{TimeService, TimeServiceTrait};
 use arc_swap::ArcSwap;
@@ -30,6 +31,7 @@ use handler::Handler;
 use mini_moka::sync::Cache;
 use moderator::RequestModerator;
 use optimistic_fetch::OptimisticFetchRequest;
+use scheduler::RequestScheduler;
 use std::{ops::Deref, sync::Arc, time::Duration};
 use storage::StorageReaderInterface;
 use thiserror::Error;
@@ -42,6 +44,7 @@ pub mod metrics;
 mod moderator;
 pub mod network;
 mod optimistic_fetch;
+mod scheduler;
 pub mod storage;
 mod subscription;
 mod utils;
@@ -81,6 +84,9 @@ pub struct StorageServiceServer<T> {
     // A moderator for incoming peer requests
     request_moderator: Arc<RequestModerator>,
 
+    // A scheduler that prioritizes incoming peer requests
+    request_scheduler: Arc<RequestScheduler>,
+
     // The listener for notifications from state sync
     storage_service_listener: Option<StorageServiceNotificationListener>,
 
@@ -115,6 +121,10 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
             storage_service_config,
             time_service.clone(),
         ));
+        let request_scheduler = Arc::new(RequestScheduler::new(
+            storage_service_config.request_scheduler,
+            time_service.clone(),
+        ));
         let storage_service_listener = Some(storage_service_listener);
 
         Self {
@@ -127,6 +137,7 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
             optimistic_fetches,
             subscriptions,
             request_moderator,
+            request_scheduler,
             storage_service_listener,
             runtime,
         }
@@ -157,7 +168,7 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
         self.spawn_subscription_handler(cache_update_listener_subscription)
             .await;
 
-        // Spawn the refresher for the request moderator
+        // Spawn the refresher for the request moderator and scheduler
         self.spawn_moderator_peer_refresher().await;
     }
 
@@ -352,12 +363,13 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
             });
     }
 
-    /// Spawns a non-terminating task that refreshes the unhealthy
-    /// peer states in the request moderator.
+    /// Spawns a non-terminating task that refreshes the unhealthy peer states
+    /// in the request moderator (and the peer states in the request scheduler).
     async fn spawn_moderator_peer_refresher(&mut self) {
         // Clone all required components for the task
         let config = self.storage_service_config;
         let request_moderator = self.request_moderator.clone();
+        let request_scheduler = self.request_scheduler.clone();
         let time_service = self.time_service.clone();
 
         // Spawn the task
@@ -377,6 +389,9 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
                         .error(&error)
                         .message("Failed to refresh the request moderator!"));
                 }
+
+                // Garbage collect the idle peer states in the scheduler
+                request_scheduler.refresh_peer_states();
             }
         });
     }
@@ -388,6 +403,31 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
 
         // Handle the storage requests as they arrive
         while let Some(network_request) = self.network_requests.next().await {
+            // Schedule the request (bulk requests may be throttled to ensure fairness)
+            let request_permit = match self.request_scheduler.schedule_request(
+                &network_request.peer_network_id,
+                &network_request.storage_service_request,
+            ) {
+                Ok(request_permit) => request_permit,
+                Err(error) => {
+                    // Notify the peer that the request was throttled. Older clients
+                    // are unable to decode throttling errors, so (by default) these
+                    // are sent as errors that all clients are able to decode.
+                    let error = error.to_string();
+                    let response = if self
+                        .storage_service_config
+                        .request_scheduler
+                        .legacy_throttling_errors
+                    {
+                        Err(StorageServiceError::TooManyInvalidRequests(error))
+                    } else {
+                        Err(StorageServiceError::RequestThrottled(error))
+                    };
+                    network_request.response_sender.send(response);
+                    continue;
+                },
+            };
+
             // All handler methods are currently CPU-bound and synchronous
             // I/O-bound, so we want to spawn on the blocking thread pool to
             // avoid starving other async tasks on the same runtime.
@@ -415,6 +455,7 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
                     network_request.protocol_id,
                     network_request.storage_service_request,
                     network_request.response_sender,
+                    request_permit,
                 );
             });
         }
@@ -426,6 +467,12 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
         self.request_moderator.clone()
     }
 
+    #[cfg(test)]
+    /// Returns a copy of the request scheduler for test purposes
+    pub(crate) fn get_request_scheduler(&self) -> Arc<RequestScheduler> {
+        self.request_scheduler.clone()
+    }
+
     #[cfg(test)]
     /// Returns a copy of the active optimistic fetches for test purposes
     pub(crate) fn get_optimistic_fetches(
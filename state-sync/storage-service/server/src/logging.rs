@@ -44,6 +44,7 @@ pub enum LogEntry {
     ReceivedStorageRequest,
     RequestModeratorIgnoredPeer,
     RequestModeratorRefresh,
+    RequestSchedulerThrottledPeer,
     SentStorageResponse,
     StorageServiceError,
     StorageSummaryRefresh,
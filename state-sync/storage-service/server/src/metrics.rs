@@ -11,16 +11,21 @@ use once_cell::sync::Lazy;
 use std::time::Instant;
 
 /// Useful metric constants for the storage service
+pub const BULK_REQUEST_ADMITTED: &str = "bulk_request_admitted";
 pub const LRU_CACHE_HIT: &str = "lru_cache_hit";
 pub const LRU_CACHE_PROBE: &str = "lru_cache_probe";
 pub const OPTIMISTIC_FETCH_ADD: &str = "optimistic_fetch_add";
 pub const OPTIMISTIC_FETCH_EXPIRE: &str = "optimistic_fetch_expire";
+pub const REALTIME_REQUEST_ADMITTED: &str = "realtime_request_admitted";
 pub const RESULT_SUCCESS: &str = "success";
 pub const RESULT_FAILURE: &str = "failure";
 pub const SUBSCRIPTION_ADD: &str = "subscription_add";
 pub const SUBSCRIPTION_EXPIRE: &str = "subscription_expire";
 pub const SUBSCRIPTION_FAILURE: &str = "subscription_failure";
 pub const SUBSCRIPTION_NEW_STREAM: &str = "subscription_new_stream";
+pub const THROTTLED_FOR_CONCURRENCY: &str = "throttled_for_concurrency";
+pub const THROTTLED_FOR_PEER_BYTES: &str = "throttled_for_peer_bytes";
+pub const THROTTLED_FOR_PEER_CONCURRENCY: &str = "throttled_for_peer_concurrency";
 pub const TRUNCATION_FOR_SIZE: &str = "size_truncation";
 pub const TRUNCATION_FOR_TIME: &str = "time_truncation";
 
@@ -30,6 +35,26 @@ const REQUEST_PROCESSING_LATENCY_BUCKETS_SECS: &[f64] = &[
     60.0, 120.0, 180.0, 240.0, 300.0,
 ];
 
+/// Gauge for tracking the number of active bulk requests
+pub static ACTIVE_BULK_REQUESTS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "lumio_storage_service_server_active_bulk_requests",
+        "Gauge for tracking the number of active bulk requests",
+        &["network_id"]
+    )
+    .unwrap()
+});
+
+/// Counter for the number of response bytes sent for bulk requests
+pub static BULK_RESPONSE_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "lumio_storage_service_server_bulk_response_bytes",
+        "Counters for the response bytes sent for bulk requests",
+        &["network_id"]
+    )
+    .unwrap()
+});
+
 /// Gauge for tracking the number of actively ignored peers
 pub static IGNORED_PEER_COUNT: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
@@ -102,6 +127,16 @@ pub static PENDING_STORAGE_SERVER_NETWORK_EVENTS: Lazy<IntCounterVec> = Lazy::ne
     .unwrap()
 });
 
+/// Counter for request scheduler events (e.g., admitted and throttled requests)
+pub static REQUEST_SCHEDULER_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "lumio_storage_service_server_request_scheduler_event",
+        "Counters related to request scheduler events",
+        &["network_id", "event"]
+    )
+    .unwrap()
+});
+
 /// Counter for storage service errors encountered
 pub static STORAGE_ERRORS_ENCOUNTERED: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -229,6 +264,13 @@ pub fn increment_counter(counter: &Lazy<IntCounterVec>, network_id: NetworkId, l
         .inc();
 }
 
+/// Increments the bulk response bytes counter for the given network
+pub fn increment_bulk_response_bytes(network_id: NetworkId, num_bytes: u64) {
+    BULK_RESPONSE_BYTES
+        .with_label_values(&[network_id.as_str()])
+        .inc_by(num_bytes);
+}
+
 /// Sets the gauge with the specific label and value
 pub fn set_gauge(counter: &Lazy<IntGaugeVec>, label: &str, value: u64) {
     counter.with_label_values(&[label]).set(value as i64);
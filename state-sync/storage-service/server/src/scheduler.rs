@@ -0,0 +1,368 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::Error,
+    logging::{LogEntry, LogSchema},
+    metrics,
+    metrics::{
+        increment_counter, BULK_REQUEST_ADMITTED, REALTIME_REQUEST_ADMITTED,
+        THROTTLED_FOR_CONCURRENCY, THROTTLED_FOR_PEER_BYTES, THROTTLED_FOR_PEER_CONCURRENCY,
+    },
+};
+use lumio_config::{
+    config::RequestSchedulerConfig,
+    network_id::{NetworkId, PeerNetworkId},
+};
+use lumio_infallible::Mutex;
+use lumio_logger::{sample, sample::SampleRate, warn};
+use lumio_storage_service_types::requests::{DataRequest, StorageServiceRequest};
+use lumio_time_service::{TimeService, TimeServiceTrait};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+// The duration of each per-peer byte budget window
+const BYTE_BUDGET_WINDOW_DURATION: Duration = Duration::from_secs(1);
+
+// The frequency (secs) to log throttled requests
+const THROTTLE_LOG_FREQUENCY_SECS: u64 = 5;
+
+/// The class of a storage service request (used to prioritize requests)
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RequestClass {
+    /// Latency sensitive requests that are cheap to serve, e.g., optimistic
+    /// fetches, subscriptions and storage summaries. These are never throttled.
+    Realtime,
+    /// Requests for (potentially large) chunks of historical data, e.g., states,
+    /// transactions and epoch ending ledger infos. These may be throttled.
+    Bulk,
+}
+
+impl RequestClass {
+    /// Returns the class of the given request
+    pub fn from_request(request: &StorageServiceRequest) -> Self {
+        let data_request = &request.data_request;
+        if data_request.is_optimistic_fetch()
+            || data_request.is_subscription_request()
+            || matches!(
+                data_request,
+                DataRequest::GetNumberOfStatesAtVersion(_)
+                    | DataRequest::GetServerProtocolVersion
+                    | DataRequest::GetStorageServerSummary
+            )
+        {
+            RequestClass::Realtime
+        } else {
+            RequestClass::Bulk
+        }
+    }
+}
+
+/// A simple struct that tracks the bulk request usage of a single peer
+#[derive(Clone, Debug)]
+struct PeerSchedulingState {
+    num_active_bulk_requests: u64, // The number of bulk requests currently being processed
+    window_bytes_sent: u64,        // The number of bulk response bytes sent in the window
+    window_start_time: Instant,    // The start time of the current byte budget window
+}
+
+impl PeerSchedulingState {
+    fn new(window_start_time: Instant) -> Self {
+        Self {
+            num_active_bulk_requests: 0,
+            window_bytes_sent: 0,
+            window_start_time,
+        }
+    }
+
+    /// Returns true iff the peer has no active bulk requests and the
+    /// byte budget window has expired (i.e., the state can be removed).
+    fn is_idle(&self, now: Instant) -> bool {
+        self.num_active_bulk_requests == 0
+            && now.duration_since(self.window_start_time) >= BYTE_BUDGET_WINDOW_DURATION
+    }
+
+    /// Starts a new byte budget window (if the current window has expired)
+    fn refresh_window(&mut self, now: Instant) {
+        if now.duration_since(self.window_start_time) >= BYTE_BUDGET_WINDOW_DURATION {
+            self.window_bytes_sent = 0;
+            self.window_start_time = now;
+        }
+    }
+}
+
+/// The scheduling state shared by all requests. This is protected by a
+/// single lock to ensure that request admission is atomic.
+#[derive(Default)]
+struct SchedulerState {
+    // The number of active bulk requests (per network)
+    active_bulk_requests: HashMap<NetworkId, u64>,
+
+    // The scheduling state of each (non-validator) peer
+    peer_states: HashMap<PeerNetworkId, PeerSchedulingState>,
+}
+
+/// The request scheduler is responsible for prioritizing inbound storage
+/// requests and ensuring fairness between peers. Realtime requests are always
+/// admitted, while bulk requests are admitted only if: (i) the number of active
+/// bulk requests is below the global limit (with a reduced limit for the public
+/// network, to reserve capacity for validators and VFNs); and (ii) the peer is
+/// within its concurrency and byte budgets (validators are exempt from these).
+pub struct RequestScheduler {
+    config: RequestSchedulerConfig,
+    scheduler_state: Mutex<SchedulerState>,
+    time_service: TimeService,
+}
+
+impl RequestScheduler {
+    pub fn new(config: RequestSchedulerConfig, time_service: TimeService) -> Self {
+        Self {
+            config,
+            scheduler_state: Mutex::new(SchedulerState::default()),
+            time_service,
+        }
+    }
+
+    /// Schedules the given request for processing. If the request is admitted,
+    /// a permit is returned (which should be released once the response has
+    /// been sent). Otherwise, the request is throttled and an error is returned.
+    ///
+    /// Note: throttled bulk requests are rejected outright (rather than queued),
+    /// so the server never holds on to work it cannot process. The client is
+    /// expected to back off from the peer and retry the request elsewhere (or
+    /// later), which ensures that a burst of requests from a single peer cannot
+    /// delay the requests of other peers.
+    pub fn schedule_request(
+        self: &Arc<Self>,
+        peer_network_id: &PeerNetworkId,
+        request: &StorageServiceRequest,
+    ) -> Result<RequestPermit, Error> {
+        // If scheduling is disabled, admit the request immediately
+        if !self.config.enable_request_scheduling {
+            return Ok(RequestPermit::unscheduled());
+        }
+
+        // Realtime requests are never throttled
+        let network_id = peer_network_id.network_id();
+        if RequestClass::from_request(request) == RequestClass::Realtime {
+            increment_counter(
+                &metrics::REQUEST_SCHEDULER_EVENTS,
+                network_id,
+                REALTIME_REQUEST_ADMITTED.into(),
+            );
+            return Ok(RequestPermit::unscheduled());
+        }
+
+        // Verify the global bulk request limit
+        let mut scheduler_state = self.scheduler_state.lock();
+        let num_active_bulk_requests: u64 = scheduler_state.active_bulk_requests.values().sum();
+        if num_active_bulk_requests >= self.config.max_concurrent_bulk_requests {
+            return Err(self.throttle_request(
+                peer_network_id,
+                request,
+                THROTTLED_FOR_CONCURRENCY,
+                "The server is at the maximum number of concurrent bulk requests!",
+            ));
+        }
+
+        // Verify the public network bulk request limit
+        if network_id.is_public_network() {
+            let num_active_public_bulk_requests = scheduler_state
+                .active_bulk_requests
+                .get(&network_id)
+                .copied()
+                .unwrap_or(0);
+            if num_active_public_bulk_requests >= self.config.max_concurrent_public_bulk_requests()
+            {
+                return Err(self.throttle_request(
+                    peer_network_id,
+                    request,
+                    THROTTLED_FOR_CONCURRENCY,
+                    "The server is at the maximum number of concurrent public bulk requests!",
+                ));
+            }
+        }
+
+        // Verify the per-peer limits (validators are exempt)
+        if !network_id.is_validator_network() {
+            let now = self.time_service.now();
+            let peer_state = scheduler_state
+                .peer_states
+                .entry(*peer_network_id)
+                .or_insert_with(|| PeerSchedulingState::new(now));
+            peer_state.refresh_window(now);
+
+            // Verify the peer's concurrency limit
+            if peer_state.num_active_bulk_requests
+                >= self.config.max_concurrent_bulk_requests_per_peer
+            {
+                return Err(self.throttle_request(
+                    peer_network_id,
+                    request,
+                    THROTTLED_FOR_PEER_CONCURRENCY,
+                    "The peer is at the maximum number of concurrent bulk requests!",
+                ));
+            }
+
+            // Verify the peer's byte budget
+            if peer_state.window_bytes_sent >= self.config.max_bulk_bytes_per_peer_per_second {
+                return Err(self.throttle_request(
+                    peer_network_id,
+                    request,
+                    THROTTLED_FOR_PEER_BYTES,
+                    "The peer has exhausted its bulk response byte budget!",
+                ));
+            }
+
+            // Admit the request for the peer
+            peer_state.num_active_bulk_requests += 1;
+        }
+
+        // Admit the request for the network
+        let num_active_network_requests = scheduler_state
+            .active_bulk_requests
+            .entry(network_id)
+            .or_insert(0);
+        *num_active_network_requests += 1;
+
+        // Update the metrics
+        metrics::set_gauge(
+            &metrics::ACTIVE_BULK_REQUESTS,
+            network_id.as_str(),
+            *num_active_network_requests,
+        );
+        increment_counter(
+            &metrics::REQUEST_SCHEDULER_EVENTS,
+            network_id,
+            BULK_REQUEST_ADMITTED.into(),
+        );
+
+        Ok(RequestPermit::new(*peer_network_id, self.clone()))
+    }
+
+    /// Releases the bulk request slot held by the given peer and charges
+    /// the number of response bytes to the peer's byte budget.
+    fn release_bulk_request(&self, peer_network_id: &PeerNetworkId, num_response_bytes: u64) {
+        let network_id = peer_network_id.network_id();
+        let mut scheduler_state = self.scheduler_state.lock();
+
+        // Update the active bulk requests for the network
+        if let Some(num_active_network_requests) =
+            scheduler_state.active_bulk_requests.get_mut(&network_id)
+        {
+            *num_active_network_requests = num_active_network_requests.saturating_sub(1);
+            metrics::set_gauge(
+                &metrics::ACTIVE_BULK_REQUESTS,
+                network_id.as_str(),
+                *num_active_network_requests,
+            );
+        }
+
+        // Update the peer's scheduling state
+        if let Some(peer_state) = scheduler_state.peer_states.get_mut(peer_network_id) {
+            peer_state.num_active_bulk_requests =
+                peer_state.num_active_bulk_requests.saturating_sub(1);
+            peer_state.refresh_window(self.time_service.now());
+            peer_state.window_bytes_sent = peer_state
+                .window_bytes_sent
+                .saturating_add(num_response_bytes);
+        }
+
+        // Update the response bytes metric
+        metrics::increment_bulk_response_bytes(network_id, num_response_bytes);
+    }
+
+    /// Garbage collects the scheduling states of idle peers
+    pub fn refresh_peer_states(&self) {
+        let now = self.time_service.now();
+        self.scheduler_state
+            .lock()
+            .peer_states
+            .retain(|_, peer_state| !peer_state.is_idle(now));
+    }
+
+    /// Updates the throttling metrics and logs, and returns a throttling error
+    fn throttle_request(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        request: &StorageServiceRequest,
+        throttle_reason: &str,
+        message: &str,
+    ) -> Error {
+        // Update the throttled request metrics
+        increment_counter(
+            &metrics::REQUEST_SCHEDULER_EVENTS,
+            peer_network_id.network_id(),
+            throttle_reason.into(),
+        );
+
+        // Periodically log the throttled request
+        let error = Error::RequestThrottled(format!("{} Request: {:?}", message, request));
+        sample!(
+            SampleRate::Duration(Duration::from_secs(THROTTLE_LOG_FREQUENCY_SECS)),
+            warn!(LogSchema::new(LogEntry::RequestSchedulerThrottledPeer)
+                .error(&error)
+                .peer_network_id(peer_network_id))
+        );
+
+        error
+    }
+
+    #[cfg(test)]
+    /// Returns the number of active bulk requests for the given network
+    pub(crate) fn get_num_active_bulk_requests(&self, network_id: NetworkId) -> u64 {
+        self.scheduler_state
+            .lock()
+            .active_bulk_requests
+            .get(&network_id)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    #[cfg(test)]
+    /// Returns the number of tracked peer states
+    pub(crate) fn get_num_peer_states(&self) -> usize {
+        self.scheduler_state.lock().peer_states.len()
+    }
+}
+
+/// A permit for a scheduled request. Permits for bulk requests hold a
+/// slot in the scheduler until they are released (or dropped).
+pub struct RequestPermit {
+    bulk_request_slot: Option<(PeerNetworkId, Arc<RequestScheduler>)>,
+    num_response_bytes: u64,
+}
+
+impl RequestPermit {
+    fn new(peer_network_id: PeerNetworkId, request_scheduler: Arc<RequestScheduler>) -> Self {
+        Self {
+            bulk_request_slot: Some((peer_network_id, request_scheduler)),
+            num_response_bytes: 0,
+        }
+    }
+
+    /// Creates a permit that doesn't hold a slot in the scheduler
+    pub fn unscheduled() -> Self {
+        Self {
+            bulk_request_slot: None,
+            num_response_bytes: 0,
+        }
+    }
+
+    /// Releases the permit and charges the given number
+    /// of response bytes to the peer's byte budget.
+    pub fn release(mut self, num_response_bytes: u64) {
+        self.num_response_bytes = num_response_bytes;
+    }
+}
+
+impl Drop for RequestPermit {
+    fn drop(&mut self) {
+        if let Some((peer_network_id, request_scheduler)) = self.bulk_request_slot.take() {
+            request_scheduler.release_bulk_request(&peer_network_id, self.num_response_bytes);
+        }
+    }
+}
@@ -8,27 +8,29 @@ use lumio_storage_interface::{LumioDbError, DbReader, Result as StorageResult};
 use lumio_storage_service_types::{
     requests::{GetTransactionDataWithProofRequest, TransactionDataRequestType},
     responses::{
-        CompleteDataRange, DataResponse, DataSummary, TransactionDataResponseType,
-        TransactionDataWithProofResponse,
+        CompleteDataRange, DataResponse, DataSummary, FilteredStateValuesWithProof,
+        TransactionDataResponseType, TransactionDataWithProofResponse,
     },
 };
 use lumio_time_service::{TimeService, TimeServiceTrait};
 use lumio_types::{
     contract_event::ContractEvent,
     epoch_change::EpochChangeProof,
+    indexer::indexer_db_reader::IndexerReader,
     ledger_info::LedgerInfoWithSignatures,
     proof::{
-        AccumulatorRangeProof, TransactionAccumulatorRangeProof, TransactionInfoListWithProof,
+        AccumulatorRangeProof, SparseMerkleProof, TransactionAccumulatorRangeProof,
+        TransactionInfoListWithProof,
     },
     state_store::{
-        state_key::StateKey,
+        state_key::{prefix::StateKeyPrefix, StateKey},
         state_value::{StateValue, StateValueChunkWithProof},
     },
     transaction::{
         PersistedAuxiliaryInfo, Transaction, TransactionAuxiliaryData, TransactionInfo,
         TransactionListWithAuxiliaryInfos, TransactionListWithProof, TransactionListWithProofV2,
         TransactionOutput, TransactionOutputListWithAuxiliaryInfos, TransactionOutputListWithProof,
-        TransactionOutputListWithProofV2, Version,
+        TransactionOutputListWithProofV2, TransactionWithProof, Version,
     },
     write_set::WriteSet,
 };
@@ -113,6 +115,20 @@ pub trait StorageReaderInterface: Clone + Send + 'static {
         start_index: u64,
         end_index: u64,
     ) -> lumio_storage_service_types::Result<StateValueChunkWithProof, Error>;
+
+    /// Returns the state values (each with a proof) of the state keys with
+    /// the given `key_prefix` at the specified version, starting at the
+    /// `start_key` (inclusive). In some cases, less state values may be
+    /// returned (e.g., due to network or chunk limits), in which case the
+    /// next start key is also returned. Any `known_keys` (in the range of
+    /// the response) that don't exist are returned with non-inclusion proofs.
+    fn get_filtered_state_values_with_proof(
+        &self,
+        version: u64,
+        key_prefix: &StateKeyPrefix,
+        start_key: Option<&StateKey>,
+        known_keys: &[StateKey],
+    ) -> lumio_storage_service_types::Result<FilteredStateValuesWithProof, Error>;
 }
 
 /// The underlying implementation of the StorageReaderInterface, used by the
@@ -121,6 +137,7 @@ pub trait StorageReaderInterface: Clone + Send + 'static {
 pub struct StorageReader {
     config: StorageServiceConfig,
     storage: Arc<dyn DbReader>,
+    indexer_reader: Option<Arc<dyn IndexerReader>>, // Used to iterate over states (if sharded)
     time_service: TimeService,
 }
 
@@ -136,10 +153,48 @@ impl StorageReader {
         Self {
             config,
             storage,
+            indexer_reader: None,
             time_service,
         }
     }
 
+    /// Sets the indexer reader used to iterate over prefixed state values.
+    /// This is required if storage sharding is enabled, as the DB no longer
+    /// supports prefixed state value iteration.
+    pub fn with_indexer_reader(mut self, indexer_reader: Arc<dyn IndexerReader>) -> Self {
+        self.indexer_reader = Some(indexer_reader);
+        self
+    }
+
+    /// Returns an iterator over the state values with the given key prefix
+    /// (starting at the start key). If an indexer reader exists, it is used
+    /// to serve the iterator. Otherwise, the DB is used.
+    fn get_prefixed_state_value_iterator(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        start_key: Option<&StateKey>,
+        version: u64,
+    ) -> Result<Box<dyn Iterator<Item = Result<(StateKey, StateValue), Error>> + '_>, Error> {
+        match &self.indexer_reader {
+            Some(indexer_reader) => {
+                let state_value_iterator = indexer_reader
+                    .get_prefixed_state_value_iterator(key_prefix, start_key, version)
+                    .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+                Ok(Box::new(state_value_iterator.map(|result| {
+                    result.map_err(|error| Error::StorageErrorEncountered(error.to_string()))
+                })))
+            },
+            None => {
+                let state_value_iterator = self
+                    .storage
+                    .get_prefixed_state_value_iterator(key_prefix, start_key, version)?;
+                Ok(Box::new(
+                    state_value_iterator.map(|result| result.map_err(Error::from)),
+                ))
+            },
+        }
+    }
+
     /// Returns the state values range held in the database (lowest to highest).
     /// Note: it is currently assumed that if a node contains a transaction at a
     /// version, V, the node also contains all state values at V.
@@ -1034,6 +1089,112 @@ impl StorageReader {
             version, start_index, end_index
         )))
     }
+
+    /// Returns the filtered state values with proof response (bound by the max response size in bytes)
+    fn get_filtered_state_values_with_proof_by_size(
+        &self,
+        version: u64,
+        key_prefix: &StateKeyPrefix,
+        start_key: Option<&StateKey>,
+        known_keys: &[StateKey],
+        max_response_size: u64,
+    ) -> Result<FilteredStateValuesWithProof, Error> {
+        // Get the prefixed state value iterator
+        let state_value_iterator =
+            self.get_prefixed_state_value_iterator(key_prefix, start_key, version)?;
+
+        // Initialize the fetched state values and the next start key
+        let mut state_values = vec![];
+        let mut next_start_key = None;
+
+        // Create a response progress tracker
+        let mut response_progress_tracker = ResponseDataProgressTracker::new(
+            self.config.max_state_chunk_size,
+            max_response_size,
+            self.config.max_storage_read_wait_time_ms,
+            self.time_service.clone(),
+        );
+
+        // Fetch as many state values (and proofs) as possible
+        for result in state_value_iterator {
+            let (state_key, state_value) = result?;
+
+            // If the response is already complete, the key is the start of the next chunk
+            if response_progress_tracker.is_response_complete() {
+                next_start_key = Some(state_key);
+                break;
+            }
+
+            // Fetch the proof for the state value
+            let (_, proof) = self
+                .storage
+                .get_state_value_with_proof_by_version(&state_key, version)?;
+            let state_value_with_proof = (state_key, state_value, proof);
+
+            // Calculate the number of serialized bytes for the state value and proof
+            let num_serialized_bytes = get_num_serialized_bytes(&state_value_with_proof)
+                .map_err(|error| Error::UnexpectedErrorEncountered(error.to_string()))?;
+
+            // Add the state value to the list
+            if response_progress_tracker.data_items_fits_in_response(true, num_serialized_bytes) {
+                state_values.push(state_value_with_proof);
+                response_progress_tracker.add_data_item(num_serialized_bytes);
+            } else {
+                next_start_key = Some(state_value_with_proof.0);
+                break; // Cannot add any more data items
+            }
+        }
+
+        // Fetch the non-inclusion proofs of the known keys (in the range of
+        // the response) that don't exist at the version.
+        let mut absent_keys = vec![];
+        for known_key in known_keys {
+            let is_in_range = key_prefix.is_prefix(known_key).unwrap_or(false)
+                && start_key.is_none_or(|start_key| known_key.encoded() >= start_key.encoded())
+                && next_start_key
+                    .as_ref()
+                    .is_none_or(|next_start_key| known_key.encoded() < next_start_key.encoded());
+            let is_returned = state_values
+                .iter()
+                .any(|(state_key, _, _)| state_key == known_key);
+            if !is_in_range || is_returned {
+                continue;
+            }
+
+            let (state_value, proof) = self
+                .storage
+                .get_state_value_with_proof_by_version(known_key, version)?;
+            if state_value.is_some() {
+                return Err(Error::UnexpectedErrorEncountered(format!(
+                    "The known key: {:?} exists at version: {:?}, but was not returned!",
+                    known_key, version
+                )));
+            }
+            absent_keys.push((known_key.clone(), proof));
+        }
+
+        // Fetch the transaction info (and proof) used to authenticate the state values
+        let transaction_info_with_proof = self
+            .storage
+            .get_transaction_by_version(version, version, false)?
+            .proof;
+
+        // Update the data truncation metrics
+        if next_start_key.is_some() {
+            response_progress_tracker.update_data_truncation_metrics(
+                DataResponse::get_filtered_state_values_with_proof_label(),
+            );
+        }
+
+        Ok(FilteredStateValuesWithProof {
+            version,
+            key_prefix: key_prefix.clone(),
+            state_values,
+            absent_keys,
+            next_start_key,
+            transaction_info_with_proof,
+        })
+    }
 }
 
 impl StorageReaderInterface for StorageReader {
@@ -1217,6 +1378,22 @@ impl StorageReaderInterface for StorageReader {
             self.config.enable_size_and_time_aware_chunking,
         )
     }
+
+    fn get_filtered_state_values_with_proof(
+        &self,
+        version: u64,
+        key_prefix: &StateKeyPrefix,
+        start_key: Option<&StateKey>,
+        known_keys: &[StateKey],
+    ) -> lumio_storage_service_types::Result<FilteredStateValuesWithProof, Error> {
+        self.get_filtered_state_values_with_proof_by_size(
+            version,
+            key_prefix,
+            start_key,
+            known_keys,
+            self.config.max_network_chunk_bytes,
+        )
+    }
 }
 
 // A simple macro that wraps each storage read call with a timer
@@ -1355,6 +1532,26 @@ impl DbReader for TimedStorageReader {
             state_key_values: Vec<(StateKey, StateValue)>,
         ) -> StorageResult<StateValueChunkWithProof>;
 
+        fn get_prefixed_state_value_iterator(
+            &self,
+            key_prefix: &StateKeyPrefix,
+            cursor: Option<&StateKey>,
+            version: Version,
+        ) -> StorageResult<Box<dyn Iterator<Item = StorageResult<(StateKey, StateValue)>> + '_>>;
+
+        fn get_state_value_with_proof_by_version(
+            &self,
+            state_key: &StateKey,
+            version: Version,
+        ) -> StorageResult<(Option<StateValue>, SparseMerkleProof)>;
+
+        fn get_transaction_by_version(
+            &self,
+            version: Version,
+            ledger_version: Version,
+            fetch_events: bool,
+        ) -> StorageResult<TransactionWithProof>;
+
         fn get_persisted_auxiliary_info_iterator(
             &self,
             start_version: Version,
@@ -0,0 +1,395 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::tests::{
+    mock,
+    mock::{MockClient, MockDatabaseReader, MockIndexerReader},
+    utils,
+};
+use lumio_config::config::StorageServiceConfig;
+use lumio_crypto::hash::HashValue;
+use lumio_storage_service_types::{
+    requests::{DataRequest, FilteredStateValuesWithProofRequest},
+    responses::{DataResponse, FilteredStateValuesWithProof, StorageServiceResponse},
+    StorageServiceError,
+};
+use lumio_types::{
+    account_address::AccountAddress,
+    proof::{SparseMerkleProof, TransactionAccumulatorProof, TransactionInfoWithProof},
+    state_store::{
+        state_key::{prefix::StateKeyPrefix, StateKey},
+        state_value::StateValue,
+        table::TableHandle,
+    },
+    transaction::{ExecutionStatus, Transaction, TransactionInfo, TransactionWithProof, Version},
+};
+use claims::assert_matches;
+use mockall::predicate::eq;
+
+#[tokio::test]
+async fn test_get_filtered_state_values_with_proof() {
+    // Test different numbers of state values (all less than the chunk size)
+    for num_state_values in [0, 1, 10, 50] {
+        // Create test data
+        let version = 101;
+        let address = AccountAddress::random();
+        let key_prefix = StateKeyPrefix::from(address);
+        let state_values = create_prefixed_state_values(address, num_state_values);
+        let transaction_info_with_proof = create_transaction_info_with_proof();
+
+        // Create the mock db reader
+        let mut db_reader = mock::create_mock_db_reader();
+        expect_get_filtered_state_values_with_proof(
+            &mut db_reader,
+            version,
+            key_prefix.clone(),
+            None,
+            state_values.clone(),
+            transaction_info_with_proof.clone(),
+        );
+
+        // Create the storage client and server
+        let (mut mock_client, mut service, _, _, _) = MockClient::new(Some(db_reader), None);
+        utils::update_storage_server_summary(&mut service, version, 10);
+        tokio::spawn(service.start());
+
+        // Process a request to fetch the filtered state values
+        let response = get_filtered_state_values_with_proof(
+            &mut mock_client,
+            version,
+            key_prefix.clone(),
+            vec![],
+        )
+        .await
+        .unwrap();
+
+        // Verify the response is correct
+        let expected_response = FilteredStateValuesWithProof {
+            version,
+            key_prefix,
+            state_values: add_proofs_to_state_values(state_values),
+            absent_keys: vec![],
+            next_start_key: None,
+            transaction_info_with_proof,
+        };
+        assert_matches!(response, StorageServiceResponse::RawResponse(_));
+        assert_eq!(
+            response.get_data_response().unwrap(),
+            DataResponse::FilteredStateValuesWithProof(expected_response)
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_get_filtered_state_values_with_proof_chunk_limit() {
+    // Create test data (with more state values than the max chunk size)
+    let max_state_chunk_size = StorageServiceConfig::default().max_state_chunk_size;
+    let version = 101;
+    let address = AccountAddress::random();
+    let key_prefix = StateKeyPrefix::from(address);
+    let state_values = create_prefixed_state_values(address, max_state_chunk_size + 10);
+    let transaction_info_with_proof = create_transaction_info_with_proof();
+
+    // Create the mock db reader
+    let mut db_reader = mock::create_mock_db_reader();
+    expect_get_filtered_state_values_with_proof(
+        &mut db_reader,
+        version,
+        key_prefix.clone(),
+        None,
+        state_values.clone(),
+        transaction_info_with_proof.clone(),
+    );
+
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(Some(db_reader), None);
+    utils::update_storage_server_summary(&mut service, version, 10);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch the filtered state values
+    let response =
+        get_filtered_state_values_with_proof(&mut mock_client, version, key_prefix.clone(), vec![])
+            .await
+            .unwrap();
+
+    // Verify the response is truncated at the chunk size and contains the next start key
+    let max_state_chunk_size = max_state_chunk_size as usize;
+    let next_start_key = state_values[max_state_chunk_size].0.clone();
+    let expected_response = FilteredStateValuesWithProof {
+        version,
+        key_prefix,
+        state_values: add_proofs_to_state_values(state_values[..max_state_chunk_size].to_vec()),
+        absent_keys: vec![],
+        next_start_key: Some(next_start_key),
+        transaction_info_with_proof,
+    };
+    assert_eq!(
+        response.get_data_response().unwrap(),
+        DataResponse::FilteredStateValuesWithProof(expected_response)
+    );
+}
+
+#[tokio::test]
+async fn test_get_filtered_state_values_with_proof_sharded() {
+    // Create test data
+    let version = 101;
+    let address = AccountAddress::random();
+    let key_prefix = StateKeyPrefix::from(address);
+    let state_values = create_prefixed_state_values(address, 10);
+    let transaction_info_with_proof = create_transaction_info_with_proof();
+
+    // Create the mock db reader (prefixed state value iteration is not supported when sharded)
+    let mut db_reader = mock::create_mock_db_reader();
+    expect_get_state_value_proofs_and_transaction_info(
+        &mut db_reader,
+        version,
+        transaction_info_with_proof.clone(),
+    );
+    db_reader.expect_get_prefixed_state_value_iterator().never();
+
+    // Create the mock indexer reader (which serves the prefixed state value iterator)
+    let mut indexer_reader = MockIndexerReader::new();
+    let state_value_iterator = state_values.clone().into_iter().map(Ok);
+    let expected_key_prefix = key_prefix.clone();
+    indexer_reader
+        .expect_get_prefixed_state_value_iterator()
+        .times(1)
+        .withf(move |given_key_prefix, given_start_key, given_version| {
+            given_key_prefix == &expected_key_prefix
+                && given_start_key.is_none()
+                && *given_version == version
+        })
+        .returning(move |_, _, _| Ok(Box::new(state_value_iterator.clone())));
+
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) =
+        MockClient::new_with_indexer_reader(Some(db_reader), Some(indexer_reader), None);
+    utils::update_storage_server_summary(&mut service, version, 10);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch the filtered state values
+    let response =
+        get_filtered_state_values_with_proof(&mut mock_client, version, key_prefix.clone(), vec![])
+            .await
+            .unwrap();
+
+    // Verify the response is correct
+    let expected_response = FilteredStateValuesWithProof {
+        version,
+        key_prefix,
+        state_values: add_proofs_to_state_values(state_values),
+        absent_keys: vec![],
+        next_start_key: None,
+        transaction_info_with_proof,
+    };
+    assert_eq!(
+        response.get_data_response().unwrap(),
+        DataResponse::FilteredStateValuesWithProof(expected_response)
+    );
+}
+
+#[tokio::test]
+async fn test_get_filtered_state_values_with_proof_absent_keys() {
+    // Create test data
+    let version = 101;
+    let address = AccountAddress::random();
+    let key_prefix = StateKeyPrefix::from(address);
+    let state_values = create_prefixed_state_values(address, 10);
+    let transaction_info_with_proof = create_transaction_info_with_proof();
+
+    // Create the known keys (some of which have been deleted, or have another prefix)
+    let deleted_keys: Vec<_> = (100..103u64)
+        .map(|index| StateKey::table_item(&TableHandle(address), &index.to_be_bytes()))
+        .collect();
+    let other_address = AccountAddress::random();
+    let unprefixed_key = StateKey::table_item(&TableHandle(other_address), &[0]);
+    let mut known_keys: Vec<_> = state_values
+        .iter()
+        .take(5)
+        .map(|(state_key, _)| state_key.clone())
+        .collect();
+    known_keys.extend(deleted_keys.clone());
+    known_keys.push(unprefixed_key);
+
+    // Create the mock db reader
+    let mut db_reader = mock::create_mock_db_reader();
+    expect_get_filtered_state_values_with_proof(
+        &mut db_reader,
+        version,
+        key_prefix.clone(),
+        None,
+        state_values.clone(),
+        transaction_info_with_proof.clone(),
+    );
+
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(Some(db_reader), None);
+    utils::update_storage_server_summary(&mut service, version, 10);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch the filtered state values
+    let response = get_filtered_state_values_with_proof(
+        &mut mock_client,
+        version,
+        key_prefix.clone(),
+        known_keys,
+    )
+    .await
+    .unwrap();
+
+    // Verify the response contains non-inclusion proofs for the deleted (prefixed) keys only
+    let expected_response = FilteredStateValuesWithProof {
+        version,
+        key_prefix,
+        state_values: add_proofs_to_state_values(state_values),
+        absent_keys: deleted_keys
+            .into_iter()
+            .map(|state_key| (state_key, create_empty_proof()))
+            .collect(),
+        next_start_key: None,
+        transaction_info_with_proof,
+    };
+    assert_eq!(
+        response.get_data_response().unwrap(),
+        DataResponse::FilteredStateValuesWithProof(expected_response)
+    );
+}
+
+#[tokio::test]
+async fn test_get_filtered_state_values_with_proof_not_serviceable() {
+    // Create test data
+    let version = 101;
+    let key_prefix = StateKeyPrefix::from(AccountAddress::random());
+
+    // Create the storage client and server (that cannot service the request)
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(None, None);
+    utils::update_storage_server_summary(&mut service, version - 1, 10);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch the filtered state values
+    let response =
+        get_filtered_state_values_with_proof(&mut mock_client, version, key_prefix, vec![])
+            .await
+            .unwrap_err();
+
+    // Verify the request is not serviceable
+    assert_matches!(response, StorageServiceError::InvalidRequest(_));
+}
+
+/// Adds an (empty) sparse merkle proof to each of the given state values
+fn add_proofs_to_state_values(
+    state_values: Vec<(StateKey, StateValue)>,
+) -> Vec<(StateKey, StateValue, SparseMerkleProof)> {
+    state_values
+        .into_iter()
+        .map(|(state_key, state_value)| (state_key, state_value, create_empty_proof()))
+        .collect()
+}
+
+/// Creates an empty sparse merkle proof
+fn create_empty_proof() -> SparseMerkleProof {
+    SparseMerkleProof::new(None, vec![])
+}
+
+/// Creates the given number of state values (ordered by key) under the specified address
+fn create_prefixed_state_values(
+    address: AccountAddress,
+    num_state_values: u64,
+) -> Vec<(StateKey, StateValue)> {
+    let mut state_values: Vec<_> = (0..num_state_values)
+        .map(|index| {
+            let state_key = StateKey::table_item(&TableHandle(address), &index.to_be_bytes());
+            let state_value = StateValue::new_legacy(index.to_le_bytes().to_vec().into());
+            (state_key, state_value)
+        })
+        .collect();
+    state_values.sort_by(|(key_1, _), (key_2, _)| key_1.encoded().cmp(key_2.encoded()));
+    state_values
+}
+
+/// Creates a test transaction info with proof
+fn create_transaction_info_with_proof() -> TransactionInfoWithProof {
+    let transaction_info = TransactionInfo::new(
+        HashValue::zero(),
+        HashValue::zero(),
+        HashValue::zero(),
+        Some(HashValue::random()),
+        0,
+        ExecutionStatus::Success,
+        None,
+    );
+    TransactionInfoWithProof::new(TransactionAccumulatorProof::new(vec![]), transaction_info)
+}
+
+/// Sets an expectation on the given mock db for a call to fetch filtered state values
+fn expect_get_filtered_state_values_with_proof(
+    mock_db: &mut MockDatabaseReader,
+    version: Version,
+    key_prefix: StateKeyPrefix,
+    start_key: Option<StateKey>,
+    state_values: Vec<(StateKey, StateValue)>,
+    transaction_info_with_proof: TransactionInfoWithProof,
+) {
+    // Expect a call to get the prefixed state value iterator
+    let state_value_iterator = state_values.clone().into_iter().map(Ok);
+    mock_db
+        .expect_get_prefixed_state_value_iterator()
+        .times(1)
+        .withf(move |given_key_prefix, given_start_key, given_version| {
+            given_key_prefix == &key_prefix
+                && given_start_key == &start_key.as_ref()
+                && *given_version == version
+        })
+        .returning(move |_, _, _| Ok(Box::new(state_value_iterator.clone())));
+
+    // Expect calls to get the state value proofs and the transaction info
+    expect_get_state_value_proofs_and_transaction_info(
+        mock_db,
+        version,
+        transaction_info_with_proof,
+    );
+}
+
+/// Sets an expectation on the given mock db for calls to fetch the state value
+/// proofs and the transaction info (used to authenticate filtered state values).
+fn expect_get_state_value_proofs_and_transaction_info(
+    mock_db: &mut MockDatabaseReader,
+    version: Version,
+    transaction_info_with_proof: TransactionInfoWithProof,
+) {
+    // Expect a call to get the proof of each state value
+    mock_db
+        .expect_get_state_value_with_proof_by_version()
+        .returning(move |_, _| Ok((None, create_empty_proof())));
+
+    // Expect a call to get the transaction info with proof
+    mock_db
+        .expect_get_transaction_by_version()
+        .times(1)
+        .with(eq(version), eq(version), eq(false))
+        .return_once(move |version, _, _| {
+            Ok(TransactionWithProof::new(
+                version,
+                Transaction::StateCheckpoint(HashValue::zero()),
+                None,
+                transaction_info_with_proof,
+            ))
+        });
+}
+
+/// Sends a filtered state values with proof request and processes the response
+async fn get_filtered_state_values_with_proof(
+    mock_client: &mut MockClient,
+    version: u64,
+    key_prefix: StateKeyPrefix,
+    known_keys: Vec<StateKey>,
+) -> Result<StorageServiceResponse, StorageServiceError> {
+    let data_request =
+        DataRequest::GetFilteredStateValuesWithProof(FilteredStateValuesWithProofRequest {
+            version,
+            key_prefix,
+            start_key: None,
+            known_keys,
+        });
+    utils::send_storage_request(mock_client, false, data_request).await
+}
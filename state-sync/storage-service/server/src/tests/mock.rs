@@ -31,9 +31,10 @@ use lumio_storage_service_types::{
 use lumio_time_service::{MockTimeService, TimeService};
 use lumio_types::{
     account_address::AccountAddress,
-    contract_event::{ContractEvent, EventWithVersion},
+    contract_event::{ContractEvent, ContractEventV1, ContractEventV2, EventWithVersion},
     epoch_change::EpochChangeProof,
     event::EventKey,
+    indexer::indexer_db_reader::IndexerReader,
     ledger_info::LedgerInfoWithSignatures,
     proof::{
         AccumulatorConsistencyProof, SparseMerkleProof, TransactionAccumulatorRangeProof,
@@ -41,8 +42,9 @@ use lumio_types::{
     },
     state_proof::StateProof,
     state_store::{
-        state_key::StateKey,
+        state_key::{prefix::StateKeyPrefix, StateKey},
         state_value::{StateValue, StateValueChunkWithProof},
+        table::{TableHandle, TableInfo},
     },
     transaction::{
         AccountOrderedTransactionsWithProof, PersistedAuxiliaryInfo, Transaction,
@@ -78,6 +80,22 @@ impl MockClient {
         StorageServiceNotifier,
         MockTimeService,
         Arc<PeersAndMetadata>,
+    ) {
+        Self::new_with_indexer_reader(db_reader, None, storage_config)
+    }
+
+    /// Creates a new mock client and server, where the server uses the
+    /// given indexer reader (e.g., to emulate storage sharding).
+    pub fn new_with_indexer_reader(
+        db_reader: Option<MockDatabaseReader>,
+        indexer_reader: Option<MockIndexerReader>,
+        storage_config: Option<StorageServiceConfig>,
+    ) -> (
+        Self,
+        StorageServiceServer<StorageReader>,
+        StorageServiceNotifier,
+        MockTimeService,
+        Arc<PeersAndMetadata>,
     ) {
         utils::initialize_logger();
 
@@ -88,11 +106,14 @@ impl MockClient {
 
         // Create the storage reader
         let mock_time_service = TimeService::mock();
-        let storage_reader = StorageReader::new(
+        let mut storage_reader = StorageReader::new(
             storage_service_config,
             Arc::new(db_reader.unwrap_or_else(create_mock_db_reader)),
             mock_time_service.clone(),
         );
+        if let Some(indexer_reader) = indexer_reader {
+            storage_reader = storage_reader.with_indexer_reader(Arc::new(indexer_reader));
+        }
 
         // Setup the networks and the network events
         let network_ids = vec![NetworkId::Validator, NetworkId::Vfn, NetworkId::Public];
@@ -423,6 +444,69 @@ mock! {
             first_index: usize,
             state_key_values: Vec<(StateKey, StateValue)>,
         ) -> lumio_storage_interface::Result<StateValueChunkWithProof>;
+
+        fn get_prefixed_state_value_iterator(
+            &self,
+            key_prefix: &StateKeyPrefix,
+            cursor: Option<&StateKey>,
+            version: Version,
+        ) -> lumio_storage_interface::Result<Box<dyn Iterator<Item = lumio_storage_interface::Result<(StateKey, StateValue)>>>>;
+    }
+}
+
+// This automatically creates a MockIndexerReader.
+mock! {
+    pub IndexerReader {}
+    impl IndexerReader for IndexerReader {
+        fn is_internal_indexer_enabled(&self) -> bool;
+
+        fn get_table_info(&self, handle: TableHandle) -> Result<Option<TableInfo>>;
+
+        fn get_events(
+            &self,
+            event_key: &EventKey,
+            start: u64,
+            order: Order,
+            limit: u64,
+            ledger_version: Version,
+        ) -> Result<Vec<EventWithVersion>>;
+
+        fn get_events_by_event_key(
+            &self,
+            event_key: &EventKey,
+            start_seq_num: u64,
+            order: Order,
+            limit: u64,
+            ledger_version: Version,
+        ) -> Result<Vec<EventWithVersion>>;
+
+        fn get_account_ordered_transactions(
+            &self,
+            address: AccountAddress,
+            start_seq_num: u64,
+            limit: u64,
+            include_events: bool,
+            ledger_version: Version,
+        ) -> Result<AccountOrderedTransactionsWithProof>;
+
+        fn get_prefixed_state_value_iterator(
+            &self,
+            key_prefix: &StateKeyPrefix,
+            cursor: Option<&StateKey>,
+            version: Version,
+        ) -> Result<Box<dyn Iterator<Item = Result<(StateKey, StateValue)>>>>;
+
+        fn get_latest_internal_indexer_ledger_version(&self) -> Result<Option<Version>>;
+
+        fn get_latest_table_info_ledger_version(&self) -> Result<Option<Version>>;
+
+        fn get_translated_v1_event_by_version_and_index(
+            &self,
+            version: Version,
+            index: u64,
+        ) -> Result<ContractEventV1>;
+
+        fn translate_event_v2_to_v1(&self, v2: &ContractEventV2) -> Result<Option<ContractEventV1>>;
     }
 }
 
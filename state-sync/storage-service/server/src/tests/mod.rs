@@ -5,6 +5,7 @@
 
 mod cache;
 mod epoch_ending;
+mod filtered_state_values;
 mod mock;
 mod new_transaction_outputs;
 mod new_transactions;
@@ -13,6 +14,7 @@ mod number_of_states;
 mod optimistic_fetch;
 mod protocol_version;
 mod request_moderator;
+mod request_scheduler;
 mod response_progress_tracker;
 mod state_values;
 mod storage_summary;
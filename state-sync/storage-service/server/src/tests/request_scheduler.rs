@@ -0,0 +1,410 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::Error,
+    scheduler::{RequestClass, RequestPermit, RequestScheduler},
+    tests::{mock::MockClient, utils},
+};
+use lumio_config::{
+    config::{RequestSchedulerConfig, StorageServiceConfig},
+    network_id::{NetworkId, PeerNetworkId},
+};
+use lumio_storage_service_types::{
+    requests::{
+        DataRequest, NewTransactionsWithProofRequest, StateValuesWithProofRequest,
+        StorageServiceRequest, TransactionsWithProofRequest,
+    },
+    StorageServiceError,
+};
+use lumio_time_service::TimeService;
+use lumio_types::PeerId;
+use claims::assert_matches;
+use std::{sync::Arc, time::Duration};
+
+#[test]
+fn test_request_classes() {
+    // Verify the realtime requests
+    for data_request in [
+        DataRequest::GetNewTransactionsWithProof(NewTransactionsWithProofRequest {
+            known_version: 10,
+            known_epoch: 1,
+            include_events: false,
+        }),
+        DataRequest::GetNumberOfStatesAtVersion(10),
+        DataRequest::GetServerProtocolVersion,
+        DataRequest::GetStorageServerSummary,
+    ] {
+        let request = StorageServiceRequest::new(data_request, true);
+        assert_eq!(RequestClass::from_request(&request), RequestClass::Realtime);
+    }
+
+    // Verify the bulk requests
+    for data_request in [
+        create_state_values_request().data_request,
+        create_transactions_request().data_request,
+    ] {
+        let request = StorageServiceRequest::new(data_request, true);
+        assert_eq!(RequestClass::from_request(&request), RequestClass::Bulk);
+    }
+}
+
+#[test]
+fn test_realtime_requests_not_throttled() {
+    // Create a scheduler that only allows a single bulk request
+    let request_scheduler = create_request_scheduler(RequestSchedulerConfig {
+        max_concurrent_bulk_requests: 1,
+        max_concurrent_bulk_requests_per_peer: 1,
+        ..Default::default()
+    });
+
+    // Schedule a bulk request for a PFN (to exhaust all bulk request slots)
+    let peer_network_id = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+    let _bulk_permit = request_scheduler
+        .schedule_request(&peer_network_id, &create_state_values_request())
+        .unwrap();
+
+    // Verify that realtime requests are still admitted for the peer
+    let summary_request = StorageServiceRequest::new(DataRequest::GetStorageServerSummary, true);
+    let mut realtime_permits = vec![];
+    for _ in 0..10 {
+        let permit = request_scheduler
+            .schedule_request(&peer_network_id, &summary_request)
+            .unwrap();
+        realtime_permits.push(permit);
+    }
+
+    // Verify that only the bulk request holds a slot
+    assert_eq!(
+        request_scheduler.get_num_active_bulk_requests(NetworkId::Public),
+        1
+    );
+}
+
+#[test]
+fn test_per_peer_concurrency_limit() {
+    // Create a scheduler with a per-peer concurrency limit
+    let max_concurrent_bulk_requests_per_peer = 3;
+    let request_scheduler = create_request_scheduler(RequestSchedulerConfig {
+        max_concurrent_bulk_requests_per_peer,
+        ..Default::default()
+    });
+
+    // Schedule the maximum number of bulk requests for a VFN
+    let vfn_peer = PeerNetworkId::new(NetworkId::Vfn, PeerId::random());
+    let mut vfn_permits = schedule_bulk_requests(
+        &request_scheduler,
+        &vfn_peer,
+        max_concurrent_bulk_requests_per_peer,
+    );
+
+    // Verify the next request from the VFN is throttled
+    verify_request_throttled(&request_scheduler, &vfn_peer);
+
+    // Verify that requests from another VFN are still admitted
+    let other_vfn_peer = PeerNetworkId::new(NetworkId::Vfn, PeerId::random());
+    let _other_vfn_permits = schedule_bulk_requests(&request_scheduler, &other_vfn_peer, 1);
+
+    // Release a permit and verify the VFN can send another request
+    vfn_permits.pop().unwrap().release(0);
+    let _vfn_permit = schedule_bulk_requests(&request_scheduler, &vfn_peer, 1);
+
+    // Verify that validators are exempt from the per-peer concurrency limit
+    let validator_peer = PeerNetworkId::new(NetworkId::Validator, PeerId::random());
+    let _validator_permits = schedule_bulk_requests(
+        &request_scheduler,
+        &validator_peer,
+        max_concurrent_bulk_requests_per_peer * 2,
+    );
+}
+
+#[test]
+fn test_public_network_limit() {
+    // Create a scheduler that reserves half of the bulk request slots
+    let max_concurrent_bulk_requests = 10;
+    let request_scheduler = create_request_scheduler(RequestSchedulerConfig {
+        max_concurrent_bulk_requests,
+        max_concurrent_bulk_requests_per_peer: 1,
+        max_public_bulk_requests_percentage: 50,
+        ..Default::default()
+    });
+
+    // Schedule bulk requests for many PFNs (until the public limit is reached)
+    let mut pfn_permits = vec![];
+    for _ in 0..max_concurrent_bulk_requests / 2 {
+        let pfn_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        pfn_permits.extend(schedule_bulk_requests(&request_scheduler, &pfn_peer, 1));
+    }
+
+    // Verify that requests from new PFNs are now throttled
+    let pfn_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+    verify_request_throttled(&request_scheduler, &pfn_peer);
+
+    // Verify that VFNs can use the reserved bulk request slots
+    let mut vfn_permits = vec![];
+    for _ in 0..max_concurrent_bulk_requests / 2 {
+        let vfn_peer = PeerNetworkId::new(NetworkId::Vfn, PeerId::random());
+        vfn_permits.extend(schedule_bulk_requests(&request_scheduler, &vfn_peer, 1));
+    }
+
+    // Verify that all peers (including validators) are now throttled
+    for network_id in [NetworkId::Validator, NetworkId::Vfn, NetworkId::Public] {
+        let peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        verify_request_throttled(&request_scheduler, &peer_network_id);
+    }
+
+    // Drop all PFN permits and verify the slots are released
+    drop(pfn_permits);
+    assert_eq!(
+        request_scheduler.get_num_active_bulk_requests(NetworkId::Public),
+        0
+    );
+    assert_eq!(
+        request_scheduler.get_num_active_bulk_requests(NetworkId::Vfn),
+        max_concurrent_bulk_requests / 2
+    );
+
+    // Verify that PFNs are admitted again
+    let _pfn_permits = schedule_bulk_requests(&request_scheduler, &pfn_peer, 1);
+}
+
+#[test]
+fn test_per_peer_byte_budget() {
+    // Create a scheduler with a per-peer byte budget
+    let max_bulk_bytes_per_peer_per_second = 1000;
+    let time_service = TimeService::mock();
+    let request_scheduler = Arc::new(RequestScheduler::new(
+        RequestSchedulerConfig {
+            max_bulk_bytes_per_peer_per_second,
+            ..Default::default()
+        },
+        time_service.clone(),
+    ));
+
+    // Send a response that doesn't exhaust the peer's byte budget
+    let pfn_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+    let permit = schedule_bulk_requests(&request_scheduler, &pfn_peer, 1).remove(0);
+    permit.release(max_bulk_bytes_per_peer_per_second - 1);
+
+    // Send a response that exhausts the peer's byte budget
+    let permit = schedule_bulk_requests(&request_scheduler, &pfn_peer, 1).remove(0);
+    permit.release(max_bulk_bytes_per_peer_per_second);
+
+    // Verify the next request from the peer is throttled
+    verify_request_throttled(&request_scheduler, &pfn_peer);
+
+    // Verify that a validator is exempt from the byte budget
+    let validator_peer = PeerNetworkId::new(NetworkId::Validator, PeerId::random());
+    for _ in 0..3 {
+        let permit = schedule_bulk_requests(&request_scheduler, &validator_peer, 1).remove(0);
+        permit.release(max_bulk_bytes_per_peer_per_second * 10);
+    }
+
+    // Elapse the byte budget window and verify the peer is admitted again
+    time_service.into_mock().advance(Duration::from_secs(1));
+    let _permits = schedule_bulk_requests(&request_scheduler, &pfn_peer, 1);
+}
+
+#[test]
+fn test_refresh_peer_states() {
+    // Create a scheduler
+    let time_service = TimeService::mock();
+    let request_scheduler = Arc::new(RequestScheduler::new(
+        RequestSchedulerConfig::default(),
+        time_service.clone(),
+    ));
+
+    // Schedule bulk requests for several peers
+    let num_peers = 5;
+    let mut permits = vec![];
+    for _ in 0..num_peers {
+        let pfn_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        permits.extend(schedule_bulk_requests(&request_scheduler, &pfn_peer, 1));
+    }
+
+    // Elapse the byte budget window and verify active peers are not removed
+    let time_service = time_service.into_mock();
+    time_service.advance(Duration::from_secs(1));
+    request_scheduler.refresh_peer_states();
+    assert_eq!(request_scheduler.get_num_peer_states(), num_peers);
+
+    // Release all permits and verify the peers are not removed (the window is active)
+    for permit in permits {
+        permit.release(10);
+    }
+    request_scheduler.refresh_peer_states();
+    assert_eq!(request_scheduler.get_num_peer_states(), num_peers);
+
+    // Elapse the byte budget window and verify the idle peers are removed
+    time_service.advance(Duration::from_secs(1));
+    request_scheduler.refresh_peer_states();
+    assert_eq!(request_scheduler.get_num_peer_states(), 0);
+}
+
+#[test]
+fn test_request_scheduling_disabled() {
+    // Create a scheduler with request scheduling disabled
+    let request_scheduler = create_request_scheduler(RequestSchedulerConfig {
+        enable_request_scheduling: false,
+        max_concurrent_bulk_requests: 1,
+        max_concurrent_bulk_requests_per_peer: 1,
+        ..Default::default()
+    });
+
+    // Verify that bulk requests are never throttled
+    let pfn_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+    let _permits = schedule_bulk_requests(&request_scheduler, &pfn_peer, 10);
+    assert_eq!(
+        request_scheduler.get_num_active_bulk_requests(NetworkId::Public),
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_throttled_request_response() {
+    for legacy_throttling_errors in [false, true] {
+        // Create a storage service config with a tiny byte budget
+        let storage_service_config = StorageServiceConfig {
+            request_scheduler: RequestSchedulerConfig {
+                legacy_throttling_errors,
+                max_bulk_bytes_per_peer_per_second: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Create the storage client and server
+        let (mut mock_client, service, _, _, _) =
+            MockClient::new(None, Some(storage_service_config));
+        let request_scheduler = service.get_request_scheduler();
+        tokio::spawn(service.start());
+
+        // Send a bulk request from a PFN and verify it is not throttled
+        let pfn_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        let response = send_transaction_request(&mut mock_client, pfn_peer).await;
+        assert_matches!(
+            response.unwrap_err(),
+            StorageServiceError::InvalidRequest(_)
+        );
+
+        // Wait for the permit to be released (and the response bytes to be charged)
+        wait_for_bulk_requests_to_complete(&request_scheduler, NetworkId::Public).await;
+
+        // Send another bulk request and verify it is throttled (using
+        // the legacy error, if older clients must be supported).
+        let response = send_transaction_request(&mut mock_client, pfn_peer).await;
+        if legacy_throttling_errors {
+            assert_matches!(
+                response.unwrap_err(),
+                StorageServiceError::TooManyInvalidRequests(_)
+            );
+        } else {
+            assert_matches!(
+                response.unwrap_err(),
+                StorageServiceError::RequestThrottled(_)
+            );
+        }
+
+        // Verify that realtime requests from the PFN are still served
+        let request = StorageServiceRequest::new(DataRequest::GetStorageServerSummary, true);
+        let receiver = mock_client
+            .send_request(
+                request,
+                Some(pfn_peer.peer_id()),
+                Some(pfn_peer.network_id()),
+            )
+            .await;
+        mock_client.wait_for_response(receiver).await.unwrap();
+    }
+}
+
+/// Creates a request scheduler with the given config
+fn create_request_scheduler(config: RequestSchedulerConfig) -> Arc<RequestScheduler> {
+    Arc::new(RequestScheduler::new(config, TimeService::mock()))
+}
+
+/// Creates a bulk request for state values
+fn create_state_values_request() -> StorageServiceRequest {
+    StorageServiceRequest::new(
+        DataRequest::GetStateValuesWithProof(StateValuesWithProofRequest {
+            version: 100,
+            start_index: 0,
+            end_index: 999,
+        }),
+        true,
+    )
+}
+
+/// Creates a bulk request for transactions
+fn create_transactions_request() -> StorageServiceRequest {
+    StorageServiceRequest::new(
+        DataRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+            proof_version: 100,
+            start_version: 50,
+            end_version: 60,
+            include_events: false,
+        }),
+        true,
+    )
+}
+
+/// Schedules the given number of bulk requests for the peer
+/// and verifies that all requests are admitted.
+fn schedule_bulk_requests(
+    request_scheduler: &Arc<RequestScheduler>,
+    peer_network_id: &PeerNetworkId,
+    num_requests: u64,
+) -> Vec<RequestPermit> {
+    (0..num_requests)
+        .map(|_| {
+            request_scheduler
+                .schedule_request(peer_network_id, &create_state_values_request())
+                .unwrap()
+        })
+        .collect()
+}
+
+/// Sends a (bulk) transaction request that cannot be satisfied by the server
+async fn send_transaction_request(
+    mock_client: &mut MockClient,
+    peer_network_id: PeerNetworkId,
+) -> Result<(), StorageServiceError> {
+    let receiver = mock_client
+        .send_request(
+            create_transactions_request(),
+            Some(peer_network_id.peer_id()),
+            Some(peer_network_id.network_id()),
+        )
+        .await;
+    mock_client.wait_for_response(receiver).await.map(|_| ())
+}
+
+/// Verifies that the next bulk request for the peer is throttled
+fn verify_request_throttled(
+    request_scheduler: &Arc<RequestScheduler>,
+    peer_network_id: &PeerNetworkId,
+) {
+    let result =
+        request_scheduler.schedule_request(peer_network_id, &create_state_values_request());
+    assert_matches!(result, Err(Error::RequestThrottled(_)));
+}
+
+/// Waits for all active bulk requests on the given network to complete
+async fn wait_for_bulk_requests_to_complete(
+    request_scheduler: &Arc<RequestScheduler>,
+    network_id: NetworkId,
+) {
+    let request_scheduler = request_scheduler.clone();
+    let bulk_requests_complete = async move {
+        while request_scheduler.get_num_active_bulk_requests(network_id) > 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    };
+
+    // Spawn the task with a timeout
+    utils::spawn_with_timeout(
+        bulk_requests_complete,
+        "Timed-out while waiting for the bulk requests to complete",
+    )
+    .await;
+}
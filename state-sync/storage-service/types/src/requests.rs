@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::COMPRESSION_SUFFIX_LABEL;
-use lumio_types::transaction::Version;
+use lumio_types::{
+    state_store::state_key::{prefix::StateKeyPrefix, StateKey},
+    transaction::Version,
+};
 use serde::{Deserialize, Serialize};
 
 /// A storage service request.
@@ -53,6 +56,9 @@ pub enum DataRequest {
     GetTransactionDataWithProof(GetTransactionDataWithProofRequest), // Fetches transaction data with a proof
     GetNewTransactionDataWithProof(GetNewTransactionDataWithProofRequest), // Optimistically fetches new transaction data with a proof
     SubscribeTransactionDataWithProof(SubscribeTransactionDataWithProofRequest), // Subscribes to transaction data with a proof
+
+    // All the requests listed below are for partial state syncing (i.e., syncing the states of a subset of accounts).
+    GetFilteredStateValuesWithProof(FilteredStateValuesWithProofRequest), // Fetches the states with a key prefix (and proofs)
 }
 
 impl DataRequest {
@@ -118,6 +124,9 @@ impl DataRequest {
                     },
                 }
             },
+
+            // Partial state syncing requests
+            Self::GetFilteredStateValuesWithProof(_) => "get_filtered_state_values_with_proof",
         }
     }
 
@@ -321,6 +330,19 @@ pub struct EpochEndingLedgerInfoRequest {
     pub expected_end_epoch: u64, // The epoch to finish at
 }
 
+/// A storage service request for fetching the state values (each with a
+/// proof) of all state keys with the given prefix at a specified version.
+/// The known keys are the state keys (with the prefix) already known to the
+/// client. The server must prove the non-existence of any known keys that
+/// don't exist at the version (e.g., because they have been deleted).
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct FilteredStateValuesWithProofRequest {
+    pub version: u64,                // The version to fetch the state values at
+    pub key_prefix: StateKeyPrefix,  // The prefix of the state keys to fetch
+    pub start_key: Option<StateKey>, // The key to start fetching state values (inclusive)
+    pub known_keys: Vec<StateKey>,   // The state keys already known to the client
+}
+
 /// A storage service request for fetching a new transaction output list
 /// beyond the already known version and epoch.
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
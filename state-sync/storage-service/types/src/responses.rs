@@ -4,14 +4,14 @@
 use crate::{
     requests::{
         DataRequest::{
-            GetEpochEndingLedgerInfos, GetNewTransactionDataWithProof,
-            GetNewTransactionOutputsWithProof, GetNewTransactionsOrOutputsWithProof,
-            GetNewTransactionsWithProof, GetNumberOfStatesAtVersion, GetServerProtocolVersion,
-            GetStateValuesWithProof, GetStorageServerSummary, GetTransactionDataWithProof,
-            GetTransactionOutputsWithProof, GetTransactionsOrOutputsWithProof,
-            GetTransactionsWithProof, SubscribeTransactionDataWithProof,
-            SubscribeTransactionOutputsWithProof, SubscribeTransactionsOrOutputsWithProof,
-            SubscribeTransactionsWithProof,
+            GetEpochEndingLedgerInfos, GetFilteredStateValuesWithProof,
+            GetNewTransactionDataWithProof, GetNewTransactionOutputsWithProof,
+            GetNewTransactionsOrOutputsWithProof, GetNewTransactionsWithProof,
+            GetNumberOfStatesAtVersion, GetServerProtocolVersion, GetStateValuesWithProof,
+            GetStorageServerSummary, GetTransactionDataWithProof, GetTransactionOutputsWithProof,
+            GetTransactionsOrOutputsWithProof, GetTransactionsWithProof,
+            SubscribeTransactionDataWithProof, SubscribeTransactionOutputsWithProof,
+            SubscribeTransactionsOrOutputsWithProof, SubscribeTransactionsWithProof,
         },
         TransactionDataRequestType,
     },
@@ -22,11 +22,16 @@ use lumio_compression::{client::CompressionClient, CompressedData};
 use lumio_config::config::{
     LumioDataClientConfig, StorageServiceConfig, MAX_APPLICATION_MESSAGE_SIZE,
 };
+use lumio_crypto::HashValue;
 use lumio_time_service::{TimeService, TimeServiceTrait};
 use lumio_types::{
     epoch_change::EpochChangeProof,
-    ledger_info::LedgerInfoWithSignatures,
-    state_store::state_value::StateValueChunkWithProof,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    proof::{SparseMerkleProof, TransactionInfoWithProof},
+    state_store::{
+        state_key::{prefix::StateKeyPrefix, StateKey},
+        state_value::{StateValue, StateValueChunkWithProof},
+    },
     transaction::{
         TransactionListWithProof, TransactionListWithProofV2, TransactionOutputListWithProof,
         TransactionOutputListWithProofV2, Version,
@@ -53,6 +58,8 @@ pub enum Error {
     UnexpectedErrorEncountered(String),
     #[error("Unexpected response error: {0}")]
     UnexpectedResponseError(String),
+    #[error("Unverified absence of state key: {0}")]
+    UnverifiedAbsenceError(String),
 }
 
 impl From<lumio_compression::Error> for Error {
@@ -158,6 +165,9 @@ pub enum DataResponse {
     // TODO: eventually we should deprecate all the old response types.
     TransactionDataWithProof(TransactionDataWithProofResponse),
     NewTransactionDataWithProof(NewTransactionDataWithProofResponse),
+
+    // All the responses listed below are for partial state syncing (i.e., syncing the states of a subset of accounts).
+    FilteredStateValuesWithProof(FilteredStateValuesWithProof),
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -175,6 +185,157 @@ pub struct NewTransactionDataWithProofResponse {
     pub ledger_info_with_signatures: LedgerInfoWithSignatures,
 }
 
+/// The state values (each with a sparse merkle proof) of all state keys with
+/// a given prefix. The state values are proven against the state checkpoint
+/// hash of the transaction info at the specified version. The absent keys are
+/// the known keys (requested by the client) that don't exist at the version,
+/// each with a sparse merkle non-inclusion proof. Note: the proofs cannot
+/// prove that no new state keys (with the prefix) were omitted by the server,
+/// as the state keys are not ordered by prefix in the sparse merkle tree.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FilteredStateValuesWithProof {
+    pub version: Version,           // The version of the state values
+    pub key_prefix: StateKeyPrefix, // The prefix of all state keys in the response
+    pub state_values: Vec<(StateKey, StateValue, SparseMerkleProof)>, // The state values (ordered by key)
+    pub absent_keys: Vec<(StateKey, SparseMerkleProof)>, // The known keys that do not exist
+    pub next_start_key: Option<StateKey>, // The first key of the next chunk (if there is one)
+    pub transaction_info_with_proof: TransactionInfoWithProof, // The transaction info at the version
+}
+
+impl FilteredStateValuesWithProof {
+    /// Verifies the state values against the given ledger info (which must be
+    /// at the same version as the state values). The absence of every known
+    /// key in the range of the chunk must also be proven. If verification
+    /// succeeds, the state checkpoint hash (i.e., the state root hash) is returned.
+    pub fn verify(
+        &self,
+        ledger_info: &LedgerInfo,
+        key_prefix: &StateKeyPrefix,
+        start_key: Option<&StateKey>,
+        known_keys: &[StateKey],
+    ) -> crate::Result<HashValue, Error> {
+        // Verify the version and key prefix of the response
+        if self.version != ledger_info.version() {
+            return Err(Error::UnexpectedResponseError(format!(
+                "The state values are at version: {}, but the ledger info is at version: {}",
+                self.version,
+                ledger_info.version()
+            )));
+        }
+        if &self.key_prefix != key_prefix {
+            return Err(Error::UnexpectedResponseError(format!(
+                "The key prefix of the state values is incorrect! Expected: {:?}, found: {:?}",
+                key_prefix, self.key_prefix
+            )));
+        }
+        if self.state_values.is_empty() && self.next_start_key.is_some() {
+            return Err(Error::UnexpectedResponseError(
+                "The state values are empty, but the next start key is set!".into(),
+            ));
+        }
+
+        // Verify the state keys are prefixed and strictly ordered (starting at the start key)
+        let mut previous_key = start_key.map(|start_key| start_key.encoded().clone());
+        let mut is_first_key = true;
+        let state_keys = self
+            .state_values
+            .iter()
+            .map(|(state_key, _, _)| state_key)
+            .chain(self.next_start_key.iter());
+        for state_key in state_keys {
+            if !self.key_prefix.is_prefix(state_key).unwrap_or(false) {
+                return Err(Error::UnexpectedResponseError(format!(
+                    "The state key: {:?} does not have the prefix: {:?}",
+                    state_key, self.key_prefix
+                )));
+            }
+            if let Some(previous_key) = &previous_key {
+                // The first key may be equal to the start key (it is inclusive)
+                let is_ordered = if is_first_key {
+                    state_key.encoded() >= previous_key
+                } else {
+                    state_key.encoded() > previous_key
+                };
+                if !is_ordered {
+                    return Err(Error::UnexpectedResponseError(format!(
+                        "The state keys are not strictly ordered! Found key: {:?}",
+                        state_key
+                    )));
+                }
+            }
+            previous_key = Some(state_key.encoded().clone());
+            is_first_key = false;
+        }
+
+        // Verify the transaction info and fetch the state checkpoint hash
+        self.transaction_info_with_proof
+            .verify(ledger_info, self.version)
+            .map_err(|error| Error::UnexpectedResponseError(error.to_string()))?;
+        let state_checkpoint_hash = self
+            .transaction_info_with_proof
+            .transaction_info()
+            .ensure_state_checkpoint_hash()
+            .map_err(|error| Error::UnexpectedResponseError(error.to_string()))?;
+
+        // Verify the proof of each state value
+        for (state_key, state_value, proof) in &self.state_values {
+            proof
+                .verify(
+                    state_checkpoint_hash,
+                    *state_key.crypto_hash_ref(),
+                    Some(state_value),
+                )
+                .map_err(|error| {
+                    Error::UnexpectedResponseError(format!(
+                        "Failed to verify the state value for key: {:?}. Error: {:?}",
+                        state_key, error
+                    ))
+                })?;
+        }
+
+        // Verify the non-inclusion proof of each absent key
+        for (state_key, proof) in &self.absent_keys {
+            proof
+                .verify(state_checkpoint_hash, *state_key.crypto_hash_ref(), None)
+                .map_err(|error| {
+                    Error::UnexpectedResponseError(format!(
+                        "Failed to verify the absence of key: {:?}. Error: {:?}",
+                        state_key, error
+                    ))
+                })?;
+        }
+
+        // Verify that every known key in the range of the chunk is either
+        // returned, or proven to be absent.
+        for known_key in known_keys {
+            let is_in_chunk =
+                start_key.is_none_or(|start_key| known_key.encoded() >= start_key.encoded())
+                    && self.next_start_key.as_ref().is_none_or(|next_start_key| {
+                        known_key.encoded() < next_start_key.encoded()
+                    });
+            if !is_in_chunk || !self.key_prefix.is_prefix(known_key).unwrap_or(false) {
+                continue;
+            }
+            let is_returned = self
+                .state_values
+                .iter()
+                .any(|(state_key, _, _)| state_key == known_key);
+            let is_absent = self
+                .absent_keys
+                .iter()
+                .any(|(state_key, _)| state_key == known_key);
+            if !is_returned && !is_absent {
+                return Err(Error::UnverifiedAbsenceError(format!(
+                    "The known key: {:?} was omitted without a non-inclusion proof!",
+                    known_key
+                )));
+            }
+        }
+
+        Ok(state_checkpoint_hash)
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub enum TransactionDataResponseType {
     TransactionData,
@@ -226,6 +387,11 @@ impl DataResponse {
                     },
                 }
             },
+
+            // Partial state syncing responses
+            Self::FilteredStateValuesWithProof(_) => {
+                Self::get_filtered_state_values_with_proof_label()
+            },
         }
     }
 
@@ -303,6 +469,11 @@ impl DataResponse {
     pub fn new_transaction_outputs_with_proof_v2_label() -> &'static str {
         "new_transaction_outputs_with_proof_v2"
     }
+
+    /// Returns a label for the filtered state values with proof response
+    pub fn get_filtered_state_values_with_proof_label() -> &'static str {
+        "filtered_state_values_with_proof"
+    }
 }
 
 impl Display for DataResponse {
@@ -338,6 +509,21 @@ impl TryFrom<StorageServiceResponse> for StateValueChunkWithProof {
     }
 }
 
+impl TryFrom<StorageServiceResponse> for FilteredStateValuesWithProof {
+    type Error = crate::responses::Error;
+
+    fn try_from(response: StorageServiceResponse) -> crate::Result<Self, Self::Error> {
+        let data_response = response.get_data_response()?;
+        match data_response {
+            DataResponse::FilteredStateValuesWithProof(inner) => Ok(inner),
+            _ => Err(Error::UnexpectedResponseError(format!(
+                "expected filtered_state_values_with_proof, found {}",
+                data_response.get_label()
+            ))),
+        }
+    }
+}
+
 impl TryFrom<StorageServiceResponse> for EpochChangeProof {
     type Error = crate::responses::Error;
 
@@ -804,6 +990,15 @@ impl DataSummary {
                 time_service,
                 self.synced_ledger_info.as_ref(),
             ),
+
+            // Partial state syncing requests
+            GetFilteredStateValuesWithProof(request) => {
+                let can_serve_states = self
+                    .states
+                    .map(|range| range.contains(request.version))
+                    .unwrap_or(false);
+                can_serve_states && self.can_create_proof(request.version)
+            },
         }
     }
 
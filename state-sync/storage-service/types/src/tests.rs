@@ -3,26 +3,40 @@
 
 use crate::{
     requests::{
-        DataRequest, EpochEndingLedgerInfoRequest, NewTransactionOutputsWithProofRequest,
-        NewTransactionsOrOutputsWithProofRequest, NewTransactionsWithProofRequest,
-        StateValuesWithProofRequest, SubscribeTransactionOutputsWithProofRequest,
+        DataRequest, EpochEndingLedgerInfoRequest, FilteredStateValuesWithProofRequest,
+        NewTransactionOutputsWithProofRequest, NewTransactionsOrOutputsWithProofRequest,
+        NewTransactionsWithProofRequest, StateValuesWithProofRequest,
+        SubscribeTransactionOutputsWithProofRequest,
         SubscribeTransactionsOrOutputsWithProofRequest, SubscribeTransactionsWithProofRequest,
         SubscriptionStreamMetadata, TransactionOutputsWithProofRequest,
         TransactionsOrOutputsWithProofRequest, TransactionsWithProofRequest,
     },
-    responses::{CompleteDataRange, DataSummary, ProtocolMetadata},
+    responses::{
+        CompleteDataRange, DataSummary, Error, FilteredStateValuesWithProof, ProtocolMetadata,
+    },
     Epoch, StorageServiceRequest,
 };
 use lumio_config::config::LumioDataClientConfig;
-use lumio_crypto::hash::HashValue;
+use lumio_crypto::hash::{CryptoHash, HashValue};
 use lumio_time_service::{TimeService, TimeServiceTrait};
 use lumio_types::{
+    account_address::AccountAddress,
+    account_config::{AccountResource, CoinStoreResource},
     aggregate_signature::AggregateSignature,
     block_info::BlockInfo,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
-    transaction::Version,
+    proof::{
+        SparseMerkleLeafNode, SparseMerkleProof, TransactionAccumulatorProof,
+        TransactionInfoWithProof,
+    },
+    state_store::{
+        state_key::{prefix::StateKeyPrefix, StateKey},
+        state_value::StateValue,
+    },
+    transaction::{ExecutionStatus, TransactionInfo, Version},
+    LumioCoinType,
 };
-use claims::{assert_err, assert_ok};
+use claims::{assert_err, assert_matches, assert_ok};
 use proptest::{arbitrary::any, prelude::*};
 use rand::{thread_rng, Rng};
 
@@ -498,6 +512,97 @@ fn test_data_summary_service_state_chunk_request() {
     }
 }
 
+#[test]
+fn test_data_summary_service_filtered_state_values_request() {
+    // Create a data client config and data summary
+    let data_client_config = LumioDataClientConfig::default();
+    let data_summary = DataSummary {
+        synced_ledger_info: Some(create_ledger_info_at_version(250)),
+        states: Some(create_data_range(100, 300)),
+        ..Default::default()
+    };
+
+    // Verify the different requests that can be serviced
+    for compression in [true, false] {
+        // Test the valid request versions
+        for version in [100, 200, 250] {
+            let request = create_filtered_state_values_request(version, compression);
+            verify_serviceability(&data_client_config, &data_summary, None, request, true);
+        }
+
+        // Test invalid request versions
+        for version in [50, 99, 251, 300] {
+            let request = create_filtered_state_values_request(version, compression);
+            verify_serviceability(&data_client_config, &data_summary, None, request, false);
+        }
+    }
+}
+
+#[test]
+fn test_filtered_state_values_verification() {
+    // Create a filtered state values response with a single state value
+    let address = AccountAddress::new([1u8; AccountAddress::LENGTH]);
+    let key_prefix = StateKeyPrefix::from(address);
+    let state_key = StateKey::resource_typed::<AccountResource>(&address).unwrap();
+    let (filtered_state_values, ledger_info) =
+        create_filtered_state_values(0, key_prefix.clone(), state_key.clone());
+
+    // Verify the response against the ledger info
+    assert_ok!(filtered_state_values.verify(&ledger_info, &key_prefix, None, &[]));
+    assert_ok!(filtered_state_values.verify(&ledger_info, &key_prefix, Some(&state_key), &[]));
+
+    // Verify the response fails verification with an incorrect key prefix
+    let other_address = AccountAddress::new([2u8; AccountAddress::LENGTH]);
+    let other_key_prefix = StateKeyPrefix::from(other_address);
+    assert_err!(filtered_state_values.verify(&ledger_info, &other_key_prefix, None, &[]));
+
+    // Verify the response fails verification with a ledger info at a different version
+    let (_, other_ledger_info) =
+        create_filtered_state_values(1, key_prefix.clone(), state_key.clone());
+    assert_err!(filtered_state_values.verify(&other_ledger_info, &key_prefix, None, &[]));
+
+    // Verify the response fails verification if a state value is modified
+    let mut modified_state_values = filtered_state_values.clone();
+    modified_state_values.state_values[0].1 = StateValue::from(vec![9, 9, 9]);
+    assert_err!(modified_state_values.verify(&ledger_info, &key_prefix, None, &[]));
+
+    // Verify the response fails verification if the keys don't have the prefix
+    let mut unprefixed_state_values = filtered_state_values.clone();
+    unprefixed_state_values.next_start_key = Some(StateKey::raw(&[1, 2, 3]));
+    assert_err!(unprefixed_state_values.verify(&ledger_info, &key_prefix, None, &[]));
+
+    // Verify the response fails verification if it is truncated but empty
+    let mut empty_state_values = filtered_state_values.clone();
+    empty_state_values.state_values = vec![];
+    empty_state_values.next_start_key = Some(state_key.clone());
+    assert_err!(empty_state_values.verify(&ledger_info, &key_prefix, Some(&state_key), &[]));
+
+    // Verify the response fails verification if the keys are out of order
+    let mut unordered_state_values = filtered_state_values.clone();
+    unordered_state_values.next_start_key = Some(state_key.clone());
+    assert_err!(unordered_state_values.verify(&ledger_info, &key_prefix, None, &[]));
+
+    // Verify the response fails verification if a known key is omitted without a proof
+    let deleted_key =
+        StateKey::resource_typed::<CoinStoreResource<LumioCoinType>>(&address).unwrap();
+    let known_keys = vec![state_key.clone(), deleted_key.clone()];
+    assert_matches!(
+        filtered_state_values.verify(&ledger_info, &key_prefix, None, &known_keys),
+        Err(Error::UnverifiedAbsenceError(_))
+    );
+
+    // Verify the response passes verification if the absence of the known key is proven
+    let mut absent_state_values = filtered_state_values.clone();
+    let non_inclusion_proof = absent_state_values.state_values[0].2.clone();
+    absent_state_values.absent_keys = vec![(deleted_key, non_inclusion_proof.clone())];
+    assert_ok!(absent_state_values.verify(&ledger_info, &key_prefix, None, &known_keys));
+
+    // Verify the response fails verification if an existing key is claimed to be absent
+    let mut invalid_absent_state_values = filtered_state_values;
+    invalid_absent_state_values.absent_keys = vec![(state_key, non_inclusion_proof)];
+    assert_err!(invalid_absent_state_values.verify(&ledger_info, &key_prefix, None, &known_keys));
+}
+
 #[test]
 fn test_protocol_metadata_service() {
     // Create the protocol metadata
@@ -856,6 +961,68 @@ fn create_transactions_or_outputs_request_v2(
     StorageServiceRequest::new(data_request, use_compression)
 }
 
+/// Creates a request for filtered state values at the given version
+fn create_filtered_state_values_request(
+    version: Version,
+    use_compression: bool,
+) -> StorageServiceRequest {
+    let data_request =
+        DataRequest::GetFilteredStateValuesWithProof(FilteredStateValuesWithProofRequest {
+            version,
+            key_prefix: StateKeyPrefix::from(AccountAddress::ONE),
+            start_key: None,
+            known_keys: vec![],
+        });
+    StorageServiceRequest::new(data_request, use_compression)
+}
+
+/// Creates a filtered state values response (and a ledger info that
+/// can be used to verify it) for the given version and state key.
+fn create_filtered_state_values(
+    version: Version,
+    key_prefix: StateKeyPrefix,
+    state_key: StateKey,
+) -> (FilteredStateValuesWithProof, LedgerInfo) {
+    // Create the state value and proof (the state tree only contains a single leaf)
+    let state_value = StateValue::from(vec![1, 2, 3]);
+    let leaf = SparseMerkleLeafNode::new(*state_key.crypto_hash_ref(), state_value.hash());
+    let proof = SparseMerkleProof::new(Some(leaf), vec![]);
+
+    // Create the transaction info and ledger info
+    let transaction_info = TransactionInfo::new(
+        HashValue::zero(),
+        HashValue::zero(),
+        HashValue::zero(),
+        Some(leaf.hash()),
+        0,
+        ExecutionStatus::Success,
+        None,
+    );
+    let block_info = BlockInfo::new(
+        0,
+        0,
+        HashValue::zero(),
+        transaction_info.hash(),
+        version,
+        0,
+        None,
+    );
+    let ledger_info = LedgerInfo::new(block_info, HashValue::zero());
+    let transaction_info_with_proof =
+        TransactionInfoWithProof::new(TransactionAccumulatorProof::new(vec![]), transaction_info);
+
+    // Create the filtered state values
+    let filtered_state_values = FilteredStateValuesWithProof {
+        version,
+        key_prefix,
+        state_values: vec![(state_key, state_value, proof)],
+        absent_keys: vec![],
+        next_start_key: None,
+        transaction_info_with_proof,
+    };
+    (filtered_state_values, ledger_info)
+}
+
 /// Creates a request for state values
 fn create_state_values_request(
     version: Version,
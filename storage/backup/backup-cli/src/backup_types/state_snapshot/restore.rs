@@ -251,7 +251,7 @@ impl StateSnapshotRestoreController {
         }
     }
 
-    async fn read_state_value(
+    pub async fn read_state_value(
         storage: &Arc<dyn BackupStorage>,
         file_handle: FileHandle,
     ) -> Result<Vec<(StateKey, StateValue)>> {
@@ -87,7 +87,8 @@ pub struct TransactionRestoreController {
     inner: TransactionRestoreBatchController,
 }
 
-struct LoadedChunk {
+/// A transaction chunk loaded from backup storage, verified against the ledger info in its proof.
+pub struct LoadedChunk {
     pub manifest: TransactionChunk,
     pub txns: Vec<Transaction>,
     pub persisted_aux_info: Vec<PersistedAuxiliaryInfo>,
@@ -98,7 +99,7 @@ struct LoadedChunk {
 }
 
 impl LoadedChunk {
-    async fn load(
+    pub async fn load(
         manifest: TransactionChunk,
         storage: &Arc<dyn BackupStorage>,
         epoch_history: Option<&Arc<EpochHistory>>,
@@ -5,25 +5,38 @@
 use crate::{
     backup_types::{
         epoch_ending::backup::{EpochEndingBackupController, EpochEndingBackupOpt},
-        state_snapshot::backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
-        transaction::backup::{TransactionBackupController, TransactionBackupOpt},
+        state_snapshot::{
+            backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+            manifest::StateSnapshotBackup,
+        },
+        transaction::{
+            backup::{TransactionBackupController, TransactionBackupOpt},
+            manifest::TransactionBackup,
+        },
     },
     metadata,
-    metadata::{cache::MetadataCacheOpt, view::MetadataView, CompactionTimestampsMeta, Metadata},
+    metadata::{
+        cache::MetadataCacheOpt,
+        retention::{RetentionPlan, RetentionPolicy},
+        view::MetadataView,
+        CompactionTimestampsMeta, Metadata, StateSnapshotBackupMeta,
+    },
     metrics::backup::{
         EPOCH_ENDING_EPOCH, HEARTBEAT_TS, STATE_SNAPSHOT_EPOCH, TRANSACTION_VERSION,
     },
     storage::{BackupStorage, FileHandle},
     utils::{
-        backup_service_client::BackupServiceClient, unix_timestamp_sec, ConcurrentDownloadsOpt,
-        GlobalBackupOpt,
+        backup_service_client::BackupServiceClient, storage_ext::BackupStorageExt,
+        unix_timestamp_sec, ConcurrentDownloadsOpt, GlobalBackupOpt,
     },
 };
 use anyhow::{anyhow, ensure, Result};
 use lumio_db::backup::backup_handler::DbState;
 use lumio_infallible::duration_since_epoch;
 use lumio_logger::prelude::*;
-use lumio_types::transaction::Version;
+use lumio_types::{
+    ledger_info::LedgerInfoWithSignatures, proof::TransactionInfoWithProof, transaction::Version,
+};
 use clap::Parser;
 use futures::{stream, Future, StreamExt};
 use std::{
@@ -476,6 +489,185 @@ impl BackupCompactor {
     }
 }
 
+/// Deletes the backups that fall out of a `RetentionPolicy`.
+///
+/// The metadata of the backups to keep is rewritten into new metadata files first, and the old
+/// metadata files are moved out, so that the expired backups disappear from the metadata before
+/// their data is deleted. A run interrupted before deleting all data leaves orphan files behind,
+/// but never a backup that's listed and incomplete.
+pub struct BackupGarbageCollector {
+    policy: RetentionPolicy,
+    storage: Arc<dyn BackupStorage>,
+    metadata_cache_opt: MetadataCacheOpt,
+    concurrent_downloads: usize,
+    dry_run: bool,
+}
+
+impl BackupGarbageCollector {
+    pub fn new(
+        policy: RetentionPolicy,
+        metadata_cache_opt: MetadataCacheOpt,
+        storage: Arc<dyn BackupStorage>,
+        concurrent_downloads: usize,
+        dry_run: bool,
+    ) -> Self {
+        BackupGarbageCollector {
+            policy,
+            storage,
+            metadata_cache_opt,
+            concurrent_downloads,
+            dry_run,
+        }
+    }
+
+    async fn load_snapshot_timestamp_secs(
+        &self,
+        snapshot: &StateSnapshotBackupMeta,
+    ) -> Result<u64> {
+        let manifest: StateSnapshotBackup = self.storage.load_json_file(&snapshot.manifest).await?;
+        let (_txn_info_with_proof, li): (TransactionInfoWithProof, LedgerInfoWithSignatures) =
+            self.storage.load_bcs_file(&manifest.proof).await?;
+        Ok(li.ledger_info().timestamp_usecs() / 1_000_000)
+    }
+
+    async fn save_metadata(&self, name: String, metadata: Vec<Metadata>) -> Result<FileHandle> {
+        let lines = metadata
+            .iter()
+            .map(Metadata::to_text_line)
+            .collect::<Result<Vec<_>>>()?;
+        self.storage
+            .save_metadata_lines(&name.parse()?, &lines)
+            .await
+    }
+
+    pub async fn run(self) -> Result<()> {
+        info!(dry_run = self.dry_run, "Backup garbage collection started");
+        let metaview = metadata::cache::sync_and_load(
+            &self.metadata_cache_opt,
+            Arc::clone(&self.storage),
+            self.concurrent_downloads,
+        )
+        .await?;
+
+        let mut snapshot_timestamps_secs = HashMap::new();
+        for snapshot in metaview.all_state_snapshots() {
+            match self.load_snapshot_timestamp_secs(snapshot).await {
+                Ok(timestamp_secs) => {
+                    snapshot_timestamps_secs.insert(snapshot.version, timestamp_secs);
+                },
+                Err(err) => warn!(
+                    manifest = snapshot.manifest,
+                    error = %err,
+                    "Failed to load state snapshot timestamp, keeping the snapshot.",
+                ),
+            }
+        }
+
+        let plan = RetentionPlan::new(
+            &metaview,
+            &self.policy,
+            &snapshot_timestamps_secs,
+            duration_since_epoch().as_secs(),
+        );
+        println!("{}", plan);
+        if self.dry_run {
+            info!("Dry run, nothing deleted.");
+            return Ok(());
+        }
+        if !plan.has_expired() {
+            info!("No backup expired, nothing to delete.");
+            return Ok(());
+        }
+
+        // Rewrite the metadata of what's kept, and move out the old metadata files.
+        let files = metaview.get_file_handles();
+        let mut new_files = HashSet::new();
+        let epoch_ending = metaview
+            .all_epoch_ending_backups()
+            .iter()
+            .cloned()
+            .map(Metadata::EpochEndingBackup)
+            .collect::<Vec<_>>();
+        let state_snapshots = plan
+            .kept_state_snapshots()
+            .cloned()
+            .map(Metadata::StateSnapshotBackup)
+            .collect::<Vec<_>>();
+        let transactions = plan
+            .kept_transactions()
+            .cloned()
+            .map(Metadata::TransactionBackup)
+            .collect::<Vec<_>>();
+        for (prefix, metadata) in [
+            ("epoch_ending", epoch_ending),
+            ("state_snapshot", state_snapshots),
+            ("transaction", transactions),
+        ] {
+            if !metadata.is_empty() {
+                let name = format!("{}_retained_{}.meta", prefix, plan.now_secs);
+                new_files.insert(self.save_metadata(name, metadata).await?);
+            }
+        }
+        if let Some(identity) = metaview.identity() {
+            let metadata = Metadata::Identity(identity.clone());
+            new_files.insert(
+                self.storage
+                    .save_metadata_line(&metadata.name(), &metadata.to_text_line()?)
+                    .await?,
+            );
+        }
+        // Files listed in the compaction timestamps might have been moved out already.
+        let existing_files: HashSet<_> = self
+            .storage
+            .list_metadata_files()
+            .await?
+            .into_iter()
+            .collect();
+        for file in files {
+            if new_files.contains(&file) || !existing_files.contains(&file) {
+                continue;
+            }
+            info!(file = file, "Backup metadata file.");
+            // Unlike compaction, failing here must stop the run: the expired backups could still
+            // be listed in this file.
+            self.storage.backup_metadata_file(&file).await?;
+        }
+
+        for snapshot in plan.expired_state_snapshots() {
+            info!(
+                version = snapshot.version,
+                manifest = snapshot.manifest,
+                "Deleting expired state snapshot backup."
+            );
+            let manifest: StateSnapshotBackup =
+                self.storage.load_json_file(&snapshot.manifest).await?;
+            for chunk in &manifest.chunks {
+                self.storage.delete_file(&chunk.blobs).await?;
+                self.storage.delete_file(&chunk.proof).await?;
+            }
+            self.storage.delete_file(&manifest.proof).await?;
+            self.storage.delete_file(&snapshot.manifest).await?;
+        }
+        for txn in plan.expired_transactions() {
+            info!(
+                first_version = txn.first_version,
+                last_version = txn.last_version,
+                manifest = txn.manifest,
+                "Deleting expired transaction backup."
+            );
+            let manifest: TransactionBackup = self.storage.load_json_file(&txn.manifest).await?;
+            for chunk in &manifest.chunks {
+                self.storage.delete_file(&chunk.transactions).await?;
+                self.storage.delete_file(&chunk.proof).await?;
+            }
+            self.storage.delete_file(&txn.manifest).await?;
+        }
+
+        info!("Backup garbage collection finished");
+        Ok(())
+    }
+}
+
 trait Worker<'a, S, Fut: Future<Output = Result<S>> + 'a>:
     Fn(&'a BackupCoordinator, S, DbState) -> Fut
 {
@@ -6,3 +6,6 @@ pub mod backup;
 pub mod replay_verify;
 pub mod restore;
 pub mod verify;
+
+#[cfg(test)]
+mod tests;
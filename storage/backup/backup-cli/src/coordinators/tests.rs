@@ -0,0 +1,270 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        state_snapshot::manifest::{StateSnapshotBackup, StateSnapshotChunk},
+        transaction::manifest::{TransactionBackup, TransactionChunk, TransactionChunkFormat},
+    },
+    coordinators::backup::BackupGarbageCollector,
+    metadata::{cache, cache::MetadataCacheOpt, retention::RetentionPolicy, Metadata},
+    storage::{local_fs::LocalFs, BackupHandleRef, BackupStorage, FileHandle},
+};
+use lumio_crypto::HashValue;
+use lumio_infallible::duration_since_epoch;
+use lumio_temppath::TempPath;
+use lumio_types::{
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    proof::{TransactionAccumulatorProof, TransactionInfoWithProof},
+    transaction::{ExecutionStatus, TransactionInfo, Version},
+};
+use itertools::Itertools;
+use std::sync::Arc;
+use tokio::{io::AsyncWriteExt, runtime::Runtime};
+
+const SECS_PER_HOUR: u64 = 3600;
+const SECS_PER_DAY: u64 = 24 * SECS_PER_HOUR;
+
+async fn write_file(
+    storage: &Arc<dyn BackupStorage>,
+    backup_handle: &BackupHandleRef,
+    name: &str,
+    content: &[u8],
+) -> FileHandle {
+    let (file_handle, mut file) = storage
+        .create_for_write(backup_handle, &name.parse().unwrap())
+        .await
+        .unwrap();
+    file.write_all(content).await.unwrap();
+    file.shutdown().await.unwrap();
+    file_handle
+}
+
+async fn save_metadata(storage: &Arc<dyn BackupStorage>, metadata: Metadata) {
+    storage
+        .save_metadata_line(&metadata.name(), &metadata.to_text_line().unwrap())
+        .await
+        .unwrap();
+}
+
+/// Writes a state snapshot backup at `version` taken at `timestamp_secs`, returning its manifest.
+async fn write_state_snapshot(
+    storage: &Arc<dyn BackupStorage>,
+    version: Version,
+    timestamp_secs: u64,
+) -> FileHandle {
+    let backup_handle = storage
+        .create_backup(&format!("state_ver_{}", version).parse().unwrap())
+        .await
+        .unwrap();
+    let li = LedgerInfoWithSignatures::new(
+        LedgerInfo::new(
+            BlockInfo::new(
+                0,
+                0,
+                HashValue::zero(),
+                HashValue::zero(),
+                version,
+                timestamp_secs * 1_000_000,
+                None,
+            ),
+            HashValue::zero(),
+        ),
+        AggregateSignature::empty(),
+    );
+    let txn_info_with_proof = TransactionInfoWithProof::new(
+        TransactionAccumulatorProof::new(vec![]),
+        TransactionInfo::new(
+            HashValue::zero(),
+            HashValue::zero(),
+            HashValue::zero(),
+            Some(HashValue::zero()),
+            0,
+            ExecutionStatus::Success,
+            None,
+        ),
+    );
+    let proof = write_file(
+        storage,
+        &backup_handle,
+        "proof",
+        &bcs::to_bytes(&(txn_info_with_proof, li)).unwrap(),
+    )
+    .await;
+    let manifest = StateSnapshotBackup {
+        version,
+        epoch: 0,
+        root_hash: HashValue::zero(),
+        chunks: vec![StateSnapshotChunk {
+            first_idx: 0,
+            last_idx: 0,
+            first_key: HashValue::zero(),
+            last_key: HashValue::zero(),
+            blobs: write_file(storage, &backup_handle, "0-0.chunk", b"blobs").await,
+            proof: write_file(storage, &backup_handle, "0-0.proof", b"proof").await,
+        }],
+        proof,
+    };
+    let manifest = write_file(
+        storage,
+        &backup_handle,
+        "state.manifest",
+        &serde_json::to_vec(&manifest).unwrap(),
+    )
+    .await;
+    save_metadata(
+        storage,
+        Metadata::new_state_snapshot_backup(0, version, manifest.clone()),
+    )
+    .await;
+    manifest
+}
+
+/// Writes a transaction backup of [`first_version`, `last_version`], returning its manifest.
+async fn write_transactions(
+    storage: &Arc<dyn BackupStorage>,
+    first_version: Version,
+    last_version: Version,
+) -> FileHandle {
+    let backup_handle = storage
+        .create_backup(&format!("transaction_{}", first_version).parse().unwrap())
+        .await
+        .unwrap();
+    let manifest = TransactionBackup {
+        first_version,
+        last_version,
+        chunks: vec![TransactionChunk {
+            first_version,
+            last_version,
+            transactions: write_file(storage, &backup_handle, "chunk", b"transactions").await,
+            proof: write_file(storage, &backup_handle, "chunk.proof", b"proof").await,
+            format: TransactionChunkFormat::V1,
+        }],
+    };
+    let manifest = write_file(
+        storage,
+        &backup_handle,
+        "transaction.manifest",
+        &serde_json::to_vec(&manifest).unwrap(),
+    )
+    .await;
+    save_metadata(
+        storage,
+        Metadata::new_transaction_backup(first_version, last_version, manifest.clone()),
+    )
+    .await;
+    manifest
+}
+
+#[test]
+fn test_garbage_collection_end_to_end() {
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let storage: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+    let now_secs = duration_since_epoch().as_secs();
+    let rt = Runtime::new().unwrap();
+
+    // Snapshots at versions 0, 100, 200 and 300, and transactions of [0, 399] in 4 backups.
+    let snapshot_ages_secs = [10 * SECS_PER_DAY, 5 * SECS_PER_DAY, 36 * SECS_PER_HOUR, 1];
+    let (snapshot_manifests, txn_manifests, epoch_ending_manifest) = rt.block_on(async {
+        let mut snapshot_manifests = vec![];
+        let mut txn_manifests = vec![];
+        for (i, age_secs) in snapshot_ages_secs.iter().enumerate() {
+            let version = i as Version * 100;
+            snapshot_manifests
+                .push(write_state_snapshot(&storage, version, now_secs - age_secs).await);
+            txn_manifests.push(write_transactions(&storage, version, version + 99).await);
+        }
+        let backup_handle = storage
+            .create_backup(&"epoch_ending_0".parse().unwrap())
+            .await
+            .unwrap();
+        let epoch_ending_manifest =
+            write_file(&storage, &backup_handle, "epoch_ending.manifest", b"{}").await;
+        save_metadata(
+            &storage,
+            Metadata::new_epoch_ending_backup(0, 0, 0, 0, epoch_ending_manifest.clone()),
+        )
+        .await;
+        (snapshot_manifests, txn_manifests, epoch_ending_manifest)
+    });
+
+    // Keep the snapshots of the last 2 days, and make the last day restorable.
+    let policy = RetentionPolicy {
+        daily_snapshot_days: 2,
+        weekly_snapshot_weeks: 0,
+        min_restorable_window_secs: SECS_PER_DAY,
+    };
+    let cache_dir = TempPath::new();
+    rt.block_on(
+        BackupGarbageCollector::new(
+            policy.clone(),
+            MetadataCacheOpt::new(Some(cache_dir.path())),
+            Arc::clone(&storage),
+            1,     /* concurrent_downloads */
+            false, /* dry_run */
+        )
+        .run(),
+    )
+    .unwrap();
+
+    // Snapshot 200 is the restorable window base, and snapshot 300 the latest. Transactions from
+    // the window base on are kept, the rest is deleted.
+    let exists = |file_handle: &FileHandle| backup_dir.path().join(file_handle).exists();
+    assert!(!exists(&snapshot_manifests[0]));
+    assert!(!exists(&snapshot_manifests[1]));
+    assert!(exists(&snapshot_manifests[2]));
+    assert!(exists(&snapshot_manifests[3]));
+    assert!(!exists(&txn_manifests[0]));
+    assert!(!exists(&txn_manifests[1]));
+    assert!(exists(&txn_manifests[2]));
+    assert!(exists(&txn_manifests[3]));
+    assert!(exists(&epoch_ending_manifest));
+    // Emptied backup folders are removed too.
+    assert!(!backup_dir.path().join("state_ver_0").exists());
+
+    // The rewritten metadata only lists what's left, and every version of the restorable window
+    // can still be restored from it.
+    let new_cache_dir = TempPath::new();
+    let view = rt
+        .block_on(cache::sync_and_load(
+            &MetadataCacheOpt::new(Some(new_cache_dir.path())),
+            Arc::clone(&storage),
+            1, /* concurrent_downloads */
+        ))
+        .unwrap();
+    let snapshot_versions: Vec<_> = view
+        .all_state_snapshots()
+        .iter()
+        .map(|s| s.version)
+        .sorted()
+        .collect();
+    assert_eq!(snapshot_versions, vec![200, 300]);
+    assert_eq!(view.all_transaction_backups().len(), 2);
+    assert_eq!(view.all_epoch_ending_backups().len(), 1);
+    for target_version in 200..=399 {
+        let snapshot = view.select_state_snapshot(target_version).unwrap().unwrap();
+        let txns = view
+            .select_transaction_backups(snapshot.version, target_version)
+            .unwrap();
+        assert!(txns.first().unwrap().first_version <= snapshot.version);
+        assert!(txns.last().unwrap().last_version >= target_version);
+    }
+
+    // Running again finds nothing more to delete.
+    rt.block_on(
+        BackupGarbageCollector::new(
+            policy,
+            MetadataCacheOpt::new(Some(cache_dir.path())),
+            Arc::clone(&storage),
+            1,     /* concurrent_downloads */
+            false, /* dry_run */
+        )
+        .run(),
+    )
+    .unwrap();
+    assert!(exists(&snapshot_manifests[2]));
+    assert!(exists(&txn_manifests[2]));
+}
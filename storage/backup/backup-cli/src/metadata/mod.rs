@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod cache;
+pub mod retention;
 pub mod view;
 
 use crate::storage::{FileHandle, ShellSafeName, TextLine};
@@ -0,0 +1,423 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::metadata::{view::MetadataView, StateSnapshotBackupMeta, TransactionBackupMeta};
+use lumio_types::transaction::Version;
+use clap::Parser;
+use itertools::Itertools;
+use serde::Serialize;
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+};
+
+const SECS_PER_DAY: u64 = 86400;
+const SECS_PER_WEEK: u64 = 7 * SECS_PER_DAY;
+
+#[derive(Clone, Debug, Parser)]
+pub struct RetentionPolicy {
+    #[clap(
+        long,
+        default_value_t = 7,
+        help = "Keep the latest state snapshot of each of this many most recent days (UTC)."
+    )]
+    pub daily_snapshot_days: u64,
+    #[clap(
+        long,
+        default_value_t = 4,
+        help = "Keep the latest state snapshot of each of this many most recent weeks (counted \
+        from the unix epoch), on top of the daily ones."
+    )]
+    pub weekly_snapshot_weeks: u64,
+    #[clap(
+        long,
+        default_value_t = 7 * SECS_PER_DAY,
+        help = "Any version committed within this many seconds stays restorable: the latest state \
+        snapshot before the window and all transactions since then are kept."
+    )]
+    pub min_restorable_window_secs: u64,
+}
+
+/// Why a backup is kept.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeepReason {
+    /// The latest state snapshot.
+    Latest,
+    /// The latest state snapshot of a day, identified by days since the unix epoch.
+    Daily(u64),
+    /// The latest state snapshot of a week, identified by weeks since the unix epoch.
+    Weekly(u64),
+    /// The state snapshot that the restorable window is restored from.
+    RestorableWindowBase,
+    /// Transactions needed to restore any version in the restorable window.
+    RestorableWindow,
+    /// Transactions at the version of a kept state snapshot.
+    KeptSnapshot(Version),
+    /// The timestamp of the state snapshot is unknown, it's kept to be on the safe side.
+    UnknownTimestamp,
+}
+
+impl fmt::Display for KeepReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Latest => write!(f, "latest"),
+            Self::Daily(day) => write!(f, "daily (day {})", day),
+            Self::Weekly(week) => write!(f, "weekly (week {})", week),
+            Self::RestorableWindowBase => write!(f, "restorable window base"),
+            Self::RestorableWindow => write!(f, "restorable window"),
+            Self::KeptSnapshot(version) => write!(f, "snapshot at version {}", version),
+            Self::UnknownTimestamp => write!(f, "unknown timestamp"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct StateSnapshotDecision {
+    pub backup: StateSnapshotBackupMeta,
+    pub timestamp_secs: Option<u64>,
+    /// Empty if the backup is to be deleted.
+    pub keep_reasons: Vec<KeepReason>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TransactionDecision {
+    pub backup: TransactionBackupMeta,
+    /// Empty if the backup is to be deleted.
+    pub keep_reasons: Vec<KeepReason>,
+}
+
+/// Which backups to keep and which to delete according to a `RetentionPolicy`.
+///
+/// Epoch ending backups are small and needed to verify anything restored from genesis on, so
+/// they are always kept.
+#[derive(Clone, Debug, Serialize)]
+pub struct RetentionPlan {
+    pub now_secs: u64,
+    /// Every version from this one on can be restored with the kept backups. `None` if there's
+    /// no backup at all.
+    pub restorable_from_version: Option<Version>,
+    pub num_epoch_ending_backups: usize,
+    pub state_snapshots: Vec<StateSnapshotDecision>,
+    pub transactions: Vec<TransactionDecision>,
+}
+
+impl RetentionPlan {
+    /// Plans the retention of the backups in `view`. `snapshot_timestamps_secs` tells the
+    /// timestamps of the state snapshots by version, state snapshots missing from it are kept.
+    pub fn new(
+        view: &MetadataView,
+        policy: &RetentionPolicy,
+        snapshot_timestamps_secs: &HashMap<Version, u64>,
+        now_secs: u64,
+    ) -> Self {
+        let snapshots: Vec<_> = view.all_state_snapshots().iter().sorted().collect();
+        let mut snapshot_reasons: Vec<BTreeSet<KeepReason>> =
+            vec![BTreeSet::new(); snapshots.len()];
+
+        if let Some(reasons) = snapshot_reasons.last_mut() {
+            reasons.insert(KeepReason::Latest);
+        }
+
+        let now_day = now_secs / SECS_PER_DAY;
+        let now_week = now_secs / SECS_PER_WEEK;
+        let window_start_secs = now_secs.saturating_sub(policy.min_restorable_window_secs);
+        let mut window_base = None;
+        // Newest first, so the first snapshot seen in a day or week is the one kept for it.
+        for (idx, snapshot) in snapshots.iter().enumerate().rev() {
+            let reasons = &mut snapshot_reasons[idx];
+            let timestamp_secs = match snapshot_timestamps_secs.get(&snapshot.version) {
+                Some(timestamp_secs) => *timestamp_secs,
+                None => {
+                    reasons.insert(KeepReason::UnknownTimestamp);
+                    continue;
+                },
+            };
+
+            let day = timestamp_secs / SECS_PER_DAY;
+            if now_day.saturating_sub(day) < policy.daily_snapshot_days
+                && !Self::has_reason(&snapshot_reasons, KeepReason::Daily(day))
+            {
+                snapshot_reasons[idx].insert(KeepReason::Daily(day));
+            }
+            let week = timestamp_secs / SECS_PER_WEEK;
+            if now_week.saturating_sub(week) < policy.weekly_snapshot_weeks
+                && !Self::has_reason(&snapshot_reasons, KeepReason::Weekly(week))
+            {
+                snapshot_reasons[idx].insert(KeepReason::Weekly(week));
+            }
+            if window_base.is_none() && timestamp_secs <= window_start_secs {
+                window_base = Some(idx);
+            }
+        }
+        // If all snapshots are in the window, the window can only be restored from the oldest one.
+        let window_base = window_base.or_else(|| (!snapshots.is_empty()).then_some(0));
+        if let Some(idx) = window_base {
+            snapshot_reasons[idx].insert(KeepReason::RestorableWindowBase);
+        }
+
+        let kept_snapshot_versions: Vec<Version> = snapshots
+            .iter()
+            .zip(&snapshot_reasons)
+            .filter(|(_, reasons)| !reasons.is_empty())
+            .map(|(snapshot, _)| snapshot.version)
+            .collect();
+        // Without any snapshot, transactions have to be replayed from genesis.
+        let restorable_from_version = match window_base {
+            Some(idx) => Some(snapshots[idx].version),
+            None => view
+                .all_transaction_backups()
+                .iter()
+                .map(|t| t.first_version)
+                .min(),
+        };
+
+        let transactions = view
+            .all_transaction_backups()
+            .iter()
+            .sorted()
+            .map(|backup| {
+                let mut keep_reasons = vec![];
+                if restorable_from_version.is_some_and(|v| backup.last_version >= v) {
+                    keep_reasons.push(KeepReason::RestorableWindow);
+                }
+                keep_reasons.extend(
+                    kept_snapshot_versions
+                        .iter()
+                        .filter(|v| (backup.first_version..=backup.last_version).contains(*v))
+                        .map(|v| KeepReason::KeptSnapshot(*v)),
+                );
+                TransactionDecision {
+                    backup: backup.clone(),
+                    keep_reasons,
+                }
+            })
+            .collect();
+
+        let state_snapshots = snapshots
+            .into_iter()
+            .zip(snapshot_reasons)
+            .map(|(snapshot, reasons)| StateSnapshotDecision {
+                backup: snapshot.clone(),
+                timestamp_secs: snapshot_timestamps_secs.get(&snapshot.version).copied(),
+                keep_reasons: reasons.into_iter().collect(),
+            })
+            .collect();
+
+        Self {
+            now_secs,
+            restorable_from_version,
+            num_epoch_ending_backups: view.all_epoch_ending_backups().len(),
+            state_snapshots,
+            transactions,
+        }
+    }
+
+    fn has_reason(snapshot_reasons: &[BTreeSet<KeepReason>], reason: KeepReason) -> bool {
+        snapshot_reasons
+            .iter()
+            .any(|reasons| reasons.contains(&reason))
+    }
+
+    pub fn expired_state_snapshots(&self) -> impl Iterator<Item = &StateSnapshotBackupMeta> {
+        self.state_snapshots
+            .iter()
+            .filter(|d| d.keep_reasons.is_empty())
+            .map(|d| &d.backup)
+    }
+
+    pub fn kept_state_snapshots(&self) -> impl Iterator<Item = &StateSnapshotBackupMeta> {
+        self.state_snapshots
+            .iter()
+            .filter(|d| !d.keep_reasons.is_empty())
+            .map(|d| &d.backup)
+    }
+
+    pub fn expired_transactions(&self) -> impl Iterator<Item = &TransactionBackupMeta> {
+        self.transactions
+            .iter()
+            .filter(|d| d.keep_reasons.is_empty())
+            .map(|d| &d.backup)
+    }
+
+    pub fn kept_transactions(&self) -> impl Iterator<Item = &TransactionBackupMeta> {
+        self.transactions
+            .iter()
+            .filter(|d| !d.keep_reasons.is_empty())
+            .map(|d| &d.backup)
+    }
+
+    pub fn has_expired(&self) -> bool {
+        self.expired_state_snapshots().next().is_some()
+            || self.expired_transactions().next().is_some()
+    }
+}
+
+impl fmt::Display for RetentionPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn reasons(keep_reasons: &[KeepReason]) -> String {
+            if keep_reasons.is_empty() {
+                "DELETE".to_string()
+            } else {
+                format!("keep: {}", keep_reasons.iter().join(", "))
+            }
+        }
+
+        writeln!(f, "Retention plan at {}:", self.now_secs)?;
+        writeln!(
+            f,
+            "  restorable from version: {}",
+            self.restorable_from_version
+                .map_or_else(|| "none".to_string(), |v| v.to_string())
+        )?;
+        writeln!(
+            f,
+            "  epoch ending backups: {} (all kept)",
+            self.num_epoch_ending_backups
+        )?;
+        writeln!(
+            f,
+            "  state snapshots: {} kept, {} to delete",
+            self.kept_state_snapshots().count(),
+            self.expired_state_snapshots().count(),
+        )?;
+        for d in &self.state_snapshots {
+            writeln!(
+                f,
+                "    epoch {} version {} timestamp {}: {} ({})",
+                d.backup.epoch,
+                d.backup.version,
+                d.timestamp_secs
+                    .map_or_else(|| "unknown".to_string(), |t| t.to_string()),
+                reasons(&d.keep_reasons),
+                d.backup.manifest,
+            )?;
+        }
+        writeln!(
+            f,
+            "  transaction backups: {} kept, {} to delete",
+            self.kept_transactions().count(),
+            self.expired_transactions().count(),
+        )?;
+        for d in &self.transactions {
+            writeln!(
+                f,
+                "    versions [{}, {}]: {} ({})",
+                d.backup.first_version,
+                d.backup.last_version,
+                reasons(&d.keep_reasons),
+                d.backup.manifest,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::Metadata;
+
+    fn policy() -> RetentionPolicy {
+        RetentionPolicy {
+            daily_snapshot_days: 2,
+            weekly_snapshot_weeks: 2,
+            min_restorable_window_secs: SECS_PER_DAY,
+        }
+    }
+
+    #[test]
+    fn test_retention_plan() {
+        // A snapshot every 12 hours at versions 0, 100, ..., 2900 (15 days), with a transaction
+        // backup every 100 versions.
+        let now_secs = 1000 * SECS_PER_WEEK;
+        let start_secs = now_secs - 15 * SECS_PER_DAY;
+        let mut metadata = vec![Metadata::new_epoch_ending_backup(
+            0,
+            29,
+            0,
+            2900,
+            "epoch_ending".to_string(),
+        )];
+        let mut timestamps = HashMap::new();
+        for i in 0..30 {
+            let version = i * 100;
+            metadata.push(Metadata::new_state_snapshot_backup(
+                i,
+                version,
+                format!("snapshot_{}", version),
+            ));
+            metadata.push(Metadata::new_transaction_backup(
+                version,
+                version + 99,
+                format!("txn_{}", version),
+            ));
+            // Leave one snapshot without timestamp.
+            if i != 3 {
+                timestamps.insert(version, start_secs + i * SECS_PER_DAY / 2 + 1);
+            }
+        }
+        let view = MetadataView::new(metadata, vec![]);
+
+        let plan = RetentionPlan::new(&view, &policy(), &timestamps, now_secs);
+
+        let kept: Vec<_> = plan.kept_state_snapshots().map(|s| s.version).collect();
+        // 2900: latest, also the latest of yesterday (day 6999) and of last week (week 999).
+        // 2700: the latest of the day before yesterday (day 6998), and the window base, the newest
+        //     one at least one day old.
+        // 1500: the latest of the week before last week (week 998).
+        // 300: unknown timestamp.
+        assert_eq!(kept, vec![300, 1500, 2700, 2900]);
+        assert_eq!(plan.restorable_from_version, Some(2700));
+        assert_eq!(plan.state_snapshots.last().unwrap().keep_reasons, vec![
+            KeepReason::Latest,
+            KeepReason::Daily(6999),
+            KeepReason::Weekly(999),
+        ]);
+
+        let kept: Vec<_> = plan.kept_transactions().map(|t| t.first_version).collect();
+        assert_eq!(kept, vec![300, 1500, 2700, 2800, 2900]);
+        assert_eq!(plan.expired_transactions().count(), 25);
+        assert_eq!(plan.num_epoch_ending_backups, 1);
+        assert!(plan.has_expired());
+    }
+
+    #[test]
+    fn test_retention_plan_keeps_window_without_old_snapshot() {
+        let now_secs = 1000 * SECS_PER_WEEK;
+        let view = MetadataView::new(
+            vec![
+                Metadata::new_state_snapshot_backup(1, 100, "snapshot_100".to_string()),
+                Metadata::new_state_snapshot_backup(2, 200, "snapshot_200".to_string()),
+                Metadata::new_transaction_backup(0, 99, "txn_0".to_string()),
+                Metadata::new_transaction_backup(100, 199, "txn_100".to_string()),
+                Metadata::new_transaction_backup(200, 299, "txn_200".to_string()),
+            ],
+            vec![],
+        );
+        let timestamps = HashMap::from([(100, now_secs - 10), (200, now_secs - 5)]);
+
+        let plan = RetentionPlan::new(&view, &policy(), &timestamps, now_secs);
+
+        // Both snapshots are in the window, so the window is restorable from the oldest one on.
+        assert_eq!(plan.restorable_from_version, Some(100));
+        assert_eq!(plan.expired_state_snapshots().count(), 0);
+        let expired: Vec<_> = plan
+            .expired_transactions()
+            .map(|t| t.first_version)
+            .collect();
+        assert_eq!(expired, vec![0]);
+        // What's left is still restorable.
+        let kept_view = MetadataView::new(
+            plan.kept_state_snapshots()
+                .map(|s| Metadata::StateSnapshotBackup(s.clone()))
+                .chain(
+                    plan.kept_transactions()
+                        .map(|t| Metadata::TransactionBackup(t.clone())),
+                )
+                .collect(),
+            vec![],
+        );
+        kept_view.select_transaction_backups(100, 299).unwrap();
+    }
+}
@@ -21,7 +21,7 @@ pub struct MetadataView {
     epoch_ending_backups: Vec<EpochEndingBackupMeta>,
     state_snapshot_backups: Vec<StateSnapshotBackupMeta>,
     transaction_backups: Vec<TransactionBackupMeta>,
-    _identity: Option<IdentityMeta>,
+    identity: Option<IdentityMeta>,
     // The compaction timestamps of the file handles producing this view
     compaction_timestamps: Option<CompactionTimestampsMeta>,
 }
@@ -73,7 +73,7 @@ impl MetadataView {
             epoch_ending_backups,
             state_snapshot_backups,
             transaction_backups,
-            _identity: identity,
+            identity,
             compaction_timestamps: compaction_meta_opt,
         }
     }
@@ -109,6 +109,18 @@ impl MetadataView {
         &self.state_snapshot_backups
     }
 
+    pub fn all_epoch_ending_backups(&self) -> &[EpochEndingBackupMeta] {
+        &self.epoch_ending_backups
+    }
+
+    pub fn all_transaction_backups(&self) -> &[TransactionBackupMeta] {
+        &self.transaction_backups
+    }
+
+    pub fn identity(&self) -> Option<&IdentityMeta> {
+        self.identity.as_ref()
+    }
+
     pub fn select_state_snapshot(
         &self,
         target_version: Version,
@@ -136,25 +148,34 @@ impl MetadataView {
         target_version: Version,
     ) -> Result<Vec<TransactionBackupMeta>> {
         // This can be more flexible, but for now we assume and check backups are continuous in
-        // range (which is always true when we backup from a single backup coordinator)
-        let mut next_ver = 0;
+        // range (which is always true when we backup from a single backup coordinator). Backups
+        // before `start_version` are not needed and can be removed by the retention policy.
+        let mut next_ver = None;
         let mut res = Vec::new();
         for backup in self.transaction_backups.iter().sorted() {
             if backup.first_version > target_version {
                 break;
             }
-            ensure!(
-                backup.first_version == next_ver,
-                "Transaction backup ranges not continuous, expecting version {}, got {}.",
-                next_ver,
-                backup.first_version,
-            );
-
-            if backup.last_version >= start_version {
-                res.push(backup.clone());
+            if backup.last_version < start_version {
+                continue;
+            }
+            match next_ver {
+                Some(next_ver) => ensure!(
+                    backup.first_version == next_ver,
+                    "Transaction backup ranges not continuous, expecting version {}, got {}.",
+                    next_ver,
+                    backup.first_version,
+                ),
+                None => ensure!(
+                    backup.first_version <= start_version,
+                    "Transaction backups start at version {}, after the requested version {}.",
+                    backup.first_version,
+                    start_version,
+                ),
             }
 
-            next_ver = backup.last_version + 1;
+            res.push(backup.clone());
+            next_ver = Some(backup.last_version + 1);
         }
 
         Ok(res)
@@ -70,6 +70,10 @@ pub struct Commands {
     pub list_metadata_files: String,
     /// Command line to backup one metadata file to a metadata backup folder
     pub backup_metadata_file: Option<String>,
+    /// Command line to delete a file, only needed to garbage collect backups.
+    /// input env vars:
+    ///     $FILE_HANDLE returned by `create_for_write`
+    pub delete_file: Option<String>,
 }
 
 #[derive(Clone, Default, Deserialize)]
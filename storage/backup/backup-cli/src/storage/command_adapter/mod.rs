@@ -19,7 +19,7 @@ use crate::{
     },
     utils::error_notes::ErrorNotes,
 };
-use anyhow::{format_err, Result};
+use anyhow::{bail, format_err, Result};
 use async_trait::async_trait;
 use clap::Parser;
 use serde::{Deserialize, Serialize};
@@ -160,6 +160,21 @@ impl BackupStorage for CommandAdapter {
         Ok(())
     }
 
+    async fn delete_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        let cmd = match self.config.commands.delete_file.as_ref() {
+            Some(cmd) => cmd,
+            None => bail!(
+                "delete_file command not defined, can't delete {}.",
+                file_handle
+            ),
+        };
+        let child = self
+            .cmd(cmd, vec![EnvVar::file_handle(file_handle.to_string())])
+            .spawn()?;
+        child.join().await?;
+        Ok(())
+    }
+
     async fn save_metadata_lines(
         &self,
         name: &ShellSafeName,
@@ -22,7 +22,7 @@ use std::{
     str::FromStr,
 };
 use tokio::{
-    fs::{create_dir_all, read_dir, rename, OpenOptions},
+    fs::{create_dir_all, read_dir, remove_file, rename, OpenOptions},
     io::{AsyncRead, AsyncWrite, AsyncWriteExt},
 };
 
@@ -147,6 +147,29 @@ impl BackupStorage for LocalFs {
         Ok(())
     }
 
+    async fn delete_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        let path = self.dir.join(file_handle);
+        remove_file(&path).await.err_notes(&path)?;
+
+        // Remove the backup folder once it's empty, the same way it's created with the first file.
+        if let Some(backup_dir) = path.parent() {
+            if read_dir(backup_dir)
+                .await
+                .err_notes(backup_dir)?
+                .next_entry()
+                .await
+                .err_notes(backup_dir)?
+                .is_none()
+            {
+                tokio::fs::remove_dir(backup_dir)
+                    .await
+                    .err_notes(backup_dir)?;
+            }
+        }
+
+        Ok(())
+    }
+
     async fn save_metadata_lines(
         &self,
         name: &ShellSafeName,
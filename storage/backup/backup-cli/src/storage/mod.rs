@@ -14,7 +14,7 @@ use crate::storage::{
     command_adapter::{CommandAdapter, CommandAdapterOpt},
     local_fs::{LocalFs, LocalFsOpt},
 };
-use anyhow::{ensure, Result};
+use anyhow::{bail, ensure, Result};
 use async_trait::async_trait;
 use clap::{ArgGroup, Parser};
 use once_cell::sync::Lazy;
@@ -178,6 +178,14 @@ pub trait BackupStorage: Send + Sync {
     async fn list_metadata_files(&self) -> Result<Vec<FileHandle>>;
     /// Move a metadata file to the metadata file backup folder.
     async fn backup_metadata_file(&self, file_handle: &FileHandleRef) -> Result<()>;
+    /// Delete a file created by `create_for_write()`. Only needed to garbage collect backups that
+    /// fall out of the retention policy, so a storage doesn't have to support it.
+    async fn delete_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        bail!(
+            "Deleting files is not supported by the storage, can't delete {}.",
+            file_handle
+        )
+    }
     /// Save a vector of metadata lines to file and return the file handle of saved file.
     /// If the file exists, this will overwrite
     async fn save_metadata_lines(
@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 use anyhow::Result;
 use lumio_backup_cli::{
-    coordinators::backup::BackupCompactor, metadata::cache::MetadataCacheOpt,
-    storage::DBToolStorageOpt, utils::ConcurrentDownloadsOpt,
+    coordinators::backup::{BackupCompactor, BackupGarbageCollector},
+    metadata::{cache::MetadataCacheOpt, retention::RetentionPolicy},
+    storage::DBToolStorageOpt,
+    utils::ConcurrentDownloadsOpt,
 };
 use clap::{Parser, Subcommand};
 
@@ -14,6 +16,8 @@ pub enum Command {
     Compact(CompactionOpt),
     #[clap(about = "Cleanup the backup metadata files")]
     Cleanup(CleanupOpt),
+    #[clap(about = "Delete the backups that fall out of the retention policy")]
+    Gc(GcOpt),
 }
 
 #[derive(Parser)]
@@ -48,6 +52,21 @@ pub struct CleanupOpt {
     pub storage: DBToolStorageOpt,
 }
 
+#[derive(Parser)]
+pub struct GcOpt {
+    #[clap(flatten)]
+    pub policy: RetentionPolicy,
+    /// Only print which backups would be kept and which deleted
+    #[clap(long)]
+    pub dry_run: bool,
+    #[clap(flatten)]
+    pub metadata_cache_opt: MetadataCacheOpt,
+    #[clap(flatten)]
+    pub storage: DBToolStorageOpt,
+    #[clap(flatten)]
+    pub concurrent_downloads: ConcurrentDownloadsOpt,
+}
+
 impl Command {
     pub async fn run(self) -> Result<()> {
         match self {
@@ -63,6 +82,17 @@ impl Command {
                 );
                 compactor.run().await?
             },
+            Command::Gc(opt) => {
+                BackupGarbageCollector::new(
+                    opt.policy,
+                    opt.metadata_cache_opt,
+                    opt.storage.init_storage().await?,
+                    opt.concurrent_downloads.get(),
+                    opt.dry_run,
+                )
+                .run()
+                .await?
+            },
             Command::Cleanup(_) => {
                 // TODO: add cleanup logic for removing obsolete metadata files
             },
@@ -9,6 +9,7 @@ mod bootstrap;
 mod gen_replay_verify_jobs;
 mod replay_on_archive;
 mod replay_verify;
+mod replay_with_features;
 pub mod restore;
 #[cfg(test)]
 mod tests;
@@ -41,6 +42,8 @@ pub enum DBTool {
     Restore(restore::Command),
 
     ReplayOnArchive(replay_on_archive::Opt),
+
+    ReplayWithFeatures(replay_with_features::Opt),
 }
 
 impl DBTool {
@@ -58,6 +61,7 @@ impl DBTool {
             DBTool::GenReplayVerifyJobs(cmd) => cmd.run().await,
             DBTool::Restore(cmd) => cmd.run().await,
             DBTool::ReplayOnArchive(cmd) => cmd.run().await,
+            DBTool::ReplayWithFeatures(cmd) => cmd.run().await,
         }
     }
 }
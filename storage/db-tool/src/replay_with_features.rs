@@ -0,0 +1,479 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Result};
+use lumio_backup_cli::utils::{ReplayConcurrencyLevelOpt, RocksdbOpt};
+use lumio_block_executor::txn_provider::default::DefaultTxnProvider;
+use lumio_config::config::{
+    StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use lumio_db::{backup::backup_handler::BackupHandler, LumioDB};
+use lumio_logger::prelude::*;
+use lumio_storage_interface::{
+    state_store::state_view::db_state_view::DbStateViewAtVersion, DbReader,
+};
+use lumio_types::{
+    contract_event::ContractEvent,
+    on_chain_config::{FeatureFlag, Features, GasScheduleV2, OnChainConfig, TimedFeatureOverride},
+    state_store::{
+        state_key::StateKey, state_storage_usage::StateStorageUsage, state_value::StateValue,
+        StateView, StateViewId, StateViewResult, TStateView,
+    },
+    transaction::{
+        signature_verified_transaction::SignatureVerifiedTransaction, AuxiliaryInfo,
+        PersistedAuxiliaryInfo, Transaction, TransactionInfo, TransactionOutput, TransactionStatus,
+        Version,
+    },
+    write_set::WriteSet,
+};
+use lumio_vm::{lumio_vm::LumioVMBlockExecutor, LumioVM, VMBlockExecutor};
+use lumio_vm_environment::prod_configs::{set_paranoid_type_checks, set_timed_feature_override};
+use clap::Parser;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::PathBuf,
+    sync::Arc,
+};
+
+/// Replays a range of committed transactions from a local DB with modified feature flags and gas
+/// feature version, and reports every transaction whose output differs from the committed one.
+///
+/// Each transaction is executed once, on top of the committed state before it, so a mismatch does
+/// not propagate to the following transactions. The VM config is derived from the (overridden)
+/// feature flags and gas feature version like on chain, except for the paranoid type checks which
+/// can be disabled, and the timed features which follow the replay profile. The command fails if
+/// any transaction does not match.
+#[derive(Parser)]
+pub struct Opt {
+    #[clap(long, help = "The first transaction version to replay")]
+    start_version: Version,
+
+    #[clap(long, help = "The last transaction version to replay")]
+    end_version: Version,
+
+    #[clap(flatten)]
+    replay_concurrency_level: ReplayConcurrencyLevelOpt,
+
+    #[clap(long = "target-db-dir", value_parser)]
+    pub db_dir: PathBuf,
+
+    #[clap(flatten)]
+    pub rocksdb_opt: RocksdbOpt,
+
+    #[clap(
+        long,
+        default_value = "500",
+        help = "The number of transactions to be replayed in a chunk"
+    )]
+    pub chunk_size: usize,
+
+    #[clap(
+        long,
+        num_args = 1..,
+        value_delimiter = ' ',
+        help = "List of space-separated feature flags to enable, in capital letters, e.g., \
+                GAS_PAYER_ENABLED"
+    )]
+    enable_features: Vec<FeatureFlag>,
+
+    #[clap(
+        long,
+        num_args = 1..,
+        value_delimiter = ' ',
+        help = "List of space-separated feature flags to disable, in capital letters, e.g., \
+                GAS_PAYER_ENABLED"
+    )]
+    disable_features: Vec<FeatureFlag>,
+
+    #[clap(
+        long,
+        help = "If set, overrides the gas feature version used by the gas schedule"
+    )]
+    gas_feature_version: Option<u64>,
+
+    #[clap(
+        long,
+        help = "If set, the Move VM does not perform paranoid type checks during execution"
+    )]
+    disable_paranoid_type_checks: bool,
+
+    #[clap(
+        long,
+        help = "If set, the mismatches are also saved to this file as JSON"
+    )]
+    output_file: Option<PathBuf>,
+}
+
+impl Opt {
+    pub async fn run(self) -> Result<()> {
+        if self
+            .enable_features
+            .iter()
+            .any(|feature| self.disable_features.contains(feature))
+        {
+            bail!("Enabled and disabled feature flags cannot overlap");
+        }
+        if self.end_version < self.start_version {
+            bail!("End version must not be smaller than the start version");
+        }
+
+        let lumio_db = LumioDB::open(
+            StorageDirPaths::from_path(self.db_dir.as_path()),
+            false,
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            self.rocksdb_opt.clone().into(),
+            false,
+            BUFFERED_STATE_TARGET_ITEMS,
+            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            None,
+        )?;
+        let backup_handler = lumio_db.get_backup_handler();
+        let db = Arc::new(lumio_db) as Arc<dyn DbReader>;
+
+        // Transactions are executed one at a time, in parallel with each other.
+        LumioVM::set_concurrency_level_once(1);
+        set_paranoid_type_checks(!self.disable_paranoid_type_checks);
+        set_timed_feature_override(TimedFeatureOverride::Replay);
+
+        let replayer = Replayer {
+            backup_handler,
+            db,
+            chunk_size: self.chunk_size,
+            concurrency_level: self.replay_concurrency_level.get(),
+            enable_features: self.enable_features,
+            disable_features: self.disable_features,
+            gas_feature_version: self.gas_feature_version,
+        };
+        let mismatches = replayer.replay(
+            self.start_version,
+            self.end_version - self.start_version + 1,
+        )?;
+
+        if let Some(output_file) = &self.output_file {
+            fs::write(output_file, serde_json::to_string_pretty(&mismatches)?)?;
+        }
+        if !mismatches.is_empty() {
+            for mismatch in &mismatches {
+                error!(
+                    "Mismatch at version {}: {}",
+                    mismatch.version,
+                    mismatch.differences.join("; ")
+                );
+            }
+            bail!("{} transactions do not match", mismatches.len());
+        }
+        info!("All replayed transactions match the committed outputs.");
+        Ok(())
+    }
+}
+
+/// A transaction whose replayed output differs from the committed one.
+#[derive(Debug, Serialize)]
+pub struct TransactionMismatch {
+    pub version: Version,
+    pub differences: Vec<String>,
+}
+
+pub(crate) struct Replayer {
+    pub(crate) backup_handler: BackupHandler,
+    pub(crate) db: Arc<dyn DbReader>,
+    pub(crate) chunk_size: usize,
+    pub(crate) concurrency_level: usize,
+    pub(crate) enable_features: Vec<FeatureFlag>,
+    pub(crate) disable_features: Vec<FeatureFlag>,
+    pub(crate) gas_feature_version: Option<u64>,
+}
+
+/// Committed data of a single transaction.
+struct CommittedTransaction {
+    txn: Transaction,
+    persisted_aux_info: PersistedAuxiliaryInfo,
+    txn_info: TransactionInfo,
+    events: Vec<ContractEvent>,
+    write_set: WriteSet,
+}
+
+impl Replayer {
+    pub(crate) fn replay(&self, start: Version, limit: u64) -> Result<Vec<TransactionMismatch>> {
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.concurrency_level)
+            .thread_name(|index| format!("replay-features-{}", index))
+            .build()?;
+        let mut mismatches = vec![];
+        let mut chunk = vec![];
+        let mut chunk_start_version = start;
+        for item in self
+            .backup_handler
+            .get_transaction_iter(start, limit as usize)?
+        {
+            let (txn, persisted_aux_info, txn_info, events, write_set) = item?;
+            // The config overrides are computed at the start of each chunk, so chunks end with
+            // the epoch that may have changed the configs.
+            let is_epoch_ending = events.iter().any(ContractEvent::is_new_epoch_event);
+            chunk.push(CommittedTransaction {
+                txn,
+                persisted_aux_info,
+                txn_info,
+                events,
+                write_set,
+            });
+            if is_epoch_ending || chunk.len() >= self.chunk_size {
+                mismatches.extend(
+                    thread_pool.install(|| self.replay_chunk(chunk_start_version, &chunk))?,
+                );
+                chunk_start_version += chunk.len() as u64;
+                chunk.clear();
+                info!("Replayed up to version {}.", chunk_start_version);
+            }
+        }
+        mismatches.extend(thread_pool.install(|| self.replay_chunk(chunk_start_version, &chunk))?);
+        Ok(mismatches)
+    }
+
+    /// Executes every transaction of the chunk on top of the committed state before it, i.e. the
+    /// state before the chunk with the committed write sets of the preceding transactions.
+    fn replay_chunk(
+        &self,
+        first_version: Version,
+        chunk: &[CommittedTransaction],
+    ) -> Result<Vec<TransactionMismatch>> {
+        let base_view = self
+            .db
+            .state_view_at_version(first_version.checked_sub(1))?;
+        let overrides = self.state_overrides(&base_view)?;
+        let committed_writes = CommittedWrites::new(chunk);
+
+        let outputs = chunk
+            .par_iter()
+            .enumerate()
+            .map(|(index, committed)| {
+                let state_view = StateViewWithOverrides {
+                    base_view: CommittedStateView {
+                        base_view: &base_view,
+                        committed_writes: &committed_writes,
+                        index,
+                    },
+                    overrides: overrides.clone(),
+                };
+                execute(committed, &state_view)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(outputs
+            .iter()
+            .zip(chunk)
+            .enumerate()
+            .filter_map(|(index, (output, committed))| {
+                let differences = compare_output(output, committed);
+                (!differences.is_empty()).then(|| TransactionMismatch {
+                    version: first_version + index as u64,
+                    differences,
+                })
+            })
+            .collect())
+    }
+
+    fn state_overrides(
+        &self,
+        state_view: &impl StateView,
+    ) -> Result<HashMap<StateKey, StateValue>> {
+        let mut overrides = HashMap::new();
+        if !self.enable_features.is_empty() || !self.disable_features.is_empty() {
+            let (state_key, state_value) =
+                override_config::<Features, _>(state_view, |features| {
+                    for feature in &self.enable_features {
+                        features.enable(*feature);
+                    }
+                    for feature in &self.disable_features {
+                        features.disable(*feature);
+                    }
+                })?;
+            overrides.insert(state_key, state_value);
+        }
+        if let Some(gas_feature_version) = self.gas_feature_version {
+            let (state_key, state_value) =
+                override_config::<GasScheduleV2, _>(state_view, |gas_schedule| {
+                    gas_schedule.feature_version = gas_feature_version;
+                })?;
+            overrides.insert(state_key, state_value);
+        }
+        Ok(overrides)
+    }
+}
+
+/// Executes a single committed transaction on top of the given state.
+fn execute(
+    committed: &CommittedTransaction,
+    state_view: &(impl StateView + Sync),
+) -> Result<TransactionOutput> {
+    let txns_provider = DefaultTxnProvider::new(
+        vec![SignatureVerifiedTransaction::from(committed.txn.clone())],
+        vec![AuxiliaryInfo::new(committed.persisted_aux_info, None)],
+    );
+    let mut outputs =
+        LumioVMBlockExecutor::new().execute_block_no_limit(&txns_provider, state_view)?;
+    assert_eq!(outputs.len(), 1);
+    Ok(outputs.remove(0))
+}
+
+/// Modifies an on-chain config stored in the state view.
+pub(crate) fn override_config<T: OnChainConfig + Serialize, F: FnOnce(&mut T)>(
+    state_view: &impl StateView,
+    override_func: F,
+) -> Result<(StateKey, StateValue)> {
+    let state_key = StateKey::resource(T::address(), &T::struct_tag())?;
+    let Some(state_value) = state_view.get_state_value(&state_key)? else {
+        bail!("On-chain config {:?} does not exist", state_key);
+    };
+    let mut config = T::deserialize_into_config(state_value.bytes())?;
+    override_func(&mut config);
+    let bytes = bcs::to_bytes(&config)?;
+    Ok((state_key, state_value.map_bytes(|_| Ok(bytes.into()))?))
+}
+
+/// Compares the replayed output with the committed transaction. Status and gas are checked against
+/// the `TransactionInfo`, write set and events against the committed ones, which are authenticated
+/// by it.
+fn compare_output(output: &TransactionOutput, committed: &CommittedTransaction) -> Vec<String> {
+    let mut differences = vec![];
+
+    let expected_status: TransactionStatus = committed.txn_info.status().clone().into();
+    if output.status() != &expected_status {
+        differences.push(format!(
+            "status: {:?}, expected: {:?}",
+            output.status(),
+            expected_status
+        ));
+    }
+    if output.gas_used() != committed.txn_info.gas_used() {
+        differences.push(format!(
+            "gas used: {}, expected: {}",
+            output.gas_used(),
+            committed.txn_info.gas_used()
+        ));
+    }
+
+    let expected_writes = committed
+        .write_set
+        .write_op_iter()
+        .collect::<HashMap<_, _>>();
+    let writes = output
+        .write_set()
+        .write_op_iter()
+        .collect::<HashMap<_, _>>();
+    let differing_keys = expected_writes
+        .keys()
+        .chain(writes.keys())
+        .filter(|key| expected_writes.get(*key) != writes.get(*key))
+        .map(|key| format!("{:?}", key))
+        .collect::<BTreeSet<_>>();
+    if !differing_keys.is_empty() {
+        differences.push(format!(
+            "write set differs at {} keys: {:?}",
+            differing_keys.len(),
+            differing_keys
+        ));
+    }
+
+    if output.events() != committed.events.as_slice() {
+        differences.push(format!(
+            "events: {} emitted, expected {}",
+            output.events().len(),
+            committed.events.len()
+        ));
+    }
+    differences
+}
+
+/// The committed writes of a chunk, by state key: the index of each transaction writing the key
+/// (in increasing order), and the value it left behind (`None` for deletions).
+struct CommittedWrites(HashMap<StateKey, Vec<(usize, Option<StateValue>)>>);
+
+impl CommittedWrites {
+    fn new(chunk: &[CommittedTransaction]) -> Self {
+        let mut writes: HashMap<_, Vec<_>> = HashMap::new();
+        for (index, committed) in chunk.iter().enumerate() {
+            for (state_key, write_op) in committed.write_set.write_op_iter() {
+                writes
+                    .entry(state_key.clone())
+                    .or_default()
+                    .push((index, write_op.as_state_value_opt().cloned()));
+            }
+        }
+        Self(writes)
+    }
+
+    /// Returns the value of the key after the transactions before the given index, if any of
+    /// them wrote it.
+    fn get_before(&self, state_key: &StateKey, index: usize) -> Option<Option<StateValue>> {
+        let writes = self.0.get(state_key)?;
+        let num_preceding = writes.partition_point(|(write_index, _)| *write_index < index);
+        num_preceding
+            .checked_sub(1)
+            .map(|last_preceding| writes[last_preceding].1.clone())
+    }
+}
+
+/// The committed state before the transaction at `index` of a chunk.
+struct CommittedStateView<'a, S> {
+    /// The state before the chunk.
+    base_view: &'a S,
+    committed_writes: &'a CommittedWrites,
+    index: usize,
+}
+
+impl<S: StateView> TStateView for CommittedStateView<'_, S> {
+    type Key = StateKey;
+
+    fn id(&self) -> StateViewId {
+        self.base_view.id()
+    }
+
+    fn get_usage(&self) -> StateViewResult<StateStorageUsage> {
+        self.base_view.get_usage()
+    }
+
+    fn next_version(&self) -> Version {
+        self.base_view.next_version() + self.index as Version
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> StateViewResult<Option<StateValue>> {
+        match self.committed_writes.get_before(state_key, self.index) {
+            Some(state_value) => Ok(state_value),
+            None => self.base_view.get_state_value(state_key),
+        }
+    }
+}
+
+/// A state view that shadows some of the state values of the underlying view.
+pub(crate) struct StateViewWithOverrides<S> {
+    pub(crate) base_view: S,
+    pub(crate) overrides: HashMap<StateKey, StateValue>,
+}
+
+impl<S: StateView> TStateView for StateViewWithOverrides<S> {
+    type Key = StateKey;
+
+    fn id(&self) -> StateViewId {
+        self.base_view.id()
+    }
+
+    fn get_usage(&self) -> StateViewResult<StateStorageUsage> {
+        self.base_view.get_usage()
+    }
+
+    fn next_version(&self) -> Version {
+        self.base_view.next_version()
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> StateViewResult<Option<StateValue>> {
+        match self.overrides.get(state_key) {
+            Some(state_value) => Ok(Some(state_value.clone())),
+            None => self.base_view.get_state_value(state_key),
+        }
+    }
+}
@@ -72,7 +72,10 @@ fn run_cmd(args: &[&str]) {
 
 #[cfg(test)]
 mod dbtool_tests {
-    use crate::DBTool;
+    use crate::{
+        replay_with_features::{override_config, Replayer, StateViewWithOverrides},
+        DBTool,
+    };
     use lumio_backup_cli::{
         coordinators::backup::BackupCompactor,
         metadata,
@@ -84,14 +87,21 @@ mod dbtool_tests {
     use lumio_executor_test_helpers::integration_test_impl::{
         test_execution_with_storage_impl, test_execution_with_storage_impl_inner,
     };
-    use lumio_storage_interface::DbReader;
+    use lumio_storage_interface::{
+        state_store::state_view::db_state_view::DbStateViewAtVersion, DbReader,
+    };
     use lumio_temppath::TempPath;
     use lumio_types::{
-        state_store::state_key::{inner::StateKeyTag::AccessPath, prefix::StateKeyPrefix},
+        on_chain_config::{FeatureFlag, Features, GasScheduleV2, OnChainConfig, ValidatorSet},
+        state_store::{
+            state_key::{inner::StateKeyTag::AccessPath, prefix::StateKeyPrefix, StateKey},
+            TStateView,
+        },
         transaction::Version,
     };
     use clap::Parser;
     use std::{
+        collections::HashMap,
         default::Default,
         fs,
         ops::Deref,
@@ -597,6 +607,119 @@ mod dbtool_tests {
         rt.shutdown_timeout(Duration::from_secs(1));
     }
 
+    fn replayer(
+        db: &Arc<LumioDB>,
+        enable_features: Vec<FeatureFlag>,
+        disable_features: Vec<FeatureFlag>,
+        gas_feature_version: Option<u64>,
+    ) -> Replayer {
+        Replayer {
+            backup_handler: db.get_backup_handler(),
+            db: Arc::clone(db) as Arc<dyn DbReader>,
+            chunk_size: 5,
+            concurrency_level: 2,
+            enable_features,
+            disable_features,
+            gas_feature_version,
+        }
+    }
+
+    #[test]
+    fn test_replay_with_features_without_overrides() {
+        let db = test_execution_with_storage_impl();
+        let latest_version = db.expect_synced_version();
+
+        // Without any overrides, every committed transaction must be reproduced exactly.
+        let mismatches = replayer(&db, vec![], vec![], None)
+            .replay(0, latest_version + 1)
+            .unwrap();
+        assert!(
+            mismatches.is_empty(),
+            "Unexpected mismatches: {:?}",
+            mismatches
+        );
+    }
+
+    #[test]
+    fn test_replay_with_features_reports_mismatch() {
+        let db = test_execution_with_storage_impl();
+        let latest_version = db.expect_synced_version();
+
+        // Version 0 is genesis, and version 1 is the first block metadata. The first user
+        // transaction creates an account, which registers a coin store instead of a fungible
+        // store if the feature is disabled.
+        let mismatches = replayer(
+            &db,
+            vec![],
+            vec![FeatureFlag::NEW_ACCOUNTS_DEFAULT_TO_FA_LUM_STORE],
+            None,
+        )
+        .replay(0, latest_version + 1)
+        .unwrap();
+        let first_mismatch = mismatches
+            .first()
+            .expect("Feature override must cause mismatches");
+        assert_eq!(first_mismatch.version, 2);
+        assert!(first_mismatch
+            .differences
+            .iter()
+            .any(|difference| difference.starts_with("write set differs")));
+    }
+
+    #[test]
+    fn test_state_view_with_overrides() {
+        let db = test_execution_with_storage_impl() as Arc<dyn DbReader>;
+        let base_view = db
+            .state_view_at_version(Some(db.expect_synced_version()))
+            .unwrap();
+
+        let base_features = Features::fetch_config(&base_view).unwrap();
+        let base_gas_schedule = GasScheduleV2::fetch_config(&base_view).unwrap();
+        assert!(base_features.is_enabled(FeatureFlag::NEW_ACCOUNTS_DEFAULT_TO_FA_LUM_STORE));
+
+        let mut overrides = HashMap::new();
+        let (state_key, state_value) = override_config::<Features, _>(&base_view, |features| {
+            features.disable(FeatureFlag::NEW_ACCOUNTS_DEFAULT_TO_FA_LUM_STORE);
+        })
+        .unwrap();
+        overrides.insert(state_key, state_value);
+        let (state_key, state_value) =
+            override_config::<GasScheduleV2, _>(&base_view, |gas_schedule| {
+                gas_schedule.feature_version = base_gas_schedule.feature_version + 1;
+            })
+            .unwrap();
+        overrides.insert(state_key, state_value);
+
+        let state_view = StateViewWithOverrides {
+            base_view,
+            overrides,
+        };
+
+        // Overridden configs shadow the ones in the underlying state.
+        let features = Features::fetch_config(&state_view).unwrap();
+        assert!(!features.is_enabled(FeatureFlag::NEW_ACCOUNTS_DEFAULT_TO_FA_LUM_STORE));
+        for flag in [
+            FeatureFlag::CODE_DEPENDENCY_CHECK,
+            FeatureFlag::VM_BINARY_FORMAT_V6,
+        ] {
+            assert_eq!(features.is_enabled(flag), base_features.is_enabled(flag));
+        }
+        let gas_schedule = GasScheduleV2::fetch_config(&state_view).unwrap();
+        assert_eq!(
+            gas_schedule.feature_version,
+            base_gas_schedule.feature_version + 1
+        );
+        assert_eq!(gas_schedule.entries, base_gas_schedule.entries);
+
+        // Other state values are read from the underlying state.
+        let base_view = &state_view.base_view;
+        let state_key = StateKey::on_chain_config::<ValidatorSet>().unwrap();
+        assert_eq!(
+            state_view.get_state_value(&state_key).unwrap(),
+            base_view.get_state_value(&state_key).unwrap()
+        );
+    }
+
     fn dir_size<P: AsRef<Path>>(path: P) -> u64 {
         let mut size = 0;
 
@@ -19,7 +19,7 @@ use lumio_config::config::{
     DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
 };
 use lumio_crypto::{hash::CryptoHash, HashValue};
-use lumio_storage_interface::{DbReader, Order};
+use lumio_storage_interface::{DbReader, DbWriter, Order};
 use lumio_temppath::TempPath;
 use lumio_types::{
     ledger_info::LedgerInfoWithSignatures,
@@ -40,12 +40,35 @@ proptest! {
 
     #[test]
     fn test_save_blocks(input in arb_blocks_to_commit(), threshold in 10..20usize) {
-        test_save_blocks_impl(input, threshold);
+        test_save_blocks_impl(input, threshold, false /* use_in_memory_backend */);
+    }
+
+    #[test]
+    fn test_save_blocks_in_memory(input in arb_blocks_to_commit(), threshold in 10..20usize) {
+        test_save_blocks_impl(input, threshold, true /* use_in_memory_backend */);
     }
 
     #[test]
     fn test_sync_transactions(input in arb_blocks_to_commit(), threshold in 10..20usize) {
-        test_sync_transactions_impl(input, threshold);
+        test_sync_transactions_impl(input, threshold, false /* use_in_memory_backend */);
+    }
+
+    #[test]
+    fn test_sync_transactions_in_memory(
+        input in arb_blocks_to_commit(),
+        threshold in 10..20usize,
+    ) {
+        test_sync_transactions_impl(input, threshold, true /* use_in_memory_backend */);
+    }
+
+    #[test]
+    fn test_create_online_checkpoint(input in arb_blocks_to_commit()) {
+        test_create_online_checkpoint_impl(input, false /* use_in_memory_backend */);
+    }
+
+    #[test]
+    fn test_create_online_checkpoint_in_memory(input in arb_blocks_to_commit()) {
+        test_create_online_checkpoint_impl(input, true /* use_in_memory_backend */);
     }
 }
 
@@ -225,6 +248,52 @@ fn test_get_latest_ledger_summary() {
     assert_eq!(bootstrapped.state_summary.root_hash(), state_hash);
 }
 
+fn test_create_online_checkpoint_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+    use_in_memory_backend: bool,
+) {
+    let open_db = |path: &TempPath| {
+        LumioDB::new_for_test_with_backend(
+            path,
+            BUFFERED_STATE_TARGET_ITEMS_FOR_TEST,
+            use_in_memory_backend,
+        )
+    };
+    let tmp_dir = TempPath::new();
+    let db = open_db(&tmp_dir);
+
+    let mut cur_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+        db.save_transactions_for_test(
+            txns_to_commit,
+            cur_ver, /* first_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    let latest_ledger_info = input.last().unwrap().1.clone();
+
+    let cp_dir = TempPath::new();
+    cp_dir.create_as_dir().unwrap();
+    let mut progress = vec![];
+    let version = db
+        .create_online_checkpoint(cp_dir.path(), &mut |db_name| {
+            progress.push(db_name.to_string())
+        })
+        .unwrap();
+    assert_eq!(version, latest_ledger_info.ledger_info().version());
+    assert_eq!(progress, vec!["ledger_db", "state_merkle_db"]);
+
+    // The original DB stays usable, and the checkpoint opens at the reported version.
+    assert_eq!(db.get_synced_version().unwrap(), Some(version));
+    drop(db);
+    let cp_db = open_db(&cp_dir);
+    assert_eq!(cp_db.get_synced_version().unwrap(), Some(version));
+    assert_eq!(cp_db.get_latest_ledger_info().unwrap(), latest_ledger_info);
+}
+
 pub fn test_state_merkle_pruning_impl(
     input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
 ) {
@@ -94,14 +94,44 @@ impl LumioDB {
         db_root_path: P,
         buffered_state_target_items: usize,
     ) -> Self {
-        Self::new_without_pruner(
-            db_root_path,
+        Self::new_for_test_with_backend(db_root_path, buffered_state_target_items, false)
+    }
+
+    /// Same as `new_for_test`, but keeps the data in process memory instead of RocksDB. Reopening
+    /// the same path in memory gives the data back, as long as the directory is not removed.
+    pub fn new_for_test_in_memory<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
+        Self::new_for_test_with_backend(db_root_path, BUFFERED_STATE_TARGET_ITEMS_FOR_TEST, true)
+    }
+
+    /// This opens db in non-readonly mode, without the pruner, on RocksDB or in memory.
+    pub fn new_for_test_with_backend<P: AsRef<Path> + Clone>(
+        db_root_path: P,
+        buffered_state_target_items: usize,
+        use_in_memory_backend: bool,
+    ) -> Self {
+        let mut db_config = RocksdbConfigs {
+            enable_storage_sharding: false,
+            ..Default::default()
+        };
+        for rocksdb_config in [
+            &mut db_config.ledger_db_config,
+            &mut db_config.state_merkle_db_config,
+            &mut db_config.state_kv_db_config,
+            &mut db_config.index_db_config,
+        ] {
+            rocksdb_config.use_in_memory_backend = use_in_memory_backend;
+        }
+        Self::open(
+            StorageDirPaths::from_path(db_root_path),
             false,
+            NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
+            db_config,
+            false, /* indexer */
             buffered_state_target_items,
             DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
-            false, /* indexer */
-            false,
+            None,
         )
+        .expect("Unable to open LumioDB")
     }
 
     /// This opens db in non-readonly mode, without the pruner.
@@ -7,7 +7,7 @@ use crate::{
     ledger_db::{
         ledger_metadata_db::LedgerMetadataDb,
         transaction_auxiliary_data_db::TransactionAuxiliaryDataDb,
-        transaction_info_db::TransactionInfoDb, LedgerDbSchemaBatches,
+        transaction_info_db::TransactionInfoDb, LedgerDb, LedgerDbSchemaBatches,
     },
     metrics::{
         COMMITTED_TXNS, LATEST_TXN_VERSION, LEDGER_VERSION, NEXT_BLOCK_EPOCH, OTHER_TIMERS_SECONDS,
@@ -18,8 +18,10 @@ use crate::{
         transaction_accumulator_root_hash::TransactionAccumulatorRootHashSchema,
     },
 };
+use lumio_config::config::RocksdbConfigs;
 use lumio_crypto::HashValue;
 use lumio_experimental_runtimes::thread_manager::THREAD_MANAGER;
+use lumio_logger::prelude::*;
 use lumio_metrics_core::TimerHelper;
 use lumio_schemadb::batch::SchemaBatch;
 use lumio_storage_interface::{
@@ -38,7 +40,7 @@ use lumio_types::{
 };
 use itertools::Itertools;
 use rayon::prelude::*;
-use std::{iter::Iterator, time::Instant};
+use std::{iter::Iterator, path::Path, time::Instant};
 
 impl DbWriter for LumioDB {
     fn pre_commit_ledger(&self, chunk: ChunkToCommit, sync_commit: bool) -> Result<()> {
@@ -239,6 +241,61 @@ impl DbWriter for LumioDB {
             Ok(())
         })
     }
+
+    fn create_online_checkpoint(
+        &self,
+        cp_root_path: &Path,
+        on_progress: &mut dyn FnMut(&str),
+    ) -> Result<Version> {
+        gauged_api("create_online_checkpoint", || {
+            let start = Instant::now();
+            let sharding = self.ledger_db.enable_storage_sharding();
+            info!(
+                sharding = sharding,
+                cp_path = cp_root_path,
+                "Creating online checkpoint for LumioDB."
+            );
+
+            // Commits keep going while we checkpoint, so the order matters: the ledger metadata db
+            // (holding the overall commit progress) goes first, and everything checkpointed after
+            // it is at or beyond that progress, to be truncated back to it when the checkpoint is
+            // opened.
+            on_progress("ledger_db");
+            self.ledger_db.create_checkpoint_from_opened(cp_root_path)?;
+            if sharding {
+                on_progress("state_kv_db");
+                self.state_kv_db
+                    .create_checkpoint_from_opened(cp_root_path)?;
+            }
+            on_progress("state_merkle_db");
+            self.state_store
+                .state_merkle_db
+                .create_checkpoint_from_opened(cp_root_path)?;
+
+            let cp_ledger_db = LedgerDb::new(
+                cp_root_path,
+                RocksdbConfigs {
+                    enable_storage_sharding: sharding,
+                    ..Default::default()
+                },
+                /*readonly=*/ true,
+            )?;
+            let version = cp_ledger_db
+                .metadata_db()
+                .get_synced_version()?
+                .ok_or_else(|| {
+                    LumioDbError::NotFound("No OverallCommitProgress in checkpoint.".to_string())
+                })?;
+
+            info!(
+                cp_path = cp_root_path,
+                version = version,
+                time_ms = %start.elapsed().as_millis(),
+                "Made online LumioDB checkpoint."
+            );
+            Ok(version)
+        })
+    }
 }
 
 impl LumioDB {
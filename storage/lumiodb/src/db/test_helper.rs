@@ -323,10 +323,14 @@ fn gen_snapshot_version(
 pub fn test_save_blocks_impl(
     input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
     snapshot_size_threshold: usize,
+    use_in_memory_backend: bool,
 ) {
     let tmp_dir = TempPath::new();
-    let db =
-        LumioDB::new_for_test_with_buffered_state_target_items(&tmp_dir, snapshot_size_threshold);
+    let db = LumioDB::new_for_test_with_backend(
+        &tmp_dir,
+        snapshot_size_threshold,
+        use_in_memory_backend,
+    );
 
     let num_batches = input.len();
     let mut cur_ver: Version = 0;
@@ -966,10 +970,14 @@ pub fn put_transaction_auxiliary_data(
 pub fn test_sync_transactions_impl(
     input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
     snapshot_size_threshold: usize,
+    use_in_memory_backend: bool,
 ) {
     let tmp_dir = TempPath::new();
-    let db =
-        LumioDB::new_for_test_with_buffered_state_target_items(&tmp_dir, snapshot_size_threshold);
+    let db = LumioDB::new_for_test_with_backend(
+        &tmp_dir,
+        snapshot_size_threshold,
+        use_in_memory_backend,
+    );
 
     let num_batches = input.len();
     let mut cur_ver: Version = 0;
@@ -0,0 +1,48 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::db_debugger::common::DbDir;
+use lumio_storage_interface::{db_ensure as ensure, LumioDbError, Result};
+use lumio_types::transaction::{Transaction, Version};
+use clap::Parser;
+use std::{fs, path::PathBuf};
+
+#[derive(Parser)]
+#[clap(about = "Export a range of transactions as a BCS-serialized `Vec<Transaction>`.")]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    start_version: Version,
+
+    num_versions: usize,
+
+    #[clap(long, value_parser)]
+    output_file: PathBuf,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let ledger_db = self.db_dir.open_ledger_db()?;
+        let transactions = ledger_db
+            .transaction_db()
+            .get_transaction_iter(self.start_version, self.num_versions)?
+            .collect::<Result<Vec<Transaction>>>()?;
+        ensure!(
+            transactions.len() == self.num_versions,
+            "Expected {} transactions, found {}",
+            self.num_versions,
+            transactions.len(),
+        );
+
+        fs::write(&self.output_file, bcs::to_bytes(&transactions)?)?;
+        println!(
+            "Exported transactions [{}, {}) to {}.",
+            self.start_version,
+            self.start_version + self.num_versions as u64,
+            self.output_file.display()
+        );
+
+        Ok(())
+    }
+}
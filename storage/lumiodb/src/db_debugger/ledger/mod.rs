@@ -3,6 +3,7 @@
 
 mod check_range_proof;
 mod check_txn_info_hashes;
+mod export_transactions;
 
 use lumio_storage_interface::Result;
 
@@ -11,6 +12,7 @@ use lumio_storage_interface::Result;
 pub enum Cmd {
     CheckTransactionInfoHashes(check_txn_info_hashes::Cmd),
     CheckRangeProof(check_range_proof::Cmd),
+    ExportTransactions(export_transactions::Cmd),
 }
 
 impl Cmd {
@@ -18,6 +20,7 @@ impl Cmd {
         match self {
             Self::CheckTransactionInfoHashes(cmd) => cmd.run(),
             Self::CheckRangeProof(cmd) => cmd.run(),
+            Self::ExportTransactions(cmd) => cmd.run(),
         }
     }
 }
@@ -186,11 +186,14 @@ where
     cfds
 }
 
-fn with_state_key_extractor_processor(cf_name: ColumnFamilyName, cf_opts: &mut Options) {
-    if cf_name == STATE_VALUE_CF_NAME
+fn is_prefixed_by_state_key(cf_name: &str) -> bool {
+    cf_name == STATE_VALUE_CF_NAME
         || cf_name == STATE_VALUE_BY_KEY_HASH_CF_NAME
         || cf_name == HOT_STATE_VALUE_BY_KEY_HASH_CF_NAME
-    {
+}
+
+fn with_state_key_extractor_processor(cf_name: ColumnFamilyName, cf_opts: &mut Options) {
+    if is_prefixed_by_state_key(cf_name) {
         let prefix_extractor =
             SliceTransform::create("state_key_extractor", state_key_extractor, None);
         cf_opts.set_prefix_extractor(prefix_extractor);
@@ -201,6 +204,16 @@ fn state_key_extractor(state_value_raw_key: &[u8]) -> &[u8] {
     &state_value_raw_key[..(state_value_raw_key.len() - VERSION_SIZE)]
 }
 
+/// Prefix extractors for DBs opened with `RocksdbConfig::use_in_memory_backend`, which don't get
+/// them from the column family options.
+#[cfg(any(test, feature = "fuzzing"))]
+pub(super) fn in_memory_prefix_extractor(
+    cf_name: &str,
+) -> Option<lumio_schemadb::backend::PrefixExtractor> {
+    is_prefixed_by_state_key(cf_name)
+        .then_some(state_key_extractor as lumio_schemadb::backend::PrefixExtractor)
+}
+
 pub(super) fn gen_event_cfds(
     rocksdb_config: &RocksdbConfig,
     block_cache: Option<&Cache>,
@@ -16,7 +16,7 @@ use lumio_types::{
     transaction::{TransactionOutputListWithProofV2, Version},
 };
 use either::Either;
-use std::{sync::Arc, time::Instant};
+use std::{path::Path, sync::Arc, time::Instant};
 use tokio::sync::watch::Sender;
 pub const SECONDARY_DB_DIR: &str = "fast_sync_secondary";
 
@@ -181,6 +181,15 @@ impl DbWriter for FastSyncStorageWrapper {
         self.get_lumio_db_write_ref()
             .commit_ledger(version, ledger_info_with_sigs, chunk_opt)
     }
+
+    fn create_online_checkpoint(
+        &self,
+        cp_root_path: &Path,
+        on_progress: &mut dyn FnMut(&str),
+    ) -> Result<Version> {
+        self.get_lumio_db_write_ref()
+            .create_online_checkpoint(cp_root_path, on_progress)
+    }
 }
 
 impl DbReader for FastSyncStorageWrapper {
@@ -311,6 +311,16 @@ impl LedgerDb {
             ..Default::default()
         };
         let ledger_db = Self::new(db_root_path, rocksdb_configs, /*readonly=*/ false)?;
+        ledger_db.create_checkpoint_from_opened(cp_root_path)
+    }
+
+    /// Creates a checkpoint of an already opened ledger db. The metadata db goes first, so the
+    /// commit progress recorded in the checkpoint never runs ahead of the data in the other dbs.
+    pub(crate) fn create_checkpoint_from_opened(
+        &self,
+        cp_root_path: impl AsRef<Path>,
+    ) -> Result<()> {
+        let sharding = self.enable_storage_sharding;
         let cp_ledger_db_folder = cp_root_path.as_ref().join(LEDGER_DB_FOLDER_NAME);
 
         info!(
@@ -323,31 +333,23 @@ impl LedgerDb {
             std::fs::create_dir_all(&cp_ledger_db_folder).unwrap_or(());
         }
 
-        ledger_db
-            .metadata_db()
+        self.metadata_db()
             .create_checkpoint(Self::metadata_db_path(cp_root_path.as_ref(), sharding))?;
 
         if sharding {
-            ledger_db
-                .event_db()
+            self.event_db()
                 .create_checkpoint(cp_ledger_db_folder.join(EVENT_DB_NAME))?;
-            ledger_db
-                .persisted_auxiliary_info_db()
+            self.persisted_auxiliary_info_db()
                 .create_checkpoint(cp_ledger_db_folder.join(PERSISTED_AUXILIARY_INFO_DB_NAME))?;
-            ledger_db
-                .transaction_accumulator_db()
+            self.transaction_accumulator_db()
                 .create_checkpoint(cp_ledger_db_folder.join(TRANSACTION_ACCUMULATOR_DB_NAME))?;
-            ledger_db
-                .transaction_auxiliary_data_db()
+            self.transaction_auxiliary_data_db()
                 .create_checkpoint(cp_ledger_db_folder.join(TRANSACTION_AUXILIARY_DATA_DB_NAME))?;
-            ledger_db
-                .transaction_db()
+            self.transaction_db()
                 .create_checkpoint(cp_ledger_db_folder.join(TRANSACTION_DB_NAME))?;
-            ledger_db
-                .transaction_info_db()
+            self.transaction_info_db()
                 .create_checkpoint(cp_ledger_db_folder.join(TRANSACTION_INFO_DB_NAME))?;
-            ledger_db
-                .write_set_db()
+            self.write_set_db()
                 .create_checkpoint(cp_ledger_db_folder.join(WRITE_SET_DB_NAME))?;
         }
 
@@ -446,6 +448,16 @@ impl LedgerDb {
         block_cache: &Cache,
         readonly: bool,
     ) -> Result<DB> {
+        #[cfg(any(test, feature = "fuzzing"))]
+        if db_config.use_in_memory_backend && !readonly {
+            return Ok(DB::open_cf_in_memory(
+                path,
+                name,
+                Self::gen_cfds_by_name(db_config, block_cache, name),
+                crate::db_options::in_memory_prefix_extractor,
+            )?);
+        }
+
         let db = if readonly {
             DB::open_cf_readonly(
                 &gen_rocksdb_options(db_config, true),
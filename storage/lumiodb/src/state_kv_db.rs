@@ -229,6 +229,14 @@ impl StateKvDb {
             None,
             false,
         )?;
+        state_kv_db.create_checkpoint_from_opened(cp_root_path)
+    }
+
+    /// Creates a checkpoint of an already opened (sharded) state kv db.
+    pub(crate) fn create_checkpoint_from_opened(
+        &self,
+        cp_root_path: impl AsRef<Path>,
+    ) -> Result<()> {
         let cp_state_kv_db_path = cp_root_path.as_ref().join(STATE_KV_DB_FOLDER_NAME);
 
         info!("Creating state_kv_db checkpoint at: {cp_state_kv_db_path:?}");
@@ -236,14 +244,12 @@ impl StateKvDb {
         std::fs::remove_dir_all(&cp_state_kv_db_path).unwrap_or(());
         std::fs::create_dir_all(&cp_state_kv_db_path).unwrap_or(());
 
-        state_kv_db
-            .metadata_db()
+        self.metadata_db()
             .create_checkpoint(Self::metadata_db_path(cp_root_path.as_ref()))?;
 
         // TODO(HotState): should handle hot state as well.
         for shard_id in 0..NUM_STATE_SHARDS {
-            state_kv_db
-                .db_shard(shard_id)
+            self.db_shard(shard_id)
                 .create_checkpoint(Self::db_shard_path(
                     cp_root_path.as_ref(),
                     shard_id,
@@ -330,17 +336,27 @@ impl StateKvDb {
         readonly: bool,
         is_hot: bool,
     ) -> Result<DB> {
+        let cfds = if is_hot {
+            gen_hot_state_kv_shard_cfds
+        } else {
+            gen_state_kv_shard_cfds
+        }(state_kv_db_config, block_cache);
+        #[cfg(any(test, feature = "fuzzing"))]
+        if state_kv_db_config.use_in_memory_backend && !readonly {
+            return DB::open_cf_in_memory(
+                path,
+                name,
+                cfds,
+                crate::db_options::in_memory_prefix_extractor,
+            );
+        }
+
         let open_func = if readonly {
             DB::open_cf_readonly
         } else {
             DB::open_cf
         };
         let rocksdb_opts = gen_rocksdb_options(state_kv_db_config, readonly);
-        let cfds = if is_hot {
-            gen_hot_state_kv_shard_cfds
-        } else {
-            gen_state_kv_shard_cfds
-        }(state_kv_db_config, block_cache);
 
         open_func(&rocksdb_opts, path, name, cfds)
     }
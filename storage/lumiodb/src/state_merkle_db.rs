@@ -180,6 +180,15 @@ impl StateMerkleDb {
             /*readonly=*/ false,
             /*max_nodes_per_lru_cache_shard=*/ 0,
         )?;
+        state_merkle_db.create_checkpoint_from_opened(cp_root_path)
+    }
+
+    /// Creates a checkpoint of an already opened state merkle db.
+    pub(crate) fn create_checkpoint_from_opened(
+        &self,
+        cp_root_path: impl AsRef<Path>,
+    ) -> Result<()> {
+        let sharding = self.enable_sharding;
         let cp_state_merkle_db_path = cp_root_path.as_ref().join(STATE_MERKLE_DB_FOLDER_NAME);
 
         info!("Creating state_merkle_db checkpoint at: {cp_state_merkle_db_path:?}");
@@ -189,14 +198,12 @@ impl StateMerkleDb {
             std::fs::create_dir_all(&cp_state_merkle_db_path).unwrap_or(());
         }
 
-        state_merkle_db
-            .metadata_db()
+        self.metadata_db()
             .create_checkpoint(Self::metadata_db_path(cp_root_path.as_ref(), sharding))?;
 
         if sharding {
             for shard_id in 0..NUM_STATE_SHARDS {
-                state_merkle_db
-                    .db_shard(shard_id)
+                self.db_shard(shard_id)
                     .create_checkpoint(Self::db_shard_path(cp_root_path.as_ref(), shard_id))?;
             }
         }
@@ -648,6 +655,16 @@ impl StateMerkleDb {
         block_cache: &Cache,
         readonly: bool,
     ) -> Result<DB> {
+        #[cfg(any(test, feature = "fuzzing"))]
+        if state_merkle_db_config.use_in_memory_backend && !readonly {
+            return DB::open_cf_in_memory(
+                path,
+                name,
+                gen_state_merkle_cfds(state_merkle_db_config, Some(block_cache)),
+                crate::db_options::in_memory_prefix_extractor,
+            );
+        }
+
         Ok(if readonly {
             DB::open_cf_readonly(
                 &gen_rocksdb_options(state_merkle_db_config, true),
@@ -0,0 +1,348 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backend::{Backend, BackendBatch, RawIterator, ReadOptions},
+    batch::WriteOp,
+    ColumnFamilyName,
+};
+use lumio_infallible::{Mutex, MutexGuard, RwLock};
+use lumio_storage_interface::{LumioDbError, Result as DbResult};
+use im::OrdMap;
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    fs,
+    ops::Bound,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Same as the prefix extractor of a RocksDB column family: maps a key to its prefix, which is
+/// what `ReadOptions::set_prefix_same_as_start()` compares.
+pub type PrefixExtractor = fn(&[u8]) -> &[u8];
+
+/// Persistent maps share structure between versions, so iterators can hold on to a consistent
+/// snapshot without blocking writers, and without writers having to copy the column family.
+type ColumnFamily = OrdMap<Vec<u8>, Vec<u8>>;
+
+/// The in-memory DBs of this process, by path. This is what makes reopening a path (and opening a
+/// checkpoint) in memory give back the data, as if it were on disk. An (empty) directory is created
+/// at the path, and a DB is forgotten once its directory is removed, e.g. when a `TempPath` is
+/// dropped. DBs opened with RocksDB never look in here.
+static STORES: Lazy<Mutex<HashMap<PathBuf, Arc<Store>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+struct Store {
+    column_families: RwLock<HashMap<String, ColumnFamily>>,
+    prefix_extractors: HashMap<String, PrefixExtractor>,
+}
+
+impl Store {
+    fn snapshot(&self) -> Self {
+        Self {
+            column_families: RwLock::new(self.column_families.read().clone()),
+            prefix_extractors: self.prefix_extractors.clone(),
+        }
+    }
+}
+
+/// [`Backend`] keeping all data in process memory, only meant for tests. Data is not lost when the
+/// DB is dropped though: opening the same path in memory again in the same process gives it back,
+/// as long as the directory at the path is not removed.
+pub struct InMemoryBackend {
+    path: PathBuf,
+    store: Arc<Store>,
+}
+
+impl Debug for InMemoryBackend {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "InMemoryBackend at {:?}", self.path)
+    }
+}
+
+impl InMemoryBackend {
+    /// Opens the in-memory DB at `path`, creating it and any missing column family. Prefix
+    /// extractors only take effect when the DB is created.
+    pub fn open(
+        path: impl AsRef<Path>,
+        column_families: Vec<String>,
+        prefix_extractors: HashMap<String, PrefixExtractor>,
+    ) -> DbResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut stores = Self::lock_stores();
+        if !path.exists() {
+            stores.remove(&path);
+            fs::create_dir_all(&path)?;
+        }
+        let store = stores
+            .entry(path.clone())
+            .or_insert_with(|| {
+                Arc::new(Store {
+                    column_families: RwLock::new(HashMap::new()),
+                    prefix_extractors,
+                })
+            })
+            .clone();
+        drop(stores);
+        {
+            let mut cfs = store.column_families.write();
+            for cf_name in column_families {
+                cfs.entry(cf_name).or_default();
+            }
+        }
+
+        Ok(Self { path, store })
+    }
+
+    /// Locks the registry of DBs, forgetting those whose directory was removed.
+    fn lock_stores() -> MutexGuard<'static, HashMap<PathBuf, Arc<Store>>> {
+        let mut stores = STORES.lock();
+        stores.retain(|path, _| path.exists());
+        stores
+    }
+
+    fn ops(batch: &mut BackendBatch) -> DbResult<&mut Vec<(ColumnFamilyName, WriteOp)>> {
+        match batch {
+            BackendBatch::Ops(ops) => Ok(ops),
+            BackendBatch::RocksDb(_) => Err(LumioDbError::Other(
+                "Batch was not created by the in-memory backend.".to_string(),
+            )),
+        }
+    }
+
+    fn column_family(&self, cf_name: &str) -> DbResult<ColumnFamily> {
+        self.store
+            .column_families
+            .read()
+            .get(cf_name)
+            .cloned()
+            .ok_or_else(|| Self::cf_not_found(cf_name))
+    }
+
+    fn cf_not_found(cf_name: &str) -> LumioDbError {
+        LumioDbError::Other(format!(
+            "DB::cf_handle not found for column family name: {}",
+            cf_name
+        ))
+    }
+}
+
+impl Backend for InMemoryBackend {
+    type RawIterator<'a> = InMemoryIterator;
+
+    fn get(&self, cf_name: &str, key: &[u8]) -> DbResult<Option<Vec<u8>>> {
+        Ok(self.column_family(cf_name)?.get(key).cloned())
+    }
+
+    fn new_batch(&self) -> BackendBatch {
+        BackendBatch::Ops(Vec::new())
+    }
+
+    fn batch_put(
+        &self,
+        batch: &mut BackendBatch,
+        cf_name: ColumnFamilyName,
+        key: &[u8],
+        value: &[u8],
+    ) -> DbResult<()> {
+        Self::ops(batch)?.push((cf_name, WriteOp::Value {
+            key: key.to_vec(),
+            value: value.to_vec(),
+        }));
+        Ok(())
+    }
+
+    fn batch_delete(
+        &self,
+        batch: &mut BackendBatch,
+        cf_name: ColumnFamilyName,
+        key: &[u8],
+    ) -> DbResult<()> {
+        Self::ops(batch)?.push((cf_name, WriteOp::Deletion { key: key.to_vec() }));
+        Ok(())
+    }
+
+    fn write(&self, mut batch: BackendBatch, _sync: bool) -> DbResult<()> {
+        let ops = std::mem::take(Self::ops(&mut batch)?);
+
+        // Hold the lock across the batch so that it's applied atomically.
+        let mut cfs = self.store.column_families.write();
+        if let Some((cf_name, _)) = ops.iter().find(|(cf_name, _)| !cfs.contains_key(*cf_name)) {
+            return Err(Self::cf_not_found(cf_name));
+        }
+        for (cf_name, op) in ops {
+            let cf = cfs.get_mut(cf_name).expect("Checked above.");
+            match op {
+                WriteOp::Value { key, value } => {
+                    cf.insert(key, value);
+                },
+                WriteOp::Deletion { key } => {
+                    cf.remove(&key);
+                },
+            }
+        }
+        Ok(())
+    }
+
+    fn raw_iterator(&self, cf_name: &str, opts: ReadOptions) -> DbResult<InMemoryIterator> {
+        let prefix_extractor = if opts.prefix_same_as_start && !opts.total_order_seek {
+            self.store.prefix_extractors.get(cf_name).copied()
+        } else {
+            None
+        };
+        Ok(InMemoryIterator {
+            snapshot: self.column_family(cf_name)?,
+            current: None,
+            prefix_extractor,
+            prefix: None,
+            upper_bound: opts.iterate_upper_bound,
+        })
+    }
+
+    fn flush_cf(&self, cf_name: &str) -> DbResult<()> {
+        self.column_family(cf_name).map(|_| ())
+    }
+
+    fn get_property(&self, cf_name: &str, property_name: &str) -> DbResult<Option<u64>> {
+        let cf = self.column_family(cf_name)?;
+        Ok(match property_name {
+            "rocksdb.estimate-num-keys" => Some(cf.len() as u64),
+            "rocksdb.estimate-live-data-size"
+            | "rocksdb.total-sst-files-size"
+            | "rocksdb.live-sst-files-size" => Some(
+                cf.iter()
+                    .map(|(key, value)| (key.len() + value.len()) as u64)
+                    .sum(),
+            ),
+            _ => None,
+        })
+    }
+
+    fn create_checkpoint(&self, path: &Path) -> DbResult<()> {
+        let mut stores = Self::lock_stores();
+        if stores.contains_key(path) {
+            return Err(LumioDbError::Other(format!(
+                "Checkpoint path {:?} already holds an in-memory DB.",
+                path
+            )));
+        }
+        fs::create_dir_all(path)?;
+        stores.insert(path.to_path_buf(), Arc::new(self.store.snapshot()));
+        Ok(())
+    }
+}
+
+/// Iterator of an [`InMemoryBackend`], over a snapshot of the column family taken when created.
+pub struct InMemoryIterator {
+    snapshot: ColumnFamily,
+    current: Option<(Vec<u8>, Vec<u8>)>,
+    prefix_extractor: Option<PrefixExtractor>,
+    // Prefix of the key last seeked to, when iterating with `prefix_same_as_start`.
+    prefix: Option<Vec<u8>>,
+    upper_bound: Option<Vec<u8>>,
+}
+
+impl InMemoryIterator {
+    fn set_current(&mut self, entry: Option<(Vec<u8>, Vec<u8>)>) {
+        self.current = entry
+            .filter(|(key, _)| {
+                self.upper_bound
+                    .as_ref()
+                    .is_none_or(|upper_bound| key.as_slice() < upper_bound.as_slice())
+            })
+            .filter(|(key, _)| match (&self.prefix, self.prefix_extractor) {
+                (Some(prefix), Some(prefix_extractor)) => {
+                    prefix_extractor(key) == prefix.as_slice()
+                },
+                _ => true,
+            });
+    }
+
+    fn set_prefix(&mut self, seek_key: &[u8]) {
+        self.prefix = self
+            .prefix_extractor
+            .map(|prefix_extractor| prefix_extractor(seek_key).to_vec());
+    }
+
+    fn upper_bound(&self) -> Bound<&[u8]> {
+        match &self.upper_bound {
+            Some(upper_bound) => Bound::Excluded(upper_bound.as_slice()),
+            None => Bound::Unbounded,
+        }
+    }
+
+    fn first(&self, range: (Bound<&[u8]>, Bound<&[u8]>)) -> Option<(Vec<u8>, Vec<u8>)> {
+        self.snapshot
+            .range(range)
+            .next()
+            .map(|(key, value)| (key.clone(), value.clone()))
+    }
+
+    fn last(&self, range: (Bound<&[u8]>, Bound<&[u8]>)) -> Option<(Vec<u8>, Vec<u8>)> {
+        self.snapshot
+            .range(range)
+            .next_back()
+            .map(|(key, value)| (key.clone(), value.clone()))
+    }
+}
+
+impl RawIterator for InMemoryIterator {
+    fn seek_to_first(&mut self) {
+        let first = self.first((Bound::Unbounded, Bound::Unbounded));
+        if let Some((key, _)) = &first {
+            self.set_prefix(key);
+        }
+        self.set_current(first);
+    }
+
+    fn seek_to_last(&mut self) {
+        let last = self.last((Bound::Unbounded, self.upper_bound()));
+        if let Some((key, _)) = &last {
+            self.set_prefix(key);
+        }
+        self.set_current(last);
+    }
+
+    fn seek(&mut self, key: &[u8]) {
+        self.set_prefix(key);
+        self.set_current(self.first((Bound::Included(key), Bound::Unbounded)));
+    }
+
+    fn seek_for_prev(&mut self, key: &[u8]) {
+        self.set_prefix(key);
+        // Like RocksDB, land on the last key below the upper bound if `key` is not below it.
+        let end = match self.upper_bound() {
+            Bound::Excluded(upper_bound) if key >= upper_bound => Bound::Excluded(upper_bound),
+            _ => Bound::Included(key),
+        };
+        let prev = self.last((Bound::Unbounded, end));
+        self.set_current(prev);
+    }
+
+    fn next(&mut self) {
+        let (key, _) = self.current.take().expect("Iterator is not valid.");
+        self.set_current(self.first((Bound::Excluded(key.as_slice()), Bound::Unbounded)));
+    }
+
+    fn prev(&mut self) {
+        let (key, _) = self.current.take().expect("Iterator is not valid.");
+        self.set_current(self.last((Bound::Unbounded, Bound::Excluded(key.as_slice()))));
+    }
+
+    fn valid(&self) -> bool {
+        self.current.is_some()
+    }
+
+    fn key(&self) -> Option<&[u8]> {
+        self.current.as_ref().map(|(key, _)| key.as_slice())
+    }
+
+    fn value(&self) -> Option<&[u8]> {
+        self.current.as_ref().map(|(_, value)| value.as_slice())
+    }
+
+    fn status(&self) -> DbResult<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,300 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The key-value engines a [`DB`](crate::DB) can sit on top of.
+//!
+//! A [`Backend`] deals in raw bytes organized in column families, while all the schema related
+//! work (encoding, metrics, batching by schema) happens in [`DB`](crate::DB). [`RocksDbBackend`]
+//! is what nodes run on. With the `testing` feature, `InMemoryBackend` keeps everything in process
+//! memory instead, which makes tests fast.
+//!
+//! [`DB`](crate::DB) holds its backend as a [`BackendImpl`] rather than a trait object, so the
+//! RocksDB path is statically dispatched and costs nothing extra when the `testing` feature is off.
+
+#[cfg(feature = "testing")]
+mod in_memory;
+mod rocksdb_backend;
+
+use crate::{batch::WriteOp, ColumnFamilyName};
+#[cfg(feature = "testing")]
+pub use in_memory::{InMemoryBackend, InMemoryIterator, PrefixExtractor};
+use lumio_storage_interface::Result as DbResult;
+pub use rocksdb_backend::RocksDbBackend;
+use rocksdb::DBRawIterator;
+use std::{fmt::Debug, path::Path};
+
+/// A key-value engine with column families, which is what [`DB`](crate::DB) is built on.
+pub trait Backend: Debug + Send + Sync {
+    /// Iterator returned by `raw_iterator()`.
+    type RawIterator<'a>: RawIterator
+    where
+        Self: 'a;
+
+    /// Reads the value of a single key.
+    fn get(&self, cf_name: &str, key: &[u8]) -> DbResult<Option<Vec<u8>>>;
+
+    /// Creates an empty batch to be filled by `batch_put()` and `batch_delete()` and applied by
+    /// `write()`.
+    fn new_batch(&self) -> BackendBatch;
+
+    /// Adds an insert/update operation to a batch created by this backend.
+    fn batch_put(
+        &self,
+        batch: &mut BackendBatch,
+        cf_name: ColumnFamilyName,
+        key: &[u8],
+        value: &[u8],
+    ) -> DbResult<()>;
+
+    /// Adds a delete operation to a batch created by this backend.
+    fn batch_delete(
+        &self,
+        batch: &mut BackendBatch,
+        cf_name: ColumnFamilyName,
+        key: &[u8],
+    ) -> DbResult<()>;
+
+    /// Applies a batch atomically. With `sync` the data is persisted even if the machine crashes
+    /// once this returns, if the backend persists anything at all.
+    fn write(&self, batch: BackendBatch, sync: bool) -> DbResult<()>;
+
+    /// Returns an iterator over a column family, positioned nowhere until one of the seek methods
+    /// is called.
+    fn raw_iterator(&self, cf_name: &str, opts: ReadOptions) -> DbResult<Self::RawIterator<'_>>;
+
+    /// Flushes buffered writes of a column family, a no-op for backends that don't buffer.
+    fn flush_cf(&self, cf_name: &str) -> DbResult<()>;
+
+    /// Returns an integer property of a column family, named as in RocksDB, or `None` if it's
+    /// unknown to the backend.
+    fn get_property(&self, cf_name: &str, property_name: &str) -> DbResult<Option<u64>>;
+
+    /// Creates a copy of the DB at `path`, which can be opened with the same backend.
+    fn create_checkpoint(&self, path: &Path) -> DbResult<()>;
+}
+
+/// Raw iterator over a column family of a [`Backend`], modeled after RocksDB's raw iterator.
+pub trait RawIterator {
+    fn seek_to_first(&mut self);
+
+    fn seek_to_last(&mut self);
+
+    /// Seeks to the first key equal to or greater than `key`.
+    fn seek(&mut self, key: &[u8]);
+
+    /// Seeks to the last key equal to or less than `key`.
+    fn seek_for_prev(&mut self, key: &[u8]);
+
+    fn next(&mut self);
+
+    fn prev(&mut self);
+
+    /// Whether the iterator is positioned at an entry. Only call `next()` and `prev()` on a valid
+    /// iterator.
+    fn valid(&self) -> bool;
+
+    fn key(&self) -> Option<&[u8]>;
+
+    fn value(&self) -> Option<&[u8]>;
+
+    /// Returns the error that made the iterator invalid, if any.
+    fn status(&self) -> DbResult<()>;
+}
+
+/// Forwards a call to whichever backend (or backend iterator) a [`BackendImpl`] (or
+/// [`RawIteratorImpl`]) holds.
+macro_rules! dispatch {
+    ($self:ident, $inner:ident => $call:expr) => {
+        match $self {
+            Self::RocksDb($inner) => $call,
+            #[cfg(feature = "testing")]
+            Self::InMemory($inner) => $call,
+        }
+    };
+}
+
+/// The backends a [`DB`](crate::DB) can be opened with.
+#[derive(Debug)]
+pub enum BackendImpl {
+    RocksDb(RocksDbBackend),
+    #[cfg(feature = "testing")]
+    InMemory(InMemoryBackend),
+}
+
+impl Backend for BackendImpl {
+    type RawIterator<'a> = RawIteratorImpl<'a>;
+
+    fn get(&self, cf_name: &str, key: &[u8]) -> DbResult<Option<Vec<u8>>> {
+        dispatch!(self, backend => backend.get(cf_name, key))
+    }
+
+    fn new_batch(&self) -> BackendBatch {
+        dispatch!(self, backend => backend.new_batch())
+    }
+
+    fn batch_put(
+        &self,
+        batch: &mut BackendBatch,
+        cf_name: ColumnFamilyName,
+        key: &[u8],
+        value: &[u8],
+    ) -> DbResult<()> {
+        dispatch!(self, backend => backend.batch_put(batch, cf_name, key, value))
+    }
+
+    fn batch_delete(
+        &self,
+        batch: &mut BackendBatch,
+        cf_name: ColumnFamilyName,
+        key: &[u8],
+    ) -> DbResult<()> {
+        dispatch!(self, backend => backend.batch_delete(batch, cf_name, key))
+    }
+
+    fn write(&self, batch: BackendBatch, sync: bool) -> DbResult<()> {
+        dispatch!(self, backend => backend.write(batch, sync))
+    }
+
+    fn raw_iterator(&self, cf_name: &str, opts: ReadOptions) -> DbResult<RawIteratorImpl<'_>> {
+        Ok(match self {
+            Self::RocksDb(backend) => {
+                RawIteratorImpl::RocksDb(backend.raw_iterator(cf_name, opts)?)
+            },
+            #[cfg(feature = "testing")]
+            Self::InMemory(backend) => {
+                RawIteratorImpl::InMemory(backend.raw_iterator(cf_name, opts)?)
+            },
+        })
+    }
+
+    fn flush_cf(&self, cf_name: &str) -> DbResult<()> {
+        dispatch!(self, backend => backend.flush_cf(cf_name))
+    }
+
+    fn get_property(&self, cf_name: &str, property_name: &str) -> DbResult<Option<u64>> {
+        dispatch!(self, backend => backend.get_property(cf_name, property_name))
+    }
+
+    fn create_checkpoint(&self, path: &Path) -> DbResult<()> {
+        dispatch!(self, backend => backend.create_checkpoint(path))
+    }
+}
+
+impl From<RocksDbBackend> for BackendImpl {
+    fn from(backend: RocksDbBackend) -> Self {
+        Self::RocksDb(backend)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl From<InMemoryBackend> for BackendImpl {
+    fn from(backend: InMemoryBackend) -> Self {
+        Self::InMemory(backend)
+    }
+}
+
+/// Iterator of a [`BackendImpl`].
+pub enum RawIteratorImpl<'a> {
+    RocksDb(DBRawIterator<'a>),
+    #[cfg(feature = "testing")]
+    InMemory(InMemoryIterator),
+}
+
+impl RawIterator for RawIteratorImpl<'_> {
+    fn seek_to_first(&mut self) {
+        dispatch!(self, iter => iter.seek_to_first())
+    }
+
+    fn seek_to_last(&mut self) {
+        dispatch!(self, iter => iter.seek_to_last())
+    }
+
+    fn seek(&mut self, key: &[u8]) {
+        dispatch!(self, iter => RawIterator::seek(iter, key))
+    }
+
+    fn seek_for_prev(&mut self, key: &[u8]) {
+        dispatch!(self, iter => RawIterator::seek_for_prev(iter, key))
+    }
+
+    fn next(&mut self) {
+        dispatch!(self, iter => RawIterator::next(iter))
+    }
+
+    fn prev(&mut self) {
+        dispatch!(self, iter => RawIterator::prev(iter))
+    }
+
+    fn valid(&self) -> bool {
+        dispatch!(self, iter => RawIterator::valid(iter))
+    }
+
+    fn key(&self) -> Option<&[u8]> {
+        dispatch!(self, iter => RawIterator::key(iter))
+    }
+
+    fn value(&self) -> Option<&[u8]> {
+        dispatch!(self, iter => RawIterator::value(iter))
+    }
+
+    fn status(&self) -> DbResult<()> {
+        dispatch!(self, iter => RawIterator::status(iter))
+    }
+}
+
+/// Write batch of a [`Backend`].
+pub enum BackendBatch {
+    /// Native RocksDB batch, built against the column family handles of a `RocksDbBackend`.
+    RocksDb(rocksdb::WriteBatch),
+    /// Backend agnostic list of operations, in the order they are to be applied.
+    Ops(Vec<(ColumnFamilyName, WriteOp)>),
+}
+
+impl BackendBatch {
+    pub fn size_in_bytes(&self) -> usize {
+        match self {
+            Self::RocksDb(batch) => batch.size_in_bytes(),
+            Self::Ops(ops) => ops
+                .iter()
+                .map(|(_, op)| match op {
+                    WriteOp::Value { key, value } => key.len() + value.len(),
+                    WriteOp::Deletion { key } => key.len(),
+                })
+                .sum(),
+        }
+    }
+}
+
+/// Options for iterators. Mirrors the subset of `rocksdb::ReadOptions` in use, see the RocksDB
+/// doc of the setters with the same names.
+#[derive(Clone, Debug, Default)]
+pub struct ReadOptions {
+    prefix_same_as_start: bool,
+    total_order_seek: bool,
+    iterate_upper_bound: Option<Vec<u8>>,
+    max_skippable_internal_keys: Option<u64>,
+}
+
+impl ReadOptions {
+    /// Makes the iterator stop once the prefix (as defined by the prefix extractor of the column
+    /// family) differs from that of the key it was seeked to.
+    pub fn set_prefix_same_as_start(&mut self, v: bool) {
+        self.prefix_same_as_start = v;
+    }
+
+    /// Makes the iterator go through all keys in order, regardless of the prefix extractor.
+    pub fn set_total_order_seek(&mut self, v: bool) {
+        self.total_order_seek = v;
+    }
+
+    /// Makes the iterator stop before reaching `key`.
+    pub fn set_iterate_upper_bound(&mut self, key: Vec<u8>) {
+        self.iterate_upper_bound = Some(key);
+    }
+
+    /// Makes the iterator fail with an incomplete result after skipping this many deleted
+    /// entries. Only meaningful to backends that keep tombstones.
+    pub fn set_max_skippable_internal_keys(&mut self, num: u64) {
+        self.max_skippable_internal_keys = Some(num);
+    }
+}
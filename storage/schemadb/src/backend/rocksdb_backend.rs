@@ -0,0 +1,173 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backend::{Backend, BackendBatch, RawIterator, ReadOptions},
+    ColumnFamilyName, IntoDbResult,
+};
+use anyhow::format_err;
+use lumio_storage_interface::{LumioDbError, Result as DbResult};
+use rocksdb::{DBRawIterator, WriteOptions};
+use std::path::Path;
+
+/// [`Backend`] on top of a RocksDB instance.
+#[derive(Debug)]
+pub struct RocksDbBackend {
+    inner: rocksdb::DB,
+}
+
+impl RocksDbBackend {
+    pub fn new(inner: rocksdb::DB) -> Self {
+        Self { inner }
+    }
+
+    fn get_cf_handle(&self, cf_name: &str) -> DbResult<&rocksdb::ColumnFamily> {
+        self.inner
+            .cf_handle(cf_name)
+            .ok_or_else(|| {
+                format_err!(
+                    "DB::cf_handle not found for column family name: {}",
+                    cf_name
+                )
+            })
+            .map_err(Into::into)
+    }
+
+    fn native_batch(batch: &mut BackendBatch) -> DbResult<&mut rocksdb::WriteBatch> {
+        match batch {
+            BackendBatch::RocksDb(batch) => Ok(batch),
+            BackendBatch::Ops(_) => Err(LumioDbError::Other(
+                "Batch was not created by the RocksDB backend.".to_string(),
+            )),
+        }
+    }
+}
+
+impl Backend for RocksDbBackend {
+    type RawIterator<'a> = DBRawIterator<'a>;
+
+    fn get(&self, cf_name: &str, key: &[u8]) -> DbResult<Option<Vec<u8>>> {
+        self.inner
+            .get_cf(self.get_cf_handle(cf_name)?, key)
+            .into_db_res()
+    }
+
+    fn new_batch(&self) -> BackendBatch {
+        BackendBatch::RocksDb(rocksdb::WriteBatch::default())
+    }
+
+    fn batch_put(
+        &self,
+        batch: &mut BackendBatch,
+        cf_name: ColumnFamilyName,
+        key: &[u8],
+        value: &[u8],
+    ) -> DbResult<()> {
+        Self::native_batch(batch)?.put_cf(self.get_cf_handle(cf_name)?, key, value);
+        Ok(())
+    }
+
+    fn batch_delete(
+        &self,
+        batch: &mut BackendBatch,
+        cf_name: ColumnFamilyName,
+        key: &[u8],
+    ) -> DbResult<()> {
+        Self::native_batch(batch)?.delete_cf(self.get_cf_handle(cf_name)?, key);
+        Ok(())
+    }
+
+    fn write(&self, mut batch: BackendBatch, sync: bool) -> DbResult<()> {
+        let batch = std::mem::take(Self::native_batch(&mut batch)?);
+        let mut opts = WriteOptions::default();
+        opts.set_sync(sync);
+        self.inner.write_opt(batch, &opts).into_db_res()
+    }
+
+    fn raw_iterator(&self, cf_name: &str, opts: ReadOptions) -> DbResult<DBRawIterator<'_>> {
+        let cf_handle = self.get_cf_handle(cf_name)?;
+        Ok(self
+            .inner
+            .raw_iterator_cf_opt(cf_handle, opts.into_rocksdb()))
+    }
+
+    fn flush_cf(&self, cf_name: &str) -> DbResult<()> {
+        self.inner
+            .flush_cf(self.get_cf_handle(cf_name)?)
+            .into_db_res()
+    }
+
+    fn get_property(&self, cf_name: &str, property_name: &str) -> DbResult<Option<u64>> {
+        self.inner
+            .property_int_value_cf(self.get_cf_handle(cf_name)?, property_name)
+            .into_db_res()
+    }
+
+    fn create_checkpoint(&self, path: &Path) -> DbResult<()> {
+        rocksdb::checkpoint::Checkpoint::new(&self.inner)
+            .into_db_res()?
+            .create_checkpoint(path)
+            .into_db_res()
+    }
+}
+
+impl RawIterator for DBRawIterator<'_> {
+    fn seek_to_first(&mut self) {
+        DBRawIterator::seek_to_first(self)
+    }
+
+    fn seek_to_last(&mut self) {
+        DBRawIterator::seek_to_last(self)
+    }
+
+    fn seek(&mut self, key: &[u8]) {
+        DBRawIterator::seek(self, key)
+    }
+
+    fn seek_for_prev(&mut self, key: &[u8]) {
+        DBRawIterator::seek_for_prev(self, key)
+    }
+
+    fn next(&mut self) {
+        DBRawIterator::next(self)
+    }
+
+    fn prev(&mut self) {
+        DBRawIterator::prev(self)
+    }
+
+    fn valid(&self) -> bool {
+        DBRawIterator::valid(self)
+    }
+
+    fn key(&self) -> Option<&[u8]> {
+        DBRawIterator::key(self)
+    }
+
+    fn value(&self) -> Option<&[u8]> {
+        DBRawIterator::value(self)
+    }
+
+    fn status(&self) -> DbResult<()> {
+        DBRawIterator::status(self).into_db_res()
+    }
+}
+
+impl ReadOptions {
+    fn into_rocksdb(self) -> rocksdb::ReadOptions {
+        let mut opts = rocksdb::ReadOptions::default();
+        if self.prefix_same_as_start {
+            opts.set_prefix_same_as_start(true);
+        }
+        if self.total_order_seek {
+            opts.set_total_order_seek(true);
+        }
+        if let Some(upper_bound) = self.iterate_upper_bound {
+            opts.set_iterate_upper_bound(upper_bound);
+        }
+        if let Some(num) = self.max_skippable_internal_keys {
+            opts.set_max_skippable_internal_keys(num);
+        }
+        opts
+    }
+}
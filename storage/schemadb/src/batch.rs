@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    backend::BackendBatch,
     metrics::{LUMIO_SCHEMADB_DELETES_SAMPLED, LUMIO_SCHEMADB_PUT_BYTES_SAMPLED, TIMER},
     schema::{KeyCodec, Schema, ValueCodec},
     ColumnFamilyName, DB,
@@ -76,9 +77,8 @@ impl Default for SampledBatchStats {
     }
 }
 
-#[derive(Default)]
 pub struct RawBatch {
-    pub inner: rocksdb::WriteBatch,
+    pub inner: BackendBatch,
     pub stats: SampledBatchStats,
 }
 
@@ -179,13 +179,16 @@ impl IntoRawBatch for SchemaBatch {
 
         let Self { rows, stats } = self;
 
-        let mut db_batch = rocksdb::WriteBatch::default();
+        let mut db_batch = db.backend.new_batch();
         for (cf_name, rows) in rows.iter() {
-            let cf_handle = db.get_cf_handle(cf_name)?;
             for write_op in rows {
                 match write_op {
-                    WriteOp::Value { key, value } => db_batch.put_cf(cf_handle, key, value),
-                    WriteOp::Deletion { key } => db_batch.delete_cf(cf_handle, key),
+                    WriteOp::Value { key, value } => {
+                        db.backend.batch_put(&mut db_batch, cf_name, key, value)?
+                    },
+                    WriteOp::Deletion { key } => {
+                        db.backend.batch_delete(&mut db_batch, cf_name, key)?
+                    },
                 }
             }
         }
@@ -197,7 +200,8 @@ impl IntoRawBatch for SchemaBatch {
     }
 }
 
-/// Similar to SchemaBatch, but wraps around rocksdb::WriteBatch directly.
+/// Similar to SchemaBatch, but wraps around the batch of the DB backend (e.g. rocksdb::WriteBatch)
+/// directly.
 /// For that to work, a reference to the DB needs to be held.
 pub struct NativeBatch<'db> {
     db: &'db DB,
@@ -215,7 +219,10 @@ impl<'db> NativeBatch<'db> {
     pub fn new(db: &'db DB) -> Self {
         Self {
             db,
-            raw_batch: RawBatch::default(),
+            raw_batch: RawBatch {
+                inner: db.backend.new_batch(),
+                stats: SampledBatchStats::default(),
+            },
         }
     }
 }
@@ -226,19 +233,15 @@ impl WriteBatch for NativeBatch<'_> {
     }
 
     fn raw_put(&mut self, cf_name: ColumnFamilyName, key: Vec<u8>, value: Vec<u8>) -> DbResult<()> {
-        self.raw_batch
-            .inner
-            .put_cf(&self.db.get_cf_handle(cf_name)?, &key, &value);
-
-        Ok(())
+        self.db
+            .backend
+            .batch_put(&mut self.raw_batch.inner, cf_name, &key, &value)
     }
 
     fn raw_delete(&mut self, cf_name: ColumnFamilyName, key: Vec<u8>) -> DbResult<()> {
-        self.raw_batch
-            .inner
-            .delete_cf(&self.db.get_cf_handle(cf_name)?, &key);
-
-        Ok(())
+        self.db
+            .backend
+            .batch_delete(&mut self.raw_batch.inner, cf_name, &key)
     }
 }
 
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    IntoDbResult, KeyCodec, Schema, SeekKeyCodec, ValueCodec, LUMIO_SCHEMADB_ITER_BYTES,
+    backend::{RawIterator, RawIteratorImpl},
+    KeyCodec, Schema, SeekKeyCodec, ValueCodec, LUMIO_SCHEMADB_ITER_BYTES,
     LUMIO_SCHEMADB_ITER_LATENCY_SECONDS, LUMIO_SCHEMADB_SEEK_LATENCY_SECONDS,
 };
 use lumio_metrics_core::TimerHelper;
@@ -24,7 +25,7 @@ enum Status {
 /// DB Iterator parameterized on [`Schema`] that seeks with [`Schema::Key`] and yields
 /// [`Schema::Key`] and [`Schema::Value`]
 pub struct SchemaIterator<'a, S> {
-    db_iter: rocksdb::DBRawIterator<'a>,
+    db_iter: RawIteratorImpl<'a>,
     direction: ScanDirection,
     status: Status,
     phantom: PhantomData<S>,
@@ -34,7 +35,7 @@ impl<'a, S> SchemaIterator<'a, S>
 where
     S: Schema,
 {
-    pub(crate) fn new(db_iter: rocksdb::DBRawIterator<'a>, direction: ScanDirection) -> Self {
+    pub(crate) fn new(db_iter: RawIteratorImpl<'a>, direction: ScanDirection) -> Self {
         SchemaIterator {
             db_iter,
             direction,
@@ -102,7 +103,7 @@ where
         }
 
         if !self.db_iter.valid() {
-            self.db_iter.status().into_db_res()?;
+            self.db_iter.status()?;
             // advancing an invalid raw iter results in seg fault
             self.status = Status::Invalid;
             return Ok(None);
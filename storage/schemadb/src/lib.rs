@@ -4,7 +4,8 @@
 
 #![forbid(unsafe_code)]
 
-//! This library implements a schematized DB on top of [RocksDB](https://rocksdb.org/). It makes
+//! This library implements a schematized DB on top of [RocksDB](https://rocksdb.org/), or any
+//! other key-value [`Backend`](backend::Backend) for that matter. It makes
 //! sure all data passed in and out are structured according to predefined schemas and prevents
 //! access to raw keys and values. This library also enforces a set of specific DB options,
 //! like custom comparators and schema-to-column-family mapping.
@@ -17,6 +18,7 @@
 mod metrics;
 #[macro_use]
 pub mod schema;
+pub mod backend;
 pub mod batch;
 pub mod iterator;
 
@@ -28,18 +30,18 @@ use crate::{
     },
     schema::{KeyCodec, Schema, SeekKeyCodec, ValueCodec},
 };
-use anyhow::format_err;
 use lumio_logger::prelude::*;
 use lumio_metrics_core::TimerHelper;
 use lumio_storage_interface::{LumioDbError, Result as DbResult};
+use backend::{Backend, BackendImpl, RocksDbBackend};
+pub use backend::ReadOptions;
 use batch::{IntoRawBatch, NativeBatch, WriteBatch};
 use iterator::{ScanDirection, SchemaIterator};
-/// Type alias to `rocksdb::ReadOptions`. See [`rocksdb doc`](https://github.com/pingcap/rust-rocksdb/blob/master/src/rocksdb_options.rs)
 pub use rocksdb::{
-    BlockBasedOptions, Cache, ColumnFamilyDescriptor, DBCompressionType, Options, ReadOptions,
-    SliceTransform, DEFAULT_COLUMN_FAMILY_NAME,
+    BlockBasedOptions, Cache, ColumnFamilyDescriptor, DBCompressionType, Options, SliceTransform,
+    DEFAULT_COLUMN_FAMILY_NAME,
 };
-use rocksdb::{ErrorKind, WriteOptions};
+use rocksdb::ErrorKind;
 use std::{collections::HashSet, fmt::Debug, iter::Iterator, path::Path};
 
 pub type ColumnFamilyName = &'static str;
@@ -56,7 +58,7 @@ enum OpenMode<'a> {
 #[derive(Debug)]
 pub struct DB {
     name: String, // for logging
-    inner: rocksdb::DB,
+    backend: BackendImpl,
 }
 
 impl DB {
@@ -170,7 +172,40 @@ impl DB {
         }
         .into_db_res()?;
 
-        Ok(Self::log_construct(name, open_mode, inner))
+        Ok(Self::log_construct(
+            name,
+            open_mode,
+            RocksDbBackend::new(inner).into(),
+        ))
+    }
+
+    /// Opens a DB with the [`InMemoryBackend`](backend::InMemoryBackend), for tests only. Only an
+    /// empty directory is created on disk, but the data outlives the returned instance: opening
+    /// `path` in memory again in the same process gives it back until the directory is removed.
+    /// `prefix_extractor` tells the prefix extractor of each column family, if any, in place of the
+    /// `SliceTransform` in the column family options.
+    #[cfg(feature = "testing")]
+    pub fn open_cf_in_memory(
+        path: impl AsRef<Path>,
+        name: &str,
+        cfds: Vec<ColumnFamilyDescriptor>,
+        prefix_extractor: impl Fn(&str) -> Option<backend::PrefixExtractor>,
+    ) -> DbResult<DB> {
+        use backend::{InMemoryBackend, PrefixExtractor};
+        use std::collections::HashMap;
+
+        let cf_names: Vec<String> = cfds.iter().map(|cfd| cfd.name().to_string()).collect();
+        let prefix_extractors: HashMap<String, PrefixExtractor> = cf_names
+            .iter()
+            .filter_map(|cf_name| Some((cf_name.clone(), prefix_extractor(cf_name)?)))
+            .collect();
+        let backend = InMemoryBackend::open(path, cf_names, prefix_extractors)?;
+
+        Ok(Self::log_construct(
+            name,
+            OpenMode::ReadWrite,
+            backend.into(),
+        ))
     }
 
     fn cfd_for_unrecognized_cf(cf: &String) -> ColumnFamilyDescriptor {
@@ -181,15 +216,16 @@ impl DB {
         ColumnFamilyDescriptor::new(cf.to_string(), cf_opts)
     }
 
-    fn log_construct(name: &str, open_mode: OpenMode, inner: rocksdb::DB) -> DB {
+    fn log_construct(name: &str, open_mode: OpenMode, backend: BackendImpl) -> DB {
         info!(
             rocksdb_name = name,
             open_mode = ?open_mode,
+            backend = ?backend,
             "Opened RocksDB."
         );
         DB {
             name: name.to_string(),
-            inner,
+            backend,
         }
     }
 
@@ -198,9 +234,8 @@ impl DB {
         let _timer = LUMIO_SCHEMADB_GET_LATENCY_SECONDS.timer_with(&[S::COLUMN_FAMILY_NAME]);
 
         let k = <S::Key as KeyCodec<S>>::encode_key(schema_key)?;
-        let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
 
-        let result = self.inner.get_cf(cf_handle, k).into_db_res()?;
+        let result = self.backend.get(S::COLUMN_FAMILY_NAME, &k)?;
         LUMIO_SCHEMADB_GET_BYTES.observe_with(
             &[S::COLUMN_FAMILY_NAME],
             result.as_ref().map_or(0.0, |v| v.len() as f64),
@@ -237,9 +272,8 @@ impl DB {
         opts: ReadOptions,
         direction: ScanDirection,
     ) -> DbResult<SchemaIterator<S>> {
-        let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
         Ok(SchemaIterator::new(
-            self.inner.raw_iterator_cf_opt(cf_handle, opts),
+            self.backend.raw_iterator(S::COLUMN_FAMILY_NAME, opts)?,
             direction,
         ))
     }
@@ -264,16 +298,14 @@ impl DB {
         self.iter_with_direction::<S>(opts, ScanDirection::Backward)
     }
 
-    fn write_schemas_inner(&self, batch: impl IntoRawBatch, option: &WriteOptions) -> DbResult<()> {
+    fn write_schemas_inner(&self, batch: impl IntoRawBatch, sync: bool) -> DbResult<()> {
         let labels = [self.name.as_str()];
         let _timer = LUMIO_SCHEMADB_BATCH_COMMIT_LATENCY_SECONDS.timer_with(&labels);
 
         let raw_batch = batch.into_raw_batch(self)?;
 
         let serialized_size = raw_batch.inner.size_in_bytes();
-        self.inner
-            .write_opt(raw_batch.inner, option)
-            .into_db_res()?;
+        self.backend.write(raw_batch.inner, sync)?;
 
         raw_batch.stats.commit();
         LUMIO_SCHEMADB_BATCH_COMMIT_BYTES.observe_with(&[&self.name], serialized_size as f64);
@@ -283,7 +315,11 @@ impl DB {
 
     /// Writes a group of records wrapped in a [`SchemaBatch`].
     pub fn write_schemas(&self, batch: impl IntoRawBatch) -> DbResult<()> {
-        self.write_schemas_inner(batch, &sync_write_option())
+        // For now we always use synchronous writes. This makes sure that once the operation
+        // returns `Ok(())` the data is persisted even if the machine crashes. In the future we
+        // might consider selectively turning this off for some non-critical writes to improve
+        // performance.
+        self.write_schemas_inner(batch, true)
     }
 
     /// Writes without sync flag in write option.
@@ -292,33 +328,18 @@ impl DB {
     /// crashes (i.e., the machine does not reboot), no writes will be
     /// lost even if sync==false.
     pub fn write_schemas_relaxed(&self, batch: impl IntoRawBatch) -> DbResult<()> {
-        self.write_schemas_inner(batch, &WriteOptions::default())
-    }
-
-    fn get_cf_handle(&self, cf_name: &str) -> DbResult<&rocksdb::ColumnFamily> {
-        self.inner
-            .cf_handle(cf_name)
-            .ok_or_else(|| {
-                format_err!(
-                    "DB::cf_handle not found for column family name: {}",
-                    cf_name
-                )
-            })
-            .map_err(Into::into)
+        self.write_schemas_inner(batch, false)
     }
 
     /// Flushes memtable data. This is only used for testing `get_approximate_sizes_cf` in unit
     /// tests.
     pub fn flush_cf(&self, cf_name: &str) -> DbResult<()> {
-        self.inner
-            .flush_cf(self.get_cf_handle(cf_name)?)
-            .into_db_res()
+        self.backend.flush_cf(cf_name)
     }
 
     pub fn get_property(&self, cf_name: &str, property_name: &str) -> DbResult<u64> {
-        self.inner
-            .property_int_value_cf(self.get_cf_handle(cf_name)?, property_name)
-            .into_db_res()?
+        self.backend
+            .get_property(cf_name, property_name)?
             .ok_or_else(|| {
                 lumio_storage_interface::LumioDbError::Other(
                     format!(
@@ -332,11 +353,7 @@ impl DB {
 
     /// Creates new physical DB checkpoint in directory specified by `path`.
     pub fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> DbResult<()> {
-        rocksdb::checkpoint::Checkpoint::new(&self.inner)
-            .into_db_res()?
-            .create_checkpoint(path)
-            .into_db_res()?;
-        Ok(())
+        self.backend.create_checkpoint(path.as_ref())
     }
 }
 
@@ -346,15 +363,6 @@ impl Drop for DB {
     }
 }
 
-/// For now we always use synchronous writes. This makes sure that once the operation returns
-/// `Ok(())` the data is persisted even if the machine crashes. In the future we might consider
-/// selectively turning this off for some non-critical writes to improve performance.
-fn sync_write_option() -> rocksdb::WriteOptions {
-    let mut opts = rocksdb::WriteOptions::default();
-    opts.set_sync(true);
-    opts
-}
-
 trait DeUnc: AsRef<Path> {
     fn de_unc(&self) -> &Path {
         // `dunce` is needed to "de-UNC" because rocksdb doesn't take Windows UNC paths like `\\?\C:\`
@@ -0,0 +1,208 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use lumio_schemadb::{
+    backend::PrefixExtractor,
+    batch::SchemaBatch,
+    define_schema,
+    schema::{KeyCodec, Schema, ValueCodec},
+    ColumnFamilyName, ReadOptions, DB,
+};
+use lumio_storage_interface::LumioDbError;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use rocksdb::{ColumnFamilyDescriptor, DEFAULT_COLUMN_FAMILY_NAME};
+
+define_schema!(TestSchema, TestKey, u32, "TestCF");
+
+#[derive(Debug, Eq, PartialEq)]
+struct TestKey(u32, u32);
+
+impl KeyCodec<TestSchema> for TestKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let mut bytes = vec![];
+        bytes.write_u32::<BigEndian>(self.0)?;
+        bytes.write_u32::<BigEndian>(self.1)?;
+        Ok(bytes)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        let mut reader = std::io::Cursor::new(data);
+        Ok(TestKey(
+            reader.read_u32::<BigEndian>()?,
+            reader.read_u32::<BigEndian>()?,
+        ))
+    }
+}
+
+impl ValueCodec<TestSchema> for u32 {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        let mut reader = std::io::Cursor::new(data);
+        Ok(reader.read_u32::<BigEndian>()?)
+    }
+}
+
+fn get_column_families() -> Vec<ColumnFamilyName> {
+    vec![DEFAULT_COLUMN_FAMILY_NAME, TestSchema::COLUMN_FAMILY_NAME]
+}
+
+fn get_cfds() -> Vec<ColumnFamilyDescriptor> {
+    get_column_families()
+        .iter()
+        .map(|cf_name| ColumnFamilyDescriptor::new(*cf_name, rocksdb::Options::default()))
+        .collect()
+}
+
+fn first_field_prefix(key: &[u8]) -> &[u8] {
+    &key[0..std::cmp::min(4, key.len())]
+}
+
+fn open_db(dir: &lumio_temppath::TempPath) -> DB {
+    DB::open_cf_in_memory(dir.path(), "test", get_cfds(), |cf_name| {
+        (cf_name == TestSchema::COLUMN_FAMILY_NAME).then_some(first_field_prefix as PrefixExtractor)
+    })
+    .expect("Failed to open DB.")
+}
+
+fn collect_values(db: &DB, opts: ReadOptions, seek_key: &TestKey) -> Vec<(TestKey, u32)> {
+    let mut iter = db
+        .iter_with_opts::<TestSchema>(opts)
+        .expect("Failed to create iterator.");
+    iter.seek(seek_key).unwrap();
+    iter.collect::<Result<Vec<_>, LumioDbError>>().unwrap()
+}
+
+#[test]
+fn test_put_get_delete() {
+    let tmpdir = lumio_temppath::TempPath::new();
+    let db = open_db(&tmpdir);
+
+    db.put::<TestSchema>(&TestKey(1, 1), &11).unwrap();
+    db.put::<TestSchema>(&TestKey(1, 2), &12).unwrap();
+    db.delete::<TestSchema>(&TestKey(1, 1)).unwrap();
+
+    assert_eq!(db.get::<TestSchema>(&TestKey(1, 1)).unwrap(), None);
+    assert_eq!(db.get::<TestSchema>(&TestKey(1, 2)).unwrap(), Some(12));
+    // Nothing but the directory is written to disk.
+    assert_eq!(std::fs::read_dir(tmpdir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn test_schema_batch_and_iteration() {
+    let tmpdir = lumio_temppath::TempPath::new();
+    let db = open_db(&tmpdir);
+
+    let mut batch = SchemaBatch::new();
+    for (k1, k2) in [(1, 1), (1, 2), (2, 1), (2, 2), (3, 1)] {
+        batch
+            .put::<TestSchema>(&TestKey(k1, k2), &(k1 * 10 + k2))
+            .unwrap();
+    }
+    batch.delete::<TestSchema>(&TestKey(2, 2)).unwrap();
+    db.write_schemas(batch).unwrap();
+
+    assert_eq!(
+        collect_values(&db, ReadOptions::default(), &TestKey(1, 2)),
+        vec![
+            (TestKey(1, 2), 12),
+            (TestKey(2, 1), 21),
+            (TestKey(3, 1), 31)
+        ],
+    );
+
+    let mut opts = ReadOptions::default();
+    opts.set_prefix_same_as_start(true);
+    assert_eq!(collect_values(&db, opts, &TestKey(1, 0)), vec![
+        (TestKey(1, 1), 11),
+        (TestKey(1, 2), 12)
+    ]);
+
+    let mut opts = ReadOptions::default();
+    opts.set_iterate_upper_bound(TestKey(3, 0).encode_key().unwrap());
+    assert_eq!(collect_values(&db, opts, &TestKey(2, 0)), vec![(
+        TestKey(2, 1),
+        21
+    )]);
+
+    let mut iter = db.rev_iter::<TestSchema>().unwrap();
+    iter.seek_for_prev(&TestKey(2, 2)).unwrap();
+    assert_eq!(iter.map(|res| res.unwrap().1).collect::<Vec<_>>(), vec![
+        21, 12, 11
+    ]);
+
+    // Seeking for prev past the upper bound lands on the last key below it.
+    let mut opts = ReadOptions::default();
+    opts.set_iterate_upper_bound(TestKey(2, 0).encode_key().unwrap());
+    let mut iter = db.rev_iter_with_opts::<TestSchema>(opts).unwrap();
+    iter.seek_for_prev(&TestKey(3, 1)).unwrap();
+    assert_eq!(iter.map(|res| res.unwrap().1).collect::<Vec<_>>(), vec![
+        12, 11
+    ]);
+}
+
+#[test]
+fn test_iterator_snapshot() {
+    let tmpdir = lumio_temppath::TempPath::new();
+    let db = open_db(&tmpdir);
+    db.put::<TestSchema>(&TestKey(1, 1), &11).unwrap();
+
+    let mut iter = db.iter::<TestSchema>().unwrap();
+    iter.seek_to_first();
+    db.put::<TestSchema>(&TestKey(1, 2), &12).unwrap();
+    db.delete::<TestSchema>(&TestKey(1, 1)).unwrap();
+
+    // The iterator keeps seeing the data as of its creation.
+    assert_eq!(iter.map(|res| res.unwrap().1).collect::<Vec<_>>(), vec![11]);
+    assert_eq!(db.get::<TestSchema>(&TestKey(1, 2)).unwrap(), Some(12));
+}
+
+#[test]
+fn test_get_property() {
+    let tmpdir = lumio_temppath::TempPath::new();
+    let db = open_db(&tmpdir);
+    db.put::<TestSchema>(&TestKey(1, 1), &11).unwrap();
+
+    assert_eq!(
+        db.get_property(TestSchema::COLUMN_FAMILY_NAME, "rocksdb.estimate-num-keys")
+            .unwrap(),
+        1
+    );
+    assert!(db
+        .get_property(TestSchema::COLUMN_FAMILY_NAME, "rocksdb.unknown-property")
+        .is_err());
+}
+
+#[test]
+fn test_reopen_and_checkpoint() {
+    let tmpdir = lumio_temppath::TempPath::new();
+    let checkpoint_dir = lumio_temppath::TempPath::new();
+    {
+        let db = open_db(&tmpdir);
+        db.put::<TestSchema>(&TestKey(1, 1), &11).unwrap();
+        db.create_checkpoint(checkpoint_dir.path()).unwrap();
+        db.put::<TestSchema>(&TestKey(1, 2), &12).unwrap();
+    }
+
+    // Opening the same path in memory gives back the data.
+    let db = open_db(&tmpdir);
+    assert_eq!(db.get::<TestSchema>(&TestKey(1, 2)).unwrap(), Some(12));
+
+    let checkpoint =
+        DB::open_cf_in_memory(checkpoint_dir.path(), "checkpoint", get_cfds(), |_| None).unwrap();
+    assert_eq!(
+        checkpoint.get::<TestSchema>(&TestKey(1, 1)).unwrap(),
+        Some(11)
+    );
+    assert_eq!(checkpoint.get::<TestSchema>(&TestKey(1, 2)).unwrap(), None);
+    drop(checkpoint);
+
+    // The data is gone with the directory.
+    std::fs::remove_dir_all(checkpoint_dir.path()).unwrap();
+    let checkpoint =
+        DB::open_cf_in_memory(checkpoint_dir.path(), "checkpoint", get_cfds(), |_| None).unwrap();
+    assert_eq!(checkpoint.get::<TestSchema>(&TestKey(1, 1)).unwrap(), None);
+}
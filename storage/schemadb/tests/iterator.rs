@@ -7,7 +7,7 @@ use lumio_schemadb::{
     define_schema,
     iterator::SchemaIterator,
     schema::{KeyCodec, Schema, SeekKeyCodec, ValueCodec},
-    DB,
+    ReadOptions, DB,
 };
 use lumio_storage_interface::LumioDbError;
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
@@ -379,7 +379,7 @@ impl TestDBWithPrefixExtractor {
     }
 
     fn iter_with_same_prefix(&self) -> SchemaIterator<TestSchema> {
-        let mut opts = rocksdb::ReadOptions::default();
+        let mut opts = ReadOptions::default();
         opts.set_prefix_same_as_start(true);
         self.db
             .iter_with_opts(opts)
@@ -387,7 +387,7 @@ impl TestDBWithPrefixExtractor {
     }
 
     fn iter_with_max_skipped_deletions(&self, num_skips: u64) -> SchemaIterator<TestSchema> {
-        let mut opts = rocksdb::ReadOptions::default();
+        let mut opts = ReadOptions::default();
         opts.set_max_skippable_internal_keys(num_skips);
         self.db
             .iter_with_opts(opts)
@@ -395,7 +395,7 @@ impl TestDBWithPrefixExtractor {
     }
 
     fn iter_with_upper_bound(&self, upper_bound: Vec<u8>) -> SchemaIterator<TestSchema> {
-        let mut opts = rocksdb::ReadOptions::default();
+        let mut opts = ReadOptions::default();
         opts.set_iterate_upper_bound(upper_bound);
         self.db
             .iter_with_opts(opts)
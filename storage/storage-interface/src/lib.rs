@@ -32,7 +32,7 @@ use lumio_types::{
     write_set::WriteSet,
 };
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 use thiserror::Error;
 
 pub mod block_info;
@@ -42,6 +42,7 @@ mod ledger_summary;
 mod metrics;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod mock;
+pub mod partial_state;
 pub mod state_store;
 
 use crate::{
@@ -647,6 +648,19 @@ pub trait DbWriter: Send + Sync {
     ) -> Result<()> {
         unimplemented!()
     }
+
+    /// Creates a physical checkpoint of the DB under `cp_root_path` while it keeps committing.
+    ///
+    /// `on_progress` is called with the name of each database right before it is checkpointed.
+    /// Returns the committed version the checkpoint is consistent at, which is what the
+    /// checkpoint will be truncated to once opened.
+    fn create_online_checkpoint(
+        &self,
+        cp_root_path: &Path,
+        on_progress: &mut dyn FnMut(&str),
+    ) -> Result<Version> {
+        unimplemented!()
+    }
 }
 
 #[derive(Clone)]
@@ -346,6 +346,14 @@ where
 
             match exit_code {
                 ExitCode::Return => {
+                    gas_meter
+                        .charge_return(
+                            self.operand_stack
+                                .last_n(current_frame.function.return_tys().len())
+                                .map_err(|e| set_err_info!(current_frame, e))?,
+                        )
+                        .map_err(|e| set_err_info!(current_frame, e))?;
+
                     let non_ref_vals = current_frame
                         .locals
                         .drop_all_values()
@@ -1164,16 +1172,15 @@ where
         ty: &Type,
     ) -> PartialVMResult<()> {
         let runtime_environment = self.loader.runtime_environment();
-        let res = self
-            .load_resource(
-                data_cache,
-                resource_resolver,
-                gas_meter,
-                traversal_context,
-                addr,
-                ty,
-            )?
-            .borrow_global();
+        let gv = self.load_resource(
+            data_cache,
+            resource_resolver,
+            gas_meter,
+            traversal_context,
+            addr,
+            ty,
+        )?;
+        let res = gv.borrow_global();
         gas_meter.charge_borrow_global(
             is_mut,
             is_generic,
@@ -1183,6 +1190,16 @@ where
             },
             res.is_ok(),
         )?;
+        if is_mut && res.is_ok() {
+            gas_meter.charge_global_write(
+                addr,
+                TypeWithRuntimeEnvironment {
+                    ty,
+                    runtime_environment,
+                },
+                gv.view(),
+            )?;
+        }
         self.check_access(
             runtime_environment,
             if is_mut {
@@ -1297,6 +1314,14 @@ where
                     },
                     Some(&resource),
                 )?;
+                gas_meter.charge_global_write(
+                    addr,
+                    TypeWithRuntimeEnvironment {
+                        ty,
+                        runtime_environment,
+                    },
+                    None::<&Value>,
+                )?;
                 self.check_access(runtime_environment, AccessKind::Writes, ty, addr)?;
                 resource
             },
@@ -1351,6 +1376,14 @@ where
                     gv.view().unwrap(),
                     true,
                 )?;
+                gas_meter.charge_global_write(
+                    addr,
+                    TypeWithRuntimeEnvironment {
+                        ty,
+                        runtime_environment,
+                    },
+                    gv.view(),
+                )?;
                 self.check_access(runtime_environment, AccessKind::Writes, ty, addr)?;
                 Ok(())
             },
@@ -374,6 +374,29 @@ pub trait GasMeter: NativeGasMeter {
         locals: impl Iterator<Item = impl ValueView> + Clone,
     ) -> PartialVMResult<()>;
 
+    /// Called with the values returned by a (non-native) function, right before its frame is
+    /// dropped. Returning does not cost any gas, but this allows gas meter adapters (e.g., an
+    /// execution tracer) to observe the return values.
+    fn charge_return(
+        &mut self,
+        _ret_vals: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+    ) -> PartialVMResult<()> {
+        Ok(())
+    }
+
+    /// Called after a global resource has been successfully borrowed mutably, moved from or moved
+    /// to, with its address and its value after the access. The access itself is charged by the
+    /// dedicated methods, but this allows gas meter adapters (e.g., an execution tracer) to
+    /// attribute writes to the functions performing them.
+    fn charge_global_write(
+        &mut self,
+        _addr: AccountAddress,
+        _ty: impl TypeView,
+        _val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        Ok(())
+    }
+
     fn charge_create_ty(&mut self, num_nodes: NumTypeNodes) -> PartialVMResult<()>;
 }
 
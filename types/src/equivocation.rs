@@ -0,0 +1,173 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Proofs that a validator signed two conflicting consensus messages for the same round.
+//!
+//! The signed consensus types (`VoteData`, `BlockData`) live in the consensus crates, so the
+//! proofs carry their BCS bytes, and are checked here against the domain separators those types
+//! are signed with.
+
+use crate::{
+    account_address::AccountAddress, block_info::BlockInfo, ledger_info::LedgerInfo,
+    validator_verifier::ValidatorVerifier,
+};
+use anyhow::{ensure, Context};
+use lumio_crypto::{bls12381, hash::DefaultHasher, Signature};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+
+/// A vote as signed by its author: the signature covers `ledger_info`, whose
+/// `consensus_data_hash` is the hash of `vote_data`.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SignedVote {
+    pub ledger_info: LedgerInfo,
+    /// BCS bytes of the consensus `VoteData`.
+    #[serde(with = "serde_bytes")]
+    pub vote_data: Vec<u8>,
+    pub signature: bls12381::Signature,
+}
+
+impl SignedVote {
+    /// Returns the (epoch, round) the vote is for, checking it's the one signed.
+    fn verify(
+        &self,
+        author: AccountAddress,
+        verifier: &ValidatorVerifier,
+    ) -> anyhow::Result<(u64, u64)> {
+        let mut hasher = DefaultHasher::new(b"VoteData");
+        hasher.update(&self.vote_data);
+        ensure!(
+            hasher.finish() == self.ledger_info.consensus_data_hash(),
+            "Vote data doesn't match the signed ledger info"
+        );
+        verifier
+            .verify(author, &self.ledger_info, &self.signature)
+            .context("Invalid vote signature")?;
+        let (proposed, _parent): (BlockInfo, BlockInfo) =
+            bcs::from_bytes(&self.vote_data).context("Malformed vote data")?;
+        Ok((proposed.epoch(), proposed.round()))
+    }
+}
+
+impl Debug for SignedVote {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SignedVote")
+            .field("ledger_info", &self.ledger_info)
+            .field("vote_data_len", &self.vote_data.len())
+            .finish()
+    }
+}
+
+/// A proposal as signed by its author: the signature covers the consensus `BlockData`.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SignedProposal {
+    /// BCS bytes of the consensus `BlockData`.
+    #[serde(with = "serde_bytes")]
+    pub block_data: Vec<u8>,
+    pub signature: bls12381::Signature,
+}
+
+impl SignedProposal {
+    /// Returns the (epoch, round) the proposal is for, checking it's the one signed.
+    fn verify(
+        &self,
+        author: AccountAddress,
+        verifier: &ValidatorVerifier,
+    ) -> anyhow::Result<(u64, u64)> {
+        let public_key = verifier
+            .get_public_key(&author)
+            .context("Author is not in the validator set")?;
+        let signing_message = [
+            DefaultHasher::prefixed_hash(b"BlockData").as_slice(),
+            &self.block_data,
+        ]
+        .concat();
+        self.signature
+            .verify_arbitrary_msg(&signing_message, &public_key)
+            .context("Invalid proposal signature")?;
+        // `BlockData` starts with its epoch and round.
+        ensure!(self.block_data.len() >= 16, "Malformed block data");
+        let epoch = bcs::from_bytes(&self.block_data[..8])?;
+        let round = bcs::from_bytes(&self.block_data[8..16])?;
+        Ok((epoch, round))
+    }
+}
+
+impl Debug for SignedProposal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SignedProposal")
+            .field("block_data_len", &self.block_data.len())
+            .finish()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum EquivocationProof {
+    /// Two votes for different blocks in the same round.
+    Vote(SignedVote, SignedVote),
+    /// Two different proposals for the same round.
+    Proposal(SignedProposal, SignedProposal),
+}
+
+/// Evidence that `author` equivocated in a round, verifiable by anyone holding the validator
+/// set of the epoch.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EquivocationEvidence {
+    pub author: AccountAddress,
+    pub epoch: u64,
+    pub round: u64,
+    pub proof: EquivocationProof,
+}
+
+impl EquivocationEvidence {
+    pub fn kind(&self) -> EquivocationKind {
+        match &self.proof {
+            EquivocationProof::Vote(..) => EquivocationKind::Vote,
+            EquivocationProof::Proposal(..) => EquivocationKind::Proposal,
+        }
+    }
+
+    /// Checks that both messages were signed by `author` for the epoch and round of the
+    /// evidence, and that they differ.
+    pub fn verify(&self, verifier: &ValidatorVerifier) -> anyhow::Result<()> {
+        let (first, second) = match &self.proof {
+            EquivocationProof::Vote(first, second) => {
+                ensure!(
+                    first.ledger_info != second.ledger_info,
+                    "The votes don't conflict"
+                );
+                (
+                    first.verify(self.author, verifier)?,
+                    second.verify(self.author, verifier)?,
+                )
+            },
+            EquivocationProof::Proposal(first, second) => {
+                ensure!(
+                    first.block_data != second.block_data,
+                    "The proposals don't conflict"
+                );
+                (
+                    first.verify(self.author, verifier)?,
+                    second.verify(self.author, verifier)?,
+                )
+            },
+        };
+        ensure!(
+            first == (self.epoch, self.round) && second == (self.epoch, self.round),
+            "Messages for {:?} and {:?}, but the evidence is for epoch {} round {}",
+            first,
+            second,
+            self.epoch,
+            self.round
+        );
+        Ok(())
+    }
+}
+
+/// The kind of an equivocation, with the value it has on chain.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum EquivocationKind {
+    Vote = 0,
+    Proposal = 1,
+}
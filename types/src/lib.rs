@@ -15,6 +15,7 @@ pub mod contract_event;
 pub mod dkg;
 pub mod epoch_change;
 pub mod epoch_state;
+pub mod equivocation;
 pub mod error;
 pub mod event;
 pub mod executable;
@@ -41,6 +42,7 @@ pub mod serde_helper;
 pub mod stake_pool;
 pub mod staking_contract;
 pub mod state_proof;
+pub mod state_sync_progress;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod test_helpers;
 pub mod timestamp;
@@ -237,6 +237,13 @@ pub enum TransactionShufflerType {
         platform_use_case_spread_factor: usize,
         user_use_case_spread_factor: usize,
     },
+    /// Spreads out transactions predicted to conflict by their read/write hints. Hints only
+    /// cover coin transfers and `create_account`, other transactions are never reordered for
+    /// conflicts.
+    ConflictAware {
+        conflict_window: usize,
+        max_lookahead: usize,
+    },
 }
 
 impl TransactionShufflerType {
@@ -253,7 +260,8 @@ impl TransactionShufflerType {
             TransactionShufflerType::NoShuffling
             | TransactionShufflerType::DeprecatedSenderAwareV1(_)
             | TransactionShufflerType::SenderAwareV2(_)
-            | TransactionShufflerType::DeprecatedFairness => None,
+            | TransactionShufflerType::DeprecatedFairness
+            | TransactionShufflerType::ConflictAware { .. } => None,
             TransactionShufflerType::UseCaseAware {
                 user_use_case_spread_factor,
                 ..
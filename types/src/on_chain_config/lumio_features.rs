@@ -145,6 +145,8 @@ pub enum FeatureFlag {
     DISTRIBUTE_TRANSACTION_FEE = 97,
     MONOTONICALLY_INCREASING_COUNTER = 98,
     ENABLE_CAPTURE_OPTION = 99,
+    /// Whether validators accept equivocation evidence as validator transactions.
+    EQUIVOCATION_EVIDENCE = 100,
 }
 
 impl FeatureFlag {
@@ -430,6 +432,10 @@ impl Features {
         self.is_enabled(FeatureFlag::DISTRIBUTE_TRANSACTION_FEE)
     }
 
+    pub fn is_equivocation_evidence_enabled(&self) -> bool {
+        self.is_enabled(FeatureFlag::EQUIVOCATION_EVIDENCE)
+    }
+
     pub fn get_max_identifier_size(&self) -> u64 {
         if self.is_enabled(FeatureFlag::LIMIT_MAX_IDENTIFIER_LENGTH) {
             IDENTIFIER_SIZE_MAX
@@ -14,7 +14,7 @@ use std::{
 use thiserror::Error;
 
 #[repr(u8)]
-#[derive(Clone, Debug, FromPrimitive, ToPrimitive)]
+#[derive(Clone, Debug, Deserialize, Eq, FromPrimitive, Hash, PartialEq, Serialize, ToPrimitive)]
 pub enum StateKeyTag {
     AccessPath,
     TableItem,
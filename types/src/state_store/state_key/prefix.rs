@@ -3,10 +3,11 @@
 
 use crate::state_store::state_key::{inner::StateKeyTag, StateKey};
 use move_core_types::account_address::AccountAddress;
+use serde::{Deserialize, Serialize};
 
 // Struct for defining prefix of a state key, which can be used for finding all the values with a
 // particular key prefix
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct StateKeyPrefix {
     tag: StateKeyTag,
     bytes: Vec<u8>,
@@ -17,6 +18,15 @@ impl StateKeyPrefix {
         Self { tag, bytes }
     }
 
+    /// Returns the prefixes of all state keys (i.e., access paths and table items)
+    /// held under the addresses (or table handles) that start with the given bytes.
+    pub fn for_address_prefix(address_prefix: &[u8]) -> Vec<Self> {
+        vec![
+            Self::new(StateKeyTag::AccessPath, address_prefix.to_vec()),
+            Self::new(StateKeyTag::TableItem, address_prefix.to_vec()),
+        ]
+    }
+
     /// Serializes to bytes for physical storage.
     pub fn encode(&self) -> anyhow::Result<Vec<u8>> {
         let mut out = vec![self.tag.clone() as u8];
@@ -46,7 +56,10 @@ impl From<AccountAddress> for StateKeyPrefix {
 mod tests {
     use crate::{
         account_config::{AccountResource, CoinStoreResource},
-        state_store::state_key::{inner::StateKeyTag, prefix::StateKeyPrefix, StateKey},
+        state_store::{
+            state_key::{inner::StateKeyTag, prefix::StateKeyPrefix, StateKey},
+            table::TableHandle,
+        },
         LumioCoinType,
     };
     use move_core_types::account_address::AccountAddress;
@@ -67,4 +80,28 @@ mod tests {
         assert!(!account1_key_prefx.is_prefix(&key2).unwrap());
         assert!(!account2_key_prefx.is_prefix(&key1).unwrap());
     }
+
+    #[test]
+    fn test_state_key_prefix_for_address_prefix() {
+        let address = AccountAddress::new([12u8; AccountAddress::LENGTH]);
+        let resource_key = StateKey::resource_typed::<AccountResource>(&address).unwrap();
+        let table_item_key = StateKey::table_item(&TableHandle(address), &[1, 2, 3]);
+        let raw_key = StateKey::raw(&address.to_vec());
+
+        // Verify the prefixes match the access paths and table items under the address
+        let key_prefixes = StateKeyPrefix::for_address_prefix(&[12u8, 12u8]);
+        let is_prefixed = |state_key: &StateKey| {
+            key_prefixes
+                .iter()
+                .any(|key_prefix| key_prefix.is_prefix(state_key).unwrap())
+        };
+        assert!(is_prefixed(&resource_key));
+        assert!(is_prefixed(&table_item_key));
+        assert!(!is_prefixed(&raw_key));
+
+        // Verify the prefixes don't match other addresses
+        let other_address = AccountAddress::new([13u8; AccountAddress::LENGTH]);
+        let other_key = StateKey::resource_typed::<AccountResource>(&other_address).unwrap();
+        assert!(!is_prefixed(&other_key));
+    }
 }
@@ -0,0 +1,64 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::transaction::Version;
+use lumio_infallible::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// The phase of state sync that the node is currently executing
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StateSyncPhase {
+    /// Fetching and verifying the epoch ending ledger infos (bootstrapping)
+    EpochEndingLedgerInfos,
+    /// Downloading a state snapshot (bootstrapping)
+    States,
+    /// Executing or applying transactions (bootstrapping)
+    Transactions,
+    /// Syncing to stay up-to-date after bootstrapping
+    ContinuousSyncing,
+}
+
+/// A structured report of the sync progress of the node
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateSyncProgress {
+    /// The phase that state sync is currently executing
+    pub phase: StateSyncPhase,
+    /// The highest version synced by the node
+    pub synced_version: Version,
+    /// The version the node is syncing to (if known)
+    pub target_version: Option<Version>,
+    /// The number of states downloaded (only when syncing states)
+    pub num_states_synced: Option<u64>,
+    /// The total number of states at the snapshot version (if known)
+    pub num_states_total: Option<u64>,
+    /// The sync throughput in states (or versions) per second, measured
+    /// since the start of the current phase.
+    pub throughput_per_second: Option<u64>,
+    /// The estimated number of seconds until the current phase is complete
+    pub estimated_seconds_remaining: Option<u64>,
+}
+
+/// A simple, thread-safe container for the latest sync progress report.
+/// The report is published by state sync and read by the APIs.
+#[derive(Clone, Debug, Default)]
+pub struct SharedStateSyncProgress {
+    progress: Arc<RwLock<Option<StateSyncProgress>>>,
+}
+
+impl SharedStateSyncProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the latest sync progress report (if one has been published)
+    pub fn get_progress(&self) -> Option<StateSyncProgress> {
+        self.progress.read().clone()
+    }
+
+    /// Publishes the given sync progress report
+    pub fn update_progress(&self, progress: StateSyncProgress) {
+        *self.progress.write() = Some(progress);
+    }
+}
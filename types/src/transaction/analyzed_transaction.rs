@@ -7,8 +7,8 @@ use crate::{
     on_chain_config::{CurrentTimeMicroseconds, Features, TransactionFeeBurnCap},
     state_store::{state_key::StateKey, table::TableHandle},
     transaction::{
-        signature_verified_transaction::SignatureVerifiedTransaction, EntryFunction, Transaction,
-        TransactionExecutableRef,
+        signature_verified_transaction::SignatureVerifiedTransaction, SignedTransaction,
+        Transaction, TransactionExecutableRef,
     },
     LumioCoinType, CoinType,
 };
@@ -32,6 +32,9 @@ pub struct AnalyzedTransaction {
     pub write_hints: Vec<StorageLocation>,
     /// A transaction is predictable if neither the read_hint or the write_hint have wildcards.
     predictable_transaction: bool,
+    /// Whether read/write hints could be derived for the transaction. If not, the hints are empty
+    /// and the transaction may read or write any storage location.
+    hinted: bool,
     /// The hash of the transaction - this is cached for performance reasons.
     hash: HashValue,
 }
@@ -66,7 +69,10 @@ impl StorageLocation {
 
 impl AnalyzedTransaction {
     pub fn new(transaction: SignatureVerifiedTransaction) -> Self {
-        let (read_hints, write_hints) = transaction.get_read_write_hints();
+        let (hinted, (read_hints, write_hints)) = match transaction.get_read_write_hints() {
+            Some(hints) => (true, hints),
+            None => (false, empty_rw_set()),
+        };
         let hints_contain_wildcard = read_hints
             .iter()
             .chain(write_hints.iter())
@@ -76,7 +82,8 @@ impl AnalyzedTransaction {
             transaction,
             read_hints,
             write_hints,
-            predictable_transaction: !hints_contain_wildcard,
+            predictable_transaction: hinted && !hints_contain_wildcard,
+            hinted,
             hash,
         }
     }
@@ -101,6 +108,12 @@ impl AnalyzedTransaction {
         self.predictable_transaction
     }
 
+    /// Returns false if no read/write hints could be derived for the transaction (e.g., for
+    /// generic entry functions), i.e., the empty hints don't bound what the transaction accesses.
+    pub fn hinted(&self) -> bool {
+        self.hinted
+    }
+
     pub fn sender(&self) -> Option<AccountAddress> {
         self.transaction.sender()
     }
@@ -237,60 +250,70 @@ pub fn empty_rw_set() -> (Vec<StorageLocation>, Vec<StorageLocation>) {
     (vec![], vec![])
 }
 
+/// Returns the read and write hints of a user transaction, or `None` if they can't be predicted.
+///
+/// Hints only cover `coin::transfer`, `lumio_account::transfer` and
+/// `lumio_account::create_account` entry functions. Every other transaction, including multisig
+/// and script payloads, returns `None`.
+pub fn user_transaction_read_write_hints(
+    signed_txn: &SignedTransaction,
+) -> Option<(Vec<StorageLocation>, Vec<StorageLocation>)> {
+    let func = match signed_txn.payload().executable_ref() {
+        Ok(TransactionExecutableRef::EntryFunction(func))
+            if !signed_txn.payload().is_multisig() =>
+        {
+            func
+        },
+        _ => return None,
+    };
+    let sender_address = signed_txn.sender();
+    let receiver_address = || bcs::from_bytes(func.args().first()?).ok();
+    match (
+        *func.module().address(),
+        func.module().name().as_str(),
+        func.function().as_str(),
+    ) {
+        (AccountAddress::ONE, "coin", "transfer") => Some(rw_set_for_coin_transfer(
+            sender_address,
+            receiver_address()?,
+            true,
+        )),
+        (AccountAddress::ONE, "lumio_account", "transfer") => Some(rw_set_for_coin_transfer(
+            sender_address,
+            receiver_address()?,
+            false,
+        )),
+        (AccountAddress::ONE, "lumio_account", "create_account") => Some(
+            rw_set_for_create_account(sender_address, receiver_address()?),
+        ),
+        _ => None,
+    }
+}
+
 trait AnalyzedTransactionProvider {
-    fn get_read_write_hints(&self) -> (Vec<StorageLocation>, Vec<StorageLocation>);
+    /// Returns the read and write hints, or `None` if the transaction is unhinted.
+    fn get_read_write_hints(&self) -> Option<(Vec<StorageLocation>, Vec<StorageLocation>)>;
 }
 
 impl AnalyzedTransactionProvider for Transaction {
-    fn get_read_write_hints(&self) -> (Vec<StorageLocation>, Vec<StorageLocation>) {
-        let process_entry_function = |func: &EntryFunction,
-                                      sender_address: AccountAddress|
-         -> (Vec<StorageLocation>, Vec<StorageLocation>) {
-            match (
-                *func.module().address(),
-                func.module().name().as_str(),
-                func.function().as_str(),
-            ) {
-                (AccountAddress::ONE, "coin", "transfer") => {
-                    let receiver_address = bcs::from_bytes(&func.args()[0]).unwrap();
-                    rw_set_for_coin_transfer(sender_address, receiver_address, true)
-                },
-                (AccountAddress::ONE, "lumio_account", "transfer") => {
-                    let receiver_address = bcs::from_bytes(&func.args()[0]).unwrap();
-                    rw_set_for_coin_transfer(sender_address, receiver_address, false)
-                },
-                (AccountAddress::ONE, "lumio_account", "create_account") => {
-                    let receiver_address = bcs::from_bytes(&func.args()[0]).unwrap();
-                    rw_set_for_create_account(sender_address, receiver_address)
-                },
-                _ => todo!(
-                    "Only coin transfer and create account transactions are supported for now"
-                ),
-            }
-        };
+    fn get_read_write_hints(&self) -> Option<(Vec<StorageLocation>, Vec<StorageLocation>)> {
         match self {
-            Transaction::UserTransaction(signed_txn) => match signed_txn.payload().executable_ref()
-            {
-                Ok(TransactionExecutableRef::EntryFunction(func))
-                    if !signed_txn.payload().is_multisig() =>
-                {
-                    process_entry_function(func, signed_txn.sender())
-                },
-                _ => todo!("Only entry function transactions are supported for now"),
+            Transaction::UserTransaction(signed_txn) => {
+                user_transaction_read_write_hints(signed_txn)
             },
-            _ => empty_rw_set(),
+            _ => Some(empty_rw_set()),
         }
     }
 }
 
 impl AnalyzedTransactionProvider for SignatureVerifiedTransaction {
-    fn get_read_write_hints(&self) -> (Vec<StorageLocation>, Vec<StorageLocation>) {
+    fn get_read_write_hints(&self) -> Option<(Vec<StorageLocation>, Vec<StorageLocation>)> {
         match self {
             SignatureVerifiedTransaction::Valid(txn) => txn.get_read_write_hints(),
             SignatureVerifiedTransaction::Invalid(_) => {
                 // Invalid transactions are not execute by the VM, so we don't need to provide
                 // read/write hints for them.
-                empty_rw_set()
+                Some(empty_rw_set())
             },
         }
     }
@@ -3,11 +3,12 @@
 
 #[cfg(any(test, feature = "fuzzing"))]
 use crate::dkg::DKGTranscriptMetadata;
-use crate::{dkg::DKGTranscript, jwks, validator_verifier::ValidatorVerifier};
+use crate::{
+    account_address::AccountAddress, dkg::DKGTranscript, equivocation::EquivocationEvidence, jwks,
+    validator_verifier::ValidatorVerifier,
+};
 use anyhow::Context;
 use lumio_crypto_derive::{BCSCryptoHash, CryptoHasher};
-#[cfg(any(test, feature = "fuzzing"))]
-use move_core_types::account_address::AccountAddress;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
@@ -15,6 +16,7 @@ use std::fmt::Debug;
 pub enum ValidatorTransaction {
     DKGResult(DKGTranscript),
     ObservedJWKUpdate(jwks::QuorumCertifiedUpdate),
+    EquivocationEvidence(EquivocationEvidence),
 }
 
 impl ValidatorTransaction {
@@ -39,6 +41,9 @@ impl ValidatorTransaction {
             ValidatorTransaction::ObservedJWKUpdate(_) => {
                 "validator_transaction__observed_jwk_update"
             },
+            ValidatorTransaction::EquivocationEvidence(_) => {
+                "validator_transaction__equivocation_evidence"
+            },
         }
     }
 
@@ -48,6 +53,9 @@ impl ValidatorTransaction {
                 .verify(verifier)
                 .context("DKGResult verification failed"),
             ValidatorTransaction::ObservedJWKUpdate(_) => Ok(()),
+            ValidatorTransaction::EquivocationEvidence(evidence) => evidence
+                .verify(verifier)
+                .context("EquivocationEvidence verification failed"),
         }
     }
 }
@@ -61,4 +69,9 @@ pub enum Topic {
         issuer: jwks::Issuer,
         kid: jwks::KID,
     },
+    EQUIVOCATION {
+        author: AccountAddress,
+        epoch: u64,
+        round: u64,
+    },
 }